@@ -0,0 +1,259 @@
+use std::collections::HashSet;
+
+use crate::models::events::InputEvent;
+
+/// Input pauses longer than this count as idle time.
+pub const DEFAULT_IDLE_GAP_MS: u64 = 2_000;
+/// Input pauses shorter than this are not worth cutting.
+pub const DEFAULT_AUTO_PAUSE_MIN_IDLE_MS: u64 = 5_000;
+/// Idle time kept next to the surrounding input when a pause is cut.
+pub const DEFAULT_AUTO_PAUSE_PADDING_MS: u64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityRange {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivitySummary {
+    pub duration_ms: u64,
+    pub active_ms: u64,
+    pub idle_ms: u64,
+    pub click_count: u64,
+    /// Sum of scroll wheel deltas along both axes.
+    pub scroll_distance: f64,
+    /// Key presses, not counting auto-repeat while a key is held.
+    pub keypress_count: u64,
+    /// Spans with input activity, in order.
+    pub active_ranges: Vec<ActivityRange>,
+}
+
+/// Summarizes input activity over a recording.
+///
+/// Every event keeps the recording active until the next event, but for at most
+/// `idle_gap_ms`; longer pauses are idle time.
+pub fn summarize_activity(
+    events: &[InputEvent],
+    duration_ms: u64,
+    idle_gap_ms: u64,
+) -> ActivitySummary {
+    let mut timestamps = events
+        .iter()
+        .map(InputEvent::ts)
+        .filter(|ts| *ts < duration_ms)
+        .collect::<Vec<_>>();
+    timestamps.sort_unstable();
+
+    let mut active_ranges = Vec::<ActivityRange>::new();
+    for ts in timestamps {
+        let end_ms = ts.saturating_add(idle_gap_ms).min(duration_ms);
+        match active_ranges.last_mut() {
+            Some(last) if ts <= last.end_ms => last.end_ms = last.end_ms.max(end_ms),
+            _ => active_ranges.push(ActivityRange {
+                start_ms: ts,
+                end_ms,
+            }),
+        }
+    }
+    let active_ms = active_ranges
+        .iter()
+        .map(|range| range.end_ms - range.start_ms)
+        .sum::<u64>();
+
+    let mut click_count = 0;
+    let mut scroll_distance = 0.0;
+    let mut keypress_count = 0;
+    let mut held_keys = HashSet::<&str>::new();
+    for event in events {
+        match event {
+            InputEvent::Click { .. } => click_count += 1,
+            InputEvent::Scroll { delta, .. } => scroll_distance += delta.dx.hypot(delta.dy),
+            InputEvent::KeyDown { key_code, .. } => {
+                if held_keys.insert(key_code) {
+                    keypress_count += 1;
+                }
+            }
+            InputEvent::KeyUp { key_code, .. } => {
+                held_keys.remove(key_code.as_str());
+            }
+            InputEvent::Move { .. } | InputEvent::MouseUp { .. } => {}
+        }
+    }
+
+    ActivitySummary {
+        duration_ms,
+        active_ms,
+        idle_ms: duration_ms.saturating_sub(active_ms),
+        click_count,
+        scroll_distance,
+        keypress_count,
+        active_ranges,
+    }
+}
+
+/// Idle spans suggested for cutting, with the time the cuts would save.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoPauseSuggestion {
+    /// Ranges to cut, in order.
+    pub ranges: Vec<ActivityRange>,
+    pub removed_ms: u64,
+    /// Length of the recording once the ranges are cut.
+    pub remaining_ms: u64,
+}
+
+/// Suggests cutting input pauses of at least `min_idle_ms`.
+///
+/// Each cut leaves `padding_ms` of the pause next to the input around it, so the edit does
+/// not land right on an action; the recording's own start and end get no padding. A
+/// recording without any input gets no suggestions.
+pub fn suggest_auto_pauses(
+    events: &[InputEvent],
+    duration_ms: u64,
+    min_idle_ms: u64,
+    padding_ms: u64,
+) -> AutoPauseSuggestion {
+    let mut timestamps = events
+        .iter()
+        .map(InputEvent::ts)
+        .filter(|ts| *ts < duration_ms)
+        .collect::<Vec<_>>();
+    timestamps.sort_unstable();
+    timestamps.dedup();
+
+    let mut ranges = Vec::new();
+    if !timestamps.is_empty() {
+        // `None` stands for the recording's start before the first event and its end after
+        // the last one.
+        let bounds = std::iter::once(None)
+            .chain(timestamps.iter().copied().map(Some))
+            .chain(std::iter::once(None))
+            .collect::<Vec<_>>();
+        for pair in bounds.windows(2) {
+            let (previous, next) = (pair[0], pair[1]);
+            let gap_start = previous.unwrap_or(0);
+            let gap_end = next.unwrap_or(duration_ms);
+            if gap_end.saturating_sub(gap_start) < min_idle_ms.max(1) {
+                continue;
+            }
+            let start_ms = previous.map_or(0, |ts| ts.saturating_add(padding_ms));
+            let end_ms = next.map_or(duration_ms, |ts| ts.saturating_sub(padding_ms));
+            if end_ms > start_ms {
+                ranges.push(ActivityRange { start_ms, end_ms });
+            }
+        }
+    }
+
+    let removed_ms = ranges
+        .iter()
+        .map(|range| range.end_ms - range.start_ms)
+        .sum::<u64>();
+    AutoPauseSuggestion {
+        ranges,
+        removed_ms,
+        remaining_ms: duration_ms.saturating_sub(removed_ms),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::events::{MouseButton, ScrollDelta};
+
+    #[test]
+    fn summary_splits_active_and_idle_time() {
+        let key = |ts: u64, down: bool| {
+            let key_code = "KeyA".to_string();
+            if down {
+                InputEvent::KeyDown {
+                    ts,
+                    key_code,
+                    caret: None,
+                }
+            } else {
+                InputEvent::KeyUp { ts, key_code }
+            }
+        };
+        let events = vec![
+            InputEvent::Move {
+                ts: 0,
+                x: 0.0,
+                y: 0.0,
+            },
+            InputEvent::Click {
+                ts: 500,
+                x: 0.0,
+                y: 0.0,
+                button: MouseButton::Left,
+                ui_context: None,
+            },
+            // Auto-repeat while held counts once.
+            key(1_000, true),
+            key(1_100, true),
+            key(1_200, false),
+            // 10 s pause, then a scroll near the end.
+            InputEvent::Scroll {
+                ts: 11_200,
+                x: 0.0,
+                y: 0.0,
+                delta: ScrollDelta { dx: 3.0, dy: 4.0 },
+            },
+        ];
+
+        let summary = summarize_activity(&events, 12_000, DEFAULT_IDLE_GAP_MS);
+        assert_eq!(
+            summary.active_ranges,
+            vec![
+                ActivityRange {
+                    start_ms: 0,
+                    end_ms: 3_200,
+                },
+                ActivityRange {
+                    start_ms: 11_200,
+                    end_ms: 12_000,
+                },
+            ]
+        );
+        assert_eq!(summary.active_ms, 4_000);
+        assert_eq!(summary.idle_ms, 8_000);
+        assert_eq!(summary.click_count, 1);
+        assert_eq!(summary.keypress_count, 1);
+        assert!((summary.scroll_distance - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn long_input_pauses_are_suggested_as_padded_cuts() {
+        let moved = |ts: u64| InputEvent::Move { ts, x: 0.0, y: 0.0 };
+        let events = vec![moved(12_000), moved(1_000), moved(2_000), moved(2_000)];
+
+        let suggestion = suggest_auto_pauses(&events, 20_000, 5_000, 500);
+        assert_eq!(
+            suggestion.ranges,
+            vec![
+                ActivityRange {
+                    start_ms: 2_500,
+                    end_ms: 11_500,
+                },
+                // The tail reaches the end of the recording unpadded.
+                ActivityRange {
+                    start_ms: 12_500,
+                    end_ms: 20_000,
+                },
+            ]
+        );
+        assert_eq!(suggestion.removed_ms, 16_500);
+        assert_eq!(suggestion.remaining_ms, 3_500);
+
+        // Padding larger than half the pause leaves nothing to cut.
+        let padded = suggest_auto_pauses(&events, 20_000, 5_000, 6_000);
+        assert_eq!(padded.ranges.len(), 1);
+        assert_eq!(padded.ranges[0].start_ms, 18_000);
+
+        assert!(suggest_auto_pauses(&[], 20_000, 5_000, 500)
+            .ranges
+            .is_empty());
+    }
+}
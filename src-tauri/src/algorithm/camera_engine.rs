@@ -2,8 +2,14 @@ use crate::models::events::{BoundingRect, InputEvent};
 use crate::models::project::{
     CameraSpring, NormalizedRect, TargetPoint, ZoomMode, ZoomSegment, ZoomTrigger,
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Upper bound for the number of points returned by [`decimate_camera_samples`] and
+/// [`decimate_target_points`].
+pub const MAX_CAMERA_TRACK_POINTS: usize = 2_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum ClickActivationMode {
     SingleClick,
     MultiClickWindow,
@@ -70,7 +76,9 @@ impl Spring {
     }
 }
 
-#[derive(Debug, Clone)]
+/// Tunable smart camera parameters; missing fields fall back to [`Default`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct SmartCameraConfig {
     pub fixed_dt_ms: u64,
     pub dead_zone_ratio: f64,
@@ -88,13 +96,31 @@ pub struct SmartCameraConfig {
     pub safe_zone_margin_ratio: f64,
     pub max_lookahead_ms: u64,
     pub velocity_threshold_px_per_ms: f64,
+    /// Fixed lead: click-triggered zooms start at least this long before the cluster's
+    /// first click, regardless of the velocity pre-roll (never before the previous
+    /// transition's lock ends). `0` leaves only the pre-roll.
+    pub zoom_lead_ms: u64,
     pub click_activation_mode: ClickActivationMode,
     pub activation_window_ms: u64,
     pub min_clicks_to_activate: usize,
     pub click_cluster_gap_ms: u64,
+    /// Clicks within the cluster gap merge only when they are closer than this many
+    /// sizes of their UI elements; `0` clusters by time alone.
+    pub click_cluster_element_scale: f64,
+    /// Lower bound of the element-based merge distance, so repeated clicks on a tiny
+    /// control still merge despite pointer jitter.
+    pub min_click_cluster_radius_px: f64,
     pub min_zoom_interval_ms: u64,
     pub min_lock_duration_ms: u64,
+    /// Locked segments shorter than this are dropped (or extended, see below) to avoid
+    /// blink-and-miss zooms. `0` disables the filter.
+    pub min_segment_duration_ms: u64,
+    /// Extend short segments into free time around them instead of dropping them.
+    pub extend_short_segments: bool,
     pub lock_recent_window_ms: u64,
+    /// Treat the text caret reported with keystrokes as a focus source, so the view
+    /// follows where the user types (see [`build_caret_transitions`]).
+    pub caret_follow: bool,
     pub spring_mass: f64,
     pub spring_stiffness: f64,
     pub spring_damping: f64,
@@ -123,13 +149,19 @@ impl Default for SmartCameraConfig {
             safe_zone_margin_ratio: 0.15,
             max_lookahead_ms: 400,
             velocity_threshold_px_per_ms: 0.55,
+            zoom_lead_ms: 0,
             click_activation_mode: ClickActivationMode::MultiClickWindow,
             activation_window_ms: 3_000,
             min_clicks_to_activate: 2,
             click_cluster_gap_ms: 300,
+            click_cluster_element_scale: 1.5,
+            min_click_cluster_radius_px: 24.0,
             min_zoom_interval_ms: 2_000,
             min_lock_duration_ms: 0,
+            min_segment_duration_ms: 600,
+            extend_short_segments: false,
             lock_recent_window_ms: 2_000,
+            caret_follow: false,
             spring_mass: mass,
             spring_stiffness: stiffness,
             spring_damping: damping,
@@ -150,6 +182,164 @@ pub struct CameraSample {
     pub target_zoom: f64,
 }
 
+/// Compact camera sample for timeline previews.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraTrackPoint {
+    pub ts: u64,
+    pub center_x: f64,
+    pub center_y: f64,
+    pub zoom: f64,
+    pub locked: bool,
+}
+
+impl From<&CameraSample> for CameraTrackPoint {
+    fn from(sample: &CameraSample) -> Self {
+        Self {
+            ts: sample.ts,
+            center_x: sample.center_x,
+            center_y: sample.center_y,
+            zoom: sample.zoom,
+            locked: sample.state.is_locked(),
+        }
+    }
+}
+
+/// Picks evenly spaced samples so the result has at most `max_points` entries
+/// (capped by [`MAX_CAMERA_TRACK_POINTS`]); the first and last samples are always kept.
+pub fn decimate_camera_samples(
+    samples: &[CameraSample],
+    max_points: usize,
+) -> Vec<CameraTrackPoint> {
+    let max_points = max_points.clamp(2, MAX_CAMERA_TRACK_POINTS);
+    if samples.len() <= max_points {
+        return samples.iter().map(CameraTrackPoint::from).collect();
+    }
+
+    let last_index = samples.len() - 1;
+    let step = last_index as f64 / (max_points - 1) as f64;
+    (0..max_points)
+        .map(|slot| {
+            let index = ((slot as f64 * step).round() as usize).min(last_index);
+            CameraTrackPoint::from(&samples[index])
+        })
+        .collect()
+}
+
+/// Picks evenly spaced target points of a segment so at most `max_points` remain (capped
+/// by [`MAX_CAMERA_TRACK_POINTS`]); the first and last points are always kept.
+pub fn decimate_target_points(points: &[TargetPoint], max_points: usize) -> Vec<TargetPoint> {
+    let max_points = max_points.clamp(2, MAX_CAMERA_TRACK_POINTS);
+    if points.len() <= max_points {
+        return points.to_vec();
+    }
+
+    let last_index = points.len() - 1;
+    let step = last_index as f64 / (max_points - 1) as f64;
+    (0..max_points)
+        .map(|slot| points[((slot as f64 * step).round() as usize).min(last_index)].clone())
+        .collect()
+}
+
+/// Why a click never reached clustering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClickGate {
+    /// Ctrl+click mode and Ctrl was not held.
+    CtrlNotPressed,
+    /// Multi-click mode and too few clicks fell into the activation window.
+    ActivationWindow,
+}
+
+/// One recorded click as seen by the smart camera.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClickDecision {
+    pub ts: u64,
+    pub x: f64,
+    pub y: f64,
+    pub has_bounds: bool,
+    pub ctrl_pressed: bool,
+    /// `None` when the click passed the activation mode.
+    pub gated_out: Option<ClickGate>,
+}
+
+/// What became of a click cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClusterOutcome {
+    /// Produced a focus transition.
+    Zoomed,
+    /// The target kept the full-frame zoom, so no transition was made.
+    SkippedNoOpZoom,
+}
+
+/// Screen-pixel rectangle in a decision trace.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Inputs and result of turning one click cluster into a focus transition.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterDecision {
+    pub first_click_ts: u64,
+    pub last_click_ts: u64,
+    pub click_ts: Vec<u64>,
+    /// Union of the UI element bounds of the clicks, if any were captured.
+    pub bounds: Option<TraceRect>,
+    /// `false` when the target came from the click position and `fallback_zoom`.
+    pub used_bounds: bool,
+    /// Transition start after the velocity-based pre-roll and `zoom_lead_ms`.
+    pub preroll_start_ts: u64,
+    /// Transition start after `min_zoom_interval_ms` from the previous transition.
+    pub start_ts: u64,
+    pub delayed_by_min_interval_ms: u64,
+    pub center_x: f64,
+    pub center_y: f64,
+    pub zoom: f64,
+    pub outcome: ClusterOutcome,
+}
+
+/// Step-by-step record of the smart camera's click handling, for support and debugging.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraDecisionTrace {
+    pub clicks: Vec<ClickDecision>,
+    pub clusters: Vec<ClusterDecision>,
+}
+
+/// Runs click gating, clustering and targeting exactly as [`process_camera_targets`]
+/// does and records every decision. Read-only over the events.
+pub fn trace_camera_decisions(
+    events: &[InputEvent],
+    screen_width: u32,
+    screen_height: u32,
+    output_aspect_ratio: f64,
+    config: &SmartCameraConfig,
+) -> CameraDecisionTrace {
+    let mut trace = CameraDecisionTrace::default();
+    if screen_width == 0 || screen_height == 0 {
+        return trace;
+    }
+    let velocity_samples = build_velocity_samples(&collect_cursor_samples(events));
+    build_focus_transitions(
+        events,
+        screen_width,
+        screen_height,
+        output_aspect_ratio.max(0.1),
+        &velocity_samples,
+        config,
+        Some(&mut trace),
+    );
+    trace
+}
+
 #[derive(Debug, Clone, Copy)]
 struct RectPx {
     x: f64,
@@ -215,6 +405,29 @@ struct FocusCluster {
     click_count: usize,
 }
 
+impl FocusCluster {
+    /// Appends a later cluster: the anchor follows the last click, the average weighs clicks.
+    fn merge(self, later: FocusCluster) -> FocusCluster {
+        let count = self.click_count + later.click_count;
+        let weight = |value: f64, other: f64| {
+            (value * self.click_count as f64 + other * later.click_count as f64) / count as f64
+        };
+        FocusCluster {
+            start_ts: self.start_ts,
+            end_ts: later.end_ts,
+            avg_x: weight(self.avg_x, later.avg_x),
+            avg_y: weight(self.avg_y, later.avg_y),
+            anchor_x: later.anchor_x,
+            anchor_y: later.anchor_y,
+            bounds: match (self.bounds, later.bounds) {
+                (Some(left), Some(right)) => Some(left.union(right)),
+                (bounds, None) | (None, bounds) => bounds,
+            },
+            click_count: count,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct FocusTransition {
     start_ts: u64,
@@ -224,6 +437,9 @@ struct FocusTransition {
     center_y: f64,
     zoom: f64,
     focus_rect: RectNorm,
+    /// Started by typing rather than clicks; a caret that stays inside the locked
+    /// viewport only extends the lock.
+    from_caret: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -279,6 +495,7 @@ pub fn process_camera_targets(
         safe_aspect,
         &velocity_samples,
         config,
+        None,
     );
 
     let mut sorted_events: Vec<&InputEvent> = events.iter().collect();
@@ -338,6 +555,14 @@ pub fn process_camera_targets(
                     cursor_x = x.clamp(0.0, width);
                     cursor_y = y.clamp(0.0, height);
                 }
+                // While typing, the caret rather than the parked pointer is the point of
+                // attention, so it drives the dead zone, escape and edge panning.
+                InputEvent::KeyDown {
+                    caret: Some(caret), ..
+                } if config.caret_follow => {
+                    cursor_x = (caret.x as f64 + caret.width as f64 * 0.5).clamp(0.0, width);
+                    cursor_y = (caret.y as f64 + caret.height as f64 * 0.5).clamp(0.0, height);
+                }
                 _ => {}
             }
 
@@ -417,11 +642,12 @@ pub fn process_camera_targets(
                 cluster_end_ts,
             } = state
             {
-                let keep_locked_target_on_inside_click = matches!(
-                    config.click_activation_mode,
-                    ClickActivationMode::MultiClickWindow
-                );
-                if keep_locked_target_on_inside_click {
+                let keep_locked_target_on_inside_focus = focus.from_caret
+                    || matches!(
+                        config.click_activation_mode,
+                        ClickActivationMode::MultiClickWindow
+                    );
+                if keep_locked_target_on_inside_focus {
                     let viewport = current_viewport_rect(
                         spring_x.current_pos,
                         spring_y.current_pos,
@@ -608,6 +834,7 @@ pub fn build_smart_camera_segments(
         );
     }
 
+    let mut segments = enforce_min_segment_duration(segments, duration_ms, config);
     for (idx, segment) in segments.iter_mut().enumerate() {
         segment.id = format!("auto-{}", idx + 1);
     }
@@ -615,6 +842,68 @@ pub fn build_smart_camera_segments(
     segments
 }
 
+/// Drops (or, when enabled, extends) segments shorter than `min_segment_duration_ms`.
+///
+/// Extension grows the end first and then the start, never overlapping neighbouring
+/// segments or leaving `[0, duration_ms]`. Segments that still do not fit are dropped.
+fn enforce_min_segment_duration(
+    segments: Vec<ZoomSegment>,
+    duration_ms: u64,
+    config: &SmartCameraConfig,
+) -> Vec<ZoomSegment> {
+    let min_duration = config.min_segment_duration_ms;
+    if min_duration == 0 {
+        return segments;
+    }
+
+    let mut result: Vec<ZoomSegment> = Vec::with_capacity(segments.len());
+    let next_starts = segments
+        .iter()
+        .skip(1)
+        .map(|segment| segment.start_ts)
+        .chain(std::iter::once(duration_ms.max(
+            segments.last().map(|segment| segment.end_ts).unwrap_or(0),
+        )))
+        .collect::<Vec<_>>();
+
+    for (mut segment, next_start) in segments.into_iter().zip(next_starts) {
+        let length = segment.end_ts.saturating_sub(segment.start_ts);
+        if length >= min_duration {
+            result.push(segment);
+            continue;
+        }
+        if !config.extend_short_segments {
+            continue;
+        }
+
+        let prev_end = result.last().map(|segment| segment.end_ts).unwrap_or(0);
+        let missing = min_duration - length;
+        let grow_end = missing.min(next_start.saturating_sub(segment.end_ts));
+        let grow_start = (missing - grow_end).min(segment.start_ts.saturating_sub(prev_end));
+        if grow_end + grow_start < missing {
+            continue;
+        }
+
+        segment.end_ts += grow_end;
+        segment.start_ts -= grow_start;
+        if let Some(first) = segment.target_points.first_mut() {
+            first.ts = first.ts.min(segment.start_ts);
+        }
+        if let Some(last) = segment.target_points.last().cloned() {
+            if last.ts < segment.end_ts {
+                segment.target_points.push(TargetPoint {
+                    ts: segment.end_ts,
+                    rect: last.rect,
+                    zoom: last.zoom,
+                });
+            }
+        }
+        result.push(segment);
+    }
+
+    result
+}
+
 fn push_locked_segment(
     locked_samples: &[CameraSample],
     output: &mut Vec<ZoomSegment>,
@@ -663,6 +952,7 @@ fn push_locked_segment(
                     screen_height,
                     output_aspect_ratio,
                 ),
+                zoom: None,
             });
             last_point_ts = sample.ts;
         }
@@ -684,6 +974,7 @@ fn push_locked_segment(
         mode: ZoomMode::FollowCursor,
         trigger: ZoomTrigger::AutoClick,
         is_auto: true,
+        label: None,
     });
 }
 
@@ -694,6 +985,38 @@ fn build_focus_transitions(
     output_aspect_ratio: f64,
     velocities: &[VelocitySample],
     config: &SmartCameraConfig,
+    trace: Option<&mut CameraDecisionTrace>,
+) -> Vec<FocusTransition> {
+    let mut transitions = build_click_transitions(
+        events,
+        screen_width,
+        screen_height,
+        output_aspect_ratio,
+        velocities,
+        config,
+        trace,
+    );
+    if config.caret_follow {
+        transitions.extend(build_caret_transitions(
+            events,
+            screen_width,
+            screen_height,
+            output_aspect_ratio,
+            config,
+        ));
+        transitions.sort_by_key(|transition| transition.start_ts);
+    }
+    transitions
+}
+
+fn build_click_transitions(
+    events: &[InputEvent],
+    screen_width: u32,
+    screen_height: u32,
+    output_aspect_ratio: f64,
+    velocities: &[VelocitySample],
+    config: &SmartCameraConfig,
+    mut trace: Option<&mut CameraDecisionTrace>,
 ) -> Vec<FocusTransition> {
     let clicks = collect_focus_clicks(events);
     if clicks.is_empty() {
@@ -701,15 +1024,48 @@ fn build_focus_transitions(
     }
 
     let gated_clicks = filter_clicks_by_activation_mode(&clicks, config);
+    if let Some(trace) = trace.as_deref_mut() {
+        // Single-click mode keeps every click, so only the other two modes can gate.
+        let gate = if config.click_activation_mode == ClickActivationMode::CtrlClick {
+            ClickGate::CtrlNotPressed
+        } else {
+            ClickGate::ActivationWindow
+        };
+        trace.clicks = clicks
+            .iter()
+            .map(|click| ClickDecision {
+                ts: click.ts,
+                x: click.x,
+                y: click.y,
+                has_bounds: click.bounds.is_some(),
+                ctrl_pressed: click.ctrl_pressed,
+                gated_out: (!gated_clicks
+                    .iter()
+                    .any(|kept| kept.ts == click.ts && kept.x == click.x && kept.y == click.y))
+                .then_some(gate),
+            })
+            .collect();
+    }
     if gated_clicks.is_empty() {
         return Vec::new();
     }
 
-    let clusters = cluster_focus_clicks(&gated_clicks, config.click_cluster_gap_ms.max(1));
+    let clusters = merge_preroll_clusters(
+        cluster_focus_clicks(&gated_clicks, config),
+        &gated_clicks,
+        velocities,
+        config,
+    );
     let mut transitions = Vec::with_capacity(clusters.len());
     let mut last_transition_start: Option<u64> = None;
-    for cluster in clusters {
-        let start_ts = choose_preroll_start(cluster.start_ts, velocities, config);
+    let mut last_transition_end: Option<u64> = None;
+    for (cluster, preroll_start_ts) in clusters {
+        let lead_floor = last_transition_end.unwrap_or(0).min(cluster.start_ts);
+        let lead_start_ts = cluster
+            .start_ts
+            .saturating_sub(config.zoom_lead_ms)
+            .max(lead_floor);
+        let start_ts = preroll_start_ts.min(lead_start_ts);
         let mut actual_start_ts = start_ts;
         if let Some(last_start) = last_transition_start {
             let min_allowed_start = last_start.saturating_add(config.min_zoom_interval_ms.max(1));
@@ -729,12 +1085,12 @@ fn build_focus_transitions(
         let free_roam_zoom = config.free_roam_zoom.max(1.0);
         // For strict click modes, fallback to click-centered zoom when semantic bounds
         // result in a no-op full-frame target.
-        if zoom <= free_roam_zoom + 0.001
+        let used_click_fallback = zoom <= free_roam_zoom + 0.001
             && matches!(
                 config.click_activation_mode,
                 ClickActivationMode::SingleClick | ClickActivationMode::CtrlClick
-            )
-        {
+            );
+        if used_click_fallback {
             let fallback = fallback_target(
                 cluster.anchor_x,
                 cluster.anchor_y,
@@ -747,8 +1103,38 @@ fn build_focus_transitions(
             center_y = fallback.1;
             zoom = clamp_locked_zoom(fallback.2, config);
         }
+        let is_no_op = zoom <= free_roam_zoom + 0.001;
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.clusters.push(ClusterDecision {
+                first_click_ts: cluster.start_ts,
+                last_click_ts: cluster.end_ts,
+                click_ts: gated_clicks
+                    .iter()
+                    .filter(|click| click.ts >= cluster.start_ts && click.ts <= cluster.end_ts)
+                    .map(|click| click.ts)
+                    .collect(),
+                bounds: cluster.bounds.map(|bounds| TraceRect {
+                    x: bounds.x,
+                    y: bounds.y,
+                    width: bounds.width,
+                    height: bounds.height,
+                }),
+                used_bounds: cluster.bounds.is_some() && !used_click_fallback,
+                preroll_start_ts: start_ts,
+                start_ts: actual_start_ts,
+                delayed_by_min_interval_ms: actual_start_ts - start_ts,
+                center_x,
+                center_y,
+                zoom,
+                outcome: if is_no_op {
+                    ClusterOutcome::SkippedNoOpZoom
+                } else {
+                    ClusterOutcome::Zoomed
+                },
+            });
+        }
         // Ignore no-op transitions that keep full-frame context.
-        if zoom <= free_roam_zoom + 0.001 {
+        if is_no_op {
             continue;
         }
         let focus_rect = focus_rect_from_cluster(cluster, screen_width, screen_height);
@@ -757,22 +1143,100 @@ fn build_focus_transitions(
             .start_ts
             .saturating_add(config.min_lock_duration_ms.max(1))
             .saturating_add(cluster_tail_bonus_ms);
+        let cluster_end_ts = cluster.end_ts.max(min_cluster_end).max(actual_start_ts);
         transitions.push(FocusTransition {
             start_ts: actual_start_ts,
             trigger_ts: cluster.start_ts,
-            cluster_end_ts: cluster.end_ts.max(min_cluster_end).max(actual_start_ts),
+            cluster_end_ts,
             center_x,
             center_y,
             zoom,
             focus_rect,
+            from_caret: false,
         });
         last_transition_start = Some(actual_start_ts);
+        last_transition_end = Some(cluster_end_ts);
     }
 
     transitions.sort_by_key(|transition| transition.start_ts);
     transitions
 }
 
+/// Typing pause after which the next caret sample opens a new focus transition.
+const CARET_BURST_GAP_MS: u64 = 1_500;
+
+/// Focus transitions that follow the text caret while the user types.
+///
+/// A caret sample that arrives within [`CARET_BURST_GAP_MS`] of the current burst and
+/// stays inside its safe zone only extends it; any other sample starts a new transition
+/// centred on the caret. Keystrokes without a caret (the app does not report one) add
+/// nothing, so the camera falls back to click-driven focus.
+fn build_caret_transitions(
+    events: &[InputEvent],
+    screen_width: u32,
+    screen_height: u32,
+    output_aspect_ratio: f64,
+    config: &SmartCameraConfig,
+) -> Vec<FocusTransition> {
+    let mut carets = events
+        .iter()
+        .filter_map(|event| match event {
+            InputEvent::KeyDown {
+                ts,
+                caret: Some(caret),
+                ..
+            } => rect_from_bounds(caret).map(|rect| (*ts, rect)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    carets.sort_by_key(|(ts, _)| *ts);
+
+    let free_roam_zoom = config.free_roam_zoom.max(1.0);
+    let mut transitions: Vec<FocusTransition> = Vec::new();
+    for (ts, rect) in carets {
+        let focus_rect = normalize_rect_px(rect, screen_width, screen_height);
+        if let Some(burst) = transitions.last_mut() {
+            let viewport = current_viewport_rect(
+                burst.center_x,
+                burst.center_y,
+                burst.zoom,
+                screen_width,
+                screen_height,
+                output_aspect_ratio,
+            );
+            if ts.saturating_sub(burst.cluster_end_ts) <= CARET_BURST_GAP_MS
+                && inset_rect(viewport, config.safe_zone_margin_ratio).contains(focus_rect)
+            {
+                burst.cluster_end_ts = burst.cluster_end_ts.max(ts);
+                continue;
+            }
+        }
+
+        let (center_x, center_y, zoom) = fallback_target(
+            rect.x + rect.width * 0.5,
+            rect.y + rect.height * 0.5,
+            screen_width,
+            screen_height,
+            output_aspect_ratio,
+            config,
+        );
+        if zoom <= free_roam_zoom + 0.001 {
+            continue;
+        }
+        transitions.push(FocusTransition {
+            start_ts: ts,
+            trigger_ts: ts,
+            cluster_end_ts: ts.saturating_add(config.min_lock_duration_ms.max(1)),
+            center_x,
+            center_y,
+            zoom,
+            focus_rect,
+            from_caret: true,
+        });
+    }
+    transitions
+}
+
 fn collect_focus_clicks(events: &[InputEvent]) -> Vec<FocusClick> {
     let mut sorted_events = events.iter().collect::<Vec<_>>();
     sorted_events.sort_by_key(|event| event.ts());
@@ -868,10 +1332,39 @@ fn filter_clicks_by_activation_window(
         .collect()
 }
 
-fn cluster_focus_clicks(clicks: &[FocusClick], gap_ms: u64) -> Vec<FocusCluster> {
+/// Distance under which `click` joins the cluster ending with `previous`.
+///
+/// Scales with the clicked elements, so neighbouring toolbar buttons stay apart while
+/// clicks across one large panel merge. `None` when neither click has element bounds:
+/// the time gap alone decides.
+fn click_merge_radius_px(
+    previous: &FocusClick,
+    click: &FocusClick,
+    config: &SmartCameraConfig,
+) -> Option<f64> {
+    if config.click_cluster_element_scale <= 0.0 {
+        return None;
+    }
+    let sizes = [previous.bounds, click.bounds]
+        .into_iter()
+        .flatten()
+        .map(|bounds| bounds.width.max(bounds.height))
+        .collect::<Vec<_>>();
+    if sizes.is_empty() {
+        return None;
+    }
+    let element_size = sizes.iter().sum::<f64>() / sizes.len() as f64;
+    Some(
+        (element_size * config.click_cluster_element_scale)
+            .max(config.min_click_cluster_radius_px.max(0.0)),
+    )
+}
+
+fn cluster_focus_clicks(clicks: &[FocusClick], config: &SmartCameraConfig) -> Vec<FocusCluster> {
     if clicks.is_empty() {
         return Vec::new();
     }
+    let gap_ms = config.click_cluster_gap_ms.max(1);
 
     let mut clusters: Vec<FocusCluster> = Vec::new();
     let mut current_start = clicks[0].ts;
@@ -883,9 +1376,12 @@ fn cluster_focus_clicks(clicks: &[FocusClick], gap_ms: u64) -> Vec<FocusCluster>
     let mut current_anchor_y = clicks[0].y;
     let mut current_bounds = clicks[0].bounds;
 
-    for click in clicks.iter().skip(1) {
+    for (previous, click) in clicks.iter().zip(clicks.iter().skip(1)) {
         let gap = click.ts.saturating_sub(current_end);
-        if gap <= gap_ms {
+        let near = click_merge_radius_px(previous, click, config).map_or(true, |radius| {
+            (click.x - previous.x).hypot(click.y - previous.y) <= radius
+        });
+        if gap <= gap_ms && near {
             current_end = click.ts;
             current_sum_x += click.x;
             current_sum_y += click.y;
@@ -936,6 +1432,38 @@ fn cluster_focus_clicks(clicks: &[FocusClick], gap_ms: u64) -> Vec<FocusCluster>
     clusters
 }
 
+/// Pairs each cluster with its pre-roll start, folding a cluster into the previous one when
+/// that cluster's last click falls inside its pre-roll window: the camera would already be
+/// heading for the second target, so "move, click, click" becomes one zoom instead of two.
+fn merge_preroll_clusters(
+    clusters: Vec<FocusCluster>,
+    clicks: &[FocusClick],
+    velocities: &[VelocitySample],
+    config: &SmartCameraConfig,
+) -> Vec<(FocusCluster, u64)> {
+    let mut merged: Vec<(FocusCluster, u64)> = Vec::with_capacity(clusters.len());
+    for cluster in clusters {
+        let preroll_start_ts = choose_preroll_start(cluster.start_ts, velocities, config);
+        if let Some((previous, _)) = merged.last_mut() {
+            let last_click = clicks.iter().find(|click| click.ts == previous.end_ts);
+            let first_click = clicks.iter().find(|click| click.ts == cluster.start_ts);
+            let near = match (last_click, first_click) {
+                (Some(last), Some(first)) => click_merge_radius_px(last, first, config)
+                    .map_or(true, |radius| {
+                        (first.x - last.x).hypot(first.y - last.y) <= radius
+                    }),
+                _ => true,
+            };
+            if previous.end_ts >= preroll_start_ts && near {
+                *previous = previous.merge(cluster);
+                continue;
+            }
+        }
+        merged.push((cluster, preroll_start_ts));
+    }
+    merged
+}
+
 fn collect_cursor_samples(events: &[InputEvent]) -> Vec<CursorSample> {
     let mut samples = events
         .iter()
@@ -1479,6 +2007,30 @@ mod tests {
         assert_eq!(segment.mode, ZoomMode::FollowCursor);
     }
 
+    #[test]
+    fn zoom_lead_starts_the_segment_before_the_click() {
+        let events = vec![click_with_bounds(2_000, 960.0, 540.0, None)];
+        let segment_start = |zoom_lead_ms: u64| {
+            let cfg = SmartCameraConfig {
+                click_activation_mode: ClickActivationMode::SingleClick,
+                min_clicks_to_activate: 1,
+                zoom_lead_ms,
+                ..SmartCameraConfig::default()
+            };
+            let segments =
+                build_smart_camera_segments(&events, 1_920, 1_080, 5_000, 16.0 / 9.0, &cfg);
+            assert_eq!(segments.len(), 1);
+            segments[0].start_ts
+        };
+
+        let without_lead = segment_start(0);
+        let with_lead = segment_start(500);
+        assert!(
+            without_lead.abs_diff(with_lead + 500) <= SmartCameraConfig::default().fixed_dt_ms,
+            "lead should advance the start by 500ms: {without_lead} vs {with_lead}"
+        );
+    }
+
     #[test]
     fn single_click_is_ignored_by_default_activation_rule() {
         let events = vec![click_with_bounds(1_000, 960.0, 540.0, None)];
@@ -1490,6 +2042,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn caret_follow_locks_onto_the_typing_position() {
+        let mut events = vec![InputEvent::Move {
+            ts: 0,
+            x: 100.0,
+            y: 100.0,
+        }];
+        events.extend((0..10u64).map(|idx| InputEvent::KeyDown {
+            ts: 1_000 + idx * 200,
+            key_code: "KeyA".to_string(),
+            // Apps that report no caret leave gaps; those keystrokes are ignored.
+            caret: (idx % 3 != 2).then(|| BoundingRect {
+                x: 1_400 + idx as i32 * 8,
+                y: 800,
+                width: 1,
+                height: 18,
+            }),
+        }));
+
+        let track = process_camera_targets(
+            &events,
+            1_920,
+            1_080,
+            3_500,
+            16.0 / 9.0,
+            &SmartCameraConfig::default(),
+        );
+        assert!(track.iter().all(|sample| !sample.state.is_locked()));
+
+        let cfg = SmartCameraConfig {
+            caret_follow: true,
+            ..SmartCameraConfig::default()
+        };
+        let track = process_camera_targets(&events, 1_920, 1_080, 3_500, 16.0 / 9.0, &cfg);
+        let typing = track
+            .iter()
+            .find(|sample| sample.ts >= 2_800)
+            .expect("sample while typing");
+        assert!(typing.state.is_locked(), "typing should hold the lock");
+        assert!(typing.target_zoom > 1.5);
+        assert!(
+            typing.target_center_x > 0.6 && typing.target_center_y > 0.6,
+            "camera should frame the caret, not the parked pointer: {typing:?}"
+        );
+    }
+
     #[test]
     fn two_clicks_within_activation_window_trigger_zoom() {
         let events = vec![
@@ -1511,6 +2109,7 @@ mod tests {
             InputEvent::KeyDown {
                 ts: 1_400,
                 key_code: "ControlLeft".to_string(),
+                caret: None,
             },
             click_with_bounds(1_500, 960.0, 540.0, None),
             InputEvent::KeyUp {
@@ -1824,4 +2423,267 @@ mod tests {
             after.state
         );
     }
+
+    fn locked_segment(start_ts: u64, end_ts: u64) -> ZoomSegment {
+        let rect = NormalizedRect {
+            x: 0.25,
+            y: 0.25,
+            width: 0.5,
+            height: 0.5,
+        };
+        ZoomSegment {
+            id: String::new(),
+            start_ts,
+            end_ts,
+            initial_rect: rect.clone(),
+            target_points: vec![
+                TargetPoint {
+                    ts: start_ts,
+                    rect: rect.clone(),
+                    zoom: None,
+                },
+                TargetPoint {
+                    ts: end_ts,
+                    rect,
+                    zoom: None,
+                },
+            ],
+            spring: CameraSpring {
+                mass: 1.0,
+                stiffness: 170.0,
+                damping: 26.0,
+            },
+            pan_trajectory: Vec::new(),
+            legacy_easing: None,
+            mode: ZoomMode::FollowCursor,
+            trigger: ZoomTrigger::AutoClick,
+            is_auto: true,
+            label: None,
+        }
+    }
+
+    #[test]
+    fn micro_cluster_segment_is_dropped_under_default_threshold() {
+        let cfg = SmartCameraConfig::default();
+        let segments = vec![locked_segment(1_000, 1_150), locked_segment(3_000, 5_000)];
+        let filtered = enforce_min_segment_duration(segments, 6_000, &cfg);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].start_ts, 3_000);
+    }
+
+    #[test]
+    fn short_segment_extension_respects_neighbours() {
+        let cfg = SmartCameraConfig {
+            extend_short_segments: true,
+            ..SmartCameraConfig::default()
+        };
+        let segments = vec![
+            locked_segment(0, 1_000),
+            locked_segment(1_100, 1_300),
+            locked_segment(1_600, 3_000),
+            locked_segment(3_100, 3_200),
+            locked_segment(3_300, 4_000),
+        ];
+        let extended = enforce_min_segment_duration(segments, 4_000, &cfg);
+        assert_eq!(
+            extended.len(),
+            4,
+            "segment squeezed between neighbours is dropped"
+        );
+        assert_eq!((extended[1].start_ts, extended[1].end_ts), (1_000, 1_600));
+        assert_eq!(extended[1].target_points.last().map(|p| p.ts), Some(1_600));
+    }
+
+    #[test]
+    fn camera_track_preview_is_decimated_and_config_round_trips() {
+        let cfg: SmartCameraConfig = serde_json::from_str(
+            r#"{"springStiffness": 90.0, "clickActivationMode": "single-click"}"#,
+        )
+        .expect("partial config should deserialize");
+        assert_eq!(cfg.spring_stiffness, 90.0);
+        assert_eq!(cfg.click_activation_mode, ClickActivationMode::SingleClick);
+        assert_eq!(cfg.fixed_dt_ms, SmartCameraConfig::default().fixed_dt_ms);
+
+        let events = vec![click_with_bounds(500, 300.0, 300.0, None)];
+        let track = process_camera_targets(&events, 1_920, 1_080, 10_000, 16.0 / 9.0, &cfg);
+        assert!(track.len() > 100);
+
+        let points = decimate_camera_samples(&track, 100);
+        assert_eq!(points.len(), 100);
+        assert_eq!(points[0].ts, track[0].ts);
+        assert_eq!(points[99].ts, track[track.len() - 1].ts);
+        assert!(points.windows(2).all(|pair| pair[0].ts < pair[1].ts));
+
+        let capped = decimate_camera_samples(&track, usize::MAX);
+        assert!(capped.len() <= MAX_CAMERA_TRACK_POINTS);
+    }
+
+    #[test]
+    fn segment_target_points_are_decimated_keeping_both_ends() {
+        let rect = NormalizedRect {
+            x: 0.0,
+            y: 0.0,
+            width: 0.5,
+            height: 0.5,
+        };
+        let points = (0..1_000)
+            .map(|index| TargetPoint {
+                ts: index * 10,
+                rect: rect.clone(),
+                zoom: None,
+            })
+            .collect::<Vec<_>>();
+
+        let decimated = decimate_target_points(&points, 50);
+        assert_eq!(decimated.len(), 50);
+        assert_eq!(decimated[0].ts, 0);
+        assert_eq!(decimated[49].ts, 9_990);
+        assert!(decimated.windows(2).all(|pair| pair[0].ts < pair[1].ts));
+
+        assert_eq!(decimate_target_points(&points[..3], 50).len(), 3);
+    }
+
+    #[test]
+    fn click_clustering_distance_scales_with_element_size() {
+        let cfg = SmartCameraConfig::default();
+        let click = |ts: u64, x: f64, bounds: Option<RectPx>| FocusClick {
+            ts,
+            x,
+            y: 40.0,
+            bounds,
+            ctrl_pressed: false,
+        };
+        let button = |x: f64| {
+            Some(RectPx {
+                x: x - 12.0,
+                y: 28.0,
+                width: 24.0,
+                height: 24.0,
+            })
+        };
+
+        // Dense toolbar: distinct 24px buttons 40px apart stay separate zooms...
+        let toolbar = [
+            click(1_000, 100.0, button(100.0)),
+            click(1_150, 140.0, button(140.0)),
+            click(1_300, 180.0, button(180.0)),
+        ];
+        assert_eq!(cluster_focus_clicks(&toolbar, &cfg).len(), 3);
+        // ...while a double click on one of them still merges.
+        let double = [
+            click(1_000, 100.0, button(100.0)),
+            click(1_150, 104.0, button(100.0)),
+        ];
+        assert_eq!(cluster_focus_clicks(&double, &cfg).len(), 1);
+
+        // Sparse panel: clicks 500px apart inside one 600px panel merge.
+        let panel = Some(RectPx {
+            x: 50.0,
+            y: 0.0,
+            width: 600.0,
+            height: 400.0,
+        });
+        let sparse = [click(1_000, 100.0, panel), click(1_200, 600.0, panel)];
+        let clusters = cluster_focus_clicks(&sparse, &cfg);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].click_count, 2);
+
+        // Without element bounds the time gap alone decides, as before.
+        let unknown = [click(1_000, 100.0, None), click(1_200, 1_500.0, None)];
+        assert_eq!(cluster_focus_clicks(&unknown, &cfg).len(), 1);
+        let time_only = SmartCameraConfig {
+            click_cluster_element_scale: 0.0,
+            ..SmartCameraConfig::default()
+        };
+        assert_eq!(cluster_focus_clicks(&toolbar, &time_only).len(), 1);
+    }
+
+    #[test]
+    fn clicks_inside_the_preroll_window_join_the_next_cluster() {
+        let cfg = SmartCameraConfig {
+            click_activation_mode: ClickActivationMode::SingleClick,
+            ..SmartCameraConfig::default()
+        };
+        // The cursor settles before the first click and stays put, so the pre-roll of the
+        // clicks 350ms later (past the cluster gap) reaches back to it.
+        let events = vec![
+            InputEvent::Move {
+                ts: 700,
+                x: 300.0,
+                y: 300.0,
+            },
+            InputEvent::Move {
+                ts: 850,
+                x: 490.0,
+                y: 300.0,
+            },
+            click_with_bounds(1_000, 500.0, 300.0, None),
+            InputEvent::Move {
+                ts: 1_200,
+                x: 505.0,
+                y: 300.0,
+            },
+            click_with_bounds(1_350, 510.0, 302.0, None),
+            click_with_bounds(1_400, 511.0, 302.0, None),
+        ];
+        assert_eq!(
+            cluster_focus_clicks(&collect_focus_clicks(&events), &cfg).len(),
+            2
+        );
+
+        let trace = trace_camera_decisions(&events, 1_920, 1_080, 16.0 / 9.0, &cfg);
+        assert_eq!(trace.clusters.len(), 1);
+        let cluster = &trace.clusters[0];
+        assert_eq!(cluster.click_ts, vec![1_000, 1_350, 1_400]);
+        assert_eq!(cluster.start_ts, 1_000);
+        assert_eq!(cluster.outcome, ClusterOutcome::Zoomed);
+    }
+
+    #[test]
+    fn decision_trace_reports_gating_and_min_interval() {
+        let cfg = SmartCameraConfig {
+            click_activation_mode: ClickActivationMode::MultiClickWindow,
+            activation_window_ms: 500,
+            min_clicks_to_activate: 2,
+            click_cluster_gap_ms: 300,
+            min_zoom_interval_ms: 2_000,
+            ..SmartCameraConfig::default()
+        };
+        let bounds = Some(BoundingRect {
+            x: 400,
+            y: 200,
+            width: 240,
+            height: 120,
+        });
+        let events = vec![
+            // Lone click: gated out by the activation window.
+            click_with_bounds(100, 500.0, 250.0, bounds.clone()),
+            // Double click: zooms on the element bounds.
+            click_with_bounds(3_000, 500.0, 250.0, bounds.clone()),
+            click_with_bounds(3_200, 510.0, 255.0, bounds.clone()),
+            // Second double click right after: delayed by the minimum zoom interval.
+            click_with_bounds(3_800, 900.0, 700.0, None),
+            click_with_bounds(4_000, 905.0, 705.0, None),
+        ];
+
+        let trace = trace_camera_decisions(&events, 1_920, 1_080, 16.0 / 9.0, &cfg);
+        assert_eq!(trace.clicks.len(), 5);
+        assert_eq!(trace.clicks[0].gated_out, Some(ClickGate::ActivationWindow));
+        assert!(trace.clicks[1..]
+            .iter()
+            .all(|click| click.gated_out.is_none()));
+
+        assert_eq!(trace.clusters.len(), 2);
+        let first = &trace.clusters[0];
+        assert_eq!(first.click_ts, vec![3_000, 3_200]);
+        assert!(first.used_bounds);
+        assert_eq!(first.outcome, ClusterOutcome::Zoomed);
+        assert_eq!(first.delayed_by_min_interval_ms, 0);
+
+        let second = &trace.clusters[1];
+        assert_eq!(second.click_ts, vec![3_800, 4_000]);
+        assert!(!second.used_bounds);
+        assert_eq!(second.start_ts, first.start_ts + 2_000);
+        assert!(second.delayed_by_min_interval_ms > 0);
+    }
 }
@@ -0,0 +1,182 @@
+use crate::models::events::InputEvent;
+
+pub const DEFAULT_MIN_CHAPTER_DWELL_MS: u64 = 5_000;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterMarker {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub label: String,
+}
+
+#[derive(Debug, Clone)]
+struct ContextRun {
+    label: String,
+    start_ms: u64,
+}
+
+/// Splits a recording into chapters by sustained application switches.
+///
+/// Consecutive clicks in the same application form a run. A run only opens a new
+/// chapter when the user stays in that application for at least `min_dwell_ms`
+/// (measured until the next switch), so quick alt-tab detours are folded into the
+/// surrounding chapter.
+pub fn detect_context_chapters(
+    events: &[InputEvent],
+    duration_ms: u64,
+    min_dwell_ms: u64,
+) -> Vec<ChapterMarker> {
+    let mut runs = Vec::<ContextRun>::new();
+    let mut clicks = events
+        .iter()
+        .filter_map(|event| match event {
+            InputEvent::Click { ts, ui_context, .. } => ui_context
+                .as_ref()
+                .and_then(|context| context.app_name.as_deref())
+                .map(str::trim)
+                // Old recordings stored only the process id, which is no use as a title.
+                .filter(|name| !name.is_empty() && !name.starts_with("pid:"))
+                .map(|name| (*ts, name.to_string())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    clicks.sort_by_key(|(ts, _)| *ts);
+
+    for (ts, label) in clicks {
+        if runs.last().is_some_and(|run| run.label == label) {
+            continue;
+        }
+        runs.push(ContextRun {
+            label,
+            start_ms: ts,
+        });
+    }
+
+    let duration_ms = duration_ms.max(runs.last().map(|run| run.start_ms + 1).unwrap_or(0));
+    let mut chapters = Vec::<ChapterMarker>::new();
+    for (index, run) in runs.iter().enumerate() {
+        let next_start = runs
+            .get(index + 1)
+            .map(|next| next.start_ms)
+            .unwrap_or(duration_ms);
+        let dwell_ms = next_start.saturating_sub(run.start_ms);
+        if !chapters.is_empty() && dwell_ms < min_dwell_ms {
+            continue;
+        }
+        if chapters
+            .last()
+            .is_some_and(|chapter: &ChapterMarker| chapter.label == run.label)
+        {
+            continue;
+        }
+
+        if let Some(previous) = chapters.last_mut() {
+            previous.end_ms = run.start_ms;
+        }
+        chapters.push(ChapterMarker {
+            start_ms: if chapters.is_empty() { 0 } else { run.start_ms },
+            end_ms: duration_ms,
+            label: run.label.clone(),
+        });
+    }
+
+    chapters
+}
+
+/// Serializes chapters into an FFmpeg `FFMETADATA1` file body (millisecond timebase).
+pub fn build_ffmetadata_chapters(chapters: &[ChapterMarker]) -> String {
+    let mut output = String::from(";FFMETADATA1\n");
+    for chapter in chapters {
+        output.push_str("\n[CHAPTER]\nTIMEBASE=1/1000\n");
+        output.push_str(&format!("START={}\n", chapter.start_ms));
+        output.push_str(&format!(
+            "END={}\n",
+            chapter.end_ms.max(chapter.start_ms + 1)
+        ));
+        output.push_str(&format!(
+            "title={}\n",
+            escape_ffmetadata_value(&chapter.label)
+        ));
+    }
+    output
+}
+
+fn escape_ffmetadata_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '=' | ';' | '#' | '\\' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::events::{MouseButton, UiContext};
+
+    fn click_in_app(ts: u64, app_name: &str) -> InputEvent {
+        InputEvent::Click {
+            ts,
+            x: 0.0,
+            y: 0.0,
+            button: MouseButton::Left,
+            ui_context: Some(UiContext {
+                app_name: Some(app_name.to_string()),
+                control_name: None,
+                bounding_rect: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn short_detours_do_not_open_chapters() {
+        let events = vec![
+            click_in_app(1_000, "chrome"),
+            click_in_app(8_000, "explorer"),
+            click_in_app(9_000, "chrome"),
+            click_in_app(20_000, "Code"),
+        ];
+
+        let chapters = detect_context_chapters(&events, 40_000, 5_000);
+
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].start_ms, 0);
+        assert_eq!(chapters[0].end_ms, 20_000);
+        assert_eq!(chapters[0].label, "chrome");
+        assert_eq!(chapters[1].start_ms, 20_000);
+        assert_eq!(chapters[1].end_ms, 40_000);
+        assert_eq!(chapters[1].label, "Code");
+    }
+
+    #[test]
+    fn legacy_process_id_labels_do_not_become_chapters() {
+        let events = vec![
+            click_in_app(1_000, "pid:10"),
+            click_in_app(10_000, "pid:20"),
+        ];
+
+        assert!(detect_context_chapters(&events, 20_000, 5_000).is_empty());
+    }
+
+    #[test]
+    fn ffmetadata_escapes_special_characters() {
+        let chapters = vec![ChapterMarker {
+            start_ms: 0,
+            end_ms: 1_000,
+            label: "a=b;c".to_string(),
+        }];
+
+        let metadata = build_ffmetadata_chapters(&chapters);
+
+        assert!(metadata.starts_with(";FFMETADATA1\n"));
+        assert!(metadata.contains("START=0\nEND=1000\ntitle=a\\=b\\;c\n"));
+    }
+}
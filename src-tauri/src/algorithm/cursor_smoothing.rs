@@ -1,5 +1,8 @@
 use crate::models::events::InputEvent;
 
+/// Resample rate used before smoothing when the project does not override it.
+pub const DEFAULT_RESAMPLE_HZ: f64 = 120.0;
+
 #[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CursorPoint {
@@ -34,11 +37,28 @@ pub fn collect_cursor_points(events: &[InputEvent]) -> Vec<CursorPoint> {
 }
 
 pub fn smooth_cursor_path(events: &[InputEvent], smoothing_factor: f64) -> Vec<CursorPoint> {
+    smooth_cursor_path_at_rate(events, smoothing_factor, DEFAULT_RESAMPLE_HZ)
+}
+
+/// Same as [`smooth_cursor_path`] with an explicit resample rate (clamped to 30–240 Hz).
+pub fn smooth_cursor_path_at_rate(
+    events: &[InputEvent],
+    smoothing_factor: f64,
+    resample_hz: f64,
+) -> Vec<CursorPoint> {
     let points = collect_cursor_points(events);
-    smooth_cursor_points(&points, smoothing_factor)
+    smooth_cursor_points_at_rate(&points, smoothing_factor, resample_hz)
 }
 
 pub fn smooth_cursor_points(points: &[CursorPoint], smoothing_factor: f64) -> Vec<CursorPoint> {
+    smooth_cursor_points_at_rate(points, smoothing_factor, DEFAULT_RESAMPLE_HZ)
+}
+
+pub fn smooth_cursor_points_at_rate(
+    points: &[CursorPoint],
+    smoothing_factor: f64,
+    resample_hz: f64,
+) -> Vec<CursorPoint> {
     if points.len() < 2 {
         return points.to_vec();
     }
@@ -48,7 +68,7 @@ pub fn smooth_cursor_points(points: &[CursorPoint], smoothing_factor: f64) -> Ve
         return points.to_vec();
     }
 
-    let resampled = resample_points(points, 120.0);
+    let resampled = resample_points(points, resample_hz);
     if resampled.len() < 2 {
         return resampled;
     }
@@ -75,7 +95,8 @@ pub fn catmull_rom_interpolate(
     catmull_rom_interpolate_impl(points, samples_per_segment)
 }
 
-fn resample_points(points: &[CursorPoint], hz: f64) -> Vec<CursorPoint> {
+/// Resamples the path onto a uniform `hz` grid (clamped to 30–240 Hz), keeping click samples.
+pub fn resample_points(points: &[CursorPoint], hz: f64) -> Vec<CursorPoint> {
     if points.len() < 2 {
         return points.to_vec();
     }
@@ -83,7 +104,7 @@ fn resample_points(points: &[CursorPoint], hz: f64) -> Vec<CursorPoint> {
     let safe_hz = if hz.is_finite() {
         hz.clamp(30.0, 240.0)
     } else {
-        120.0
+        DEFAULT_RESAMPLE_HZ
     };
     let step_ms = 1_000.0 / safe_hz;
     let mut sorted = dedupe_points(points.to_vec());
@@ -312,9 +333,21 @@ fn lerp_ts(start: u64, end: u64, t: f64) -> u64 {
     (start_f + (end_f - start_f) * t).round() as u64
 }
 
+/// Samples this close to a click are moved onto it; about one step at the default resample rate.
+const CLICK_SNAP_TOLERANCE_MS: u64 = 8;
+
+/// Forces the sample nearest to each click (within [`CLICK_SNAP_TOLERANCE_MS`]) onto the exact
+/// click time and position, so the rendered cursor sits on the click target when it clicks.
 fn snap_click_points(mut points: Vec<CursorPoint>, reference: &[CursorPoint]) -> Vec<CursorPoint> {
     for click_point in reference.iter().copied().filter(|point| point.is_click) {
-        if let Some(existing) = points.iter_mut().find(|point| point.ts == click_point.ts) {
+        let nearest = points
+            .iter_mut()
+            // Do not steal a sample another click already snapped to.
+            .filter(|point| !point.is_click || point.ts == click_point.ts)
+            .map(|point| (point.ts.abs_diff(click_point.ts), point))
+            .filter(|(distance, _)| *distance <= CLICK_SNAP_TOLERANCE_MS)
+            .min_by_key(|(distance, _)| *distance);
+        if let Some((_, existing)) = nearest {
             *existing = click_point;
         } else {
             points.push(click_point);
@@ -466,6 +499,85 @@ mod tests {
         assert!(click_point.is_click);
     }
 
+    #[test]
+    fn click_snaps_to_the_nearest_non_aligned_sample() {
+        let points = vec![
+            CursorPoint {
+                ts: 0,
+                x: 0.0,
+                y: 0.0,
+                is_click: false,
+            },
+            CursorPoint {
+                ts: 8,
+                x: 8.0,
+                y: 0.0,
+                is_click: false,
+            },
+            CursorPoint {
+                ts: 17,
+                x: 17.0,
+                y: 0.0,
+                is_click: false,
+            },
+            CursorPoint {
+                ts: 25,
+                x: 25.0,
+                y: 0.0,
+                is_click: false,
+            },
+        ];
+        let clicks = [
+            CursorPoint {
+                ts: 14,
+                x: 40.0,
+                y: 30.0,
+                is_click: true,
+            },
+            // Nearest sample is taken by the first click; the next free one is used.
+            CursorPoint {
+                ts: 19,
+                x: 60.0,
+                y: 35.0,
+                is_click: true,
+            },
+        ];
+
+        let snapped = snap_click_points(points, &clicks);
+        let timestamps: Vec<u64> = snapped.iter().map(|point| point.ts).collect();
+        assert_eq!(timestamps, vec![0, 8, 14, 19]);
+        assert_eq!(snapped[2], clicks[0]);
+        assert_eq!(snapped[3], clicks[1]);
+    }
+
+    #[test]
+    fn click_far_from_every_sample_is_inserted() {
+        let points = vec![
+            CursorPoint {
+                ts: 0,
+                x: 0.0,
+                y: 0.0,
+                is_click: false,
+            },
+            CursorPoint {
+                ts: 100,
+                x: 100.0,
+                y: 0.0,
+                is_click: false,
+            },
+        ];
+        let click = CursorPoint {
+            ts: 50,
+            x: 10.0,
+            y: 90.0,
+            is_click: true,
+        };
+
+        let snapped = snap_click_points(points, &[click]);
+        assert_eq!(snapped.len(), 3);
+        assert_eq!(snapped[1], click);
+    }
+
     #[test]
     fn resampler_generates_stable_time_grid() {
         let points = vec![
@@ -497,4 +609,29 @@ mod tests {
             .collect();
         assert!(deltas.iter().all(|delta| *delta >= 7 && *delta <= 10));
     }
+
+    #[test]
+    fn lower_resample_rate_keeps_click_anchors_with_fewer_points() {
+        let events = vec![
+            move_event(0, 0.0, 0.0),
+            move_event(150, 40.0, 10.0),
+            click_event(333, 90.0, 45.0),
+            move_event(500, 120.0, 60.0),
+            move_event(1_000, 200.0, 20.0),
+        ];
+
+        let coarse = smooth_cursor_path_at_rate(&events, 0.8, 30.0);
+        let fine = smooth_cursor_path_at_rate(&events, 0.8, 240.0);
+        assert!(coarse.len() < fine.len());
+
+        for path in [&coarse, &fine] {
+            let click = path
+                .iter()
+                .find(|point| point.is_click)
+                .expect("missing click point");
+            assert_eq!(click.ts, 333);
+            assert!((click.x - 90.0).abs() < 0.0001);
+            assert!((click.y - 45.0).abs() < 0.0001);
+        }
+    }
 }
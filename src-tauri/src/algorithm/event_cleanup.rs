@@ -0,0 +1,325 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::events::{EventsFile, InputEvent};
+
+/// Optional rules applied by [`normalize_events`] on top of sorting, clamping and dedupe.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCleanupOptions {
+    /// Moves closer than this to the previous kept move are dropped; `None` keeps them all.
+    #[serde(default)]
+    pub min_move_interval_ms: Option<u64>,
+    #[serde(default)]
+    pub drop_keystrokes: bool,
+    #[serde(default)]
+    pub drop_moves: bool,
+    #[serde(default)]
+    pub drop_scroll: bool,
+}
+
+/// What [`normalize_events`] changed, per rule.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCleanupReport {
+    pub input_count: usize,
+    pub output_count: usize,
+    /// True when the events were not in timestamp order.
+    pub reordered: bool,
+    /// Events with NaN or infinite coordinates.
+    pub invalid_removed: usize,
+    /// Events whose coordinates were pulled back onto the screen.
+    pub clamped: usize,
+    pub duplicates_removed: usize,
+    /// Events of a type turned off in the options.
+    pub filtered: usize,
+    pub throttled: usize,
+}
+
+/// Sorts the events, clamps pointer coordinates to the screen, drops invalid events and
+/// same-timestamp duplicates, then applies the optional filter and move throttling.
+///
+/// Coordinates are clamped in the file's own coordinate space, so logical files stay
+/// logical. Running it twice with the same options changes nothing the second time.
+pub fn normalize_events(
+    file: &mut EventsFile,
+    options: &EventCleanupOptions,
+) -> EventCleanupReport {
+    let mut report = EventCleanupReport {
+        input_count: file.events.len(),
+        ..EventCleanupReport::default()
+    };
+    let scale = file.physical_scale();
+    let max_x = (file.screen_width as f64 / scale - 1.0).max(0.0);
+    let max_y = (file.screen_height as f64 / scale - 1.0).max(0.0);
+
+    let mut events = std::mem::take(&mut file.events);
+    events.retain(|event| {
+        let keep = match event {
+            InputEvent::KeyDown { .. } | InputEvent::KeyUp { .. } => !options.drop_keystrokes,
+            InputEvent::Move { .. } => !options.drop_moves,
+            InputEvent::Scroll { .. } => !options.drop_scroll,
+            InputEvent::Click { .. } | InputEvent::MouseUp { .. } => true,
+        };
+        if !keep {
+            report.filtered += 1;
+        }
+        keep
+    });
+    events.retain(|event| {
+        let valid = event
+            .position()
+            .map_or(true, |(x, y)| x.is_finite() && y.is_finite());
+        if !valid {
+            report.invalid_removed += 1;
+        }
+        valid
+    });
+
+    report.reordered = events.windows(2).any(|pair| pair[0].ts() > pair[1].ts());
+    // Stable, so same-timestamp events keep their recorded order (click before mouse up).
+    events.sort_by_key(InputEvent::ts);
+
+    for event in &mut events {
+        if let InputEvent::Move { x, y, .. }
+        | InputEvent::Click { x, y, .. }
+        | InputEvent::MouseUp { x, y, .. }
+        | InputEvent::Scroll { x, y, .. } = event
+        {
+            let (clamped_x, clamped_y) = (x.clamp(0.0, max_x), y.clamp(0.0, max_y));
+            if clamped_x != *x || clamped_y != *y {
+                report.clamped += 1;
+                *x = clamped_x;
+                *y = clamped_y;
+            }
+        }
+    }
+
+    let mut kept: Vec<InputEvent> = Vec::with_capacity(events.len());
+    let mut last_move_ts: Option<u64> = None;
+    for event in events {
+        let ts = event.ts();
+        let same_ts_start = kept.len()
+            - kept
+                .iter()
+                .rev()
+                .take_while(|other| other.ts() == ts)
+                .count();
+        if let InputEvent::Move { .. } = event {
+            // Two moves in one millisecond are hook noise: the later position wins.
+            if let Some(offset) = kept[same_ts_start..]
+                .iter()
+                .rposition(|other| matches!(other, InputEvent::Move { .. }))
+            {
+                kept[same_ts_start + offset] = event;
+                report.duplicates_removed += 1;
+                continue;
+            }
+            if let (Some(interval), Some(last)) = (options.min_move_interval_ms, last_move_ts) {
+                if ts.saturating_sub(last) < interval {
+                    report.throttled += 1;
+                    continue;
+                }
+            }
+            last_move_ts = Some(ts);
+        } else if kept[same_ts_start..]
+            .iter()
+            .any(|other| is_duplicate(other, &event))
+        {
+            report.duplicates_removed += 1;
+            continue;
+        }
+        kept.push(event);
+    }
+
+    report.output_count = kept.len();
+    file.events = kept;
+    report
+}
+
+/// Same kind, timestamp and payload; a click's UI context is ignored.
+fn is_duplicate(a: &InputEvent, b: &InputEvent) -> bool {
+    match (a, b) {
+        (
+            InputEvent::Click {
+                ts: ts_a,
+                x: x_a,
+                y: y_a,
+                button: button_a,
+                ..
+            },
+            InputEvent::Click {
+                ts: ts_b,
+                x: x_b,
+                y: y_b,
+                button: button_b,
+                ..
+            },
+        )
+        | (
+            InputEvent::MouseUp {
+                ts: ts_a,
+                x: x_a,
+                y: y_a,
+                button: button_a,
+            },
+            InputEvent::MouseUp {
+                ts: ts_b,
+                x: x_b,
+                y: y_b,
+                button: button_b,
+            },
+        ) => ts_a == ts_b && x_a == x_b && y_a == y_b && button_a == button_b,
+        (
+            InputEvent::Scroll {
+                ts: ts_a,
+                x: x_a,
+                y: y_a,
+                delta: delta_a,
+            },
+            InputEvent::Scroll {
+                ts: ts_b,
+                x: x_b,
+                y: y_b,
+                delta: delta_b,
+            },
+        ) => {
+            ts_a == ts_b
+                && x_a == x_b
+                && y_a == y_b
+                && delta_a.dx == delta_b.dx
+                && delta_a.dy == delta_b.dy
+        }
+        (
+            InputEvent::KeyDown {
+                ts: ts_a,
+                key_code: key_a,
+                ..
+            },
+            InputEvent::KeyDown {
+                ts: ts_b,
+                key_code: key_b,
+                ..
+            },
+        )
+        | (
+            InputEvent::KeyUp {
+                ts: ts_a,
+                key_code: key_a,
+            },
+            InputEvent::KeyUp {
+                ts: ts_b,
+                key_code: key_b,
+            },
+        ) => ts_a == ts_b && key_a == key_b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::events::{CoordinateSpace, MouseButton, ScrollDelta, SCHEMA_VERSION};
+
+    fn moved(ts: u64, x: f64, y: f64) -> InputEvent {
+        InputEvent::Move { ts, x, y }
+    }
+
+    fn click(ts: u64, x: f64, y: f64) -> InputEvent {
+        InputEvent::Click {
+            ts,
+            x,
+            y,
+            button: MouseButton::Left,
+            ui_context: None,
+        }
+    }
+
+    fn key(ts: u64, key_code: &str) -> InputEvent {
+        InputEvent::KeyDown {
+            ts,
+            key_code: key_code.to_string(),
+            caret: None,
+        }
+    }
+
+    fn messy_file() -> EventsFile {
+        EventsFile {
+            schema_version: SCHEMA_VERSION,
+            recording_id: "rec".to_string(),
+            start_time_ms: 0,
+            screen_width: 1920,
+            screen_height: 1080,
+            scale_factor: 1.0,
+            coordinate_space: Some(CoordinateSpace::Physical),
+            events: vec![
+                moved(40, 300.0, 300.0),
+                moved(0, 10.0, 10.0),
+                moved(10, 20.0, 20.0),
+                moved(10, 25.0, 22.0),
+                click(20, 2_500.0, -5.0),
+                click(20, 2_500.0, -5.0),
+                key(30, "KeyA"),
+                key(30, "KeyA"),
+                key(30, "KeyB"),
+                moved(35, f64::NAN, 5.0),
+                InputEvent::Scroll {
+                    ts: 50,
+                    x: 100.0,
+                    y: 100.0,
+                    delta: ScrollDelta { dx: 0.0, dy: -1.0 },
+                },
+                moved(45, 310.0, 305.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn messy_events_are_sorted_clamped_and_deduplicated() {
+        let mut file = messy_file();
+        let report = normalize_events(&mut file, &EventCleanupOptions::default());
+
+        assert_eq!(
+            report,
+            EventCleanupReport {
+                input_count: 12,
+                output_count: 8,
+                reordered: true,
+                invalid_removed: 1,
+                clamped: 2,
+                duplicates_removed: 3,
+                filtered: 0,
+                throttled: 0,
+            }
+        );
+        let timestamps: Vec<u64> = file.events.iter().map(InputEvent::ts).collect();
+        assert_eq!(timestamps, vec![0, 10, 20, 30, 30, 40, 45, 50]);
+        assert_eq!(file.events[1].position(), Some((25.0, 22.0)));
+        assert_eq!(file.events[2].position(), Some((1_919.0, 0.0)));
+
+        // A second pass finds nothing left to fix.
+        let again = normalize_events(&mut file, &EventCleanupOptions::default());
+        assert_eq!(again.output_count, 8);
+        assert!(!again.reordered);
+        assert_eq!(again.clamped + again.duplicates_removed, 0);
+    }
+
+    #[test]
+    fn options_filter_types_and_throttle_moves() {
+        let mut file = messy_file();
+        let report = normalize_events(
+            &mut file,
+            &EventCleanupOptions {
+                min_move_interval_ms: Some(20),
+                drop_keystrokes: true,
+                drop_scroll: true,
+                ..EventCleanupOptions::default()
+            },
+        );
+
+        assert_eq!(report.filtered, 4);
+        // Both 10 ms moves are too close to the one at 0 ms, the 45 ms one to the one at 40.
+        assert_eq!(report.throttled, 3);
+        let timestamps: Vec<u64> = file.events.iter().map(InputEvent::ts).collect();
+        assert_eq!(timestamps, vec![0, 20, 40]);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::models::events::InputEvent;
+
+/// Grid width used when the caller does not ask for one.
+pub const DEFAULT_HEATMAP_COLUMNS: u32 = 64;
+const HEATMAP_COLUMNS_RANGE: (u32, u32) = (8, 512);
+/// Standard deviation of a click's splat, relative to the longer side of the grid.
+const SPLAT_SIGMA_RATIO: f64 = 0.025;
+/// Splats are cut off beyond this many standard deviations.
+const SPLAT_RADIUS_SIGMAS: f64 = 3.0;
+/// Densities below this stay fully transparent in [`ClickHeatmap::to_rgba`].
+const MIN_VISIBLE_DENSITY: f64 = 0.02;
+const MAX_HEAT_ALPHA: f64 = 200.0;
+
+/// Click density over the screen, on a grid with the screen's aspect ratio.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClickHeatmap {
+    pub columns: u32,
+    pub rows: u32,
+    pub click_count: u64,
+    /// Row-major densities, scaled so the hottest cell is 1.0 (all zero without clicks).
+    pub cells: Vec<f64>,
+}
+
+impl ClickHeatmap {
+    /// Tightly packed RGBA pixels, one per cell: transparent where nobody clicked, then
+    /// blue through green and yellow to red at the hottest cell.
+    #[must_use]
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(self.cells.len() * 4);
+        for &density in &self.cells {
+            if density < MIN_VISIBLE_DENSITY {
+                rgba.extend_from_slice(&[0, 0, 0, 0]);
+                continue;
+            }
+            let (r, g, b) = heat_color(density);
+            let alpha = (density.sqrt() * MAX_HEAT_ALPHA).round() as u8;
+            rgba.extend_from_slice(&[r, g, b, alpha]);
+        }
+        rgba
+    }
+}
+
+/// Accumulates a Gaussian splat per click, with positions normalized by the screen size.
+pub fn build_click_heatmap(
+    events: &[InputEvent],
+    screen_width: u32,
+    screen_height: u32,
+    columns: u32,
+) -> ClickHeatmap {
+    let screen_width = screen_width.max(1) as f64;
+    let screen_height = screen_height.max(1) as f64;
+    let columns = columns.clamp(HEATMAP_COLUMNS_RANGE.0, HEATMAP_COLUMNS_RANGE.1);
+    let rows = ((columns as f64 * screen_height / screen_width).round() as u32).max(1);
+
+    let sigma = (SPLAT_SIGMA_RATIO * columns.max(rows) as f64).max(0.5);
+    let radius = (sigma * SPLAT_RADIUS_SIGMAS).ceil() as i64;
+    let mut cells = vec![0.0; columns as usize * rows as usize];
+    let mut click_count = 0;
+
+    for event in events {
+        let InputEvent::Click { x, y, .. } = event else {
+            continue;
+        };
+        click_count += 1;
+        // Cell centers sit at half-integers, so a click is compared against `index + 0.5`.
+        let cx = (x / screen_width).clamp(0.0, 1.0) * columns as f64;
+        let cy = (y / screen_height).clamp(0.0, 1.0) * rows as f64;
+        let (col, row) = (cx.floor() as i64, cy.floor() as i64);
+        for cell_row in (row - radius).max(0)..=(row + radius).min(rows as i64 - 1) {
+            for cell_col in (col - radius).max(0)..=(col + radius).min(columns as i64 - 1) {
+                let dx = cell_col as f64 + 0.5 - cx;
+                let dy = cell_row as f64 + 0.5 - cy;
+                cells[cell_row as usize * columns as usize + cell_col as usize] +=
+                    (-(dx * dx + dy * dy) / (2.0 * sigma * sigma)).exp();
+            }
+        }
+    }
+
+    let peak = cells.iter().copied().fold(0.0, f64::max);
+    if peak > 0.0 {
+        cells.iter_mut().for_each(|cell| *cell /= peak);
+    }
+
+    ClickHeatmap {
+        columns,
+        rows,
+        click_count,
+        cells,
+    }
+}
+
+fn heat_color(density: f64) -> (u8, u8, u8) {
+    const STOPS: [(f64, [f64; 3]); 4] = [
+        (0.0, [0.0, 64.0, 255.0]),
+        (0.4, [0.0, 220.0, 80.0]),
+        (0.7, [255.0, 230.0, 0.0]),
+        (1.0, [255.0, 32.0, 0.0]),
+    ];
+    let t = density.clamp(0.0, 1.0);
+    let upper = STOPS
+        .iter()
+        .position(|(stop, _)| *stop >= t)
+        .unwrap_or(STOPS.len() - 1)
+        .max(1);
+    let (start, from) = STOPS[upper - 1];
+    let (end, to) = STOPS[upper];
+    let k = ((t - start) / (end - start)).clamp(0.0, 1.0);
+    let channel = |i: usize| (from[i] + (to[i] - from[i]) * k).round() as u8;
+    (channel(0), channel(1), channel(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::events::MouseButton;
+
+    fn click(ts: u64, x: f64, y: f64) -> InputEvent {
+        InputEvent::Click {
+            ts,
+            x,
+            y,
+            button: MouseButton::Left,
+            ui_context: None,
+        }
+    }
+
+    #[test]
+    fn heatmap_peaks_where_clicks_concentrate() {
+        let events = vec![
+            click(0, 100.0, 100.0),
+            click(500, 104.0, 98.0),
+            click(900, 1_800.0, 1_000.0),
+            InputEvent::Move {
+                ts: 1_000,
+                x: 960.0,
+                y: 540.0,
+            },
+        ];
+        let heatmap = build_click_heatmap(&events, 1_920, 1_080, 64);
+        assert_eq!((heatmap.columns, heatmap.rows), (64, 36));
+        assert_eq!(heatmap.click_count, 3);
+        assert_eq!(heatmap.cells.len(), 64 * 36);
+
+        let cell = |x: f64, y: f64| {
+            let col = (x / 1_920.0 * 64.0) as usize;
+            let row = (y / 1_080.0 * 36.0) as usize;
+            heatmap.cells[row * 64 + col]
+        };
+        // The double click is the hottest spot, the lone click about half as hot.
+        assert!((cell(100.0, 100.0) - 1.0).abs() < 0.1);
+        let lone = cell(1_800.0, 1_000.0);
+        assert!(lone > 0.3 && lone < 0.7, "lone click density {lone}");
+        assert_eq!(cell(960.0, 540.0), 0.0);
+
+        let rgba = heatmap.to_rgba();
+        assert_eq!(rgba.len(), heatmap.cells.len() * 4);
+        let center = (18 * 64 + 32) * 4;
+        assert_eq!(&rgba[center..center + 4], &[0, 0, 0, 0]);
+
+        let empty = build_click_heatmap(&[], 1_920, 1_080, 64);
+        assert_eq!(empty.click_count, 0);
+        assert!(empty.cells.iter().all(|cell| *cell == 0.0));
+    }
+}
@@ -1,2 +1,6 @@
+pub mod activity;
 pub mod camera_engine;
+pub mod chapters;
 pub mod cursor_smoothing;
+pub mod event_cleanup;
+pub mod heatmap;
@@ -6,13 +6,80 @@ use std::sync::{mpsc, Arc};
 use std::thread::JoinHandle;
 use std::time::Duration;
 
+use serde::Serialize;
+
 pub struct LoopbackCaptureHandle {
     pub stop_flag: Arc<AtomicBool>,
     pub join_handle: JoinHandle<Result<(), String>>,
 }
 
+/// Active audio output (render endpoint) that can be captured as system audio.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemAudioOutput {
+    /// Stable WASAPI endpoint id.
+    pub id: String,
+    pub name: String,
+    pub is_default: bool,
+}
+
+#[cfg(target_os = "windows")]
+struct ComApartment;
+
+#[cfg(target_os = "windows")]
+impl ComApartment {
+    fn initialize() -> Result<Self, String> {
+        use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
+        unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }
+            .map_err(|e| format!("WASAPI COM init failed: {e}"))?;
+        Ok(Self)
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for ComApartment {
+    fn drop(&mut self) {
+        unsafe { windows::Win32::System::Com::CoUninitialize() };
+    }
+}
+
+/// Lists active render endpoints so the UI can pick a non-default output.
+#[cfg(target_os = "windows")]
+pub fn list_system_audio_outputs() -> Result<Vec<SystemAudioOutput>, String> {
+    use windows::Win32::Media::Audio::{IMMDeviceEnumerator, MMDeviceEnumerator};
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_ALL};
+
+    let _com = ComApartment::initialize()?;
+    let enumerator: IMMDeviceEnumerator =
+        unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }
+            .map_err(|e| format!("WASAPI failed to create device enumerator: {e}"))?;
+    let default_id = open_default_render_endpoint(&enumerator)
+        .and_then(|device| endpoint_id(&device))
+        .ok();
+
+    Ok(active_render_endpoints(&enumerator)?
+        .into_iter()
+        .map(|endpoint| SystemAudioOutput {
+            is_default: default_id.as_deref() == Some(endpoint.id.as_str()),
+            id: endpoint.id,
+            name: endpoint.name,
+        })
+        .collect())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn list_system_audio_outputs() -> Result<Vec<SystemAudioOutput>, String> {
+    Err("WASAPI audio outputs are only available on Windows".to_string())
+}
+
+/// Starts WASAPI loopback capture into a WAV file.
+///
+/// `endpoint` selects a render endpoint by id (see [`list_system_audio_outputs`]) or by
+/// friendly name (case-insensitive); the default console endpoint is used when it is `None`.
 pub fn start_system_loopback_capture(
     output_path: PathBuf,
+    endpoint: Option<String>,
 ) -> Result<LoopbackCaptureHandle, String> {
     let stop_flag = Arc::new(AtomicBool::new(false));
     let stop_for_thread = Arc::clone(&stop_flag);
@@ -20,7 +87,9 @@ pub fn start_system_loopback_capture(
 
     let join_handle = std::thread::Builder::new()
         .name("wasapi-loopback-capture".to_string())
-        .spawn(move || run_loopback_capture_thread(output_path, stop_for_thread, ready_tx))
+        .spawn(move || {
+            run_loopback_capture_thread(output_path, endpoint, stop_for_thread, ready_tx)
+        })
         .map_err(|e| format!("Failed to spawn WASAPI loopback capture thread: {e}"))?;
 
     let mut join_handle = Some(join_handle);
@@ -67,34 +136,18 @@ pub fn start_system_loopback_capture(
 #[cfg(target_os = "windows")]
 fn run_loopback_capture_thread(
     output_path: PathBuf,
+    endpoint: Option<String>,
     stop_flag: Arc<AtomicBool>,
     ready_tx: mpsc::SyncSender<Result<(), String>>,
 ) -> Result<(), String> {
     use std::ptr;
 
     use windows::Win32::Media::Audio::{
-        eConsole, eRender, IAudioCaptureClient, IAudioClient, IMMDeviceEnumerator,
-        MMDeviceEnumerator, AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED,
-        AUDCLNT_STREAMFLAGS_LOOPBACK, WAVEFORMATEX,
+        IAudioCaptureClient, IAudioClient, IMMDeviceEnumerator, MMDeviceEnumerator,
+        AUDCLNT_BUFFERFLAGS_SILENT, AUDCLNT_SHAREMODE_SHARED, AUDCLNT_STREAMFLAGS_LOOPBACK,
+        WAVEFORMATEX,
     };
-    use windows::Win32::System::Com::{
-        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, CLSCTX_ALL,
-        COINIT_MULTITHREADED,
-    };
-
-    struct ComApartment;
-    impl ComApartment {
-        fn initialize() -> Result<Self, String> {
-            unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }
-                .map_err(|e| format!("WASAPI loopback COM init failed: {e}"))?;
-            Ok(Self)
-        }
-    }
-    impl Drop for ComApartment {
-        fn drop(&mut self) {
-            unsafe { CoUninitialize() };
-        }
-    }
+    use windows::Win32::System::Com::{CoCreateInstance, CoTaskMemFree, CLSCTX_ALL};
 
     let run = || -> Result<(), String> {
         let _com = ComApartment::initialize()?;
@@ -103,8 +156,10 @@ fn run_loopback_capture_thread(
             unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }
                 .map_err(|e| format!("WASAPI loopback failed to create device enumerator: {e}"))?;
 
-        let render_device = unsafe { enumerator.GetDefaultAudioEndpoint(eRender, eConsole) }
-            .map_err(|e| format!("WASAPI loopback failed to open default render endpoint: {e}"))?;
+        let render_device = match endpoint.as_deref() {
+            Some(endpoint) => find_render_endpoint(&enumerator, endpoint)?,
+            None => open_default_render_endpoint(&enumerator)?,
+        };
 
         let audio_client: IAudioClient = unsafe { render_device.Activate(CLSCTX_ALL, None) }
             .map_err(|e| format!("WASAPI loopback failed to activate audio client: {e}"))?;
@@ -234,9 +289,120 @@ fn run_loopback_capture_thread(
     }
 }
 
+#[cfg(target_os = "windows")]
+struct RenderEndpoint {
+    id: String,
+    name: String,
+    device: windows::Win32::Media::Audio::IMMDevice,
+}
+
+#[cfg(target_os = "windows")]
+fn open_default_render_endpoint(
+    enumerator: &windows::Win32::Media::Audio::IMMDeviceEnumerator,
+) -> Result<windows::Win32::Media::Audio::IMMDevice, String> {
+    use windows::Win32::Media::Audio::{eConsole, eRender};
+
+    unsafe { enumerator.GetDefaultAudioEndpoint(eRender, eConsole) }
+        .map_err(|e| format!("WASAPI loopback failed to open default render endpoint: {e}"))
+}
+
+/// Finds an active render endpoint by exact id or by friendly name (case-insensitive).
+#[cfg(target_os = "windows")]
+fn find_render_endpoint(
+    enumerator: &windows::Win32::Media::Audio::IMMDeviceEnumerator,
+    endpoint: &str,
+) -> Result<windows::Win32::Media::Audio::IMMDevice, String> {
+    let endpoint = endpoint.trim();
+    let endpoints = active_render_endpoints(enumerator)?;
+    let available = endpoints
+        .iter()
+        .map(|candidate| candidate.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let position = endpoints
+        .iter()
+        .position(|candidate| candidate.id == endpoint)
+        .or_else(|| {
+            endpoints
+                .iter()
+                .position(|candidate| candidate.name.eq_ignore_ascii_case(endpoint))
+        });
+    match position {
+        Some(index) => Ok(endpoints
+            .into_iter()
+            .nth(index)
+            .expect("endpoint index")
+            .device),
+        None => Err(format!(
+            "WASAPI render endpoint '{endpoint}' not found (available: {available})"
+        )),
+    }
+}
+
+/// Lists active render endpoints with their ids and friendly names.
+#[cfg(target_os = "windows")]
+fn active_render_endpoints(
+    enumerator: &windows::Win32::Media::Audio::IMMDeviceEnumerator,
+) -> Result<Vec<RenderEndpoint>, String> {
+    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::Media::Audio::{eRender, DEVICE_STATE_ACTIVE};
+    use windows::Win32::System::Com::StructuredStorage::PropVariantClear;
+    use windows::Win32::System::Com::STGM_READ;
+
+    let collection = unsafe { enumerator.EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE) }
+        .map_err(|e| format!("WASAPI failed to enumerate render endpoints: {e}"))?;
+    let count = unsafe { collection.GetCount() }
+        .map_err(|e| format!("WASAPI failed to count render endpoints: {e}"))?;
+
+    let mut endpoints = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let device = unsafe { collection.Item(index) }
+            .map_err(|e| format!("WASAPI failed to open render endpoint #{index}: {e}"))?;
+        let name = unsafe {
+            let store = device
+                .OpenPropertyStore(STGM_READ)
+                .map_err(|e| format!("WASAPI failed to open endpoint properties: {e}"))?;
+            let mut value = store
+                .GetValue(&PKEY_Device_FriendlyName)
+                .map_err(|e| format!("WASAPI failed to read endpoint name: {e}"))?;
+            let name = value
+                .Anonymous
+                .Anonymous
+                .Anonymous
+                .pwszVal
+                .to_string()
+                .unwrap_or_default();
+            let _ = PropVariantClear(&mut value);
+            name
+        };
+        endpoints.push(RenderEndpoint {
+            id: endpoint_id(&device)?,
+            name,
+            device,
+        });
+    }
+
+    Ok(endpoints)
+}
+
+#[cfg(target_os = "windows")]
+fn endpoint_id(device: &windows::Win32::Media::Audio::IMMDevice) -> Result<String, String> {
+    use windows::Win32::System::Com::CoTaskMemFree;
+
+    unsafe {
+        let raw = device
+            .GetId()
+            .map_err(|e| format!("WASAPI failed to read endpoint id: {e}"))?;
+        let id = raw.to_string().unwrap_or_default();
+        CoTaskMemFree(Some(raw.0 as *const std::ffi::c_void));
+        Ok(id)
+    }
+}
+
 #[cfg(not(target_os = "windows"))]
 fn run_loopback_capture_thread(
     _output_path: PathBuf,
+    _endpoint: Option<String>,
     _stop_flag: Arc<AtomicBool>,
     ready_tx: mpsc::SyncSender<Result<(), String>>,
 ) -> Result<(), String> {
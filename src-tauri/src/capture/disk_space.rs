@@ -0,0 +1,100 @@
+//! Free disk space checks for recording and export destinations.
+
+use std::path::Path;
+
+/// Default minimum free space required to start writing media files.
+pub const DEFAULT_MIN_FREE_DISK_SPACE_MB: u64 = 2048;
+/// A running recording is stopped only below this fraction of the start threshold, so a
+/// recording started just above the threshold is not cut off right away.
+const CRITICAL_FREE_DISK_SPACE_DIVISOR: u64 = 4;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Returns the number of bytes available to the current user on the volume holding `path`.
+///
+/// `path` does not need to exist yet; the nearest existing ancestor is queried instead.
+pub fn available_disk_space_bytes(path: &Path) -> Result<u64, String> {
+    let existing = path
+        .ancestors()
+        .find(|candidate| candidate.exists())
+        .ok_or_else(|| format!("No existing directory for {}", path.display()))?;
+    query_available_bytes(existing)
+}
+
+/// Free space below which a running recording is stopped; defaults to a quarter of the
+/// start threshold and never exceeds it.
+pub fn critical_free_disk_space_mb(min_free_mb: u64, critical_free_mb: Option<u64>) -> u64 {
+    critical_free_mb
+        .unwrap_or(min_free_mb / CRITICAL_FREE_DISK_SPACE_DIVISOR)
+        .min(min_free_mb)
+}
+
+/// Fails with a user-facing message when the volume holding `path` has less than `min_free_mb`.
+///
+/// If free space cannot be determined, the check is skipped with a warning.
+pub fn ensure_free_disk_space(path: &Path, min_free_mb: u64) -> Result<(), String> {
+    if min_free_mb == 0 {
+        return Ok(());
+    }
+
+    match available_disk_space_bytes(path) {
+        Ok(available) => {
+            let required = min_free_mb.saturating_mul(BYTES_PER_MB);
+            if available < required {
+                return Err(format!(
+                    "Not enough free disk space at {}: {} MB available, {} MB required",
+                    path.display(),
+                    available / BYTES_PER_MB,
+                    min_free_mb
+                ));
+            }
+            Ok(())
+        }
+        Err(err) => {
+            log::warn!("disk_space: free space check skipped: {err}");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn query_available_bytes(path: &Path) -> Result<u64, String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect::<Vec<u16>>();
+    let mut available = 0u64;
+    unsafe {
+        GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut available), None, None)
+            .map_err(|e| format!("GetDiskFreeSpaceExW failed for {}: {e}", path.display()))?;
+    }
+    Ok(available)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn query_available_bytes(path: &Path) -> Result<u64, String> {
+    Err(format!(
+        "Free space query is not supported on this platform ({})",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critical_threshold_is_below_the_start_threshold() {
+        assert_eq!(
+            critical_free_disk_space_mb(DEFAULT_MIN_FREE_DISK_SPACE_MB, None),
+            512
+        );
+        assert_eq!(critical_free_disk_space_mb(2048, Some(100)), 100);
+        assert_eq!(critical_free_disk_space_mb(2048, Some(4096)), 2048);
+        assert_eq!(critical_free_disk_space_mb(0, None), 0);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod audio_loopback;
+pub mod disk_space;
+pub mod multi_monitor;
 pub mod preview;
 pub mod recorder;
 pub mod state;
@@ -0,0 +1,316 @@
+//! Side-by-side compositing of per-monitor recordings into a single canvas.
+//!
+//! Each monitor is recorded into its own file; on stop the files are stacked
+//! horizontally (left to right in capture order) and shorter monitors are padded
+//! at the bottom so every tile shares the tallest monitor's height.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::capture::recorder::{apply_no_window_flags, find_ffmpeg_exe};
+use crate::models::events::{BoundingRect, InputEvent};
+
+/// Placement of one recorded monitor inside the combined canvas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorTile {
+    pub monitor_index: u32,
+    /// Per-monitor recording file inside the project directory.
+    pub raw_path: PathBuf,
+    /// Monitor origin in virtual-desktop coordinates (the space input events use).
+    pub origin_x: i32,
+    pub origin_y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Horizontal offset of the tile inside the canvas.
+    pub canvas_x: u32,
+}
+
+/// Computes the composite canvas size: summed widths by the tallest height, rounded up to even
+/// values so the H.264 output does not need extra cropping.
+pub fn canvas_size(tiles: &[MonitorTile]) -> (u32, u32) {
+    let width = tiles.iter().map(|tile| tile.width).sum::<u32>();
+    let height = tiles.iter().map(|tile| tile.height).max().unwrap_or(0);
+    (round_up_even(width), round_up_even(height))
+}
+
+/// Lays monitors out left to right in the given order.
+pub fn assign_canvas_offsets(tiles: &mut [MonitorTile]) {
+    let mut canvas_x = 0u32;
+    for tile in tiles {
+        tile.canvas_x = canvas_x;
+        canvas_x = canvas_x.saturating_add(tile.width);
+    }
+}
+
+/// Builds the FFmpeg filter graph that pads every input to the canvas height and stacks them,
+/// scaling the stack to `output_size` when it differs from the canvas.
+pub fn build_hstack_filter(tiles: &[MonitorTile], output_size: (u32, u32)) -> String {
+    let (canvas_width, canvas_height) = canvas_size(tiles);
+    let mut parts = Vec::with_capacity(tiles.len() + 1);
+    let mut labels = String::new();
+
+    for (index, tile) in tiles.iter().enumerate() {
+        parts.push(format!(
+            "[{index}:v]pad={}:{canvas_height}:0:0:color=black,setsar=1,format=yuv420p[m{index}]",
+            tile.width
+        ));
+        labels.push_str(&format!("[m{index}]"));
+    }
+
+    let scale = if output_size == (canvas_width, canvas_height) {
+        String::new()
+    } else {
+        format!(",scale={}:{}:flags=area", output_size.0, output_size.1)
+    };
+    parts.push(format!(
+        "{labels}hstack=inputs={},pad={canvas_width}:{canvas_height}:0:0:color=black{scale}[vout]",
+        tiles.len()
+    ));
+    parts.join(";")
+}
+
+/// Composites per-monitor recordings into an `output_size` video at `output_path`.
+///
+/// The intermediate files are removed once the composite has been written successfully.
+/// `keyframe_interval_frames` sets the GOP size of the composite; the encoder default when unset.
+pub fn composite_monitor_recordings(
+    tiles: &[MonitorTile],
+    output_path: &Path,
+    output_size: (u32, u32),
+    target_fps: u32,
+    keyframe_interval_frames: Option<u32>,
+) -> Result<(), String> {
+    let missing = tiles
+        .iter()
+        .filter(|tile| !tile.raw_path.exists())
+        .map(|tile| tile.raw_path.display().to_string())
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Err(format!(
+            "Monitor recordings are missing: {}",
+            missing.join(", ")
+        ));
+    }
+
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-loglevel")
+        .arg("error");
+    for tile in tiles {
+        command.arg("-i").arg(&tile.raw_path);
+    }
+    if let Some(frames) = keyframe_interval_frames {
+        command
+            .arg("-g")
+            .arg(frames.to_string())
+            .arg("-keyint_min")
+            .arg(frames.to_string());
+    }
+
+    let output = command
+        .arg("-filter_complex")
+        .arg(build_hstack_filter(tiles, output_size))
+        .arg("-map")
+        .arg("[vout]")
+        .arg("-r")
+        .arg(target_fps.max(1).to_string())
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-preset")
+        .arg("veryfast")
+        .arg("-crf")
+        .arg("18")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-movflags")
+        .arg("+faststart")
+        .arg(output_path)
+        .output()
+        .map_err(|e| {
+            format!(
+                "Failed to run ffmpeg ({}) for monitor composite: {e}",
+                ffmpeg.display()
+            )
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "FFmpeg monitor composite failed: {}",
+            stderr.trim()
+        ));
+    }
+
+    for tile in tiles {
+        if let Err(err) = std::fs::remove_file(&tile.raw_path) {
+            log::warn!(
+                "multi_monitor: failed to remove {}: {err}",
+                tile.raw_path.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves input event coordinates from virtual-desktop space into canvas space.
+pub fn translate_events_to_canvas(events: &mut [InputEvent], tiles: &[MonitorTile]) {
+    if tiles.is_empty() {
+        return;
+    }
+
+    for event in events.iter_mut() {
+        match event {
+            InputEvent::Move { x, y, .. }
+            | InputEvent::MouseUp { x, y, .. }
+            | InputEvent::Scroll { x, y, .. } => {
+                let tile = tile_for_point(tiles, *x, *y);
+                (*x, *y) = translate_point(tile, *x, *y);
+            }
+            InputEvent::Click {
+                x, y, ui_context, ..
+            } => {
+                let tile = tile_for_point(tiles, *x, *y);
+                (*x, *y) = translate_point(tile, *x, *y);
+                if let Some(rect) = ui_context
+                    .as_mut()
+                    .and_then(|context| context.bounding_rect.as_mut())
+                {
+                    translate_rect(tile, rect);
+                }
+            }
+            InputEvent::KeyDown {
+                caret: Some(rect), ..
+            } => {
+                let center_x = rect.x as f64 + rect.width as f64 / 2.0;
+                let center_y = rect.y as f64 + rect.height as f64 / 2.0;
+                translate_rect(tile_for_point(tiles, center_x, center_y), rect);
+            }
+            InputEvent::KeyDown { .. } | InputEvent::KeyUp { .. } => {}
+        }
+    }
+}
+
+fn translate_rect(tile: &MonitorTile, rect: &mut BoundingRect) {
+    rect.x = rect.x - tile.origin_x + tile.canvas_x as i32;
+    rect.y -= tile.origin_y;
+}
+
+fn translate_point(tile: &MonitorTile, x: f64, y: f64) -> (f64, f64) {
+    let local_x = (x - tile.origin_x as f64).clamp(0.0, tile.width.saturating_sub(1) as f64);
+    let local_y = (y - tile.origin_y as f64).clamp(0.0, tile.height.saturating_sub(1) as f64);
+    (local_x + tile.canvas_x as f64, local_y)
+}
+
+/// Picks the monitor containing the point, falling back to the horizontally nearest one
+/// for points on monitors that are not being recorded.
+fn tile_for_point(tiles: &[MonitorTile], x: f64, y: f64) -> &MonitorTile {
+    let contains = |tile: &&MonitorTile| {
+        x >= tile.origin_x as f64
+            && x < tile.origin_x as f64 + tile.width as f64
+            && y >= tile.origin_y as f64
+            && y < tile.origin_y as f64 + tile.height as f64
+    };
+    if let Some(tile) = tiles.iter().find(contains) {
+        return tile;
+    }
+
+    tiles
+        .iter()
+        .min_by(|a, b| {
+            horizontal_distance(a, x)
+                .partial_cmp(&horizontal_distance(b, x))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .unwrap_or(&tiles[0])
+}
+
+fn horizontal_distance(tile: &MonitorTile, x: f64) -> f64 {
+    let left = tile.origin_x as f64;
+    let right = left + tile.width as f64;
+    if x < left {
+        left - x
+    } else if x > right {
+        x - right
+    } else {
+        0.0
+    }
+}
+
+fn round_up_even(value: u32) -> u32 {
+    value.saturating_add(value % 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiles() -> Vec<MonitorTile> {
+        let mut tiles = vec![
+            MonitorTile {
+                monitor_index: 0,
+                raw_path: PathBuf::from("raw-monitor-0.mp4"),
+                origin_x: 0,
+                origin_y: 0,
+                width: 1920,
+                height: 1080,
+                canvas_x: 0,
+            },
+            MonitorTile {
+                monitor_index: 1,
+                raw_path: PathBuf::from("raw-monitor-1.mp4"),
+                origin_x: -1280,
+                origin_y: 200,
+                width: 1280,
+                height: 1025,
+                canvas_x: 0,
+            },
+        ];
+        assign_canvas_offsets(&mut tiles);
+        tiles
+    }
+
+    #[test]
+    fn pads_shorter_monitor_to_even_canvas_height() {
+        let tiles = tiles();
+        assert_eq!(tiles[1].canvas_x, 1920);
+        assert_eq!(canvas_size(&tiles), (3200, 1080));
+
+        let filter = build_hstack_filter(&tiles, (3200, 1080));
+        assert!(filter.contains("[1:v]pad=1280:1080:0:0"));
+        assert!(filter.contains("[m0][m1]hstack=inputs=2"));
+        assert!(filter.ends_with("color=black[vout]"));
+
+        let scaled = build_hstack_filter(&tiles, (1600, 540));
+        assert!(scaled.ends_with("color=black,scale=1600:540:flags=area[vout]"));
+    }
+
+    #[test]
+    fn translates_events_from_desktop_to_canvas_space() {
+        let mut events = vec![
+            InputEvent::Move {
+                ts: 0,
+                x: 100.0,
+                y: 50.0,
+            },
+            InputEvent::Move {
+                ts: 10,
+                x: -1000.0,
+                y: 300.0,
+            },
+        ];
+        translate_events_to_canvas(&mut events, &tiles());
+
+        match (&events[0], &events[1]) {
+            (InputEvent::Move { x: x0, y: y0, .. }, InputEvent::Move { x: x1, y: y1, .. }) => {
+                assert_eq!((*x0, *y0), (100.0, 50.0));
+                assert_eq!((*x1, *y1), (1920.0 + 280.0, 100.0));
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -2,13 +2,23 @@
 //!
 //! Used by the Record screen to render a live preview without browser
 //! `getDisplayMedia` permission prompts.
+//!
+//! Frames reach the UI either by polling `get_native_preview_frame` or, with
+//! [`PreviewDelivery::Event`], pushed as [`NATIVE_PREVIEW_FRAME_EVENT`] events the moment
+//! they are encoded, which saves one IPC round trip per frame and never re-sends a frame.
+//!
+//! While a recording is active the preview does not run its own capture session:
+//! the recorder publishes decimated frames into the same slot through [`PreviewFeed`],
+//! so a single WGC session serves both and the app window stays excluded from both.
 
 use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use base64::{engine::general_purpose, Engine as _};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
 use tokio::sync::Mutex as AsyncMutex;
 use windows_capture::{
     capture::{CaptureControl, Context, GraphicsCaptureApiHandler},
@@ -22,48 +32,218 @@ use windows_capture::{
     },
 };
 
-const PREVIEW_TARGET_FPS: u32 = 12;
-const PREVIEW_MIN_INTERVAL: Duration = Duration::from_millis(1000 / PREVIEW_TARGET_FPS as u64);
-const PREVIEW_MAX_WIDTH: u32 = 1280;
+pub const DEFAULT_PREVIEW_TARGET_FPS: u32 = 15;
+pub const DEFAULT_PREVIEW_MAX_DIMENSION: u32 = 1280;
+const PREVIEW_FPS_RANGE: (u32, u32) = (1, 30);
+const PREVIEW_DIMENSION_RANGE: (u32, u32) = (160, 3840);
+
+/// Window event carrying a [`NativePreviewFrame`] when frames are pushed.
+pub const NATIVE_PREVIEW_FRAME_EVENT: &str = "native-preview-frame";
+
+/// How preview frames reach the UI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PreviewDelivery {
+    /// The UI polls `get_native_preview_frame`.
+    #[default]
+    Poll,
+    /// Each encoded frame is emitted once as [`NATIVE_PREVIEW_FRAME_EVENT`] to the window
+    /// that started the preview; polling keeps working.
+    Event,
+}
+
+/// Preview resolution/rate limits requested by the UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreviewSettings {
+    /// Upper bound for the longer side of preview frames (aspect ratio is kept).
+    pub max_dimension: u32,
+    pub target_fps: u32,
+    pub delivery: PreviewDelivery,
+}
+
+impl PreviewSettings {
+    #[must_use]
+    pub fn new(
+        max_dimension: Option<u32>,
+        target_fps: Option<u32>,
+        delivery: Option<PreviewDelivery>,
+    ) -> Self {
+        Self {
+            max_dimension: max_dimension
+                .unwrap_or(DEFAULT_PREVIEW_MAX_DIMENSION)
+                .clamp(PREVIEW_DIMENSION_RANGE.0, PREVIEW_DIMENSION_RANGE.1),
+            target_fps: target_fps
+                .unwrap_or(DEFAULT_PREVIEW_TARGET_FPS)
+                .clamp(PREVIEW_FPS_RANGE.0, PREVIEW_FPS_RANGE.1),
+            delivery: delivery.unwrap_or_default(),
+        }
+    }
+
+    fn min_interval(self) -> Duration {
+        Duration::from_millis(1000 / self.target_fps.max(1) as u64)
+    }
+}
+
+impl Default for PreviewSettings {
+    fn default() -> Self {
+        Self::new(None, None, None)
+    }
+}
 
+/// One preview frame, as returned by `get_native_preview_frame` and carried by
+/// [`NATIVE_PREVIEW_FRAME_EVENT`].
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct NativePreviewFrame {
+    /// `data:image/jpeg;base64,...` of the downscaled frame; usable directly as an `<img>` source.
     pub data_url: String,
+    /// Effective (downscaled) preview size.
     pub width: u32,
     pub height: u32,
+    /// Size of the captured monitor frame before downscaling.
+    pub source_width: u32,
+    pub source_height: u32,
+    /// Increases with every published frame, also across a switch to the recording feed.
     pub sequence: u64,
 }
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 struct SharedPreviewFrame {
     latest: Option<NativePreviewFrame>,
 }
 
-struct PreviewCaptureFlags {
+/// Handle to a preview frame slot that an external capture (the recorder) can publish into.
+#[derive(Debug, Clone)]
+pub struct PreviewFeed {
     shared: Arc<Mutex<SharedPreviewFrame>>,
-    max_width: u32,
+    max_dimension: u32,
     min_interval: Duration,
+    /// Cleared once the publishing side goes away.
+    active: Arc<AtomicBool>,
+    /// Receives every frame as an event with [`PreviewDelivery::Event`].
+    event_target: Option<tauri::WebviewWindow>,
 }
 
-struct PreviewCaptureHandler {
-    shared: Arc<Mutex<SharedPreviewFrame>>,
+impl PreviewFeed {
+    fn new(
+        shared: Arc<Mutex<SharedPreviewFrame>>,
+        settings: PreviewSettings,
+        window: Option<&tauri::WebviewWindow>,
+    ) -> Self {
+        Self {
+            shared,
+            max_dimension: settings.max_dimension,
+            min_interval: settings.min_interval(),
+            active: Arc::new(AtomicBool::new(true)),
+            event_target: window
+                .filter(|_| settings.delivery == PreviewDelivery::Event)
+                .cloned(),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+}
+
+/// Rate-limits, downscales and JPEG-encodes BGRA frames into a [`PreviewFeed`].
+pub struct PreviewFeedWriter {
+    feed: PreviewFeed,
     image_encoder: ImageEncoder,
-    max_width: u32,
-    min_interval: Duration,
     last_encoded_at: Option<Instant>,
     sequence: u64,
 }
 
+impl PreviewFeedWriter {
+    #[must_use]
+    pub fn new(feed: PreviewFeed) -> Self {
+        // Continue the sequence so pollers notice the first frame after a source switch.
+        let sequence = feed
+            .shared
+            .lock()
+            .ok()
+            .and_then(|shared| shared.latest.as_ref().map(|frame| frame.sequence))
+            .unwrap_or(0);
+        Self {
+            feed,
+            image_encoder: ImageEncoder::new(ImageFormat::Jpeg, ColorFormat::Bgra8),
+            last_encoded_at: None,
+            sequence,
+        }
+    }
+
+    /// Returns `false` when the frame would be dropped by the preview rate limit.
+    #[must_use]
+    pub fn wants_frame(&self) -> bool {
+        !self
+            .last_encoded_at
+            .is_some_and(|last| last.elapsed() < self.feed.min_interval)
+    }
+
+    /// Publishes a tightly packed top-to-bottom BGRA frame.
+    pub fn publish(&mut self, bgra: &[u8], width: u32, height: u32) -> Result<(), String> {
+        let (scaled, scaled_width, scaled_height) =
+            downscale_bgra_for_preview(bgra, width, height, self.feed.max_dimension);
+        let jpeg = self
+            .image_encoder
+            .encode(scaled.as_ref(), scaled_width, scaled_height)
+            .map_err(|err| format!("Failed to encode preview frame: {err}"))?;
+
+        let data_url = format!(
+            "data:image/jpeg;base64,{}",
+            general_purpose::STANDARD.encode(jpeg)
+        );
+
+        self.sequence = self.sequence.saturating_add(1);
+        let preview_frame = NativePreviewFrame {
+            data_url,
+            width: scaled_width,
+            height: scaled_height,
+            source_width: width,
+            source_height: height,
+            sequence: self.sequence,
+        };
+
+        if let Some(window) = &self.feed.event_target {
+            if let Err(err) =
+                window.emit_to(window.label(), NATIVE_PREVIEW_FRAME_EVENT, &preview_frame)
+            {
+                log::warn!("Failed to emit {NATIVE_PREVIEW_FRAME_EVENT}: {err}");
+            }
+        }
+        if let Ok(mut shared) = self.feed.shared.lock() {
+            shared.latest = Some(preview_frame);
+        }
+
+        self.last_encoded_at = Some(Instant::now());
+        Ok(())
+    }
+}
+
+impl Drop for PreviewFeedWriter {
+    fn drop(&mut self) {
+        self.feed.active.store(false, Ordering::Relaxed);
+    }
+}
+
+struct PreviewCaptureFlags {
+    feed: PreviewFeed,
+}
+
+struct PreviewCaptureHandler {
+    writer: PreviewFeedWriter,
+}
+
 type PreviewCaptureControl = CaptureControl<PreviewCaptureHandler, String>;
 
 fn downscale_bgra_for_preview<'a>(
     source: &'a [u8],
     width: u32,
     height: u32,
-    max_width: u32,
+    max_dimension: u32,
 ) -> (Cow<'a, [u8]>, u32, u32) {
-    if width == 0 || height == 0 || max_width == 0 || width <= max_width {
+    let longest_side = width.max(height);
+    if width == 0 || height == 0 || max_dimension == 0 || longest_side <= max_dimension {
         return (Cow::Borrowed(source), width, height);
     }
 
@@ -72,9 +252,10 @@ fn downscale_bgra_for_preview<'a>(
         return (Cow::Borrowed(source), width, height);
     }
 
-    let out_width = max_width;
-    let out_height =
-        ((height as u64 * max_width as u64) / width as u64).clamp(1, u32::MAX as u64) as u32;
+    let out_width = ((width as u64 * max_dimension as u64) / longest_side as u64)
+        .clamp(1, u32::MAX as u64) as u32;
+    let out_height = ((height as u64 * max_dimension as u64) / longest_side as u64)
+        .clamp(1, u32::MAX as u64) as u32;
 
     let mut downscaled = vec![0u8; out_width as usize * out_height as usize * 4];
     let src_width = width as usize;
@@ -100,12 +281,7 @@ impl GraphicsCaptureApiHandler for PreviewCaptureHandler {
 
     fn new(ctx: Context<Self::Flags>) -> Result<Self, Self::Error> {
         Ok(Self {
-            shared: ctx.flags.shared,
-            image_encoder: ImageEncoder::new(ImageFormat::Jpeg, ColorFormat::Bgra8),
-            max_width: ctx.flags.max_width,
-            min_interval: ctx.flags.min_interval,
-            last_encoded_at: None,
-            sequence: 0,
+            writer: PreviewFeedWriter::new(ctx.flags.feed),
         })
     }
 
@@ -114,10 +290,7 @@ impl GraphicsCaptureApiHandler for PreviewCaptureHandler {
         frame: &mut Frame<'_>,
         control: InternalCaptureControl,
     ) -> Result<(), Self::Error> {
-        if self
-            .last_encoded_at
-            .is_some_and(|last| last.elapsed() < self.min_interval)
-        {
+        if !self.writer.wants_frame() {
             return Ok(());
         }
 
@@ -131,31 +304,7 @@ impl GraphicsCaptureApiHandler for PreviewCaptureHandler {
             .as_nopadding_buffer()
             .map_err(|err| format!("Failed to read preview frame bytes: {err}"))?;
 
-        let (scaled, scaled_width, scaled_height) =
-            downscale_bgra_for_preview(bytes, width, height, self.max_width);
-        let jpeg = self
-            .image_encoder
-            .encode(scaled.as_ref(), scaled_width, scaled_height)
-            .map_err(|err| format!("Failed to encode preview frame: {err}"))?;
-
-        let data_url = format!(
-            "data:image/jpeg;base64,{}",
-            general_purpose::STANDARD.encode(jpeg)
-        );
-
-        self.sequence = self.sequence.saturating_add(1);
-        let preview_frame = NativePreviewFrame {
-            data_url,
-            width: scaled_width,
-            height: scaled_height,
-            sequence: self.sequence,
-        };
-
-        if let Ok(mut shared) = self.shared.lock() {
-            shared.latest = Some(preview_frame);
-        }
-
-        self.last_encoded_at = Some(Instant::now());
+        self.writer.publish(bytes, width, height)?;
 
         if width == 0 || height == 0 {
             control.stop();
@@ -167,26 +316,53 @@ impl GraphicsCaptureApiHandler for PreviewCaptureHandler {
 
 struct PreviewSession {
     monitor_index: u32,
+    settings: PreviewSettings,
     control: PreviewCaptureControl,
     shared: Arc<Mutex<SharedPreviewFrame>>,
 }
 
 pub struct PreviewManager {
     session: Option<PreviewSession>,
+    /// Frames published by an active recording; replaces the capture session meanwhile.
+    recording_feed: Option<PreviewFeed>,
+    /// Settings of the last requested preview; reused for the recording feed.
+    settings: PreviewSettings,
+    /// Window that requested the preview, for [`PreviewDelivery::Event`].
+    window: Option<tauri::WebviewWindow>,
 }
 
 impl PreviewManager {
     #[must_use]
     pub fn new() -> Self {
-        Self { session: None }
+        Self {
+            session: None,
+            recording_feed: None,
+            settings: PreviewSettings::default(),
+            window: None,
+        }
     }
 
-    pub fn start_session(&mut self, monitor_index: u32) -> Result<(), String> {
+    pub fn start_session(
+        &mut self,
+        monitor_index: u32,
+        settings: PreviewSettings,
+        window: &tauri::WebviewWindow,
+    ) -> Result<(), String> {
+        self.settings = settings;
+        self.window = Some(window.clone());
         if self
-            .session
+            .recording_feed
             .as_ref()
-            .is_some_and(|session| session.monitor_index == monitor_index)
+            .is_some_and(PreviewFeed::is_active)
         {
+            // The recorder already publishes preview frames.
+            return Ok(());
+        }
+        self.recording_feed = None;
+
+        if self.session.as_ref().is_some_and(|session| {
+            session.monitor_index == monitor_index && session.settings == settings
+        }) {
             return Ok(());
         }
 
@@ -201,9 +377,7 @@ impl PreviewManager {
 
         let shared = Arc::new(Mutex::new(SharedPreviewFrame::default()));
         let flags = PreviewCaptureFlags {
-            shared: shared.clone(),
-            max_width: PREVIEW_MAX_WIDTH,
-            min_interval: PREVIEW_MIN_INTERVAL,
+            feed: PreviewFeed::new(shared.clone(), settings, Some(window)),
         };
 
         let settings = Settings::new(
@@ -211,7 +385,7 @@ impl PreviewManager {
             CursorCaptureSettings::WithCursor,
             DrawBorderSettings::WithoutBorder,
             SecondaryWindowSettings::Default,
-            MinimumUpdateIntervalSettings::Custom(PREVIEW_MIN_INTERVAL),
+            MinimumUpdateIntervalSettings::Custom(settings.min_interval()),
             DirtyRegionSettings::Default,
             ColorFormat::Bgra8,
             flags,
@@ -222,6 +396,7 @@ impl PreviewManager {
 
         self.session = Some(PreviewSession {
             monitor_index,
+            settings,
             control,
             shared,
         });
@@ -239,12 +414,37 @@ impl PreviewManager {
         }
     }
 
+    /// Stops the standalone preview session and returns a feed the recorder publishes into.
+    ///
+    /// The last preview frame is carried over so the UI keeps an image until the
+    /// recorder delivers its first frame.
+    pub fn begin_recording_feed(&mut self) -> PreviewFeed {
+        let carried_over = self.latest_frame();
+        self.stop_session();
+
+        let shared = Arc::new(Mutex::new(SharedPreviewFrame {
+            latest: carried_over,
+        }));
+        let feed = PreviewFeed::new(shared, self.settings, self.window.as_ref());
+        self.recording_feed = Some(feed.clone());
+        feed
+    }
+
+    /// Detaches the recording feed (e.g. when recording failed to start).
+    pub fn end_recording_feed(&mut self) {
+        if let Some(feed) = self.recording_feed.take() {
+            feed.active.store(false, Ordering::Relaxed);
+        }
+    }
+
     #[must_use]
     pub fn latest_frame(&self) -> Option<NativePreviewFrame> {
-        self.session
-            .as_ref()
-            .and_then(|session| session.shared.lock().ok())
-            .and_then(|shared| shared.latest.clone())
+        let shared = match (&self.session, &self.recording_feed) {
+            (Some(session), _) => &session.shared,
+            (None, Some(feed)) => &feed.shared,
+            (None, None) => return None,
+        };
+        shared.lock().ok().and_then(|shared| shared.latest.clone())
     }
 }
 
@@ -9,8 +9,11 @@ use std::sync::{Arc, Condvar, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
+use crate::capture::preview::{PreviewFeed, PreviewFeedWriter};
+use crate::models::app_settings::{self, MonitorIdentity};
+
 #[cfg(target_os = "windows")]
-use windows::Win32::Graphics::Gdi::HMONITOR;
+use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, HMONITOR, MONITORINFO};
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
 use windows_capture::{
@@ -30,15 +33,208 @@ use windows_capture::{
 
 /// Target FPS for capture/output.
 pub const DEFAULT_TARGET_FPS: u32 = 60;
+/// Smallest fraction of the native resolution a recording can be captured at.
+pub const MIN_CAPTURE_SCALE: f64 = 0.25;
 const HNS_PER_SECOND: i64 = 10_000_000;
 
 #[derive(Clone, Debug)]
 pub struct CaptureEncoderSettings {
     pub output_path: PathBuf,
+    /// Encoded frame size.
     pub width: u32,
     pub height: u32,
+    /// Downscale captured frames to `width`×`height` before encoding (reduced capture scale).
+    pub downscale: bool,
     pub target_fps: u32,
     pub quality: RecordingQuality,
+    pub color_format: CaptureColorFormat,
+    /// Write only a few frames per second while the screen is static (see [`FramePacer`]).
+    pub low_motion_economy: bool,
+}
+
+/// Pixel format requested from Windows Graphics Capture.
+///
+/// The Media Foundation encoder always takes 8-bit BGRA, so higher precision formats are
+/// converted per frame; `Rgba16F` keeps HDR highlights that 8-bit capture clips and
+/// tone-maps them into SDR instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaptureColorFormat {
+    #[default]
+    Bgra8,
+    /// Half-float linear scRGB (1.0 = SDR white).
+    Rgba16F,
+}
+
+impl CaptureColorFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            CaptureColorFormat::Bgra8 => 4,
+            CaptureColorFormat::Rgba16F => 8,
+        }
+    }
+
+    fn wgc_format(self) -> ColorFormat {
+        match self {
+            CaptureColorFormat::Bgra8 => ColorFormat::Bgra8,
+            CaptureColorFormat::Rgba16F => ColorFormat::Rgba16F,
+        }
+    }
+
+    /// Tone-mapped HDR content keeps more gradients, which needs extra bitrate.
+    fn bitrate_scale(self) -> f64 {
+        match self {
+            CaptureColorFormat::Bgra8 => 1.0,
+            CaptureColorFormat::Rgba16F => 1.2,
+        }
+    }
+}
+
+/// Converts captured rows into the encoder's 8-bit BGRA layout.
+enum FrameConverter {
+    Bgra8,
+    /// sRGB-encoded, tone-mapped output byte for every half-float bit pattern.
+    Rgba16F {
+        lut: Box<[u8]>,
+    },
+}
+
+impl FrameConverter {
+    fn new(format: CaptureColorFormat) -> Self {
+        match format {
+            CaptureColorFormat::Bgra8 => FrameConverter::Bgra8,
+            CaptureColorFormat::Rgba16F => FrameConverter::Rgba16F {
+                lut: (0..=u16::MAX)
+                    .map(|bits| linear_to_srgb8(tone_map_scrgb(half_to_f32(bits))))
+                    .collect(),
+            },
+        }
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        match self {
+            FrameConverter::Bgra8 => CaptureColorFormat::Bgra8.bytes_per_pixel(),
+            FrameConverter::Rgba16F { .. } => CaptureColorFormat::Rgba16F.bytes_per_pixel(),
+        }
+    }
+
+    fn convert_row(&self, src: &[u8], dst: &mut [u8]) {
+        match self {
+            FrameConverter::Bgra8 => dst.copy_from_slice(src),
+            FrameConverter::Rgba16F { lut } => {
+                for (pixel, out) in src.chunks_exact(8).zip(dst.chunks_exact_mut(4)) {
+                    let channel =
+                        |index: usize| u16::from_le_bytes([pixel[index], pixel[index + 1]]);
+                    out[0] = lut[channel(4) as usize];
+                    out[1] = lut[channel(2) as usize];
+                    out[2] = lut[channel(0) as usize];
+                    out[3] = 255;
+                }
+            }
+        }
+    }
+
+    /// Converts a top-to-bottom frame to tightly packed BGRA8, optionally flipping the row
+    /// order.
+    ///
+    /// The source row stride is derived from the buffer length, so padded rows are handled;
+    /// a buffer that does not split into whole rows of whole pixels is passed through.
+    fn convert_frame(&self, buffer: &[u8], width: usize, height: usize, flip: bool) -> Vec<u8> {
+        let bytes_per_pixel = self.bytes_per_pixel();
+        let src_row_bytes = width.saturating_mul(bytes_per_pixel);
+        let dst_row_bytes = width.saturating_mul(4);
+        let stride = match source_row_stride(buffer.len(), width, height, bytes_per_pixel) {
+            Ok(stride) => stride,
+            Err(reason) => {
+                if !STRIDE_MISMATCH_LOGGED.swap(true, Ordering::Relaxed) {
+                    log::warn!("capture: frame passed through unconverted: {reason}");
+                }
+                return match self {
+                    FrameConverter::Bgra8 => buffer.to_vec(),
+                    FrameConverter::Rgba16F { .. } => vec![0u8; dst_row_bytes * height],
+                };
+            }
+        };
+
+        let mut converted = vec![0u8; dst_row_bytes * height];
+        for row in 0..height {
+            let src_row = if flip { height - 1 - row } else { row };
+            let src_start = src_row * stride;
+            let dst_start = row * dst_row_bytes;
+            self.convert_row(
+                &buffer[src_start..src_start + src_row_bytes],
+                &mut converted[dst_start..dst_start + dst_row_bytes],
+            );
+        }
+        converted
+    }
+}
+
+/// Set once a malformed frame has been reported, so a persistent mismatch logs only once.
+static STRIDE_MISMATCH_LOGGED: AtomicBool = AtomicBool::new(false);
+
+/// Row stride of a `height`-row frame buffer of `len` bytes.
+fn source_row_stride(
+    len: usize,
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+) -> Result<usize, String> {
+    if width == 0 || height == 0 {
+        return Err(format!("empty {width}x{height} frame"));
+    }
+    if len % height != 0 {
+        return Err(format!(
+            "{len}-byte buffer does not split into {height} equal rows"
+        ));
+    }
+    let stride = len / height;
+    if stride % bytes_per_pixel != 0 {
+        return Err(format!(
+            "row stride {stride} is not a multiple of {bytes_per_pixel} bytes per pixel"
+        ));
+    }
+    if stride < width * bytes_per_pixel {
+        return Err(format!(
+            "row stride {stride} is shorter than {width} pixels of {bytes_per_pixel} bytes"
+        ));
+    }
+    Ok(stride)
+}
+
+fn half_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+/// Compresses scRGB values above the SDR range with a soft knee so highlights keep detail
+/// instead of clipping to white.
+fn tone_map_scrgb(value: f32) -> f32 {
+    const KNEE: f32 = 0.8;
+    if !value.is_finite() {
+        return if value > 0.0 { 1.0 } else { 0.0 };
+    }
+    if value <= KNEE {
+        return value.max(0.0);
+    }
+    let headroom = 1.0 - KNEE;
+    KNEE + headroom * (1.0 - (-(value - KNEE) / headroom).exp())
+}
+
+fn linear_to_srgb8(value: f32) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let encoded = if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round() as u8
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -63,6 +259,190 @@ pub struct CaptureFlags {
     pub stop_flag: Arc<AtomicBool>,
     pub pause_flag: Arc<AtomicBool>,
     pub encoder: CaptureEncoderSettings,
+    /// Optional live preview slot fed with decimated frames while recording.
+    pub preview_feed: Option<PreviewFeed>,
+    /// Optional sink for the muxer's active runs, used to align audio with the video.
+    pub timeline: Option<MuxerTimeline>,
+}
+
+/// Continuous run of frames written by the CFR muxer between pauses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MuxerSegment {
+    /// Unix timestamp in ms at which the first frame of the run was written.
+    pub start_ms: u64,
+    /// Frame slots covered by the run (including slots skipped in low-motion economy mode);
+    /// the run lasts exactly `frames / fps` in the video.
+    pub frames: u64,
+}
+
+/// Stretch of the video in which no new frame was captured, so the muxer only repeated
+/// the last one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StaticRun {
+    /// Offset of the first repeated frame from the start of the video, in ms.
+    pub start_ms: u64,
+    pub duration_ms: u64,
+}
+
+/// Static runs at least this long are reported to the UI.
+pub const LONG_STATIC_RUN: Duration = Duration::from_secs(5);
+/// Static time after which low-motion economy starts skipping frames.
+const ECONOMY_AFTER: Duration = Duration::from_secs(1);
+/// Frames per second still written while the screen is static in economy mode.
+const ECONOMY_FPS: u64 = 4;
+
+/// Runs written by the CFR muxer, in order; the video is these runs back to back.
+#[derive(Clone, Debug, Default)]
+pub struct MuxerTimeline {
+    segments: Arc<Mutex<Vec<MuxerSegment>>>,
+    static_runs: Arc<Mutex<Vec<StaticRun>>>,
+}
+
+impl MuxerTimeline {
+    pub fn segments(&self) -> Vec<MuxerSegment> {
+        self.segments
+            .lock()
+            .map(|segments| segments.clone())
+            .unwrap_or_default()
+    }
+
+    /// Static runs of at least [`LONG_STATIC_RUN`] finished so far.
+    pub fn static_runs(&self) -> Vec<StaticRun> {
+        self.static_runs
+            .lock()
+            .map(|runs| runs.clone())
+            .unwrap_or_default()
+    }
+
+    fn push(&self, segment: MuxerSegment) {
+        if let Ok(mut segments) = self.segments.lock() {
+            segments.push(segment);
+        }
+    }
+
+    fn push_static_run(&self, run: StaticRun) {
+        if let Ok(mut runs) = self.static_runs.lock() {
+            runs.push(run);
+        }
+    }
+}
+
+fn ticks_for(duration: Duration, fps: u64) -> u64 {
+    (duration.as_millis() as u64 * fps).div_ceil(1000).max(1)
+}
+
+fn ticks_to_ms(ticks: u64, fps: u64) -> u64 {
+    ticks.saturating_mul(1000) / fps.max(1)
+}
+
+/// Frame thinning applied to static periods in low-motion economy mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct EconomyPacing {
+    /// Repeated ticks written at full rate before thinning starts.
+    after_ticks: u64,
+    /// Once thinning, one tick in this many is written.
+    every_ticks: u64,
+}
+
+impl EconomyPacing {
+    fn for_fps(fps: u64) -> Self {
+        Self {
+            after_ticks: ticks_for(ECONOMY_AFTER, fps),
+            every_ticks: (fps / ECONOMY_FPS).max(1),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct TickDecision {
+    /// Whether the tick's frame goes to the encoder; skipped ticks still advance the PTS grid.
+    send: bool,
+    /// Long static run closed by this tick's new frame.
+    ended_run: Option<StaticRun>,
+}
+
+/// Decides which CFR ticks reach the encoder and tracks runs without new frames.
+///
+/// Every tick keeps its slot on the CFR grid (`pts = tick * interval`), so skipping ticks
+/// in economy mode turns static periods into a capped variable frame rate without moving
+/// any later frame. A skipped tick at the end of a run is written on [`FramePacer::finish`]
+/// so the video keeps its full length.
+#[derive(Debug)]
+struct FramePacer {
+    fps: u64,
+    economy: Option<EconomyPacing>,
+    long_run_ticks: u64,
+    /// Consecutive ticks without a new captured frame.
+    static_ticks: u64,
+    static_start_tick: u64,
+    /// Last skipped tick not yet covered by a written frame.
+    pending_tick: Option<u64>,
+}
+
+impl FramePacer {
+    fn new(target_fps: u32, low_motion_economy: bool) -> Self {
+        let fps = target_fps.max(1) as u64;
+        Self {
+            fps,
+            economy: low_motion_economy.then(|| EconomyPacing::for_fps(fps)),
+            long_run_ticks: ticks_for(LONG_STATIC_RUN, fps),
+            static_ticks: 0,
+            static_start_tick: 0,
+            pending_tick: None,
+        }
+    }
+
+    /// `tick` is the slot on the CFR grid; `fresh` is true when it shows a new frame.
+    fn on_tick(&mut self, tick: u64, fresh: bool) -> TickDecision {
+        let ended_run = if fresh {
+            self.end_static_run()
+        } else {
+            if self.static_ticks == 0 {
+                self.static_start_tick = tick;
+            }
+            self.static_ticks = self.static_ticks.saturating_add(1);
+            None
+        };
+
+        let send = match self.economy {
+            Some(pacing) if !fresh && self.static_ticks > pacing.after_ticks => {
+                (self.static_ticks - pacing.after_ticks) % pacing.every_ticks == 0
+            }
+            _ => true,
+        };
+        self.pending_tick = if send { None } else { Some(tick) };
+        TickDecision { send, ended_run }
+    }
+
+    /// Closes the current run on pause or stop. Returns the skipped tick that still has to
+    /// be written and the static run that ended with it.
+    fn finish(&mut self) -> (Option<u64>, Option<StaticRun>) {
+        (self.pending_tick.take(), self.end_static_run())
+    }
+
+    fn end_static_run(&mut self) -> Option<StaticRun> {
+        let ticks = std::mem::take(&mut self.static_ticks);
+        (ticks >= self.long_run_ticks).then(|| StaticRun {
+            start_ms: ticks_to_ms(self.static_start_tick, self.fps),
+            duration_ms: ticks_to_ms(ticks, self.fps),
+        })
+    }
+}
+
+/// First error of a capture thread that ended before the stop flag was set.
+#[derive(Clone, Debug, Default)]
+pub struct CaptureFailure(Arc<Mutex<Option<String>>>);
+
+impl CaptureFailure {
+    pub fn error(&self) -> Option<String> {
+        self.0.lock().ok().and_then(|error| error.clone())
+    }
+
+    fn record(&self, error: String) {
+        if let Ok(mut slot) = self.0.lock() {
+            slot.get_or_insert(error);
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -81,12 +461,19 @@ struct FrameSlot {
 struct MuxerStats {
     encoded_frames: u64,
     duplicated_frames: u64,
+    /// Ticks not written in low-motion economy mode.
+    skipped_frames: u64,
+    long_static_runs: u64,
 }
 
 pub struct ScreenRecorder {
     stop_flag: Arc<AtomicBool>,
     frame_slot: Arc<(Mutex<FrameSlot>, Condvar)>,
     muxer_thread: Option<JoinHandle<Result<MuxerStats, Box<dyn std::error::Error + Send + Sync>>>>,
+    preview_writer: Option<PreviewFeedWriter>,
+    converter: FrameConverter,
+    /// Encoded frame size when captured frames are downscaled before encoding.
+    downscale_to: Option<(usize, usize)>,
     received_frames: u64,
 }
 
@@ -113,6 +500,8 @@ fn run_cfr_muxer(
     pause_flag: Arc<AtomicBool>,
     frame_slot: Arc<(Mutex<FrameSlot>, Condvar)>,
     target_fps: u32,
+    low_motion_economy: bool,
+    timeline: Option<MuxerTimeline>,
 ) -> Result<MuxerStats, Box<dyn std::error::Error + Send + Sync>> {
     let safe_fps = target_fps.max(1) as u64;
     let frame_interval_hns = (HNS_PER_SECOND / safe_fps as i64).max(1);
@@ -125,6 +514,41 @@ fn run_cfr_muxer(
     let mut frame_index = 0i64;
     let mut next_tick: Option<Instant> = None;
     let mut was_paused = false;
+    let mut current_segment: Option<MuxerSegment> = None;
+    let mut pacer = FramePacer::new(target_fps, low_motion_economy);
+    // The first tick of every run shows a frame the encoder has not seen in this run.
+    let mut fresh = true;
+    let close_segment = |segment: Option<MuxerSegment>| {
+        if let (Some(timeline), Some(segment)) = (timeline.as_ref(), segment) {
+            timeline.push(segment);
+        }
+    };
+    let record_static_run = |run: Option<StaticRun>, stats: &mut MuxerStats| {
+        if let Some(run) = run {
+            stats.long_static_runs = stats.long_static_runs.saturating_add(1);
+            if let Some(timeline) = timeline.as_ref() {
+                timeline.push_static_run(run);
+            }
+        }
+    };
+    // Writes the skipped tail of a thinned static period before the run is closed.
+    let finish_run = |pacer: &mut FramePacer,
+                      encoder: &mut VideoEncoder,
+                      active_frame: Option<&LatestFrame>,
+                      stats: &mut MuxerStats|
+     -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let (pending_tick, ended_run) = pacer.finish();
+        record_static_run(ended_run, stats);
+        if let (Some(tick), Some(snapshot)) = (pending_tick, active_frame) {
+            let pts_hns = (tick as i64).saturating_mul(frame_interval_hns);
+            encoder
+                .send_frame_buffer(snapshot.pixels.as_ref(), pts_hns)
+                .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)?;
+            stats.encoded_frames = stats.encoded_frames.saturating_add(1);
+            stats.skipped_frames = stats.skipped_frames.saturating_sub(1);
+        }
+        Ok(())
+    };
 
     loop {
         if stop_flag.load(Ordering::Relaxed) {
@@ -132,6 +556,10 @@ fn run_cfr_muxer(
         }
 
         if pause_flag.load(Ordering::Relaxed) {
+            if current_segment.is_some() {
+                finish_run(&mut pacer, &mut encoder, active_frame.as_ref(), &mut stats)?;
+            }
+            close_segment(current_segment.take());
             was_paused = true;
             thread::sleep(Duration::from_millis(12));
             continue;
@@ -140,6 +568,7 @@ fn run_cfr_muxer(
         if was_paused {
             next_tick = Some(Instant::now());
             was_paused = false;
+            fresh = true;
         }
 
         if active_frame.is_none() {
@@ -175,6 +604,7 @@ fn run_cfr_muxer(
                 if snapshot.sequence != last_sequence {
                     last_sequence = snapshot.sequence;
                     active_frame = Some(snapshot);
+                    fresh = true;
                 } else if stats.encoded_frames > 0 {
                     stats.duplicated_frames = stats.duplicated_frames.saturating_add(1);
                 }
@@ -184,12 +614,23 @@ fn run_cfr_muxer(
         }
 
         if let Some(snapshot) = active_frame.as_ref() {
-            let pts_hns = frame_index.saturating_mul(frame_interval_hns);
-            encoder
-                .send_frame_buffer(snapshot.pixels.as_ref(), pts_hns)
-                .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)?;
+            let decision = pacer.on_tick(frame_index as u64, std::mem::take(&mut fresh));
+            record_static_run(decision.ended_run, &mut stats);
+            if decision.send {
+                let pts_hns = frame_index.saturating_mul(frame_interval_hns);
+                encoder
+                    .send_frame_buffer(snapshot.pixels.as_ref(), pts_hns)
+                    .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)?;
+                stats.encoded_frames = stats.encoded_frames.saturating_add(1);
+            } else {
+                stats.skipped_frames = stats.skipped_frames.saturating_add(1);
+            }
             frame_index = frame_index.saturating_add(1);
-            stats.encoded_frames = stats.encoded_frames.saturating_add(1);
+            let segment = current_segment.get_or_insert_with(|| MuxerSegment {
+                start_ms: chrono::Utc::now().timestamp_millis() as u64,
+                frames: 0,
+            });
+            segment.frames = segment.frames.saturating_add(1);
         }
 
         let mut candidate = deadline + frame_interval;
@@ -199,6 +640,10 @@ fn run_cfr_muxer(
         }
         next_tick = Some(candidate);
     }
+    if current_segment.is_some() {
+        finish_run(&mut pacer, &mut encoder, active_frame.as_ref(), &mut stats)?;
+    }
+    close_segment(current_segment.take());
 
     encoder
         .finish()
@@ -206,25 +651,80 @@ fn run_cfr_muxer(
     Ok(stats)
 }
 
-fn normalize_frame_for_encoder(buffer: &[u8], width: usize, height: usize) -> Vec<u8> {
-    let pixel_count = width.saturating_mul(height);
-    let expected_len = pixel_count.saturating_mul(4);
-    if pixel_count == 0 || buffer.len() < expected_len {
-        return buffer.to_vec();
+fn normalize_frame_for_encoder(
+    buffer: &[u8],
+    width: usize,
+    height: usize,
+    converter: &FrameConverter,
+) -> Vec<u8> {
+    // `send_frame_buffer` expects bottom-to-top BGRA8 rows; the capture buffer is
+    // top-to-bottom with `converter.bytes_per_pixel()` bytes per pixel.
+    converter.convert_frame(buffer, width, height, true)
+}
+
+/// Frame size for capturing a `width`×`height` monitor at `scale` of its resolution.
+///
+/// `scale` is clamped to [`MIN_CAPTURE_SCALE`]–1.0; reduced sizes are rounded to even
+/// values for H.264, while the native size is returned unchanged.
+pub fn scaled_capture_size(width: u32, height: u32, scale: f64) -> (u32, u32) {
+    let scale = if scale.is_finite() {
+        scale.clamp(MIN_CAPTURE_SCALE, 1.0)
+    } else {
+        1.0
+    };
+    if scale >= 1.0 {
+        return (width, height);
     }
+    let scaled = |side: u32| {
+        let value = ((side as f64 * scale).round() as u32).max(2);
+        value - value % 2
+    };
+    (scaled(width), scaled(height))
+}
 
-    // `send_frame_buffer` expects bottom-to-top row order.
-    // Convert from top-to-bottom buffer by flipping rows only.
-    let row_bytes = width.saturating_mul(4);
-    let mut normalized = vec![0u8; expected_len];
-    for row in 0..height {
-        let src_row = height - 1 - row;
-        let src_start = src_row.saturating_mul(row_bytes);
-        let dst_start = row.saturating_mul(row_bytes);
-        normalized[dst_start..dst_start + row_bytes]
-            .copy_from_slice(&buffer[src_start..src_start + row_bytes]);
+/// Shrinks a tightly packed BGRA8 frame by averaging the source pixels each output pixel
+/// covers.
+///
+/// A buffer that is not `width`×`height` pixels yields a black frame of the output size,
+/// so the encoder always receives frames of the size it was configured with.
+fn downscale_bgra(
+    buffer: &[u8],
+    width: usize,
+    height: usize,
+    out_width: usize,
+    out_height: usize,
+) -> Vec<u8> {
+    let mut scaled = vec![0u8; out_width * out_height * 4];
+    if width == 0 || height == 0 || buffer.len() != width * height * 4 {
+        return scaled;
     }
-    normalized
+    // Source span `[start, end)` covered by output index `index` along one axis.
+    let span = |index: usize, out: usize, src: usize| {
+        let start = (index * src / out).min(src - 1);
+        let end = ((index + 1) * src / out).clamp(start + 1, src);
+        (start, end)
+    };
+    let columns: Vec<(usize, usize)> = (0..out_width).map(|x| span(x, out_width, width)).collect();
+    for out_y in 0..out_height {
+        let (y0, y1) = span(out_y, out_height, height);
+        for (out_x, &(x0, x1)) in columns.iter().enumerate() {
+            let mut sums = [0u32; 4];
+            for y in y0..y1 {
+                let row = &buffer[(y * width + x0) * 4..(y * width + x1) * 4];
+                for pixel in row.chunks_exact(4) {
+                    for (sum, value) in sums.iter_mut().zip(pixel) {
+                        *sum += *value as u32;
+                    }
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)) as u32;
+            let out = (out_y * out_width + out_x) * 4;
+            for (channel, sum) in sums.iter().enumerate() {
+                scaled[out + channel] = ((sum + count / 2) / count) as u8;
+            }
+        }
+    }
+    scaled
 }
 
 impl GraphicsCaptureApiHandler for ScreenRecorder {
@@ -233,12 +733,15 @@ impl GraphicsCaptureApiHandler for ScreenRecorder {
 
     fn new(ctx: Context<Self::Flags>) -> Result<Self, Self::Error> {
         let flags = ctx.flags;
+        // Created first so the preview feed is released if encoder setup fails below.
+        let preview_writer = flags.preview_feed.map(PreviewFeedWriter::new);
         let target_fps = flags.encoder.target_fps.max(1);
         let bitrate = estimate_h264_bitrate(
             flags.encoder.width,
             flags.encoder.height,
             target_fps,
             flags.encoder.quality,
+            flags.encoder.color_format,
         );
 
         let video_settings = VideoSettingsBuilder::new(flags.encoder.width, flags.encoder.height)
@@ -263,6 +766,8 @@ impl GraphicsCaptureApiHandler for ScreenRecorder {
         let muxer_stop_flag = flags.stop_flag.clone();
         let muxer_pause_flag = flags.pause_flag.clone();
         let muxer_slot = frame_slot.clone();
+        let muxer_timeline = flags.timeline.clone();
+        let low_motion_economy = flags.encoder.low_motion_economy;
         let muxer_thread = thread::Builder::new()
             .name("nsc-cfr-muxer".to_string())
             .spawn(move || {
@@ -272,6 +777,8 @@ impl GraphicsCaptureApiHandler for ScreenRecorder {
                     muxer_pause_flag,
                     muxer_slot,
                     target_fps,
+                    low_motion_economy,
+                    muxer_timeline,
                 )
             })
             .map_err(|err| format!("Failed to spawn CFR muxer thread: {err}"))?;
@@ -280,6 +787,12 @@ impl GraphicsCaptureApiHandler for ScreenRecorder {
             stop_flag: flags.stop_flag,
             frame_slot,
             muxer_thread: Some(muxer_thread),
+            preview_writer,
+            converter: FrameConverter::new(flags.encoder.color_format),
+            downscale_to: flags
+                .encoder
+                .downscale
+                .then_some((flags.encoder.width as usize, flags.encoder.height as usize)),
             received_frames: 0,
         })
     }
@@ -302,7 +815,24 @@ impl GraphicsCaptureApiHandler for ScreenRecorder {
         let bytes = frame_buffer
             .as_nopadding_buffer()
             .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)?;
-        let normalized = normalize_frame_for_encoder(bytes, width, height);
+        if let Some(writer) = self.preview_writer.as_mut().filter(|w| w.wants_frame()) {
+            let published = match self.converter {
+                FrameConverter::Bgra8 => writer.publish(bytes, width as u32, height as u32),
+                FrameConverter::Rgba16F { .. } => {
+                    let bgra = self.converter.convert_frame(bytes, width, height, false);
+                    writer.publish(&bgra, width as u32, height as u32)
+                }
+            };
+            if let Err(err) = published {
+                log::warn!("capture: failed to publish preview frame: {err}");
+            }
+        }
+        let mut normalized = normalize_frame_for_encoder(bytes, width, height, &self.converter);
+        if let Some((out_width, out_height)) = self.downscale_to {
+            if (out_width, out_height) != (width, height) {
+                normalized = downscale_bgra(&normalized, width, height, out_width, out_height);
+            }
+        }
         let pixels: Arc<[u8]> = Arc::from(normalized);
 
         let (lock, cvar) = &*self.frame_slot;
@@ -325,20 +855,29 @@ impl GraphicsCaptureApiHandler for ScreenRecorder {
     fn on_closed(&mut self) -> Result<(), Self::Error> {
         let stats = self.finish_encoder()?;
         log::info!(
-            "capture closed: received_frames={} encoded_frames={} duplicated_frames={}",
+            "capture closed: received_frames={} encoded_frames={} duplicated_frames={} skipped_frames={} long_static_runs={}",
             self.received_frames,
             stats.encoded_frames,
-            stats.duplicated_frames
+            stats.duplicated_frames,
+            stats.skipped_frames,
+            stats.long_static_runs
         );
         Ok(())
     }
 }
 
-fn estimate_h264_bitrate(width: u32, height: u32, fps: u32, quality: RecordingQuality) -> u32 {
+fn estimate_h264_bitrate(
+    width: u32,
+    height: u32,
+    fps: u32,
+    quality: RecordingQuality,
+    color_format: CaptureColorFormat,
+) -> u32 {
     // Bitrate heuristic tuned for screen content:
     // 1080p30 ~= 7 Mbps, 1440p60 ~= 20 Mbps, 2160p60 ~= 45 Mbps (clamped).
     let pixels_per_second = width as f64 * height as f64 * fps.max(1) as f64;
-    let raw = (pixels_per_second * 0.11 * quality.bitrate_scale()).round() as u64;
+    let scale = quality.bitrate_scale() * color_format.bitrate_scale();
+    let raw = (pixels_per_second * 0.11 * scale).round() as u64;
     raw.clamp(3_000_000, 60_000_000) as u32
 }
 
@@ -362,6 +901,116 @@ pub fn get_monitor_size(monitor_index: u32) -> Result<(u32, u32), String> {
     Ok((width, height))
 }
 
+/// Returns the number of monitors currently attached.
+pub fn monitor_count() -> Result<usize, String> {
+    Monitor::enumerate()
+        .map(|monitors| monitors.len())
+        .map_err(|e| format!("Failed to enumerate monitors: {e}"))
+}
+
+/// Identities of the attached monitors, in `Monitor::enumerate()` order.
+pub fn monitor_identities() -> Result<Vec<MonitorIdentity>, String> {
+    let monitors =
+        Monitor::enumerate().map_err(|e| format!("Failed to enumerate monitors: {e}"))?;
+
+    monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| {
+            let describe = |e: windows_capture::monitor::Error| {
+                format!("Failed to query monitor {index}: {e}")
+            };
+            Ok(MonitorIdentity {
+                name: monitor.name().unwrap_or_default(),
+                device_name: monitor.device_name().map_err(describe)?,
+                width: monitor.width().map_err(describe)?,
+                height: monitor.height().map_err(describe)?,
+            })
+        })
+        .collect()
+}
+
+/// Finds the attached monitor that matches a saved identity and returns its index.
+///
+/// The device name alone is not stable across docking changes, so matches are tried from
+/// the most to the least specific and a weaker match is only accepted when it is unique.
+pub fn match_monitor_identity(
+    wanted: &MonitorIdentity,
+    attached: &[MonitorIdentity],
+) -> Result<u32, String> {
+    let same_name = |monitor: &MonitorIdentity| {
+        !wanted.name.is_empty() && monitor.name.eq_ignore_ascii_case(&wanted.name)
+    };
+    let same_size = |monitor: &MonitorIdentity| {
+        monitor.width == wanted.width && monitor.height == wanted.height
+    };
+    let same_device = |monitor: &MonitorIdentity| monitor.device_name == wanted.device_name;
+
+    let rules: [&dyn Fn(&MonitorIdentity) -> bool; 4] = [
+        &|monitor: &MonitorIdentity| {
+            same_name(monitor) && same_size(monitor) && same_device(monitor)
+        },
+        &|monitor: &MonitorIdentity| same_name(monitor) && same_size(monitor),
+        &|monitor: &MonitorIdentity| same_name(monitor),
+        &|monitor: &MonitorIdentity| {
+            wanted.name.is_empty() && same_device(monitor) && same_size(monitor)
+        },
+    ];
+    for rule in rules {
+        let mut matches = attached
+            .iter()
+            .enumerate()
+            .filter(|(_, monitor)| rule(monitor));
+        if let (Some((index, _)), None) = (matches.next(), matches.next()) {
+            return Ok(index as u32);
+        }
+    }
+
+    let label = if wanted.name.is_empty() {
+        &wanted.device_name
+    } else {
+        &wanted.name
+    };
+    Err(format!(
+        "Monitor \"{label}\" ({}x{}) is not connected",
+        wanted.width, wanted.height
+    ))
+}
+
+/// Returns the monitor's top-left corner in virtual-desktop coordinates (primary at `0,0`).
+pub fn get_monitor_origin(monitor_index: u32) -> Result<(i32, i32), String> {
+    #[cfg(target_os = "windows")]
+    {
+        let monitors =
+            Monitor::enumerate().map_err(|e| format!("Failed to enumerate monitors: {e}"))?;
+
+        let monitor = monitors
+            .into_iter()
+            .nth(monitor_index as usize)
+            .ok_or_else(|| format!("Monitor index {monitor_index} not found"))?;
+
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        let ok =
+            unsafe { GetMonitorInfoW(HMONITOR(monitor.as_raw_hmonitor() as isize), &mut info) };
+        if !ok.as_bool() {
+            return Err(format!(
+                "Failed to get monitor info for index {monitor_index}"
+            ));
+        }
+
+        Ok((info.rcMonitor.left, info.rcMonitor.top))
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = monitor_index;
+        Ok((0, 0))
+    }
+}
+
 /// Returns monitor scale factor (1.0 = 100%, 1.25 = 125%, etc).
 pub fn get_monitor_scale_factor(monitor_index: u32) -> Result<f64, String> {
     #[cfg(target_os = "windows")]
@@ -405,10 +1054,45 @@ pub fn get_monitor_scale_factor(monitor_index: u32) -> Result<f64, String> {
 /// Finds ffmpeg binary without requiring it in system PATH.
 ///
 /// Search order:
-/// 1. Dev build: `src-tauri/binaries/ffmpeg-x86_64-pc-windows-msvc.exe`
-/// 2. Production: next to bundled app executable (`ffmpeg.exe`)
-/// 3. Fallback: system PATH
+/// 1. User override: `ffmpegPath` from the app settings, when the file still exists
+/// 2. Dev build: `src-tauri/binaries/ffmpeg-x86_64-pc-windows-msvc.exe`
+/// 3. Production: next to bundled app executable (`ffmpeg.exe`)
+/// 4. Fallback: system PATH
+///
+/// The result is cached for the session (every export and proxy step calls this), and is
+/// resolved again when the cached binary disappears or after [`invalidate_ffmpeg_exe_cache`].
 pub fn find_ffmpeg_exe() -> std::path::PathBuf {
+    let mut cached = FFMPEG_EXE_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(path) = cached.as_ref() {
+        if path.components().count() == 1 || path.is_file() {
+            return path.clone();
+        }
+    }
+    let path = resolve_ffmpeg_exe();
+    *cached = Some(path.clone());
+    path
+}
+
+static FFMPEG_EXE_CACHE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Drops the cached FFmpeg location, e.g. after the configured binary changed.
+pub fn invalidate_ffmpeg_exe_cache() {
+    *FFMPEG_EXE_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn resolve_ffmpeg_exe() -> std::path::PathBuf {
+    if let Some(custom) = app_settings::load_app_settings().ffmpeg_path {
+        let custom = std::path::PathBuf::from(custom);
+        if custom.is_file() {
+            log::debug!("ffmpeg: using configured binary at {}", custom.display());
+            return custom;
+        }
+        log::warn!(
+            "ffmpeg: configured binary {} not found, using the default search",
+            custom.display()
+        );
+    }
+
     #[cfg(debug_assertions)]
     {
         let dev = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -434,6 +1118,32 @@ pub fn find_ffmpeg_exe() -> std::path::PathBuf {
     std::path::PathBuf::from("ffmpeg")
 }
 
+/// Runs `ffmpeg -version` and returns the first line of its output, e.g.
+/// `ffmpeg version 7.0 Copyright ...`.
+pub fn ffmpeg_version(ffmpeg: &std::path::Path) -> Result<String, String> {
+    let mut command = std::process::Command::new(ffmpeg);
+    apply_no_window_flags(&mut command);
+    let output = command
+        .arg("-hide_banner")
+        .arg("-version")
+        .stdin(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg ({}): {e}", ffmpeg.display()))?;
+    if !output.status.success() {
+        return Err(format!(
+            "FFmpeg ({}) exited with status {}",
+            ffmpeg.display(),
+            output.status
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let version = stdout.lines().next().unwrap_or_default().trim();
+    if !version.starts_with("ffmpeg version") {
+        return Err(format!("Not an FFmpeg binary: {}", ffmpeg.display()));
+    }
+    Ok(version.to_string())
+}
+
 /// Configures external process launch so it does not spawn a visible console window on Windows.
 pub fn apply_no_window_flags(command: &mut std::process::Command) {
     #[cfg(target_os = "windows")]
@@ -452,8 +1162,15 @@ pub fn start_capture(
     output_path: PathBuf,
     width: u32,
     height: u32,
+    capture_scale: f64,
     target_fps: u32,
     quality: RecordingQuality,
+    color_format: CaptureColorFormat,
+    low_motion_economy: bool,
+    min_update_interval: Option<Duration>,
+    preview_feed: Option<PreviewFeed>,
+    timeline: Option<MuxerTimeline>,
+    failure: CaptureFailure,
 ) -> Result<std::thread::JoinHandle<Result<(), String>>, String> {
     let monitors =
         Monitor::enumerate().map_err(|e| format!("Failed to enumerate monitors: {e}"))?;
@@ -463,37 +1180,289 @@ pub fn start_capture(
         .nth(monitor_index as usize)
         .ok_or_else(|| format!("Monitor index {monitor_index} not found"))?;
 
+    let stopped = stop_flag.clone();
+    let (encoded_width, encoded_height) = scaled_capture_size(width, height, capture_scale);
     let flags = CaptureFlags {
         stop_flag,
         pause_flag,
         encoder: CaptureEncoderSettings {
             output_path,
-            width,
-            height,
+            width: encoded_width,
+            height: encoded_height,
+            downscale: (encoded_width, encoded_height) != (width, height),
             target_fps: target_fps.max(1),
             quality,
+            color_format,
+            low_motion_economy,
         },
+        preview_feed,
+        timeline,
     };
 
     let safe_fps = target_fps.max(1);
+    // WGC may deliver frames less often than the encode rate; the CFR muxer repeats the
+    // last frame to fill the gaps.
+    let min_update_interval =
+        min_update_interval.unwrap_or_else(|| Duration::from_secs_f64(1.0 / safe_fps as f64));
 
     let settings = Settings::new(
         monitor,
         CursorCaptureSettings::WithoutCursor,
         DrawBorderSettings::WithoutBorder,
         SecondaryWindowSettings::Default,
-        MinimumUpdateIntervalSettings::Custom(Duration::from_secs_f64(1.0 / safe_fps as f64)),
+        MinimumUpdateIntervalSettings::Custom(min_update_interval),
         DirtyRegionSettings::Default,
-        ColorFormat::Bgra8,
+        color_format.wgc_format(),
         flags,
     );
 
     let handle = std::thread::Builder::new()
         .name("nsc-capture".to_string())
         .spawn(move || {
-            ScreenRecorder::start(settings).map_err(|e| format!("WGC capture failed: {e}"))
+            let result =
+                ScreenRecorder::start(settings).map_err(|e| format!("WGC capture failed: {e}"));
+            if !stopped.load(Ordering::Relaxed) {
+                let error = match &result {
+                    Ok(()) => "Capture session ended before the recording was stopped".to_string(),
+                    Err(err) => err.clone(),
+                };
+                log::error!("capture thread for monitor {monitor_index} failed: {error}");
+                failure.record(format!("Monitor {monitor_index}: {error}"));
+            }
+            result
         })
         .map_err(|e| format!("Failed to spawn capture thread: {e}"))?;
 
     Ok(handle)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn half_pixel(r: u16, g: u16, b: u16) -> Vec<u8> {
+        [r, g, b, 0x3c00]
+            .iter()
+            .flat_map(|channel| channel.to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn half_float_frames_are_flipped_and_swizzled_to_bgra() {
+        assert_eq!(half_to_f32(0x3c00), 1.0);
+        assert_eq!(half_to_f32(0x4000), 2.0);
+        assert_eq!(half_to_f32(0x0000), 0.0);
+
+        // Two rows of one pixel: pure red on top, SDR white then HDR white below.
+        let mut frame = half_pixel(0x3c00, 0, 0);
+        frame.extend(half_pixel(0x4400, 0x3c00, 0));
+        let converter = FrameConverter::new(CaptureColorFormat::Rgba16F);
+        let normalized = normalize_frame_for_encoder(&frame, 1, 2, &converter);
+
+        assert_eq!(normalized.len(), 8);
+        let (bottom_up_first, top) = normalized.split_at(4);
+        assert_eq!(top[0], 0);
+        assert_eq!(top[1], 0);
+        assert!(top[2] > 200);
+        assert_eq!(top[3], 255);
+        // 4.0 (HDR highlight in red) stays brighter than 1.0 (SDR white in green).
+        assert!(bottom_up_first[2] > bottom_up_first[1]);
+    }
+
+    #[test]
+    fn padded_rows_are_flipped_using_the_buffer_stride() {
+        // 1x2 frame with 4 bytes of padding after every row.
+        let frame = [1, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8, 0, 0, 0, 0];
+        assert_eq!(source_row_stride(frame.len(), 1, 2, 4), Ok(8));
+
+        let normalized = normalize_frame_for_encoder(&frame, 1, 2, &FrameConverter::Bgra8);
+        assert_eq!(normalized, vec![5, 6, 7, 8, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn malformed_buffers_pass_through_unchanged() {
+        let uneven_rows = [1, 2, 3, 4, 5, 6, 7];
+        assert!(source_row_stride(uneven_rows.len(), 1, 2, 4).is_err());
+        let normalized = normalize_frame_for_encoder(&uneven_rows, 1, 2, &FrameConverter::Bgra8);
+        assert_eq!(normalized, uneven_rows.to_vec());
+
+        // Whole rows, but 6 bytes per row is not a whole number of BGRA pixels.
+        assert!(source_row_stride(12, 1, 2, 4).is_err());
+        // Rows shorter than the frame width.
+        assert!(source_row_stride(8, 2, 2, 4).is_err());
+    }
+
+    #[test]
+    fn reduced_capture_scale_averages_frames_down_to_even_sizes() {
+        assert_eq!(scaled_capture_size(3840, 2160, 0.5), (1920, 1080));
+        assert_eq!(scaled_capture_size(2560, 1440, 0.75), (1920, 1080));
+        assert_eq!(scaled_capture_size(1366, 768, 0.7), (956, 538));
+        // Native and out-of-range scales keep the monitor size; tiny ones are clamped.
+        assert_eq!(scaled_capture_size(1365, 767, 1.0), (1365, 767));
+        assert_eq!(scaled_capture_size(1920, 1080, f64::NAN), (1920, 1080));
+        assert_eq!(scaled_capture_size(1920, 1080, 0.01), (480, 270));
+
+        // 2x2 blocks of a 4x2 frame: black/white and a uniform grey.
+        let frame: Vec<u8> = [
+            [0, 0, 0, 255],
+            [255, 255, 255, 255],
+            [100, 100, 100, 255],
+            [100, 100, 100, 255],
+        ]
+        .iter()
+        .cycle()
+        .take(8)
+        .flatten()
+        .copied()
+        .collect();
+        let scaled = downscale_bgra(&frame, 4, 2, 2, 1);
+        assert_eq!(scaled, vec![128, 128, 128, 255, 100, 100, 100, 255]);
+
+        // A frame of the wrong size is replaced rather than encoded at the wrong size.
+        assert_eq!(downscale_bgra(&frame[..12], 4, 2, 2, 1), vec![0; 8]);
+    }
+
+    /// Feeds `fresh` flags tick by tick and returns the ticks sent to the encoder.
+    fn run_pacer(pacer: &mut FramePacer, ticks: &[bool]) -> (Vec<u64>, Vec<StaticRun>) {
+        let mut sent = Vec::new();
+        let mut runs = Vec::new();
+        for (tick, fresh) in ticks.iter().enumerate() {
+            let decision = pacer.on_tick(tick as u64, *fresh);
+            if decision.send {
+                sent.push(tick as u64);
+            }
+            runs.extend(decision.ended_run);
+        }
+        (sent, runs)
+    }
+
+    #[test]
+    fn pacer_writes_every_tick_without_economy() {
+        let mut pacer = FramePacer::new(60, false);
+        let mut ticks = vec![true];
+        ticks.resize(600, false);
+        let (sent, runs) = run_pacer(&mut pacer, &ticks);
+
+        assert_eq!(sent, (0..600).collect::<Vec<_>>());
+        assert!(runs.is_empty());
+        assert_eq!(
+            pacer.finish(),
+            (
+                None,
+                Some(StaticRun {
+                    start_ms: 16,
+                    duration_ms: 9_983,
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn static_runs_shorter_than_the_threshold_are_not_reported() {
+        let mut pacer = FramePacer::new(30, false);
+        // 149 repeated ticks at 30 fps is just under five seconds.
+        let mut ticks = vec![true];
+        ticks.resize(150, false);
+        ticks.push(true);
+        ticks.resize(301, false);
+        ticks.push(true);
+        let (_, runs) = run_pacer(&mut pacer, &ticks);
+
+        assert_eq!(
+            runs,
+            vec![StaticRun {
+                start_ms: 5_033,
+                duration_ms: 5_000,
+            }]
+        );
+        assert_eq!(pacer.finish(), (None, None));
+    }
+
+    #[test]
+    fn economy_thins_static_periods_on_the_cfr_grid() {
+        let pacing = EconomyPacing::for_fps(60);
+        assert_eq!(pacing.after_ticks, 60);
+        assert_eq!(pacing.every_ticks, 15);
+
+        let mut pacer = FramePacer::new(60, true);
+        let mut ticks = vec![true];
+        ticks.resize(121, false);
+        ticks.push(true);
+        let (sent, _) = run_pacer(&mut pacer, &ticks);
+
+        // Full rate for the first second of repeats, then 4 fps, and the new frame at once.
+        let mut expected: Vec<u64> = (0..=60).collect();
+        expected.extend([75, 90, 105, 120, 121]);
+        assert_eq!(sent, expected);
+        // Sent ticks keep their grid slot, so the PTS of the new frame is unchanged.
+        assert_eq!(
+            *sent.last().unwrap() as i64 * (HNS_PER_SECOND / 60),
+            20_166_586
+        );
+    }
+
+    #[test]
+    fn economy_writes_the_skipped_tail_when_the_run_closes() {
+        let mut pacer = FramePacer::new(60, true);
+        let mut ticks = vec![true];
+        ticks.resize(71, false);
+        let (sent, _) = run_pacer(&mut pacer, &ticks);
+
+        assert_eq!(sent.last(), Some(&60));
+        // Tick 70 is the last slot of the run: writing it keeps the video 71 frames long.
+        assert_eq!(pacer.finish(), (Some(70), None));
+        assert_eq!(pacer.finish(), (None, None));
+
+        // After a pause the first tick is written even if the screen did not change.
+        let decision = pacer.on_tick(71, true);
+        assert!(decision.send);
+    }
+
+    fn monitor(name: &str, device_name: &str, width: u32, height: u32) -> MonitorIdentity {
+        MonitorIdentity {
+            name: name.to_string(),
+            device_name: device_name.to_string(),
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn saved_monitor_is_found_after_display_numbers_change() {
+        let saved = monitor("DELL U2720Q", r"\\.\DISPLAY1", 3840, 2160);
+        let attached = [
+            monitor("Generic PnP Monitor", r"\\.\DISPLAY1", 1920, 1080),
+            monitor("DELL U2720Q", r"\\.\DISPLAY3", 3840, 2160),
+        ];
+        assert_eq!(match_monitor_identity(&saved, &attached), Ok(1));
+
+        // Two identical monitors: the device name breaks the tie.
+        let saved = monitor("DELL U2720Q", r"\\.\DISPLAY3", 3840, 2160);
+        let attached = [
+            monitor("DELL U2720Q", r"\\.\DISPLAY2", 3840, 2160),
+            monitor("DELL U2720Q", r"\\.\DISPLAY3", 3840, 2160),
+        ];
+        assert_eq!(match_monitor_identity(&saved, &attached), Ok(1));
+    }
+
+    #[test]
+    fn missing_or_ambiguous_monitor_is_an_error() {
+        let saved = monitor("DELL U2720Q", r"\\.\DISPLAY2", 3840, 2160);
+        let attached = [monitor("Generic PnP Monitor", r"\\.\DISPLAY1", 1920, 1080)];
+        let err = match_monitor_identity(&saved, &attached).unwrap_err();
+        assert!(err.contains("DELL U2720Q"));
+
+        let attached = [
+            monitor("DELL U2720Q", r"\\.\DISPLAY4", 3840, 2160),
+            monitor("DELL U2720Q", r"\\.\DISPLAY5", 3840, 2160),
+        ];
+        assert!(match_monitor_identity(&saved, &attached).is_err());
+    }
+
+    #[test]
+    fn bgra_frames_keep_bytes_and_flip_rows() {
+        let frame = [1, 2, 3, 4, 5, 6, 7, 8];
+        let normalized = normalize_frame_for_encoder(&frame, 1, 2, &FrameConverter::Bgra8);
+        assert_eq!(normalized, vec![5, 6, 7, 8, 1, 2, 3, 4]);
+    }
+}
@@ -1,22 +1,16 @@
 //! Active screen-recording state.
 
 use std::path::PathBuf;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicU64};
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
+use crate::capture::multi_monitor::MonitorTile;
+use crate::capture::recorder::{CaptureFailure, MuxerTimeline};
 use crate::models::events::InputEvent;
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
-#[serde(rename_all = "kebab-case")]
-pub enum AutoZoomTriggerMode {
-    #[default]
-    SingleClick,
-    MultiClickWindow,
-    CtrlClick,
-}
+pub use crate::models::project::AutoZoomTriggerMode;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
@@ -29,7 +23,8 @@ pub enum RecordingAudioMode {
 }
 
 pub enum AudioCaptureBackend {
-    FfmpegChild(std::process::Child),
+    /// Shared with the session's [`AudioTrackWatcher`], which polls it for an early exit.
+    FfmpegChild(Arc<std::sync::Mutex<std::process::Child>>),
     NativeLoopback {
         stop_flag: Arc<AtomicBool>,
         join_handle: std::thread::JoinHandle<Result<(), String>>,
@@ -39,11 +34,77 @@ pub enum AudioCaptureBackend {
 pub struct AudioCaptureProcess {
     pub backend: AudioCaptureBackend,
     pub output_path: PathBuf,
+    /// Unix timestamp in ms when the capture was started; the track's time zero.
+    pub started_at_ms: u64,
+    /// Unix timestamp in ms when the capture process died on its own; `0` while running.
+    pub ended_early_at_ms: Arc<AtomicU64>,
+}
+
+/// Background thread that notices audio capture processes exiting before the stop.
+pub struct AudioTrackWatcher {
+    pub stop_flag: Arc<AtomicBool>,
+    pub join_handle: std::thread::JoinHandle<()>,
+}
+
+/// Channel count and sample rate every captured audio track is conformed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioCaptureFormat {
+    pub channels: u16,
+    pub sample_rate: u32,
+}
+
+impl AudioCaptureFormat {
+    pub const SUPPORTED_CHANNELS: [u16; 2] = [1, 2];
+    pub const SUPPORTED_SAMPLE_RATES: [u32; 4] = [32_000, 44_100, 48_000, 96_000];
+
+    /// Validates requested values, falling back to stereo 48 kHz for unset ones.
+    pub fn new(channels: Option<u16>, sample_rate: Option<u32>) -> Result<Self, String> {
+        let defaults = Self::default();
+        let channels = channels.unwrap_or(defaults.channels);
+        if !Self::SUPPORTED_CHANNELS.contains(&channels) {
+            return Err(format!(
+                "Unsupported audio channel count {channels}; expected 1 (mono) or 2 (stereo)"
+            ));
+        }
+        let sample_rate = sample_rate.unwrap_or(defaults.sample_rate);
+        if !Self::SUPPORTED_SAMPLE_RATES.contains(&sample_rate) {
+            return Err(format!(
+                "Unsupported audio sample rate {sample_rate} Hz; expected one of {:?}",
+                Self::SUPPORTED_SAMPLE_RATES
+            ));
+        }
+        Ok(Self {
+            channels,
+            sample_rate,
+        })
+    }
+
+    /// FFmpeg channel layout name for the channel count.
+    pub fn channel_layout(self) -> &'static str {
+        if self.channels == 1 {
+            "mono"
+        } else {
+            "stereo"
+        }
+    }
+}
+
+impl Default for AudioCaptureFormat {
+    fn default() -> Self {
+        Self {
+            channels: 2,
+            sample_rate: 48_000,
+        }
+    }
 }
 
 pub struct AudioCaptureSession {
     pub system_capture: Option<AudioCaptureProcess>,
     pub microphone_capture: Option<AudioCaptureProcess>,
+    /// Target format of the finalized tracks; the WASAPI loopback is resampled to it.
+    pub format: AudioCaptureFormat,
+    /// Watches ffmpeg capture children (e.g. a microphone unplugged mid-recording).
+    pub watcher: Option<AudioTrackWatcher>,
 }
 
 /// Data for one active recording session.
@@ -55,10 +116,26 @@ pub struct ActiveRecording {
     pub pause_flag: Arc<AtomicBool>,
     /// WGC capture thread; exits once stop flag is observed.
     pub capture_thread: std::thread::JoinHandle<Result<(), String>>,
+    /// Capture threads of additional monitors in a multi-monitor recording.
+    pub extra_capture_threads: Vec<std::thread::JoinHandle<Result<(), String>>>,
+    /// Per-monitor layout of a multi-monitor recording; empty for a single monitor.
+    pub monitor_tiles: Vec<MonitorTile>,
+    /// Capture frame rate, reused when compositing monitor recordings.
+    pub target_fps: u32,
+    /// Key-frame distance requested for `raw.mp4`; applied by a re-encode after stopping.
+    pub keyframe_interval_ms: Option<u64>,
+    /// Active runs of the primary monitor's muxer; audio is trimmed to match them.
+    pub muxer_timeline: MuxerTimeline,
+    /// Set by any capture thread that fails before the recording is stopped.
+    pub capture_failure: CaptureFailure,
     /// Project directory: `{Videos}/NeuroScreenCaster/{recording_id}/`
     pub output_dir: PathBuf,
+    /// Output canvas size (combined size for multi-monitor recordings).
     pub width: u32,
     pub height: u32,
+    /// Size of `raw.mp4`; smaller than the canvas when recording at a reduced capture scale.
+    pub video_width: u32,
+    pub video_height: u32,
     pub scale_factor: f64,
     /// Unix timestamp in ms when recording started.
     pub start_ms: u64,
@@ -82,6 +159,33 @@ pub struct ActiveRecording {
     pub audio_capture_session: Option<AudioCaptureSession>,
     /// Telemetry processor thread (returns all collected events on join).
     pub telemetry_processor: std::thread::JoinHandle<Vec<InputEvent>>,
+    /// Run an export with project defaults once the recording is finalized.
+    pub auto_export: bool,
+    /// Build the editor proxy in the background once the recording is finalized.
+    pub generate_proxy: bool,
+    /// The app window was minimized because content protection is unavailable; restored on stop.
+    pub window_hidden_for_capture: bool,
+    /// Recording time limit excluding pauses; the watchdog stops the recording once reached.
+    pub max_duration_ms: Option<u64>,
+}
+
+impl ActiveRecording {
+    /// Recorded (non-paused) time up to `now_ms`.
+    pub fn active_duration_ms(&self, now_ms: u64) -> u64 {
+        let closed_pauses = self
+            .pause_ranges_ms
+            .iter()
+            .map(|(start, end)| end.saturating_sub(*start))
+            .sum::<u64>();
+        let open_pause = self
+            .pause_started_at_ms
+            .map(|started| now_ms.saturating_sub(started))
+            .unwrap_or(0);
+        now_ms
+            .saturating_sub(self.start_ms)
+            .saturating_sub(closed_pauses)
+            .saturating_sub(open_pause)
+    }
 }
 
 /// Tauri managed recorder state.
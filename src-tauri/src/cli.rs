@@ -0,0 +1,178 @@
+//! Headless command line entry point: `neuroscreencaster export --project PATH ...` runs
+//! an export without creating any window and exits with a status code.
+//!
+//! Arguments are checked in [`run`] before Tauri starts; without a known subcommand the
+//! regular GUI launches.
+
+use crate::commands::export::{run_export_blocking, ExportRequest};
+
+/// Exit code for an invalid command line.
+const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str =
+    "Usage: neuroscreencaster export --project PATH [--out FILE] [--codec h264|h265|vp9] \
+[--width W] [--height H] [--fps FPS] [--threads N] [--audio-copy] [--resumable]";
+
+/// Parsed `export` subcommand.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct ExportArgs {
+    project: String,
+    out: Option<String>,
+    codec: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    fps: Option<u32>,
+    threads: Option<u32>,
+    audio_copy: bool,
+    resumable: bool,
+}
+
+/// Runs a CLI subcommand from the process arguments (without the program name).
+///
+/// Returns the process exit code, or `None` when the arguments name no subcommand and the
+/// GUI should start.
+pub fn run(args: impl IntoIterator<Item = String>) -> Option<i32> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        Some("export") => {}
+        _ => return None,
+    }
+    attach_parent_console();
+
+    let export_args = match parse_export_args(args) {
+        Ok(export_args) => export_args,
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            return Some(EXIT_USAGE);
+        }
+    };
+    Some(run_export(export_args))
+}
+
+fn run_export(args: ExportArgs) -> i32 {
+    let request = ExportRequest {
+        width: args.width,
+        height: args.height,
+        fps: args.fps,
+        codec: args.codec,
+        output_path: args.out,
+        threads: args.threads,
+        audio_copy: args.audio_copy.then_some(true),
+        resumable: args.resumable.then_some(true),
+        ..ExportRequest::default()
+    };
+    let result = run_export_blocking(&args.project, request, |status| {
+        println!("[{:>3.0}%] {}", status.progress * 100.0, status.message);
+    });
+    match result {
+        Ok(output_path) => {
+            println!("{output_path}");
+            0
+        }
+        Err(err) => {
+            eprintln!("Export failed: {err}");
+            1
+        }
+    }
+}
+
+fn parse_export_args(args: impl IntoIterator<Item = String>) -> Result<ExportArgs, String> {
+    let mut parsed = ExportArgs::default();
+    let mut project = None;
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        if flag == "--audio-copy" {
+            parsed.audio_copy = true;
+            continue;
+        }
+        if flag == "--resumable" {
+            parsed.resumable = true;
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {flag}"))?;
+        let number = || {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid number for {flag}: {value}"))
+        };
+        match flag.as_str() {
+            "--project" => project = Some(value.clone()),
+            "--out" => parsed.out = Some(value.clone()),
+            "--codec" => parsed.codec = Some(value.clone()),
+            "--width" => parsed.width = Some(number()?),
+            "--height" => parsed.height = Some(number()?),
+            "--fps" => parsed.fps = Some(number()?),
+            "--threads" => parsed.threads = Some(number()?),
+            _ => return Err(format!("Unknown option: {flag}")),
+        }
+    }
+    parsed.project = project.ok_or("Missing --project")?;
+    Ok(parsed)
+}
+
+/// Release builds use the GUI subsystem on Windows, which has no console; borrow the
+/// terminal that started the process so progress output is visible.
+#[cfg(windows)]
+fn attach_parent_console() {
+    use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    // Fails harmlessly when there is no parent console or one is already attached.
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn export_arguments_are_parsed_into_a_request() {
+        let parsed = parse_export_args(args(&[
+            "--project",
+            "C:/Videos/demo/project.json",
+            "--out",
+            "demo.mp4",
+            "--codec",
+            "h265",
+            "--fps",
+            "30",
+            "--audio-copy",
+            "--resumable",
+        ]))
+        .expect("valid arguments");
+        assert_eq!(
+            parsed,
+            ExportArgs {
+                project: "C:/Videos/demo/project.json".to_string(),
+                out: Some("demo.mp4".to_string()),
+                codec: Some("h265".to_string()),
+                fps: Some(30),
+                audio_copy: true,
+                resumable: true,
+                ..ExportArgs::default()
+            }
+        );
+
+        assert!(parse_export_args(args(&["--out", "demo.mp4"])).is_err());
+        assert!(parse_export_args(args(&["--project", "p.json", "--fps", "fast"])).is_err());
+        assert!(parse_export_args(args(&["--project", "p.json", "--width"])).is_err());
+        assert!(parse_export_args(args(&["--project", "p.json", "--bogus", "1"])).is_err());
+    }
+
+    #[test]
+    fn gui_starts_without_a_subcommand() {
+        assert_eq!(run(Vec::new()), None);
+        assert_eq!(run(args(&["--some-webview-flag"])), None);
+        assert_eq!(
+            run(args(&["export", "--out", "demo.mp4"])),
+            Some(EXIT_USAGE)
+        );
+    }
+}
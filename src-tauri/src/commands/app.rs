@@ -1,5 +1,189 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tauri::Emitter;
+use tauri_plugin_shell::ShellExt;
+
+use crate::capture::recorder::{ffmpeg_version, find_ffmpeg_exe, invalidate_ffmpeg_exe_cache};
+use crate::logging;
+use crate::models::app_settings::{self, HotkeyBindings};
+use crate::telemetry::hotkeys::{self, GLOBAL_HOTKEY_EVENT};
+use crate::telemetry::logger::{TelemetryGlobal, TelemetryState};
+
 #[tauri::command]
 pub fn exit_application(app: tauri::AppHandle) {
     app.exit(0);
 }
 
+/// Reveals a file (selected in its folder) or opens a directory in the system file manager.
+#[tauri::command]
+pub async fn open_in_file_manager(app: tauri::AppHandle, path: String) -> Result<(), String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("Path is empty".to_string());
+    }
+
+    let target = PathBuf::from(trimmed);
+    if !target.exists() {
+        return Err(format!("Path does not exist: {}", target.display()));
+    }
+    let target = target.canonicalize().unwrap_or(target);
+
+    if target.is_dir() {
+        open_directory(&app, &target)
+    } else {
+        reveal_file(&app, &target)
+    }
+}
+
+/// Makes exports and audio capture use the FFmpeg at `path` after checking that it runs.
+///
+/// An empty or missing path restores the default search. Returns the version line of
+/// the binary that will be used.
+#[tauri::command]
+pub async fn set_ffmpeg_path(path: Option<String>) -> Result<String, String> {
+    let path = path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty());
+    tokio::task::spawn_blocking(move || {
+        if let Some(path) = &path {
+            let binary = PathBuf::from(path);
+            if !binary.is_file() {
+                return Err(format!("FFmpeg binary not found: {}", binary.display()));
+            }
+            ffmpeg_version(&binary)?;
+        }
+        app_settings::update_app_settings(|settings| settings.ffmpeg_path = path)?;
+        invalidate_ffmpeg_exe_cache();
+
+        let ffmpeg = find_ffmpeg_exe();
+        let version = ffmpeg_version(&ffmpeg)?;
+        log::info!("set_ffmpeg_path: using {} ({version})", ffmpeg.display());
+        Ok(version)
+    })
+    .await
+    .map_err(|e| format!("FFmpeg check task join error: {e}"))?
+}
+
+/// Keeps (`true`) or discards the audio-free `raw-video-only.mp4` left by the audio mux
+/// of future recordings.
+#[tauri::command]
+pub fn set_keep_video_only_backup(keep: bool) -> Result<(), String> {
+    app_settings::update_app_settings(|settings| settings.keep_video_only_backup = keep)
+}
+
+/// Returns the saved global recording hotkeys.
+#[tauri::command]
+pub fn get_hotkeys() -> HotkeyBindings {
+    app_settings::load_app_settings().hotkeys
+}
+
+/// Validates, saves and activates the global recording hotkeys.
+///
+/// Fails without changing anything when a combination is malformed, collides with a
+/// system shortcut or is bound twice. Returns the bindings in canonical form.
+#[tauri::command]
+pub fn set_hotkeys(
+    telemetry: tauri::State<'_, TelemetryState>,
+    hotkeys: HotkeyBindings,
+) -> Result<HotkeyBindings, String> {
+    let parsed = hotkeys::parse_bindings(&hotkeys)?;
+    let canonical = hotkeys::canonical_bindings(&parsed);
+    app_settings::update_app_settings(|settings| settings.hotkeys = canonical.clone())?;
+    telemetry
+        .0
+        .hotkeys
+        .lock()
+        .map_err(|_| "Failed to access hotkey state".to_string())?
+        .set_bindings(parsed);
+    Ok(canonical)
+}
+
+/// Loads the saved hotkeys and forwards every one that fires to the UI as a
+/// `global-hotkey` event. Called once from the app setup.
+pub fn spawn_hotkey_dispatcher(app: tauri::AppHandle, global: Arc<TelemetryGlobal>) {
+    match hotkeys::parse_bindings(&app_settings::load_app_settings().hotkeys) {
+        Ok(parsed) => {
+            if let Ok(mut matcher) = global.hotkeys.lock() {
+                matcher.set_bindings(parsed);
+            }
+        }
+        Err(err) => log::warn!("hotkeys: ignoring saved bindings: {err}"),
+    }
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    if let Ok(mut hotkey_tx) = global.hotkey_tx.lock() {
+        *hotkey_tx = Some(tx);
+    }
+    let spawned = std::thread::Builder::new()
+        .name("nsc-hotkeys".to_string())
+        .spawn(move || {
+            for action in rx {
+                log::info!("hotkeys: {action:?}");
+                if let Err(err) = app.emit(GLOBAL_HOTKEY_EVENT, action) {
+                    log::warn!("hotkeys: failed to emit {action:?}: {err}");
+                }
+            }
+        });
+    if let Err(err) = spawned {
+        log::error!("hotkeys: failed to spawn dispatcher thread: {err}");
+    }
+}
+
+/// Returns the path of the active application log file.
+#[tauri::command]
+pub fn get_log_path() -> Result<String, String> {
+    logging::log_file_path().map(|path| path.to_string_lossy().to_string())
+}
+
+/// Opens the log directory in the system file manager with the active log selected.
+#[tauri::command]
+pub async fn open_logs(app: tauri::AppHandle) -> Result<(), String> {
+    log::logger().flush();
+    let path = logging::log_file_path()?;
+    let target = if path.exists() {
+        path
+    } else {
+        logging::log_dir()?
+    };
+    open_in_file_manager(app, target.to_string_lossy().to_string()).await
+}
+
+/// Opens a directory through the shell plugin.
+fn open_directory(app: &tauri::AppHandle, dir: &Path) -> Result<(), String> {
+    // `Shell::open` is deprecated in favour of tauri-plugin-opener, which this app does not ship.
+    #[allow(deprecated)]
+    app.shell()
+        .open(display_path(dir), None)
+        .map_err(|e| format!("Failed to open file manager for {}: {e}", dir.display()))
+}
+
+/// Selecting a file needs Explorer's `/select,` switch, which the shell opener cannot pass,
+/// so Explorer is spawned directly.
+#[cfg(target_os = "windows")]
+fn reveal_file(_app: &tauri::AppHandle, file: &Path) -> Result<(), String> {
+    use crate::capture::recorder::apply_no_window_flags;
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    let mut command = Command::new("explorer");
+    apply_no_window_flags(&mut command);
+    // Explorer parses `/select,` itself; pass it raw so std does not re-quote the argument.
+    command
+        .raw_arg(format!("/select,\"{}\"", display_path(file)))
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open file manager for {}: {e}", file.display()))
+}
+
+/// Other platforms open the containing folder.
+#[cfg(not(target_os = "windows"))]
+fn reveal_file(app: &tauri::AppHandle, file: &Path) -> Result<(), String> {
+    open_directory(app, file.parent().unwrap_or(file))
+}
+
+/// `canonicalize` yields a verbatim `\\?\` path on Windows that Explorer does not understand.
+fn display_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    path.strip_prefix(r"\\?\").unwrap_or(&path).to_string()
+}
@@ -1,36 +1,60 @@
 //! Tauri IPC commands for recording.
 
-use std::collections::HashSet;
-use std::io::Write;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crate::algorithm::{camera_engine, cursor_smoothing};
-use crate::capture::audio_loopback::start_system_loopback_capture;
-use crate::capture::preview::{NativePreviewFrame, NativePreviewState};
-use crate::capture::recorder::RecordingQuality;
+use crate::capture::audio_loopback::{self, start_system_loopback_capture, SystemAudioOutput};
+use crate::capture::disk_space::{
+    critical_free_disk_space_mb, ensure_free_disk_space, DEFAULT_MIN_FREE_DISK_SPACE_MB,
+};
+use crate::capture::multi_monitor::{self, MonitorTile};
+use crate::capture::preview::{
+    NativePreviewFrame, NativePreviewState, PreviewDelivery, PreviewSettings,
+};
 use crate::capture::recorder::{
-    apply_no_window_flags, find_ffmpeg_exe, get_monitor_scale_factor, get_monitor_size,
-    start_capture, DEFAULT_TARGET_FPS,
+    apply_no_window_flags, find_ffmpeg_exe, get_monitor_origin, get_monitor_scale_factor,
+    get_monitor_size, match_monitor_identity, monitor_count, monitor_identities,
+    scaled_capture_size, start_capture, CaptureFailure, MuxerSegment, MuxerTimeline,
+    DEFAULT_TARGET_FPS,
 };
+use crate::capture::recorder::{CaptureColorFormat, RecordingQuality};
 use crate::capture::state::{
-    ActiveRecording, AudioCaptureBackend, AudioCaptureProcess, AudioCaptureSession,
-    AutoZoomTriggerMode, RecorderState, RecordingAudioMode,
+    ActiveRecording, AudioCaptureBackend, AudioCaptureFormat, AudioCaptureProcess,
+    AudioCaptureSession, AudioTrackWatcher, AutoZoomTriggerMode, RecorderState, RecordingAudioMode,
+};
+use crate::commands::export::{
+    spawn_export_job, ExportRequest, ExportState, ExportStatus, FfmpegProgress,
+};
+use crate::commands::project::patch_project_file;
+use crate::models::app_settings::{self, CaptureProfile};
+use crate::models::events::{
+    CoordinateSpace, EventsFile, InputEvent, SCHEMA_VERSION as EVENTS_VERSION,
 };
-use crate::models::events::{EventsFile, InputEvent, SCHEMA_VERSION as EVENTS_VERSION};
 use crate::models::project::{
-    NormalizedRect, Project, ProjectSettings, TargetPoint, Timeline, ZoomSegment,
-    SCHEMA_VERSION as PROJECT_VERSION,
+    AudioStartOffsets, AudioTrackIssue, AudioTrackKind, CameraSettings, Project, ProjectSettings,
+    TargetPoint, Timeline, ZoomSegment, ACTIVATION_WINDOW_MS_RANGE, CAMERA_ENGINE_VERSION,
+    CLICK_CLUSTER_GAP_MS_RANGE, DEAD_ZONE_RATIO_RANGE, ESCAPE_DISTANCE_RATIO_RANGE,
+    HARD_EDGE_RATIO_RANGE, MAX_ZOOM_RANGE, MIN_CLICKS_TO_ACTIVATE_RANGE,
+    MIN_ZOOM_INTERVAL_MS_RANGE, SCHEMA_VERSION as PROJECT_VERSION, SCROLL_FOLLOW_STRENGTH_RANGE,
+    ZOOM_LEAD_MS_RANGE, ZOOM_PADDING_RATIO_RANGE,
 };
-use crate::telemetry::logger::{self, TelemetryState};
-use serde::Deserialize;
+use crate::telemetry::logger::{self, CaptureFilter, TelemetryGlobal, TelemetryState};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::HWND;
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::Input::KeyboardAndMouse::{
     GetAsyncKeyState, VK_CONTROL, VK_LCONTROL, VK_RCONTROL,
 };
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::WindowsAndMessaging::{GetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE};
 
 #[derive(Debug, Clone, Copy, Deserialize, Default)]
 #[serde(rename_all = "kebab-case")]
@@ -51,14 +75,248 @@ impl RecordingQualityOption {
     }
 }
 
+#[derive(Debug, Clone, Copy, Deserialize, Default)]
+enum CaptureColorFormatOption {
+    #[default]
+    #[serde(rename = "bgra8")]
+    Bgra8,
+    /// Higher precision capture for HDR monitors, tone-mapped to SDR for the encoder.
+    #[serde(rename = "rgba16f")]
+    Rgba16F,
+}
+
+impl CaptureColorFormatOption {
+    fn as_recorder_format(self) -> CaptureColorFormat {
+        match self {
+            CaptureColorFormatOption::Bgra8 => CaptureColorFormat::Bgra8,
+            CaptureColorFormatOption::Rgba16F => CaptureColorFormat::Rgba16F,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct StartRecordingOptions {
     auto_zoom_trigger_mode: Option<AutoZoomTriggerMode>,
     quality: Option<RecordingQualityOption>,
+    /// Capture pixel format; `bgra8` (default) or `rgba16f` for HDR sources.
+    color_format: Option<CaptureColorFormatOption>,
+    /// Distance between key frames in `raw.mp4` (clamped to 0.25–10 s). The capture encoder
+    /// has no GOP setting, so a set interval re-encodes `raw.mp4` once after stopping.
+    keyframe_interval_ms: Option<u64>,
+    /// Write only a few frames per second while the screen is static instead of repeating
+    /// the last frame at full rate (off by default).
+    low_motion_economy: Option<bool>,
+    /// Minimum time between captured frames (clamped to 1–1000 ms), independent of the
+    /// output frame rate. Defaults to one frame interval at the target fps.
+    min_update_interval_ms: Option<u64>,
     target_fps: Option<u32>,
+    /// Fraction of the native resolution to record at (clamped to 0.25–1.0; native by
+    /// default). Event coordinates are stored in the reduced video's pixels.
+    capture_scale: Option<f64>,
     audio_capture_mode: Option<RecordingAudioMode>,
     microphone_device: Option<String>,
+    /// Output device to record as system audio: a WASAPI render endpoint id or name, or a
+    /// dshow loopback device name for the fallback path. Auto-detected when unset.
+    system_audio_device: Option<String>,
+    /// Captured audio channel count: 1 (mono) or 2 (stereo, default).
+    audio_channels: Option<u16>,
+    /// Captured audio sample rate in Hz (48000 by default).
+    audio_sample_rate: Option<u32>,
+    /// Free space required to start recording (2048 MB by default).
+    min_free_disk_space_mb: Option<u64>,
+    /// Free space below which a running recording is stopped automatically; a quarter of
+    /// `min_free_disk_space_mb` by default, never above it.
+    critical_free_disk_space_mb: Option<u64>,
+    /// Export a ready-to-share video with project defaults right after stopping.
+    auto_export: Option<bool>,
+    /// Build the editor proxy after stopping (default). When disabled the editor
+    /// decodes `raw.mp4` directly.
+    generate_proxy: Option<bool>,
+    /// Stop automatically after this much recorded time (pauses are not counted).
+    max_duration_ms: Option<u64>,
+    /// How long audio capture runs before the screen capture starts, so the first
+    /// words are not lost while the devices warm up.
+    audio_preroll_ms: Option<u64>,
+    /// Minimize the app window while recording when the platform cannot exclude it from
+    /// capture, so the app never records itself. Off by default: the window stays
+    /// reachable from the taskbar to stop the recording.
+    hide_window_if_unprotected: Option<bool>,
+    /// Record key presses (default). Disabling it keeps typed text such as passwords out
+    /// of `events.json` and makes Ctrl+click zoom unavailable.
+    record_keystrokes: Option<bool>,
+    /// Record mouse button presses and releases (default).
+    record_clicks: Option<bool>,
+    /// Record cursor movement (default).
+    record_moves: Option<bool>,
+    /// Record mouse wheel scrolling (default).
+    record_scroll: Option<bool>,
+    /// Drop keystrokes typed while a password field has focus (default).
+    redact_secure_fields: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NativePreviewOptions {
+    max_dimension: Option<u32>,
+    target_fps: Option<u32>,
+    delivery: Option<PreviewDelivery>,
+}
+
+/// File name of the editor proxy video inside the project directory.
+pub(crate) const PROXY_VIDEO_FILE: &str = "proxy-edit.mp4";
+
+#[derive(Debug, Clone, Copy, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProxyQuality {
+    Low,
+    Balanced,
+    #[default]
+    High,
+}
+
+impl ProxyQuality {
+    fn crf(self) -> u32 {
+        match self {
+            ProxyQuality::Low => 28,
+            ProxyQuality::Balanced => 23,
+            ProxyQuality::High => 17,
+        }
+    }
+}
+
+/// Editor proxy encoding options; defaults match the proxy built after recording.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyOptions {
+    pub quality: Option<ProxyQuality>,
+    /// Limits the proxy height (the width follows the source aspect ratio).
+    pub max_height: Option<u32>,
+}
+
+/// Default audio warm-up before the screen capture starts.
+const DEFAULT_AUDIO_PREROLL_MS: u64 = 200;
+const MAX_AUDIO_PREROLL_MS: u64 = 2_000;
+/// How long a finished proxy build stays queryable through `get_proxy_status`.
+const PROXY_STATUS_RETENTION_MS: u64 = 60 * 60 * 1000;
+
+/// Progress of the editor proxy built in the background after a recording stops.
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyStatus {
+    pub is_running: bool,
+    pub progress: f64,
+    /// Proxy file name once the build has finished successfully.
+    pub proxy_video_path: Option<String>,
+    pub error: Option<String>,
+    pub started_at_ms: Option<u64>,
+    pub finished_at_ms: Option<u64>,
+}
+
+/// Background proxy builds keyed by recording id.
+///
+/// Finished entries are dropped after [`PROXY_STATUS_RETENTION_MS`] when the next build starts.
+#[derive(Clone, Default)]
+pub struct ProxyState(pub Arc<std::sync::Mutex<HashMap<String, ProxyStatus>>>);
+
+/// Session that needs the app's own windows kept out of screen capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CaptureExclusionHolder {
+    Preview,
+    Recording,
+}
+
+/// Tracks which sessions hold the capture exclusion so that, for example, stopping the
+/// preview while a recording is running does not un-protect the app windows.
+#[derive(Clone, Default)]
+pub struct CaptureExclusionState(pub Arc<std::sync::Mutex<HashSet<CaptureExclusionHolder>>>);
+
+impl CaptureExclusionState {
+    pub fn is_active(&self) -> bool {
+        self.0
+            .lock()
+            .map(|holders| !holders.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// Excludes every app window from capture on behalf of `holder`.
+    ///
+    /// Fails when the platform did not actually exclude the windows; the hold is kept either
+    /// way so a later `release` stays balanced.
+    pub fn acquire(
+        &self,
+        app: &tauri::AppHandle,
+        holder: CaptureExclusionHolder,
+    ) -> Result<(), String> {
+        if let Ok(mut holders) = self.0.lock() {
+            holders.insert(holder);
+        }
+        set_app_windows_excluded_from_capture(app, true).inspect_err(|err| {
+            log::warn!("{holder:?}: failed to exclude app windows from capture: {err}");
+        })
+    }
+
+    /// Drops `holder`; app windows become capturable again once no session needs them hidden.
+    pub fn release(&self, app: &tauri::AppHandle, holder: CaptureExclusionHolder) {
+        let still_held = match self.0.lock() {
+            Ok(mut holders) => {
+                holders.remove(&holder);
+                !holders.is_empty()
+            }
+            Err(_) => false,
+        };
+        if still_held {
+            return;
+        }
+        if let Err(err) = set_app_windows_excluded_from_capture(app, false) {
+            log::warn!("{holder:?}: failed to restore app window capture visibility: {err}");
+        }
+    }
+}
+
+/// Interval between background health checks of an active recording.
+const RECORDING_WATCHDOG_INTERVAL: Duration = Duration::from_secs(5);
+/// Shorter recordings are accidental start/stop presses: they hold a frame or two and leave
+/// the camera and cursor tracks without any meaningful timeline, so they are not saved.
+const MIN_RECORDING_DURATION_MS: u64 = 500;
+/// Accepted key-frame distance for `raw.mp4`.
+const MIN_KEYFRAME_INTERVAL_MS: u64 = 250;
+const MAX_KEYFRAME_INTERVAL_MS: u64 = 10_000;
+
+/// Payload of the `recording-auto-stopped` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordingAutoStoppedPayload {
+    recording_id: String,
+    reason: String,
+    error: Option<String>,
+}
+
+/// Payload of the `recording-started`, `recording-paused`, `recording-resumed`,
+/// `recording-stopped` and `recording-discarded` events.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordingStatePayload {
+    recording_id: String,
+}
+
+/// Payload of the `recording-error` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordingErrorPayload {
+    recording_id: String,
+    error: String,
+    /// Whatever was captured before the failure was saved as a project.
+    finalized: bool,
+}
+
+fn emit_recording_state(window: &tauri::WebviewWindow, event: &str, recording_id: &str) {
+    let payload = RecordingStatePayload {
+        recording_id: recording_id.to_string(),
+    };
+    if let Err(err) = window.emit(event, payload) {
+        log::warn!("Failed to emit {event}: {err}");
+    }
 }
 
 #[tauri::command]
@@ -66,17 +324,24 @@ pub async fn start_native_preview(
     preview: tauri::State<'_, NativePreviewState>,
     window: tauri::WebviewWindow,
     monitor_index: Option<u32>,
+    options: Option<NativePreviewOptions>,
 ) -> Result<(), String> {
-    if let Err(err) = set_window_excluded_from_capture(&window, true) {
-        log::warn!("start_native_preview: failed to exclude window from capture: {err}");
-    }
+    let options = options.unwrap_or_default();
+    let settings =
+        PreviewSettings::new(options.max_dimension, options.target_fps, options.delivery);
+    let exclusion = window.state::<CaptureExclusionState>();
+    let _ = exclusion.acquire(window.app_handle(), CaptureExclusionHolder::Preview);
     tokio::time::sleep(Duration::from_millis(80)).await;
 
+    let monitor_index = resolve_monitor_index(monitor_index)?;
     let mut guard = preview.0.lock().await;
-    match guard.start_session(monitor_index.unwrap_or(0)) {
-        Ok(()) => Ok(()),
+    match guard.start_session(monitor_index, settings, &window) {
+        Ok(()) => {
+            remember_monitor_index(monitor_index);
+            Ok(())
+        }
         Err(err) => {
-            let _ = set_window_excluded_from_capture(&window, false);
+            exclusion.release(window.app_handle(), CaptureExclusionHolder::Preview);
             Err(err)
         }
     }
@@ -93,7 +358,7 @@ pub async fn get_native_preview_frame(
 #[tauri::command]
 pub async fn stop_native_preview(
     preview: tauri::State<'_, NativePreviewState>,
-    state: tauri::State<'_, RecorderState>,
+    exclusion: tauri::State<'_, CaptureExclusionState>,
     window: tauri::WebviewWindow,
 ) -> Result<(), String> {
     {
@@ -101,12 +366,8 @@ pub async fn stop_native_preview(
         guard.stop_session();
     }
 
-    let has_active_recording = state.0.lock().await.is_some();
-    if !has_active_recording {
-        if let Err(err) = set_window_excluded_from_capture(&window, false) {
-            log::warn!("stop_native_preview: failed to restore window capture visibility: {err}");
-        }
-    }
+    // An active recording keeps its own hold on the exclusion.
+    exclusion.release(window.app_handle(), CaptureExclusionHolder::Preview);
 
     Ok(())
 }
@@ -124,6 +385,14 @@ pub async fn list_audio_input_devices() -> Result<Vec<String>, String> {
         .map_err(|e| format!("Failed to fetch audio devices: {e}"))?
 }
 
+/// Lists active audio outputs (WASAPI render endpoints) available for system audio capture.
+#[tauri::command]
+pub async fn list_system_audio_outputs() -> Result<Vec<SystemAudioOutput>, String> {
+    tokio::task::spawn_blocking(audio_loopback::list_system_audio_outputs)
+        .await
+        .map_err(|e| format!("Failed to fetch audio outputs: {e}"))?
+}
+
 #[cfg(target_os = "windows")]
 fn is_ctrl_pressed_now() -> Option<bool> {
     // High-order bit is set when key is currently down.
@@ -144,7 +413,159 @@ pub async fn start_recording(
     telemetry: tauri::State<'_, TelemetryState>,
     preview: tauri::State<'_, NativePreviewState>,
     window: tauri::WebviewWindow,
-    monitor_index: u32,
+    monitor_index: Option<u32>,
+    options: Option<StartRecordingOptions>,
+) -> Result<String, String> {
+    let monitor_index = resolve_monitor_index(monitor_index)?;
+    let recording_id =
+        begin_recording(state, telemetry, preview, window, &[monitor_index], options).await?;
+    remember_monitor_index(monitor_index);
+    Ok(recording_id)
+}
+
+/// Picks the monitor to capture: the requested one, else the remembered one if it is
+/// still attached, else the primary monitor.
+fn resolve_monitor_index(requested: Option<u32>) -> Result<u32, String> {
+    let count = monitor_count()?;
+    if let Some(index) = requested {
+        if index as usize >= count {
+            return Err(format!(
+                "Monitor index {index} not found ({count} monitors attached)"
+            ));
+        }
+        return Ok(index);
+    }
+
+    match app_settings::load_app_settings().last_monitor_index {
+        Some(index) if (index as usize) < count => Ok(index),
+        Some(index) => {
+            log::warn!(
+                "Remembered monitor {index} is no longer attached ({count} monitors); using primary"
+            );
+            Ok(0)
+        }
+        None => Ok(0),
+    }
+}
+
+fn remember_monitor_index(monitor_index: u32) {
+    if let Err(err) = app_settings::update_app_settings(|settings| {
+        settings.last_monitor_index = Some(monitor_index);
+    }) {
+        log::warn!("Failed to remember monitor {monitor_index}: {err}");
+    }
+}
+
+/// Saves the monitor and recording options under `name`, replacing a profile with the
+/// same name. The monitor is stored by identity so the profile survives docking changes.
+#[tauri::command]
+pub async fn save_capture_profile(
+    name: String,
+    monitor_index: Option<u32>,
+    options: Option<serde_json::Value>,
+) -> Result<CaptureProfile, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name must not be empty".to_string());
+    }
+    let options = options.unwrap_or_else(|| serde_json::json!({}));
+    serde_json::from_value::<StartRecordingOptions>(options.clone())
+        .map_err(|e| format!("Invalid recording options for profile \"{name}\": {e}"))?;
+
+    let monitor_index = resolve_monitor_index(monitor_index)?;
+    let monitor = monitor_identities()?
+        .into_iter()
+        .nth(monitor_index as usize)
+        .ok_or_else(|| format!("Monitor index {monitor_index} not found"))?;
+    let profile = CaptureProfile {
+        name,
+        monitor,
+        options,
+    };
+
+    let saved = profile.clone();
+    app_settings::update_app_settings(move |settings| {
+        match settings
+            .capture_profiles
+            .iter_mut()
+            .find(|existing| existing.name.eq_ignore_ascii_case(&saved.name))
+        {
+            Some(existing) => *existing = saved,
+            None => settings.capture_profiles.push(saved),
+        }
+    })?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn list_capture_profiles() -> Result<Vec<CaptureProfile>, String> {
+    Ok(app_settings::load_app_settings().capture_profiles)
+}
+
+/// Starts a recording with a saved profile, re-resolving its monitor among the ones
+/// attached now.
+#[tauri::command]
+pub async fn start_recording_from_profile(
+    state: tauri::State<'_, RecorderState>,
+    telemetry: tauri::State<'_, TelemetryState>,
+    preview: tauri::State<'_, NativePreviewState>,
+    window: tauri::WebviewWindow,
+    name: String,
+) -> Result<String, String> {
+    let profile = app_settings::load_app_settings()
+        .capture_profiles
+        .into_iter()
+        .find(|profile| profile.name.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| format!("Capture profile \"{}\" not found", name.trim()))?;
+    let options = serde_json::from_value::<StartRecordingOptions>(profile.options.clone())
+        .map_err(|e| {
+            format!(
+                "Capture profile \"{}\" has invalid options: {e}",
+                profile.name
+            )
+        })?;
+    let monitor_index = match_monitor_identity(&profile.monitor, &monitor_identities()?)
+        .map_err(|err| format!("Capture profile \"{}\": {err}", profile.name))?;
+
+    let recording_id = begin_recording(
+        state,
+        telemetry,
+        preview,
+        window,
+        &[monitor_index],
+        Some(options),
+    )
+    .await?;
+    remember_monitor_index(monitor_index);
+    Ok(recording_id)
+}
+
+/// Records several monitors at once and composites them side by side on stop.
+///
+/// Monitors are placed left to right in the given order; events and the smart camera
+/// operate on the combined canvas.
+#[tauri::command]
+pub async fn start_multi_monitor_recording(
+    state: tauri::State<'_, RecorderState>,
+    telemetry: tauri::State<'_, TelemetryState>,
+    preview: tauri::State<'_, NativePreviewState>,
+    window: tauri::WebviewWindow,
+    monitor_indices: Vec<u32>,
+    options: Option<StartRecordingOptions>,
+) -> Result<String, String> {
+    let mut unique = HashSet::new();
+    if monitor_indices.is_empty() || !monitor_indices.iter().all(|idx| unique.insert(*idx)) {
+        return Err("Select one or more distinct monitors to record".to_string());
+    }
+    begin_recording(state, telemetry, preview, window, &monitor_indices, options).await
+}
+
+async fn begin_recording(
+    state: tauri::State<'_, RecorderState>,
+    telemetry: tauri::State<'_, TelemetryState>,
+    preview: tauri::State<'_, NativePreviewState>,
+    window: tauri::WebviewWindow,
+    monitor_indices: &[u32],
     options: Option<StartRecordingOptions>,
 ) -> Result<String, String> {
     let mut guard = state.0.lock().await;
@@ -156,7 +577,19 @@ pub async fn start_recording(
     let options = options.unwrap_or_default();
     let auto_zoom_trigger_mode = options.auto_zoom_trigger_mode.unwrap_or_default();
     let quality = options.quality.unwrap_or_default().as_recorder_quality();
+    let color_format = options
+        .color_format
+        .unwrap_or_default()
+        .as_recorder_format();
+    let low_motion_economy = options.low_motion_economy.unwrap_or(false);
+    let min_update_interval = options
+        .min_update_interval_ms
+        .map(|ms| Duration::from_millis(ms.clamp(1, 1_000)));
+    let keyframe_interval_ms = options
+        .keyframe_interval_ms
+        .map(|ms| ms.clamp(MIN_KEYFRAME_INTERVAL_MS, MAX_KEYFRAME_INTERVAL_MS));
     let target_fps = sanitize_recording_fps(options.target_fps.unwrap_or(DEFAULT_TARGET_FPS));
+    let capture_scale = options.capture_scale.unwrap_or(1.0);
     let audio_mode = options.audio_capture_mode.unwrap_or_default();
     let microphone_device = options.microphone_device.and_then(|value| {
         let trimmed = value.trim();
@@ -166,14 +599,64 @@ pub async fn start_recording(
             Some(trimmed.to_string())
         }
     });
-
+    let system_audio_device = options
+        .system_audio_device
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    let audio_format = AudioCaptureFormat::new(options.audio_channels, options.audio_sample_rate)?;
+    let min_free_disk_space_mb = options
+        .min_free_disk_space_mb
+        .unwrap_or(DEFAULT_MIN_FREE_DISK_SPACE_MB);
+    let critical_free_disk_space_mb =
+        critical_free_disk_space_mb(min_free_disk_space_mb, options.critical_free_disk_space_mb);
+    let auto_export = options.auto_export.unwrap_or(false);
+    let generate_proxy = options.generate_proxy.unwrap_or(true);
+    let hide_window_if_unprotected = options.hide_window_if_unprotected.unwrap_or(false);
+    let capture_filter = CaptureFilter {
+        record_keystrokes: options.record_keystrokes.unwrap_or(true),
+        record_clicks: options.record_clicks.unwrap_or(true),
+        record_moves: options.record_moves.unwrap_or(true),
+        record_scroll: options.record_scroll.unwrap_or(true),
+        redact_secure_fields: options.redact_secure_fields.unwrap_or(true),
+    };
+    let max_duration_ms = options.max_duration_ms.filter(|limit| *limit > 0);
+    let audio_preroll_ms = options
+        .audio_preroll_ms
+        .unwrap_or(DEFAULT_AUDIO_PREROLL_MS)
+        .min(MAX_AUDIO_PREROLL_MS);
+
+    let primary_monitor = monitor_indices[0];
+    let scale_factors = monitor_indices
+        .iter()
+        .map(|&monitor_index| {
+            get_monitor_scale_factor(monitor_index).unwrap_or_else(|err| {
+                log::warn!(
+                    "start_recording: failed to resolve scale factor of monitor {monitor_index}: {err}"
+                );
+                1.0
+            })
+        })
+        .collect::<Vec<_>>();
+    let scale_factor = scale_factors[0];
+    // Events are recorded in physical pixels of the virtual desktop; one canvas cannot
+    // hold monitors whose pixels map to different logical sizes.
+    if scale_factors
+        .iter()
+        .any(|factor| (factor - scale_factor).abs() > 0.01)
     {
-        let mut preview_guard = preview.0.lock().await;
-        preview_guard.stop_session();
+        return Err(format!(
+            "Monitors with different display scaling ({}) cannot be recorded together; record them separately or set the same scaling",
+            scale_factors
+                .iter()
+                .map(|factor| format!("{}%", (factor * 100.0).round()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
     }
 
     let recording_id = uuid::Uuid::new_v4().to_string();
     let output_dir = project_dir(&recording_id)?;
+    ensure_free_disk_space(&output_dir, min_free_disk_space_mb)?;
     std::fs::create_dir_all(&output_dir)
         .map_err(|e| format!("Failed to create output directory: {e}"))?;
 
@@ -182,42 +665,154 @@ pub async fn start_recording(
         output_dir.display()
     );
 
-    let (width, height) = get_monitor_size(monitor_index)?;
-    let scale_factor = get_monitor_scale_factor(monitor_index).unwrap_or_else(|err| {
-        log::warn!("start_recording: failed to resolve monitor scale factor: {err}");
-        1.0
-    });
-    log::info!("start_recording: monitor={monitor_index} resolution={width}x{height}");
-
-    if let Err(err) = set_window_excluded_from_capture(&window, true) {
-        log::warn!("start_recording: failed to exclude window from capture: {err}");
+    let mut monitor_tiles = Vec::new();
+    if monitor_indices.len() > 1 {
+        for &monitor_index in monitor_indices {
+            let (width, height) = get_monitor_size(monitor_index)?;
+            let (origin_x, origin_y) = get_monitor_origin(monitor_index)?;
+            monitor_tiles.push(MonitorTile {
+                monitor_index,
+                raw_path: output_dir.join(format!("raw-monitor-{monitor_index}.mp4")),
+                origin_x,
+                origin_y,
+                width,
+                height,
+                canvas_x: 0,
+            });
+        }
+        multi_monitor::assign_canvas_offsets(&mut monitor_tiles);
     }
+    let (width, height) = if monitor_tiles.is_empty() {
+        get_monitor_size(primary_monitor)?
+    } else {
+        multi_monitor::canvas_size(&monitor_tiles)
+    };
+    // Monitor tiles are captured natively and scaled once they are composited.
+    let (video_width, video_height) = scaled_capture_size(width, height, capture_scale);
+    log::info!(
+        "start_recording: monitors={monitor_indices:?} resolution={width}x{height} video={video_width}x{video_height}"
+    );
+
+    let exclusion = window.state::<CaptureExclusionState>();
+    let window_hidden_for_capture =
+        match exclusion.acquire(window.app_handle(), CaptureExclusionHolder::Recording) {
+            Ok(()) => false,
+            Err(_) if hide_window_if_unprotected => {
+                log::warn!(
+                    "start_recording: content protection unavailable, minimizing the app window"
+                );
+                // Minimized rather than hidden: the taskbar button is the only way back to the
+                // Stop button while recording.
+                match window.minimize() {
+                    Ok(()) => true,
+                    Err(err) => {
+                        log::warn!("start_recording: failed to minimize the app window: {err}");
+                        false
+                    }
+                }
+            }
+            Err(_) => false,
+        };
 
-    let raw_mp4 = output_dir.join("raw.mp4");
-    let mut audio_capture_session =
-        start_audio_capture_session(&output_dir, audio_mode, microphone_device.as_deref())?;
+    let mut audio_capture_session = start_audio_capture_session(
+        &output_dir,
+        audio_mode,
+        microphone_device.as_deref(),
+        system_audio_device.as_deref(),
+        audio_format,
+    )
+    .inspect_err(|_| {
+        exclusion.release(window.app_handle(), CaptureExclusionHolder::Recording);
+        if window_hidden_for_capture {
+            restore_hidden_window(&window);
+        }
+    })?;
+    if audio_capture_session.is_some() && audio_preroll_ms > 0 {
+        // The measured start offset is compensated on finalize; the pre-roll only makes
+        // sure the devices already deliver samples when the first frame is captured.
+        tokio::time::sleep(Duration::from_millis(audio_preroll_ms)).await;
+    }
+    // The recorder takes over the live preview (without the cursor, like the recording
+    // itself) instead of running a second capture session on the same monitor.
+    let preview_feed = preview.0.lock().await.begin_recording_feed();
     let stop_flag = Arc::new(AtomicBool::new(false));
     let pause_flag = Arc::new(AtomicBool::new(false));
-    let capture_thread = match start_capture(
-        monitor_index,
-        stop_flag.clone(),
-        pause_flag.clone(),
-        raw_mp4,
-        width,
-        height,
-        target_fps,
-        quality,
-    ) {
-        Ok(thread) => thread,
-        Err(err) => {
-            stop_audio_capture_session(&mut audio_capture_session);
-            let _ = set_window_excluded_from_capture(&window, false);
-            return Err(err);
-        }
+    let capture_targets = if monitor_tiles.is_empty() {
+        vec![(
+            primary_monitor,
+            output_dir.join("raw.mp4"),
+            width,
+            height,
+            capture_scale,
+        )]
+    } else {
+        monitor_tiles
+            .iter()
+            .map(|tile| {
+                (
+                    tile.monitor_index,
+                    tile.raw_path.clone(),
+                    tile.width,
+                    tile.height,
+                    1.0,
+                )
+            })
+            .collect()
     };
+    let mut preview_feed = Some(preview_feed);
+    // Only the primary monitor's runs are tracked: every tile is paused by the same flag.
+    let muxer_timeline = MuxerTimeline::default();
+    let mut timeline = Some(muxer_timeline.clone());
+    let capture_failure = CaptureFailure::default();
+    let mut capture_threads = Vec::with_capacity(capture_targets.len());
+    for (monitor_index, raw_path, capture_width, capture_height, target_scale) in capture_targets {
+        match start_capture(
+            monitor_index,
+            stop_flag.clone(),
+            pause_flag.clone(),
+            raw_path,
+            capture_width,
+            capture_height,
+            target_scale,
+            target_fps,
+            quality,
+            color_format,
+            low_motion_economy,
+            min_update_interval,
+            preview_feed.take(),
+            timeline.take(),
+            capture_failure.clone(),
+        ) {
+            Ok(thread) => capture_threads.push(thread),
+            Err(err) => {
+                // Stop the monitors that already started and wait for them to release
+                // their encoders before reporting the failure.
+                stop_flag.store(true, Ordering::Relaxed);
+                let started = std::mem::take(&mut capture_threads);
+                let _ = tokio::task::spawn_blocking(move || {
+                    for thread in started {
+                        match thread.join() {
+                            Ok(Ok(())) => {}
+                            Ok(Err(e)) => log::warn!("Capture thread finished with error: {e}"),
+                            Err(_) => log::error!("Capture thread panicked"),
+                        }
+                    }
+                })
+                .await;
+                preview.0.lock().await.end_recording_feed();
+                stop_audio_capture_session(&mut audio_capture_session);
+                exclusion.release(window.app_handle(), CaptureExclusionHolder::Recording);
+                if window_hidden_for_capture {
+                    restore_hidden_window(&window);
+                }
+                return Err(err);
+            }
+        }
+    }
+    let capture_thread = capture_threads.remove(0);
 
     let start_ms = chrono::Utc::now().timestamp_millis() as u64;
-    let telemetry_processor = logger::start_session(&telemetry.0, start_ms);
+    let telemetry_processor = logger::start_session(&telemetry.0, start_ms, capture_filter);
     logger::set_paused(&telemetry.0, false);
 
     *guard = Some(ActiveRecording {
@@ -225,9 +820,17 @@ pub async fn start_recording(
         stop_flag,
         pause_flag,
         capture_thread,
-        output_dir,
+        extra_capture_threads: capture_threads,
+        monitor_tiles,
+        target_fps,
+        keyframe_interval_ms,
+        muxer_timeline,
+        capture_failure,
+        output_dir: output_dir.clone(),
         width,
         height,
+        video_width,
+        video_height,
         scale_factor,
         start_ms,
         pause_started_at_ms: None,
@@ -240,7 +843,22 @@ pub async fn start_recording(
         microphone_device,
         audio_capture_session,
         telemetry_processor,
+        auto_export,
+        generate_proxy,
+        window_hidden_for_capture,
+        max_duration_ms,
     });
+    drop(guard);
+
+    emit_recording_state(&window, "recording-started", &recording_id);
+    spawn_recording_watchdog(
+        state.0.clone(),
+        telemetry.0.clone(),
+        window,
+        recording_id.clone(),
+        output_dir,
+        critical_free_disk_space_mb,
+    );
 
     Ok(recording_id)
 }
@@ -252,11 +870,185 @@ pub async fn stop_recording(
     window: tauri::WebviewWindow,
     recording_id: String,
 ) -> Result<(), String> {
-    let mut rec = state.0.lock().await.take().ok_or("No active recording")?;
+    let capture_failure = state
+        .0
+        .lock()
+        .await
+        .as_ref()
+        .map(|rec| rec.capture_failure.clone());
+    finish_recording(state.0.clone(), telemetry.0.clone(), window, recording_id).await?;
+
+    match capture_failure.and_then(|failure| failure.error()) {
+        Some(error) => Err(format!(
+            "Screen capture failed during recording ({error}); the part captured before the failure was saved"
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Snapshot of the active recording for the UI.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStats {
+    pub recording_id: String,
+    /// Recorded time excluding pauses.
+    pub active_duration_ms: u64,
+    pub is_paused: bool,
+    /// Set once a capture thread has failed; the watchdog finalizes the recording shortly after.
+    pub capture_error: Option<String>,
+    /// Finished static periods of at least 5 s, in which the video only repeats one frame.
+    pub long_static_runs: usize,
+    pub longest_static_run_ms: u64,
+}
+
+#[tauri::command]
+pub async fn get_recording_stats(
+    state: tauri::State<'_, RecorderState>,
+) -> Result<Option<RecordingStats>, String> {
+    let guard = state.0.lock().await;
+    let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+    Ok(guard.as_ref().map(|rec| {
+        let static_runs = rec.muxer_timeline.static_runs();
+        RecordingStats {
+            recording_id: rec.recording_id.clone(),
+            active_duration_ms: rec.active_duration_ms(now_ms),
+            is_paused: rec.pause_started_at_ms.is_some(),
+            capture_error: rec.capture_failure.error(),
+            long_static_runs: static_runs.len(),
+            longest_static_run_ms: static_runs
+                .iter()
+                .map(|run| run.duration_ms)
+                .max()
+                .unwrap_or(0),
+        }
+    }))
+}
+
+/// Polls an active recording in the background and stops it automatically when
+/// the output volume drops below `critical_free_disk_space_mb` or the recording time
+/// limit is reached, so the recording is finalized exactly like a manual stop.
+///
+/// A capture thread that exits on its own (encoder or WGC failure) is reported with
+/// `recording-error` and the recording is finalized with whatever was captured.
+fn spawn_recording_watchdog(
+    recorder: Arc<tokio::sync::Mutex<Option<ActiveRecording>>>,
+    telemetry: Arc<TelemetryGlobal>,
+    window: tauri::WebviewWindow,
+    recording_id: String,
+    output_dir: PathBuf,
+    critical_free_disk_space_mb: u64,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut sleep_for = RECORDING_WATCHDOG_INTERVAL;
+        loop {
+            tokio::time::sleep(sleep_for).await;
+
+            let (remaining_ms, capture_error) = {
+                let guard = recorder.lock().await;
+                let Some(rec) = guard
+                    .as_ref()
+                    .filter(|rec| rec.recording_id == recording_id)
+                else {
+                    return;
+                };
+                let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+                let capture_error = rec.capture_failure.error().or_else(|| {
+                    let thread_exited = rec.capture_thread.is_finished()
+                        || rec
+                            .extra_capture_threads
+                            .iter()
+                            .any(|thread| thread.is_finished());
+                    (thread_exited && !rec.stop_flag.load(Ordering::Relaxed))
+                        .then(|| "Screen capture stopped unexpectedly".to_string())
+                });
+                let remaining_ms = rec
+                    .max_duration_ms
+                    .map(|limit| (limit, limit.saturating_sub(rec.active_duration_ms(now_ms))));
+                (remaining_ms, capture_error)
+            };
+
+            if let Some(error) = capture_error {
+                log::error!("recording watchdog: {recording_id}: {error}");
+                let result = finish_recording(
+                    recorder.clone(),
+                    telemetry.clone(),
+                    window.clone(),
+                    recording_id.clone(),
+                )
+                .await;
+                if let Err(err) = &result {
+                    log::error!("recording watchdog: failed to finalize {recording_id}: {err}");
+                }
+
+                let payload = RecordingErrorPayload {
+                    recording_id: recording_id.clone(),
+                    error,
+                    finalized: result.is_ok(),
+                };
+                if let Err(err) = window.emit("recording-error", payload) {
+                    log::warn!("recording watchdog: failed to emit recording error: {err}");
+                }
+                return;
+            }
+
+            let reason = match remaining_ms {
+                Some((limit, 0)) => {
+                    format!("Recording time limit of {}s reached", limit.div_ceil(1000))
+                }
+                _ => match ensure_free_disk_space(&output_dir, critical_free_disk_space_mb) {
+                    Ok(()) => {
+                        // Wake up right at the limit; while paused the remaining time stays
+                        // constant, so the regular interval keeps polling.
+                        sleep_for = remaining_ms
+                            .map(|(_, remaining)| {
+                                RECORDING_WATCHDOG_INTERVAL.min(Duration::from_millis(remaining))
+                            })
+                            .unwrap_or(RECORDING_WATCHDOG_INTERVAL);
+                        continue;
+                    }
+                    Err(reason) => reason,
+                },
+            };
+
+            log::warn!("recording watchdog: auto-stopping {recording_id}: {reason}");
+            let result = finish_recording(
+                recorder.clone(),
+                telemetry.clone(),
+                window.clone(),
+                recording_id.clone(),
+            )
+            .await;
+            if let Err(err) = &result {
+                log::error!("recording watchdog: failed to finalize {recording_id}: {err}");
+            }
+
+            let payload = RecordingAutoStoppedPayload {
+                recording_id: recording_id.clone(),
+                reason,
+                error: result.err(),
+            };
+            if let Err(err) = window.emit("recording-auto-stopped", payload) {
+                log::warn!("recording watchdog: failed to emit auto-stop event: {err}");
+            }
+            return;
+        }
+    });
+}
+
+/// Stops the active recording and writes all project files.
+///
+/// Shared by the `stop_recording` command and automatic stops from the watchdog.
+async fn finish_recording(
+    recorder: Arc<tokio::sync::Mutex<Option<ActiveRecording>>>,
+    telemetry: Arc<TelemetryGlobal>,
+    window: tauri::WebviewWindow,
+    recording_id: String,
+) -> Result<(), String> {
+    let mut rec = recorder.lock().await.take().ok_or("No active recording")?;
 
     if rec.recording_id != recording_id {
         let active_id = rec.recording_id.clone();
-        *state.0.lock().await = Some(rec);
+        *recorder.lock().await = Some(rec);
         return Err(format!(
             "Recording ID mismatch: active={active_id}, requested={recording_id}"
         ));
@@ -274,12 +1066,17 @@ pub async fn stop_recording(
     }
     rec.pause_flag.store(false, Ordering::Relaxed);
     rec.stop_flag.store(true, Ordering::Relaxed);
-    logger::set_paused(&telemetry.0, false);
-    logger::stop_session(&telemetry.0);
+    logger::set_paused(&telemetry, false);
+    logger::stop_session(&telemetry);
 
     let output_dir = rec.output_dir.clone();
+    let project_file = output_dir.join("project.json");
+    let auto_export = rec.auto_export;
+    let generate_proxy = rec.generate_proxy;
+    let window_hidden_for_capture = rec.window_hidden_for_capture;
     let width = rec.width;
     let height = rec.height;
+    let video_size = (rec.video_width, rec.video_height);
     let scale_factor = rec.scale_factor;
     let start_ms = rec.start_ms;
     let auto_zoom_trigger_mode = rec.auto_zoom_trigger_mode;
@@ -288,32 +1085,100 @@ pub async fn stop_recording(
     let mut audio_capture_session = rec.audio_capture_session.take();
     let pause_ranges_ms = rec.pause_ranges_ms.clone();
     let cursor_hidden_ranges_abs_ms = rec.cursor_hidden_ranges_abs_ms.clone();
+    let monitor_tiles = std::mem::take(&mut rec.monitor_tiles);
+    let extra_capture_threads = std::mem::take(&mut rec.extra_capture_threads);
+    let target_fps = rec.target_fps;
+    let keyframe_interval_frames = rec
+        .keyframe_interval_ms
+        .map(|interval_ms| keyframe_interval_frames(interval_ms, target_fps));
+    let muxer_timeline = rec.muxer_timeline.clone();
+    let capture_failure = rec.capture_failure.clone();
     let paused_total_ms = total_pause_duration_ms(&pause_ranges_ms);
+    let duration_ms = end_ms
+        .saturating_sub(start_ms)
+        .saturating_sub(paused_total_ms);
+    let discard = duration_ms < MIN_RECORDING_DURATION_MS;
+
+    let stop_result = tokio::task::spawn_blocking(move || -> Result<(u64, Option<String>), String> {
+        for capture_thread in std::iter::once(rec.capture_thread).chain(extra_capture_threads) {
+            match capture_thread.join() {
+                Ok(Ok(())) => {}
+                // Failures before the stop were recorded by the thread and are reported by
+                // the caller; anything else happened while shutting down.
+                Ok(Err(e)) if capture_failure.error().is_some() => {
+                    log::debug!("Capture thread error already reported: {e}")
+                }
+                Ok(Err(e)) => log::warn!("Capture thread finished with error: {e}"),
+                Err(_) => log::error!("Capture thread panicked"),
+            }
+        }
+        // Checked before compositing so an accidental start/stop does not pay for an encode.
+        if discard {
+            let _ = stop_audio_capture_session(&mut audio_capture_session);
+            let _ = rec.telemetry_processor.join();
+            if let Err(err) = std::fs::remove_dir_all(&output_dir) {
+                log::warn!(
+                    "stop_recording: failed to remove {}: {err}",
+                    output_dir.display()
+                );
+            }
+            return Ok((duration_ms, None));
+        }
 
-    let stop_result = tokio::task::spawn_blocking(move || -> Result<(), String> {
-        match rec.capture_thread.join() {
-            Ok(Ok(())) => {}
-            Ok(Err(e)) => log::warn!("Capture thread finished with error: {e}"),
-            Err(_) => log::error!("Capture thread panicked"),
+        let muxer_segments = muxer_timeline.segments();
+        let (mut width, mut height, mut video_size) = (width, height, video_size);
+        let mut canvas_tiles = monitor_tiles.clone();
+        let video_path = "raw.mp4".to_string();
+        let mut monitor_composite_error = None;
+        if !monitor_tiles.is_empty() {
+            if let Err(err) = multi_monitor::composite_monitor_recordings(
+                &monitor_tiles,
+                &output_dir.join(&video_path),
+                video_size,
+                target_fps,
+                keyframe_interval_frames,
+            ) {
+                // Keep the events and the project: fall back to the primary monitor and
+                // leave the other monitor recordings next to it.
+                log::error!("stop_recording: monitor composite failed: {err}");
+                let primary = MonitorTile {
+                    canvas_x: 0,
+                    ..monitor_tiles[0].clone()
+                };
+                // Audio muxing, trimming and the proxy all work on `raw.mp4`, so the primary
+                // recording has to end up there.
+                move_or_copy_file(&primary.raw_path, &output_dir.join(&video_path)).map_err(
+                    |move_err| {
+                        let _ = stop_audio_capture_session(&mut audio_capture_session);
+                        format!(
+                            "Monitor recordings could not be combined ({err}) and {} could not be moved to raw.mp4 ({move_err}); the monitor recordings were kept in {}",
+                            primary.raw_path.display(),
+                            output_dir.display()
+                        )
+                    },
+                )?;
+                (width, height) = (primary.width, primary.height);
+                video_size = (primary.width, primary.height);
+                canvas_tiles = vec![primary];
+                monitor_composite_error = Some(err);
+            }
         }
 
         let telemetry_events = rec.telemetry_processor.join().unwrap_or_default();
-        let telemetry_events =
+        let mut telemetry_events =
             normalize_events_for_pauses(telemetry_events, start_ms, &pause_ranges_ms);
+        multi_monitor::translate_events_to_canvas(&mut telemetry_events, &canvas_tiles);
         log::info!(
             "stop_recording: collected {} telemetry events",
             telemetry_events.len()
         );
 
-        let duration_ms = end_ms
-            .saturating_sub(start_ms)
-            .saturating_sub(paused_total_ms);
-
         save_recording_files(
             &output_dir,
             &recording_id,
             width,
             height,
+            video_size,
             scale_factor,
             start_ms,
             duration_ms,
@@ -324,7 +1189,11 @@ pub async fn stop_recording(
             pause_ranges_ms.clone(),
             cursor_hidden_ranges_abs_ms.clone(),
             audio_capture_session.take(),
+            &muxer_segments,
+            target_fps,
             telemetry_events,
+            video_path,
+            monitor_composite_error.clone(),
         )?;
 
         log::info!(
@@ -333,25 +1202,101 @@ pub async fn stop_recording(
             output_dir.display()
         );
 
-        Ok(())
+        // A successful composite was already encoded with the requested key-frame distance.
+        let composited = !monitor_tiles.is_empty() && monitor_composite_error.is_none();
+        if let Some(frames) = keyframe_interval_frames.filter(|_| !composited) {
+            if let Err(err) = reencode_with_keyframe_interval(&output_dir, frames) {
+                log::warn!("stop_recording: key-frame interval not applied: {err}");
+            }
+        }
+
+        Ok((duration_ms, monitor_composite_error))
     })
     .await
     .map_err(|e| format!("Task join error: {e}"))?;
 
-    if let Err(err) = set_window_excluded_from_capture(&window, false) {
-        log::warn!("stop_recording: failed to restore window capture visibility: {err}");
+    window
+        .state::<CaptureExclusionState>()
+        .release(window.app_handle(), CaptureExclusionHolder::Recording);
+    if window_hidden_for_capture {
+        restore_hidden_window(&window);
     }
 
-    stop_result?;
-    Ok(())
-}
-
-#[tauri::command]
-pub async fn pause_recording(
-    state: tauri::State<'_, RecorderState>,
-    telemetry: tauri::State<'_, TelemetryState>,
-    recording_id: String,
-) -> Result<(), String> {
+    let (duration_ms, monitor_composite_error) = stop_result?;
+    if discard {
+        emit_recording_state(&window, "recording-discarded", &recording_id);
+        return Err(format!(
+            "Recording was too short ({duration_ms} ms, at least {MIN_RECORDING_DURATION_MS} ms needed) and was discarded"
+        ));
+    }
+    emit_recording_state(&window, "recording-stopped", &recording_id);
+
+    // Runs only after audio muxing has finished above; the export reads `raw.mp4`.
+    let auto_export_state = auto_export.then(|| window.state::<ExportState>().0.clone());
+    if generate_proxy {
+        // The quick export waits for the proxy so only one FFmpeg encode runs at a time.
+        let proxy_state = window.state::<ProxyState>();
+        if let Err(err) = spawn_proxy_job(
+            proxy_state.0.clone(),
+            recording_id.clone(),
+            project_file.clone(),
+            duration_ms,
+            auto_export_state.clone(),
+        ) {
+            log::warn!("stop_recording: failed to start proxy build: {err}");
+            if let Some(export_state) = auto_export_state {
+                start_auto_export(export_state, &project_file);
+            }
+        }
+    } else {
+        log::info!("stop_recording: proxy generation disabled, editor will use raw.mp4");
+        if let Some(export_state) = auto_export_state {
+            start_auto_export(export_state, &project_file);
+        }
+    }
+
+    if let Some(err) = monitor_composite_error {
+        // The recording itself was saved; the project carries the error for the editor.
+        let payload = RecordingErrorPayload {
+            recording_id: recording_id.clone(),
+            error: format!(
+                "Monitor recordings could not be combined ({err}); the primary monitor was saved and the other monitor recordings were kept in the project folder"
+            ),
+            finalized: true,
+        };
+        if let Err(err) = window.emit("recording-error", payload) {
+            log::warn!("stop_recording: failed to emit monitor composite error: {err}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Renames `from` to `to`, copying it when a rename is not possible (e.g. across volumes
+/// or while another process holds the file open without delete sharing).
+fn move_or_copy_file(from: &Path, to: &Path) -> Result<(), String> {
+    let Err(rename_err) = std::fs::rename(from, to) else {
+        return Ok(());
+    };
+    log::warn!(
+        "stop_recording: failed to rename {} to {}: {rename_err}, copying instead",
+        from.display(),
+        to.display()
+    );
+    std::fs::copy(from, to).map_err(|e| e.to_string())?;
+    if let Err(err) = std::fs::remove_file(from) {
+        log::warn!("stop_recording: failed to remove {}: {err}", from.display());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn pause_recording(
+    state: tauri::State<'_, RecorderState>,
+    telemetry: tauri::State<'_, TelemetryState>,
+    window: tauri::WebviewWindow,
+    recording_id: String,
+) -> Result<(), String> {
     let mut guard = state.0.lock().await;
     let rec = guard.as_mut().ok_or("No active recording")?;
     if rec.recording_id != recording_id {
@@ -368,6 +1313,7 @@ pub async fn pause_recording(
     rec.pause_started_at_ms = Some(now_ms);
     rec.pause_flag.store(true, Ordering::Relaxed);
     logger::set_paused(&telemetry.0, true);
+    emit_recording_state(&window, "recording-paused", &recording_id);
     Ok(())
 }
 
@@ -375,6 +1321,7 @@ pub async fn pause_recording(
 pub async fn resume_recording(
     state: tauri::State<'_, RecorderState>,
     telemetry: tauri::State<'_, TelemetryState>,
+    window: tauri::WebviewWindow,
     recording_id: String,
 ) -> Result<(), String> {
     let mut guard = state.0.lock().await;
@@ -395,6 +1342,7 @@ pub async fn resume_recording(
     }
     rec.pause_flag.store(false, Ordering::Relaxed);
     logger::set_paused(&telemetry.0, false);
+    emit_recording_state(&window, "recording-resumed", &recording_id);
     Ok(())
 }
 
@@ -451,10 +1399,12 @@ fn sanitize_recording_fps(raw_fps: u32) -> u32 {
     }
 }
 
-fn camera_config_for_trigger_mode(
+pub(crate) fn camera_config_for_trigger_mode(
     auto_zoom_trigger_mode: AutoZoomTriggerMode,
+    camera_settings: &CameraSettings,
 ) -> camera_engine::SmartCameraConfig {
     let mut config = camera_engine::SmartCameraConfig::default();
+    apply_camera_sensitivity(&mut config, camera_settings);
     config.click_activation_mode = match auto_zoom_trigger_mode {
         AutoZoomTriggerMode::SingleClick => camera_engine::ClickActivationMode::SingleClick,
         AutoZoomTriggerMode::MultiClickWindow => {
@@ -478,10 +1428,90 @@ fn camera_config_for_trigger_mode(
             config.min_zoom_interval_ms = 2_000;
         }
     }
+    apply_click_activation_overrides(&mut config, camera_settings);
+    apply_zoom_framing(&mut config, camera_settings);
+    config.caret_follow = camera_settings.caret_follow;
 
     config
 }
 
+/// Applies the project's zoom tightness (padding around clicked elements and the zoom cap)
+/// and how strongly the camera follows scrolling.
+fn apply_zoom_framing(
+    config: &mut camera_engine::SmartCameraConfig,
+    camera_settings: &CameraSettings,
+) {
+    let finite = |value: Option<f64>| value.filter(|value| value.is_finite());
+    if let Some(padding) = finite(camera_settings.zoom_padding_ratio) {
+        let (min, max) = ZOOM_PADDING_RATIO_RANGE;
+        config.semantic_padding_ratio = padding.clamp(min, max);
+    }
+    if let Some(max_zoom) = finite(camera_settings.max_zoom) {
+        let (min, max) = MAX_ZOOM_RANGE;
+        config.max_zoom_limit = max_zoom.clamp(min, max);
+        // Clicks without element bounds zoom to the fallback, which must respect the cap.
+        config.fallback_zoom = config.fallback_zoom.min(config.max_zoom_limit);
+    }
+    // Scales how far a locked camera pans per scroll step; zero keeps it still.
+    if let Some(strength) = finite(camera_settings.scroll_follow_strength) {
+        let (min, max) = SCROLL_FOLLOW_STRENGTH_RANGE;
+        config.scroll_shift_ratio *= strength.clamp(min, max);
+    }
+}
+
+/// Applies per-project click activation/clustering overrides on top of the trigger mode preset.
+fn apply_click_activation_overrides(
+    config: &mut camera_engine::SmartCameraConfig,
+    camera_settings: &CameraSettings,
+) {
+    let clamp = |value: u64, (min, max): (u64, u64)| value.clamp(min, max);
+    if let Some(value) = camera_settings.activation_window_ms {
+        config.activation_window_ms = clamp(value, ACTIVATION_WINDOW_MS_RANGE);
+    }
+    if let Some(value) = camera_settings.min_clicks_to_activate {
+        config.min_clicks_to_activate = clamp(value, MIN_CLICKS_TO_ACTIVATE_RANGE) as usize;
+    }
+    if let Some(value) = camera_settings.click_cluster_gap_ms {
+        config.click_cluster_gap_ms = clamp(value, CLICK_CLUSTER_GAP_MS_RANGE);
+    }
+    if let Some(value) = camera_settings.min_zoom_interval_ms {
+        config.min_zoom_interval_ms = clamp(value, MIN_ZOOM_INTERVAL_MS_RANGE);
+    }
+    if let Some(value) = camera_settings.zoom_lead_ms {
+        config.zoom_lead_ms = clamp(value, ZOOM_LEAD_MS_RANGE);
+    }
+}
+
+/// Scales the dead zone, escape distance and hard edge by the sensitivity slider,
+/// then applies explicit overrides; every value is clamped to its allowed range.
+fn apply_camera_sensitivity(
+    config: &mut camera_engine::SmartCameraConfig,
+    camera_settings: &CameraSettings,
+) {
+    let scale = camera_settings.ratio_scale();
+    let resolve = |base: f64, value: Option<f64>, (min, max): (f64, f64)| {
+        value
+            .filter(|value| value.is_finite())
+            .unwrap_or(base * scale)
+            .clamp(min, max)
+    };
+    config.dead_zone_ratio = resolve(
+        config.dead_zone_ratio,
+        camera_settings.dead_zone_ratio,
+        DEAD_ZONE_RATIO_RANGE,
+    );
+    config.escape_distance_ratio = resolve(
+        config.escape_distance_ratio,
+        camera_settings.escape_distance_ratio,
+        ESCAPE_DISTANCE_RATIO_RANGE,
+    );
+    config.hard_edge_ratio = resolve(
+        config.hard_edge_ratio,
+        camera_settings.hard_edge_ratio,
+        HARD_EDGE_RATIO_RANGE,
+    );
+}
+
 fn total_pause_duration_ms(pause_ranges_ms: &[(u64, u64)]) -> u64 {
     pause_ranges_ms
         .iter()
@@ -553,6 +1583,103 @@ fn normalize_events_for_pauses(
     normalized_events
 }
 
+/// Keeps events inside `[start_ms, end_ms]` and re-bases them to `start_ms`.
+pub(crate) fn trim_events_to_range(
+    events: Vec<InputEvent>,
+    start_ms: u64,
+    end_ms: u64,
+) -> Vec<InputEvent> {
+    let mut events = events;
+    events.sort_by_key(InputEvent::ts);
+
+    events
+        .into_iter()
+        .filter(|event| (start_ms..=end_ms).contains(&event.ts()))
+        .map(|mut event| {
+            let ts = event.ts() - start_ms;
+            set_event_ts(&mut event, ts);
+            event
+        })
+        .collect()
+}
+
+/// Writes `raw.mp4` cut down to `[start_ms, end_ms)` into a temporary file next to it and
+/// returns its path; the caller swaps it in once everything else is ready.
+///
+/// A cut from the very beginning is stream-copied; any other start needs a re-encode to
+/// land on the exact frame instead of the previous keyframe.
+pub(crate) fn trim_raw_video(
+    output_dir: &Path,
+    start_ms: u64,
+    end_ms: u64,
+) -> Result<PathBuf, String> {
+    let raw_video_path = output_dir.join("raw.mp4");
+    if !raw_video_path.exists() {
+        return Err(format!(
+            "Source video not found: {}",
+            raw_video_path.display()
+        ));
+    }
+
+    let temp_path = output_dir.join("raw-trimmed.tmp.mp4");
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-loglevel")
+        .arg("error");
+    if start_ms > 0 {
+        command
+            .arg("-ss")
+            .arg(format_seconds(start_ms as f64 / 1000.0));
+    }
+    command
+        .arg("-i")
+        .arg(&raw_video_path)
+        .arg("-t")
+        .arg(format_seconds(
+            end_ms.saturating_sub(start_ms) as f64 / 1000.0,
+        ))
+        .arg("-map")
+        .arg("0:v:0")
+        .arg("-map")
+        .arg("0:a?");
+    if start_ms == 0 {
+        command.arg("-c").arg("copy");
+    } else {
+        command
+            .arg("-c:v")
+            .arg("libx264")
+            .arg("-preset")
+            .arg("veryfast")
+            .arg("-crf")
+            .arg("16")
+            .arg("-pix_fmt")
+            .arg("yuv420p")
+            .arg("-c:a")
+            .arg("aac")
+            .arg("-b:a")
+            .arg("192k");
+    }
+
+    let output = command
+        .arg("-movflags")
+        .arg("+faststart")
+        .arg(&temp_path)
+        .output()
+        .map_err(|e| format!("Failed to run ffmpeg ({}) for trim: {e}", ffmpeg.display()))?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&temp_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg trim failed: {}", stderr.trim()));
+    }
+
+    Ok(temp_path)
+}
+
 fn set_event_ts(event: &mut InputEvent, ts: u64) {
     match event {
         InputEvent::Move { ts: event_ts, .. }
@@ -699,15 +1826,23 @@ fn filter_events_outside_ranges(
         .collect()
 }
 
-fn sample_segment_rect_at_ts(segment: &ZoomSegment, ts: u64) -> NormalizedRect {
-    let mut rect = segment.initial_rect.clone();
+/// Target point in effect at `ts` (the segment holds each point until the next), moved to `ts`.
+fn sample_segment_point_at_ts(segment: &ZoomSegment, ts: u64) -> TargetPoint {
+    let mut sampled = TargetPoint {
+        ts,
+        rect: segment.initial_rect.clone(),
+        zoom: None,
+    };
     for point in &segment.target_points {
         if point.ts > ts {
             break;
         }
-        rect = point.rect.clone();
+        sampled = TargetPoint {
+            ts,
+            ..point.clone()
+        };
     }
-    rect
+    sampled
 }
 
 fn crop_segment_target_points(
@@ -716,10 +1851,7 @@ fn crop_segment_target_points(
     end_ts: u64,
 ) -> Vec<TargetPoint> {
     let mut points = Vec::new();
-    points.push(TargetPoint {
-        ts: start_ts,
-        rect: sample_segment_rect_at_ts(segment, start_ts),
-    });
+    points.push(sample_segment_point_at_ts(segment, start_ts));
 
     for point in &segment.target_points {
         if point.ts <= start_ts || point.ts >= end_ts {
@@ -729,10 +1861,7 @@ fn crop_segment_target_points(
     }
 
     if end_ts > start_ts {
-        points.push(TargetPoint {
-            ts: end_ts,
-            rect: sample_segment_rect_at_ts(segment, end_ts),
-        });
+        points.push(sample_segment_point_at_ts(segment, end_ts));
     }
 
     points.sort_by_key(|point| point.ts);
@@ -802,7 +1931,7 @@ fn suppress_zoom_segments_in_ranges(
             fragment.id = format!("{}-{}", fragment.id, index + 1);
             fragment.start_ts = start_ts;
             fragment.end_ts = end_ts;
-            fragment.initial_rect = sample_segment_rect_at_ts(&segment, start_ts);
+            fragment.initial_rect = sample_segment_point_at_ts(&segment, start_ts).rect;
             fragment.target_points = crop_segment_target_points(&segment, start_ts, end_ts);
             result.push(fragment);
         }
@@ -902,7 +2031,20 @@ fn list_microphone_input_devices() -> Result<Vec<String>, String> {
     Ok(microphones)
 }
 
-fn resolve_system_audio_device(all_devices: &[String]) -> Option<String> {
+fn resolve_system_audio_device(
+    all_devices: &[String],
+    requested: Option<&str>,
+) -> Result<String, String> {
+    if let Some(requested_name) = requested {
+        return all_devices
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(requested_name))
+            .cloned()
+            .ok_or_else(|| {
+                format!("System audio device '{requested_name}' not found via ffmpeg dshow")
+            });
+    }
+
     let priority = [
         "virtual-audio-capturer",
         "stereo mix",
@@ -917,10 +2059,10 @@ fn resolve_system_audio_device(all_devices: &[String]) -> Option<String> {
             .iter()
             .find(|name| name.to_lowercase().contains(token))
         {
-            return Some(device.clone());
+            return Ok(device.clone());
         }
     }
-    None
+    Err("no dshow loopback device was found".to_string())
 }
 
 fn resolve_microphone_device(
@@ -957,11 +2099,13 @@ fn resolve_microphone_device(
 fn spawn_audio_capture_process(
     device_name: &str,
     output_path: &Path,
+    format: AudioCaptureFormat,
 ) -> Result<AudioCaptureProcess, String> {
     let ffmpeg = find_ffmpeg_exe();
     let mut command = Command::new(&ffmpeg);
     apply_no_window_flags(&mut command);
 
+    let started_at_ms = chrono::Utc::now().timestamp_millis() as u64;
     let mut child = command
         .arg("-y")
         .arg("-hide_banner")
@@ -971,10 +2115,7 @@ fn spawn_audio_capture_process(
         .arg("dshow")
         .arg("-i")
         .arg(format!("audio={device_name}"))
-        .arg("-ac")
-        .arg("2")
-        .arg("-ar")
-        .arg("48000")
+        .args(audio_format_args(format))
         .arg("-c:a")
         .arg("pcm_s16le")
         .arg(output_path)
@@ -1000,8 +2141,68 @@ fn spawn_audio_capture_process(
     }
 
     Ok(AudioCaptureProcess {
-        backend: AudioCaptureBackend::FfmpegChild(child),
+        backend: AudioCaptureBackend::FfmpegChild(Arc::new(std::sync::Mutex::new(child))),
         output_path: output_path.to_path_buf(),
+        started_at_ms,
+        ended_early_at_ms: Arc::new(AtomicU64::new(0)),
+    })
+}
+
+/// How often the audio watcher polls the ffmpeg capture children.
+const AUDIO_WATCHER_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Starts a thread that records when an ffmpeg audio capture child exits on its own
+/// (device unplugged, driver reset). Native WASAPI loopback is not watched.
+fn spawn_audio_track_watcher(session: &AudioCaptureSession) -> Option<AudioTrackWatcher> {
+    let watched = [
+        ("system", session.system_capture.as_ref()),
+        ("microphone", session.microphone_capture.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(label, process)| {
+        let process = process?;
+        match &process.backend {
+            AudioCaptureBackend::FfmpegChild(child) => {
+                Some((label, child.clone(), process.ended_early_at_ms.clone()))
+            }
+            AudioCaptureBackend::NativeLoopback { .. } => None,
+        }
+    })
+    .collect::<Vec<_>>();
+    if watched.is_empty() {
+        return None;
+    }
+
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let thread_stop_flag = stop_flag.clone();
+    let join_handle = std::thread::Builder::new()
+        .name("nsc-audio-watch".to_string())
+        .spawn(move || {
+            while !thread_stop_flag.load(Ordering::Relaxed) {
+                for (label, child, ended_early_at_ms) in &watched {
+                    if ended_early_at_ms.load(Ordering::Relaxed) != 0 {
+                        continue;
+                    }
+                    let Ok(mut child) = child.lock() else {
+                        continue;
+                    };
+                    if let Ok(Some(status)) = child.try_wait() {
+                        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+                        ended_early_at_ms.store(now_ms.max(1), Ordering::Relaxed);
+                        log::warn!(
+                            "audio watcher: {label} capture exited during recording with status {status}"
+                        );
+                    }
+                }
+                std::thread::sleep(AUDIO_WATCHER_POLL_INTERVAL);
+            }
+        })
+        .map_err(|e| log::warn!("Failed to spawn audio watcher thread: {e}"))
+        .ok()?;
+
+    Some(AudioTrackWatcher {
+        stop_flag,
+        join_handle,
     })
 }
 
@@ -1009,6 +2210,8 @@ fn start_audio_capture_session(
     output_dir: &Path,
     mode: RecordingAudioMode,
     requested_microphone: Option<&str>,
+    requested_system_device: Option<&str>,
+    format: AudioCaptureFormat,
 ) -> Result<Option<AudioCaptureSession>, String> {
     if mode == RecordingAudioMode::NoAudio {
         return Ok(None);
@@ -1040,11 +2243,17 @@ fn start_audio_capture_session(
     let mut session = AudioCaptureSession {
         system_capture: None,
         microphone_capture: None,
+        format,
+        watcher: None,
     };
 
     if wants_system {
         let system_path = output_dir.join("audio-system.wav");
-        match start_system_loopback_capture(system_path.clone()) {
+        let started_at_ms = chrono::Utc::now().timestamp_millis() as u64;
+        match start_system_loopback_capture(
+            system_path.clone(),
+            requested_system_device.map(str::to_string),
+        ) {
             Ok(native_loopback) => {
                 session.system_capture = Some(AudioCaptureProcess {
                     backend: AudioCaptureBackend::NativeLoopback {
@@ -1052,19 +2261,25 @@ fn start_audio_capture_session(
                         join_handle: native_loopback.join_handle,
                     },
                     output_path: system_path,
+                    started_at_ms,
+                    ended_early_at_ms: Arc::new(AtomicU64::new(0)),
                 });
             }
             Err(native_err) => {
                 log::warn!(
                     "start_audio_capture_session: WASAPI loopback unavailable, falling back to dshow loopback: {native_err}"
                 );
-                let system_device = resolve_system_audio_device(&all_devices).ok_or_else(|| {
-                    format!(
-                        "System audio capture failed via WASAPI ({native_err}) and no dshow loopback device was found."
-                    )
-                })?;
+                let system_device =
+                    resolve_system_audio_device(&all_devices, requested_system_device).map_err(
+                        |dshow_err| {
+                            format!(
+                                "System audio capture failed via WASAPI ({}) and {}.",
+                                native_err, dshow_err
+                            )
+                        },
+                    )?;
                 session.system_capture = Some(
-                    spawn_audio_capture_process(&system_device, &system_path).map_err(|ffmpeg_err| {
+                    spawn_audio_capture_process(&system_device, &system_path, format).map_err(|ffmpeg_err| {
                         format!(
                             "System audio capture failed via WASAPI ({native_err}) and dshow fallback '{system_device}' failed: {ffmpeg_err}"
                         )
@@ -1077,7 +2292,7 @@ fn start_audio_capture_session(
     if wants_microphone {
         let microphone_device = resolve_microphone_device(&all_devices, requested_microphone)?;
         let microphone_path = output_dir.join("audio-microphone.wav");
-        match spawn_audio_capture_process(&microphone_device, &microphone_path) {
+        match spawn_audio_capture_process(&microphone_device, &microphone_path, format) {
             Ok(process) => {
                 session.microphone_capture = Some(process);
             }
@@ -1089,6 +2304,7 @@ fn start_audio_capture_session(
         }
     }
 
+    session.watcher = spawn_audio_track_watcher(&session);
     Ok(Some(session))
 }
 
@@ -1123,12 +2339,14 @@ fn stop_audio_capture_process(process: AudioCaptureProcess) -> PathBuf {
     let AudioCaptureProcess {
         backend,
         output_path,
+        ..
     } = process;
 
     match backend {
-        AudioCaptureBackend::FfmpegChild(mut child) => {
-            stop_ffmpeg_child(&mut child);
-        }
+        AudioCaptureBackend::FfmpegChild(child) => match child.lock() {
+            Ok(mut child) => stop_ffmpeg_child(&mut child),
+            Err(_) => log::warn!("Audio capture process lock poisoned; leaving it to exit"),
+        },
         AudioCaptureBackend::NativeLoopback {
             stop_flag,
             join_handle,
@@ -1155,6 +2373,13 @@ fn stop_audio_capture_session(
     let Some(mut captured) = session.take() else {
         return (None, None);
     };
+    // Stop the watcher first so the requested shutdown is not reported as an early exit.
+    if let Some(watcher) = captured.watcher.take() {
+        watcher.stop_flag.store(true, Ordering::Relaxed);
+        if watcher.join_handle.join().is_err() {
+            log::warn!("Audio watcher thread panicked");
+        }
+    }
 
     let system_path = captured
         .system_capture
@@ -1167,6 +2392,51 @@ fn stop_audio_capture_session(
     (system_path, microphone_path)
 }
 
+/// Lists audio tracks whose capture process died before the recording was stopped,
+/// with the cut-off moment on the pause-free project timeline.
+fn audio_track_issues(
+    session: Option<&AudioCaptureSession>,
+    start_ms: u64,
+    duration_ms: u64,
+    pause_ranges_ms: &[(u64, u64)],
+) -> Vec<AudioTrackIssue> {
+    let Some(session) = session else {
+        return Vec::new();
+    };
+    [
+        (AudioTrackKind::System, session.system_capture.as_ref()),
+        (
+            AudioTrackKind::Microphone,
+            session.microphone_capture.as_ref(),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(track, process)| {
+        let ended_abs_ms = process?.ended_early_at_ms.load(Ordering::Relaxed);
+        (ended_abs_ms != 0).then(|| AudioTrackIssue {
+            track,
+            ended_at_ms: timeline_ms_for_abs_ts(ended_abs_ms, start_ms, pause_ranges_ms)
+                .min(duration_ms),
+        })
+    })
+    .collect()
+}
+
+/// Maps an absolute Unix ms timestamp onto the recording timeline with pauses cut out.
+fn timeline_ms_for_abs_ts(
+    ts_abs_ms: u64,
+    start_ms: u64,
+    pause_ranges_abs_ms: &[(u64, u64)],
+) -> u64 {
+    let paused_before_ms = pause_ranges_abs_ms
+        .iter()
+        .map(|(start, end)| (*end).min(ts_abs_ms).saturating_sub(*start))
+        .sum::<u64>();
+    ts_abs_ms
+        .saturating_sub(start_ms)
+        .saturating_sub(paused_before_ms)
+}
+
 fn keep_ranges_after_pauses(
     start_ms: u64,
     end_ms: u64,
@@ -1217,39 +2487,178 @@ fn keep_ranges_after_pauses(
     keep
 }
 
-fn format_seconds(ms: u64) -> String {
-    format!("{:.3}", ms as f64 / 1000.0)
+/// Audio ranges (seconds from recording start) that match the video frame for frame.
+///
+/// Each muxer run lasts exactly `frames / fps` in the video, so cutting the audio to the runs
+/// instead of the wall-clock pause ranges keeps per-pause rounding from accumulating into drift.
+fn keep_ranges_from_muxer_timeline(
+    start_ms: u64,
+    end_ms: u64,
+    segments: &[MuxerSegment],
+    target_fps: u32,
+) -> Vec<(f64, f64)> {
+    let total_s = end_ms.saturating_sub(start_ms) as f64 / 1000.0;
+    let fps = target_fps.max(1) as f64;
+
+    let mut keep: Vec<(f64, f64)> = Vec::with_capacity(segments.len());
+    for segment in segments {
+        let offset_s = (segment.start_ms as f64 - start_ms as f64) / 1000.0;
+        let previous_end = keep.last().map(|(_, end)| *end).unwrap_or(0.0);
+        let start = offset_s.max(previous_end);
+        let end = (start + segment.frames as f64 / fps).min(total_s);
+        if end > start {
+            keep.push((start, end));
+        }
+    }
+    keep
+}
+
+fn ms_ranges_to_seconds(ranges_ms: &[(u64, u64)]) -> Vec<(f64, f64)> {
+    ranges_ms
+        .iter()
+        .map(|(start, end)| (*start as f64 / 1000.0, *end as f64 / 1000.0))
+        .collect()
+}
+
+/// Maximum difference between kept audio and encoded video before a drift warning is logged.
+const AUDIO_VIDEO_DURATION_TOLERANCE_S: f64 = 0.05;
+
+fn warn_on_audio_video_duration_mismatch(
+    keep_ranges_s: &[(f64, f64)],
+    segments: &[MuxerSegment],
+    target_fps: u32,
+) {
+    let audio_s = keep_ranges_s
+        .iter()
+        .map(|(start, end)| end - start)
+        .sum::<f64>();
+    let video_frames = segments.iter().map(|segment| segment.frames).sum::<u64>();
+    let video_s = video_frames as f64 / target_fps.max(1) as f64;
+    if (audio_s - video_s).abs() > AUDIO_VIDEO_DURATION_TOLERANCE_S {
+        log::warn!(
+            "finalize_recording_audio: kept audio {:.3}s differs from video {:.3}s ({} frames at {} fps)",
+            audio_s,
+            video_s,
+            video_frames,
+            target_fps
+        );
+    }
+}
+
+fn format_seconds(seconds: f64) -> String {
+    format!("{:.6}", seconds.max(0.0))
+}
+
+/// FFmpeg output options that force the captured channel count and sample rate.
+fn audio_format_args(format: AudioCaptureFormat) -> [String; 4] {
+    [
+        "-ac".to_string(),
+        format.channels.to_string(),
+        "-ar".to_string(),
+        format.sample_rate.to_string(),
+    ]
+}
+
+/// Filter that resamples a stream and pins its sample format, rate and channel layout.
+fn audio_normalize_filter(format: AudioCaptureFormat) -> String {
+    format!(
+        "aresample={rate},aformat=sample_fmts=s16:sample_rates={rate}:channel_layouts={layout}",
+        rate = format.sample_rate,
+        layout = format.channel_layout()
+    )
+}
+
+/// Builds the `amix` graph for the microphone (input 0) and system (input 1) stems.
+///
+/// Both inputs are normalized first: `amix` negotiates a single format, so stems recorded
+/// at different rates or channel counts would otherwise be converted implicitly or fail.
+fn build_normalized_mix_filter(format: AudioCaptureFormat) -> String {
+    let normalize = audio_normalize_filter(format);
+    format!(
+        "[0:a]{normalize}[mic];[1:a]{normalize}[sys];\
+         [mic][sys]amix=inputs=2:normalize=0:dropout_transition=0,{normalize}[aout]"
+    )
+}
+
+/// Re-encodes a track to the capture format (the WASAPI loopback is written in the
+/// device mix format, which usually differs from the dshow tracks).
+fn conform_audio_track(
+    input_path: &Path,
+    output_path: &Path,
+    format: AudioCaptureFormat,
+) -> Result<(), String> {
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+
+    let status = command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-i")
+        .arg(input_path)
+        .args(audio_format_args(format))
+        .arg("-c:a")
+        .arg("pcm_s16le")
+        .arg(output_path)
+        .status()
+        .map_err(|e| {
+            format!(
+                "Failed to run ffmpeg ({}) for audio resampling: {e}",
+                ffmpeg.display()
+            )
+        })?;
+
+    if !status.success() {
+        return Err("FFmpeg audio resampling failed".to_string());
+    }
+
+    Ok(())
 }
 
 fn trim_audio_track_to_active_ranges(
     input_path: &Path,
     output_path: &Path,
-    keep_ranges_ms: &[(u64, u64)],
+    keep_ranges_s: &[(f64, f64)],
+    lead_ms: i64,
+    format: AudioCaptureFormat,
 ) -> Result<(), String> {
-    if keep_ranges_ms.is_empty() {
+    if keep_ranges_s.is_empty() {
         return Err("No active (non-paused) ranges available for audio trimming".to_string());
     }
 
+    // A track that started before the video is cut by the lead; one that started late is
+    // padded with silence so both share the video's time zero.
+    let shift_s = lead_ms.max(0) as f64 / 1000.0;
+    let delay = if lead_ms < 0 {
+        format!("adelay={}:all=1,", -lead_ms)
+    } else {
+        String::new()
+    };
     let mut chain = Vec::new();
-    for (index, (start_ms, end_ms)) in keep_ranges_ms.iter().enumerate() {
+    for (index, (start_s, end_s)) in keep_ranges_s.iter().enumerate() {
         chain.push(format!(
-            "[0:a]atrim=start={}:end={},asetpts=PTS-STARTPTS[a{}]",
-            format_seconds(*start_ms),
-            format_seconds(*end_ms),
+            "[0:a]{}atrim=start={}:end={},asetpts=PTS-STARTPTS[a{}]",
+            delay,
+            format_seconds(start_s + shift_s),
+            format_seconds(end_s + shift_s),
             index
         ));
     }
 
-    if keep_ranges_ms.len() == 1 {
-        chain.push("[a0]anull[aout]".to_string());
+    let normalize = audio_normalize_filter(format);
+    if keep_ranges_s.len() == 1 {
+        chain.push(format!("[a0]{normalize}[aout]"));
     } else {
-        let labels = (0..keep_ranges_ms.len())
+        let labels = (0..keep_ranges_s.len())
             .map(|idx| format!("[a{}]", idx))
             .collect::<String>();
         chain.push(format!(
-            "{}concat=n={}:v=0:a=1[aout]",
+            "{}concat=n={}:v=0:a=1,{}[aout]",
             labels,
-            keep_ranges_ms.len()
+            keep_ranges_s.len(),
+            normalize
         ));
     }
 
@@ -1269,6 +2678,7 @@ fn trim_audio_track_to_active_ranges(
         .arg(filter)
         .arg("-map")
         .arg("[aout]")
+        .args(audio_format_args(format))
         .arg("-c:a")
         .arg("pcm_s16le")
         .arg(output_path)
@@ -1291,6 +2701,7 @@ fn mix_audio_tracks(
     microphone_path: &Path,
     system_path: &Path,
     output_path: &Path,
+    format: AudioCaptureFormat,
 ) -> Result<(), String> {
     let ffmpeg = find_ffmpeg_exe();
     let mut command = Command::new(&ffmpeg);
@@ -1306,9 +2717,10 @@ fn mix_audio_tracks(
         .arg("-i")
         .arg(system_path)
         .arg("-filter_complex")
-        .arg("[0:a][1:a]amix=inputs=2:normalize=0:dropout_transition=0[aout]")
+        .arg(build_normalized_mix_filter(format))
         .arg("-map")
         .arg("[aout]")
+        .args(audio_format_args(format))
         .arg("-c:a")
         .arg("pcm_s16le")
         .arg(output_path)
@@ -1327,6 +2739,10 @@ fn mix_audio_tracks(
     Ok(())
 }
 
+/// Audio-free original of `raw.mp4`, kept after the audio mux when
+/// `AppSettings::keep_video_only_backup` is set.
+pub(crate) const VIDEO_ONLY_BACKUP_FILE_NAME: &str = "raw-video-only.mp4";
+
 fn mux_audio_into_raw_video(output_dir: &Path, audio_path: &Path) -> Result<(), String> {
     let raw_video_path = output_dir.join("raw.mp4");
     if !raw_video_path.exists() {
@@ -1375,7 +2791,7 @@ fn mux_audio_into_raw_video(output_dir: &Path, audio_path: &Path) -> Result<(),
         return Err("FFmpeg mux (video+audio) failed".to_string());
     }
 
-    let backup_path = output_dir.join("raw-video-only.mp4");
+    let backup_path = output_dir.join(VIDEO_ONLY_BACKUP_FILE_NAME);
     let _ = std::fs::remove_file(&backup_path);
     std::fs::rename(&raw_video_path, &backup_path)
         .map_err(|e| format!("Failed to backup raw.mp4 before mux replacement: {e}"))?;
@@ -1384,9 +2800,100 @@ fn mux_audio_into_raw_video(output_dir: &Path, audio_path: &Path) -> Result<(),
         format!("Failed to replace raw.mp4 with muxed file: {e}")
     })?;
 
+    if !app_settings::load_app_settings().keep_video_only_backup {
+        if let Err(err) = std::fs::remove_file(&backup_path) {
+            log::warn!(
+                "Failed to remove {} after mux: {err}",
+                backup_path.display()
+            );
+        }
+    }
+
     Ok(())
 }
 
+/// Key-frame distance in frames for `interval_ms` at `target_fps`.
+fn keyframe_interval_frames(interval_ms: u64, target_fps: u32) -> u32 {
+    ((interval_ms as f64 * target_fps.max(1) as f64 / 1000.0).round() as u32).max(1)
+}
+
+/// Re-encodes `raw.mp4` with a key frame every `keyframe_interval_frames` frames so the
+/// editor can seek in it quickly; the audio track is copied as is.
+fn reencode_with_keyframe_interval(
+    output_dir: &Path,
+    keyframe_interval_frames: u32,
+) -> Result<(), String> {
+    let raw_video_path = output_dir.join("raw.mp4");
+    if !raw_video_path.exists() {
+        return Ok(());
+    }
+
+    let reencoded_path = output_dir.join("raw-keyframes.mp4");
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+
+    let frames = keyframe_interval_frames.to_string();
+    let output = command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-i")
+        .arg(&raw_video_path)
+        .arg("-map")
+        .arg("0:v:0")
+        .arg("-map")
+        .arg("0:a?")
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-preset")
+        .arg("veryfast")
+        .arg("-crf")
+        .arg("18")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg("-g")
+        .arg(&frames)
+        .arg("-keyint_min")
+        .arg(&frames)
+        .arg("-c:a")
+        .arg("copy")
+        .arg("-movflags")
+        .arg("+faststart")
+        .arg(&reencoded_path)
+        .output()
+        .map_err(|e| {
+            format!(
+                "Failed to run ffmpeg ({}) for key-frame re-encode: {e}",
+                ffmpeg.display()
+            )
+        })?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&reencoded_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "FFmpeg key-frame re-encode failed: {}",
+            stderr.trim()
+        ));
+    }
+
+    std::fs::rename(&reencoded_path, &raw_video_path).map_err(|e| {
+        let _ = std::fs::remove_file(&reencoded_path);
+        format!("Failed to replace raw.mp4 with the re-encoded file: {e}")
+    })
+}
+
+/// How long before the first video frame (`start_ms`) each audio capture started.
+fn measure_audio_start_offsets(session: &AudioCaptureSession, start_ms: u64) -> AudioStartOffsets {
+    let lead_ms = |process: &AudioCaptureProcess| start_ms as i64 - process.started_at_ms as i64;
+    AudioStartOffsets {
+        system_ms: session.system_capture.as_ref().map(lead_ms),
+        microphone_ms: session.microphone_capture.as_ref().map(lead_ms),
+    }
+}
+
 fn finalize_recording_audio(
     output_dir: &Path,
     audio_capture_session: &mut Option<AudioCaptureSession>,
@@ -1394,19 +2901,55 @@ fn finalize_recording_audio(
     start_ms: u64,
     end_ms: u64,
     pause_ranges_ms: &[(u64, u64)],
+    muxer_segments: &[MuxerSegment],
+    target_fps: u32,
 ) -> Result<(), String> {
     if mode == RecordingAudioMode::NoAudio {
         let _ = stop_audio_capture_session(audio_capture_session);
         return Ok(());
     }
 
+    let format = audio_capture_session
+        .as_ref()
+        .map(|session| session.format)
+        .unwrap_or_default();
+    let system_is_native = audio_capture_session
+        .as_ref()
+        .and_then(|session| session.system_capture.as_ref())
+        .is_some_and(|process| {
+            matches!(process.backend, AudioCaptureBackend::NativeLoopback { .. })
+        });
+    let offsets = audio_capture_session
+        .as_ref()
+        .map(|session| measure_audio_start_offsets(session, start_ms))
+        .unwrap_or_default();
+    let system_lead_ms = offsets.system_ms.unwrap_or(0);
+    let microphone_lead_ms = offsets.microphone_ms.unwrap_or(0);
+    log::info!(
+        "finalize_recording_audio: start offsets system={}ms microphone={}ms",
+        system_lead_ms,
+        microphone_lead_ms
+    );
     let (system_raw, microphone_raw) = stop_audio_capture_session(audio_capture_session);
-    let keep_ranges = keep_ranges_after_pauses(start_ms, end_ms, pause_ranges_ms);
+    let keep_ranges = if muxer_segments.is_empty() {
+        log::warn!(
+            "finalize_recording_audio: muxer timeline unavailable, trimming audio by wall-clock pauses"
+        );
+        ms_ranges_to_seconds(&keep_ranges_after_pauses(start_ms, end_ms, pause_ranges_ms))
+    } else {
+        let ranges = keep_ranges_from_muxer_timeline(start_ms, end_ms, muxer_segments, target_fps);
+        warn_on_audio_video_duration_mismatch(&ranges, muxer_segments, target_fps);
+        ranges
+    };
     if keep_ranges.is_empty() {
         return Ok(());
     }
 
-    let prepare_track = |raw: Option<PathBuf>, label: &str| -> Result<Option<PathBuf>, String> {
+    let prepare_track = |raw: Option<PathBuf>,
+                         label: &str,
+                         lead_ms: i64,
+                         needs_resample: bool|
+     -> Result<Option<PathBuf>, String> {
         let Some(raw_path) = raw else {
             return Ok(None);
         };
@@ -1418,18 +2961,28 @@ fn finalize_recording_audio(
             return Ok(None);
         }
 
-        let total_ms = end_ms.saturating_sub(start_ms);
-        if keep_ranges.len() == 1 && keep_ranges[0].0 == 0 && keep_ranges[0].1 >= total_ms {
-            return Ok(Some(raw_path));
+        let total_s = end_ms.saturating_sub(start_ms) as f64 / 1000.0;
+        if lead_ms == 0
+            && keep_ranges.len() == 1
+            && keep_ranges[0].0 <= 0.0
+            && keep_ranges[0].1 >= total_s
+        {
+            if !needs_resample {
+                return Ok(Some(raw_path));
+            }
+            let conformed_path = output_dir.join(format!("audio-{}-conformed.wav", label));
+            conform_audio_track(&raw_path, &conformed_path, format)?;
+            return Ok(Some(conformed_path));
         }
 
         let trimmed_path = output_dir.join(format!("audio-{}-trimmed.wav", label));
-        trim_audio_track_to_active_ranges(&raw_path, &trimmed_path, &keep_ranges)?;
+        trim_audio_track_to_active_ranges(&raw_path, &trimmed_path, &keep_ranges, lead_ms, format)?;
         Ok(Some(trimmed_path))
     };
 
-    let system_prepared = prepare_track(system_raw, "system")?;
-    let microphone_prepared = prepare_track(microphone_raw, "microphone")?;
+    let system_prepared = prepare_track(system_raw, "system", system_lead_ms, system_is_native)?;
+    let microphone_prepared =
+        prepare_track(microphone_raw, "microphone", microphone_lead_ms, false)?;
 
     let final_audio = match mode {
         RecordingAudioMode::NoAudio => None,
@@ -1438,7 +2991,7 @@ fn finalize_recording_audio(
         RecordingAudioMode::MicrophoneAndSystem => match (microphone_prepared, system_prepared) {
             (Some(microphone), Some(system)) => {
                 let mixed_path = output_dir.join("audio-mixed.wav");
-                mix_audio_tracks(&microphone, &system, &mixed_path)?;
+                mix_audio_tracks(&microphone, &system, &mixed_path, format)?;
                 Some(mixed_path)
             }
             (Some(microphone), None) => Some(microphone),
@@ -1454,21 +3007,209 @@ fn finalize_recording_audio(
     Ok(())
 }
 
-fn set_window_excluded_from_capture(
-    window: &tauri::WebviewWindow,
+fn restore_hidden_window(window: &tauri::WebviewWindow) {
+    if let Err(err) = window.unminimize().and_then(|()| window.show()) {
+        log::warn!("Failed to restore the app window after recording: {err}");
+        return;
+    }
+    let _ = window.set_focus();
+}
+
+/// What the current system supports for recording.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingCapabilities {
+    pub monitor_count: usize,
+    /// The app windows can be excluded from screen capture.
+    pub content_protection_supported: bool,
+    /// Why content protection is unavailable; recordings can then minimize the window.
+    pub content_protection_error: Option<String>,
+}
+
+#[tauri::command]
+pub async fn check_recording_capabilities(
+    exclusion: tauri::State<'_, CaptureExclusionState>,
+    window: tauri::WebviewWindow,
+) -> Result<RecordingCapabilities, String> {
+    let monitor_count = monitor_count()?;
+    let protection = window
+        .set_content_protected(true)
+        .map_err(|e| format!("Failed to set content protection: {e}"))
+        .and_then(|()| verify_excluded_from_capture(&window));
+    if !exclusion.is_active() {
+        let _ = window.set_content_protected(false);
+    }
+    if let Err(err) = &protection {
+        log::warn!("check_recording_capabilities: content protection unavailable: {err}");
+    }
+
+    Ok(RecordingCapabilities {
+        monitor_count,
+        content_protection_supported: protection.is_ok(),
+        content_protection_error: protection.err(),
+    })
+}
+
+/// Confirms the window really is excluded from capture: older Windows builds accept the
+/// request but only black the window out on the monitor (`WDA_MONITOR`).
+#[cfg(target_os = "windows")]
+fn verify_excluded_from_capture(window: &tauri::WebviewWindow) -> Result<(), String> {
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| format!("Failed to get window handle: {e}"))?;
+    let mut affinity = 0u32;
+    unsafe { GetWindowDisplayAffinity(HWND(hwnd.0 as isize), &mut affinity) }
+        .map_err(|e| format!("Failed to read window display affinity: {e}"))?;
+    if affinity == WDA_EXCLUDEFROMCAPTURE.0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Window display affinity is {affinity:#x}; this Windows version cannot exclude windows from capture"
+        ))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn verify_excluded_from_capture(_window: &tauri::WebviewWindow) -> Result<(), String> {
+    Ok(())
+}
+
+/// Applies content protection to every open app window (main window, recording overlay, ...).
+fn set_app_windows_excluded_from_capture(
+    app: &tauri::AppHandle,
     excluded_from_capture: bool,
 ) -> Result<(), String> {
-    window
-        .set_content_protected(excluded_from_capture)
-        .map_err(|e| format!("Failed to set content protection: {e}"))
+    let errors = app
+        .webview_windows()
+        .into_iter()
+        .filter_map(|(label, window)| {
+            window
+                .set_content_protected(excluded_from_capture)
+                .map_err(|e| e.to_string())
+                .and_then(|()| {
+                    if excluded_from_capture {
+                        verify_excluded_from_capture(&window)
+                    } else {
+                        Ok(())
+                    }
+                })
+                .err()
+                .map(|e| format!("{label}: {e}"))
+        })
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to set content protection: {}",
+            errors.join("; ")
+        ))
+    }
+}
+
+/// Runs the smart camera over recorded events, skipping clicks while the cursor was hidden.
+///
+/// Shared by recording finalization and auto-zoom regeneration of existing projects.
+pub(crate) fn build_auto_zoom_segments(
+    events: &[InputEvent],
+    width: u32,
+    height: u32,
+    duration_ms: u64,
+    output_aspect_ratio: f64,
+    cursor_hidden_ranges: &[(u64, u64)],
+    auto_zoom_trigger_mode: AutoZoomTriggerMode,
+    camera_settings: &CameraSettings,
+) -> Vec<ZoomSegment> {
+    let camera_config = camera_config_for_trigger_mode(auto_zoom_trigger_mode, camera_settings);
+    build_camera_segments(
+        events,
+        width,
+        height,
+        duration_ms,
+        output_aspect_ratio,
+        cursor_hidden_ranges,
+        &camera_config,
+    )
+}
+
+/// Builds smart camera segments with an explicit config and the same hidden-cursor
+/// click filtering as [`build_auto_zoom_segments`].
+pub(crate) fn build_camera_segments(
+    events: &[InputEvent],
+    width: u32,
+    height: u32,
+    duration_ms: u64,
+    output_aspect_ratio: f64,
+    cursor_hidden_ranges: &[(u64, u64)],
+    camera_config: &camera_engine::SmartCameraConfig,
+) -> Vec<ZoomSegment> {
+    let zoom_events =
+        filter_events_outside_ranges(events, cursor_hidden_ranges, suppress_for_auto_zoom);
+    let zoom_segments = camera_engine::build_smart_camera_segments(
+        &zoom_events,
+        width,
+        height,
+        duration_ms,
+        output_aspect_ratio,
+        camera_config,
+    );
+    suppress_zoom_segments_in_ranges(zoom_segments, cursor_hidden_ranges)
+}
+
+/// Runs only the smart camera simulation (no segment building) with the same
+/// hidden-cursor click filtering as [`build_auto_zoom_segments`].
+pub(crate) fn simulate_camera_track(
+    events: &[InputEvent],
+    width: u32,
+    height: u32,
+    duration_ms: u64,
+    output_aspect_ratio: f64,
+    cursor_hidden_ranges: &[(u64, u64)],
+    camera_config: &camera_engine::SmartCameraConfig,
+) -> Vec<camera_engine::CameraSample> {
+    let zoom_events =
+        filter_events_outside_ranges(events, cursor_hidden_ranges, suppress_for_auto_zoom);
+    camera_engine::process_camera_targets(
+        &zoom_events,
+        width,
+        height,
+        duration_ms,
+        output_aspect_ratio,
+        camera_config,
+    )
+}
+
+/// Records the smart camera's click gating and clustering decisions, with the same
+/// hidden-cursor click filtering as [`build_auto_zoom_segments`].
+pub(crate) fn trace_camera_decisions(
+    events: &[InputEvent],
+    width: u32,
+    height: u32,
+    output_aspect_ratio: f64,
+    cursor_hidden_ranges: &[(u64, u64)],
+    camera_config: &camera_engine::SmartCameraConfig,
+) -> camera_engine::CameraDecisionTrace {
+    let zoom_events =
+        filter_events_outside_ranges(events, cursor_hidden_ranges, suppress_for_auto_zoom);
+    camera_engine::trace_camera_decisions(
+        &zoom_events,
+        width,
+        height,
+        output_aspect_ratio,
+        camera_config,
+    )
 }
 
 /// Writes `project.json` and `events.json` into project directory.
+///
+/// `width`×`height` is the captured screen area and `video_size` the size of `raw.mp4`,
+/// which is smaller when recording at a reduced capture scale.
 fn save_recording_files(
     output_dir: &std::path::Path,
     recording_id: &str,
     width: u32,
     height: u32,
+    (video_width, video_height): (u32, u32),
     scale_factor: f64,
     start_ms: u64,
     duration_ms: u64,
@@ -1479,8 +3220,24 @@ fn save_recording_files(
     pause_ranges_ms: Vec<(u64, u64)>,
     cursor_hidden_ranges_abs_ms: Vec<(u64, u64)>,
     mut audio_capture_session: Option<AudioCaptureSession>,
+    muxer_segments: &[MuxerSegment],
+    target_fps: u32,
     events: Vec<InputEvent>,
+    video_path: String,
+    monitor_composite_error: Option<String>,
 ) -> Result<(), String> {
+    let audio_issues = audio_track_issues(
+        audio_capture_session.as_ref(),
+        start_ms,
+        duration_ms,
+        &pause_ranges_ms,
+    );
+    for issue in &audio_issues {
+        log::warn!("save_recording_files: {}", issue.warning());
+    }
+    let audio_start_offsets = audio_capture_session
+        .as_ref()
+        .map(|session| measure_audio_start_offsets(session, start_ms));
     if let Err(err) = finalize_recording_audio(
         output_dir,
         &mut audio_capture_session,
@@ -1488,30 +3245,46 @@ fn save_recording_files(
         start_ms,
         end_ms,
         &pause_ranges_ms,
+        muxer_segments,
+        target_fps,
     ) {
         log::warn!("save_recording_files: audio finalize failed: {err}");
     }
 
+    // The process is DPI-aware, so the hooks already report physical pixels; tag the file
+    // so loaders never reinterpret it, then store coordinates in video pixels.
+    let mut events_file = EventsFile {
+        schema_version: EVENTS_VERSION,
+        recording_id: recording_id.to_string(),
+        start_time_ms: start_ms,
+        screen_width: width,
+        screen_height: height,
+        scale_factor,
+        coordinate_space: Some(CoordinateSpace::Physical),
+        events,
+    };
+    events_file.scale_to_video_size(video_width, video_height);
+    let events = &events_file.events;
+    let (width, height) = (video_width, video_height);
+
     let mut settings = ProjectSettings::default();
-    let output_aspect_ratio = settings.export.width as f64 / settings.export.height.max(1) as f64;
-    let camera_config = camera_config_for_trigger_mode(auto_zoom_trigger_mode);
+    settings.camera.trigger_mode = auto_zoom_trigger_mode;
     let cursor_hidden_ranges = normalize_time_ranges_for_pauses(
         &cursor_hidden_ranges_abs_ms,
         start_ms,
         &pause_ranges_ms,
         end_ms,
     );
-    let zoom_events =
-        filter_events_outside_ranges(&events, &cursor_hidden_ranges, suppress_for_auto_zoom);
-    let zoom_segments = camera_engine::build_smart_camera_segments(
-        &zoom_events,
+    let zoom_segments = build_auto_zoom_segments(
+        events,
         width,
         height,
         duration_ms,
-        output_aspect_ratio,
-        &camera_config,
+        settings.export.output_aspect_ratio(),
+        &cursor_hidden_ranges,
+        auto_zoom_trigger_mode,
+        &settings.camera,
     );
-    let zoom_segments = suppress_zoom_segments_in_ranges(zoom_segments, &cursor_hidden_ranges);
     settings.cursor.hidden_ranges = cursor_hidden_ranges
         .iter()
         .map(|(start_ts, end_ts)| crate::models::project::TimeRange {
@@ -1519,21 +3292,15 @@ fn save_recording_files(
             end_ts: *end_ts,
         })
         .collect();
-    let smoothed_cursor_path =
-        cursor_smoothing::smooth_cursor_path(&events, settings.cursor.smoothing_factor);
-    let proxy_video_path = match build_editor_proxy(output_dir) {
-        Ok(path) => path,
-        Err(err) => {
-            log::warn!("save_recording_files: failed to build proxy video: {err}");
-            None
-        }
-    };
-
+    let smoothed_cursor_path = cursor_smoothing::smooth_cursor_path_at_rate(
+        events,
+        settings.cursor.smoothing_factor,
+        settings.cursor.resample_hz,
+    );
     log::info!(
-        "save_recording_files: smart_camera_segments={} smoothed_cursor_points={} proxy={} audio_mode={:?} microphone={}",
+        "save_recording_files: smart_camera_segments={} smoothed_cursor_points={} audio_mode={:?} microphone={}",
         zoom_segments.len(),
         smoothed_cursor_path.len(),
-        proxy_video_path.as_deref().unwrap_or("none"),
         audio_mode,
         microphone_device.as_deref().unwrap_or("default")
     );
@@ -1542,15 +3309,22 @@ fn save_recording_files(
         schema_version: PROJECT_VERSION,
         id: recording_id.to_string(),
         name: format_recording_name(start_ms),
+        description: None,
+        tags: Vec::new(),
         created_at: start_ms,
-        video_path: "raw.mp4".to_string(),
-        proxy_video_path,
+        video_path,
+        // Filled in by the background proxy job once the proxy is ready.
+        proxy_video_path: None,
         events_path: "events.json".to_string(),
         duration_ms,
         video_width: width,
         video_height: height,
         timeline: Timeline { zoom_segments },
         settings,
+        audio_issues,
+        camera_engine_version: CAMERA_ENGINE_VERSION,
+        monitor_composite_error,
+        audio_start_offsets,
     };
 
     let project_json = serde_json::to_string_pretty(&project)
@@ -1558,16 +3332,6 @@ fn save_recording_files(
     std::fs::write(output_dir.join("project.json"), project_json)
         .map_err(|e| format!("Failed to write project.json: {e}"))?;
 
-    let events_file = EventsFile {
-        schema_version: EVENTS_VERSION,
-        recording_id: recording_id.to_string(),
-        start_time_ms: start_ms,
-        screen_width: width,
-        screen_height: height,
-        scale_factor,
-        events,
-    };
-
     let events_json = serde_json::to_string_pretty(&events_file)
         .map_err(|e| format!("Failed to serialize events.json: {e}"))?;
     std::fs::write(output_dir.join("events.json"), events_json)
@@ -1576,36 +3340,66 @@ fn save_recording_files(
     Ok(())
 }
 
-fn build_editor_proxy(output_dir: &std::path::Path) -> Result<Option<String>, String> {
+/// Regenerates `proxy-edit.mp4` next to `raw.mp4` and returns the proxy file name.
+///
+/// The proxy is encoded into a temporary file first and then renamed over the old one,
+/// so a failed rebuild never leaves a truncated proxy behind.
+pub(crate) fn build_editor_proxy(
+    output_dir: &std::path::Path,
+    options: &ProxyOptions,
+) -> Result<String, String> {
+    build_editor_proxy_with_progress(output_dir, options, 0, |_| {})
+}
+
+/// Same as [`build_editor_proxy`], reporting completion in `0..=0.99` when the source
+/// duration is known.
+fn build_editor_proxy_with_progress(
+    output_dir: &std::path::Path,
+    options: &ProxyOptions,
+    source_duration_ms: u64,
+    mut on_progress: impl FnMut(f64),
+) -> Result<String, String> {
     let source = output_dir.join("raw.mp4");
     if !source.exists() {
-        return Ok(None);
+        return Err(format!("Source video not found: {}", source.display()));
     }
 
     let ffmpeg = find_ffmpeg_exe();
-    let proxy_name = "proxy-edit.mp4";
-    let proxy_path = output_dir.join(proxy_name);
+    let proxy_path = output_dir.join(PROXY_VIDEO_FILE);
+    let temp_path = output_dir.join("proxy-edit.tmp.mp4");
 
     let mut command = std::process::Command::new(&ffmpeg);
     apply_no_window_flags(&mut command);
 
-    let status = command
+    command
         .arg("-y")
         .arg("-hide_banner")
         .arg("-loglevel")
         .arg("error")
+        .arg("-nostats")
+        .arg("-progress")
+        .arg("pipe:1")
         .arg("-i")
         .arg(&source)
         .arg("-map")
         .arg("0:v:0")
         .arg("-map")
-        .arg("0:a?")
+        .arg("0:a?");
+    if let Some(max_height) = options.max_height.filter(|height| *height > 0) {
+        // Keep the aspect ratio and an even width; never upscale small sources.
+        command.arg("-vf").arg(format!(
+            "scale=-2:'min(ih,{})'",
+            max_height + max_height % 2
+        ));
+    }
+
+    let mut child = command
         .arg("-c:v")
         .arg("libx264")
         .arg("-preset")
         .arg("veryfast")
         .arg("-crf")
-        .arg("17")
+        .arg(options.quality.unwrap_or_default().crf().to_string())
         .arg("-g")
         .arg("15")
         .arg("-keyint_min")
@@ -1620,15 +3414,205 @@ fn build_editor_proxy(output_dir: &std::path::Path) -> Result<Option<String>, St
         .arg("yuv420p")
         .arg("-movflags")
         .arg("+faststart")
-        .arg(&proxy_path)
-        .status()
+        .arg("-f")
+        .arg("mp4")
+        .arg(&temp_path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .map_err(|e| format!("Failed to run ffmpeg ({}) for proxy: {e}", ffmpeg.display()))?;
 
+    // Drain stderr concurrently so FFmpeg never blocks on a full pipe while we read progress.
+    let stderr = child.stderr.take();
+    let stderr_handle = std::thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(mut stderr) = stderr {
+            let _ = std::io::Read::read_to_string(&mut stderr, &mut text);
+        }
+        text
+    });
+
+    if let Some(stdout) = child.stdout.take() {
+        let mut progress = FfmpegProgress::default();
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if !progress.apply_line(&line) {
+                continue;
+            }
+            if let Some(time_ms) = progress.out_time_ms.filter(|_| source_duration_ms > 0) {
+                on_progress((time_ms as f64 / source_duration_ms as f64).clamp(0.0, 0.99));
+            }
+            if progress.ended {
+                break;
+            }
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for ffmpeg proxy encode: {e}"))?;
+    let stderr = stderr_handle.join().unwrap_or_default();
+
     if !status.success() {
-        return Ok(None);
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(format!(
+            "FFmpeg proxy encode failed ({status}): {}",
+            stderr.trim()
+        ));
+    }
+
+    std::fs::rename(&temp_path, &proxy_path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        format!("Failed to replace proxy {}: {e}", proxy_path.display())
+    })?;
+
+    Ok(PROXY_VIDEO_FILE.to_string())
+}
+
+/// Builds the editor proxy on a background thread and records it in `project.json`.
+///
+/// The editor keeps playing `raw.mp4` until `get_proxy_status` reports the proxy as ready.
+/// When `auto_export` is set, the quick export starts on the same thread once the proxy
+/// build has finished, whether or not it succeeded.
+fn spawn_proxy_job(
+    state: Arc<std::sync::Mutex<HashMap<String, ProxyStatus>>>,
+    recording_id: String,
+    project_file: PathBuf,
+    duration_ms: u64,
+    auto_export: Option<Arc<std::sync::Mutex<ExportStatus>>>,
+) -> Result<(), String> {
+    let output_dir = project_file
+        .parent()
+        .ok_or_else(|| format!("Project file has no parent: {}", project_file.display()))?
+        .to_path_buf();
+
+    let update_status = {
+        let state = state.clone();
+        let recording_id = recording_id.clone();
+        move |update: &dyn Fn(&mut ProxyStatus)| {
+            if let Ok(mut jobs) = state.lock() {
+                update(jobs.entry(recording_id.clone()).or_default());
+            }
+        }
+    };
+    if let Ok(mut jobs) = state.lock() {
+        let now_ms = chrono::Utc::now().timestamp_millis() as u64;
+        jobs.retain(|_, status| {
+            status.is_running
+                || status.finished_at_ms.map_or(true, |finished_at_ms| {
+                    now_ms.saturating_sub(finished_at_ms) < PROXY_STATUS_RETENTION_MS
+                })
+        });
+    }
+    update_status(&|status| {
+        *status = ProxyStatus {
+            is_running: true,
+            started_at_ms: Some(chrono::Utc::now().timestamp_millis() as u64),
+            ..ProxyStatus::default()
+        };
+    });
+
+    let worker_status = update_status.clone();
+    let spawn_result = std::thread::Builder::new()
+        .name("nsc-proxy".to_string())
+        .spawn(move || {
+            let result = build_editor_proxy_with_progress(
+                &output_dir,
+                &ProxyOptions::default(),
+                duration_ms,
+                |fraction| {
+                    worker_status(&|status| status.progress = status.progress.max(fraction));
+                },
+            )
+            .and_then(|proxy_name| {
+                patch_project_file(&project_file, |project| {
+                    project.proxy_video_path = Some(proxy_name.clone())
+                })?;
+                Ok(proxy_name)
+            });
+
+            if let Err(err) = &result {
+                log::warn!("proxy job {recording_id}: failed to build proxy video: {err}");
+            } else {
+                log::info!("proxy job {recording_id}: proxy ready");
+            }
+            worker_status(&|status| {
+                status.is_running = false;
+                status.finished_at_ms = Some(chrono::Utc::now().timestamp_millis() as u64);
+                match &result {
+                    Ok(proxy_name) => {
+                        status.progress = 1.0;
+                        status.proxy_video_path = Some(proxy_name.clone());
+                    }
+                    Err(err) => status.error = Some(err.clone()),
+                }
+            });
+
+            if let Some(export_state) = auto_export {
+                start_auto_export(export_state, &project_file);
+            }
+        });
+
+    if let Err(err) = spawn_result {
+        update_status(&|status| {
+            status.is_running = false;
+            status.error = Some(format!("Failed to spawn proxy thread: {err}"));
+        });
+        return Err(format!("Failed to spawn proxy thread: {err}"));
     }
+    Ok(())
+}
+
+/// Starts the post-recording quick export with the default request.
+///
+/// A failure to start is reported through the export status, which the UI polls: as a
+/// failed export when the exporter is idle, or as a warning of the export that is running.
+fn start_auto_export(export_state: Arc<std::sync::Mutex<ExportStatus>>, project_file: &Path) {
+    let Err(err) = spawn_export_job(
+        export_state.clone(),
+        &project_file.to_string_lossy(),
+        ExportRequest::default(),
+    ) else {
+        log::info!(
+            "stop_recording: started quick export for {}",
+            project_file.display()
+        );
+        return;
+    };
 
-    Ok(Some(proxy_name.to_string()))
+    log::warn!("stop_recording: quick export failed to start: {err}");
+    let Ok(mut status) = export_state.lock() else {
+        return;
+    };
+    if status.is_running {
+        status.warnings.push(format!(
+            "Quick export after recording was not started: {err}"
+        ));
+    } else {
+        *status = ExportStatus {
+            message: "Quick export failed to start".to_string(),
+            project_dir: project_file
+                .parent()
+                .map(|dir| dir.to_string_lossy().to_string()),
+            error: Some(err),
+            finished_at_ms: Some(chrono::Utc::now().timestamp_millis() as u64),
+            ..ExportStatus::default()
+        };
+    }
+}
+
+/// Returns the background proxy build status for a recording, or `None` when no build
+/// was started in this session.
+#[tauri::command]
+pub async fn get_proxy_status(
+    state: tauri::State<'_, ProxyState>,
+    recording_id: String,
+) -> Result<Option<ProxyStatus>, String> {
+    let jobs = state
+        .0
+        .lock()
+        .map_err(|_| "Failed to access proxy status".to_string())?;
+    Ok(jobs.get(&recording_id).cloned())
 }
 
 fn format_recording_name(start_ms: u64) -> String {
@@ -1640,3 +3624,309 @@ fn format_recording_name(start_ms: u64) -> String {
         .unwrap_or_else(Utc::now);
     format!("Recording {}", dt.format("%Y-%m-%d %H:%M:%S"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyframe_interval_is_converted_to_frames_at_the_capture_rate() {
+        assert_eq!(keyframe_interval_frames(1_000, 60), 60);
+        assert_eq!(keyframe_interval_frames(2_000, 30), 60);
+        assert_eq!(keyframe_interval_frames(250, 30), 8);
+        assert_eq!(keyframe_interval_frames(1, 30), 1);
+    }
+
+    #[test]
+    fn camera_sensitivity_scales_ratios_and_honors_overrides() {
+        let defaults = camera_engine::SmartCameraConfig::default();
+        let mode = AutoZoomTriggerMode::SingleClick;
+        let approx = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        let neutral = camera_config_for_trigger_mode(mode, &CameraSettings::default());
+        assert!(approx(neutral.dead_zone_ratio, defaults.dead_zone_ratio));
+        assert!(approx(
+            neutral.escape_distance_ratio,
+            defaults.escape_distance_ratio
+        ));
+        assert!(approx(neutral.hard_edge_ratio, defaults.hard_edge_ratio));
+
+        let twitchy = CameraSettings {
+            sensitivity: 1.0,
+            ..CameraSettings::default()
+        };
+        let twitchy = camera_config_for_trigger_mode(mode, &twitchy);
+        assert!(approx(
+            twitchy.dead_zone_ratio,
+            defaults.dead_zone_ratio * 0.5
+        ));
+        assert!(approx(
+            twitchy.escape_distance_ratio,
+            defaults.escape_distance_ratio * 0.5
+        ));
+        assert!(approx(
+            twitchy.hard_edge_ratio,
+            defaults.hard_edge_ratio * 0.5
+        ));
+
+        // Sticky end of the slider doubles the ratios; an override wins over the slider.
+        let sticky = CameraSettings {
+            sensitivity: 0.0,
+            hard_edge_ratio: Some(0.2),
+            ..CameraSettings::default()
+        };
+        let sticky = camera_config_for_trigger_mode(mode, &sticky);
+        assert!(approx(
+            sticky.dead_zone_ratio,
+            defaults.dead_zone_ratio * 2.0
+        ));
+        assert!(approx(
+            sticky.escape_distance_ratio,
+            defaults.escape_distance_ratio * 2.0
+        ));
+        assert!(approx(sticky.hard_edge_ratio, 0.2));
+
+        assert!(CameraSettings {
+            sensitivity: 1.5,
+            ..CameraSettings::default()
+        }
+        .validate()
+        .is_err());
+        assert!(CameraSettings {
+            escape_distance_ratio: Some(0.1),
+            ..CameraSettings::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn zoom_padding_widens_semantic_framing() {
+        let events = vec![InputEvent::Click {
+            ts: 1_000,
+            x: 960.0,
+            y: 540.0,
+            button: crate::models::events::MouseButton::Left,
+            ui_context: Some(crate::models::events::UiContext {
+                app_name: Some("app".to_string()),
+                control_name: Some("panel".to_string()),
+                bounding_rect: Some(crate::models::events::BoundingRect {
+                    x: 560,
+                    y: 340,
+                    width: 800,
+                    height: 400,
+                }),
+            }),
+        }];
+        let initial_width = |settings: CameraSettings| {
+            let segments = build_auto_zoom_segments(
+                &events,
+                1_920,
+                1_080,
+                4_000,
+                16.0 / 9.0,
+                &[],
+                AutoZoomTriggerMode::SingleClick,
+                &settings,
+            );
+            assert_eq!(segments.len(), 1);
+            segments[0].initial_rect.width
+        };
+
+        let tight = initial_width(CameraSettings {
+            zoom_padding_ratio: Some(0.0),
+            max_zoom: Some(3.0),
+            ..CameraSettings::default()
+        });
+        let default = initial_width(CameraSettings::default());
+        let loose = initial_width(CameraSettings {
+            zoom_padding_ratio: Some(0.8),
+            ..CameraSettings::default()
+        });
+        assert!(tight < default, "tight={tight} default={default}");
+        assert!(default < loose, "default={default} loose={loose}");
+
+        assert!(CameraSettings {
+            max_zoom: Some(0.5),
+            ..CameraSettings::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn scroll_follow_strength_scales_the_scroll_pan() {
+        let mut events = vec![InputEvent::Click {
+            ts: 1_000,
+            x: 960.0,
+            y: 540.0,
+            button: crate::models::events::MouseButton::Left,
+            ui_context: Some(crate::models::events::UiContext {
+                app_name: Some("app".to_string()),
+                control_name: Some("doc".to_string()),
+                bounding_rect: Some(crate::models::events::BoundingRect {
+                    x: 880,
+                    y: 480,
+                    width: 160,
+                    height: 120,
+                }),
+            }),
+        }];
+        events.extend((0..3).map(|step| InputEvent::Scroll {
+            ts: 1_300 + step * 100,
+            x: 960.0,
+            y: 540.0,
+            delta: crate::models::events::ScrollDelta {
+                dx: 0.0,
+                dy: -120.0,
+            },
+        }));
+        let pan = |strength: Option<f64>| {
+            let config = camera_config_for_trigger_mode(
+                AutoZoomTriggerMode::SingleClick,
+                &CameraSettings {
+                    scroll_follow_strength: strength,
+                    ..CameraSettings::default()
+                },
+            );
+            let track =
+                simulate_camera_track(&events, 1_920, 1_080, 2_000, 16.0 / 9.0, &[], &config);
+            let target_y_at = |ts: u64| {
+                track
+                    .iter()
+                    .find(|sample| sample.ts >= ts)
+                    .map(|sample| sample.target_center_y)
+                    .expect("camera sample")
+            };
+            target_y_at(1_700) - target_y_at(1_200)
+        };
+
+        let still = pan(Some(0.0));
+        let default = pan(None);
+        let strong = pan(Some(3.0));
+        assert_eq!(still, 0.0);
+        assert!(default > 0.0, "default={default}");
+        assert!(strong > default * 2.0, "strong={strong} default={default}");
+    }
+
+    #[test]
+    fn early_audio_exit_is_reported_on_the_pause_free_timeline() {
+        let process = |ended_early_at_ms: u64| AudioCaptureProcess {
+            backend: AudioCaptureBackend::NativeLoopback {
+                stop_flag: Arc::new(AtomicBool::new(true)),
+                join_handle: std::thread::spawn(|| Ok(())),
+            },
+            output_path: PathBuf::from("audio.wav"),
+            started_at_ms: 1_000,
+            ended_early_at_ms: Arc::new(AtomicU64::new(ended_early_at_ms)),
+        };
+        let session = AudioCaptureSession {
+            system_capture: Some(process(0)),
+            // Recording started at 1_000 and was paused for 2s before the mic died.
+            microphone_capture: Some(process(8_000)),
+            format: AudioCaptureFormat::default(),
+            watcher: None,
+        };
+
+        let issues = audio_track_issues(Some(&session), 1_000, 20_000, &[(3_000, 5_000)]);
+        assert_eq!(
+            issues,
+            vec![AudioTrackIssue {
+                track: AudioTrackKind::Microphone,
+                ended_at_ms: 5_000,
+            }]
+        );
+        assert_eq!(
+            issues[0].warning(),
+            "Microphone track ended early at 5000ms"
+        );
+        assert!(audio_track_issues(None, 1_000, 20_000, &[]).is_empty());
+    }
+
+    #[test]
+    fn click_activation_overrides_replace_trigger_mode_preset() {
+        let settings = CameraSettings {
+            activation_window_ms: Some(5_000),
+            min_clicks_to_activate: Some(3),
+            click_cluster_gap_ms: Some(50_000),
+            ..CameraSettings::default()
+        };
+        let config =
+            camera_config_for_trigger_mode(AutoZoomTriggerMode::MultiClickWindow, &settings);
+        assert_eq!(config.activation_window_ms, 5_000);
+        assert_eq!(config.min_clicks_to_activate, 3);
+        assert_eq!(config.click_cluster_gap_ms, CLICK_CLUSTER_GAP_MS_RANGE.1);
+        // Fields without an override keep the preset value.
+        assert_eq!(config.min_zoom_interval_ms, 2_000);
+
+        let preset = camera_config_for_trigger_mode(
+            AutoZoomTriggerMode::SingleClick,
+            &CameraSettings::default(),
+        );
+        assert_eq!(preset.min_clicks_to_activate, 1);
+        assert_eq!(preset.click_cluster_gap_ms, 1);
+
+        assert!(settings.validate().is_err());
+        assert!(CameraSettings {
+            min_clicks_to_activate: Some(0),
+            ..CameraSettings::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn mix_filter_normalizes_both_stems_before_amix() {
+        let format = AudioCaptureFormat::new(Some(1), Some(44_100)).unwrap();
+        let filter = build_normalized_mix_filter(format);
+        let normalize =
+            "aresample=44100,aformat=sample_fmts=s16:sample_rates=44100:channel_layouts=mono";
+
+        assert!(filter.starts_with(&format!("[0:a]{normalize}[mic];[1:a]{normalize}[sys];")));
+        assert!(filter.contains("[mic][sys]amix=inputs=2:normalize=0:dropout_transition=0"));
+        assert!(filter.ends_with(&format!("{normalize}[aout]")));
+    }
+
+    #[test]
+    fn muxer_runs_define_exact_audio_ranges() {
+        let start_ms = 1_000_000;
+        let segments = [
+            MuxerSegment {
+                start_ms: start_ms + 20,
+                frames: 100,
+            },
+            MuxerSegment {
+                start_ms: start_ms + 5_000,
+                frames: 61,
+            },
+        ];
+        let ranges = keep_ranges_from_muxer_timeline(start_ms, start_ms + 10_000, &segments, 60);
+
+        assert_eq!(ranges.len(), 2);
+        assert!((ranges[0].0 - 0.02).abs() < 1e-9);
+        assert!((ranges[0].1 - ranges[0].0 - 100.0 / 60.0).abs() < 1e-9);
+        assert!((ranges[1].1 - ranges[1].0 - 61.0 / 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn audio_format_rejects_unsupported_values() {
+        assert_eq!(
+            AudioCaptureFormat::new(None, None).unwrap(),
+            AudioCaptureFormat::default()
+        );
+        assert!(AudioCaptureFormat::new(Some(6), None).is_err());
+        assert!(AudioCaptureFormat::new(None, Some(22_050)).is_err());
+    }
+
+    #[test]
+    fn trimmed_events_are_rebased_to_the_cut_start() {
+        let events = [4_000, 500, 1_000, 2_500]
+            .into_iter()
+            .map(|ts| InputEvent::Move { ts, x: 0.0, y: 0.0 })
+            .collect();
+
+        let trimmed = trim_events_to_range(events, 1_000, 3_000);
+        let timestamps = trimmed.iter().map(InputEvent::ts).collect::<Vec<_>>();
+        assert_eq!(timestamps, vec![0, 1_500]);
+    }
+}
@@ -0,0 +1,224 @@
+//! Developer-only commands, compiled with the `dev-tools` feature.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::capture::recorder::{apply_no_window_flags, find_ffmpeg_exe};
+use crate::capture::state::AutoZoomTriggerMode;
+use crate::commands::capture::build_auto_zoom_segments;
+use crate::commands::export::{spawn_export_job, ExportRequest, ExportState};
+use crate::models::events::{
+    CoordinateSpace, EventsFile, InputEvent, SCHEMA_VERSION as EVENTS_VERSION,
+};
+use crate::models::project::{
+    Project, ProjectSettings, Timeline, CAMERA_ENGINE_VERSION, SCHEMA_VERSION,
+};
+
+const SYNTHETIC_FPS: u32 = 30;
+/// Video kept after the last event so the camera can settle.
+const SYNTHETIC_TAIL_MS: u64 = 1_000;
+const MAX_SYNTHETIC_DURATION_MS: u64 = 10 * 60 * 1000;
+
+/// Builds a throwaway project from `events` over a generated test-pattern video and
+/// exports it, so camera and cursor behavior can be reproduced without a recording.
+///
+/// Returns the path of the generated `project.json`; progress is reported through
+/// `get_export_status` like a regular export.
+#[tauri::command]
+pub async fn render_synthetic_export(
+    state: tauri::State<'_, ExportState>,
+    events: Vec<InputEvent>,
+    width: u32,
+    height: u32,
+    duration_ms: Option<u64>,
+    auto_zoom_trigger_mode: Option<AutoZoomTriggerMode>,
+) -> Result<String, String> {
+    let status_state = state.0.clone();
+    tokio::task::spawn_blocking(move || {
+        let width = (width.clamp(320, 7680) / 2) * 2;
+        let height = (height.clamp(240, 4320) / 2) * 2;
+        let duration_ms = synthetic_duration_ms(&events, duration_ms);
+
+        let project_dir =
+            std::env::temp_dir().join(format!("nsc-synthetic-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&project_dir)
+            .map_err(|e| format!("Failed to create {}: {e}", project_dir.display()))?;
+        generate_test_pattern_video(&project_dir.join("raw.mp4"), width, height, duration_ms)?;
+
+        let project_file = write_synthetic_project(
+            &project_dir,
+            events,
+            width,
+            height,
+            duration_ms,
+            auto_zoom_trigger_mode.unwrap_or_default(),
+        )?;
+        let project_path = project_file.to_string_lossy().to_string();
+        spawn_export_job(
+            status_state,
+            &project_path,
+            ExportRequest {
+                output_path: Some(
+                    project_dir
+                        .join("synthetic.mp4")
+                        .to_string_lossy()
+                        .to_string(),
+                ),
+                ..ExportRequest::default()
+            },
+        )?;
+        log::info!("render_synthetic_export: exporting {project_path}");
+        Ok(project_path)
+    })
+    .await
+    .map_err(|e| format!("Synthetic export task join error: {e}"))?
+}
+
+/// Requested duration, or the last event plus a short tail.
+fn synthetic_duration_ms(events: &[InputEvent], requested_ms: Option<u64>) -> u64 {
+    requested_ms
+        .unwrap_or_else(|| events.iter().map(InputEvent::ts).max().unwrap_or(0) + SYNTHETIC_TAIL_MS)
+        .clamp(SYNTHETIC_TAIL_MS, MAX_SYNTHETIC_DURATION_MS)
+}
+
+fn generate_test_pattern_video(
+    output_path: &Path,
+    width: u32,
+    height: u32,
+    duration_ms: u64,
+) -> Result<(), String> {
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    let output = command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-f")
+        .arg("lavfi")
+        .arg("-i")
+        .arg(format!(
+            "testsrc2=size={width}x{height}:rate={SYNTHETIC_FPS}:duration={:.3}",
+            duration_ms as f64 / 1000.0
+        ))
+        .arg("-c:v")
+        .arg("libx264")
+        .arg("-preset")
+        .arg("ultrafast")
+        .arg("-pix_fmt")
+        .arg("yuv420p")
+        .arg(output_path)
+        .output()
+        .map_err(|e| {
+            format!(
+                "Failed to run ffmpeg ({}) for test pattern: {e}",
+                ffmpeg.display()
+            )
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg test pattern failed: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+/// Writes `project.json` with smart-camera segments and `events.json` for the events.
+fn write_synthetic_project(
+    project_dir: &Path,
+    events: Vec<InputEvent>,
+    width: u32,
+    height: u32,
+    duration_ms: u64,
+    auto_zoom_trigger_mode: AutoZoomTriggerMode,
+) -> Result<PathBuf, String> {
+    let mut settings = ProjectSettings::default();
+    settings.camera.trigger_mode = auto_zoom_trigger_mode;
+    let zoom_segments = build_auto_zoom_segments(
+        &events,
+        width,
+        height,
+        duration_ms,
+        settings.export.output_aspect_ratio(),
+        &[],
+        auto_zoom_trigger_mode,
+        &settings.camera,
+    );
+    let recording_id = project_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let created_at = chrono::Utc::now().timestamp_millis() as u64;
+
+    let project = Project {
+        schema_version: SCHEMA_VERSION,
+        id: recording_id.clone(),
+        name: "Synthetic export".to_string(),
+        description: None,
+        tags: Vec::new(),
+        created_at,
+        video_path: "raw.mp4".to_string(),
+        proxy_video_path: None,
+        events_path: "events.json".to_string(),
+        duration_ms,
+        video_width: width,
+        video_height: height,
+        timeline: Timeline { zoom_segments },
+        settings,
+        audio_issues: Vec::new(),
+        camera_engine_version: CAMERA_ENGINE_VERSION,
+        monitor_composite_error: None,
+        audio_start_offsets: None,
+    };
+    let events_file = EventsFile {
+        schema_version: EVENTS_VERSION,
+        recording_id,
+        start_time_ms: created_at,
+        screen_width: width,
+        screen_height: height,
+        scale_factor: 1.0,
+        coordinate_space: Some(CoordinateSpace::Physical),
+        events,
+    };
+
+    let project_file = project_dir.join("project.json");
+    let project_json = serde_json::to_string_pretty(&project)
+        .map_err(|e| format!("Failed to serialize project.json: {e}"))?;
+    std::fs::write(&project_file, project_json)
+        .map_err(|e| format!("Failed to write project.json: {e}"))?;
+    let events_json = serde_json::to_string_pretty(&events_file)
+        .map_err(|e| format!("Failed to serialize events.json: {e}"))?;
+    std::fs::write(project_dir.join("events.json"), events_json)
+        .map_err(|e| format!("Failed to write events.json: {e}"))?;
+
+    Ok(project_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synthetic_duration_covers_the_last_event() {
+        let events = vec![
+            InputEvent::Move {
+                ts: 2_400,
+                x: 10.0,
+                y: 10.0,
+            },
+            InputEvent::Move {
+                ts: 900,
+                x: 20.0,
+                y: 20.0,
+            },
+        ];
+        assert_eq!(synthetic_duration_ms(&events, None), 3_400);
+        assert_eq!(synthetic_duration_ms(&[], None), SYNTHETIC_TAIL_MS);
+        assert_eq!(synthetic_duration_ms(&events, Some(5_000)), 5_000);
+        assert_eq!(
+            synthetic_duration_ms(&events, Some(u64::MAX)),
+            MAX_SYNTHETIC_DURATION_MS
+        );
+    }
+}
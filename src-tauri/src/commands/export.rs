@@ -1,6 +1,6 @@
 use std::collections::VecDeque;
 use std::fs::File;
-use std::io::{BufReader, Write};
+use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::{
@@ -9,15 +9,24 @@ use std::sync::{
 };
 use std::time::UNIX_EPOCH;
 
+use chrono::{TimeZone, Utc};
 use rfd::FileDialog;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+use crate::algorithm::cursor_smoothing::{collect_cursor_points, resample_points, CursorPoint};
+use crate::algorithm::heatmap::ClickHeatmap;
+use crate::capture::disk_space::{ensure_free_disk_space, DEFAULT_MIN_FREE_DISK_SPACE_MB};
 use crate::capture::recorder::{apply_no_window_flags, find_ffmpeg_exe};
+use crate::capture::state::AutoZoomTriggerMode;
+use crate::commands::capture::{camera_config_for_trigger_mode, simulate_camera_track};
 use crate::commands::cursor::resolve_cursor_asset_for_render;
+use crate::commands::project::{cursor_hidden_ranges, CHAPTERS_METADATA_FILE};
 use crate::models::events::{EventsFile, InputEvent, SCHEMA_VERSION as EVENTS_SCHEMA_VERSION};
 use crate::models::project::{
-    CameraSpring, NormalizedRect, PanKeyframe, Project, TargetPoint, TimeRange, ZoomSegment,
-    SCHEMA_VERSION,
+    parse_hex_rgb, AspectMode, AspectRatio, AudioTrackIssue, CameraSpring, ClickSlowMotion,
+    CursorRenderMode, CursorSettings, CursorShape, FrameBackground, LabelPosition, NormalizedRect,
+    PanKeyframe, Project, ScalingQuality, SegmentLabel, TargetPoint, TimeRange, ZoomSegment,
+    AUDIO_OFFSET_MS_RANGE, SCHEMA_VERSION, TARGET_POINT_ZOOM_RANGE,
 };
 
 const DEFAULT_SPRING_MASS: f64 = 1.0;
@@ -45,7 +54,15 @@ const BASE_VECTOR_CURSOR_ASS_SAMPLES: usize = 1_200;
 const MAX_VECTOR_CURSOR_ASS_SAMPLES: usize = 36_000;
 const VECTOR_CURSOR_ASS_BUDGET_GROWTH_PER_SEC: f64 = 18.0;
 const VECTOR_CURSOR_ASS_BASE_HEIGHT: f64 = 112.0;
-const VECTOR_CURSOR_ASS_PATH: &str = "m 0 0 l 0 90 l 22 70 l 35 110 l 50 102 l 38 63 l 72 63 l 0 0";
+const LABEL_MARGIN_RATIO: f64 = 0.05;
+const VECTOR_CURSOR_ARROW_PATH: &str =
+    "m 0 0 l 0 90 l 22 70 l 35 110 l 50 102 l 38 63 l 72 63 l 0 0";
+const VECTOR_CURSOR_HAND_PATH: &str =
+    "m 22 0 l 38 0 l 38 45 l 80 52 l 80 95 l 65 110 l 25 110 l 0 70 l 10 62 l 22 72 l 22 0";
+const VECTOR_CURSOR_CROSSHAIR_PATH: &str =
+    "m 44 0 l 56 0 l 56 44 l 100 44 l 100 56 l 56 56 l 56 100 l 44 100 l 44 56 l 0 56 l 0 44 l 44 44 l 44 0";
+const VECTOR_CURSOR_DOT_PATH: &str =
+    "m 0 20 b 0 9 9 0 20 0 b 31 0 40 9 40 20 b 40 31 31 40 20 40 b 9 40 0 31 0 20";
 const EXPORT_CANCELLED_SENTINEL: &str = "__NSC_EXPORT_CANCELLED__";
 static EXPORT_CANCEL_REQUESTED: AtomicBool = AtomicBool::new(false);
 
@@ -63,9 +80,23 @@ pub struct ExportStatus {
     pub progress: f64,
     pub message: String,
     pub output_path: Option<String>,
+    /// Directory of the project being exported.
+    pub project_dir: Option<String>,
     pub error: Option<String>,
     pub started_at_ms: Option<u64>,
     pub finished_at_ms: Option<u64>,
+    /// Last frame number reported by FFmpeg `-progress`.
+    pub frame: Option<u64>,
+    /// Current encoding speed in frames per second.
+    pub fps: Option<f64>,
+    /// Encoding speed relative to realtime (`1.0` = realtime).
+    pub speed: Option<f64>,
+    /// Every file of a multi-rendition export, largest first; empty for a single output.
+    pub rendition_paths: Vec<String>,
+    /// Non-fatal problems with the source, e.g. an audio track that ended early.
+    pub warnings: Vec<String>,
+    /// Probe of every written file once the export has finished.
+    pub verification: Vec<ExportVerification>,
 }
 
 impl Default for ExportStatus {
@@ -75,13 +106,39 @@ impl Default for ExportStatus {
             progress: 0.0,
             message: "Idle".to_string(),
             output_path: None,
+            project_dir: None,
             error: None,
             started_at_ms: None,
             finished_at_ms: None,
+            frame: None,
+            fps: None,
+            speed: None,
+            rendition_paths: Vec::new(),
+            warnings: Vec::new(),
+            verification: Vec::new(),
         }
     }
 }
 
+/// Allowed gap between the probed and the expected output duration.
+const VERIFY_DURATION_TOLERANCE_MS: u64 = 1_000;
+const VERIFY_DURATION_TOLERANCE_RATIO: f64 = 0.02;
+/// Below this average bitrate the output is almost certainly missing most of its frames.
+const MIN_PLAUSIBLE_EXPORT_KBPS: f64 = 32.0;
+
+/// Result of probing a finished export file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportVerification {
+    pub path: String,
+    pub file_size_bytes: u64,
+    pub duration_ms: Option<u64>,
+    pub expected_duration_ms: u64,
+    pub bitrate_kbps: Option<f64>,
+    /// Suspicious findings; empty when the file looks sound.
+    pub issues: Vec<String>,
+}
+
 #[derive(Clone, Default)]
 pub struct ExportState(pub Arc<Mutex<ExportStatus>>);
 
@@ -117,13 +174,34 @@ struct SegmentRuntime {
     spring: SpringParams,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Default)]
 struct MediaProbe {
     duration_ms: Option<u64>,
     width: Option<u32>,
     height: Option<u32>,
+    fps: Option<f64>,
+    /// Average frame rate disagrees with the stream time base rate (`fps` vs `tbr`).
+    variable_frame_rate: bool,
+    has_audio: bool,
+    /// Codec name of the first audio stream, e.g. `aac`.
+    audio_codec: Option<String>,
 }
 
+/// Clock used by the camera expressions in the export filter graph.
+///
+/// Frame-index (`n`) expressions are exact at segment boundaries and cheap to evaluate, but they
+/// assume every frame lasts `1/fps`. Timestamp (`t`) expressions follow real presentation time,
+/// so they stay in sync with the `t`-based cursor track even if the source timing is irregular,
+/// at the cost of boundaries snapping to whichever frame timestamp crosses them first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CameraTimeBase {
+    FrameIndex,
+    Timestamp,
+}
+
+/// Relative `fps`/`tbr` mismatch above which a source is treated as variable frame rate.
+const VFR_RATE_MISMATCH_RATIO: f64 = 0.02;
+
 #[derive(Debug, Clone)]
 struct CursorOverlayPlan {
     cursor_png_path: PathBuf,
@@ -192,19 +270,445 @@ pub async fn pick_export_folder(initial_dir: Option<String>) -> Result<Option<St
     .map_err(|e| format!("Failed to open folder dialog: {e}"))?
 }
 
+/// Quality tier for export recommendations.
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExportQualityTier {
+    Draft,
+    #[default]
+    Standard,
+    High,
+}
+
+impl ExportQualityTier {
+    /// Upper bound for the short side of the output frame.
+    fn max_short_side(self) -> u32 {
+        match self {
+            ExportQualityTier::Draft => 720,
+            ExportQualityTier::Standard => 1080,
+            ExportQualityTier::High => 2160,
+        }
+    }
+
+    fn max_fps(self) -> u32 {
+        match self {
+            ExportQualityTier::Draft => 30,
+            ExportQualityTier::Standard | ExportQualityTier::High => 60,
+        }
+    }
+
+    fn h264_crf(self) -> u32 {
+        match self {
+            ExportQualityTier::Draft => 23,
+            ExportQualityTier::Standard => 20,
+            ExportQualityTier::High => 18,
+        }
+    }
+}
+
+/// Advisory export settings; `width`, `height`, `fps` and `codec` map onto `start_export`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRecommendation {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub codec: String,
+    pub crf: u32,
+    /// Rough average bitrate for size estimates.
+    pub estimated_bitrate_kbps: u32,
+    pub source_width: u32,
+    pub source_height: u32,
+    pub source_fps: Option<f64>,
+}
+
+/// Recommends an export size, frame rate and codec that never upscale the source.
 #[tauri::command]
-pub async fn start_export(
+pub async fn recommend_export_settings(
+    project_path: String,
+    aspect_ratio: Option<AspectRatio>,
+    quality: Option<ExportQualityTier>,
+) -> Result<ExportRecommendation, String> {
+    tokio::task::spawn_blocking(move || {
+        let project_file = resolve_project_file(&project_path)?;
+        let project = load_project_file(&project_file)?;
+        let project_dir = project_file.parent().ok_or_else(|| {
+            format!(
+                "Project path has no parent directory: {}",
+                project_file.display()
+            )
+        })?;
+        let source_video = resolve_media_path(project_dir, &project.video_path)?;
+        let probe = probe_media_info(&source_video);
+
+        Ok(recommend_export(
+            probe.width.unwrap_or(project.video_width),
+            probe.height.unwrap_or(project.video_height),
+            probe.fps,
+            project.settings.export.fps,
+            aspect_ratio,
+            quality.unwrap_or_default(),
+        ))
+    })
+    .await
+    .map_err(|e| format!("Recommendation task join error: {e}"))?
+}
+
+fn recommend_export(
+    source_width: u32,
+    source_height: u32,
+    source_fps: Option<f64>,
+    fallback_fps: u32,
+    aspect_ratio: Option<AspectRatio>,
+    quality: ExportQualityTier,
+) -> ExportRecommendation {
+    let sw = source_width.max(2);
+    let sh = source_height.max(2);
+    let (ratio_w, ratio_h) = aspect_ratio.map(AspectRatio::ratio).unwrap_or((sw, sh));
+
+    // Largest frame of the requested shape that fits inside the source, capped by the tier.
+    // The 240px floor never exceeds what the source can fill, so small sources are not upscaled.
+    let fit_scale = (sw as f64 / ratio_w as f64).min(sh as f64 / ratio_h as f64);
+    let fit_short = (fit_scale * ratio_w.min(ratio_h) as f64).floor() as u32;
+    let short_side = fit_short
+        .min(quality.max_short_side())
+        .max(240.min(fit_short))
+        .max(2);
+    let short_side = short_side - short_side % 2;
+    let long_side = (short_side as f64 * ratio_w.max(ratio_h) as f64 / ratio_w.min(ratio_h) as f64)
+        .round() as u32;
+    let long_side = long_side - long_side % 2;
+    let (width, height) = if ratio_w >= ratio_h {
+        (long_side, short_side)
+    } else {
+        (short_side, long_side)
+    };
+
+    let fps = source_fps
+        .map(|fps| fps.round() as u32)
+        .unwrap_or(fallback_fps)
+        .min(quality.max_fps())
+        .clamp(10, 120);
+
+    // ~0.1 bits per pixel at the chosen CRF is typical for screen content.
+    let estimated_bitrate_kbps =
+        ((width as f64 * height as f64 * fps as f64 * 0.1) / 1000.0).round() as u32;
+
+    ExportRecommendation {
+        width: width.min(7680),
+        height: height.min(4320),
+        fps,
+        codec: "h264".to_string(),
+        crf: quality.h264_crf(),
+        estimated_bitrate_kbps,
+        source_width,
+        source_height,
+        source_fps,
+    }
+}
+
+/// AAC bitrate of re-encoded export audio.
+const EXPORT_AUDIO_KBPS: u32 = 192;
+/// Screen content at a codec's default CRF averages about this many bits per pixel; static
+/// slides land far below it, scrolling and video playback above.
+const CRF_BITS_PER_PIXEL_LOW: f64 = 0.03;
+const CRF_BITS_PER_PIXEL_HIGH: f64 = 0.2;
+/// Container overhead on top of the elementary streams.
+const CONTAINER_OVERHEAD_RATIO: f64 = 0.01;
+
+/// How the export size was estimated.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SizeEstimateMode {
+    /// Constant quality: the size depends on the content, so the bounds are wide.
+    Crf,
+    /// Capped bitrate: the upper bound follows from the cap and the duration.
+    Bitrate,
+}
+
+/// Expected size of an export, before running it.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSizeEstimate {
+    pub estimated_bytes: u64,
+    pub low_bytes: u64,
+    pub high_bytes: u64,
+    /// Exported length, including slow motion and title cards.
+    pub duration_ms: u64,
+    pub video_bitrate_kbps: u32,
+    /// `0` when the export has no audio.
+    pub audio_bitrate_kbps: u32,
+    pub mode: SizeEstimateMode,
+    /// What the estimate assumes, for display next to the numbers.
+    pub note: String,
+}
+
+/// Estimates the size of an export with the given settings; omitted settings fall back to
+/// the project's export settings like `start_export`.
+///
+/// `max_bitrate_kbps` bounds the file from above; without it the estimate is a heuristic
+/// over resolution, frame rate and CRF with wide low/high bounds.
+#[tauri::command]
+pub async fn estimate_export_size(
+    project_path: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    fps: Option<u32>,
+    codec: Option<String>,
+    crf: Option<u32>,
+    max_bitrate_kbps: Option<u32>,
+    music_path: Option<String>,
+    intro_duration_ms: Option<u64>,
+    outro_duration_ms: Option<u64>,
+) -> Result<ExportSizeEstimate, String> {
+    tokio::task::spawn_blocking(move || {
+        let project_file = resolve_project_file(&project_path)?;
+        let project = load_project_file(&project_file)?;
+        let project_dir = project_file.parent().ok_or_else(|| {
+            format!(
+                "Project path has no parent directory: {}",
+                project_file.display()
+            )
+        })?;
+        let source_video = resolve_media_path(project_dir, &project.video_path)?;
+        let probe = probe_media_info(&source_video);
+        let source_duration_ms = probe.duration_ms.unwrap_or(project.duration_ms).max(1);
+
+        let body_duration_ms = project
+            .settings
+            .export
+            .click_slow_motion
+            .as_ref()
+            .and_then(|slow_motion| {
+                let events = load_events_file(project_dir, &project.events_path).ok();
+                build_click_time_remap(
+                    slow_motion,
+                    events.as_ref(),
+                    &project.settings.cursor.hidden_ranges,
+                    project.duration_ms.max(1),
+                    source_duration_ms,
+                )
+            })
+            .map_or(source_duration_ms, |remap| {
+                remap.output_duration_ms(source_duration_ms)
+            });
+        let duration_ms =
+            body_duration_ms + intro_duration_ms.unwrap_or(0) + outro_duration_ms.unwrap_or(0);
+
+        let has_music = music_path.is_some_and(|path| !path.trim().is_empty());
+        let codec = codec
+            .unwrap_or(project.settings.export.codec.clone())
+            .trim()
+            .to_lowercase();
+        estimate_export_bytes(
+            (
+                width
+                    .unwrap_or(project.settings.export.width)
+                    .clamp(320, 7680),
+                height
+                    .unwrap_or(project.settings.export.height)
+                    .clamp(240, 4320),
+                fps.unwrap_or(project.settings.export.fps).clamp(10, 120),
+            ),
+            &codec,
+            crf,
+            max_bitrate_kbps.filter(|kbps| *kbps > 0),
+            probe.has_audio || has_music,
+            duration_ms,
+        )
+    })
+    .await
+    .map_err(|e| format!("Size estimate task join error: {e}"))?
+}
+
+fn estimate_export_bytes(
+    (width, height, fps): (u32, u32, u32),
+    codec: &str,
+    crf: Option<u32>,
+    max_bitrate_kbps: Option<u32>,
+    has_audio: bool,
+    duration_ms: u64,
+) -> Result<ExportSizeEstimate, String> {
+    let (_, default_crf) = video_encoder(codec)?;
+    let crf = crf.map_or(default_crf, |crf| crf.min(51));
+    // Each +6 CRF roughly halves the bitrate.
+    let crf_scale = 2f64.powf((default_crf as f64 - crf as f64) / 6.0);
+    let pixels_per_second = width as f64 * height as f64 * fps as f64;
+    let crf_kbps = |bits_per_pixel: f64| pixels_per_second * bits_per_pixel * crf_scale / 1000.0;
+    let mut low_kbps = crf_kbps(CRF_BITS_PER_PIXEL_LOW);
+    let mut high_kbps = crf_kbps(CRF_BITS_PER_PIXEL_HIGH);
+    let mut video_kbps = (low_kbps * high_kbps).sqrt();
+    if let Some(cap) = max_bitrate_kbps.map(f64::from) {
+        low_kbps = low_kbps.min(cap);
+        high_kbps = cap;
+        video_kbps = video_kbps.min(cap);
+    }
+
+    let audio_kbps = if has_audio { EXPORT_AUDIO_KBPS } else { 0 };
+    let bytes = |video_kbps: f64| {
+        let kbits = (video_kbps + audio_kbps as f64) * duration_ms as f64 / 1000.0;
+        (kbits * 1000.0 / 8.0 * (1.0 + CONTAINER_OVERHEAD_RATIO)).round() as u64
+    };
+
+    let (mode, note) = match max_bitrate_kbps {
+        Some(cap) => (
+            SizeEstimateMode::Bitrate,
+            format!(
+                "The file will not exceed the {cap} kbps cap; static screens compress below it."
+            ),
+        ),
+        None => (
+            SizeEstimateMode::Crf,
+            format!(
+                "Rough estimate for {codec} at CRF {crf}: the size depends on how much the screen changes."
+            ),
+        ),
+    };
+    Ok(ExportSizeEstimate {
+        estimated_bytes: bytes(video_kbps),
+        low_bytes: bytes(low_kbps),
+        high_bytes: bytes(high_kbps),
+        duration_ms,
+        video_bitrate_kbps: video_kbps.round() as u32,
+        audio_bitrate_kbps: audio_kbps,
+        mode,
+        note,
+    })
+}
+
+const DEFAULT_BENCHMARK_MS: u64 = 10_000;
+const MAX_BENCHMARK_MS: u64 = 60_000;
+
+/// Phase of the export pipeline measured by `benchmark_export`.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportStage {
+    /// Reading the source's size, frame rate and streams.
+    Probe,
+    /// Building the camera and cursor filter graph and running it over the decoded source
+    /// without encoding.
+    FilterGraph,
+    /// Encoding the filtered frames: the full FFmpeg run minus the decode and filter pass.
+    Encode,
+}
+
+/// Timings of a short render of the project's opening seconds.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportBenchmark {
+    /// Length of the rendered excerpt.
+    pub rendered_ms: u64,
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub codec: String,
+    pub probe_ms: u64,
+    pub filter_graph_ms: u64,
+    pub encode_ms: u64,
+    pub frames: u64,
+    /// Frames encoded per wall-clock second.
+    pub encode_fps: f64,
+    /// Rendered length divided by the total time; below `1.0` the export is slower than realtime.
+    pub realtime_factor: f64,
+    pub dominant_stage: ExportStage,
+}
+
+impl ExportBenchmark {
+    fn new(
+        (rendered_ms, width, height, fps): (u64, u32, u32, u32),
+        codec: String,
+        (probe_ms, filter_graph_ms, encode_ms): (u64, u64, u64),
+        frames: u64,
+    ) -> Self {
+        let total_ms = (probe_ms + filter_graph_ms + encode_ms).max(1);
+        let dominant_stage = [
+            (ExportStage::Probe, probe_ms),
+            (ExportStage::FilterGraph, filter_graph_ms),
+            (ExportStage::Encode, encode_ms),
+        ]
+        .into_iter()
+        .max_by_key(|(_, ms)| *ms)
+        .map_or(ExportStage::Encode, |(stage, _)| stage);
+        Self {
+            rendered_ms,
+            width,
+            height,
+            fps,
+            codec,
+            probe_ms,
+            filter_graph_ms,
+            encode_ms,
+            frames,
+            encode_fps: frames as f64 * 1000.0 / encode_ms.max(1) as f64,
+            realtime_factor: rendered_ms as f64 / total_ms as f64,
+            dominant_stage,
+        }
+    }
+}
+
+/// Renders the first seconds of the project to a temporary file and reports how long
+/// each pipeline phase took, to help choose between hardware encoding and a smaller output.
+///
+/// Holds the export status while it runs, so no export starts alongside it and
+/// `cancel_export` stops it; deletes its output.
+#[tauri::command]
+pub async fn benchmark_export(
     state: tauri::State<'_, ExportState>,
     project_path: String,
+    duration_ms: Option<u64>,
     width: Option<u32>,
     height: Option<u32>,
     fps: Option<u32>,
     codec: Option<String>,
-    output_path: Option<String>,
-) -> Result<(), String> {
+    threads: Option<u32>,
+) -> Result<ExportBenchmark, String> {
+    let previous_status = {
+        let mut status = state
+            .0
+            .lock()
+            .map_err(|_| "Failed to access export status".to_string())?;
+        // A benchmark would compete with the export for the CPU and skew both.
+        if status.is_running {
+            return Err("Cannot benchmark while an export is running".to_string());
+        }
+        let previous_status = status.clone();
+        *status = ExportStatus {
+            is_running: true,
+            message: "Benchmarking export".to_string(),
+            started_at_ms: Some(now_ms()),
+            ..ExportStatus::default()
+        };
+        previous_status
+    };
+    EXPORT_CANCEL_REQUESTED.store(false, Ordering::Relaxed);
+
+    let result = tokio::task::spawn_blocking(move || {
+        run_export_benchmark(
+            &project_path,
+            duration_ms,
+            (width, height, fps),
+            codec,
+            threads,
+        )
+    })
+    .await;
+
+    // The benchmark is not an export of its own; show the last export's result again.
+    if let Ok(mut status) = state.0.lock() {
+        *status = previous_status;
+    }
     EXPORT_CANCEL_REQUESTED.store(false, Ordering::Relaxed);
+    result.map_err(|e| format!("Export benchmark task join error: {e}"))?
+}
 
-    let project_file = resolve_project_file(&project_path)?;
+fn run_export_benchmark(
+    project_path: &str,
+    duration_ms: Option<u64>,
+    (width, height, fps): (Option<u32>, Option<u32>, Option<u32>),
+    codec: Option<String>,
+    threads: Option<u32>,
+) -> Result<ExportBenchmark, String> {
+    let project_file = resolve_project_file(project_path)?;
     let project = load_project_file(&project_file)?;
     let project_dir = project_file.parent().ok_or_else(|| {
         format!(
@@ -212,7 +716,6 @@ pub async fn start_export(
             project_file.display()
         )
     })?;
-
     let source_video = resolve_media_path(project_dir, &project.video_path)?;
     if !source_video.exists() {
         return Err(format!(
@@ -220,110 +723,53 @@ pub async fn start_export(
             source_video.display()
         ));
     }
+    let events = load_events_file(project_dir, &project.events_path).ok();
 
-    let events = match load_events_file(project_dir, &project.events_path) {
-        Ok(events) => Some(events),
-        Err(err) => {
-            log::warn!("start_export: cannot load events file: {err}");
-            None
-        }
-    };
-
-    let probe = probe_media_info(&source_video);
-    let source_duration_ms = probe.duration_ms.unwrap_or(project.duration_ms).max(1);
-    let source_width = probe.width.unwrap_or(project.video_width).max(1);
-    let source_height = probe.height.unwrap_or(project.video_height).max(1);
-
-    let target_width = width
+    let width = width
         .unwrap_or(project.settings.export.width)
         .clamp(320, 7680);
-    let target_height = height
+    let height = height
         .unwrap_or(project.settings.export.height)
         .clamp(240, 4320);
-    let target_fps = fps.unwrap_or(project.settings.export.fps).clamp(10, 120);
-    let target_codec = codec
+    let fps = fps.unwrap_or(project.settings.export.fps).clamp(10, 120);
+    let codec = codec
         .unwrap_or(project.settings.export.codec.clone())
         .trim()
         .to_lowercase();
-
-    if !matches!(target_codec.as_str(), "h264" | "h265" | "vp9") {
-        return Err(format!("Unsupported codec: {target_codec}"));
-    }
-
-    let output_video = resolve_output_path(project_dir, &project.id, output_path)?;
-    if let Some(parent) = output_video.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| {
-            format!(
-                "Failed to create export output directory {}: {e}",
-                parent.display()
-            )
-        })?;
-    }
-
-    {
-        let mut status = state
-            .0
-            .lock()
-            .map_err(|_| "Failed to access export status".to_string())?;
-
-        if status.is_running {
-            return Err("Another export is already running".to_string());
-        }
-
-        *status = ExportStatus {
-            is_running: true,
-            progress: 0.0,
-            message: format!(
-                "Starting export {}x{} @ {}fps ({})",
-                target_width, target_height, target_fps, target_codec
-            ),
-            output_path: Some(output_video.to_string_lossy().to_string()),
-            error: None,
-            started_at_ms: Some(now_ms()),
-            finished_at_ms: None,
-        };
+    if !matches!(codec.as_str(), "h264" | "h265" | "vp9") {
+        return Err(format!("Unsupported codec: {codec}"));
     }
+    let threads = validate_export_threads(threads)?;
 
-    let status_state = state.0.clone();
-    let project_for_export = project.clone();
-    std::thread::Builder::new()
-        .name("nsc-export".to_string())
-        .spawn(move || {
-            run_export_job(
-                status_state,
-                source_video,
-                output_video,
-                project_for_export,
-                events,
-                target_width,
-                target_height,
-                target_fps,
-                target_codec,
-                source_duration_ms,
-                source_width,
-                source_height,
-            )
-        })
-        .map_err(|e| format!("Failed to spawn export thread: {e}"))?;
+    let probe_started = std::time::Instant::now();
+    let probe = probe_media_info(&source_video);
+    let probe_ms = probe_started.elapsed().as_millis() as u64;
 
-    Ok(())
-}
+    let source_duration_ms = probe.duration_ms.unwrap_or(project.duration_ms).max(1);
+    let rendered_ms = duration_ms
+        .unwrap_or(DEFAULT_BENCHMARK_MS)
+        .clamp(1_000, MAX_BENCHMARK_MS)
+        .min(source_duration_ms);
+    let source_width = probe.width.unwrap_or(project.video_width).max(1);
+    let source_height = probe.height.unwrap_or(project.video_height).max(1);
+    let source_crop = project
+        .settings
+        .export
+        .crop
+        .as_ref()
+        .and_then(|rect| SourceCrop::from_rect(rect, source_width, source_height));
+    let (project, events, source_width, source_height) = match source_crop {
+        Some(crop) => (
+            crop.project_into(project),
+            events.map(|events| crop.events_into(events)),
+            crop.width,
+            crop.height,
+        ),
+        None => (project, events, source_width, source_height),
+    };
 
-fn run_export_job(
-    status_state: Arc<Mutex<ExportStatus>>,
-    source_video: PathBuf,
-    output_video: PathBuf,
-    project: Project,
-    events: Option<EventsFile>,
-    width: u32,
-    height: u32,
-    fps: u32,
-    codec: String,
-    source_duration_ms: u64,
-    source_width: u32,
-    source_height: u32,
-) {
-    let filter_build = build_export_filter_graph(
+    let filter_started = std::time::Instant::now();
+    let (filter_graph, cursor_image_input, temp_files) = build_export_filter_graph(
         &project,
         events.as_ref(),
         width,
@@ -332,95 +778,119 @@ fn run_export_job(
         source_duration_ms,
         source_width,
         source_height,
+        source_crop,
+        probe.variable_frame_rate,
+    )?;
+    let filter_pass_started = std::time::Instant::now();
+    let filter_pass = run_filter_only_pass(
+        &source_video,
+        cursor_image_input.as_deref(),
+        &filter_graph,
+        threads,
+        rendered_ms,
     );
-
-    let (filter_graph, cursor_image_input, cursor_temp_file) = match filter_build {
-        Ok(result) => result,
-        Err(err) => {
-            update_status(&status_state, |status| {
-                status.is_running = false;
-                status.finished_at_ms = Some(now_ms());
-                status.message = "Export failed".to_string();
-                status.error = Some(err);
-            });
-            return;
+    let filter_pass_ms = filter_pass_started.elapsed().as_millis() as u64;
+    let filter_graph_ms = filter_started.elapsed().as_millis() as u64;
+    if let Err(err) = filter_pass {
+        for path in temp_files {
+            let _ = std::fs::remove_file(path);
         }
-    };
+        return Err(err);
+    }
 
+    let output = ExportOutput {
+        path: std::env::temp_dir().join(format!("nsc-benchmark-{}.mp4", now_ms())),
+        width,
+        height,
+        crf: None,
+        max_bitrate_kbps: None,
+    };
+    let status = Arc::new(Mutex::new(ExportStatus::default()));
+    let encode_started = std::time::Instant::now();
     let result = execute_ffmpeg_export(
-        &status_state,
-        &source_video,
-        cursor_image_input.as_deref(),
-        &output_video,
-        &filter_graph,
-        &codec,
-        fps,
-        source_duration_ms,
+        &status,
+        FfmpegExportOptions {
+            source_video: &source_video,
+            cursor_image: cursor_image_input.as_deref(),
+            chapters_metadata: None,
+            metadata: &[],
+            music: None,
+            source_has_audio: probe.has_audio,
+            time_remap: None,
+            audio_offset_ms: 0,
+            loop_blend: None,
+            outputs: std::slice::from_ref(&output),
+            filter_graph: &filter_graph,
+            frame_background: &project.settings.frame_background,
+            codec: &codec,
+            threads,
+            color_format: ExportColorFormat::default(),
+            title_card_audio: false,
+            audio_copy: false,
+            output_limit_ms: Some(rendered_ms),
+            target_fps: fps,
+            source_duration_ms,
+        },
     );
+    // The full run decodes and filters again; only the remainder is attributed to encoding.
+    let encode_ms = (encode_started.elapsed().as_millis() as u64).saturating_sub(filter_pass_ms);
 
-    if let Some(path) = cursor_temp_file {
+    for path in temp_files {
         let _ = std::fs::remove_file(path);
     }
-
-    update_status(&status_state, |status| {
-        status.is_running = false;
-        status.finished_at_ms = Some(now_ms());
-        match result {
-            Ok(()) => {
-                status.progress = 1.0;
-                status.message = "Export finished".to_string();
-                status.output_path = Some(output_video.to_string_lossy().to_string());
-                status.error = None;
-            }
-            Err(err) => {
-                if err == EXPORT_CANCELLED_SENTINEL {
-                    status.message = "Export cancelled".to_string();
-                    status.error = None;
-                } else {
-                    status.message = "Export failed".to_string();
-                    status.error = Some(err);
-                }
-            }
+    let _ = std::fs::remove_file(&output.path);
+    result.map_err(|err| {
+        if err == EXPORT_CANCELLED_SENTINEL {
+            "Export benchmark was cancelled".to_string()
+        } else {
+            err
         }
-    });
+    })?;
 
-    EXPORT_CANCEL_REQUESTED.store(false, Ordering::Relaxed);
+    let frames = status
+        .lock()
+        .ok()
+        .and_then(|status| status.frame)
+        .unwrap_or_else(|| (rendered_ms * fps as u64).div_ceil(1000));
+    log::info!(
+        "benchmark_export: {rendered_ms}ms {width}x{height}@{fps} {codec}: probe={probe_ms}ms filter_graph={filter_graph_ms}ms encode={encode_ms}ms frames={frames}"
+    );
+    Ok(ExportBenchmark::new(
+        (rendered_ms, width, height, fps),
+        codec,
+        (probe_ms, filter_graph_ms, encode_ms),
+        frames,
+    ))
 }
 
-fn execute_ffmpeg_export(
-    status_state: &Arc<Mutex<ExportStatus>>,
+/// Decodes and filters the first `limit_ms` of the source into FFmpeg's null muxer, timing
+/// the filter graph without any encoding.
+fn run_filter_only_pass(
     source_video: &Path,
     cursor_image: Option<&Path>,
-    output_video: &Path,
     filter_graph: &str,
-    codec: &str,
-    target_fps: u32,
-    source_duration_ms: u64,
+    threads: Option<u32>,
+    limit_ms: u64,
 ) -> Result<(), String> {
-    let filter_script_path = std::env::temp_dir().join(format!("nsc-filter-{}.txt", now_ms()));
+    let filter_script_path =
+        std::env::temp_dir().join(format!("nsc-benchmark-filter-{}.txt", now_ms()));
     std::fs::write(&filter_script_path, filter_graph).map_err(|e| {
         format!(
             "Failed to write temporary FFmpeg filter script {}: {e}",
             filter_script_path.display()
         )
     })?;
-    let progress_file_path = std::env::temp_dir().join(format!("nsc-progress-{}.txt", now_ms()));
-    let _ = std::fs::write(&progress_file_path, "");
 
     let ffmpeg = find_ffmpeg_exe();
-
     let mut command = Command::new(&ffmpeg);
     apply_no_window_flags(&mut command);
     command
-        .arg("-y")
         .arg("-hide_banner")
-        .arg("-stats_period")
-        .arg("0.5")
-        .arg("-progress")
-        .arg(&progress_file_path)
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-nostats")
         .arg("-i")
         .arg(source_video);
-
     if let Some(cursor_image_path) = cursor_image {
         command
             .arg("-loop")
@@ -428,2544 +898,8380 @@ fn execute_ffmpeg_export(
             .arg("-i")
             .arg(cursor_image_path);
     }
-
-    command
+    if let Some(threads) = threads {
+        command
+            .arg("-filter_complex_threads")
+            .arg(threads.to_string());
+    }
+    let output = command
         .arg("-filter_complex_script")
         .arg(&filter_script_path)
         .arg("-map")
         .arg("[vout]")
-        .arg("-map")
-        .arg("0:a?");
+        .arg("-t")
+        .arg(format_seconds_arg(limit_ms))
+        .arg("-f")
+        .arg("null")
+        .arg("-")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output();
+    let _ = std::fs::remove_file(&filter_script_path);
 
-    match codec {
-        "h264" => {
-            command
-                .arg("-c:v")
-                .arg("libx264")
-                .arg("-preset")
-                .arg("ultrafast")
-                .arg("-crf")
-                .arg("18")
-                .arg("-pix_fmt")
-                .arg("yuv420p");
-        }
-        "h265" => {
-            command
-                .arg("-c:v")
-                .arg("libx265")
-                .arg("-preset")
-                .arg("ultrafast")
-                .arg("-crf")
-                .arg("24")
-                .arg("-pix_fmt")
-                .arg("yuv420p");
-        }
-        "vp9" => {
-            command
-                .arg("-c:v")
-                .arg("libvpx-vp9")
-                .arg("-b:v")
-                .arg("0")
-                .arg("-crf")
-                .arg("33")
-                .arg("-pix_fmt")
-                .arg("yuv420p");
-        }
-        _ => {
-            let _ = std::fs::remove_file(&filter_script_path);
-            let _ = std::fs::remove_file(&progress_file_path);
-            return Err(format!("Unsupported codec: {codec}"));
-        }
+    let output = output.map_err(|e| format!("Failed to run ffmpeg ({}): {e}", ffmpeg.display()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg filter pass failed: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+/// Renders a single frame of the project at `timestamp_ms` (project time) to a PNG or JPEG,
+/// with the export crop, camera and cursor applied, and returns the image path.
+#[tauri::command]
+pub async fn export_still(
+    project_path: String,
+    timestamp_ms: u64,
+    output_path: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        run_export_still(&project_path, timestamp_ms, output_path, width, height)
+    })
+    .await
+    .map_err(|e| format!("Export still task join error: {e}"))?
+}
+
+fn run_export_still(
+    project_path: &str,
+    timestamp_ms: u64,
+    output_path: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<String, String> {
+    let project_file = resolve_project_file(project_path)?;
+    let project = load_project_file(&project_file)?;
+    let project_dir = project_file.parent().ok_or_else(|| {
+        format!(
+            "Project path has no parent directory: {}",
+            project_file.display()
+        )
+    })?;
+    let source_video = resolve_media_path(project_dir, &project.video_path)?;
+    if !source_video.exists() {
+        return Err(format!(
+            "Source video not found: {}",
+            source_video.display()
+        ));
+    }
+    let events = load_events_file(project_dir, &project.events_path).ok();
+
+    let width = width
+        .unwrap_or(project.settings.export.width)
+        .clamp(320, 7680);
+    let height = height
+        .unwrap_or(project.settings.export.height)
+        .clamp(240, 4320);
+    let output_path = match output_path.map(|raw| raw.trim().to_string()) {
+        Some(raw) if !raw.is_empty() => PathBuf::from(raw),
+        _ => project_dir.join(format!("still-{}-{timestamp_ms}.png", project.id)),
+    };
+    let format = StillFormat::from_path(&output_path)?;
+
+    let probe = probe_media_info(&source_video);
+    let source_duration_ms = probe.duration_ms.unwrap_or(project.duration_ms).max(1);
+    let source_width = probe.width.unwrap_or(project.video_width).max(1);
+    let source_height = probe.height.unwrap_or(project.video_height).max(1);
+    let source_crop = project
+        .settings
+        .export
+        .crop
+        .as_ref()
+        .and_then(|rect| SourceCrop::from_rect(rect, source_width, source_height));
+    let (project, events, source_width, source_height) = match source_crop {
+        Some(crop) => (
+            crop.project_into(project),
+            events.map(|events| crop.events_into(events)),
+            crop.width,
+            crop.height,
+        ),
+        None => (project, events, source_width, source_height),
     };
 
-    command.arg("-c:a").arg("aac").arg("-b:a").arg("192k");
+    let project_duration_ms = project.duration_ms.max(1);
+    if timestamp_ms > project_duration_ms {
+        return Err(format!(
+            "Timestamp {timestamp_ms}ms is past the end of the project ({project_duration_ms}ms)"
+        ));
+    }
+    let source_ms = map_time_ms(timestamp_ms, project_duration_ms, source_duration_ms)
+        .min(source_duration_ms.saturating_sub(1));
 
-    let mut child = command
-        .arg(output_video)
+    // The camera is sampled on the export frame grid, exactly like the animated export.
+    let render_fps = project.settings.export.fps.max(1) as f64;
+    let camera_states = build_camera_states(
+        &project,
+        source_duration_ms,
+        project_duration_ms,
+        source_width,
+        source_height,
+        render_fps,
+    );
+    let frame_no = (source_ms as f64 / 1000.0) * render_fps;
+    let zoom = sample_camera_axis_value(&camera_states, frame_no, render_fps, |s| s.zoom, 1.0);
+    let offset_x =
+        sample_camera_axis_value(&camera_states, frame_no, render_fps, |s| s.offset_x, 0.0);
+    let offset_y =
+        sample_camera_axis_value(&camera_states, frame_no, render_fps, |s| s.offset_y, 0.0);
+    let camera = CameraCrop::new(
+        zoom,
+        offset_x,
+        offset_y,
+        source_width as f64,
+        source_height as f64,
+    );
+
+    let cursor_ass = match events.as_ref() {
+        Some(events_file) => build_still_cursor_ass_file(
+            &project,
+            events_file,
+            source_ms,
+            source_duration_ms,
+            (zoom, offset_x, offset_y),
+            (source_width, source_height),
+            (width, height),
+        )
+        .unwrap_or_else(|err| {
+            log::warn!("export_still: cursor disabled: {err}");
+            None
+        }),
+        None => None,
+    };
+
+    let filter = build_still_filter(
+        source_crop,
+        camera,
+        (source_width, source_height),
+        (width, height),
+        &project.settings.frame_background,
+        cursor_ass.as_deref(),
+    );
+
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    // Input seeking decodes from the previous keyframe and drops frames up to the timestamp.
+    command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-ss")
+        .arg(format_seconds_arg(source_ms))
+        .arg("-i")
+        .arg(&source_video)
+        .arg("-vf")
+        .arg(&filter)
+        .arg("-frames:v")
+        .arg("1");
+    if format == StillFormat::Jpeg {
+        command.arg("-q:v").arg("2");
+    }
+    let output = command
+        .arg(&output_path)
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
-        .spawn()
+        .output()
         .map_err(|e| {
-            let _ = std::fs::remove_file(&filter_script_path);
-            let _ = std::fs::remove_file(&progress_file_path);
             format!(
-                "Failed to start FFmpeg export ({}): {e}",
-                ffmpeg.to_string_lossy()
+                "Failed to run ffmpeg ({}) for still export: {e}",
+                ffmpeg.display()
             )
-        })?;
+        });
 
-    let stderr = child.stderr.take();
+    if let Some(path) = cursor_ass {
+        let _ = std::fs::remove_file(path);
+    }
+    let output = output?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg still export failed: {}", stderr.trim()));
+    }
+    if !output_path.exists() {
+        return Err(format!(
+            "FFmpeg did not write the still image: {}",
+            output_path.display()
+        ));
+    }
 
-    let progress_status = Arc::clone(status_state);
-    let expected_total_frames = ((source_duration_ms as f64 / 1000.0) * (target_fps as f64))
-        .max(1.0)
-        .round();
-    let progress_done = Arc::new(AtomicBool::new(false));
-    let progress_done_worker = Arc::clone(&progress_done);
-    let progress_path_worker = progress_file_path.clone();
-    let progress_handle = std::thread::spawn(move || {
-        let started_at = std::time::Instant::now();
-        let mut last_reported_progress = 0.0f64;
-
-        loop {
-            if let Ok(snapshot) = std::fs::read_to_string(&progress_path_worker) {
-                let (time_ms, frame, progress_end) = parse_ffmpeg_progress_snapshot(&snapshot);
-                if let Some(time_ms) = time_ms {
-                    let progress = (time_ms as f64 / source_duration_ms as f64).clamp(0.0, 0.99);
-                    if progress > last_reported_progress {
-                        last_reported_progress = progress;
-                        update_status(&progress_status, |status| {
-                            status.progress = progress;
-                            status.message =
-                                format!("Exporting... {}%", (progress * 100.0).round() as u32);
-                        });
-                    }
-                } else if let Some(frame) = frame {
-                    let progress = (frame as f64 / expected_total_frames).clamp(0.0, 0.99);
-                    if progress > last_reported_progress {
-                        last_reported_progress = progress;
-                        update_status(&progress_status, |status| {
-                            status.progress = progress;
-                            status.message =
-                                format!("Exporting... {}%", (progress * 100.0).round() as u32);
-                        });
-                    }
-                } else {
-                    update_status(&progress_status, |status| {
-                        if status.progress <= 0.0 {
-                            let elapsed = started_at.elapsed().as_secs();
-                            status.message =
-                                format!("Exporting... preparing frames ({}s)", elapsed);
-                        }
-                    });
-                }
+    Ok(output_path.to_string_lossy().to_string())
+}
 
-                if progress_end && progress_done_worker.load(Ordering::Relaxed) {
-                    break;
-                }
-            } else {
-                update_status(&progress_status, |status| {
-                    if status.progress <= 0.0 {
-                        let elapsed = started_at.elapsed().as_secs();
-                        status.message = format!("Exporting... preparing frames ({}s)", elapsed);
-                    }
-                });
-            }
+/// Upscales a click heatmap to `width`x`height` and writes it as a PNG, blended over the
+/// source frame at `background` when given.
+pub(crate) fn render_click_heatmap_png(
+    heatmap: &ClickHeatmap,
+    background: Option<(&Path, u64)>,
+    (width, height): (u32, u32),
+    output_path: &Path,
+) -> Result<(), String> {
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-f")
+        .arg("rawvideo")
+        .arg("-pix_fmt")
+        .arg("rgba")
+        .arg("-s")
+        .arg(format!("{}x{}", heatmap.columns, heatmap.rows))
+        .arg("-i")
+        .arg("-");
+    // Bicubic upscaling smooths the coarse grid into soft blobs.
+    let heat = format!("[0:v]scale={width}:{height}:flags=bicubic,format=rgba");
+    match background {
+        Some((video, ts_ms)) => {
+            command
+                .arg("-ss")
+                .arg(format_seconds_arg(ts_ms))
+                .arg("-i")
+                .arg(video)
+                .arg("-filter_complex")
+                .arg(format!(
+                    "{heat}[heat];[1:v]scale={width}:{height},format=rgba[frame];\
+                     [frame][heat]overlay=format=auto"
+                ));
+        }
+        None => {
+            command.arg("-vf").arg(heat.trim_start_matches("[0:v]"));
+        }
+    }
+    command
+        .arg("-frames:v")
+        .arg("1")
+        .arg(output_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
 
-            // Last-resort fallback so UI progress is never stuck at 0 on platforms where
-            // ffmpeg runtime stats are delayed or suppressed.
-            let estimated = (started_at.elapsed().as_millis() as f64 / source_duration_ms as f64)
-                .clamp(0.0, 0.95);
-            if estimated > last_reported_progress {
-                last_reported_progress = estimated;
-                update_status(&progress_status, |status| {
-                    if estimated > status.progress {
-                        status.progress = estimated;
-                        status.message = format!(
-                            "Exporting... {}% (estimated)",
-                            (estimated * 100.0).round() as u32
-                        );
-                    }
-                });
-            }
+    let mut child = command.spawn().map_err(|e| {
+        format!(
+            "Failed to run ffmpeg ({}) for click heatmap: {e}",
+            ffmpeg.display()
+        )
+    })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(&heatmap.to_rgba())
+            .map_err(|e| format!("Failed to send heatmap pixels to ffmpeg: {e}"))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for ffmpeg: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("FFmpeg click heatmap failed: {}", stderr.trim()));
+    }
+    Ok(())
+}
 
-            if progress_done_worker.load(Ordering::Relaxed) {
-                break;
-            }
-            std::thread::sleep(std::time::Duration::from_millis(250));
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StillFormat {
+    Png,
+    Jpeg,
+}
 
-        if let Ok(snapshot) = std::fs::read_to_string(&progress_path_worker) {
-            let (time_ms, frame, _) = parse_ffmpeg_progress_snapshot(&snapshot);
-            let progress = if let Some(time_ms) = time_ms {
-                Some((time_ms as f64 / source_duration_ms as f64).clamp(0.0, 0.99))
-            } else {
-                frame.map(|frame| (frame as f64 / expected_total_frames).clamp(0.0, 0.99))
-            };
-            if let Some(progress) = progress {
-                if progress > last_reported_progress {
-                    update_status(&progress_status, |status| {
-                        status.progress = progress;
-                        status.message =
-                            format!("Exporting... {}%", (progress * 100.0).round() as u32);
-                    });
-                }
-            }
+impl StillFormat {
+    fn from_path(path: &Path) -> Result<Self, String> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        match extension.as_str() {
+            "png" => Ok(Self::Png),
+            "jpg" | "jpeg" => Ok(Self::Jpeg),
+            _ => Err(format!(
+                "Unsupported still image format: {} (expected .png or .jpg)",
+                path.display()
+            )),
         }
-    });
+    }
+}
 
-    let stderr_status = Arc::clone(status_state);
-    let stderr_handle = std::thread::spawn(move || -> VecDeque<String> {
-        let mut stderr_tail: VecDeque<String> = VecDeque::new();
-        if let Some(stderr) = stderr {
-            let mut reader = BufReader::new(stderr);
-            let mut chunk = [0u8; 4096];
-            let mut buffer = String::new();
+/// Optional overrides for an export job; `None` falls back to the project's export settings.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ExportRequest {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fps: Option<u32>,
+    pub codec: Option<String>,
+    pub output_path: Option<String>,
+    pub min_free_disk_space_mb: Option<u64>,
+    pub music: Option<MusicBed>,
+    /// Extra sizes rendered in the same pass; empty renders a single output.
+    pub renditions: Vec<RenditionSpec>,
+    /// Caps FFmpeg's encoder and filter threads; `None` or `0` lets FFmpeg decide.
+    pub threads: Option<u32>,
+    /// Output pixel format, e.g. `yuv444p`; `None` keeps `yuv420p`.
+    pub pix_fmt: Option<String>,
+    /// `tv` (limited) or `pc` (full); `None` keeps limited range.
+    pub color_range: Option<String>,
+    /// Still images shown before and after the recording.
+    pub title_cards: TitleCards,
+    /// `Some(false)` writes no container tags; `None` embeds them.
+    pub embed_metadata: Option<bool>,
+    /// Replaces the project name as the `title` tag.
+    pub metadata_title: Option<String>,
+    /// Replaces the tool name as the `comment` tag.
+    pub metadata_comment: Option<String>,
+    /// Blends the end of the export into its start so the file loops seamlessly.
+    pub loop_blend: Option<LoopBlend>,
+    /// `Some(true)` stream-copies the source audio when nothing edits it and the container
+    /// accepts its codec; `None` re-encodes to AAC.
+    pub audio_copy: Option<bool>,
+    /// `Some(true)` renders long exports in checkpointed chunks, so a rerun after a crash
+    /// or cancel only renders what is missing.
+    pub resumable: Option<bool>,
+}
 
-            let process_line = |line: &str, tail: &mut VecDeque<String>| {
-                let trimmed = line.trim();
-                if trimmed.is_empty() {
-                    return;
-                }
+const DEFAULT_PIX_FMT: &str = "yuv420p";
+/// Pixel formats beyond 8-bit 4:2:0; libx264 output stays 4:2:0 for player compatibility.
+const HIGH_FIDELITY_PIX_FMTS: [&str; 3] = ["yuv444p", "yuv420p10le", "yuv444p10le"];
 
-                if let Some(time_ms) = extract_ffmpeg_time_ms(trimmed) {
-                    let progress = (time_ms as f64 / source_duration_ms as f64).clamp(0.0, 0.99);
-                    update_status(&stderr_status, |status| {
-                        if progress > status.progress {
-                            status.progress = progress;
-                            status.message =
-                                format!("Exporting... {}%", (progress * 100.0).round() as u32);
-                        }
-                    });
-                } else if let Some(frame) = extract_ffmpeg_status_frame(trimmed) {
-                    let progress = (frame as f64 / expected_total_frames).clamp(0.0, 0.99);
-                    update_status(&stderr_status, |status| {
-                        if progress > status.progress {
-                            status.progress = progress;
-                            status.message =
-                                format!("Exporting... {}%", (progress * 100.0).round() as u32);
-                        }
-                    });
-                }
+/// Pixel format and color range of the encoded video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ExportColorFormat {
+    pix_fmt: &'static str,
+    full_range: bool,
+}
 
-                tail.push_back(trimmed.to_string());
-                if tail.len() > 120 {
-                    tail.pop_front();
-                }
-            };
+impl Default for ExportColorFormat {
+    fn default() -> Self {
+        Self {
+            pix_fmt: DEFAULT_PIX_FMT,
+            full_range: false,
+        }
+    }
+}
 
-            loop {
-                let read = match std::io::Read::read(&mut reader, &mut chunk) {
-                    Ok(read) => read,
-                    Err(_) => break,
+impl ExportColorFormat {
+    /// Validates the requested pixel format and range against the codec.
+    fn resolve(
+        codec: &str,
+        pix_fmt: Option<&str>,
+        color_range: Option<&str>,
+    ) -> Result<Self, String> {
+        let pix_fmt = match pix_fmt.map(|value| value.trim().to_lowercase()) {
+            None => DEFAULT_PIX_FMT,
+            Some(value) if value.is_empty() || value == DEFAULT_PIX_FMT => DEFAULT_PIX_FMT,
+            Some(value) => {
+                let Some(known) = HIGH_FIDELITY_PIX_FMTS
+                    .iter()
+                    .copied()
+                    .find(|known| *known == value)
+                else {
+                    return Err(format!("Unsupported pixel format: {value}"));
                 };
-                if read == 0 {
-                    break;
-                }
-
-                buffer.push_str(&String::from_utf8_lossy(&chunk[..read]));
-                while let Some(idx) = buffer.find(|ch| ch == '\n' || ch == '\r') {
-                    let line = buffer[..idx].to_string();
-                    process_line(&line, &mut stderr_tail);
-                    buffer = buffer[idx + 1..].to_string();
+                if codec == "h264" {
+                    return Err(format!(
+                        "Pixel format {known} requires the h265 or vp9 codec"
+                    ));
                 }
+                known
             }
+        };
+        let full_range = match color_range.map(|value| value.trim().to_lowercase()) {
+            None => false,
+            Some(value) => match value.as_str() {
+                "" | "tv" | "limited" => false,
+                "pc" | "full" => true,
+                _ => return Err(format!("Unsupported color range: {value}")),
+            },
+        };
+        Ok(Self {
+            pix_fmt,
+            full_range,
+        })
+    }
 
-            if !buffer.trim().is_empty() {
-                process_line(&buffer, &mut stderr_tail);
-            }
-        }
-        stderr_tail
-    });
-
-    let mut cancelled = false;
-    let exit_status = loop {
-        if EXPORT_CANCEL_REQUESTED.load(Ordering::Relaxed) {
-            cancelled = true;
-            let _ = child.kill();
-        }
-
-        match child.try_wait() {
-            Ok(Some(status)) => break status,
-            Ok(None) => {
-                std::thread::sleep(std::time::Duration::from_millis(120));
-            }
-            Err(e) => {
-                let _ = std::fs::remove_file(&filter_script_path);
-                let _ = std::fs::remove_file(&progress_file_path);
-                return Err(format!("Failed to wait for FFmpeg export: {e}"));
-            }
-        }
-    };
-    progress_done.store(true, Ordering::Relaxed);
-    let _ = progress_handle.join();
-    let stderr_tail = stderr_handle.join().unwrap_or_default();
+    fn is_default(self) -> bool {
+        self == Self::default()
+    }
 
-    if cancelled {
-        let _ = std::fs::remove_file(&filter_script_path);
-        let _ = std::fs::remove_file(&progress_file_path);
-        return Err(EXPORT_CANCELLED_SENTINEL.to_string());
+    /// Explicit BT.709 conversion for non-default formats, so the tags written by
+    /// [`Self::push_args`] describe the pixels; the default keeps FFmpeg's implicit one.
+    fn video_filter(self) -> Option<String> {
+        (!self.is_default()).then(|| {
+            format!(
+                "scale=out_color_matrix=bt709:out_range={},format={}",
+                if self.full_range { "full" } else { "limited" },
+                self.pix_fmt
+            )
+        })
     }
 
-    if !exit_status.success() {
-        let stderr_excerpt = stderr_tail
-            .iter()
-            .filter(|line| {
-                line.contains("Error")
-                    || line.contains("error")
-                    || line.contains("Invalid")
-                    || line.contains("Failed")
-                    || line.contains("failed")
-            })
-            .cloned()
-            .collect::<Vec<_>>();
-        let _ = std::fs::remove_file(&filter_script_path);
-        let _ = std::fs::remove_file(&progress_file_path);
-        if stderr_excerpt.is_empty() {
-            return Err(format!("FFmpeg export failed with status: {exit_status}"));
+    fn push_args(self, command: &mut Command) {
+        command.arg("-pix_fmt").arg(self.pix_fmt);
+        if self.is_default() {
+            return;
         }
-        return Err(format!(
-            "FFmpeg export failed with status: {exit_status}\n{}",
-            stderr_excerpt.join("\n")
-        ));
+        command
+            .arg("-colorspace")
+            .arg("bt709")
+            .arg("-color_primaries")
+            .arg("bt709")
+            .arg("-color_trc")
+            .arg("bt709")
+            .arg("-color_range")
+            .arg(if self.full_range { "pc" } else { "tv" });
     }
+}
 
-    let _ = std::fs::remove_file(&filter_script_path);
-    let _ = std::fs::remove_file(&progress_file_path);
-    Ok(())
+const MAX_RENDITIONS: usize = 4;
+
+/// One output of a multi-rendition export.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenditionSpec {
+    /// Appended to the output file name (`export-...-{label}.mp4`); letters, digits, `-`, `_`.
+    pub label: String,
+    pub width: u32,
+    pub height: u32,
+    /// Overrides the codec's default constant rate factor.
+    #[serde(default)]
+    pub crf: Option<u32>,
+    /// Caps the video bitrate, e.g. for a size-limited share.
+    #[serde(default)]
+    pub max_bitrate_kbps: Option<u32>,
 }
 
-fn build_export_filter_graph(
-    project: &Project,
-    events: Option<&EventsFile>,
-    target_width: u32,
-    target_height: u32,
-    target_fps: u32,
-    source_duration_ms: u64,
-    source_width: u32,
-    source_height: u32,
-) -> Result<(String, Option<PathBuf>, Option<PathBuf>), String> {
-    let project_duration_ms = project.duration_ms.max(1);
-    let cursor_hidden_ranges_ms = map_hidden_ranges_to_source(
-        &project.settings.cursor.hidden_ranges,
-        project_duration_ms,
-        source_duration_ms,
-    );
-
-    let render_fps = target_fps as f64;
-    let camera_states = build_camera_states(
-        project,
-        source_duration_ms,
-        project_duration_ms,
-        source_width.max(1),
-        source_height.max(1),
-        render_fps,
-    );
+/// A file written by the export FFmpeg run.
+#[derive(Debug, Clone, PartialEq)]
+struct ExportOutput {
+    path: PathBuf,
+    width: u32,
+    height: u32,
+    crf: Option<u32>,
+    max_bitrate_kbps: Option<u32>,
+}
 
-    let zoom_expr = build_camera_value_expr(&camera_states, |state| state.zoom, 1.0, render_fps);
-    let offset_x_expr =
-        build_camera_value_expr(&camera_states, |state| state.offset_x, 0.0, render_fps);
-    let offset_y_expr =
-        build_camera_value_expr(&camera_states, |state| state.offset_y, 0.0, render_fps);
+const DEFAULT_MUSIC_VOLUME_DB: f64 = -18.0;
+const DEFAULT_MUSIC_FADE_MS: u64 = 2_000;
 
-    let mut input_chain: Vec<String> = Vec::new();
-    let mut cursor_overlay_filter = None;
-    let mut cursor_input_path = None;
-    let mut cursor_temp_file = None;
+/// Background music mixed under the exported audio.
+#[derive(Debug, Clone)]
+pub(crate) struct MusicBed {
+    pub path: PathBuf,
+    /// Gain applied to the music; negative values keep it under the voice.
+    pub volume_db: f64,
+    /// Repeat the track until the video ends.
+    pub loop_to_video: bool,
+    /// Length of the fade-in at the start and the fade-out at the end of the video.
+    pub fade_ms: u64,
+}
 
-    // Upsample to target FPS before camera transforms to match preview smoothness.
-    input_chain.push(format!("fps={target_fps}"));
+const DEFAULT_TITLE_CARD_MS: u64 = 3_000;
+const MAX_TITLE_CARD_MS: u64 = 30_000;
+/// Audio sample rate of the card silence; the recording's audio is resampled to match
+/// when cards are attached, so all parts concatenate without re-encoding.
+const TITLE_CARD_AUDIO_RATE: u32 = 48_000;
+
+/// Still image shown as its own segment before or after the rendered recording.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TitleCard {
+    pub path: PathBuf,
+    pub duration_ms: u64,
+}
 
-    if ENABLE_CUSTOM_CURSOR_OVERLAY_EXPORT {
-        if let Some(plan) = build_cursor_overlay_plan(
-            project,
-            events,
-            &camera_states,
-            &cursor_hidden_ranges_ms,
-            source_duration_ms,
-            project_duration_ms,
-            source_width.max(1),
-            source_height.max(1),
-            target_width.max(1),
-            target_height.max(1),
-            render_fps,
-        )? {
-            cursor_input_path = Some(plan.cursor_png_path);
-            cursor_overlay_filter = Some(plan.filter_chain);
-        }
-    } else if let Some(events_file) = events {
-        if !events_file.events.is_empty() {
-            match build_vector_cursor_ass_file(
-                project,
-                events_file,
-                &camera_states,
-                &cursor_hidden_ranges_ms,
-                source_duration_ms,
-                project_duration_ms,
-                source_width.max(1),
-                source_height.max(1),
-                target_width.max(1),
-                target_height.max(1),
-                render_fps,
-            ) {
-                Ok(ass) => {
-                    let escaped = escape_filter_path(&ass);
-                    cursor_overlay_filter =
-                        Some(format!("[framed]subtitles=filename='{escaped}'[vout]"));
-                    cursor_temp_file = Some(ass);
-                }
-                Err(err) => {
-                    log::warn!("build_export_filter_graph: vector cursor overlay disabled: {err}");
-                }
-            }
-        }
+impl TitleCard {
+    fn from_args(path: Option<String>, duration_ms: Option<u64>) -> Option<Self> {
+        path.map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty())
+            .map(|path| Self {
+                path: PathBuf::from(path),
+                duration_ms: duration_ms
+                    .unwrap_or(DEFAULT_TITLE_CARD_MS)
+                    .clamp(500, MAX_TITLE_CARD_MS),
+            })
     }
+}
 
-    input_chain.push("split=2[base][zoom]".to_string());
-
-    let post_camera_chain = if let Some(cursor_overlay_filter) = cursor_overlay_filter {
-        format!(
-            "[cam]scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:black[framed];\
-             {cursor_overlay_filter}",
-            w = target_width,
-            h = target_height,
-            cursor_overlay_filter = cursor_overlay_filter
-        )
-    } else {
-        format!(
-            "[cam]scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:black[vout]",
-            w = target_width,
-            h = target_height
-        )
-    };
+/// Intro and outro cards of an export.
+///
+/// Cards are encoded separately and joined with the concat demuxer, so the camera and
+/// cursor expressions of the main render keep their own timeline.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TitleCards {
+    pub intro: Option<TitleCard>,
+    pub outro: Option<TitleCard>,
+}
 
-    let graph = format!(
-        "{input};\
-         [zoom]scale=w='iw*({zoom})':h='ih*({zoom})':eval=frame[scaled];\
-         [base][scaled]overlay=x='-max(0,min({x},overlay_w-main_w))':y='-max(0,min({y},overlay_h-main_h))':eval=frame[cam];\
-         {post_camera}",
-        input = input_chain.join(","),
-        zoom = zoom_expr,
-        x = offset_x_expr,
-        y = offset_y_expr,
-        post_camera = post_camera_chain
-    );
+impl TitleCards {
+    fn is_empty(&self) -> bool {
+        self.intro.is_none() && self.outro.is_none()
+    }
+}
 
-    Ok((graph, cursor_input_path, cursor_temp_file))
+#[tauri::command]
+pub async fn start_export(
+    state: tauri::State<'_, ExportState>,
+    project_path: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    fps: Option<u32>,
+    codec: Option<String>,
+    output_path: Option<String>,
+    min_free_disk_space_mb: Option<u64>,
+    music_path: Option<String>,
+    music_volume_db: Option<f64>,
+    music_loop: Option<bool>,
+    music_fade_ms: Option<u64>,
+    threads: Option<u32>,
+    pix_fmt: Option<String>,
+    color_range: Option<String>,
+    intro_image_path: Option<String>,
+    intro_duration_ms: Option<u64>,
+    outro_image_path: Option<String>,
+    outro_duration_ms: Option<u64>,
+    embed_metadata: Option<bool>,
+    metadata_title: Option<String>,
+    metadata_comment: Option<String>,
+    loop_seamless: Option<bool>,
+    loop_crossfade_ms: Option<u64>,
+    audio_copy: Option<bool>,
+    resumable: Option<bool>,
+) -> Result<(), String> {
+    let music = music_path
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .map(|path| MusicBed {
+            path: PathBuf::from(path),
+            volume_db: music_volume_db
+                .unwrap_or(DEFAULT_MUSIC_VOLUME_DB)
+                .clamp(-60.0, 12.0),
+            loop_to_video: music_loop.unwrap_or(true),
+            fade_ms: music_fade_ms.unwrap_or(DEFAULT_MUSIC_FADE_MS),
+        });
+    spawn_export_job(
+        state.0.clone(),
+        &project_path,
+        ExportRequest {
+            width,
+            height,
+            fps,
+            codec,
+            output_path,
+            min_free_disk_space_mb,
+            music,
+            renditions: Vec::new(),
+            threads,
+            pix_fmt,
+            color_range,
+            title_cards: TitleCards {
+                intro: TitleCard::from_args(intro_image_path, intro_duration_ms),
+                outro: TitleCard::from_args(outro_image_path, outro_duration_ms),
+            },
+            embed_metadata,
+            metadata_title,
+            metadata_comment,
+            loop_blend: LoopBlend::from_args(loop_seamless, loop_crossfade_ms),
+            audio_copy,
+            resumable,
+        },
+    )
 }
 
-fn build_camera_states(
-    project: &Project,
-    source_duration_ms: u64,
-    project_duration_ms: u64,
-    source_width: u32,
-    source_height: u32,
-    source_fps: f64,
-) -> Vec<CameraState> {
-    let safe_fps = source_fps.max(1.0);
-    let runtime_segments = build_runtime_segments(project);
-    let mut anchors = vec![0, project_duration_ms];
-    for segment in &runtime_segments {
-        anchors.push(segment.start_ts);
-        anchors.push(segment.end_ts);
-        anchors.extend(segment.target_points.iter().map(|point| point.ts));
+/// Renders several sizes of the same export in one decode and filter pass.
+///
+/// The largest rendition is rendered by the regular pipeline and the others are scaled
+/// from it; each file is named after `output_path` (or the default name) plus `-{label}`.
+#[tauri::command]
+pub async fn start_multi_rendition_export(
+    state: tauri::State<'_, ExportState>,
+    project_path: String,
+    renditions: Vec<RenditionSpec>,
+    fps: Option<u32>,
+    codec: Option<String>,
+    output_path: Option<String>,
+    min_free_disk_space_mb: Option<u64>,
+    threads: Option<u32>,
+    pix_fmt: Option<String>,
+    color_range: Option<String>,
+) -> Result<(), String> {
+    if renditions.is_empty() {
+        return Err("At least one rendition is required".to_string());
     }
-    anchors.sort_unstable();
-    anchors.dedup();
+    spawn_export_job(
+        state.0.clone(),
+        &project_path,
+        ExportRequest {
+            fps,
+            codec,
+            output_path,
+            min_free_disk_space_mb,
+            renditions,
+            threads,
+            pix_fmt,
+            color_range,
+            ..ExportRequest::default()
+        },
+    )
+}
 
-    let sw = source_width as f64;
-    let sh = source_height as f64;
-    let full_rect = NormalizedRect {
-        x: 0.0,
-        y: 0.0,
-        width: 1.0,
-        height: 1.0,
-    };
-    let default_camera = rect_to_camera_values(full_rect, sw, sh);
-    let default_spring = default_spring_params();
-    let mut zoom_state = AxisSpringState {
-        value: default_camera.0,
-        velocity: 0.0,
-    };
-    let mut offset_x_state = AxisSpringState {
-        value: default_camera.1,
-        velocity: 0.0,
-    };
-    let mut offset_y_state = AxisSpringState {
-        value: default_camera.2,
-        velocity: 0.0,
-    };
+/// Validates the request, marks the export as running and starts it on a background thread.
+///
+/// Shared by `start_export` and the post-recording quick export.
+pub(crate) fn spawn_export_job(
+    status_state: Arc<Mutex<ExportStatus>>,
+    project_path: &str,
+    request: ExportRequest,
+) -> Result<(), String> {
+    let ExportRequest {
+        width,
+        height,
+        fps,
+        codec,
+        output_path,
+        min_free_disk_space_mb,
+        music,
+        renditions,
+        threads,
+        pix_fmt,
+        color_range,
+        title_cards,
+        embed_metadata,
+        metadata_title,
+        metadata_comment,
+        loop_blend,
+        audio_copy,
+        resumable,
+    } = request;
 
-    let mut states: Vec<CameraState> = Vec::new();
-    for pair in anchors.windows(2) {
-        let start_ts = pair[0];
-        let end_ts = pair[1];
-        if end_ts <= start_ts {
-            continue;
-        }
+    EXPORT_CANCEL_REQUESTED.store(false, Ordering::Relaxed);
 
-        let (target_camera, spring) =
-            if let Some(segment) = resolve_runtime_segment(&runtime_segments, start_ts) {
-                let target_rect = target_rect_at_ts(segment, start_ts);
-                (rect_to_camera_values(target_rect, sw, sh), segment.spring)
-            } else {
-                (default_camera, default_spring)
-            };
+    let project_file = resolve_project_file(project_path)?;
+    let project = load_project_file(&project_file)?;
+    let project_dir = project_file.parent().ok_or_else(|| {
+        format!(
+            "Project path has no parent directory: {}",
+            project_file.display()
+        )
+    })?;
 
-        let start_ms = map_time_ms(start_ts, project_duration_ms, source_duration_ms);
-        let end_ms = map_time_ms(end_ts, project_duration_ms, source_duration_ms);
-        if end_ms <= start_ms {
-            continue;
-        }
+    let source_video = resolve_media_path(project_dir, &project.video_path)?;
+    if !source_video.exists() {
+        return Err(format!(
+            "Source video not found: {}",
+            source_video.display()
+        ));
+    }
 
-        let start_frame = start_ms as f64 / 1000.0 * safe_fps;
-        let end_frame = end_ms as f64 / 1000.0 * safe_fps;
-        if end_frame <= start_frame {
-            continue;
+    let events = match load_events_file(project_dir, &project.events_path) {
+        Ok(events) => Some(events),
+        Err(err) => {
+            log::warn!("start_export: cannot load events file: {err}");
+            None
         }
+    };
 
-        states.push(CameraState {
-            start_frame,
-            end_frame,
-            spring,
-            zoom: AxisSpringSegment {
-                start: zoom_state.value,
-                velocity: zoom_state.velocity,
-                target: target_camera.0,
-            },
-            offset_x: AxisSpringSegment {
-                start: offset_x_state.value,
-                velocity: offset_x_state.velocity,
-                target: target_camera.1,
-            },
-            offset_y: AxisSpringSegment {
-                start: offset_y_state.value,
-                velocity: offset_y_state.velocity,
-                target: target_camera.2,
-            },
-        });
+    let chapters_metadata =
+        Some(project_dir.join(CHAPTERS_METADATA_FILE)).filter(|path| path.is_file());
 
-        let dt_seconds = (end_frame - start_frame).max(0.0) / safe_fps;
-        zoom_state = evaluate_spring_axis(zoom_state, target_camera.0, spring, dt_seconds);
-        offset_x_state = evaluate_spring_axis(offset_x_state, target_camera.1, spring, dt_seconds);
-        offset_y_state = evaluate_spring_axis(offset_y_state, target_camera.2, spring, dt_seconds);
+    if let Some(music) = &music {
+        if !music.path.is_file() {
+            return Err(format!("Music file not found: {}", music.path.display()));
+        }
+    }
+    if let FrameBackground::Image { path } = &project.settings.frame_background {
+        if !Path::new(path).is_file() {
+            return Err(format!("Frame background image not found: {path}"));
+        }
+    }
+    if loop_blend.is_some() && !title_cards.is_empty() {
+        return Err("A seamless loop cannot have intro or outro cards".to_string());
+    }
+    for (name, card) in [("Intro", &title_cards.intro), ("Outro", &title_cards.outro)] {
+        if let Some(card) = card {
+            if !card.path.is_file() {
+                return Err(format!(
+                    "{name} card image not found: {}",
+                    card.path.display()
+                ));
+            }
+        }
     }
 
-    states
-}
+    let probe = probe_media_info(&source_video);
+    let source_duration_ms = probe.duration_ms.unwrap_or(project.duration_ms).max(1);
+    let source_width = probe.width.unwrap_or(project.video_width).max(1);
+    let source_height = probe.height.unwrap_or(project.video_height).max(1);
+    let source_crop = project
+        .settings
+        .export
+        .crop
+        .as_ref()
+        .and_then(|rect| SourceCrop::from_rect(rect, source_width, source_height));
+    let source_fps = probe.fps;
+    let source_is_vfr = probe.variable_frame_rate;
+    let source_has_audio = probe.has_audio;
+    let source_audio_codec = probe.audio_codec;
+    if source_is_vfr {
+        log::info!(
+            "start_export: variable frame rate source detected, using timestamp-based camera"
+        );
+    }
 
-fn build_runtime_segments(project: &Project) -> Vec<SegmentRuntime> {
-    let mut segments = project.timeline.zoom_segments.clone();
-    segments.sort_by_key(|segment| segment.start_ts);
+    let output_video = resolve_output_path(project_dir, &project.id, output_path)?;
+    let outputs = if renditions.is_empty() {
+        vec![ExportOutput {
+            path: output_video,
+            width: width
+                .unwrap_or(project.settings.export.width)
+                .clamp(320, 7680),
+            height: height
+                .unwrap_or(project.settings.export.height)
+                .clamp(240, 4320),
+            crf: None,
+            max_bitrate_kbps: None,
+        }]
+    } else {
+        rendition_outputs(&output_video, &renditions)?
+    };
+    let (target_width, target_height) = (outputs[0].width, outputs[0].height);
+    let target_fps = fps.unwrap_or(project.settings.export.fps).clamp(10, 120);
+    let target_codec = codec
+        .unwrap_or(project.settings.export.codec.clone())
+        .trim()
+        .to_lowercase();
 
-    let mut runtime: Vec<SegmentRuntime> = Vec::new();
-    for segment in segments {
-        let start_ts = segment.start_ts.min(project.duration_ms);
-        let end_ts = segment.end_ts.min(project.duration_ms);
-        if end_ts <= start_ts {
-            continue;
-        }
+    if !matches!(target_codec.as_str(), "h264" | "h265" | "vp9") {
+        return Err(format!("Unsupported codec: {target_codec}"));
+    }
+    let threads = validate_export_threads(threads)?;
+    let color_format =
+        ExportColorFormat::resolve(&target_codec, pix_fmt.as_deref(), color_range.as_deref())?;
 
-        let base_rect = normalize_segment_rect(segment.initial_rect.clone());
-        let target_points = if segment.target_points.is_empty() {
-            normalize_target_points(
-                target_points_from_legacy_pan(&segment, &base_rect),
-                start_ts,
-                end_ts,
-                &base_rect,
+    let output_video = outputs[0].path.clone();
+    if let Some(parent) = output_video.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            format!(
+                "Failed to create export output directory {}: {e}",
+                parent.display()
             )
-        } else {
-            normalize_target_points(segment.target_points.clone(), start_ts, end_ts, &base_rect)
-        };
-
-        runtime.push(SegmentRuntime {
-            start_ts,
-            end_ts,
-            base_rect,
-            target_points,
-            spring: normalize_spring_params(&segment.spring),
-        });
+        })?;
+        ensure_free_disk_space(
+            parent,
+            min_free_disk_space_mb.unwrap_or(DEFAULT_MIN_FREE_DISK_SPACE_MB),
+        )?;
     }
 
-    runtime.sort_by_key(|segment| segment.start_ts);
-    runtime
-}
-
-fn normalize_target_points(
-    points: Vec<TargetPoint>,
-    start_ts: u64,
-    end_ts: u64,
-    fallback_rect: &NormalizedRect,
-) -> Vec<TargetPoint> {
-    let mut normalized = points
-        .into_iter()
-        .map(|point| TargetPoint {
-            ts: point.ts.clamp(start_ts, end_ts),
-            rect: normalize_segment_rect(point.rect),
-        })
-        .collect::<Vec<_>>();
-    normalized.sort_by_key(|point| point.ts);
+    {
+        let mut status = status_state
+            .lock()
+            .map_err(|_| "Failed to access export status".to_string())?;
 
-    let mut dedup: Vec<TargetPoint> = Vec::new();
-    for point in normalized {
-        if let Some(last) = dedup.last_mut() {
-            if last.ts == point.ts {
-                *last = point;
-                continue;
-            }
+        if status.is_running {
+            return Err("Another export is already running".to_string());
         }
-        dedup.push(point);
-    }
 
-    if dedup.is_empty() {
-        return vec![
-            TargetPoint {
-                ts: start_ts,
-                rect: fallback_rect.clone(),
-            },
-            TargetPoint {
-                ts: end_ts,
-                rect: fallback_rect.clone(),
+        *status = ExportStatus {
+            is_running: true,
+            progress: 0.0,
+            message: format!(
+                "Starting export {}x{} @ {}fps ({})",
+                target_width, target_height, target_fps, target_codec
+            ),
+            output_path: Some(output_video.to_string_lossy().to_string()),
+            project_dir: Some(project_dir.to_string_lossy().to_string()),
+            error: None,
+            started_at_ms: Some(now_ms()),
+            finished_at_ms: None,
+            frame: None,
+            fps: None,
+            speed: None,
+            rendition_paths: if outputs.len() > 1 {
+                outputs
+                    .iter()
+                    .map(|output| output.path.to_string_lossy().to_string())
+                    .collect()
+            } else {
+                Vec::new()
             },
-        ];
+            warnings: project
+                .audio_issues
+                .iter()
+                .map(AudioTrackIssue::warning)
+                .collect(),
+            verification: Vec::new(),
+        };
     }
 
-    if dedup.first().is_some_and(|point| point.ts > start_ts) {
-        let rect = dedup[0].rect.clone();
-        dedup.insert(0, TargetPoint { ts: start_ts, rect });
-    }
+    let metadata = if embed_metadata.unwrap_or(true) {
+        export_metadata_tags(
+            &project,
+            metadata_title.as_deref(),
+            metadata_comment.as_deref(),
+        )
+    } else {
+        Vec::new()
+    };
 
-    if dedup.last().is_some_and(|point| point.ts < end_ts) {
-        let rect = dedup
-            .last()
-            .expect("target points has last element")
-            .rect
+    let job = ExportJob {
+        source_video,
+        outputs,
+        project,
+        events,
+        chapters_metadata,
+        music,
+        source_has_audio,
+        width: target_width,
+        height: target_height,
+        fps: target_fps,
+        codec: target_codec,
+        source_duration_ms,
+        source_width,
+        source_height,
+        source_crop,
+        source_fps,
+        source_is_vfr,
+        threads,
+        color_format,
+        title_cards,
+        metadata,
+        loop_blend,
+        audio_copy: audio_copy.unwrap_or(false),
+        source_audio_codec,
+        resumable: resumable.unwrap_or(false),
+    };
+    std::thread::Builder::new()
+        .name("nsc-export".to_string())
+        .spawn(move || run_export_job(status_state, job))
+        .map_err(|e| format!("Failed to spawn export thread: {e}"))?;
+
+    Ok(())
+}
+
+/// Runs an export to completion on the calling thread, for callers without a Tauri runtime.
+///
+/// `on_progress` sees every status change; returns the output path, or the error message
+/// when the export failed or was cancelled.
+pub(crate) fn run_export_blocking(
+    project_path: &str,
+    request: ExportRequest,
+    mut on_progress: impl FnMut(&ExportStatus),
+) -> Result<String, String> {
+    let status_state = Arc::new(Mutex::new(ExportStatus::default()));
+    spawn_export_job(status_state.clone(), project_path, request)?;
+
+    let mut last_message = String::new();
+    loop {
+        std::thread::sleep(std::time::Duration::from_millis(250));
+        let status = status_state
+            .lock()
+            .map_err(|_| "Failed to access export status".to_string())?
             .clone();
-        dedup.push(TargetPoint { ts: end_ts, rect });
+        if status.message != last_message {
+            last_message = status.message.clone();
+            on_progress(&status);
+        }
+        if status.is_running {
+            continue;
+        }
+        return match (status.error, status.output_path) {
+            (Some(err), _) => Err(err),
+            (None, Some(path)) if status.progress >= 1.0 => Ok(path),
+            _ => Err(status.message),
+        };
     }
+}
 
-    dedup
+/// Everything an export run needs, resolved once from the `ExportRequest`, the project
+/// and the source probe before the export thread starts.
+struct ExportJob {
+    source_video: PathBuf,
+    outputs: Vec<ExportOutput>,
+    project: Project,
+    events: Option<EventsFile>,
+    chapters_metadata: Option<PathBuf>,
+    music: Option<MusicBed>,
+    source_has_audio: bool,
+    width: u32,
+    height: u32,
+    fps: u32,
+    codec: String,
+    source_duration_ms: u64,
+    source_width: u32,
+    source_height: u32,
+    source_crop: Option<SourceCrop>,
+    source_fps: Option<f64>,
+    source_is_vfr: bool,
+    threads: Option<u32>,
+    color_format: ExportColorFormat,
+    title_cards: TitleCards,
+    metadata: Vec<(&'static str, String)>,
+    loop_blend: Option<LoopBlend>,
+    /// Copy the source audio stream instead of re-encoding it, when nothing edits it.
+    audio_copy: bool,
+    source_audio_codec: Option<String>,
+    resumable: bool,
 }
 
-fn target_points_from_legacy_pan(
-    segment: &ZoomSegment,
-    base_rect: &NormalizedRect,
-) -> Vec<TargetPoint> {
-    let mut pan_trajectory = segment.pan_trajectory.clone();
-    pan_trajectory.sort_by_key(|keyframe| keyframe.ts);
+fn run_export_job(status_state: Arc<Mutex<ExportStatus>>, job: ExportJob) {
+    let ExportJob {
+        source_video,
+        outputs,
+        project,
+        events,
+        chapters_metadata,
+        music,
+        source_has_audio,
+        width,
+        height,
+        fps,
+        codec,
+        source_duration_ms,
+        source_width,
+        source_height,
+        source_crop,
+        source_fps,
+        source_is_vfr,
+        threads,
+        color_format,
+        title_cards,
+        metadata,
+        loop_blend,
+        audio_copy,
+        source_audio_codec,
+        resumable,
+    } = job;
+    let output_video = outputs[0].path.clone();
+    // A static crop redefines the source frame: everything below works in cropped space.
+    let (project, events, source_width, source_height) = match source_crop {
+        Some(crop) => (
+            crop.project_into(project),
+            events.map(|events| crop.events_into(events)),
+            crop.width,
+            crop.height,
+        ),
+        None => (project, events, source_width, source_height),
+    };
 
-    if pan_trajectory.is_empty() {
-        return vec![
-            TargetPoint {
-                ts: segment.start_ts,
-                rect: base_rect.clone(),
-            },
-            TargetPoint {
-                ts: segment.end_ts,
-                rect: base_rect.clone(),
-            },
-        ];
+    let time_remap = project
+        .settings
+        .export
+        .click_slow_motion
+        .as_ref()
+        .and_then(|slow_motion| {
+            build_click_time_remap(
+                slow_motion,
+                events.as_ref(),
+                &project.settings.cursor.hidden_ranges,
+                project.duration_ms.max(1),
+                source_duration_ms,
+            )
+        });
+    let (min_offset_ms, max_offset_ms) = AUDIO_OFFSET_MS_RANGE;
+    let audio_offset_ms = project
+        .settings
+        .export
+        .audio_offset_ms
+        .clamp(min_offset_ms, max_offset_ms);
+    let has_audio_edits = music.is_some() || time_remap.is_some() || audio_offset_ms != 0;
+
+    let passthrough = if outputs.len() > 1 {
+        Some("multiple renditions")
+    } else if !color_format.is_default() {
+        Some("custom pixel format")
+    } else if !title_cards.is_empty() {
+        Some("title cards")
+    } else if loop_blend.is_some() {
+        Some("seamless loop")
+    } else {
+        passthrough_blocker(
+            &project,
+            events.as_ref(),
+            source_crop.is_some(),
+            has_audio_edits,
+            (width, height, fps),
+            &codec,
+            &output_video,
+            (source_width, source_height),
+            source_fps,
+            source_is_vfr,
+        )
+    };
+    match passthrough {
+        None => {
+            log::info!("run_export_job: nothing to render, copying source streams");
+            update_status(&status_state, |status| {
+                status.message = "Copying source video".to_string();
+            });
+            let result = execute_passthrough_export(
+                &status_state,
+                &source_video,
+                chapters_metadata.as_deref(),
+                &metadata,
+                &output_video,
+                fps,
+                source_duration_ms,
+            );
+            let result = result
+                .and_then(|()| verify_export_outputs(&status_state, &outputs, source_duration_ms));
+            finish_export_job(&status_state, &output_video, result);
+            return;
+        }
+        Some(reason) => log::info!("run_export_job: full render ({reason})"),
     }
 
-    let (start_offset_x, start_offset_y) = pan_offset_at_ts(&pan_trajectory, segment.start_ts);
-    let mut points = vec![TargetPoint {
-        ts: segment.start_ts,
-        rect: apply_pan_offset(base_rect, start_offset_x, start_offset_y),
-    }];
+    let chunk_blocker = |has_cursor_image: bool| {
+        checkpoint_blocker(
+            outputs.len(),
+            has_audio_edits,
+            !title_cards.is_empty() || loop_blend.is_some(),
+            has_cursor_image,
+            source_duration_ms,
+        )
+    };
+    // The cursor image input is only known once the graph is built; it is checked below.
+    let chunkable = resumable && chunk_blocker(false).is_none();
 
-    for keyframe in &pan_trajectory {
-        if keyframe.ts < segment.start_ts || keyframe.ts > segment.end_ts {
-            continue;
+    // Chunks start from a seeked input, which only the timestamp camera clock survives.
+    let filter_build = build_export_filter_graph(
+        &project,
+        events.as_ref(),
+        width,
+        height,
+        fps,
+        source_duration_ms,
+        source_width,
+        source_height,
+        source_crop,
+        source_is_vfr || chunkable,
+    );
+
+    let (filter_graph, cursor_image_input, temp_files) = match filter_build {
+        Ok(result) => result,
+        Err(err) => {
+            update_status(&status_state, |status| {
+                status.is_running = false;
+                status.finished_at_ms = Some(now_ms());
+                status.message = "Export failed".to_string();
+                status.error = Some(err);
+            });
+            return;
         }
-        points.push(TargetPoint {
-            ts: keyframe.ts,
-            rect: apply_pan_offset(base_rect, keyframe.offset_x, keyframe.offset_y),
-        });
+    };
+
+    let checkpoint_key = if resumable {
+        match chunk_blocker(cursor_image_input.is_some()) {
+            None => Some(export_checkpoint_key(
+                &project,
+                events.as_ref(),
+                &source_video,
+                (width, height, fps),
+                &codec,
+                color_format,
+                source_crop,
+            )),
+            Some(reason) => {
+                log::info!("run_export_job: rendering in one pass ({reason})");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Chapters are stored on the source timeline; slow motion moves everything after a
+    // slowed range later in the output.
+    let remapped_chapters = match (&chapters_metadata, &time_remap) {
+        (Some(path), Some(remap)) => match remap_chapters_file(path, remap) {
+            Ok(remapped) => Some(remapped),
+            Err(err) => {
+                log::warn!("run_export_job: dropping chapters: {err}");
+                None
+            }
+        },
+        _ => None,
+    };
+    let chapters_metadata = if time_remap.is_some() {
+        remapped_chapters.clone()
+    } else {
+        chapters_metadata
+    };
+
+    // With title cards the recording is rendered next to the final file and joined with
+    // the cards afterwards; chapters are attached by the join, shifted past the intro.
+    let body_outputs = if title_cards.is_empty() {
+        outputs.clone()
+    } else {
+        outputs
+            .iter()
+            .map(|output| ExportOutput {
+                path: title_card_part_path(&output.path, "body"),
+                ..output.clone()
+            })
+            .collect::<Vec<_>>()
+    };
+    let has_audio = source_has_audio || music.is_some();
+    let audio_copy = audio_copy
+        && source_has_audio
+        && match audio_copy_blocker(
+            source_audio_codec.as_deref(),
+            &output_video,
+            has_audio_edits || loop_blend.is_some() || !title_cards.is_empty(),
+        ) {
+            None => true,
+            Some(reason) => {
+                log::info!("run_export_job: re-encoding audio ({reason})");
+                false
+            }
+        };
+    let mut result = match checkpoint_key {
+        Some(key) => execute_checkpointed_export(
+            &status_state,
+            &source_video,
+            chapters_metadata.as_deref(),
+            &metadata,
+            &outputs[0],
+            &filter_graph,
+            &codec,
+            threads,
+            color_format,
+            audio_copy,
+            fps,
+            source_duration_ms,
+            (&project.id, key),
+        ),
+        None => execute_ffmpeg_export(
+            &status_state,
+            FfmpegExportOptions {
+                source_video: &source_video,
+                cursor_image: cursor_image_input.as_deref(),
+                chapters_metadata: chapters_metadata
+                    .as_deref()
+                    .filter(|_| title_cards.is_empty()),
+                metadata: &metadata,
+                music: music.as_ref(),
+                source_has_audio,
+                time_remap: time_remap.as_ref(),
+                audio_offset_ms,
+                loop_blend: loop_blend.as_ref(),
+                outputs: &body_outputs,
+                filter_graph: &filter_graph,
+                frame_background: &project.settings.frame_background,
+                codec: &codec,
+                threads,
+                color_format,
+                title_card_audio: !title_cards.is_empty(),
+                audio_copy,
+                output_limit_ms: None,
+                target_fps: fps,
+                source_duration_ms,
+            },
+        ),
+    };
+
+    for path in temp_files {
+        let _ = std::fs::remove_file(path);
     }
 
-    let (end_offset_x, end_offset_y) = pan_offset_at_ts(&pan_trajectory, segment.end_ts);
-    points.push(TargetPoint {
-        ts: segment.end_ts,
-        rect: apply_pan_offset(base_rect, end_offset_x, end_offset_y),
+    let body_duration_ms = time_remap.as_ref().map_or(source_duration_ms, |remap| {
+        remap.output_duration_ms(source_duration_ms)
     });
-    points
-}
+    if !title_cards.is_empty() {
+        if result.is_ok() {
+            result = attach_title_cards(
+                &status_state,
+                &title_cards,
+                &body_outputs,
+                &outputs,
+                chapters_metadata.as_deref(),
+                &metadata,
+                &codec,
+                threads,
+                color_format,
+                has_audio,
+                fps,
+                body_duration_ms,
+            );
+        }
+        for body in &body_outputs {
+            let _ = std::fs::remove_file(&body.path);
+        }
+    }
+    if let Some(path) = remapped_chapters {
+        let _ = std::fs::remove_file(path);
+    }
 
-fn resolve_runtime_segment<'a>(
-    segments: &'a [SegmentRuntime],
-    ts: u64,
-) -> Option<&'a SegmentRuntime> {
-    segments
-        .iter()
-        .rev()
-        .find(|segment| ts >= segment.start_ts && ts < segment.end_ts)
+    let expected_duration_ms = title_cards
+        .intro
+        .as_ref()
+        .map_or(0, |card| card.duration_ms)
+        + body_duration_ms
+        - loop_blend
+            .as_ref()
+            .and_then(|blend| blend.crossfade_ms_for(body_duration_ms))
+            .unwrap_or(0)
+        + title_cards
+            .outro
+            .as_ref()
+            .map_or(0, |card| card.duration_ms);
+    let result =
+        result.and_then(|()| verify_export_outputs(&status_state, &outputs, expected_duration_ms));
+    finish_export_job(&status_state, &output_video, result);
 }
 
-fn target_rect_at_ts(segment: &SegmentRuntime, ts: u64) -> NormalizedRect {
-    if segment.target_points.is_empty() {
-        return segment.base_rect.clone();
-    }
-    if ts <= segment.target_points[0].ts {
-        return segment.target_points[0].rect.clone();
-    }
-    for point in segment.target_points.iter().rev() {
-        if ts >= point.ts {
-            return point.rect.clone();
+/// Probes every output of an export that FFmpeg reported as successful.
+///
+/// A missing, empty or undecodable file fails the export; a duration or bitrate that is
+/// off only adds warnings, since the file may still be usable.
+fn verify_export_outputs(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    outputs: &[ExportOutput],
+    expected_duration_ms: u64,
+) -> Result<(), String> {
+    update_status(status_state, |status| {
+        status.message = "Verifying export".to_string();
+    });
+    let mut verifications = Vec::with_capacity(outputs.len());
+    for output in outputs {
+        let file_size_bytes = std::fs::metadata(&output.path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        if file_size_bytes == 0 {
+            return Err(format!(
+                "Export produced no data: {}",
+                output.path.display()
+            ));
+        }
+        let probe = probe_media_info(&output.path);
+        if probe.width.is_none() {
+            return Err(format!(
+                "Exported file has no readable video stream: {}",
+                output.path.display()
+            ));
+        }
+        let verification = check_export_output(
+            &output.path,
+            file_size_bytes,
+            probe.duration_ms,
+            expected_duration_ms,
+        );
+        for issue in &verification.issues {
+            log::warn!("verify_export_outputs: {}: {issue}", output.path.display());
         }
+        verifications.push(verification);
     }
-    segment.target_points[0].rect.clone()
+    update_status(status_state, |status| {
+        status.warnings.extend(
+            verifications
+                .iter()
+                .flat_map(|verification| verification.issues.iter().cloned()),
+        );
+        status.verification = verifications;
+    });
+    Ok(())
 }
 
-fn default_spring_params() -> SpringParams {
-    SpringParams {
-        mass: DEFAULT_SPRING_MASS,
-        stiffness: DEFAULT_SPRING_STIFFNESS,
-        damping: DEFAULT_SPRING_DAMPING,
+fn check_export_output(
+    path: &Path,
+    file_size_bytes: u64,
+    duration_ms: Option<u64>,
+    expected_duration_ms: u64,
+) -> ExportVerification {
+    let mut issues = Vec::new();
+    let bitrate_kbps = duration_ms
+        .filter(|duration_ms| *duration_ms > 0)
+        .map(|duration_ms| file_size_bytes as f64 * 8.0 / duration_ms as f64);
+    match duration_ms {
+        None => issues.push("Exported file reports no duration".to_string()),
+        Some(duration_ms) => {
+            let tolerance_ms = VERIFY_DURATION_TOLERANCE_MS
+                .max((expected_duration_ms as f64 * VERIFY_DURATION_TOLERANCE_RATIO) as u64);
+            if duration_ms.abs_diff(expected_duration_ms) > tolerance_ms {
+                issues.push(format!(
+                    "Exported duration {:.1}s differs from the expected {:.1}s",
+                    duration_ms as f64 / 1000.0,
+                    expected_duration_ms as f64 / 1000.0
+                ));
+            }
+        }
     }
-}
-
-fn normalize_spring_params(spring: &CameraSpring) -> SpringParams {
-    SpringParams {
-        mass: spring.mass.max(0.001),
-        stiffness: spring.stiffness.max(0.001),
-        damping: spring.damping.max(0.0),
+    if let Some(kbps) = bitrate_kbps.filter(|kbps| *kbps < MIN_PLAUSIBLE_EXPORT_KBPS) {
+        issues.push(format!(
+            "Exported bitrate {kbps:.0} kbps is implausibly low; the file may be missing frames"
+        ));
     }
-}
-
-fn evaluate_spring_axis(
-    state: AxisSpringState,
-    target: f64,
-    spring: SpringParams,
-    dt_seconds: f64,
-) -> AxisSpringState {
-    let dt = dt_seconds.max(0.0);
-    if dt <= 0.0 {
-        return state;
+    ExportVerification {
+        path: path.to_string_lossy().to_string(),
+        file_size_bytes,
+        duration_ms,
+        expected_duration_ms,
+        bitrate_kbps,
+        issues,
     }
+}
 
-    let mass = spring.mass.max(0.001);
-    let stiffness = spring.stiffness.max(0.001);
-    let damping = spring.damping.max(0.0);
-    let y0 = state.value - target;
-    let v0 = state.velocity;
-    let alpha = damping / (2.0 * mass);
-    let omega_sq = stiffness / mass;
-    let discriminant = alpha * alpha - omega_sq;
+fn finish_export_job(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    output_video: &Path,
+    result: Result<(), String>,
+) {
+    update_status(status_state, |status| {
+        status.is_running = false;
+        status.finished_at_ms = Some(now_ms());
+        match result {
+            Ok(()) => {
+                status.progress = 1.0;
+                status.message = "Export finished".to_string();
+                status.output_path = Some(output_video.to_string_lossy().to_string());
+                status.error = None;
+            }
+            Err(err) => {
+                if err == EXPORT_CANCELLED_SENTINEL {
+                    status.message = "Export cancelled".to_string();
+                    status.error = None;
+                } else {
+                    status.message = "Export failed".to_string();
+                    status.error = Some(err);
+                }
+            }
+        }
+    });
 
-    let (y, v) = if discriminant.abs() <= 1e-9 {
-        let c2 = v0 + alpha * y0;
-        let exp = (-alpha * dt).exp();
-        let y = (y0 + c2 * dt) * exp;
-        let v = (v0 - alpha * c2 * dt) * exp;
-        (y, v)
-    } else if discriminant > 0.0 {
-        let sqrt_disc = discriminant.sqrt();
-        let r1 = -alpha + sqrt_disc;
-        let r2 = -alpha - sqrt_disc;
-        let denom = (r1 - r2).abs().max(1e-9);
-        let c1 = (v0 - r2 * y0) / denom;
-        let c2 = y0 - c1;
-        let exp1 = (r1 * dt).exp();
-        let exp2 = (r2 * dt).exp();
-        let y = c1 * exp1 + c2 * exp2;
-        let v = c1 * r1 * exp1 + c2 * r2 * exp2;
-        (y, v)
-    } else {
-        let beta = (omega_sq - alpha * alpha).max(1e-9).sqrt();
-        let c1 = y0;
-        let c2 = (v0 + alpha * y0) / beta;
-        let exp = (-alpha * dt).exp();
-        let cos = (beta * dt).cos();
-        let sin = (beta * dt).sin();
-        let y = exp * (c1 * cos + c2 * sin);
-        let v = exp * ((-alpha) * (c1 * cos + c2 * sin) + (-c1 * beta * sin + c2 * beta * cos));
-        (y, v)
-    };
+    EXPORT_CANCEL_REQUESTED.store(false, Ordering::Relaxed);
+}
 
-    AxisSpringState {
-        value: target + y,
-        velocity: v,
-    }
+/// Static export crop in source pixels, aligned to the even grid of 4:2:0 video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SourceCrop {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    source_width: u32,
+    source_height: u32,
 }
 
-fn build_camera_value_expr(
-    states: &[CameraState],
-    axis: impl Fn(&CameraState) -> AxisSpringSegment + Copy,
-    default_value: f64,
-    source_fps: f64,
-) -> String {
-    let mut ordered = states.to_vec();
-    ordered.sort_by(|left, right| {
-        left.start_frame
-            .total_cmp(&right.start_frame)
-            .then_with(|| left.end_frame.total_cmp(&right.end_frame))
-    });
-    let safe_fps = source_fps.max(1.0);
+impl SourceCrop {
+    /// Converts the normalized crop to pixels; `None` when it keeps the whole frame.
+    fn from_rect(rect: &NormalizedRect, source_width: u32, source_height: u32) -> Option<Self> {
+        let rect = normalize_segment_rect(rect.clone());
+        let axis = |start: f64, size: f64, source_size: u32| {
+            let source = source_size as f64;
+            let start = ((start * source) as u32) & !1;
+            let size = (((size * source).round() as u32) & !1)
+                .max(2)
+                .min(source_size - start);
+            (start, size)
+        };
+        let (x, width) = axis(rect.x, rect.width, source_width);
+        let (y, height) = axis(rect.y, rect.height, source_height);
+        let crop = Self {
+            x,
+            y,
+            width,
+            height,
+            source_width,
+            source_height,
+        };
+        (crop.width < source_width || crop.height < source_height).then_some(crop)
+    }
 
-    if ordered.len() > MAX_CAMERA_STATES_FOR_ANALYTIC_EXPR {
-        let sampled = sample_camera_value_points(&ordered, axis, default_value, safe_fps);
-        let duration_ms = sampled.last().map(|(ts, _)| *ts).unwrap_or(0);
-        let max_points = adaptive_sample_budget(
-            duration_ms,
-            MAX_CAMERA_POINTS_FOR_EXPR,
-            MAX_CAMERA_POINTS_FOR_EXPR_HARD_CAP,
-            CAMERA_POINTS_BUDGET_GROWTH_PER_SEC,
-            sampled.len(),
-        );
-        let reduced = decimate_time_value_points(&sampled, max_points);
-        return build_piecewise_track_expr(&reduced, duration_ms);
+    fn filter(&self) -> String {
+        format!("crop={}:{}:{}:{}", self.width, self.height, self.x, self.y)
     }
 
-    let default_expr = format_f64(default_value);
-    let mut terms = Vec::with_capacity(ordered.len() + 1);
-    terms.push(default_expr.clone());
+    /// Maps a normalized point of the full frame into the crop.
+    fn map_normalized(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            (x * self.source_width as f64 - self.x as f64) / self.width as f64,
+            (y * self.source_height as f64 - self.y as f64) / self.height as f64,
+        )
+    }
 
-    for state in ordered {
-        let axis_state = axis(&state);
-        let elapsed = format!(
-            "max(0,(n-{start})/{fps})",
-            start = format_f64(state.start_frame),
-            fps = format_f64(safe_fps)
-        );
-        let value = spring_value_expr(&elapsed, axis_state, state.spring);
+    fn map_rect(&self, rect: &NormalizedRect) -> NormalizedRect {
+        let (x, y) = self.map_normalized(rect.x, rect.y);
+        normalize_segment_rect(NormalizedRect {
+            x,
+            y,
+            width: rect.width * self.source_width as f64 / self.width as f64,
+            height: rect.height * self.source_height as f64 / self.height as f64,
+        })
+    }
 
-        // Build a flat sum of disjoint interval terms instead of deeply nested if-expressions.
-        // Nested expressions can exceed FFmpeg parser depth on projects with many segments.
-        terms.push(format!(
-            "if(gte(n,{start})*lt(n,{end}),({value})-({default}),0)",
-            start = format_f64(state.start_frame),
-            end = format_f64(state.end_frame),
-            value = value,
-            default = default_expr
-        ));
+    /// Re-expresses zoom segments relative to the crop.
+    fn project_into(&self, mut project: Project) -> Project {
+        let scale_x = self.source_width as f64 / self.width as f64;
+        let scale_y = self.source_height as f64 / self.height as f64;
+        for segment in &mut project.timeline.zoom_segments {
+            segment.initial_rect = self.map_rect(&segment.initial_rect);
+            // Explicit zooms are relative to the full frame, so they are folded into the rect.
+            for point in &mut segment.target_points {
+                point.rect = self.map_rect(&point.effective_rect());
+                point.zoom = None;
+            }
+            for keyframe in &mut segment.pan_trajectory {
+                keyframe.offset_x *= scale_x;
+                keyframe.offset_y *= scale_y;
+            }
+        }
+        project
     }
 
-    terms.join("+")
+    /// Re-expresses pointer positions relative to the crop, keeping the screen size.
+    fn events_into(&self, mut events: EventsFile) -> EventsFile {
+        let screen_width = events.screen_width.max(1) as f64;
+        let screen_height = events.screen_height.max(1) as f64;
+        for event in &mut events.events {
+            match event {
+                InputEvent::Move { x, y, .. }
+                | InputEvent::Click { x, y, .. }
+                | InputEvent::MouseUp { x, y, .. }
+                | InputEvent::Scroll { x, y, .. } => {
+                    let (nx, ny) = self.map_normalized(*x / screen_width, *y / screen_height);
+                    *x = nx * screen_width;
+                    *y = ny * screen_height;
+                }
+                InputEvent::KeyDown { .. } | InputEvent::KeyUp { .. } => {}
+            }
+        }
+        events
+    }
 }
 
-fn sample_camera_value_points(
-    states: &[CameraState],
-    axis: impl Fn(&CameraState) -> AxisSpringSegment + Copy,
-    default_value: f64,
-    source_fps: f64,
-) -> Vec<(u64, f64)> {
-    if states.is_empty() {
-        return vec![(0, default_value)];
+/// Returns why the export needs the full filter pipeline, or `None` when the output would
+/// be identical to the source and a stream copy can replace the re-encode.
+///
+/// Recordings are H.264, so the copy is only valid for an `h264` export into an MP4/MOV
+/// container at the source size and frame rate, with no zoom, cursor overlay or audio edits.
+fn passthrough_blocker(
+    project: &Project,
+    events: Option<&EventsFile>,
+    has_crop: bool,
+    has_audio_edits: bool,
+    (target_width, target_height, target_fps): (u32, u32, u32),
+    codec: &str,
+    output_video: &Path,
+    (source_width, source_height): (u32, u32),
+    source_fps: Option<f64>,
+    source_is_vfr: bool,
+) -> Option<&'static str> {
+    let is_full_frame = |rect: &NormalizedRect| {
+        rect.x <= 1e-6 && rect.y <= 1e-6 && rect.width >= 1.0 - 1e-6 && rect.height >= 1.0 - 1e-6
+    };
+    let camera_moves = project.timeline.zoom_segments.iter().any(|segment| {
+        !is_full_frame(&segment.initial_rect)
+            || segment
+                .target_points
+                .iter()
+                .any(|point| !is_full_frame(&point.effective_rect()))
+            || segment
+                .pan_trajectory
+                .iter()
+                .any(|keyframe| keyframe.offset_x != 0.0 || keyframe.offset_y != 0.0)
+    });
+    if camera_moves {
+        return Some("zoom segments");
     }
-
-    let safe_fps = source_fps.max(1.0);
-    let max_frame = states
+    if project
+        .timeline
+        .zoom_segments
         .iter()
-        .map(|state| state.end_frame)
-        .fold(0.0, f64::max)
-        .ceil()
-        .max(1.0);
-    let step_frames = (safe_fps / CAMERA_FALLBACK_SAMPLE_RATE_HZ.max(1.0)).max(1.0);
-
-    let mut points: Vec<(u64, f64)> = Vec::new();
-    let mut frame = 0.0;
-    while frame <= max_frame {
-        let ts_ms = ((frame / safe_fps) * 1000.0).round().max(0.0) as u64;
-        let value = sample_camera_axis_value(states, frame, safe_fps, axis, default_value);
-        points.push((ts_ms, value));
-        frame += step_frames;
+        .any(|segment| segment.label.is_some())
+    {
+        return Some("segment labels");
+    }
+    if has_crop {
+        return Some("export crop");
     }
 
-    let last_ts_ms = ((max_frame / safe_fps) * 1000.0).round().max(0.0) as u64;
-    if points
-        .last()
-        .map(|(ts, _)| *ts != last_ts_ms)
-        .unwrap_or(true)
-    {
-        let value = sample_camera_axis_value(states, max_frame, safe_fps, axis, default_value);
-        points.push((last_ts_ms, value));
+    let show_cursor = project.settings.cursor.show_cursor
+        && project.settings.cursor.render_mode != CursorRenderMode::Off;
+    let has_cursor_events = events.is_some_and(|events| !events.events.is_empty());
+    if show_cursor && (ENABLE_CUSTOM_CURSOR_OVERLAY_EXPORT || has_cursor_events) {
+        return Some("cursor overlay");
+    }
+    if has_audio_edits {
+        return Some("audio edits");
     }
+    if codec != "h264" {
+        return Some("codec change");
+    }
+    let container_ok = output_video
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            matches!(
+                extension.to_ascii_lowercase().as_str(),
+                "mp4" | "mov" | "m4v"
+            )
+        });
+    if !container_ok {
+        return Some("output container");
+    }
+    if (target_width, target_height) != (source_width, source_height) {
+        return Some("resolution change");
+    }
+    if source_is_vfr {
+        return Some("variable frame rate source");
+    }
+    let fps_matches = source_fps.is_some_and(|source_fps| {
+        (source_fps - target_fps as f64).abs() <= target_fps as f64 * VFR_RATE_MISMATCH_RATIO
+    });
+    if !fps_matches {
+        return Some("frame rate change");
+    }
+    None
+}
 
-    points.sort_by_key(|(ts, _)| *ts);
-    points.dedup_by(|left, right| left.0 == right.0);
-    points
+/// Returns why the source audio has to be re-encoded, or `None` when `-c:a copy` can carry
+/// it into the output unchanged.
+///
+/// A copy skips the audio filter graph, so it needs an unedited track whose codec the
+/// output container can hold.
+fn audio_copy_blocker(
+    source_audio_codec: Option<&str>,
+    output_video: &Path,
+    has_audio_edits: bool,
+) -> Option<&'static str> {
+    if has_audio_edits {
+        return Some("audio edits");
+    }
+    let Some(codec) = source_audio_codec else {
+        return Some("unknown source audio codec");
+    };
+    let extension = output_video
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let container_codecs: &[&str] = match extension.as_str() {
+        "mp4" | "m4v" | "mov" => &["aac", "mp3", "alac", "ac3", "eac3"],
+        "mkv" => &[
+            "aac", "mp3", "opus", "vorbis", "flac", "alac", "ac3", "eac3",
+        ],
+        "webm" => &["opus", "vorbis"],
+        _ => return Some("output container"),
+    };
+    if !container_codecs.contains(&codec) {
+        return Some("audio codec not supported by the container");
+    }
+    None
 }
 
-fn spring_value_expr(
-    elapsed_expr: &str,
-    axis_state: AxisSpringSegment,
-    spring: SpringParams,
-) -> String {
-    let mass = spring.mass.max(0.001);
-    let stiffness = spring.stiffness.max(0.001);
-    let damping = spring.damping.max(0.0);
-    let y0 = axis_state.start - axis_state.target;
-    let v0 = axis_state.velocity;
-    let alpha = damping / (2.0 * mass);
-    let omega_sq = stiffness / mass;
-    let discriminant = alpha * alpha - omega_sq;
+/// Inputs and encoder settings of one full FFmpeg render.
+struct FfmpegExportOptions<'a> {
+    source_video: &'a Path,
+    cursor_image: Option<&'a Path>,
+    chapters_metadata: Option<&'a Path>,
+    metadata: &'a [(&'static str, String)],
+    music: Option<&'a MusicBed>,
+    source_has_audio: bool,
+    time_remap: Option<&'a TimeRemap>,
+    audio_offset_ms: i64,
+    loop_blend: Option<&'a LoopBlend>,
+    outputs: &'a [ExportOutput],
+    filter_graph: &'a str,
+    frame_background: &'a FrameBackground,
+    codec: &'a str,
+    threads: Option<u32>,
+    color_format: ExportColorFormat,
+    /// Resamples the audio to the title cards' format so the parts can be joined.
+    title_card_audio: bool,
+    audio_copy: bool,
+    /// Stops the render early, e.g. for a benchmark.
+    output_limit_ms: Option<u64>,
+    target_fps: u32,
+    source_duration_ms: u64,
+}
 
-    if discriminant.abs() <= 1e-9 {
-        let c2 = v0 + alpha * y0;
-        return format!(
-            "{target}+(({y0})+({c2})*({t}))*exp(-{alpha}*({t}))",
-            target = format_f64(axis_state.target),
-            y0 = format_f64(y0),
-            c2 = format_f64(c2),
-            alpha = format_f64(alpha),
-            t = elapsed_expr
+fn execute_ffmpeg_export(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    options: FfmpegExportOptions<'_>,
+) -> Result<(), String> {
+    let FfmpegExportOptions {
+        source_video,
+        cursor_image,
+        chapters_metadata,
+        metadata,
+        music,
+        source_has_audio,
+        time_remap,
+        audio_offset_ms,
+        loop_blend,
+        outputs,
+        filter_graph,
+        frame_background,
+        codec,
+        threads,
+        color_format,
+        title_card_audio,
+        audio_copy,
+        output_limit_ms,
+        target_fps,
+        source_duration_ms,
+    } = options;
+    let mut filter_graph = filter_graph.to_string();
+    let mut source_audio = source_has_audio.then(|| "0:a".to_string());
+    let mut audio_map = "0:a?".to_string();
+    // Progress is reported against the output, which slow motion makes longer.
+    let output_duration_ms = time_remap.map_or(source_duration_ms, |remap| {
+        remap.output_duration_ms(source_duration_ms)
+    });
+    // The loop blend overlaps the clip's start with its end, shortening it by one crossfade.
+    let loop_crossfade_ms = loop_blend.and_then(|blend| blend.crossfade_ms_for(output_duration_ms));
+    let looped_duration_ms = output_duration_ms - loop_crossfade_ms.unwrap_or(0);
+    let progress_duration_ms =
+        output_limit_ms.map_or(looped_duration_ms, |limit| limit.min(looped_duration_ms));
+    if audio_offset_ms != 0 && source_audio.is_some() {
+        filter_graph.push(';');
+        filter_graph.push_str(&audio_offset_filter(
+            "0:a",
+            "ashift",
+            audio_offset_ms,
+            source_duration_ms,
+        ));
+        source_audio = Some("ashift".to_string());
+        audio_map = "[ashift]".to_string();
+    }
+    if let Some(remap) = time_remap {
+        let composed = filter_graph
+            .strip_suffix("[vout]")
+            .ok_or("Export filter graph does not end with [vout]")?;
+        filter_graph = format!(
+            "{composed}[vcomposed];{}",
+            remap.video_filter("vcomposed", "vout", source_duration_ms, target_fps)
+        );
+        if source_audio.is_some() {
+            filter_graph.push(';');
+            let input = source_audio.as_deref().unwrap_or("0:a");
+            filter_graph.push_str(&remap.audio_filter(input, "aslow", source_duration_ms));
+            source_audio = Some("aslow".to_string());
+            audio_map = "[aslow]".to_string();
+        }
+    }
+    if let Some(crossfade_ms) = loop_crossfade_ms {
+        let composed = filter_graph
+            .strip_suffix("[vout]")
+            .ok_or("Export filter graph does not end with [vout]")?;
+        filter_graph = format!(
+            "{composed}[vunlooped];{}",
+            loop_video_filter("vunlooped", "vout", output_duration_ms, crossfade_ms)
         );
     }
+    if let Some(color_filter) = color_format.video_filter() {
+        let composed = filter_graph
+            .strip_suffix("[vout]")
+            .ok_or("Export filter graph does not end with [vout]")?;
+        filter_graph = format!("{composed}[vpix];[vpix]{color_filter}[vout]");
+    }
 
-    if discriminant > 0.0 {
-        let sqrt_disc = discriminant.sqrt();
-        let r1 = -alpha + sqrt_disc;
-        let r2 = -alpha - sqrt_disc;
-        let denom = (r1 - r2).abs().max(1e-9);
-        let c1 = (v0 - r2 * y0) / denom;
-        let c2 = y0 - c1;
-        return format!(
-            "{target}+({c1})*exp({r1}*({t}))+({c2})*exp({r2}*({t}))",
-            target = format_f64(axis_state.target),
-            c1 = format_f64(c1),
-            c2 = format_f64(c2),
-            r1 = format_f64(r1),
-            r2 = format_f64(r2),
-            t = elapsed_expr
-        );
+    // Inputs: source, then the optional cursor image, music and chapters metadata.
+    let music_input_index = music.map(|_| 1 + usize::from(cursor_image.is_some()));
+    if let (Some(music), Some(index)) = (music, music_input_index) {
+        filter_graph.push(';');
+        filter_graph.push_str(&build_music_filter(
+            index,
+            source_audio.as_deref(),
+            music,
+            output_duration_ms,
+        ));
+        audio_map = "[aout]".to_string();
+    }
+    let mixed_audio = if music.is_some() {
+        Some("aout")
+    } else {
+        source_audio.as_deref()
+    };
+    if let (Some(crossfade_ms), Some(input)) = (loop_crossfade_ms, mixed_audio) {
+        filter_graph.push(';');
+        filter_graph.push_str(&loop_audio_filter(input, "aloop", crossfade_ms));
+        audio_map = "[aloop]".to_string();
     }
+    let (rendition_filter, output_maps) =
+        build_rendition_split(outputs, &audio_map, frame_background, target_fps);
+    filter_graph.push_str(&rendition_filter);
+    let filter_script_path = std::env::temp_dir().join(format!("nsc-filter-{}.txt", now_ms()));
+    std::fs::write(&filter_script_path, &filter_graph).map_err(|e| {
+        format!(
+            "Failed to write temporary FFmpeg filter script {}: {e}",
+            filter_script_path.display()
+        )
+    })?;
 
-    let beta = (omega_sq - alpha * alpha).max(1e-9).sqrt();
-    let c1 = y0;
-    let c2 = (v0 + alpha * y0) / beta;
-    format!(
-        "{target}+exp(-{alpha}*({t}))*(({c1})*cos({beta}*({t}))+({c2})*sin({beta}*({t})))",
-        target = format_f64(axis_state.target),
-        alpha = format_f64(alpha),
-        c1 = format_f64(c1),
-        c2 = format_f64(c2),
-        beta = format_f64(beta),
-        t = elapsed_expr
-    )
-}
+    let ffmpeg = find_ffmpeg_exe();
 
-fn rect_to_camera_values(
-    rect: NormalizedRect,
-    source_width: f64,
-    source_height: f64,
-) -> (f64, f64, f64) {
-    let zoom = (1.0 / rect.width.max(rect.height).max(0.0001)).clamp(1.0, 20.0);
-    let crop_w = (source_width / zoom).clamp(32.0, source_width);
-    let crop_h = (source_height / zoom).clamp(32.0, source_height);
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-stats_period")
+        .arg("0.5")
+        .arg("-progress")
+        .arg("pipe:1")
+        .arg("-i")
+        .arg(source_video);
 
-    let center_x = (rect.x + rect.width / 2.0) * source_width;
-    let center_y = (rect.y + rect.height / 2.0) * source_height;
-    let crop_x = (center_x - crop_w / 2.0).clamp(0.0, (source_width - crop_w).max(0.0));
-    let crop_y = (center_y - crop_h / 2.0).clamp(0.0, (source_height - crop_h).max(0.0));
+    if let Some(cursor_image_path) = cursor_image {
+        command
+            .arg("-loop")
+            .arg("1")
+            .arg("-i")
+            .arg(cursor_image_path);
+    }
 
-    let max_offset_x = (source_width * zoom - source_width).max(0.0);
-    let max_offset_y = (source_height * zoom - source_height).max(0.0);
-    let offset_x = (crop_x * zoom).clamp(0.0, max_offset_x);
-    let offset_y = (crop_y * zoom).clamp(0.0, max_offset_y);
+    if let Some(music) = music {
+        if music.loop_to_video {
+            command.arg("-stream_loop").arg("-1");
+        }
+        command.arg("-i").arg(&music.path);
+    }
 
-    (zoom, offset_x, offset_y)
-}
+    let chapters_input_index = chapters_metadata.map(|metadata_path| {
+        command
+            .arg("-f")
+            .arg("ffmetadata")
+            .arg("-i")
+            .arg(metadata_path);
+        1 + usize::from(cursor_image.is_some()) + usize::from(music.is_some())
+    });
 
-fn normalize_segment_rect(rect: NormalizedRect) -> NormalizedRect {
-    let width = rect.width.clamp(0.001, 1.0);
-    let height = rect.height.clamp(0.001, 1.0);
+    if let Some(threads) = threads {
+        command
+            .arg("-filter_complex_threads")
+            .arg(threads.to_string());
+    }
+    command
+        .arg("-filter_complex_script")
+        .arg(&filter_script_path);
 
-    NormalizedRect {
-        x: rect.x.clamp(0.0, 1.0 - width),
-        y: rect.y.clamp(0.0, 1.0 - height),
-        width,
-        height,
+    // Output options apply to the file that follows them, so each rendition repeats them.
+    for (output, (video_map, audio_map)) in outputs.iter().zip(&output_maps) {
+        command
+            .arg("-map")
+            .arg(video_map)
+            .arg("-map")
+            .arg(audio_map);
+        if let Some(index) = chapters_input_index {
+            command.arg("-map_chapters").arg(index.to_string());
+        }
+        push_metadata_args(&mut command, metadata);
+        if let Err(err) = push_video_codec_args(&mut command, codec, output, threads, color_format)
+        {
+            let _ = std::fs::remove_file(&filter_script_path);
+            return Err(err);
+        }
+        if audio_copy {
+            command.arg("-c:a").arg("copy");
+        } else {
+            command
+                .arg("-c:a")
+                .arg("aac")
+                .arg("-b:a")
+                .arg(format!("{EXPORT_AUDIO_KBPS}k"));
+        }
+        if title_card_audio {
+            push_title_card_audio_args(&mut command);
+        }
+        if let Some(limit) = output_limit_ms {
+            command.arg("-t").arg(format_seconds_arg(limit));
+        }
+        command.arg(&output.path);
     }
-}
 
-fn apply_pan_offset(base_rect: &NormalizedRect, offset_x: f64, offset_y: f64) -> NormalizedRect {
-    let normalized = normalize_segment_rect(base_rect.clone());
-    let x = (normalized.x + offset_x).clamp(0.0, 1.0 - normalized.width);
-    let y = (normalized.y + offset_y).clamp(0.0, 1.0 - normalized.height);
+    let result = run_ffmpeg_with_progress(
+        status_state,
+        command,
+        &ffmpeg,
+        progress_duration_ms,
+        target_fps,
+    );
+    let _ = std::fs::remove_file(&filter_script_path);
+    result
+}
 
-    NormalizedRect {
-        x,
-        y,
-        width: normalized.width,
-        height: normalized.height,
+/// Length of one checkpointed part of a resumable export.
+const CHECKPOINT_CHUNK_MS: u64 = 60_000;
+/// Bumped whenever chunk rendering changes, so checkpoints from older builds are not reused.
+const CHECKPOINT_FORMAT_VERSION: u32 = 1;
+const CHECKPOINT_DIR_NAME: &str = "nsc-export-checkpoints";
+
+/// Returns why a resumable export has to render in one pass, or `None` when it can be
+/// split into checkpointed chunks.
+///
+/// Chunks are video only and the source audio is muxed over their join, so anything that
+/// retimes or mixes audio, or joins other clips, keeps the single pass.
+fn checkpoint_blocker(
+    output_count: usize,
+    has_audio_edits: bool,
+    has_title_cards_or_loop: bool,
+    has_cursor_image: bool,
+    source_duration_ms: u64,
+) -> Option<&'static str> {
+    if source_duration_ms <= CHECKPOINT_CHUNK_MS {
+        Some("shorter than one chunk")
+    } else if output_count > 1 {
+        Some("multiple renditions")
+    } else if has_audio_edits {
+        Some("audio edits")
+    } else if has_title_cards_or_loop {
+        Some("title cards or seamless loop")
+    } else if has_cursor_image {
+        Some("cursor image overlay")
+    } else {
+        None
     }
 }
 
-fn pan_offset_at_ts(pan_trajectory: &[PanKeyframe], ts: u64) -> (f64, f64) {
-    if pan_trajectory.is_empty() {
-        return (0.0, 0.0);
-    }
+/// `(start_ms, end_ms)` of each checkpointed chunk of the source timeline.
+fn checkpoint_chunks(source_duration_ms: u64, chunk_ms: u64) -> Vec<(u64, u64)> {
+    let chunk_ms = chunk_ms.max(1);
+    (0..source_duration_ms.div_ceil(chunk_ms))
+        .map(|index| {
+            let start = index * chunk_ms;
+            (start, (start + chunk_ms).min(source_duration_ms))
+        })
+        .collect()
+}
 
-    if ts <= pan_trajectory[0].ts {
-        return (0.0, 0.0);
+/// Hash of everything that shapes the rendered frames: a changed edit, event, source file
+/// or output setting yields a new key, so its chunks are rendered afresh.
+fn export_checkpoint_key(
+    project: &Project,
+    events: Option<&EventsFile>,
+    source_video: &Path,
+    (width, height, fps): (u32, u32, u32),
+    codec: &str,
+    color_format: ExportColorFormat,
+    source_crop: Option<SourceCrop>,
+) -> u64 {
+    let mut hash = Fnv1a::new();
+    hash.field(&CHECKPOINT_FORMAT_VERSION.to_le_bytes());
+    hash.field(
+        serde_json::to_string(project)
+            .unwrap_or_default()
+            .as_bytes(),
+    );
+    hash.field(
+        events
+            .and_then(|events| serde_json::to_string(events).ok())
+            .unwrap_or_default()
+            .as_bytes(),
+    );
+    hash.field(source_video.to_string_lossy().as_bytes());
+    if let Ok(meta) = std::fs::metadata(source_video) {
+        hash.field(&meta.len().to_le_bytes());
+        let modified_ms = meta
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |modified| modified.as_millis() as u64);
+        hash.field(&modified_ms.to_le_bytes());
     }
+    hash.field(
+        format!("{width}x{height}@{fps} {codec} {color_format:?} {source_crop:?}").as_bytes(),
+    );
+    hash.finish()
+}
 
-    let last = pan_trajectory
-        .last()
-        .expect("pan trajectory has at least one keyframe");
-    if ts >= last.ts {
-        return (last.offset_x, last.offset_y);
+/// 64-bit FNV-1a. Unlike `DefaultHasher` its output is fixed, so checkpoint keys stay
+/// valid across app builds and Rust releases.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
     }
 
-    for pair in pan_trajectory.windows(2) {
-        let left = &pair[0];
-        let right = &pair[1];
-        if ts < left.ts || ts > right.ts {
-            continue;
-        }
-        let span = right.ts.saturating_sub(left.ts);
-        if span == 0 {
-            return (right.offset_x, right.offset_y);
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(Self::PRIME);
         }
-        let t = (ts.saturating_sub(left.ts)) as f64 / span as f64;
-        return (
-            left.offset_x + (right.offset_x - left.offset_x) * t,
-            left.offset_y + (right.offset_y - left.offset_y) * t,
-        );
     }
 
-    (last.offset_x, last.offset_y)
+    /// Length-prefixed, so adjacent fields cannot run into each other.
+    fn field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
 }
 
-fn format_f64(value: f64) -> String {
-    format!("{value:.4}")
+/// Stable path of one chunk: `{project id}-{key}-{start}-{end}.{extension}`.
+fn checkpoint_chunk_path(
+    dir: &Path,
+    project_id: &str,
+    key: u64,
+    (start_ms, end_ms): (u64, u64),
+    extension: &str,
+) -> PathBuf {
+    dir.join(format!(
+        "{project_id}-{key:016x}-{start_ms}-{end_ms}.{extension}"
+    ))
 }
 
-fn build_cursor_overlay_plan(
-    project: &Project,
-    events: Option<&EventsFile>,
-    camera_states: &[CameraState],
-    hidden_ranges_ms: &[(u64, u64)],
-    source_duration_ms: u64,
-    project_duration_ms: u64,
-    source_width: u32,
-    source_height: u32,
-    target_width: u32,
-    target_height: u32,
-    render_fps: f64,
-) -> Result<Option<CursorOverlayPlan>, String> {
-    let Some(events_file) = events else {
-        return Ok(None);
+/// Deletes the project's chunks rendered under another key: their settings are gone.
+fn prune_stale_checkpoints(dir: &Path, project_id: &str, key: u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
     };
-    if events_file.events.is_empty() {
-        return Ok(None);
+    let project_prefix = format!("{project_id}-");
+    let current_prefix = format!("{project_id}-{key:016x}-");
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(rest) = name.strip_prefix(&project_prefix) else {
+            continue;
+        };
+        // A project whose id extends this one after a dash (`rec-1-b`) has no key right here.
+        let owned = rest
+            .split('-')
+            .next()
+            .is_some_and(|key| key.len() == 16 && key.chars().all(|c| c.is_ascii_hexdigit()));
+        if owned && !name.starts_with(&current_prefix) {
+            let _ = std::fs::remove_file(entry.path());
+        }
     }
+}
 
-    let Some(cursor_asset) = resolve_cursor_asset_for_render()? else {
-        return Ok(None);
-    };
-
-    let mut points = extract_preview_cursor_points(
-        &events_file.events,
-        events_file.screen_width.max(1) as f64,
-        events_file.screen_height.max(1) as f64,
-        project.settings.cursor.smoothing_factor,
-    );
-    if points.is_empty() {
-        return Ok(None);
+/// A chunk from an earlier run is reused when it decodes to its expected length.
+fn checkpoint_is_valid(path: &Path, expected_ms: u64) -> bool {
+    if std::fs::metadata(path).map_or(true, |meta| meta.len() == 0) {
+        return false;
     }
+    probe_media_info(path)
+        .duration_ms
+        .is_some_and(|duration_ms| {
+            duration_ms.abs_diff(expected_ms) <= VERIFY_DURATION_TOLERANCE_MS
+        })
+}
 
-    points.sort_by_key(|point| point.ts);
-    let raw_desired_samples = (((source_duration_ms as f64) / 100.0).ceil() as usize).max(2);
-    let cursor_expr_budget = adaptive_sample_budget(
-        source_duration_ms,
-        MAX_CURSOR_SAMPLES_FOR_EXPR,
-        MAX_CURSOR_SAMPLES_FOR_EXPR_HARD_CAP,
-        CURSOR_EXPR_BUDGET_GROWTH_PER_SEC,
-        raw_desired_samples,
+/// Adapts the export graph to render the chunk starting at `start_ms` from a seeked input.
+///
+/// The graph must use the timestamp camera clock (built as for a VFR source): its rebase
+/// is shifted to the chunk start so the camera, cursor and labels keep project time, and
+/// the output is rebased to zero for the join.
+fn chunk_filter_graph(
+    filter_graph: &str,
+    start_ms: u64,
+    color_format: ExportColorFormat,
+) -> Result<String, String> {
+    const REBASE: &str = "setpts=PTS-STARTPTS";
+    if !filter_graph.contains(REBASE) {
+        return Err("Export filter graph does not use the timestamp clock".to_string());
+    }
+    let shifted = filter_graph.replacen(
+        REBASE,
+        &format!("{REBASE}+{}/TB", format_seconds_arg(start_ms)),
+        1,
     );
-    let desired_samples = raw_desired_samples
-        .clamp(24, cursor_expr_budget.max(24))
-        .max(2);
-    let frame_count = desired_samples - 1;
-    let frame_step_ms = (source_duration_ms as f64 / frame_count as f64).max(1.0);
+    let composed = shifted
+        .strip_suffix("[vout]")
+        .ok_or("Export filter graph does not end with [vout]")?;
+    let pixels = color_format
+        .video_filter()
+        .map_or_else(String::new, |filter| format!(",{filter}"));
+    Ok(format!("{composed}[vabs];[vabs]{REBASE}{pixels}[vout]"))
+}
 
-    let raw_click_times: Vec<u64> = events_file
-        .events
-        .iter()
-        .filter_map(|event| match event {
-            InputEvent::Click { ts, .. } => {
-                let mapped = apply_cursor_timing_offset_ms(
-                    map_time_ms(*ts, project_duration_ms, source_duration_ms),
-                    source_duration_ms,
-                );
-                (!is_hidden_at(mapped, hidden_ranges_ms)).then_some(mapped)
-            }
-            _ => None,
-        })
-        .collect();
-    let click_times = compact_click_times(
-        &decimate_u64_points(&raw_click_times, MAX_CLICK_EVENTS_FOR_EXPR),
-        MIN_CLICK_PULSE_GAP_MS,
+/// Renders the export in checkpointed chunks and muxes them with the source audio.
+///
+/// Chunks live under a stable temp path keyed by [`export_checkpoint_key`]; a run that
+/// failed or was cancelled leaves its finished chunks behind, and the next run of the same
+/// export only renders the missing ones. They are deleted once the export succeeds.
+fn execute_checkpointed_export(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    source_video: &Path,
+    chapters_metadata: Option<&Path>,
+    metadata: &[(&'static str, String)],
+    output: &ExportOutput,
+    filter_graph: &str,
+    codec: &str,
+    threads: Option<u32>,
+    color_format: ExportColorFormat,
+    audio_copy: bool,
+    fps: u32,
+    source_duration_ms: u64,
+    (project_id, key): (&str, u64),
+) -> Result<(), String> {
+    let dir = std::env::temp_dir().join(CHECKPOINT_DIR_NAME);
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        format!(
+            "Failed to create export checkpoint folder {}: {e}",
+            dir.display()
+        )
+    })?;
+    prune_stale_checkpoints(&dir, project_id, key);
+
+    let extension = output.path.extension().map_or_else(
+        || "mp4".to_string(),
+        |ext| ext.to_string_lossy().to_string(),
     );
+    let chunks = checkpoint_chunks(source_duration_ms, CHECKPOINT_CHUNK_MS);
+    let mut parts = Vec::with_capacity(chunks.len());
+    for (index, &(start_ms, end_ms)) in chunks.iter().enumerate() {
+        let path = checkpoint_chunk_path(&dir, project_id, key, (start_ms, end_ms), &extension);
+        update_status(status_state, |status| {
+            status.progress = status
+                .progress
+                .max(index as f64 / chunks.len() as f64 * 0.95);
+            status.message = format!("Rendering part {} of {}", index + 1, chunks.len());
+        });
+        if checkpoint_is_valid(&path, end_ms - start_ms) {
+            log::info!("run_export_job: reusing checkpoint {}", path.display());
+        } else {
+            let partial = path.with_extension(format!("partial.{extension}"));
+            render_checkpoint_chunk(
+                status_state,
+                source_video,
+                &chunk_filter_graph(filter_graph, start_ms, color_format)?,
+                (start_ms, end_ms),
+                source_duration_ms,
+                &ExportOutput {
+                    path: partial.clone(),
+                    ..output.clone()
+                },
+                codec,
+                threads,
+                color_format,
+                fps,
+            )?;
+            std::fs::rename(&partial, &path).map_err(|e| {
+                format!("Failed to store export checkpoint {}: {e}", path.display())
+            })?;
+        }
+        parts.push(path);
+    }
 
-    let target_min_side = target_width.min(target_height).max(1) as f64;
-    let cursor_height_px =
-        (project.settings.cursor.size * target_min_side * CURSOR_SIZE_TO_FRAME_RATIO)
-            .clamp(8.0, 280.0);
+    update_status(status_state, |status| {
+        status.message = "Joining rendered parts".to_string();
+    });
+    mux_checkpoint_chunks(
+        status_state,
+        &parts,
+        source_video,
+        chapters_metadata,
+        metadata,
+        audio_copy,
+        &output.path,
+        fps,
+        source_duration_ms,
+    )?;
+    for part in &parts {
+        let _ = std::fs::remove_file(part);
+    }
+    Ok(())
+}
 
-    let src_w = source_width as f64;
-    let src_h = source_height as f64;
+/// Encodes `start_ms..end_ms` of the source through a [`chunk_filter_graph`], video only.
+///
+/// Progress is reported against the whole `source_duration_ms`, so a chunk never moves the
+/// bar past the share the chunks before it have set.
+fn render_checkpoint_chunk(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    source_video: &Path,
+    filter_graph: &str,
+    (start_ms, end_ms): (u64, u64),
+    source_duration_ms: u64,
+    output: &ExportOutput,
+    codec: &str,
+    threads: Option<u32>,
+    color_format: ExportColorFormat,
+    fps: u32,
+) -> Result<(), String> {
+    let filter_script_path =
+        std::env::temp_dir().join(format!("nsc-filter-{}-{start_ms}.txt", now_ms()));
+    std::fs::write(&filter_script_path, filter_graph).map_err(|e| {
+        format!(
+            "Failed to write temporary FFmpeg filter script {}: {e}",
+            filter_script_path.display()
+        )
+    })?;
+
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-stats_period")
+        .arg("0.5")
+        .arg("-progress")
+        .arg("pipe:1")
+        .arg("-ss")
+        .arg(format_seconds_arg(start_ms))
+        .arg("-i")
+        .arg(source_video);
+    if let Some(threads) = threads {
+        command
+            .arg("-filter_complex_threads")
+            .arg(threads.to_string());
+    }
+    command
+        .arg("-filter_complex_script")
+        .arg(&filter_script_path)
+        .arg("-map")
+        .arg("[vout]")
+        .arg("-an");
+    if let Err(err) = push_video_codec_args(&mut command, codec, output, threads, color_format) {
+        let _ = std::fs::remove_file(&filter_script_path);
+        return Err(err);
+    }
+    command
+        .arg("-t")
+        .arg(format_seconds_arg(end_ms - start_ms))
+        .arg(&output.path);
+
+    let result = run_ffmpeg_with_progress(status_state, command, &ffmpeg, source_duration_ms, fps);
+    let _ = std::fs::remove_file(&filter_script_path);
+    result
+}
+
+/// Joins the chunks with stream copy and adds the source audio, chapters and tags.
+fn mux_checkpoint_chunks(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    parts: &[PathBuf],
+    source_video: &Path,
+    chapters_metadata: Option<&Path>,
+    metadata: &[(&'static str, String)],
+    audio_copy: bool,
+    output_video: &Path,
+    fps: u32,
+    total_duration_ms: u64,
+) -> Result<(), String> {
+    let list_path = title_card_part_path(output_video, "checkpoints").with_extension("txt");
+    std::fs::write(&list_path, build_concat_list(parts))
+        .map_err(|e| format!("Failed to write concat list {}: {e}", list_path.display()))?;
+
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-progress")
+        .arg("pipe:1")
+        .arg("-f")
+        .arg("concat")
+        .arg("-safe")
+        .arg("0")
+        .arg("-i")
+        .arg(&list_path)
+        .arg("-i")
+        .arg(source_video);
+    if let Some(chapters) = chapters_metadata {
+        command
+            .arg("-f")
+            .arg("ffmetadata")
+            .arg("-i")
+            .arg(chapters)
+            .arg("-map_chapters")
+            .arg("2");
+    }
+    command
+        .arg("-map")
+        .arg("0:v")
+        .arg("-map")
+        .arg("1:a?")
+        .arg("-c:v")
+        .arg("copy");
+    if audio_copy {
+        command.arg("-c:a").arg("copy");
+    } else {
+        command
+            .arg("-c:a")
+            .arg("aac")
+            .arg("-b:a")
+            .arg(format!("{EXPORT_AUDIO_KBPS}k"));
+    }
+    push_metadata_args(&mut command, metadata);
+    command
+        .arg("-t")
+        .arg(format_seconds_arg(total_duration_ms))
+        .arg("-movflags")
+        .arg("+faststart")
+        .arg(output_video);
+
+    let result = run_ffmpeg_with_progress(status_state, command, &ffmpeg, total_duration_ms, fps);
+    let _ = std::fs::remove_file(&list_path);
+    result
+}
+
+/// Temporary file next to `output_video` holding one part of a title-card export.
+fn title_card_part_path(output_video: &Path, part: &str) -> PathBuf {
+    let stem = output_video
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "export".to_string());
+    let extension = output_video
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mp4".to_string());
+    output_video.with_file_name(format!(".{stem}.{part}.{extension}"))
+}
+
+fn push_title_card_audio_args(command: &mut Command) {
+    command
+        .arg("-ar")
+        .arg(TITLE_CARD_AUDIO_RATE.to_string())
+        .arg("-ac")
+        .arg("2");
+}
+
+/// Encodes the cards for every output and joins them with the rendered recording.
+fn attach_title_cards(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    title_cards: &TitleCards,
+    body_outputs: &[ExportOutput],
+    outputs: &[ExportOutput],
+    chapters_metadata: Option<&Path>,
+    metadata: &[(&'static str, String)],
+    codec: &str,
+    threads: Option<u32>,
+    color_format: ExportColorFormat,
+    has_audio: bool,
+    fps: u32,
+    body_duration_ms: u64,
+) -> Result<(), String> {
+    let intro_ms = title_cards
+        .intro
+        .as_ref()
+        .map_or(0, |card| card.duration_ms);
+    let outro_ms = title_cards
+        .outro
+        .as_ref()
+        .map_or(0, |card| card.duration_ms);
+    let chapters = match chapters_metadata {
+        Some(path) => {
+            let metadata = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read chapters metadata {}: {e}", path.display()))?;
+            Some(shift_ffmetadata_chapters(&metadata, intro_ms))
+        }
+        None => None,
+    };
+
+    for (body, output) in body_outputs.iter().zip(outputs) {
+        update_status(status_state, |status| {
+            status.message = "Adding title cards".to_string();
+        });
+        let intro = title_cards
+            .intro
+            .as_ref()
+            .map(|card| (card, title_card_part_path(&output.path, "intro")));
+        let outro = title_cards
+            .outro
+            .as_ref()
+            .map(|card| (card, title_card_part_path(&output.path, "outro")));
+        let result = intro
+            .iter()
+            .chain(&outro)
+            .try_for_each(|(card, path)| {
+                render_title_card(
+                    status_state,
+                    card,
+                    output,
+                    path,
+                    codec,
+                    threads,
+                    color_format,
+                    has_audio,
+                    fps,
+                )
+            })
+            .and_then(|()| {
+                let parts = intro
+                    .iter()
+                    .map(|(_, path)| path.clone())
+                    .chain(std::iter::once(body.path.clone()))
+                    .chain(outro.iter().map(|(_, path)| path.clone()))
+                    .collect::<Vec<_>>();
+                concat_export_parts(
+                    status_state,
+                    &parts,
+                    chapters.as_deref(),
+                    metadata,
+                    &output.path,
+                    fps,
+                    intro_ms + body_duration_ms + outro_ms,
+                )
+            });
+        for (_, path) in intro.iter().chain(&outro) {
+            let _ = std::fs::remove_file(path);
+        }
+        result?;
+    }
+    Ok(())
+}
+
+/// Encodes a still image into a clip with the output's size, codec and pixel format,
+/// plus silence when the recording has audio, so it concatenates with stream copy.
+fn render_title_card(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    card: &TitleCard,
+    output: &ExportOutput,
+    path: &Path,
+    codec: &str,
+    threads: Option<u32>,
+    color_format: ExportColorFormat,
+    has_audio: bool,
+    fps: u32,
+) -> Result<(), String> {
+    let ffmpeg = find_ffmpeg_exe();
+    let seconds = format_seconds_arg(card.duration_ms);
+
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-progress")
+        .arg("pipe:1")
+        .arg("-loop")
+        .arg("1")
+        .arg("-framerate")
+        .arg(fps.to_string())
+        .arg("-t")
+        .arg(&seconds)
+        .arg("-i")
+        .arg(&card.path);
+    if has_audio {
+        command
+            .arg("-f")
+            .arg("lavfi")
+            .arg("-t")
+            .arg(&seconds)
+            .arg("-i")
+            .arg(format!(
+                "anullsrc=channel_layout=stereo:sample_rate={TITLE_CARD_AUDIO_RATE}"
+            ));
+    }
+    command
+        .arg("-vf")
+        .arg(title_card_video_filter(output, fps, color_format))
+        .arg("-map")
+        .arg("0:v");
+    push_video_codec_args(&mut command, codec, output, threads, color_format)?;
+    if has_audio {
+        command
+            .arg("-map")
+            .arg("1:a")
+            .arg("-c:a")
+            .arg("aac")
+            .arg("-b:a")
+            .arg(format!("{EXPORT_AUDIO_KBPS}k"));
+        push_title_card_audio_args(&mut command);
+    }
+    command.arg("-t").arg(&seconds).arg(path);
+
+    run_ffmpeg_with_progress(status_state, command, &ffmpeg, card.duration_ms, fps)
+}
+
+/// Letterboxes the card image into the output frame in the export's pixel format.
+fn title_card_video_filter(
+    output: &ExportOutput,
+    fps: u32,
+    color_format: ExportColorFormat,
+) -> String {
+    format!(
+        "scale={w}:{h}:force_original_aspect_ratio=decrease,pad={w}:{h}:(ow-iw)/2:(oh-ih)/2:black,setsar=1,fps={fps},{pixels}",
+        w = output.width,
+        h = output.height,
+        pixels = color_format
+            .video_filter()
+            .unwrap_or_else(|| format!("format={}", color_format.pix_fmt)),
+    )
+}
+
+/// Joins encoded parts with the concat demuxer into `output_video` without re-encoding.
+fn concat_export_parts(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    parts: &[PathBuf],
+    chapters: Option<&str>,
+    metadata: &[(&'static str, String)],
+    output_video: &Path,
+    fps: u32,
+    total_duration_ms: u64,
+) -> Result<(), String> {
+    let list_path = title_card_part_path(output_video, "concat").with_extension("txt");
+    std::fs::write(&list_path, build_concat_list(parts))
+        .map_err(|e| format!("Failed to write concat list {}: {e}", list_path.display()))?;
+    let chapters_path = title_card_part_path(output_video, "chapters").with_extension("ffmeta");
+    if let Some(chapters) = chapters {
+        if let Err(e) = std::fs::write(&chapters_path, chapters) {
+            let _ = std::fs::remove_file(&list_path);
+            return Err(format!(
+                "Failed to write chapters metadata {}: {e}",
+                chapters_path.display()
+            ));
+        }
+    }
+
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-progress")
+        .arg("pipe:1")
+        .arg("-f")
+        .arg("concat")
+        .arg("-safe")
+        .arg("0")
+        .arg("-i")
+        .arg(&list_path);
+    if chapters.is_some() {
+        command
+            .arg("-f")
+            .arg("ffmetadata")
+            .arg("-i")
+            .arg(&chapters_path)
+            .arg("-map_chapters")
+            .arg("1");
+    }
+    command.arg("-map").arg("0").arg("-c").arg("copy");
+    push_metadata_args(&mut command, metadata);
+    command.arg("-movflags").arg("+faststart").arg(output_video);
+
+    let result = run_ffmpeg_with_progress(status_state, command, &ffmpeg, total_duration_ms, fps);
+    let _ = std::fs::remove_file(&list_path);
+    let _ = std::fs::remove_file(&chapters_path);
+    result
+}
+
+/// Concat demuxer script listing `parts` in order.
+fn build_concat_list(parts: &[PathBuf]) -> String {
+    parts
+        .iter()
+        .map(|part| {
+            // Inside single quotes only the quote itself needs escaping.
+            format!("file '{}'\n", part.to_string_lossy().replace('\'', "'\\''"))
+        })
+        .collect()
+}
+
+/// Moves every chapter of a millisecond-timebase `FFMETADATA1` file by `offset_ms`.
+fn shift_ffmetadata_chapters(metadata: &str, offset_ms: u64) -> String {
+    map_ffmetadata_chapters(metadata, |ms| ms.saturating_add(offset_ms))
+}
+
+/// Rewrites every chapter `START`/`END` of a millisecond-timebase `FFMETADATA1` file.
+fn map_ffmetadata_chapters(metadata: &str, map_ms: impl Fn(u64) -> u64) -> String {
+    let mut mapped = String::with_capacity(metadata.len());
+    for line in metadata.lines() {
+        let moved = ["START=", "END="].iter().find_map(|key| {
+            let value = line.strip_prefix(key)?.trim().parse::<u64>().ok()?;
+            Some(format!("{key}{}", map_ms(value)))
+        });
+        mapped.push_str(moved.as_deref().unwrap_or(line));
+        mapped.push('\n');
+    }
+    mapped
+}
+
+/// Writes the chapters of `path` retimed through `remap` to a temporary file.
+fn remap_chapters_file(path: &Path, remap: &TimeRemap) -> Result<PathBuf, String> {
+    let metadata = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read chapters metadata {}: {e}", path.display()))?;
+    let remapped_path = std::env::temp_dir().join(format!("nsc-chapters-{}.txt", now_ms()));
+    std::fs::write(
+        &remapped_path,
+        map_ffmetadata_chapters(&metadata, |ms| remap.output_ms(ms)),
+    )
+    .map_err(|e| format!("Failed to write {}: {e}", remapped_path.display()))?;
+    Ok(remapped_path)
+}
+
+/// Container tags describing the export: title, description, creation time and the tool.
+fn export_metadata_tags(
+    project: &Project,
+    title: Option<&str>,
+    comment: Option<&str>,
+) -> Vec<(&'static str, String)> {
+    let override_or = |value: Option<&str>, fallback: String| {
+        value
+            .map(sanitize_metadata_value)
+            .filter(|value| !value.is_empty())
+            .unwrap_or(fallback)
+    };
+    let mut tags = Vec::with_capacity(4);
+    let title = override_or(title, sanitize_metadata_value(&project.name));
+    if !title.is_empty() {
+        tags.push(("title", title));
+    }
+    if let Some(description) = project
+        .description
+        .as_deref()
+        .map(sanitize_metadata_value)
+        .filter(|description| !description.is_empty())
+    {
+        tags.push(("description", description));
+    }
+    if let Some(created_at) = Utc
+        .timestamp_millis_opt(project.created_at as i64)
+        .single()
+        .filter(|_| project.created_at > 0)
+    {
+        tags.push((
+            "creation_time",
+            created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        ));
+    }
+    tags.push((
+        "comment",
+        override_or(
+            comment,
+            format!("NeuroScreenCaster {}", env!("CARGO_PKG_VERSION")),
+        ),
+    ));
+    tags
+}
+
+/// FFmpeg takes `-metadata key=value` as one argument split at the first `=`, so only
+/// line breaks and other control characters, which the muxers cannot store, are replaced.
+fn sanitize_metadata_value(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn push_metadata_args(command: &mut Command, metadata: &[(&'static str, String)]) {
+    for (key, value) in metadata {
+        command.arg("-metadata").arg(format!("{key}={value}"));
+    }
+}
+
+/// Checks the requested thread cap against the machine; `0` and `None` mean automatic.
+fn validate_export_threads(threads: Option<u32>) -> Result<Option<u32>, String> {
+    let Some(threads) = threads.filter(|threads| *threads > 0) else {
+        return Ok(None);
+    };
+    let cores = std::thread::available_parallelism()
+        .map(|cores| cores.get() as u32)
+        .unwrap_or(1);
+    if threads > cores {
+        return Err(format!(
+            "Export threads ({threads}) exceed the available CPU cores ({cores})"
+        ));
+    }
+    Ok(Some(threads))
+}
+
+/// FFmpeg encoder and its default constant rate factor for an export codec.
+fn video_encoder(codec: &str) -> Result<(&'static str, u32), String> {
+    match codec {
+        "h264" => Ok(("libx264", 18)),
+        "h265" => Ok(("libx265", 24)),
+        "vp9" => Ok(("libvpx-vp9", 33)),
+        _ => Err(format!("Unsupported codec: {codec}")),
+    }
+}
+
+fn push_video_codec_args(
+    command: &mut Command,
+    codec: &str,
+    output: &ExportOutput,
+    threads: Option<u32>,
+    color_format: ExportColorFormat,
+) -> Result<(), String> {
+    let (encoder, default_crf) = video_encoder(codec)?;
+    command.arg("-c:v").arg(encoder);
+    if codec == "vp9" {
+        // Constant quality mode; `-maxrate` below turns it into constrained quality.
+        command.arg("-b:v").arg("0");
+    } else {
+        command.arg("-preset").arg("ultrafast");
+    }
+    command
+        .arg("-crf")
+        .arg(output.crf.unwrap_or(default_crf).to_string());
+    if let Some(kbps) = output.max_bitrate_kbps {
+        command
+            .arg("-maxrate")
+            .arg(format!("{kbps}k"))
+            .arg("-bufsize")
+            .arg(format!("{}k", kbps.saturating_mul(2)));
+    }
+    color_format.push_args(command);
+    if let Some(threads) = threads {
+        if codec == "vp9" {
+            // libvpx only spreads a frame over threads with row-based multithreading.
+            command.arg("-row-mt").arg("1");
+        }
+        command.arg("-threads").arg(threads.to_string());
+    }
+    Ok(())
+}
+
+/// Validates the rendition list and names its files after `output_video`, largest first.
+fn rendition_outputs(
+    output_video: &Path,
+    renditions: &[RenditionSpec],
+) -> Result<Vec<ExportOutput>, String> {
+    if renditions.len() > MAX_RENDITIONS {
+        return Err(format!(
+            "At most {MAX_RENDITIONS} renditions can be exported at once"
+        ));
+    }
+    let stem = output_video
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "export".to_string());
+    let extension = output_video
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mp4".to_string());
+
+    let mut outputs = Vec::with_capacity(renditions.len());
+    for rendition in renditions {
+        let label = rendition.label.trim();
+        let label_ok = !label.is_empty()
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !label_ok {
+            return Err(format!("Invalid rendition label: {:?}", rendition.label));
+        }
+        let path = output_video.with_file_name(format!("{stem}-{label}.{extension}"));
+        if outputs
+            .iter()
+            .any(|output: &ExportOutput| output.path == path)
+        {
+            return Err(format!("Duplicate rendition label: {label}"));
+        }
+        outputs.push(ExportOutput {
+            path,
+            // H.264 with 4:2:0 chroma needs even dimensions.
+            width: rendition.width.clamp(320, 7680) & !1,
+            height: rendition.height.clamp(240, 4320) & !1,
+            crf: rendition.crf.map(|crf| crf.min(51)),
+            max_bitrate_kbps: rendition.max_bitrate_kbps.filter(|kbps| *kbps > 0),
+        });
+    }
+    outputs.sort_by_key(|output| std::cmp::Reverse(output.width as u64 * output.height as u64));
+    Ok(outputs)
+}
+
+/// Fans the single-use `[vout]` out to one branch per output and returns the filter suffix
+/// plus the `(video, audio)` maps of each output.
+///
+/// The first output keeps the rendered size; the others are scaled from it, with margins
+/// filled by the project's frame background. A filtered audio label is split as well, while
+/// an input stream like `0:a?` can be mapped by every output directly.
+fn build_rendition_split(
+    outputs: &[ExportOutput],
+    audio_map: &str,
+    frame_background: &FrameBackground,
+    fps: u32,
+) -> (String, Vec<(String, String)>) {
+    if outputs.len() <= 1 {
+        return (
+            String::new(),
+            vec![("[vout]".to_string(), audio_map.to_string())],
+        );
+    }
+
+    let count = outputs.len();
+    let labels = |prefix: &str| {
+        (0..count)
+            .map(|index| format!("[{prefix}{index}]"))
+            .collect::<Vec<_>>()
+    };
+    let video_branches = labels("vr");
+    let mut filter = format!(";[vout]split={count}{}", video_branches.concat());
+    let mut maps = Vec::with_capacity(count);
+    for (index, output) in outputs.iter().enumerate() {
+        if index == 0 {
+            maps.push(video_branches[0].clone());
+            continue;
+        }
+        filter.push_str(&format!(
+            ";{branch}{fill}[vrs{index}]",
+            branch = video_branches[index],
+            fill = tagged_frame_fill_filter(
+                frame_background,
+                (output.width, output.height),
+                fps,
+                "",
+                &format!("r{index}"),
+            ),
+        ));
+        maps.push(format!("[vrs{index}]"));
+    }
+
+    let audio_maps = match audio_map
+        .strip_prefix('[')
+        .and_then(|label| label.strip_suffix(']'))
+    {
+        Some(label) => {
+            let audio_branches = labels("ar");
+            filter.push_str(&format!(
+                ";[{label}]asplit={count}{}",
+                audio_branches.concat()
+            ));
+            audio_branches
+        }
+        None => vec![audio_map.to_string(); count],
+    };
+
+    (filter, maps.into_iter().zip(audio_maps).collect())
+}
+
+/// Copies the source streams into the output without decoding, for exports that
+/// [`passthrough_blocker`] found to need no filtering.
+fn execute_passthrough_export(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    source_video: &Path,
+    chapters_metadata: Option<&Path>,
+    metadata: &[(&'static str, String)],
+    output_video: &Path,
+    target_fps: u32,
+    source_duration_ms: u64,
+) -> Result<(), String> {
+    let ffmpeg = find_ffmpeg_exe();
+
+    let mut command = Command::new(&ffmpeg);
+    apply_no_window_flags(&mut command);
+    command
+        .arg("-y")
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-stats_period")
+        .arg("0.5")
+        .arg("-progress")
+        .arg("pipe:1")
+        .arg("-i")
+        .arg(source_video);
+    if let Some(metadata_path) = chapters_metadata {
+        command
+            .arg("-f")
+            .arg("ffmetadata")
+            .arg("-i")
+            .arg(metadata_path)
+            .arg("-map_chapters")
+            .arg("1");
+    }
+    command
+        .arg("-map")
+        .arg("0:v:0")
+        .arg("-map")
+        .arg("0:a?")
+        .arg("-c")
+        .arg("copy");
+    push_metadata_args(&mut command, metadata);
+    command.arg("-movflags").arg("+faststart").arg(output_video);
+
+    run_ffmpeg_with_progress(
+        status_state,
+        command,
+        &ffmpeg,
+        source_duration_ms,
+        target_fps,
+    )
+}
+
+/// Runs a prepared FFmpeg export command to completion, reporting `-progress pipe:1`
+/// output into the export status and honoring cancellation.
+fn run_ffmpeg_with_progress(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    mut command: Command,
+    ffmpeg: &Path,
+    output_duration_ms: u64,
+    target_fps: u32,
+) -> Result<(), String> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            format!(
+                "Failed to start FFmpeg export ({}): {e}",
+                ffmpeg.to_string_lossy()
+            )
+        })?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let progress_status = Arc::clone(status_state);
+    let expected_total_frames = ((output_duration_ms as f64 / 1000.0) * (target_fps as f64))
+        .max(1.0)
+        .round();
+    let progress_reported = Arc::new(AtomicBool::new(false));
+    let progress_reported_worker = Arc::clone(&progress_reported);
+    let progress_handle = std::thread::spawn(move || {
+        let Some(stdout) = stdout else {
+            return;
+        };
+
+        // `-progress pipe:1` writes blocks of `key=value` lines terminated by `progress=...`.
+        let mut progress = FfmpegProgress::default();
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if !progress.apply_line(&line) {
+                continue;
+            }
+
+            let fraction = progress.fraction(output_duration_ms, expected_total_frames);
+            if fraction.is_some() {
+                progress_reported_worker.store(true, Ordering::Relaxed);
+            }
+            let snapshot = progress.clone();
+            update_status(&progress_status, |status| {
+                if let Some(fraction) = fraction.filter(|value| *value > status.progress) {
+                    status.progress = fraction;
+                }
+                status.frame = snapshot.frame;
+                status.fps = snapshot.fps;
+                status.speed = snapshot.speed;
+                status.message = format_export_progress_message(status.progress, &snapshot);
+            });
+
+            if progress.ended {
+                break;
+            }
+        }
+    });
+
+    // Stderr is only kept for error reporting; progress comes from the `-progress` pipe.
+    let stderr_handle = std::thread::spawn(move || -> VecDeque<String> {
+        let mut stderr_tail: VecDeque<String> = VecDeque::new();
+        if let Some(stderr) = stderr {
+            for line in BufReader::new(stderr).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                stderr_tail.push_back(trimmed.to_string());
+                if stderr_tail.len() > 120 {
+                    stderr_tail.pop_front();
+                }
+            }
+        }
+        stderr_tail
+    });
+
+    let started_at = std::time::Instant::now();
+    let mut cancelled = false;
+    let exit_status = loop {
+        if EXPORT_CANCEL_REQUESTED.load(Ordering::Relaxed) {
+            cancelled = true;
+            let _ = child.kill();
+        }
+
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {
+                if !progress_reported.load(Ordering::Relaxed) {
+                    report_export_progress_estimate(
+                        status_state,
+                        started_at.elapsed(),
+                        output_duration_ms,
+                    );
+                }
+                std::thread::sleep(std::time::Duration::from_millis(120));
+            }
+            Err(e) => {
+                return Err(format!("Failed to wait for FFmpeg export: {e}"));
+            }
+        }
+    };
+    let _ = progress_handle.join();
+    let stderr_tail = stderr_handle.join().unwrap_or_default();
+
+    if cancelled {
+        return Err(EXPORT_CANCELLED_SENTINEL.to_string());
+    }
+
+    if !exit_status.success() {
+        let stderr_excerpt = stderr_tail
+            .iter()
+            .filter(|line| {
+                line.contains("Error")
+                    || line.contains("error")
+                    || line.contains("Invalid")
+                    || line.contains("Failed")
+                    || line.contains("failed")
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if stderr_excerpt.is_empty() {
+            return Err(format!("FFmpeg export failed with status: {exit_status}"));
+        }
+        return Err(format!(
+            "FFmpeg export failed with status: {exit_status}\n{}",
+            stderr_excerpt.join("\n")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Audio part of the filter graph: the music trimmed to the video, attenuated and faded,
+/// mixed under the `source_audio` stream into `[aout]` (or used alone when there is none).
+/// Shifts the source audio by `offset_ms` (positive delays it) and keeps it within the
+/// source duration.
+fn audio_offset_filter(
+    input: &str,
+    output: &str,
+    offset_ms: i64,
+    source_duration_ms: u64,
+) -> String {
+    if offset_ms > 0 {
+        format!(
+            "[{input}]adelay={offset_ms}:all=1,atrim=end={},asetpts=PTS-STARTPTS[{output}]",
+            format_seconds_arg(source_duration_ms)
+        )
+    } else {
+        format!(
+            "[{input}]atrim=start={},asetpts=PTS-STARTPTS[{output}]",
+            format_seconds_arg(offset_ms.unsigned_abs())
+        )
+    }
+}
+
+fn build_music_filter(
+    music_input_index: usize,
+    source_audio: Option<&str>,
+    music: &MusicBed,
+    duration_ms: u64,
+) -> String {
+    let duration_s = duration_ms.max(1) as f64 / 1000.0;
+    let mut chain = format!(
+        "[{music_input_index}:a]atrim=duration={duration_s:.3},asetpts=PTS-STARTPTS,volume={:.2}dB",
+        music.volume_db
+    );
+    let fade_s = (music.fade_ms as f64 / 1000.0).min(duration_s / 2.0);
+    if fade_s > 0.0 {
+        chain.push_str(&format!(
+            ",afade=t=in:st=0:d={fade_s:.3},afade=t=out:st={:.3}:d={fade_s:.3}",
+            duration_s - fade_s
+        ));
+    }
+
+    match source_audio {
+        Some(label) => format!(
+            "{chain}[music];[{label}][music]amix=inputs=2:duration=first:dropout_transition=0:normalize=0[aout]"
+        ),
+        None => format!("{chain}[aout]"),
+    }
+}
+
+/// Source ranges (ms) played back `factor` times slower in the export.
+///
+/// Applied to the composed frames, so the camera and cursor drawn on the source timeline
+/// slow down together with the video.
+#[derive(Debug, Clone, PartialEq)]
+struct TimeRemap {
+    ranges: Vec<(u64, u64)>,
+    factor: f64,
+}
+
+impl TimeRemap {
+    /// Output time of the source timestamp `source_ms`.
+    fn output_ms(&self, source_ms: u64) -> u64 {
+        let stretched = self
+            .ranges
+            .iter()
+            .take_while(|(start, _)| *start < source_ms)
+            .map(|(start, end)| (source_ms.min(*end) - start) as f64 * (self.factor - 1.0))
+            .sum::<f64>();
+        source_ms + stretched.round() as u64
+    }
+
+    fn output_duration_ms(&self, source_duration_ms: u64) -> u64 {
+        let stretched = self
+            .ranges
+            .iter()
+            .map(|(start, end)| (end - start) as f64 * (self.factor - 1.0))
+            .sum::<f64>();
+        source_duration_ms + stretched.round() as u64
+    }
+
+    /// Consecutive `(start_ms, end_ms, slowed)` pieces covering the whole source.
+    fn pieces(&self, source_duration_ms: u64) -> Vec<(u64, u64, bool)> {
+        let mut pieces = Vec::with_capacity(self.ranges.len() * 2 + 1);
+        let mut cursor = 0;
+        for &(start, end) in &self.ranges {
+            if start > cursor {
+                pieces.push((cursor, start, false));
+            }
+            pieces.push((start, end, true));
+            cursor = end;
+        }
+        if source_duration_ms > cursor {
+            pieces.push((cursor, source_duration_ms, false));
+        }
+        pieces
+    }
+
+    fn video_filter(&self, input: &str, output: &str, source_duration_ms: u64, fps: u32) -> String {
+        let pieces = self.pieces(source_duration_ms);
+        let labels = (0..pieces.len())
+            .map(|i| format!("[vs{i}]"))
+            .collect::<String>();
+        let mut chains = vec![format!("[{input}]split={}{labels}", pieces.len())];
+        for (i, (start, end, slowed)) in pieces.iter().enumerate() {
+            let pts = if *slowed {
+                format!("{:.4}*(PTS-STARTPTS)", self.factor)
+            } else {
+                "PTS-STARTPTS".to_string()
+            };
+            chains.push(format!(
+                "[vs{i}]trim=start={}:end={},setpts={pts}[vp{i}]",
+                format_seconds_arg(*start),
+                format_seconds_arg(*end)
+            ));
+        }
+        let inputs = (0..pieces.len())
+            .map(|i| format!("[vp{i}]"))
+            .collect::<String>();
+        // Slowed pieces have sparser frames; `fps` duplicates them back onto the output grid.
+        chains.push(format!(
+            "{inputs}concat=n={}:v=1:a=0,fps={fps}[{output}]",
+            pieces.len()
+        ));
+        chains.join(";")
+    }
+
+    fn audio_filter(&self, input: &str, output: &str, source_duration_ms: u64) -> String {
+        let pieces = self.pieces(source_duration_ms);
+        let labels = (0..pieces.len())
+            .map(|i| format!("[as{i}]"))
+            .collect::<String>();
+        let mut chains = vec![format!("[{input}]asplit={}{labels}", pieces.len())];
+        for (i, (start, end, slowed)) in pieces.iter().enumerate() {
+            let tempo = if *slowed {
+                format!(",{}", atempo_chain(1.0 / self.factor))
+            } else {
+                String::new()
+            };
+            chains.push(format!(
+                "[as{i}]atrim=start={}:end={},asetpts=PTS-STARTPTS{tempo}[ap{i}]",
+                format_seconds_arg(*start),
+                format_seconds_arg(*end)
+            ));
+        }
+        let inputs = (0..pieces.len())
+            .map(|i| format!("[ap{i}]"))
+            .collect::<String>();
+        chains.push(format!(
+            "{inputs}concat=n={}:v=0:a=1[{output}]",
+            pieces.len()
+        ));
+        chains.join(";")
+    }
+}
+
+const DEFAULT_LOOP_CROSSFADE_MS: u64 = 500;
+const MIN_LOOP_CROSSFADE_MS: u64 = 100;
+/// Longest crossfade as a share of the clip, so most of it still plays unblended.
+const MAX_LOOP_CROSSFADE_FRACTION: f64 = 0.25;
+
+/// Crossfade of the clip's end into its start for exports meant to play on a loop.
+///
+/// The first `crossfade_ms` of the clip are moved behind its end and faded in over the
+/// last ones, so the final frame leads straight into the first and the cut disappears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LoopBlend {
+    pub crossfade_ms: u64,
+}
+
+impl LoopBlend {
+    fn from_args(loop_seamless: Option<bool>, crossfade_ms: Option<u64>) -> Option<Self> {
+        loop_seamless.unwrap_or(false).then(|| Self {
+            crossfade_ms: crossfade_ms.unwrap_or(DEFAULT_LOOP_CROSSFADE_MS),
+        })
+    }
+
+    /// Crossfade clamped to the clip length; `None` when the clip is too short to blend.
+    fn crossfade_ms_for(&self, clip_duration_ms: u64) -> Option<u64> {
+        let max_ms = (clip_duration_ms as f64 * MAX_LOOP_CROSSFADE_FRACTION) as u64;
+        Some(self.crossfade_ms.min(max_ms)).filter(|ms| *ms >= MIN_LOOP_CROSSFADE_MS)
+    }
+}
+
+fn loop_video_filter(
+    input: &str,
+    output: &str,
+    clip_duration_ms: u64,
+    crossfade_ms: u64,
+) -> String {
+    let crossfade = format_seconds_arg(crossfade_ms);
+    // The body is `clip - crossfade` long, so the fade starts one crossfade before its end.
+    let offset = format_seconds_arg(clip_duration_ms.saturating_sub(crossfade_ms * 2));
+    format!(
+        "[{input}]split=2[lpv0][lpv1];\
+         [lpv0]trim=start={crossfade},setpts=PTS-STARTPTS[lpbody];\
+         [lpv1]trim=end={crossfade},setpts=PTS-STARTPTS[lphead];\
+         [lpbody][lphead]xfade=transition=fade:duration={crossfade}:offset={offset}[{output}]"
+    )
+}
+
+fn loop_audio_filter(input: &str, output: &str, crossfade_ms: u64) -> String {
+    let crossfade = format_seconds_arg(crossfade_ms);
+    format!(
+        "[{input}]asplit=2[lpa0][lpa1];\
+         [lpa0]atrim=start={crossfade},asetpts=PTS-STARTPTS[lpabody];\
+         [lpa1]atrim=end={crossfade},asetpts=PTS-STARTPTS[lpahead];\
+         [lpabody][lpahead]acrossfade=d={crossfade}[{output}]"
+    )
+}
+
+fn format_seconds_arg(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+/// `atempo` only accepts 0.5–2.0 per instance, so slower tempos are chained.
+fn atempo_chain(tempo: f64) -> String {
+    let mut remaining = tempo.clamp(0.01, 2.0);
+    let mut filters = Vec::new();
+    while remaining < 0.5 {
+        filters.push("atempo=0.5".to_string());
+        remaining /= 0.5;
+    }
+    filters.push(format!("atempo={remaining:.4}"));
+    filters.join(",")
+}
+
+/// Merged slow-motion windows around visible clicks, in source time.
+fn build_click_time_remap(
+    slow_motion: &ClickSlowMotion,
+    events: Option<&EventsFile>,
+    hidden_ranges: &[TimeRange],
+    project_duration_ms: u64,
+    source_duration_ms: u64,
+) -> Option<TimeRemap> {
+    let factor = slow_motion.factor.clamp(1.0, 4.0);
+    if factor <= 1.0 {
+        return None;
+    }
+    let half_window_ms = slow_motion.window_ms.clamp(200, 5_000) / 2;
+    let hidden_ranges_ms =
+        map_hidden_ranges_to_source(hidden_ranges, project_duration_ms, source_duration_ms);
+
+    let mut click_times = events?
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            InputEvent::Click { ts, .. } => {
+                Some(map_time_ms(*ts, project_duration_ms, source_duration_ms))
+            }
+            _ => None,
+        })
+        .filter(|ts| !is_hidden_at(*ts, &hidden_ranges_ms))
+        .collect::<Vec<_>>();
+    click_times.sort_unstable();
+
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+    for click_ms in click_times {
+        let start = click_ms.saturating_sub(half_window_ms);
+        let end = click_ms
+            .saturating_add(half_window_ms)
+            .min(source_duration_ms);
+        if end <= start {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    (!ranges.is_empty()).then_some(TimeRemap { ranges, factor })
+}
+
+fn build_export_filter_graph(
+    project: &Project,
+    events: Option<&EventsFile>,
+    target_width: u32,
+    target_height: u32,
+    target_fps: u32,
+    source_duration_ms: u64,
+    source_width: u32,
+    source_height: u32,
+    source_crop: Option<SourceCrop>,
+    source_is_vfr: bool,
+) -> Result<(String, Option<PathBuf>, Vec<PathBuf>), String> {
+    let project_duration_ms = project.duration_ms.max(1);
+    let cursor_hidden_ranges_ms = map_hidden_ranges_to_source(
+        &project.settings.cursor.hidden_ranges,
+        project_duration_ms,
+        source_duration_ms,
+    );
+
+    let render_fps = target_fps as f64;
+    let camera_states = build_camera_states(
+        project,
+        source_duration_ms,
+        project_duration_ms,
+        source_width.max(1),
+        source_height.max(1),
+        render_fps,
+    );
+
+    // CFR sources keep the exact frame-index camera; VFR sources switch to timestamps so the
+    // camera cannot drift against the `t`-based cursor track.
+    let time_base = if source_is_vfr {
+        CameraTimeBase::Timestamp
+    } else {
+        CameraTimeBase::FrameIndex
+    };
+    let zoom_expr = clamp_camera_zoom_expr(&build_camera_value_expr(
+        &camera_states,
+        |state| state.zoom,
+        1.0,
+        render_fps,
+        time_base,
+    ));
+    let offset_x_expr = build_camera_value_expr(
+        &camera_states,
+        |state| state.offset_x,
+        0.0,
+        render_fps,
+        time_base,
+    );
+    let offset_y_expr = build_camera_value_expr(
+        &camera_states,
+        |state| state.offset_y,
+        0.0,
+        render_fps,
+        time_base,
+    );
+
+    // Auto-reframe renders the camera frame at full output height onto a wider canvas and
+    // crops the output out of it; the cursor is drawn on the canvas, labels on the output.
+    let reframe = match project.settings.export.aspect_mode {
+        AspectMode::Fit => None,
+        AspectMode::AutoReframe => {
+            let canvas = auto_reframe_canvas(
+                (source_width.max(1), source_height.max(1)),
+                (target_width.max(1), target_height.max(1)),
+            );
+            if canvas.is_none() {
+                log::info!(
+                    "build_export_filter_graph: output is not narrower than the source, auto-reframe falls back to fit"
+                );
+            }
+            canvas
+        }
+    };
+    let (frame_width, frame_height) = reframe.unwrap_or((target_width, target_height));
+
+    let mut input_chain: Vec<String> = Vec::new();
+    let mut cursor_overlay_filter = None;
+    let mut cursor_input_path = None;
+    let mut temp_files = Vec::new();
+
+    if let Some(crop) = source_crop {
+        input_chain.push(crop.filter());
+    }
+    if source_is_vfr {
+        // Rebase timestamps so `t` starts at zero like the project timeline.
+        input_chain.push("setpts=PTS-STARTPTS".to_string());
+    }
+    // Upsample to target FPS before camera transforms to match preview smoothness.
+    // This also resamples VFR sources onto a constant frame grid.
+    input_chain.push(format!("fps={target_fps}"));
+
+    // With the cursor disabled neither overlay is built, so `[framed]` is emitted as `[vout]`.
+    let show_cursor = project.settings.cursor.show_cursor
+        && project.settings.cursor.render_mode != CursorRenderMode::Off;
+
+    if show_cursor && ENABLE_CUSTOM_CURSOR_OVERLAY_EXPORT {
+        if let Some(plan) = build_cursor_overlay_plan(
+            project,
+            events,
+            &camera_states,
+            &cursor_hidden_ranges_ms,
+            source_duration_ms,
+            project_duration_ms,
+            source_width.max(1),
+            source_height.max(1),
+            frame_width.max(1),
+            frame_height.max(1),
+            render_fps,
+        )? {
+            cursor_input_path = Some(plan.cursor_png_path);
+            cursor_overlay_filter = Some(plan.filter_chain);
+        }
+    } else if let Some(events_file) = events.filter(|_| show_cursor) {
+        if !events_file.events.is_empty() {
+            match build_vector_cursor_ass_file(
+                project,
+                events_file,
+                &camera_states,
+                &cursor_hidden_ranges_ms,
+                source_duration_ms,
+                project_duration_ms,
+                source_width.max(1),
+                source_height.max(1),
+                frame_width.max(1),
+                frame_height.max(1),
+                render_fps,
+            ) {
+                Ok(ass) => {
+                    let escaped = escape_filter_path(&ass);
+                    cursor_overlay_filter =
+                        Some(format!("[framed]subtitles=filename='{escaped}'[vout]"));
+                    temp_files.push(ass);
+                }
+                Err(err) => {
+                    log::warn!("build_export_filter_graph: vector cursor overlay disabled: {err}");
+                }
+            }
+        }
+    }
+
+    // Without any zoom or pan the camera stage would only redraw the source at 1x.
+    let camera_is_identity = is_identity_camera(&camera_states);
+    if !camera_is_identity {
+        input_chain.push("split=2[base][zoom]".to_string());
+    }
+
+    let supersample = match project.settings.export.scaling_quality {
+        ScalingQuality::Fast => None,
+        ScalingQuality::Supersampled => Some(supersample_factor(
+            source_width,
+            source_height,
+            frame_width,
+            frame_height,
+        )),
+    };
+    let scale_flags = if supersample.is_some() {
+        ":flags=lanczos"
+    } else {
+        ""
+    };
+
+    // The cursor is drawn after this scale, so it always composites at the final resolution
+    // and on top of the frame background.
+    let framing = frame_fill_filter(
+        &project.settings.frame_background,
+        (frame_width, frame_height),
+        target_fps,
+        scale_flags,
+    );
+    let mut post_camera_chain = if let Some(cursor_overlay_filter) = cursor_overlay_filter {
+        format!("[cam]{framing}[framed];{cursor_overlay_filter}")
+    } else {
+        format!("[cam]{framing}[vout]")
+    };
+
+    if let Some((canvas_width, _)) = reframe {
+        let track = auto_reframe_track(
+            project,
+            events,
+            &camera_states,
+            source_duration_ms,
+            (source_width.max(1), source_height.max(1)),
+            (canvas_width, target_height.max(1)),
+            target_width.max(1),
+            render_fps,
+        );
+        let x_expr = build_piecewise_track_expr(&track, source_duration_ms);
+        if let Some(chain) = post_camera_chain.strip_suffix("[vout]") {
+            post_camera_chain = format!(
+                "{chain}[wide];[wide]crop={target_width}:{target_height}:x='{x_expr}':y=0[vout]"
+            );
+        }
+    }
+
+    // Labels go on last, over the cursor, so they stay readable wherever the cursor is.
+    match build_segment_label_ass_file(
+        project,
+        source_duration_ms,
+        target_width.max(1),
+        target_height.max(1),
+    ) {
+        Ok(Some(ass)) => {
+            let escaped = escape_filter_path(&ass);
+            if let Some(chain) = post_camera_chain.strip_suffix("[vout]") {
+                post_camera_chain =
+                    format!("{chain}[labeled];[labeled]subtitles=filename='{escaped}'[vout]");
+            }
+            temp_files.push(ass);
+        }
+        Ok(None) => {}
+        Err(err) => {
+            log::warn!("build_export_filter_graph: segment labels disabled: {err}");
+        }
+    }
+
+    if camera_is_identity {
+        let graph = format!(
+            "{input}[cam];{post_camera}",
+            input = input_chain.join(","),
+            post_camera = post_camera_chain
+        );
+        return Ok((graph, cursor_input_path, temp_files));
+    }
+
+    if let Some(factor) = supersample {
+        // The zoomed layer always covers the canvas (zoom >= 1), so the canvas only sets
+        // the internal size and takes the cheapest scaler.
+        let canvas = if factor > 1 {
+            format!("[base]scale=iw*{factor}:ih*{factor}:flags=neighbor[canvas];")
+        } else {
+            "[base]null[canvas];".to_string()
+        };
+        let graph = format!(
+            "{input};\
+             {canvas}\
+             [zoom]scale=w='iw*({zoom})*{factor}':h='ih*({zoom})*{factor}':eval=frame:flags=lanczos[scaled];\
+             [canvas][scaled]overlay=x='-max(0,min(({x})*{factor},overlay_w-main_w))':y='-max(0,min(({y})*{factor},overlay_h-main_h))':eval=frame[cam];\
+             {post_camera}",
+            input = input_chain.join(","),
+            zoom = zoom_expr,
+            x = offset_x_expr,
+            y = offset_y_expr,
+            post_camera = post_camera_chain
+        );
+        return Ok((graph, cursor_input_path, temp_files));
+    }
+
+    let graph = format!(
+        "{input};\
+         [zoom]scale=w='iw*({zoom})':h='ih*({zoom})':eval=frame[scaled];\
+         [base][scaled]overlay=x='-max(0,min({x},overlay_w-main_w))':y='-max(0,min({y},overlay_h-main_h))':eval=frame[cam];\
+         {post_camera}",
+        input = input_chain.join(","),
+        zoom = zoom_expr,
+        x = offset_x_expr,
+        y = offset_y_expr,
+        post_camera = post_camera_chain
+    );
+
+    Ok((graph, cursor_input_path, temp_files))
+}
+
+/// Canvas the camera frame is fitted into before the auto-reframe crop: the output height
+/// at the source aspect. `None` when the output is not narrower than the source, so there
+/// is nothing to pan across.
+fn auto_reframe_canvas(
+    (source_width, source_height): (u32, u32),
+    (target_width, target_height): (u32, u32),
+) -> Option<(u32, u32)> {
+    let width = (target_height as f64 * source_width as f64 / source_height.max(1) as f64).round()
+        as u32
+        & !1;
+    (width > target_width).then_some((width, target_height))
+}
+
+/// Left edge of the auto-reframe window in canvas pixels, on the source timeline.
+///
+/// The smart camera's activity center is carried through the export camera the same way
+/// as the cursor, and the `crop_width` window is centred on it without leaving the canvas.
+fn auto_reframe_track(
+    project: &Project,
+    events: Option<&EventsFile>,
+    camera_states: &[CameraState],
+    source_duration_ms: u64,
+    (source_width, source_height): (u32, u32),
+    (canvas_width, canvas_height): (u32, u32),
+    crop_width: u32,
+    render_fps: f64,
+) -> Vec<(u64, f64)> {
+    let project_duration_ms = project.duration_ms.max(1);
+    let max_left = canvas_width.saturating_sub(crop_width) as f64;
+
+    let samples = events
+        .filter(|events_file| !events_file.events.is_empty())
+        .map(|events_file| {
+            let camera_config = camera_config_for_trigger_mode(
+                AutoZoomTriggerMode::default(),
+                &project.settings.camera,
+            );
+            simulate_camera_track(
+                &events_file.events,
+                events_file.screen_width.max(1),
+                events_file.screen_height.max(1),
+                project_duration_ms,
+                crop_width as f64 / canvas_height.max(1) as f64,
+                &cursor_hidden_ranges(project),
+                &camera_config,
+            )
+        })
+        .unwrap_or_default();
+    if samples.is_empty() {
+        return vec![(0, max_left / 2.0)];
+    }
+
+    let src_w = source_width as f64;
+    let src_h = source_height as f64;
+    let safe_fps = render_fps.max(1.0);
+    let mut points: Vec<(u64, f64)> = samples
+        .iter()
+        .map(|sample| {
+            let ts = map_time_ms(sample.ts, project_duration_ms, source_duration_ms);
+            let frame = ts as f64 * safe_fps / 1000.0;
+            let zoom = sample_camera_axis_value(camera_states, frame, safe_fps, |s| s.zoom, 1.0);
+            let offset_x =
+                sample_camera_axis_value(camera_states, frame, safe_fps, |s| s.offset_x, 0.0);
+            let offset_y =
+                sample_camera_axis_value(camera_states, frame, safe_fps, |s| s.offset_y, 0.0);
+            let (center_x, _) = map_cursor_to_output_space(
+                sample.center_x.clamp(0.0, 1.0) * src_w,
+                sample.center_y.clamp(0.0, 1.0) * src_h,
+                zoom,
+                offset_x,
+                offset_y,
+                src_w,
+                src_h,
+                canvas_width as f64,
+                canvas_height as f64,
+            );
+            (
+                ts,
+                (center_x - crop_width as f64 / 2.0).clamp(0.0, max_left),
+            )
+        })
+        .collect();
+    points.sort_by_key(|point| point.0);
+    points.dedup_by(|left, right| left.0 == right.0);
+
+    let max_points = adaptive_sample_budget(
+        source_duration_ms,
+        MAX_CAMERA_POINTS_FOR_EXPR,
+        MAX_CAMERA_POINTS_FOR_EXPR_HARD_CAP,
+        CAMERA_POINTS_BUDGET_GROWTH_PER_SEC,
+        points.len(),
+    );
+    decimate_time_value_points(&points, max_points)
+}
+
+/// Static counterpart of [`build_export_filter_graph`] for one frame: the camera becomes a
+/// plain `scale`+`crop` of the zoomed layer described by `camera`.
+fn build_still_filter(
+    source_crop: Option<SourceCrop>,
+    camera: CameraCrop,
+    (source_width, source_height): (u32, u32),
+    (target_width, target_height): (u32, u32),
+    background: &FrameBackground,
+    cursor_ass: Option<&Path>,
+) -> String {
+    let mut chain: Vec<String> = Vec::new();
+    if let Some(crop) = source_crop {
+        chain.push(crop.filter());
+    }
+    let zoomed_width = (source_width as f64 * camera.scale_x).round() as u32;
+    let zoomed_height = (source_height as f64 * camera.scale_y).round() as u32;
+    if zoomed_width > source_width || zoomed_height > source_height {
+        chain.push(format!(
+            "scale={zoomed_width}:{zoomed_height}:flags=lanczos,crop={source_width}:{source_height}:{left}:{top}",
+            left = camera.left as u32,
+            top = camera.top as u32,
+        ));
+    }
+    chain.push(frame_fill_filter(
+        background,
+        (target_width, target_height),
+        1,
+        ":flags=lanczos",
+    ));
+    if let Some(ass) = cursor_ass {
+        chain.push(format!("subtitles=filename='{}'", escape_filter_path(ass)));
+    }
+    chain.join(",")
+}
+
+/// Fits the camera frame into the output and fills the margins per `background`.
+///
+/// The fragment takes one unlabeled input and leaves one unlabeled output, so it serves both
+/// the export graph (`[cam]...[framed]`) and a plain `-vf` chain. Every variant places the
+/// frame where `pad` would, which keeps [`FrameFit`] valid for the cursor overlay.
+fn frame_fill_filter(
+    background: &FrameBackground,
+    size: (u32, u32),
+    fps: u32,
+    scale_flags: &str,
+) -> String {
+    tagged_frame_fill_filter(background, size, fps, scale_flags, "")
+}
+
+/// [`frame_fill_filter`] with `tag` appended to its internal labels, so several copies can
+/// share one graph.
+fn tagged_frame_fill_filter(
+    background: &FrameBackground,
+    (width, height): (u32, u32),
+    fps: u32,
+    scale_flags: &str,
+    tag: &str,
+) -> String {
+    let fit = format!("scale={width}:{height}:force_original_aspect_ratio=decrease{scale_flags}");
+    let cover = format!(
+        "scale={width}:{height}:force_original_aspect_ratio=increase,crop={width}:{height},setsar=1"
+    );
+    let pad = |color: &str| format!("{fit},pad={width}:{height}:(ow-iw)/2:(oh-ih)/2:{color}");
+    match background {
+        FrameBackground::None => pad("black"),
+        FrameBackground::Solid { color } => pad(
+            &parse_hex_rgb(color).map_or_else(
+                || "black".to_string(),
+                |(r, g, b)| format!("0x{r:02X}{g:02X}{b:02X}"),
+            ),
+        ),
+        FrameBackground::Blur { sigma } => format!(
+            "split=2[fgsrc{tag}][bgsrc{tag}];[bgsrc{tag}]{cover},gblur=sigma={sigma}[bg{tag}];\
+             [fgsrc{tag}]{fit}[fg{tag}];[bg{tag}][fg{tag}]overlay=(W-w)/2:(H-h)/2",
+            sigma = format_f64(*sigma)
+        ),
+        // The still image is looped onto the output frame rate; the overlay ends with the video.
+        FrameBackground::Image { path } => format!(
+            "{fit}[fg{tag}];movie=filename='{escaped}',loop=loop=-1:size=1,setpts=N/{fps}/TB,{cover}[bg{tag}];\
+             [bg{tag}][fg{tag}]overlay=(W-w)/2:(H-h)/2:shortest=1",
+            escaped = escape_filter_path(Path::new(path))
+        ),
+    }
+}
+
+/// Internal resolution multiplier of a supersampled camera: 2x unless the source already
+/// has twice the output's pixels on both axes.
+fn supersample_factor(
+    source_width: u32,
+    source_height: u32,
+    target_width: u32,
+    target_height: u32,
+) -> u32 {
+    if source_width >= target_width.saturating_mul(2)
+        && source_height >= target_height.saturating_mul(2)
+    {
+        1
+    } else {
+        2
+    }
+}
+
+fn build_camera_states(
+    project: &Project,
+    source_duration_ms: u64,
+    project_duration_ms: u64,
+    source_width: u32,
+    source_height: u32,
+    source_fps: f64,
+) -> Vec<CameraState> {
+    let safe_fps = source_fps.max(1.0);
+    let runtime_segments = build_runtime_segments(project);
+    let mut anchors = vec![0, project_duration_ms];
+    for segment in &runtime_segments {
+        anchors.push(segment.start_ts);
+        anchors.push(segment.end_ts);
+        anchors.extend(segment.target_points.iter().map(|point| point.ts));
+    }
+    anchors.sort_unstable();
+    anchors.dedup();
+
+    let sw = source_width as f64;
+    let sh = source_height as f64;
+    let full_rect = NormalizedRect {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+    };
+    let default_camera = rect_to_camera_values(full_rect, sw, sh);
+    let default_spring = default_spring_params();
+    let mut zoom_state = AxisSpringState {
+        value: default_camera.0,
+        velocity: 0.0,
+    };
+    let mut offset_x_state = AxisSpringState {
+        value: default_camera.1,
+        velocity: 0.0,
+    };
+    let mut offset_y_state = AxisSpringState {
+        value: default_camera.2,
+        velocity: 0.0,
+    };
+
+    let mut states: Vec<CameraState> = Vec::new();
+    for pair in anchors.windows(2) {
+        let start_ts = pair[0];
+        let end_ts = pair[1];
+        if end_ts <= start_ts {
+            continue;
+        }
+
+        let (target_camera, spring) =
+            if let Some(segment) = resolve_runtime_segment(&runtime_segments, start_ts) {
+                let target_rect = target_rect_at_ts(segment, start_ts);
+                (rect_to_camera_values(target_rect, sw, sh), segment.spring)
+            } else {
+                (default_camera, default_spring)
+            };
+
+        let start_ms = map_time_ms(start_ts, project_duration_ms, source_duration_ms);
+        let end_ms = map_time_ms(end_ts, project_duration_ms, source_duration_ms);
+        if end_ms <= start_ms {
+            continue;
+        }
+
+        let start_frame = start_ms as f64 / 1000.0 * safe_fps;
+        let end_frame = end_ms as f64 / 1000.0 * safe_fps;
+        if end_frame <= start_frame {
+            continue;
+        }
+
+        states.push(CameraState {
+            start_frame,
+            end_frame,
+            spring,
+            zoom: AxisSpringSegment {
+                start: zoom_state.value,
+                velocity: zoom_state.velocity,
+                target: target_camera.0,
+            },
+            offset_x: AxisSpringSegment {
+                start: offset_x_state.value,
+                velocity: offset_x_state.velocity,
+                target: target_camera.1,
+            },
+            offset_y: AxisSpringSegment {
+                start: offset_y_state.value,
+                velocity: offset_y_state.velocity,
+                target: target_camera.2,
+            },
+        });
+
+        let dt_seconds = (end_frame - start_frame).max(0.0) / safe_fps;
+        zoom_state = evaluate_spring_axis(zoom_state, target_camera.0, spring, dt_seconds);
+        offset_x_state = evaluate_spring_axis(offset_x_state, target_camera.1, spring, dt_seconds);
+        offset_y_state = evaluate_spring_axis(offset_y_state, target_camera.2, spring, dt_seconds);
+    }
+
+    states
+}
+
+fn build_runtime_segments(project: &Project) -> Vec<SegmentRuntime> {
+    let mut segments = project.timeline.zoom_segments.clone();
+    segments.sort_by_key(|segment| segment.start_ts);
+
+    let mut runtime: Vec<SegmentRuntime> = Vec::new();
+    for segment in segments {
+        let start_ts = segment.start_ts.min(project.duration_ms);
+        let end_ts = segment.end_ts.min(project.duration_ms);
+        if end_ts <= start_ts {
+            continue;
+        }
+
+        let base_rect = normalize_segment_rect(segment.initial_rect.clone());
+        let target_points = if segment.target_points.is_empty() {
+            normalize_target_points(
+                target_points_from_legacy_pan(&segment, &base_rect),
+                start_ts,
+                end_ts,
+                &base_rect,
+            )
+        } else {
+            normalize_target_points(segment.target_points.clone(), start_ts, end_ts, &base_rect)
+        };
+
+        runtime.push(SegmentRuntime {
+            start_ts,
+            end_ts,
+            base_rect,
+            target_points,
+            spring: normalize_spring_params(&segment.spring),
+        });
+    }
+
+    runtime.sort_by_key(|segment| segment.start_ts);
+    runtime
+}
+
+fn normalize_target_points(
+    points: Vec<TargetPoint>,
+    start_ts: u64,
+    end_ts: u64,
+    fallback_rect: &NormalizedRect,
+) -> Vec<TargetPoint> {
+    let mut normalized = points
+        .into_iter()
+        .map(|point| TargetPoint {
+            ts: point.ts.clamp(start_ts, end_ts),
+            // An explicit zoom is folded into the rect, so the camera only follows rects.
+            rect: normalize_segment_rect(point.effective_rect()),
+            zoom: None,
+        })
+        .collect::<Vec<_>>();
+    normalized.sort_by_key(|point| point.ts);
+
+    let mut dedup: Vec<TargetPoint> = Vec::new();
+    for point in normalized {
+        if let Some(last) = dedup.last_mut() {
+            if last.ts == point.ts {
+                *last = point;
+                continue;
+            }
+        }
+        dedup.push(point);
+    }
+
+    if dedup.is_empty() {
+        return vec![
+            TargetPoint {
+                ts: start_ts,
+                rect: fallback_rect.clone(),
+                zoom: None,
+            },
+            TargetPoint {
+                ts: end_ts,
+                rect: fallback_rect.clone(),
+                zoom: None,
+            },
+        ];
+    }
+
+    if dedup.first().is_some_and(|point| point.ts > start_ts) {
+        let rect = dedup[0].rect.clone();
+        dedup.insert(
+            0,
+            TargetPoint {
+                ts: start_ts,
+                rect,
+                zoom: None,
+            },
+        );
+    }
+
+    if dedup.last().is_some_and(|point| point.ts < end_ts) {
+        let rect = dedup
+            .last()
+            .expect("target points has last element")
+            .rect
+            .clone();
+        dedup.push(TargetPoint {
+            ts: end_ts,
+            rect,
+            zoom: None,
+        });
+    }
+
+    dedup
+}
+
+fn target_points_from_legacy_pan(
+    segment: &ZoomSegment,
+    base_rect: &NormalizedRect,
+) -> Vec<TargetPoint> {
+    let mut pan_trajectory = segment.pan_trajectory.clone();
+    pan_trajectory.sort_by_key(|keyframe| keyframe.ts);
+
+    if pan_trajectory.is_empty() {
+        return vec![
+            TargetPoint {
+                ts: segment.start_ts,
+                rect: base_rect.clone(),
+                zoom: None,
+            },
+            TargetPoint {
+                ts: segment.end_ts,
+                rect: base_rect.clone(),
+                zoom: None,
+            },
+        ];
+    }
+
+    let (start_offset_x, start_offset_y) = pan_offset_at_ts(&pan_trajectory, segment.start_ts);
+    let mut points = vec![TargetPoint {
+        ts: segment.start_ts,
+        rect: apply_pan_offset(base_rect, start_offset_x, start_offset_y),
+        zoom: None,
+    }];
+
+    for keyframe in &pan_trajectory {
+        if keyframe.ts < segment.start_ts || keyframe.ts > segment.end_ts {
+            continue;
+        }
+        points.push(TargetPoint {
+            ts: keyframe.ts,
+            rect: apply_pan_offset(base_rect, keyframe.offset_x, keyframe.offset_y),
+            zoom: None,
+        });
+    }
+
+    let (end_offset_x, end_offset_y) = pan_offset_at_ts(&pan_trajectory, segment.end_ts);
+    points.push(TargetPoint {
+        ts: segment.end_ts,
+        rect: apply_pan_offset(base_rect, end_offset_x, end_offset_y),
+        zoom: None,
+    });
+    points
+}
+
+fn resolve_runtime_segment<'a>(
+    segments: &'a [SegmentRuntime],
+    ts: u64,
+) -> Option<&'a SegmentRuntime> {
+    segments
+        .iter()
+        .rev()
+        .find(|segment| ts >= segment.start_ts && ts < segment.end_ts)
+}
+
+fn target_rect_at_ts(segment: &SegmentRuntime, ts: u64) -> NormalizedRect {
+    if segment.target_points.is_empty() {
+        return segment.base_rect.clone();
+    }
+    if ts <= segment.target_points[0].ts {
+        return segment.target_points[0].rect.clone();
+    }
+    for point in segment.target_points.iter().rev() {
+        if ts >= point.ts {
+            return point.rect.clone();
+        }
+    }
+    segment.target_points[0].rect.clone()
+}
+
+fn default_spring_params() -> SpringParams {
+    SpringParams {
+        mass: DEFAULT_SPRING_MASS,
+        stiffness: DEFAULT_SPRING_STIFFNESS,
+        damping: DEFAULT_SPRING_DAMPING,
+    }
+}
+
+fn normalize_spring_params(spring: &CameraSpring) -> SpringParams {
+    SpringParams {
+        mass: spring.mass.max(0.001),
+        stiffness: spring.stiffness.max(0.001),
+        damping: spring.damping.max(0.0),
+    }
+}
+
+fn evaluate_spring_axis(
+    state: AxisSpringState,
+    target: f64,
+    spring: SpringParams,
+    dt_seconds: f64,
+) -> AxisSpringState {
+    let dt = dt_seconds.max(0.0);
+    if dt <= 0.0 {
+        return state;
+    }
+
+    let mass = spring.mass.max(0.001);
+    let stiffness = spring.stiffness.max(0.001);
+    let damping = spring.damping.max(0.0);
+    let y0 = state.value - target;
+    let v0 = state.velocity;
+    let alpha = damping / (2.0 * mass);
+    let omega_sq = stiffness / mass;
+    let discriminant = alpha * alpha - omega_sq;
+
+    let (y, v) = if discriminant.abs() <= 1e-9 {
+        let c2 = v0 + alpha * y0;
+        let exp = (-alpha * dt).exp();
+        let y = (y0 + c2 * dt) * exp;
+        let v = (v0 - alpha * c2 * dt) * exp;
+        (y, v)
+    } else if discriminant > 0.0 {
+        let sqrt_disc = discriminant.sqrt();
+        let r1 = -alpha + sqrt_disc;
+        let r2 = -alpha - sqrt_disc;
+        let denom = (r1 - r2).abs().max(1e-9);
+        let c1 = (v0 - r2 * y0) / denom;
+        let c2 = y0 - c1;
+        let exp1 = (r1 * dt).exp();
+        let exp2 = (r2 * dt).exp();
+        let y = c1 * exp1 + c2 * exp2;
+        let v = c1 * r1 * exp1 + c2 * r2 * exp2;
+        (y, v)
+    } else {
+        let beta = (omega_sq - alpha * alpha).max(1e-9).sqrt();
+        let c1 = y0;
+        let c2 = (v0 + alpha * y0) / beta;
+        let exp = (-alpha * dt).exp();
+        let cos = (beta * dt).cos();
+        let sin = (beta * dt).sin();
+        let y = exp * (c1 * cos + c2 * sin);
+        let v = exp * ((-alpha) * (c1 * cos + c2 * sin) + (-c1 * beta * sin + c2 * beta * cos));
+        (y, v)
+    };
+
+    AxisSpringState {
+        value: target + y,
+        velocity: v,
+    }
+}
+
+fn build_camera_value_expr(
+    states: &[CameraState],
+    axis: impl Fn(&CameraState) -> AxisSpringSegment + Copy,
+    default_value: f64,
+    source_fps: f64,
+    time_base: CameraTimeBase,
+) -> String {
+    let mut ordered = states.to_vec();
+    ordered.sort_by(|left, right| {
+        left.start_frame
+            .total_cmp(&right.start_frame)
+            .then_with(|| left.end_frame.total_cmp(&right.end_frame))
+    });
+    let safe_fps = source_fps.max(1.0);
+
+    if ordered.len() > MAX_CAMERA_STATES_FOR_ANALYTIC_EXPR {
+        let sampled = sample_camera_value_points(&ordered, axis, default_value, safe_fps);
+        let duration_ms = sampled.last().map(|(ts, _)| *ts).unwrap_or(0);
+        let max_points = adaptive_sample_budget(
+            duration_ms,
+            MAX_CAMERA_POINTS_FOR_EXPR,
+            MAX_CAMERA_POINTS_FOR_EXPR_HARD_CAP,
+            CAMERA_POINTS_BUDGET_GROWTH_PER_SEC,
+            sampled.len(),
+        );
+        let reduced = decimate_time_value_points(&sampled, max_points);
+        return build_piecewise_track_expr(&reduced, duration_ms);
+    }
+
+    let default_expr = format_f64(default_value);
+    let mut terms = Vec::with_capacity(ordered.len() + 1);
+    terms.push(default_expr.clone());
+
+    for state in ordered {
+        let axis_state = axis(&state);
+        let (clock, start, end, elapsed) = match time_base {
+            CameraTimeBase::FrameIndex => (
+                "n",
+                format_f64(state.start_frame),
+                format_f64(state.end_frame),
+                format!(
+                    "max(0,(n-{start})/{fps})",
+                    start = format_f64(state.start_frame),
+                    fps = format_f64(safe_fps)
+                ),
+            ),
+            CameraTimeBase::Timestamp => {
+                let start_s = state.start_frame / safe_fps;
+                (
+                    "t",
+                    format_f64(start_s),
+                    format_f64(state.end_frame / safe_fps),
+                    format!("max(0,t-{start})", start = format_f64(start_s)),
+                )
+            }
+        };
+        let value = spring_value_expr(&elapsed, axis_state, state.spring);
+
+        // Build a flat sum of disjoint interval terms instead of deeply nested if-expressions.
+        // Nested expressions can exceed FFmpeg parser depth on projects with many segments.
+        terms.push(format!(
+            "if(gte({clock},{start})*lt({clock},{end}),({value})-({default}),0)",
+            value = value,
+            default = default_expr
+        ));
+    }
+
+    terms.join("+")
+}
+
+/// True when every camera state rests at 1x with no offset, i.e. the camera never moves.
+fn is_identity_camera(states: &[CameraState]) -> bool {
+    const EPSILON: f64 = 1e-6;
+    let at_rest = |axis: AxisSpringSegment, value: f64| {
+        (axis.start - value).abs() < EPSILON
+            && (axis.target - value).abs() < EPSILON
+            && axis.velocity.abs() < EPSILON
+    };
+    states.iter().all(|state| {
+        at_rest(state.zoom, 1.0) && at_rest(state.offset_x, 0.0) && at_rest(state.offset_y, 0.0)
+    })
+}
+
+/// Keeps the animated zoom at or above 1 on every frame.
+///
+/// Springs only clamp their targets: an underdamped zoom-out overshoots below 1 and the
+/// shrunken layer would uncover the frame underneath. Offsets need no extra wrapping:
+/// `overlay` already clamps them to `overlay_w-main_w` of the per-frame scaled layer.
+fn clamp_camera_zoom_expr(zoom_expr: &str) -> String {
+    format!("max(1,{zoom_expr})")
+}
+
+fn sample_camera_value_points(
+    states: &[CameraState],
+    axis: impl Fn(&CameraState) -> AxisSpringSegment + Copy,
+    default_value: f64,
+    source_fps: f64,
+) -> Vec<(u64, f64)> {
+    if states.is_empty() {
+        return vec![(0, default_value)];
+    }
+
+    let safe_fps = source_fps.max(1.0);
+    let max_frame = states
+        .iter()
+        .map(|state| state.end_frame)
+        .fold(0.0, f64::max)
+        .ceil()
+        .max(1.0);
+    let step_frames = (safe_fps / CAMERA_FALLBACK_SAMPLE_RATE_HZ.max(1.0)).max(1.0);
+
+    let mut points: Vec<(u64, f64)> = Vec::new();
+    let mut frame = 0.0;
+    while frame <= max_frame {
+        let ts_ms = ((frame / safe_fps) * 1000.0).round().max(0.0) as u64;
+        let value = sample_camera_axis_value(states, frame, safe_fps, axis, default_value);
+        points.push((ts_ms, value));
+        frame += step_frames;
+    }
+
+    let last_ts_ms = ((max_frame / safe_fps) * 1000.0).round().max(0.0) as u64;
+    if points
+        .last()
+        .map(|(ts, _)| *ts != last_ts_ms)
+        .unwrap_or(true)
+    {
+        let value = sample_camera_axis_value(states, max_frame, safe_fps, axis, default_value);
+        points.push((last_ts_ms, value));
+    }
+
+    points.sort_by_key(|(ts, _)| *ts);
+    points.dedup_by(|left, right| left.0 == right.0);
+    points
+}
+
+fn spring_value_expr(
+    elapsed_expr: &str,
+    axis_state: AxisSpringSegment,
+    spring: SpringParams,
+) -> String {
+    let mass = spring.mass.max(0.001);
+    let stiffness = spring.stiffness.max(0.001);
+    let damping = spring.damping.max(0.0);
+    let y0 = axis_state.start - axis_state.target;
+    let v0 = axis_state.velocity;
+    let alpha = damping / (2.0 * mass);
+    let omega_sq = stiffness / mass;
+    let discriminant = alpha * alpha - omega_sq;
+
+    if discriminant.abs() <= 1e-9 {
+        let c2 = v0 + alpha * y0;
+        return format!(
+            "{target}+(({y0})+({c2})*({t}))*exp(-{alpha}*({t}))",
+            target = format_f64(axis_state.target),
+            y0 = format_f64(y0),
+            c2 = format_f64(c2),
+            alpha = format_f64(alpha),
+            t = elapsed_expr
+        );
+    }
+
+    if discriminant > 0.0 {
+        let sqrt_disc = discriminant.sqrt();
+        let r1 = -alpha + sqrt_disc;
+        let r2 = -alpha - sqrt_disc;
+        let denom = (r1 - r2).abs().max(1e-9);
+        let c1 = (v0 - r2 * y0) / denom;
+        let c2 = y0 - c1;
+        return format!(
+            "{target}+({c1})*exp({r1}*({t}))+({c2})*exp({r2}*({t}))",
+            target = format_f64(axis_state.target),
+            c1 = format_f64(c1),
+            c2 = format_f64(c2),
+            r1 = format_f64(r1),
+            r2 = format_f64(r2),
+            t = elapsed_expr
+        );
+    }
+
+    let beta = (omega_sq - alpha * alpha).max(1e-9).sqrt();
+    let c1 = y0;
+    let c2 = (v0 + alpha * y0) / beta;
+    format!(
+        "{target}+exp(-{alpha}*({t}))*(({c1})*cos({beta}*({t}))+({c2})*sin({beta}*({t})))",
+        target = format_f64(axis_state.target),
+        alpha = format_f64(alpha),
+        c1 = format_f64(c1),
+        c2 = format_f64(c2),
+        beta = format_f64(beta),
+        t = elapsed_expr
+    )
+}
+
+fn rect_to_camera_values(
+    rect: NormalizedRect,
+    source_width: f64,
+    source_height: f64,
+) -> (f64, f64, f64) {
+    let zoom = (1.0 / rect.width.max(rect.height).max(0.0001)).clamp(1.0, 20.0);
+    let crop_w = (source_width / zoom).clamp(32.0, source_width);
+    let crop_h = (source_height / zoom).clamp(32.0, source_height);
+
+    let center_x = (rect.x + rect.width / 2.0) * source_width;
+    let center_y = (rect.y + rect.height / 2.0) * source_height;
+    let crop_x = (center_x - crop_w / 2.0).clamp(0.0, (source_width - crop_w).max(0.0));
+    let crop_y = (center_y - crop_h / 2.0).clamp(0.0, (source_height - crop_h).max(0.0));
+
+    let max_offset_x = (source_width * zoom - source_width).max(0.0);
+    let max_offset_y = (source_height * zoom - source_height).max(0.0);
+    let offset_x = (crop_x * zoom).clamp(0.0, max_offset_x);
+    let offset_y = (crop_y * zoom).clamp(0.0, max_offset_y);
+
+    (zoom, offset_x, offset_y)
+}
+
+/// Effective zoom and normalized crop center for a segment rect, using the same
+/// clamping as the export camera.
+pub(crate) fn segment_rect_camera_summary(
+    rect: &NormalizedRect,
+    source_width: f64,
+    source_height: f64,
+) -> (f64, f64, f64) {
+    let source_width = source_width.max(1.0);
+    let source_height = source_height.max(1.0);
+    let (zoom, offset_x, offset_y) = rect_to_camera_values(
+        normalize_segment_rect(rect.clone()),
+        source_width,
+        source_height,
+    );
+    let crop_w = (source_width / zoom).clamp(32.0, source_width);
+    let crop_h = (source_height / zoom).clamp(32.0, source_height);
+    let center_x = (offset_x / zoom + crop_w / 2.0) / source_width;
+    let center_y = (offset_y / zoom + crop_h / 2.0) / source_height;
+    (zoom, center_x, center_y)
+}
+
+/// Camera crop at one editor timestamp, as the export will render it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraRectSample {
+    pub ts: u64,
+    pub zoom: f64,
+    /// Visible part of the source in normalized coordinates.
+    pub rect: NormalizedRect,
+}
+
+/// Sampling rate for editor previews: one frame per millisecond, so timestamps map to frames
+/// exactly. The springs are analytic, so sampled values do not depend on the rate.
+const PREVIEW_SAMPLE_FPS: f64 = 1000.0;
+
+/// Evaluates the export camera at `timestamps` (clamped to the segment) for one zoom segment.
+///
+/// Camera states are built for the whole project so the spring carries over from earlier
+/// segments exactly as it does in the export.
+pub(crate) fn sample_segment_camera_rects(
+    project: &Project,
+    segment_id: &str,
+    timestamps: &[u64],
+) -> Result<Vec<CameraRectSample>, String> {
+    let segment = project
+        .timeline
+        .zoom_segments
+        .iter()
+        .find(|segment| segment.id == segment_id)
+        .ok_or_else(|| format!("Zoom segment not found: {segment_id}"))?;
+    let duration_ms = project.duration_ms.max(1);
+    let first_ts = segment.start_ts.min(duration_ms - 1);
+    let last_ts = segment.end_ts.min(duration_ms - 1).max(first_ts);
+
+    let source_width = project.video_width.max(1);
+    let source_height = project.video_height.max(1);
+    let states = build_camera_states(
+        project,
+        duration_ms,
+        duration_ms,
+        source_width,
+        source_height,
+        PREVIEW_SAMPLE_FPS,
+    );
+
+    let sw = source_width as f64;
+    let sh = source_height as f64;
+    Ok(timestamps
+        .iter()
+        .map(|ts| {
+            let ts = (*ts).clamp(first_ts, last_ts);
+            let frame = ts as f64;
+            let zoom =
+                sample_camera_axis_value(&states, frame, PREVIEW_SAMPLE_FPS, |s| s.zoom, 1.0);
+            let offset_x =
+                sample_camera_axis_value(&states, frame, PREVIEW_SAMPLE_FPS, |s| s.offset_x, 0.0);
+            let offset_y =
+                sample_camera_axis_value(&states, frame, PREVIEW_SAMPLE_FPS, |s| s.offset_y, 0.0);
+            CameraRectSample {
+                ts,
+                zoom: zoom.max(1.0),
+                rect: camera_values_to_rect(zoom, offset_x, offset_y, sw, sh),
+            }
+        })
+        .collect())
+}
+
+/// Cursor path as the export draws it, in project time and normalized coordinates.
+///
+/// Depends only on the events and the cursor render settings, so the editor can keep
+/// one per project and sample it repeatedly while scrubbing.
+#[derive(Debug, Clone)]
+pub(crate) struct CursorTrack {
+    points: Vec<(u64, f64, f64)>,
+    click_times: Vec<u64>,
+    screen_width: f64,
+    screen_height: f64,
+}
+
+/// Cursor state at one editor timestamp.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorSample {
+    pub ts: u64,
+    /// Position in screen pixels of the recording.
+    pub x: f64,
+    pub y: f64,
+    /// Position in normalized coordinates (0.0–1.0).
+    pub normalized_x: f64,
+    pub normalized_y: f64,
+    /// False inside cursor hidden ranges or when the cursor is not rendered.
+    pub visible: bool,
+    /// A click pulse is playing at this moment.
+    pub click_active: bool,
+}
+
+impl CursorTrack {
+    pub(crate) fn new(events: &EventsFile, cursor: &CursorSettings, duration_ms: u64) -> Self {
+        let screen_width = events.screen_width.max(1) as f64;
+        let screen_height = events.screen_height.max(1) as f64;
+        let duration_ms = duration_ms.max(1);
+        let mut points = export_cursor_points(&events.events, screen_width, screen_height, cursor)
+            .into_iter()
+            .map(|point| {
+                (
+                    apply_cursor_timing_offset_ms(point.ts, duration_ms),
+                    point.x,
+                    point.y,
+                )
+            })
+            .collect::<Vec<_>>();
+        points.sort_by_key(|point| point.0);
+        points.dedup_by(|left, right| left.0 == right.0);
+
+        let mut click_times = events
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                InputEvent::Click { ts, .. } => {
+                    Some(apply_cursor_timing_offset_ms(*ts, duration_ms))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        click_times.sort_unstable();
+
+        Self {
+            points,
+            click_times,
+            screen_width,
+            screen_height,
+        }
+    }
+
+    /// Samples the track; only `show_cursor` and `hidden_ranges` are read from `cursor`,
+    /// the rest was fixed when the track was built.
+    pub(crate) fn sample(&self, ts: u64, cursor: &CursorSettings) -> CursorSample {
+        let (normalized_x, normalized_y) = interpolate_cursor_position(&self.points, ts);
+        let hidden = cursor
+            .hidden_ranges
+            .iter()
+            .any(|range| ts >= range.start_ts && ts < range.end_ts);
+        let click_active = self
+            .click_times
+            .iter()
+            .any(|click_ts| ts >= *click_ts && ((ts - click_ts) as f64) < CLICK_PULSE_TOTAL_MS);
+        let visible = cursor.show_cursor && !hidden && !self.points.is_empty();
+        CursorSample {
+            ts,
+            x: normalized_x * self.screen_width,
+            y: normalized_y * self.screen_height,
+            normalized_x,
+            normalized_y,
+            visible,
+            click_active: visible && click_active,
+        }
+    }
+}
+
+/// Inverse of [`rect_to_camera_values`] with the offset clamping used by the export overlay.
+fn camera_values_to_rect(
+    zoom: f64,
+    offset_x: f64,
+    offset_y: f64,
+    source_width: f64,
+    source_height: f64,
+) -> NormalizedRect {
+    let safe_zoom = zoom.max(1.0);
+    let max_offset_x = (source_width * safe_zoom - source_width).max(0.0);
+    let max_offset_y = (source_height * safe_zoom - source_height).max(0.0);
+    let crop_x = offset_x.clamp(0.0, max_offset_x) / safe_zoom;
+    let crop_y = offset_y.clamp(0.0, max_offset_y) / safe_zoom;
+
+    NormalizedRect {
+        x: crop_x / source_width,
+        y: crop_y / source_height,
+        width: 1.0 / safe_zoom,
+        height: 1.0 / safe_zoom,
+    }
+}
+
+fn normalize_segment_rect(rect: NormalizedRect) -> NormalizedRect {
+    let width = rect.width.clamp(0.001, 1.0);
+    let height = rect.height.clamp(0.001, 1.0);
+
+    NormalizedRect {
+        x: rect.x.clamp(0.0, 1.0 - width),
+        y: rect.y.clamp(0.0, 1.0 - height),
+        width,
+        height,
+    }
+}
+
+fn apply_pan_offset(base_rect: &NormalizedRect, offset_x: f64, offset_y: f64) -> NormalizedRect {
+    let normalized = normalize_segment_rect(base_rect.clone());
+    let x = (normalized.x + offset_x).clamp(0.0, 1.0 - normalized.width);
+    let y = (normalized.y + offset_y).clamp(0.0, 1.0 - normalized.height);
+
+    NormalizedRect {
+        x,
+        y,
+        width: normalized.width,
+        height: normalized.height,
+    }
+}
+
+fn pan_offset_at_ts(pan_trajectory: &[PanKeyframe], ts: u64) -> (f64, f64) {
+    if pan_trajectory.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    if ts <= pan_trajectory[0].ts {
+        return (0.0, 0.0);
+    }
+
+    let last = pan_trajectory
+        .last()
+        .expect("pan trajectory has at least one keyframe");
+    if ts >= last.ts {
+        return (last.offset_x, last.offset_y);
+    }
+
+    for pair in pan_trajectory.windows(2) {
+        let left = &pair[0];
+        let right = &pair[1];
+        if ts < left.ts || ts > right.ts {
+            continue;
+        }
+        let span = right.ts.saturating_sub(left.ts);
+        if span == 0 {
+            return (right.offset_x, right.offset_y);
+        }
+        let t = (ts.saturating_sub(left.ts)) as f64 / span as f64;
+        return (
+            left.offset_x + (right.offset_x - left.offset_x) * t,
+            left.offset_y + (right.offset_y - left.offset_y) * t,
+        );
+    }
+
+    (last.offset_x, last.offset_y)
+}
+
+fn format_f64(value: f64) -> String {
+    format!("{value:.4}")
+}
+
+fn build_cursor_overlay_plan(
+    project: &Project,
+    events: Option<&EventsFile>,
+    camera_states: &[CameraState],
+    hidden_ranges_ms: &[(u64, u64)],
+    source_duration_ms: u64,
+    project_duration_ms: u64,
+    source_width: u32,
+    source_height: u32,
+    target_width: u32,
+    target_height: u32,
+    render_fps: f64,
+) -> Result<Option<CursorOverlayPlan>, String> {
+    let Some(events_file) = events else {
+        return Ok(None);
+    };
+    if events_file.events.is_empty() {
+        return Ok(None);
+    }
+
+    let Some(cursor_asset) = resolve_cursor_asset_for_render()? else {
+        return Ok(None);
+    };
+
+    let mut points = export_cursor_points(
+        &events_file.events,
+        events_file.screen_width.max(1) as f64,
+        events_file.screen_height.max(1) as f64,
+        &project.settings.cursor,
+    );
+    if points.is_empty() {
+        return Ok(None);
+    }
+
+    points.sort_by_key(|point| point.ts);
+    let raw_desired_samples = (((source_duration_ms as f64) / 100.0).ceil() as usize).max(2);
+    let cursor_expr_budget = adaptive_sample_budget(
+        source_duration_ms,
+        MAX_CURSOR_SAMPLES_FOR_EXPR,
+        MAX_CURSOR_SAMPLES_FOR_EXPR_HARD_CAP,
+        CURSOR_EXPR_BUDGET_GROWTH_PER_SEC,
+        raw_desired_samples,
+    );
+    let desired_samples = raw_desired_samples
+        .clamp(24, cursor_expr_budget.max(24))
+        .max(2);
+    let frame_count = desired_samples - 1;
+    let frame_step_ms = (source_duration_ms as f64 / frame_count as f64).max(1.0);
+
+    let raw_click_times: Vec<u64> = events_file
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            InputEvent::Click { ts, .. } => {
+                let mapped = apply_cursor_timing_offset_ms(
+                    map_time_ms(*ts, project_duration_ms, source_duration_ms),
+                    source_duration_ms,
+                );
+                (!is_hidden_at(mapped, hidden_ranges_ms)).then_some(mapped)
+            }
+            _ => None,
+        })
+        .collect();
+    let click_times = compact_click_times(
+        &decimate_u64_points(&raw_click_times, MAX_CLICK_EVENTS_FOR_EXPR),
+        MIN_CLICK_PULSE_GAP_MS,
+    );
+
+    let target_min_side = target_width.min(target_height).max(1) as f64;
+    let cursor_height_px =
+        (project.settings.cursor.size * target_min_side * CURSOR_SIZE_TO_FRAME_RATIO)
+            .clamp(8.0, 280.0);
+
+    let src_w = source_width as f64;
+    let src_h = source_height as f64;
     let dst_w = target_width as f64;
     let dst_h = target_height as f64;
     let mut mapped_points: Vec<(u64, f64, f64)> = points
         .into_iter()
-        .map(|point| {
+        .map(|point| {
+            (
+                apply_cursor_timing_offset_ms(
+                    map_time_ms(point.ts, project_duration_ms, source_duration_ms),
+                    source_duration_ms,
+                ),
+                (point.x * src_w).clamp(0.0, src_w),
+                (point.y * src_h).clamp(0.0, src_h),
+            )
+        })
+        .collect();
+    mapped_points.sort_by_key(|point| point.0);
+    mapped_points.dedup_by(|current, next| current.0 == next.0);
+
+    if mapped_points.is_empty() {
+        return Err("No mapped cursor points for export".to_string());
+    }
+    if mapped_points.len() == 1 {
+        let only = mapped_points[0];
+        mapped_points.push((source_duration_ms, only.1, only.2));
+    }
+
+    let mut sampled: Vec<(u64, f64, f64)> = Vec::with_capacity(frame_count + 1);
+    for frame in 0..=frame_count {
+        let frame_ms = ((frame as f64) * frame_step_ms)
+            .round()
+            .clamp(0.0, source_duration_ms as f64) as u64;
+        if is_hidden_at(frame_ms, hidden_ranges_ms) {
+            continue;
+        }
+        let frame_no = frame as f64;
+        let (src_x, src_y) = interpolate_cursor_position(&mapped_points, frame_ms);
+        let zoom =
+            sample_camera_axis_value(camera_states, frame_no, render_fps, |state| state.zoom, 1.0);
+        let offset_x = sample_camera_axis_value(
+            camera_states,
+            frame_no,
+            render_fps,
+            |state| state.offset_x,
+            0.0,
+        );
+        let offset_y = sample_camera_axis_value(
+            camera_states,
+            frame_no,
+            render_fps,
+            |state| state.offset_y,
+            0.0,
+        );
+
+        let (x, y) = map_cursor_to_output_space(
+            src_x, src_y, zoom, offset_x, offset_y, src_w, src_h, dst_w, dst_h,
+        );
+        sampled.push((frame_ms, x, y));
+    }
+    if sampled.is_empty() {
+        return Ok(None);
+    }
+
+    sampled.dedup_by(|left, right| {
+        left.0 == right.0 && (left.1 - right.1).abs() < 0.1 && (left.2 - right.2).abs() < 0.1
+    });
+    let sampled = decimate_cursor_samples(&sampled, cursor_expr_budget.max(24));
+
+    let x_points: Vec<(u64, f64)> = sampled.iter().map(|(ts, x, _)| (*ts, *x)).collect();
+    let y_points: Vec<(u64, f64)> = sampled.iter().map(|(ts, _, y)| (*ts, *y)).collect();
+
+    let x_track_expr = build_piecewise_track_expr(&x_points, source_duration_ms);
+    let y_track_expr = build_piecewise_track_expr(&y_points, source_duration_ms);
+
+    let base_cursor_scale = cursor_height_px / cursor_asset.height.max(1) as f64;
+    let pulse_factor_expr = build_click_pulse_factor_expr(&click_times);
+    let scale_expr = format!(
+        "({base_scale})*({pulse_factor})",
+        base_scale = format_f64(base_cursor_scale),
+        pulse_factor = pulse_factor_expr
+    );
+
+    let cursor_width_expr = format!(
+        "max(2,{asset_w}*({scale}))",
+        asset_w = format_f64(cursor_asset.width as f64),
+        scale = scale_expr
+    );
+    let cursor_height_expr = format!(
+        "max(2,{asset_h}*({scale}))",
+        asset_h = format_f64(cursor_asset.height as f64),
+        scale = scale_expr
+    );
+    let overlay_x_expr = format!(
+        "({x})-({hotspot_x})*({scale})",
+        x = x_track_expr,
+        hotspot_x = format_f64(cursor_asset.hotspot_x),
+        scale = scale_expr
+    );
+    let overlay_y_expr = format!(
+        "({y})-({hotspot_y})*({scale})",
+        y = y_track_expr,
+        hotspot_y = format_f64(cursor_asset.hotspot_y),
+        scale = scale_expr
+    );
+
+    let Some(shadow) = &project.settings.cursor.shadow else {
+        return Ok(Some(CursorOverlayPlan {
+            cursor_png_path: cursor_asset.png_path,
+            filter_chain: format!(
+                "[1:v]format=rgba,scale=w='{w}':h='{h}':eval=frame[cursor];\
+                 [framed][cursor]overlay=x='{x}':y='{y}':eval=frame:format=auto[vout]",
+                w = cursor_width_expr,
+                h = cursor_height_expr,
+                x = overlay_x_expr,
+                y = overlay_y_expr,
+            ),
+        }));
+    };
+
+    // The shadow is blurred at asset size, before the per-frame scale, on a copy padded so
+    // the blur is not clipped; the overlay then shifts it back by the scaled padding.
+    let (blur_px, offset_px) = shadow.pixels(cursor_asset.height.max(1) as f64);
+    let pad_px = (blur_px * 2.0).ceil();
+    let (r, g, b) = shadow.rgb();
+    let shadow_shift = |position: &str| {
+        format!(
+            "({position})+({shift})*({scale})",
+            shift = format_f64(offset_px - pad_px),
+            scale = scale_expr
+        )
+    };
+    Ok(Some(CursorOverlayPlan {
+        cursor_png_path: cursor_asset.png_path,
+        filter_chain: format!(
+            "[1:v]format=rgba,split[cursor_src][shadow_src];\
+             [shadow_src]lutrgb=r={r}:g={g}:b={b},\
+             pad=w=iw+{pad2}:h=ih+{pad2}:x={pad}:y={pad}:color=black@0,\
+             gblur=sigma={sigma},scale=w='{sw}':h='{sh}':eval=frame[cursor_shadow];\
+             [cursor_src]scale=w='{w}':h='{h}':eval=frame[cursor];\
+             [framed][cursor_shadow]overlay=x='{sx}':y='{sy}':eval=frame:format=auto[shadowed];\
+             [shadowed][cursor]overlay=x='{x}':y='{y}':eval=frame:format=auto[vout]",
+            pad = pad_px as u32,
+            pad2 = 2 * pad_px as u32,
+            sigma = format_f64(blur_px.max(0.01)),
+            sw = format!(
+                "max(2,{}*({scale_expr}))",
+                format_f64(cursor_asset.width as f64 + 2.0 * pad_px)
+            ),
+            sh = format!(
+                "max(2,{}*({scale_expr}))",
+                format_f64(cursor_asset.height as f64 + 2.0 * pad_px)
+            ),
+            w = cursor_width_expr,
+            h = cursor_height_expr,
+            sx = shadow_shift(&overlay_x_expr),
+            sy = shadow_shift(&overlay_y_expr),
+            x = overlay_x_expr,
+            y = overlay_y_expr,
+        ),
+    }))
+}
+
+fn build_vector_cursor_ass_file(
+    project: &Project,
+    events_file: &EventsFile,
+    camera_states: &[CameraState],
+    hidden_ranges_ms: &[(u64, u64)],
+    source_duration_ms: u64,
+    project_duration_ms: u64,
+    source_width: u32,
+    source_height: u32,
+    target_width: u32,
+    target_height: u32,
+    render_fps: f64,
+) -> Result<PathBuf, String> {
+    let mapped_points = vector_cursor_source_points(
+        project,
+        events_file,
+        source_duration_ms,
+        project_duration_ms,
+        source_width,
+        source_height,
+    )?;
+
+    let sample_fps = render_fps.clamp(VECTOR_CURSOR_MIN_SAMPLE_FPS, VECTOR_CURSOR_MAX_SAMPLE_FPS);
+    let frame_step_ms = (1000.0 / sample_fps).max(1.0);
+    let frame_count = ((source_duration_ms as f64 / frame_step_ms).ceil() as usize).max(2);
+
+    let src_w = source_width.max(1) as f64;
+    let src_h = source_height.max(1) as f64;
+    let dst_w = target_width.max(1) as f64;
+    let dst_h = target_height.max(1) as f64;
+
+    let raw_click_times: Vec<u64> = events_file
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            InputEvent::Click { ts, .. } => {
+                let mapped = apply_cursor_timing_offset_ms(
+                    map_time_ms(*ts, project_duration_ms, source_duration_ms),
+                    source_duration_ms,
+                );
+                (!is_hidden_at(mapped, hidden_ranges_ms)).then_some(mapped)
+            }
+            _ => None,
+        })
+        .collect();
+    let click_times = compact_click_times(
+        &decimate_u64_points(&raw_click_times, MAX_CLICK_EVENTS_FOR_EXPR),
+        MIN_CLICK_PULSE_GAP_MS,
+    );
+
+    let mut sampled: Vec<(u64, i64, i64, f64)> = Vec::with_capacity(frame_count + 1);
+    for frame in 0..=frame_count {
+        let frame_ms = ((frame as f64) * frame_step_ms)
+            .round()
+            .clamp(0.0, source_duration_ms as f64) as u64;
+        if is_hidden_at(frame_ms, hidden_ranges_ms) {
+            continue;
+        }
+        let frame_no = (frame_ms as f64 / 1000.0) * render_fps.max(1.0);
+        let (src_x, src_y) = interpolate_cursor_position(&mapped_points, frame_ms);
+        let zoom = sample_camera_axis_value(
+            camera_states,
+            frame_no,
+            render_fps.max(1.0),
+            |state| state.zoom,
+            1.0,
+        );
+        let offset_x = sample_camera_axis_value(
+            camera_states,
+            frame_no,
+            render_fps.max(1.0),
+            |state| state.offset_x,
+            0.0,
+        );
+        let offset_y = sample_camera_axis_value(
+            camera_states,
+            frame_no,
+            render_fps.max(1.0),
+            |state| state.offset_y,
+            0.0,
+        );
+        let (x, y) = map_cursor_to_output_space(
+            src_x, src_y, zoom, offset_x, offset_y, src_w, src_h, dst_w, dst_h,
+        );
+        let pulse_scale = sample_click_pulse_scale_scalar(&click_times, frame_ms);
+        let combined_scale = (zoom.max(1.0) * pulse_scale).clamp(0.5, 4.0);
+        sampled.push((frame_ms, x.round() as i64, y.round() as i64, combined_scale));
+    }
+
+    sampled.dedup_by(|left, right| {
+        left.0 == right.0
+            && left.1 == right.1
+            && left.2 == right.2
+            && (left.3 - right.3).abs() < 0.01
+    });
+    let vector_ass_budget = adaptive_sample_budget(
+        source_duration_ms,
+        BASE_VECTOR_CURSOR_ASS_SAMPLES,
+        MAX_VECTOR_CURSOR_ASS_SAMPLES,
+        VECTOR_CURSOR_ASS_BUDGET_GROWTH_PER_SEC,
+        sampled.len(),
+    );
+    let sampled = decimate_cursor_samples_scaled(&sampled, vector_ass_budget);
+    if sampled.len() < 2 {
+        return Err("No visible cursor samples after hidden-range filtering".to_string());
+    }
+
+    let (cursor_scale_percent, cursor_outline_px) =
+        vector_cursor_style(project, target_width, target_height);
+    let (glyph_path, (hotspot_x, hotspot_y)) = vector_cursor_glyph(project.settings.cursor.shape);
+    let (ass_path, mut file) = create_vector_cursor_ass(project, target_width, target_height)?;
+
+    for pair in sampled.windows(2) {
+        let (start_ms, x1, y1, start_scale) = pair[0];
+        let (end_ms, x2, y2, _) = pair[1];
+        if end_ms <= start_ms {
+            continue;
+        }
+        for (visible_start_ms, visible_end_ms) in
+            visible_subranges(start_ms, end_ms, hidden_ranges_ms)
+        {
+            if visible_end_ms <= visible_start_ms {
+                continue;
+            }
+
+            let span_ms = (end_ms - start_ms).max(1);
+            let lerp = |a: i64, b: i64, ts: u64| -> i64 {
+                let t = (ts.saturating_sub(start_ms)) as f64 / span_ms as f64;
+                (a as f64 + (b as f64 - a as f64) * t).round() as i64
+            };
+
+            let vx1 = lerp(x1, x2, visible_start_ms);
+            let vy1 = lerp(y1, y2, visible_start_ms);
+            let vx2 = lerp(x1, x2, visible_end_ms);
+            let vy2 = lerp(y1, y2, visible_end_ms);
+
+            let scale_percent = cursor_scale_percent * start_scale;
+            let outline_px = cursor_outline_px * start_scale.clamp(0.75, 2.5);
+            let dx = (hotspot_x * scale_percent / 100.0).round() as i64;
+            let dy = (hotspot_y * scale_percent / 100.0).round() as i64;
+
+            for (layer, (tags, offset)) in
+                vector_cursor_layers(&project.settings.cursor, scale_percent)
+                    .iter()
+                    .enumerate()
+            {
+                writeln!(
+                    file,
+                    "Dialogue: {layer},{},{},Cursor,,0,0,0,,{{\\an7\\p1\\fscx{:.2}\\fscy{:.2}\\bord{:.2}\\shad0{tags}\\move({},{},{},{})}}{}",
+                    format_ass_time(visible_start_ms),
+                    format_ass_time(visible_end_ms),
+                    scale_percent,
+                    scale_percent,
+                    outline_px,
+                    vx1 - dx + offset,
+                    vy1 - dy + offset,
+                    vx2 - dx + offset,
+                    vy2 - dy + offset,
+                    glyph_path
+                )
+                .map_err(|e| format!("Failed to write ass cursor event: {e}"))?;
+            }
+        }
+    }
+
+    Ok(ass_path)
+}
+
+/// One cursor event at the position of the still frame; `None` when the cursor is off or
+/// hidden at that moment.
+fn build_still_cursor_ass_file(
+    project: &Project,
+    events_file: &EventsFile,
+    source_ms: u64,
+    source_duration_ms: u64,
+    (zoom, offset_x, offset_y): (f64, f64, f64),
+    (source_width, source_height): (u32, u32),
+    (target_width, target_height): (u32, u32),
+) -> Result<Option<PathBuf>, String> {
+    let cursor = &project.settings.cursor;
+    if !cursor.show_cursor
+        || cursor.render_mode == CursorRenderMode::Off
+        || events_file.events.is_empty()
+    {
+        return Ok(None);
+    }
+    let project_duration_ms = project.duration_ms.max(1);
+    let hidden_ranges_ms = map_hidden_ranges_to_source(
+        &cursor.hidden_ranges,
+        project_duration_ms,
+        source_duration_ms,
+    );
+    if is_hidden_at(source_ms, &hidden_ranges_ms) {
+        return Ok(None);
+    }
+
+    let points = vector_cursor_source_points(
+        project,
+        events_file,
+        source_duration_ms,
+        project_duration_ms,
+        source_width,
+        source_height,
+    )?;
+    let (src_x, src_y) = interpolate_cursor_position(&points, source_ms);
+    let (x, y) = map_cursor_to_output_space(
+        src_x,
+        src_y,
+        zoom,
+        offset_x,
+        offset_y,
+        source_width.max(1) as f64,
+        source_height.max(1) as f64,
+        target_width.max(1) as f64,
+        target_height.max(1) as f64,
+    );
+
+    let scale = zoom.max(1.0).clamp(0.5, 4.0);
+    let (scale_percent, outline_px) = vector_cursor_style(project, target_width, target_height);
+    let scale_percent = scale_percent * scale;
+    let (glyph_path, (hotspot_x, hotspot_y)) = vector_cursor_glyph(cursor.shape);
+    let (ass_path, mut file) = create_vector_cursor_ass(project, target_width, target_height)?;
+    // The seeked input starts at zero, so a one-second event covers the single output frame.
+    for (layer, (tags, offset)) in vector_cursor_layers(cursor, scale_percent)
+        .iter()
+        .enumerate()
+    {
+        writeln!(
+            file,
+            "Dialogue: {layer},{},{},Cursor,,0,0,0,,{{\\an7\\p1\\fscx{:.2}\\fscy{:.2}\\bord{:.2}\\shad0{tags}\\pos({},{})}}{}",
+            format_ass_time(0),
+            format_ass_time(1_000),
+            scale_percent,
+            scale_percent,
+            outline_px * scale.clamp(0.75, 2.5),
+            (x - hotspot_x * scale_percent / 100.0).round() as i64 + offset,
+            (y - hotspot_y * scale_percent / 100.0).round() as i64 + offset,
+            glyph_path
+        )
+        .map_err(|e| format!("Failed to write ass cursor event: {e}"))?;
+    }
+
+    Ok(Some(ass_path))
+}
+
+/// Cursor track in source pixels and source time, with the cursor timing offset applied.
+fn vector_cursor_source_points(
+    project: &Project,
+    events_file: &EventsFile,
+    source_duration_ms: u64,
+    project_duration_ms: u64,
+    source_width: u32,
+    source_height: u32,
+) -> Result<Vec<(u64, f64, f64)>, String> {
+    let mut points = export_cursor_points(
+        &events_file.events,
+        events_file.screen_width.max(1) as f64,
+        events_file.screen_height.max(1) as f64,
+        &project.settings.cursor,
+    );
+    if points.is_empty() {
+        return Err("No cursor points available for export".to_string());
+    }
+
+    points.sort_by_key(|point| point.ts);
+
+    let src_w = source_width.max(1) as f64;
+    let src_h = source_height.max(1) as f64;
+    let mut mapped_points: Vec<(u64, f64, f64)> = points
+        .into_iter()
+        .map(|point| {
+            (
+                apply_cursor_timing_offset_ms(
+                    map_time_ms(point.ts, project_duration_ms, source_duration_ms),
+                    source_duration_ms,
+                ),
+                (point.x * src_w).clamp(0.0, src_w),
+                (point.y * src_h).clamp(0.0, src_h),
+            )
+        })
+        .collect();
+    mapped_points.sort_by_key(|point| point.0);
+    mapped_points.dedup_by(|left, right| left.0 == right.0);
+    if mapped_points.is_empty() {
+        return Err("No mapped cursor points for export".to_string());
+    }
+    if mapped_points.len() == 1 {
+        let only = mapped_points[0];
+        mapped_points.push((source_duration_ms, only.1, only.2));
+    }
+    Ok(mapped_points)
+}
+
+/// Cursor height as an ASS scale percent of a glyph [`VECTOR_CURSOR_ASS_BASE_HEIGHT`] units
+/// tall, and its outline width.
+fn vector_cursor_style(project: &Project, target_width: u32, target_height: u32) -> (f64, f64) {
+    let cursor = &project.settings.cursor;
+    let target_min_side = target_width.min(target_height).max(1) as f64;
+    let cursor_height_px =
+        (cursor.size * target_min_side * CURSOR_SIZE_TO_FRAME_RATIO).clamp(8.0, 220.0);
+    let outline_scale = if cursor.outline_scale.is_finite() {
+        cursor.outline_scale.clamp(0.0, 4.0)
+    } else {
+        1.0
+    };
+    (
+        (cursor_height_px / VECTOR_CURSOR_ASS_BASE_HEIGHT) * 100.0,
+        (cursor_height_px * 0.08).clamp(1.0, 5.0) * outline_scale,
+    )
+}
+
+/// ASS drawing of a cursor shape and its hotspot in drawing units.
+///
+/// `\an7` pins the drawing's top-left corner, so events shift by the scaled hotspot to put
+/// the arrow tip or the shape's centre on the cursor position. Every shape is drawn in the
+/// arrow's [`VECTOR_CURSOR_ASS_BASE_HEIGHT`] frame, which keeps the dot smaller than the rest.
+fn vector_cursor_glyph(shape: CursorShape) -> (&'static str, (f64, f64)) {
+    match shape {
+        CursorShape::Arrow => (VECTOR_CURSOR_ARROW_PATH, (0.0, 0.0)),
+        CursorShape::Hand => (VECTOR_CURSOR_HAND_PATH, (30.0, 0.0)),
+        CursorShape::Crosshair => (VECTOR_CURSOR_CROSSHAIR_PATH, (50.0, 50.0)),
+        CursorShape::Dot => (VECTOR_CURSOR_DOT_PATH, (20.0, 20.0)),
+    }
+}
+
+/// Extra override tags and pixel offset of each ASS layer drawn at a cursor position: the
+/// shadow, when enabled, under the cursor itself.
+///
+/// The shadow is the same glyph filled and outlined in the shadow colour and blurred with
+/// `\blur`, so it also works as a glow around light cursors when it has no offset.
+fn vector_cursor_layers(cursor: &CursorSettings, scale_percent: f64) -> Vec<(String, i64)> {
+    let mut layers = Vec::with_capacity(2);
+    if let Some(shadow) = &cursor.shadow {
+        let (blur_px, offset_px) =
+            shadow.pixels(VECTOR_CURSOR_ASS_BASE_HEIGHT * scale_percent / 100.0);
+        let (r, g, b) = shadow.rgb();
+        layers.push((
+            format!("\\blur{blur_px:.2}\\1c&H{b:02X}{g:02X}{r:02X}&\\3c&H{b:02X}{g:02X}{r:02X}&"),
+            offset_px.round() as i64,
+        ));
+    }
+    layers.push((String::new(), 0));
+    layers
+}
+
+/// `&HAABBGGRR` colour code for an opaque (r, g, b).
+fn ass_color((r, g, b): (u8, u8, u8)) -> String {
+    format!("&H00{b:02X}{g:02X}{r:02X}")
+}
+
+/// Creates a temporary ASS script with the cursor style; the caller appends the events.
+fn create_vector_cursor_ass(
+    project: &Project,
+    target_width: u32,
+    target_height: u32,
+) -> Result<(PathBuf, File), String> {
+    let cursor = &project.settings.cursor;
+    let fill = ass_color(cursor.fill_rgb());
+    create_ass_script(
+        "vcursor",
+        &project.id,
+        target_width,
+        target_height,
+        &format!(
+            "Style: Cursor,Arial,12,{fill},{fill},{},&H00000000,0,0,0,0,100,100,0,0,1,2,0,7,0,0,0,1",
+            ass_color(cursor.outline_rgb())
+        ),
+    )
+}
+
+/// Creates a temporary ASS script with one style line and the events header.
+fn create_ass_script(
+    kind: &str,
+    project_id: &str,
+    target_width: u32,
+    target_height: u32,
+    style: &str,
+) -> Result<(PathBuf, File), String> {
+    let ass_path = std::env::temp_dir().join(format!("nsc-{kind}-{}-{}.ass", project_id, now_ms()));
+    let mut file =
+        File::create(&ass_path).map_err(|e| format!("Failed to create {kind} ass file: {e}"))?;
+
+    writeln!(file, "[Script Info]").map_err(|e| format!("Failed to write ass header: {e}"))?;
+    writeln!(file, "ScriptType: v4.00+").map_err(|e| format!("Failed to write ass header: {e}"))?;
+    writeln!(file, "PlayResX: {target_width}")
+        .map_err(|e| format!("Failed to write ass header: {e}"))?;
+    writeln!(file, "PlayResY: {target_height}")
+        .map_err(|e| format!("Failed to write ass header: {e}"))?;
+    writeln!(file).map_err(|e| format!("Failed to write ass header: {e}"))?;
+    writeln!(file, "[V4+ Styles]").map_err(|e| format!("Failed to write ass styles: {e}"))?;
+    writeln!(
+        file,
+        "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding"
+    )
+    .map_err(|e| format!("Failed to write ass styles: {e}"))?;
+    writeln!(file, "{style}").map_err(|e| format!("Failed to write ass styles: {e}"))?;
+    writeln!(file).map_err(|e| format!("Failed to write ass styles: {e}"))?;
+    writeln!(file, "[Events]").map_err(|e| format!("Failed to write ass events: {e}"))?;
+    writeln!(
+        file,
+        "Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text"
+    )
+    .map_err(|e| format!("Failed to write ass events: {e}"))?;
+
+    Ok((ass_path, file))
+}
+
+/// Labeled zoom segments as ASS events in source time; `None` when no segment has a label.
+fn build_segment_label_ass_file(
+    project: &Project,
+    source_duration_ms: u64,
+    target_width: u32,
+    target_height: u32,
+) -> Result<Option<PathBuf>, String> {
+    let project_duration_ms = project.duration_ms.max(1);
+    let events: Vec<(u64, u64, &ZoomSegment, &SegmentLabel)> = project
+        .timeline
+        .zoom_segments
+        .iter()
+        .filter_map(|segment| {
+            let label = segment.label.as_ref()?;
+            let start = map_time_ms(segment.start_ts, project_duration_ms, source_duration_ms);
+            let end = map_time_ms(segment.end_ts, project_duration_ms, source_duration_ms);
+            (end > start && !label.text.trim().is_empty()).then_some((start, end, segment, label))
+        })
+        .collect();
+    if events.is_empty() {
+        return Ok(None);
+    }
+
+    // BorderStyle 3 draws an opaque box in the outline colour behind each line; every event
+    // overrides size, colours and alignment, so the style only carries the margins.
+    let margin_h = (target_width as f64 * LABEL_MARGIN_RATIO).round() as u32;
+    let margin_v = (target_height as f64 * LABEL_MARGIN_RATIO).round() as u32;
+    let (ass_path, mut file) = create_ass_script(
+        "labels",
+        &project.id,
+        target_width,
+        target_height,
+        &format!(
+            "Style: Label,Arial,32,&H00FFFFFF,&H00FFFFFF,&H00000000,&H00000000,-1,0,0,0,100,100,0,0,3,8,0,2,{margin_h},{margin_h},{margin_v},1"
+        ),
+    )?;
+    for (start, end, segment, label) in events {
+        writeln!(
+            file,
+            "Dialogue: 1,{},{},Label,,0,0,0,,{}{}",
+            format_ass_time(start),
+            format_ass_time(end),
+            segment_label_overrides(segment, label, target_height),
+            escape_ass_text(label.text.trim())
+        )
+        .map_err(|e| format!("Failed to write ass label event: {e}"))?;
+    }
+
+    Ok(Some(ass_path))
+}
+
+/// Override block for one label: `Auto` puts the label on the side of the frame away from
+/// the segment's target area, so it does not cover what the zoom is showing.
+fn segment_label_overrides(
+    segment: &ZoomSegment,
+    label: &SegmentLabel,
+    target_height: u32,
+) -> String {
+    let at_top = match label.position {
+        LabelPosition::Top => true,
+        LabelPosition::Bottom => false,
+        LabelPosition::Auto => segment.initial_rect.y + segment.initial_rect.height / 2.0 >= 0.5,
+    };
+    let font_size = (label.font_scale * target_height as f64).round().max(8.0);
+    let (r, g, b) = label.rgb().unwrap_or((255, 255, 255));
+    let box_alpha = ((1.0 - label.background_opacity.clamp(0.0, 1.0)) * 255.0).round() as u8;
+    format!(
+        "{{\\an{}\\fs{font_size}\\bord{:.0}\\c&H{b:02X}{g:02X}{r:02X}&\\3c&H000000&\\3a&H{box_alpha:02X}&\\shad0}}",
+        if at_top { 8 } else { 2 },
+        (font_size * 0.3).max(2.0)
+    )
+}
+
+/// Keeps user text literal: braces would open override blocks and backslashes start tags.
+fn escape_ass_text(text: &str) -> String {
+    text.replace('\\', "\u{FF3C}")
+        .replace('{', "(")
+        .replace('}', ")")
+        .replace("\r\n", "\\N")
+        .replace('\n', "\\N")
+}
+
+fn decimate_cursor_samples(points: &[(u64, f64, f64)], max_points: usize) -> Vec<(u64, f64, f64)> {
+    let keep_indices = select_motion_aware_indices(points.len(), max_points, |index| {
+        let (prev_t, prev_x, prev_y) = points[index - 1];
+        let (curr_t, curr_x, curr_y) = points[index];
+        let (next_t, next_x, next_y) = points[index + 1];
+
+        let total_dt_ms = next_t.saturating_sub(prev_t).max(1) as f64;
+        let alpha = (curr_t.saturating_sub(prev_t) as f64 / total_dt_ms).clamp(0.0, 1.0);
+        let interp_x = prev_x + (next_x - prev_x) * alpha;
+        let interp_y = prev_y + (next_y - prev_y) * alpha;
+        let fit_error = (curr_x - interp_x).hypot(curr_y - interp_y);
+
+        let dt1 = (curr_t.saturating_sub(prev_t).max(1) as f64) / 1000.0;
+        let dt2 = (next_t.saturating_sub(curr_t).max(1) as f64) / 1000.0;
+        let vx1 = (curr_x - prev_x) / dt1;
+        let vy1 = (curr_y - prev_y) / dt1;
+        let vx2 = (next_x - curr_x) / dt2;
+        let vy2 = (next_y - curr_y) / dt2;
+        let speed1 = vx1.hypot(vy1);
+        let speed2 = vx2.hypot(vy2);
+        let accel = (speed2 - speed1).abs();
+
+        let cross = (curr_x - prev_x) * (next_y - curr_y) - (curr_y - prev_y) * (next_x - curr_x);
+        let norm = ((curr_x - prev_x).hypot(curr_y - prev_y)
+            * (next_x - curr_x).hypot(next_y - curr_y))
+        .max(1.0);
+        let turn_factor = (cross.abs() / norm).clamp(0.0, 1.0);
+
+        fit_error * 3.0 + accel * 0.08 + turn_factor * 16.0
+    });
+
+    keep_indices
+        .into_iter()
+        .map(|index| points[index])
+        .collect()
+}
+
+fn decimate_cursor_samples_scaled(
+    points: &[(u64, i64, i64, f64)],
+    max_points: usize,
+) -> Vec<(u64, i64, i64, f64)> {
+    let keep_indices = select_motion_aware_indices(points.len(), max_points, |index| {
+        let (prev_t, prev_x, prev_y, prev_scale) = points[index - 1];
+        let (curr_t, curr_x, curr_y, curr_scale) = points[index];
+        let (next_t, next_x, next_y, next_scale) = points[index + 1];
+
+        let prev_x = prev_x as f64;
+        let prev_y = prev_y as f64;
+        let curr_x = curr_x as f64;
+        let curr_y = curr_y as f64;
+        let next_x = next_x as f64;
+        let next_y = next_y as f64;
+
+        let total_dt_ms = next_t.saturating_sub(prev_t).max(1) as f64;
+        let alpha = (curr_t.saturating_sub(prev_t) as f64 / total_dt_ms).clamp(0.0, 1.0);
+        let interp_x = prev_x + (next_x - prev_x) * alpha;
+        let interp_y = prev_y + (next_y - prev_y) * alpha;
+        let interp_scale = prev_scale + (next_scale - prev_scale) * alpha;
+
+        let fit_error = (curr_x - interp_x).hypot(curr_y - interp_y);
+        let scale_error = (curr_scale - interp_scale).abs() * 36.0;
+
+        let dt1 = (curr_t.saturating_sub(prev_t).max(1) as f64) / 1000.0;
+        let dt2 = (next_t.saturating_sub(curr_t).max(1) as f64) / 1000.0;
+        let speed1 = (curr_x - prev_x).hypot(curr_y - prev_y) / dt1;
+        let speed2 = (next_x - curr_x).hypot(next_y - curr_y) / dt2;
+        let accel = (speed2 - speed1).abs();
+
+        let scale_rate_1 = (curr_scale - prev_scale).abs() / dt1;
+        let scale_rate_2 = (next_scale - curr_scale).abs() / dt2;
+        let scale_accel = (scale_rate_2 - scale_rate_1).abs();
+
+        fit_error * 3.0 + scale_error + accel * 0.08 + scale_accel * 18.0
+    });
+
+    keep_indices
+        .into_iter()
+        .map(|index| points[index])
+        .collect()
+}
+
+fn decimate_time_value_points(points: &[(u64, f64)], max_points: usize) -> Vec<(u64, f64)> {
+    let keep_indices = select_motion_aware_indices(points.len(), max_points, |index| {
+        let (prev_t, prev_value) = points[index - 1];
+        let (curr_t, curr_value) = points[index];
+        let (next_t, next_value) = points[index + 1];
+
+        let total_dt_ms = next_t.saturating_sub(prev_t).max(1) as f64;
+        let alpha = (curr_t.saturating_sub(prev_t) as f64 / total_dt_ms).clamp(0.0, 1.0);
+        let interp = prev_value + (next_value - prev_value) * alpha;
+        let fit_error = (curr_value - interp).abs();
+
+        let dt1 = (curr_t.saturating_sub(prev_t).max(1) as f64) / 1000.0;
+        let dt2 = (next_t.saturating_sub(curr_t).max(1) as f64) / 1000.0;
+        let speed1 = (curr_value - prev_value).abs() / dt1;
+        let speed2 = (next_value - curr_value).abs() / dt2;
+        let accel = (speed2 - speed1).abs();
+
+        fit_error * 4.0 + accel * 0.2
+    });
+
+    keep_indices
+        .into_iter()
+        .map(|index| points[index])
+        .collect()
+}
+
+fn adaptive_sample_budget(
+    duration_ms: u64,
+    base_budget: usize,
+    hard_cap: usize,
+    growth_per_second: f64,
+    input_len: usize,
+) -> usize {
+    if input_len <= 2 {
+        return input_len;
+    }
+
+    let cap = hard_cap.max(2);
+    let base = base_budget.clamp(2, cap);
+    let extra = ((duration_ms as f64 / 1000.0).max(0.0) * growth_per_second.max(0.0)).round();
+    let grown = (base as f64 + extra).round() as usize;
+    grown.clamp(base, cap).min(input_len)
+}
+
+fn select_motion_aware_indices(
+    len: usize,
+    max_points: usize,
+    mut score_for_index: impl FnMut(usize) -> f64,
+) -> Vec<usize> {
+    if len <= max_points || max_points < 2 || len <= 2 {
+        return (0..len).collect();
+    }
+
+    let target_total = max_points.clamp(2, len);
+    let target_internal = target_total.saturating_sub(2).min(len.saturating_sub(2));
+    if target_internal == 0 {
+        return vec![0, len - 1];
+    }
+
+    let anchor_target = ((target_internal as f64) * 0.35).round() as usize;
+    let anchor_count = anchor_target.clamp(1, target_internal);
+
+    let mut keep_mask = vec![false; len];
+    keep_mask[0] = true;
+    keep_mask[len - 1] = true;
+
+    let mut kept_internal = 0usize;
+    for index in select_uniform_internal_indices(len, anchor_count) {
+        if !keep_mask[index] {
+            keep_mask[index] = true;
+            kept_internal += 1;
+        }
+    }
+
+    let mut scored: Vec<(usize, f64)> = (1..(len - 1))
+        .map(|index| {
+            let score = score_for_index(index);
             (
-                apply_cursor_timing_offset_ms(
-                    map_time_ms(point.ts, project_duration_ms, source_duration_ms),
-                    source_duration_ms,
-                ),
-                (point.x * src_w).clamp(0.0, src_w),
-                (point.y * src_h).clamp(0.0, src_h),
+                index,
+                if score.is_finite() {
+                    score.max(0.0)
+                } else {
+                    0.0
+                },
             )
         })
         .collect();
-    mapped_points.sort_by_key(|point| point.0);
-    mapped_points.dedup_by(|current, next| current.0 == next.0);
+    scored.sort_by(|left, right| {
+        right
+            .1
+            .total_cmp(&left.1)
+            .then_with(|| left.0.cmp(&right.0))
+    });
+
+    for (index, _) in scored {
+        if kept_internal >= target_internal {
+            break;
+        }
+        if keep_mask[index] {
+            continue;
+        }
+        keep_mask[index] = true;
+        kept_internal += 1;
+    }
+
+    if kept_internal < target_internal {
+        for index in select_uniform_internal_indices(len, target_internal) {
+            if kept_internal >= target_internal {
+                break;
+            }
+            if keep_mask[index] {
+                continue;
+            }
+            keep_mask[index] = true;
+            kept_internal += 1;
+        }
+    }
+
+    let mut indices: Vec<usize> = keep_mask
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, keep)| keep.then_some(index))
+        .collect();
+    indices.sort_unstable();
+    indices
+}
+
+fn select_uniform_internal_indices(len: usize, count: usize) -> Vec<usize> {
+    if len <= 2 || count == 0 {
+        return Vec::new();
+    }
+
+    let interior = len - 2;
+    let target = count.min(interior);
+    let mut result: Vec<usize> = Vec::with_capacity(target);
+
+    for slot in 0..target {
+        let idx = 1 + ((slot + 1) * interior) / (target + 1);
+        let idx = idx.clamp(1, len - 2);
+        if result.last().copied() != Some(idx) {
+            result.push(idx);
+        }
+    }
+
+    if result.len() < target {
+        for idx in 1..(len - 1) {
+            if result.len() >= target {
+                break;
+            }
+            if result.contains(&idx) {
+                continue;
+            }
+            result.push(idx);
+        }
+    }
+
+    result
+}
+
+fn decimate_u64_points(points: &[u64], max_points: usize) -> Vec<u64> {
+    if points.len() <= max_points || max_points < 2 {
+        return points.to_vec();
+    }
+
+    let mut result = Vec::with_capacity(max_points);
+    let last_index = points.len() - 1;
+    let max_index = max_points - 1;
+    let mut prev_idx = usize::MAX;
+
+    for index in 0..max_points {
+        let sample_idx = index * last_index / max_index;
+        if sample_idx == prev_idx {
+            continue;
+        }
+        result.push(points[sample_idx]);
+        prev_idx = sample_idx;
+    }
+
+    if result.last().copied() != points.last().copied() {
+        result.push(points[last_index]);
+    }
+
+    result
+}
+
+fn compact_click_times(points: &[u64], min_gap_ms: u64) -> Vec<u64> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sorted = points.to_vec();
+    sorted.sort_unstable();
+
+    let mut result = Vec::with_capacity(sorted.len());
+    let mut last_kept: Option<u64> = None;
+    for ts in sorted {
+        if let Some(last) = last_kept {
+            if ts.saturating_sub(last) < min_gap_ms {
+                continue;
+            }
+        }
+        result.push(ts);
+        last_kept = Some(ts);
+    }
+    result
+}
+
+fn build_piecewise_track_expr(points: &[(u64, f64)], duration_ms: u64) -> String {
+    if points.is_empty() {
+        return "0".to_string();
+    }
+
+    let mut normalized = points.to_vec();
+    normalized.sort_by_key(|(ts, _)| *ts);
+    normalized.dedup_by(|left, right| left.0 == right.0);
+
+    if normalized[0].0 > 0 {
+        normalized.insert(0, (0, normalized[0].1));
+    }
+    if normalized
+        .last()
+        .is_some_and(|(last_ts, _)| *last_ts < duration_ms)
+    {
+        let last = *normalized.last().expect("normalized has last");
+        normalized.push((duration_ms, last.1));
+    }
+
+    let base = normalized[0].1;
+    let mut terms = vec![format_f64(base)];
+
+    for pair in normalized.windows(2) {
+        let (start_ms, start_value) = pair[0];
+        let (end_ms, end_value) = pair[1];
+        if end_ms <= start_ms {
+            continue;
+        }
+
+        let start_s = start_ms as f64 / 1000.0;
+        let end_s = end_ms as f64 / 1000.0;
+        let span_s = ((end_ms - start_ms) as f64 / 1000.0).max(0.0001);
+        let delta = end_value - start_value;
+        let interp_expr = format!(
+            "({start}+((t-{start_t})/{span})*({delta}))",
+            start = format_f64(start_value),
+            start_t = format_f64(start_s),
+            span = format_f64(span_s),
+            delta = format_f64(delta)
+        );
+        terms.push(format!(
+            "if(gte(t,{start})*lt(t,{end}),({interp})-({base}),0)",
+            start = format_f64(start_s),
+            end = format_f64(end_s),
+            interp = interp_expr,
+            base = format_f64(base)
+        ));
+    }
 
-    if mapped_points.is_empty() {
-        return Err("No mapped cursor points for export".to_string());
+    if let Some((last_ts, last_value)) = normalized.last() {
+        terms.push(format!(
+            "if(gte(t,{start}),({last})-({base}),0)",
+            start = format_f64(*last_ts as f64 / 1000.0),
+            last = format_f64(*last_value),
+            base = format_f64(base)
+        ));
     }
-    if mapped_points.len() == 1 {
-        let only = mapped_points[0];
-        mapped_points.push((source_duration_ms, only.1, only.2));
+
+    terms.join("+")
+}
+
+fn build_click_pulse_factor_expr(click_times_ms: &[u64]) -> String {
+    if click_times_ms.is_empty() {
+        return "1".to_string();
     }
 
-    let mut sampled: Vec<(u64, f64, f64)> = Vec::with_capacity(frame_count + 1);
-    for frame in 0..=frame_count {
-        let frame_ms = ((frame as f64) * frame_step_ms)
-            .round()
-            .clamp(0.0, source_duration_ms as f64) as u64;
-        if is_hidden_at(frame_ms, hidden_ranges_ms) {
-            continue;
-        }
-        let frame_no = frame as f64;
-        let (src_x, src_y) = interpolate_cursor_position(&mapped_points, frame_ms);
-        let zoom =
-            sample_camera_axis_value(camera_states, frame_no, render_fps, |state| state.zoom, 1.0);
-        let offset_x = sample_camera_axis_value(
-            camera_states,
-            frame_no,
-            render_fps,
-            |state| state.offset_x,
-            0.0,
+    let mut terms = vec!["1".to_string()];
+    let amp = 1.0 - CLICK_PULSE_MIN_SCALE;
+    let down_s = CLICK_PULSE_DOWN_MS / 1000.0;
+    let up_s = (CLICK_PULSE_TOTAL_MS - CLICK_PULSE_DOWN_MS).max(1.0) / 1000.0;
+
+    for click_ms in click_times_ms {
+        let click_s = *click_ms as f64 / 1000.0;
+        let down_end_s = click_s + down_s;
+        let up_end_s = click_s + (CLICK_PULSE_TOTAL_MS / 1000.0);
+
+        let down_expr = format!(
+            "1-({amp})*((t-{start})/{down})",
+            amp = format_f64(amp),
+            start = format_f64(click_s),
+            down = format_f64(down_s)
         );
-        let offset_y = sample_camera_axis_value(
-            camera_states,
-            frame_no,
-            render_fps,
-            |state| state.offset_y,
-            0.0,
+        let up_expr = format!(
+            "{min}+({amp})*((t-{start})/{up})",
+            min = format_f64(CLICK_PULSE_MIN_SCALE),
+            amp = format_f64(amp),
+            start = format_f64(down_end_s),
+            up = format_f64(up_s)
         );
 
-        let (x, y) = map_cursor_to_output_space(
-            src_x, src_y, zoom, offset_x, offset_y, src_w, src_h, dst_w, dst_h,
-        );
-        sampled.push((frame_ms, x, y));
-    }
-    if sampled.is_empty() {
-        return Ok(None);
+        terms.push(format!(
+            "if(gte(t,{start})*lt(t,{end}),({value})-1,0)",
+            start = format_f64(click_s),
+            end = format_f64(down_end_s),
+            value = down_expr
+        ));
+        terms.push(format!(
+            "if(gte(t,{start})*lt(t,{end}),({value})-1,0)",
+            start = format_f64(down_end_s),
+            end = format_f64(up_end_s),
+            value = up_expr
+        ));
     }
 
-    sampled.dedup_by(|left, right| {
-        left.0 == right.0 && (left.1 - right.1).abs() < 0.1 && (left.2 - right.2).abs() < 0.1
-    });
-    let sampled = decimate_cursor_samples(&sampled, cursor_expr_budget.max(24));
+    terms.join("+")
+}
 
-    let x_points: Vec<(u64, f64)> = sampled.iter().map(|(ts, x, _)| (*ts, *x)).collect();
-    let y_points: Vec<(u64, f64)> = sampled.iter().map(|(ts, _, y)| (*ts, *y)).collect();
+fn sample_click_pulse_scale_scalar(click_times_ms: &[u64], ts_ms: u64) -> f64 {
+    if click_times_ms.is_empty() {
+        return 1.0;
+    }
 
-    let x_track_expr = build_piecewise_track_expr(&x_points, source_duration_ms);
-    let y_track_expr = build_piecewise_track_expr(&y_points, source_duration_ms);
+    let idx = click_times_ms.partition_point(|&click_ts| click_ts <= ts_ms);
+    if idx == 0 {
+        return 1.0;
+    }
 
-    let base_cursor_scale = cursor_height_px / cursor_asset.height.max(1) as f64;
-    let pulse_factor_expr = build_click_pulse_factor_expr(&click_times);
-    let scale_expr = format!(
-        "({base_scale})*({pulse_factor})",
-        base_scale = format_f64(base_cursor_scale),
-        pulse_factor = pulse_factor_expr
-    );
+    let click_ts = click_times_ms[idx - 1];
+    let dt_ms = ts_ms.saturating_sub(click_ts) as f64;
+    if dt_ms < 0.0 || dt_ms > CLICK_PULSE_TOTAL_MS {
+        return 1.0;
+    }
 
-    let cursor_width_expr = format!(
-        "max(2,{asset_w}*({scale}))",
-        asset_w = format_f64(cursor_asset.width as f64),
-        scale = scale_expr
-    );
-    let cursor_height_expr = format!(
-        "max(2,{asset_h}*({scale}))",
-        asset_h = format_f64(cursor_asset.height as f64),
-        scale = scale_expr
-    );
-    let overlay_x_expr = format!(
-        "({x})-({hotspot_x})*({scale})",
-        x = x_track_expr,
-        hotspot_x = format_f64(cursor_asset.hotspot_x),
-        scale = scale_expr
-    );
-    let overlay_y_expr = format!(
-        "({y})-({hotspot_y})*({scale})",
-        y = y_track_expr,
-        hotspot_y = format_f64(cursor_asset.hotspot_y),
-        scale = scale_expr
-    );
+    if dt_ms <= CLICK_PULSE_DOWN_MS {
+        let t = dt_ms / CLICK_PULSE_DOWN_MS.max(1.0);
+        return 1.0 - (1.0 - CLICK_PULSE_MIN_SCALE) * t;
+    }
 
-    Ok(Some(CursorOverlayPlan {
-        cursor_png_path: cursor_asset.png_path,
-        filter_chain: format!(
-            "[1:v]format=rgba,scale=w='{w}':h='{h}':eval=frame[cursor];\
-             [framed][cursor]overlay=x='{x}':y='{y}':eval=frame:format=auto[vout]",
-            w = cursor_width_expr,
-            h = cursor_height_expr,
-            x = overlay_x_expr,
-            y = overlay_y_expr,
-        ),
-    }))
+    let up_duration = (CLICK_PULSE_TOTAL_MS - CLICK_PULSE_DOWN_MS).max(1.0);
+    let t = (dt_ms - CLICK_PULSE_DOWN_MS) / up_duration;
+    CLICK_PULSE_MIN_SCALE + (1.0 - CLICK_PULSE_MIN_SCALE) * t
 }
 
-fn build_vector_cursor_ass_file(
-    project: &Project,
-    events_file: &EventsFile,
-    camera_states: &[CameraState],
-    hidden_ranges_ms: &[(u64, u64)],
-    source_duration_ms: u64,
-    project_duration_ms: u64,
-    source_width: u32,
-    source_height: u32,
-    target_width: u32,
-    target_height: u32,
-    render_fps: f64,
-) -> Result<PathBuf, String> {
-    let mut points = extract_preview_cursor_points(
-        &events_file.events,
-        events_file.screen_width.max(1) as f64,
-        events_file.screen_height.max(1) as f64,
-        project.settings.cursor.smoothing_factor,
-    );
-    if points.is_empty() {
-        return Err("No cursor points available for export".to_string());
+fn update_status(state: &Arc<Mutex<ExportStatus>>, updater: impl FnOnce(&mut ExportStatus)) {
+    if let Ok(mut status) = state.lock() {
+        updater(&mut status);
     }
+}
 
-    points.sort_by_key(|point| point.ts);
+fn resolve_project_file(path: &str) -> Result<PathBuf, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("Project path is empty".to_string());
+    }
 
-    let sample_fps = render_fps.clamp(VECTOR_CURSOR_MIN_SAMPLE_FPS, VECTOR_CURSOR_MAX_SAMPLE_FPS);
-    let frame_step_ms = (1000.0 / sample_fps).max(1.0);
-    let frame_count = ((source_duration_ms as f64 / frame_step_ms).ceil() as usize).max(2);
+    let input = PathBuf::from(trimmed);
+    if input
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+    {
+        Ok(input)
+    } else {
+        Ok(input.join("project.json"))
+    }
+}
 
-    let src_w = source_width.max(1) as f64;
-    let src_h = source_height.max(1) as f64;
-    let dst_w = target_width.max(1) as f64;
-    let dst_h = target_height.max(1) as f64;
+fn load_project_file(path: &Path) -> Result<Project, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read project file {}: {e}", path.display()))?;
+    let mut project: Project = serde_json::from_str(&raw)
+        .map_err(|e| format!("Failed to parse project file {}: {e}", path.display()))?;
 
-    let mut mapped_points: Vec<(u64, f64, f64)> = points
-        .into_iter()
-        .map(|point| {
-            (
-                apply_cursor_timing_offset_ms(
-                    map_time_ms(point.ts, project_duration_ms, source_duration_ms),
-                    source_duration_ms,
-                ),
-                (point.x * src_w).clamp(0.0, src_w),
-                (point.y * src_h).clamp(0.0, src_h),
-            )
-        })
-        .collect();
-    mapped_points.sort_by_key(|point| point.0);
-    mapped_points.dedup_by(|left, right| left.0 == right.0);
-    if mapped_points.is_empty() {
-        return Err("No mapped cursor points for export".to_string());
+    if project.schema_version != SCHEMA_VERSION {
+        return Err(format!(
+            "Unsupported project schemaVersion: expected {}, got {}",
+            SCHEMA_VERSION, project.schema_version
+        ));
     }
-    if mapped_points.len() == 1 {
-        let only = mapped_points[0];
-        mapped_points.push((source_duration_ms, only.1, only.2));
+
+    sanitize_loaded_project(&mut project, path);
+    Ok(project)
+}
+
+/// Runs [`sanitize_project`] on a freshly loaded project and logs every correction.
+pub(crate) fn sanitize_loaded_project(project: &mut Project, path: &Path) {
+    for correction in sanitize_project(project) {
+        log::warn!("project {}: {correction}", path.display());
+    }
+}
+
+/// Clamps timestamps and rects of a loaded project into valid ranges so hand-edited or
+/// corrupt files cannot push bad values into the camera math. Returns what was changed.
+pub(crate) fn sanitize_project(project: &mut Project) -> Vec<String> {
+    let mut corrections = Vec::new();
+
+    if project.duration_ms == 0 {
+        let latest_ts = project
+            .timeline
+            .zoom_segments
+            .iter()
+            .map(|segment| segment.end_ts)
+            .max()
+            .unwrap_or(0);
+        project.duration_ms = latest_ts.max(1);
+        corrections.push(format!("durationMs was 0, set to {}", project.duration_ms));
     }
+    let duration_ms = project.duration_ms;
+
+    let mut fix_rect = |rect: &mut NormalizedRect, label: &str| {
+        let normalized = normalize_segment_rect(rect.clone());
+        let changed = [
+            (rect.x, normalized.x),
+            (rect.y, normalized.y),
+            (rect.width, normalized.width),
+            (rect.height, normalized.height),
+        ]
+        .iter()
+        .any(|(before, after)| (before - after).abs() > 1e-9);
+        if changed {
+            corrections.push(format!(
+                "{label}: rect ({:.4}, {:.4}, {:.4}x{:.4}) clamped to ({:.4}, {:.4}, {:.4}x{:.4})",
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                normalized.x,
+                normalized.y,
+                normalized.width,
+                normalized.height
+            ));
+            *rect = normalized;
+        }
+    };
+
+    let mut segment_corrections = Vec::new();
+    project.timeline.zoom_segments.retain_mut(|segment| {
+        let label = format!("zoom segment {}", segment.id);
+        let start_ts = segment.start_ts.min(duration_ms);
+        let end_ts = segment.end_ts.min(duration_ms);
+        if end_ts <= start_ts {
+            segment_corrections.push(format!(
+                "{label}: dropped, empty time range {}..{}",
+                segment.start_ts, segment.end_ts
+            ));
+            return false;
+        }
+        if (start_ts, end_ts) != (segment.start_ts, segment.end_ts) {
+            segment_corrections.push(format!(
+                "{label}: time range {}..{} clamped to {start_ts}..{end_ts}",
+                segment.start_ts, segment.end_ts
+            ));
+            segment.start_ts = start_ts;
+            segment.end_ts = end_ts;
+        }
 
-    let raw_click_times: Vec<u64> = events_file
-        .events
-        .iter()
-        .filter_map(|event| match event {
-            InputEvent::Click { ts, .. } => {
-                let mapped = apply_cursor_timing_offset_ms(
-                    map_time_ms(*ts, project_duration_ms, source_duration_ms),
-                    source_duration_ms,
-                );
-                (!is_hidden_at(mapped, hidden_ranges_ms)).then_some(mapped)
+        fix_rect(&mut segment.initial_rect, &label);
+        for point in &mut segment.target_points {
+            point.ts = point.ts.clamp(start_ts, end_ts);
+            fix_rect(&mut point.rect, &format!("{label} target point"));
+            let (min_zoom, max_zoom) = TARGET_POINT_ZOOM_RANGE;
+            if let Some(zoom) = point
+                .zoom
+                .filter(|zoom| !(zoom.is_finite() && (min_zoom..=max_zoom).contains(zoom)))
+            {
+                segment_corrections.push(format!(
+                    "{label} target point at {}: zoom {zoom} outside {min_zoom}–{max_zoom}, removed",
+                    point.ts
+                ));
+                point.zoom = None;
             }
-            _ => None,
-        })
-        .collect();
-    let click_times = compact_click_times(
-        &decimate_u64_points(&raw_click_times, MAX_CLICK_EVENTS_FOR_EXPR),
-        MIN_CLICK_PULSE_GAP_MS,
-    );
-
-    let mut sampled: Vec<(u64, i64, i64, f64)> = Vec::with_capacity(frame_count + 1);
-    for frame in 0..=frame_count {
-        let frame_ms = ((frame as f64) * frame_step_ms)
-            .round()
-            .clamp(0.0, source_duration_ms as f64) as u64;
-        if is_hidden_at(frame_ms, hidden_ranges_ms) {
-            continue;
         }
-        let frame_no = (frame_ms as f64 / 1000.0) * render_fps.max(1.0);
-        let (src_x, src_y) = interpolate_cursor_position(&mapped_points, frame_ms);
-        let zoom = sample_camera_axis_value(
-            camera_states,
-            frame_no,
-            render_fps.max(1.0),
-            |state| state.zoom,
-            1.0,
-        );
-        let offset_x = sample_camera_axis_value(
-            camera_states,
-            frame_no,
-            render_fps.max(1.0),
-            |state| state.offset_x,
-            0.0,
-        );
-        let offset_y = sample_camera_axis_value(
-            camera_states,
-            frame_no,
-            render_fps.max(1.0),
-            |state| state.offset_y,
-            0.0,
-        );
-        let (x, y) = map_cursor_to_output_space(
-            src_x, src_y, zoom, offset_x, offset_y, src_w, src_h, dst_w, dst_h,
-        );
-        let pulse_scale = sample_click_pulse_scale_scalar(&click_times, frame_ms);
-        let combined_scale = (zoom.max(1.0) * pulse_scale).clamp(0.5, 4.0);
-        sampled.push((frame_ms, x.round() as i64, y.round() as i64, combined_scale));
-    }
+        for keyframe in &mut segment.pan_trajectory {
+            keyframe.ts = keyframe.ts.clamp(start_ts, end_ts);
+        }
+        true
+    });
+    corrections.extend(segment_corrections);
 
-    sampled.dedup_by(|left, right| {
-        left.0 == right.0
-            && left.1 == right.1
-            && left.2 == right.2
-            && (left.3 - right.3).abs() < 0.01
+    let hidden_before = project.settings.cursor.hidden_ranges.len();
+    project.settings.cursor.hidden_ranges.retain_mut(|range| {
+        range.start_ts = range.start_ts.min(duration_ms);
+        range.end_ts = range.end_ts.min(duration_ms);
+        range.end_ts > range.start_ts
     });
-    let vector_ass_budget = adaptive_sample_budget(
-        source_duration_ms,
-        BASE_VECTOR_CURSOR_ASS_SAMPLES,
-        MAX_VECTOR_CURSOR_ASS_SAMPLES,
-        VECTOR_CURSOR_ASS_BUDGET_GROWTH_PER_SEC,
-        sampled.len(),
-    );
-    let sampled = decimate_cursor_samples_scaled(&sampled, vector_ass_budget);
-    if sampled.len() < 2 {
-        return Err("No visible cursor samples after hidden-range filtering".to_string());
+    let hidden_dropped = hidden_before - project.settings.cursor.hidden_ranges.len();
+    if hidden_dropped > 0 {
+        corrections.push(format!(
+            "dropped {hidden_dropped} empty cursor hidden range(s)"
+        ));
     }
 
-    let target_min_side = target_width.min(target_height).max(1) as f64;
-    let cursor_height_px =
-        (project.settings.cursor.size * target_min_side * CURSOR_SIZE_TO_FRAME_RATIO)
-            .clamp(8.0, 220.0);
-    let cursor_scale_percent = (cursor_height_px / VECTOR_CURSOR_ASS_BASE_HEIGHT) * 100.0;
-    let cursor_outline_px = (cursor_height_px * 0.08).clamp(1.0, 5.0);
+    if let Err(err) = project.settings.camera.validate() {
+        corrections.push(format!("{err}; camera settings reset to defaults"));
+        project.settings.camera = Default::default();
+    }
+    if let Err(err) = project.settings.frame_background.validate() {
+        corrections.push(format!("{err}; frame background reset to black"));
+        project.settings.frame_background = FrameBackground::None;
+    }
 
-    let ass_path =
-        std::env::temp_dir().join(format!("nsc-vcursor-{}-{}.ass", project.id, now_ms()));
-    let mut file = File::create(&ass_path)
-        .map_err(|e| format!("Failed to create vector cursor ass file: {e}"))?;
+    corrections
+}
 
-    writeln!(file, "[Script Info]").map_err(|e| format!("Failed to write ass header: {e}"))?;
-    writeln!(file, "ScriptType: v4.00+").map_err(|e| format!("Failed to write ass header: {e}"))?;
-    writeln!(file, "PlayResX: {target_width}")
-        .map_err(|e| format!("Failed to write ass header: {e}"))?;
-    writeln!(file, "PlayResY: {target_height}")
-        .map_err(|e| format!("Failed to write ass header: {e}"))?;
-    writeln!(file).map_err(|e| format!("Failed to write ass header: {e}"))?;
-    writeln!(file, "[V4+ Styles]").map_err(|e| format!("Failed to write ass styles: {e}"))?;
-    writeln!(
-        file,
-        "Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding"
-    )
-    .map_err(|e| format!("Failed to write ass styles: {e}"))?;
-    writeln!(
-        file,
-        "Style: Cursor,Arial,12,&H00000000,&H00000000,&H00FFFFFF,&H00000000,0,0,0,0,100,100,0,0,1,2,0,7,0,0,0,1"
-    )
-    .map_err(|e| format!("Failed to write ass styles: {e}"))?;
-    writeln!(file).map_err(|e| format!("Failed to write ass styles: {e}"))?;
-    writeln!(file, "[Events]").map_err(|e| format!("Failed to write ass events: {e}"))?;
-    writeln!(
-        file,
-        "Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text"
-    )
-    .map_err(|e| format!("Failed to write ass events: {e}"))?;
+fn load_events_file(project_dir: &Path, events_path: &str) -> Result<EventsFile, String> {
+    let path = resolve_media_path(project_dir, events_path)?;
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read events file {}: {e}", path.display()))?;
+    let mut events: EventsFile = serde_json::from_str(&raw)
+        .map_err(|e| format!("Failed to parse events file {}: {e}", path.display()))?;
 
-    for pair in sampled.windows(2) {
-        let (start_ms, x1, y1, start_scale) = pair[0];
-        let (end_ms, x2, y2, _) = pair[1];
-        if end_ms <= start_ms {
-            continue;
-        }
-        for (visible_start_ms, visible_end_ms) in
-            visible_subranges(start_ms, end_ms, hidden_ranges_ms)
-        {
-            if visible_end_ms <= visible_start_ms {
-                continue;
-            }
+    if events.schema_version != EVENTS_SCHEMA_VERSION {
+        return Err(format!(
+            "Unsupported events schemaVersion: expected {}, got {}",
+            EVENTS_SCHEMA_VERSION, events.schema_version
+        ));
+    }
 
-            let span_ms = (end_ms - start_ms).max(1);
-            let lerp = |a: i64, b: i64, ts: u64| -> i64 {
-                let t = (ts.saturating_sub(start_ms)) as f64 / span_ms as f64;
-                (a as f64 + (b as f64 - a as f64) * t).round() as i64
-            };
+    events.normalize_to_physical();
+    Ok(events)
+}
 
-            let vx1 = lerp(x1, x2, visible_start_ms);
-            let vy1 = lerp(y1, y2, visible_start_ms);
-            let vx2 = lerp(x1, x2, visible_end_ms);
-            let vy2 = lerp(y1, y2, visible_end_ms);
+fn resolve_media_path(project_dir: &Path, raw_path: &str) -> Result<PathBuf, String> {
+    let trimmed = raw_path.trim();
+    if trimmed.is_empty() {
+        return Err("Project videoPath is empty".to_string());
+    }
 
-            let scale_percent = cursor_scale_percent * start_scale;
-            let outline_px = cursor_outline_px * start_scale.clamp(0.75, 2.5);
+    let candidate = PathBuf::from(trimmed);
+    if candidate.is_absolute() {
+        Ok(candidate)
+    } else {
+        Ok(project_dir.join(candidate))
+    }
+}
 
-            writeln!(
-                file,
-                "Dialogue: 0,{},{},Cursor,,0,0,0,,{{\\an7\\p1\\fscx{:.2}\\fscy{:.2}\\bord{:.2}\\shad0\\move({},{},{},{})}}{}",
-                format_ass_time(visible_start_ms),
-                format_ass_time(visible_end_ms),
-                scale_percent,
-                scale_percent,
-                outline_px,
-                vx1,
-                vy1,
-                vx2,
-                vy2,
-                VECTOR_CURSOR_ASS_PATH
-            )
-            .map_err(|e| format!("Failed to write ass cursor event: {e}"))?;
+fn resolve_output_path(
+    project_dir: &Path,
+    project_id: &str,
+    output_path: Option<String>,
+) -> Result<PathBuf, String> {
+    if let Some(raw) = output_path {
+        let trimmed = raw.trim();
+        if !trimmed.is_empty() {
+            return Ok(PathBuf::from(trimmed));
         }
     }
 
-    Ok(ass_path)
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    Ok(project_dir.join(format!("export-{project_id}-{timestamp}.mp4")))
 }
 
-fn decimate_cursor_samples(points: &[(u64, f64, f64)], max_points: usize) -> Vec<(u64, f64, f64)> {
-    let keep_indices = select_motion_aware_indices(points.len(), max_points, |index| {
-        let (prev_t, prev_x, prev_y) = points[index - 1];
-        let (curr_t, curr_x, curr_y) = points[index];
-        let (next_t, next_x, next_y) = points[index + 1];
+fn map_time_ms(ts: u64, from_duration_ms: u64, to_duration_ms: u64) -> u64 {
+    if from_duration_ms == 0 || to_duration_ms == 0 {
+        return 0;
+    }
+    let mapped = (ts as f64 / from_duration_ms as f64) * to_duration_ms as f64;
+    mapped.round().clamp(0.0, to_duration_ms as f64) as u64
+}
 
-        let total_dt_ms = next_t.saturating_sub(prev_t).max(1) as f64;
-        let alpha = (curr_t.saturating_sub(prev_t) as f64 / total_dt_ms).clamp(0.0, 1.0);
-        let interp_x = prev_x + (next_x - prev_x) * alpha;
-        let interp_y = prev_y + (next_y - prev_y) * alpha;
-        let fit_error = (curr_x - interp_x).hypot(curr_y - interp_y);
+fn apply_cursor_timing_offset_ms(ts_ms: u64, duration_ms: u64) -> u64 {
+    ts_ms
+        .saturating_add(CURSOR_TIMING_OFFSET_MS)
+        .min(duration_ms)
+}
 
-        let dt1 = (curr_t.saturating_sub(prev_t).max(1) as f64) / 1000.0;
-        let dt2 = (next_t.saturating_sub(curr_t).max(1) as f64) / 1000.0;
-        let vx1 = (curr_x - prev_x) / dt1;
-        let vy1 = (curr_y - prev_y) / dt1;
-        let vx2 = (next_x - curr_x) / dt2;
-        let vy2 = (next_y - curr_y) / dt2;
-        let speed1 = vx1.hypot(vy1);
-        let speed2 = vx2.hypot(vy2);
-        let accel = (speed2 - speed1).abs();
+fn merge_time_ranges(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    if ranges.is_empty() {
+        return ranges;
+    }
+    ranges.sort_by_key(|(start, _)| *start);
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges.into_iter().filter(|(start, end)| end > start) {
+        if let Some((_, last_end)) = merged.last_mut() {
+            if start <= *last_end {
+                *last_end = (*last_end).max(end);
+                continue;
+            }
+        }
+        merged.push((start, end));
+    }
+    merged
+}
 
-        let cross = (curr_x - prev_x) * (next_y - curr_y) - (curr_y - prev_y) * (next_x - curr_x);
-        let norm = ((curr_x - prev_x).hypot(curr_y - prev_y)
-            * (next_x - curr_x).hypot(next_y - curr_y))
-        .max(1.0);
-        let turn_factor = (cross.abs() / norm).clamp(0.0, 1.0);
+fn map_hidden_ranges_to_source(
+    ranges: &[TimeRange],
+    project_duration_ms: u64,
+    source_duration_ms: u64,
+) -> Vec<(u64, u64)> {
+    if ranges.is_empty() || project_duration_ms == 0 || source_duration_ms == 0 {
+        return Vec::new();
+    }
+    let mapped = ranges
+        .iter()
+        .map(|range| {
+            (
+                map_time_ms(range.start_ts, project_duration_ms, source_duration_ms),
+                map_time_ms(range.end_ts, project_duration_ms, source_duration_ms),
+            )
+        })
+        .collect::<Vec<_>>();
+    merge_time_ranges(mapped)
+}
 
-        fit_error * 3.0 + accel * 0.08 + turn_factor * 16.0
-    });
+fn is_hidden_at(ts_ms: u64, hidden_ranges_ms: &[(u64, u64)]) -> bool {
+    hidden_ranges_ms
+        .iter()
+        .any(|(start, end)| ts_ms >= *start && ts_ms < *end)
+}
+
+fn visible_subranges(
+    start_ms: u64,
+    end_ms: u64,
+    hidden_ranges_ms: &[(u64, u64)],
+) -> Vec<(u64, u64)> {
+    if end_ms <= start_ms {
+        return Vec::new();
+    }
+    if hidden_ranges_ms.is_empty() {
+        return vec![(start_ms, end_ms)];
+    }
 
-    keep_indices
-        .into_iter()
-        .map(|index| points[index])
-        .collect()
+    let mut out = vec![(start_ms, end_ms)];
+    for (hidden_start, hidden_end) in hidden_ranges_ms {
+        if *hidden_end <= *hidden_start {
+            continue;
+        }
+        let mut next = Vec::new();
+        for (frag_start, frag_end) in out {
+            if *hidden_end <= frag_start || *hidden_start >= frag_end {
+                next.push((frag_start, frag_end));
+                continue;
+            }
+            if *hidden_start > frag_start {
+                next.push((frag_start, (*hidden_start).min(frag_end)));
+            }
+            if *hidden_end < frag_end {
+                next.push(((*hidden_end).max(frag_start), frag_end));
+            }
+        }
+        out = next;
+        if out.is_empty() {
+            break;
+        }
+    }
+    out.into_iter().filter(|(s, e)| e > s).collect()
 }
 
-fn decimate_cursor_samples_scaled(
-    points: &[(u64, i64, i64, f64)],
-    max_points: usize,
-) -> Vec<(u64, i64, i64, f64)> {
-    let keep_indices = select_motion_aware_indices(points.len(), max_points, |index| {
-        let (prev_t, prev_x, prev_y, prev_scale) = points[index - 1];
-        let (curr_t, curr_x, curr_y, curr_scale) = points[index];
-        let (next_t, next_x, next_y, next_scale) = points[index + 1];
-
-        let prev_x = prev_x as f64;
-        let prev_y = prev_y as f64;
-        let curr_x = curr_x as f64;
-        let curr_y = curr_y as f64;
-        let next_x = next_x as f64;
-        let next_y = next_y as f64;
-
-        let total_dt_ms = next_t.saturating_sub(prev_t).max(1) as f64;
-        let alpha = (curr_t.saturating_sub(prev_t) as f64 / total_dt_ms).clamp(0.0, 1.0);
-        let interp_x = prev_x + (next_x - prev_x) * alpha;
-        let interp_y = prev_y + (next_y - prev_y) * alpha;
-        let interp_scale = prev_scale + (next_scale - prev_scale) * alpha;
+fn format_ass_time(ms: u64) -> String {
+    let total_centis = ms / 10;
+    let centis = total_centis % 100;
+    let total_seconds = total_centis / 100;
+    let seconds = total_seconds % 60;
+    let total_minutes = total_seconds / 60;
+    let minutes = total_minutes % 60;
+    let hours = total_minutes / 60;
+    format!("{hours}:{minutes:02}:{seconds:02}.{centis:02}")
+}
 
-        let fit_error = (curr_x - interp_x).hypot(curr_y - interp_y);
-        let scale_error = (curr_scale - interp_scale).abs() * 36.0;
+fn escape_filter_path(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .replace(':', "\\:")
+        .replace('\'', "\\'")
+}
 
-        let dt1 = (curr_t.saturating_sub(prev_t).max(1) as f64) / 1000.0;
-        let dt2 = (next_t.saturating_sub(curr_t).max(1) as f64) / 1000.0;
-        let speed1 = (curr_x - prev_x).hypot(curr_y - prev_y) / dt1;
-        let speed2 = (next_x - curr_x).hypot(next_y - curr_y) / dt2;
-        let accel = (speed2 - speed1).abs();
+#[derive(Debug, Clone, Copy)]
+struct PreviewCursorPoint {
+    ts: u64,
+    x: f64,
+    y: f64,
+}
 
-        let scale_rate_1 = (curr_scale - prev_scale).abs() / dt1;
-        let scale_rate_2 = (next_scale - curr_scale).abs() / dt2;
-        let scale_accel = (scale_rate_2 - scale_rate_1).abs();
+/// Picks the cursor path for export according to the project's cursor render mode.
+fn export_cursor_points(
+    events: &[InputEvent],
+    screen_width: f64,
+    screen_height: f64,
+    cursor: &CursorSettings,
+) -> Vec<PreviewCursorPoint> {
+    match cursor.render_mode {
+        CursorRenderMode::Off => Vec::new(),
+        CursorRenderMode::Smoothed => extract_preview_cursor_points(
+            events,
+            screen_width,
+            screen_height,
+            cursor.smoothing_factor,
+            cursor.resample_hz,
+        ),
+        CursorRenderMode::Raw => {
+            if screen_width <= 0.0 || screen_height <= 0.0 {
+                return Vec::new();
+            }
+            collect_cursor_points(events)
+                .into_iter()
+                .map(|point| PreviewCursorPoint {
+                    ts: point.ts,
+                    x: (point.x / screen_width).clamp(0.0, 1.0),
+                    y: (point.y / screen_height).clamp(0.0, 1.0),
+                })
+                .collect()
+        }
+    }
+}
 
-        fit_error * 3.0 + scale_error + accel * 0.08 + scale_accel * 18.0
-    });
+// Keep export cursor math aligned with Edit.tsx preview:
+// - same event set (move/click/mouseUp/scroll)
+// - same resampling at resample_hz before smoothing
+// - same EMA smoothing formula based on smoothing_factor.
+fn extract_preview_cursor_points(
+    events: &[InputEvent],
+    screen_width: f64,
+    screen_height: f64,
+    smoothing_factor: f64,
+    resample_hz: f64,
+) -> Vec<PreviewCursorPoint> {
+    if events.is_empty() || screen_width <= 0.0 || screen_height <= 0.0 {
+        return Vec::new();
+    }
 
-    keep_indices
-        .into_iter()
-        .map(|index| points[index])
-        .collect()
-}
+    let mut samples: Vec<CursorPoint> = events
+        .iter()
+        .filter_map(|event| match event {
+            InputEvent::Move { ts, x, y }
+            | InputEvent::Click { ts, x, y, .. }
+            | InputEvent::MouseUp { ts, x, y, .. }
+            | InputEvent::Scroll { ts, x, y, .. } => Some(CursorPoint {
+                ts: *ts,
+                x: (*x / screen_width).clamp(0.0, 1.0),
+                y: (*y / screen_height).clamp(0.0, 1.0),
+                is_click: matches!(event, InputEvent::Click { .. }),
+            }),
+            _ => None,
+        })
+        .collect();
+    samples.sort_by_key(|sample| sample.ts);
 
-fn decimate_time_value_points(points: &[(u64, f64)], max_points: usize) -> Vec<(u64, f64)> {
-    let keep_indices = select_motion_aware_indices(points.len(), max_points, |index| {
-        let (prev_t, prev_value) = points[index - 1];
-        let (curr_t, curr_value) = points[index];
-        let (next_t, next_value) = points[index + 1];
+    let factor = smoothing_factor.clamp(0.0, 1.0);
+    let samples: Vec<PreviewCursorPoint> = if samples.len() > 1 && factor > f64::EPSILON {
+        resample_points(&samples, resample_hz)
+    } else {
+        samples
+    }
+    .into_iter()
+    .map(|point| PreviewCursorPoint {
+        ts: point.ts,
+        x: point.x,
+        y: point.y,
+    })
+    .collect();
 
-        let total_dt_ms = next_t.saturating_sub(prev_t).max(1) as f64;
-        let alpha = (curr_t.saturating_sub(prev_t) as f64 / total_dt_ms).clamp(0.0, 1.0);
-        let interp = prev_value + (next_value - prev_value) * alpha;
-        let fit_error = (curr_value - interp).abs();
+    if samples.len() <= 1 || factor <= f64::EPSILON {
+        return samples;
+    }
 
-        let dt1 = (curr_t.saturating_sub(prev_t).max(1) as f64) / 1000.0;
-        let dt2 = (next_t.saturating_sub(curr_t).max(1) as f64) / 1000.0;
-        let speed1 = (curr_value - prev_value).abs() / dt1;
-        let speed2 = (next_value - curr_value).abs() / dt2;
-        let accel = (speed2 - speed1).abs();
+    let alpha = 1.0 - factor * 0.9;
+    let mut smoothed_x = samples[0].x;
+    let mut smoothed_y = samples[0].y;
+    let mut smoothed = Vec::with_capacity(samples.len());
+    smoothed.push(samples[0]);
 
-        fit_error * 4.0 + accel * 0.2
-    });
+    for sample in samples.iter().skip(1).copied() {
+        smoothed_x += alpha * (sample.x - smoothed_x);
+        smoothed_y += alpha * (sample.y - smoothed_y);
+        smoothed.push(PreviewCursorPoint {
+            ts: sample.ts,
+            x: smoothed_x,
+            y: smoothed_y,
+        });
+    }
 
-    keep_indices
-        .into_iter()
-        .map(|index| points[index])
-        .collect()
+    smoothed
 }
 
-fn adaptive_sample_budget(
-    duration_ms: u64,
-    base_budget: usize,
-    hard_cap: usize,
-    growth_per_second: f64,
-    input_len: usize,
-) -> usize {
-    if input_len <= 2 {
-        return input_len;
+fn interpolate_cursor_position(points: &[(u64, f64, f64)], ts: u64) -> (f64, f64) {
+    if points.is_empty() {
+        return (0.0, 0.0);
+    }
+    if ts <= points[0].0 {
+        return (points[0].1, points[0].2);
+    }
+    let last = points[points.len() - 1];
+    if ts >= last.0 {
+        return (last.1, last.2);
     }
 
-    let cap = hard_cap.max(2);
-    let base = base_budget.clamp(2, cap);
-    let extra = ((duration_ms as f64 / 1000.0).max(0.0) * growth_per_second.max(0.0)).round();
-    let grown = (base as f64 + extra).round() as usize;
-    grown.clamp(base, cap).min(input_len)
-}
+    let mut low = 0usize;
+    let mut high = points.len() - 1;
+    while low <= high {
+        let mid = (low + high) / 2;
+        if points[mid].0 == ts {
+            return (points[mid].1, points[mid].2);
+        }
+        if points[mid].0 < ts {
+            low = mid + 1;
+        } else if mid == 0 {
+            break;
+        } else {
+            high = mid - 1;
+        }
+    }
 
-fn select_motion_aware_indices(
-    len: usize,
-    max_points: usize,
-    mut score_for_index: impl FnMut(usize) -> f64,
-) -> Vec<usize> {
-    if len <= max_points || max_points < 2 || len <= 2 {
-        return (0..len).collect();
+    let next = points[low.min(points.len() - 1)];
+    let prev = points[low.saturating_sub(1)];
+    let span = next.0.saturating_sub(prev.0);
+    if span == 0 {
+        return (prev.1, prev.2);
     }
+    let t = (ts.saturating_sub(prev.0)) as f64 / span as f64;
+    (
+        prev.1 + (next.1 - prev.1) * t,
+        prev.2 + (next.2 - prev.2) * t,
+    )
+}
 
-    let target_total = max_points.clamp(2, len);
-    let target_internal = target_total.saturating_sub(2).min(len.saturating_sub(2));
-    if target_internal == 0 {
-        return vec![0, len - 1];
+fn sample_camera_axis_value(
+    states: &[CameraState],
+    frame: f64,
+    source_fps: f64,
+    axis: impl Fn(&CameraState) -> AxisSpringSegment,
+    default_value: f64,
+) -> f64 {
+    let safe_fps = source_fps.max(1.0);
+    for state in states {
+        if frame < state.start_frame || frame >= state.end_frame {
+            continue;
+        }
+        let elapsed_seconds = ((frame - state.start_frame) / safe_fps).max(0.0);
+        let axis_state = axis(state);
+        let evaluated = evaluate_spring_axis(
+            AxisSpringState {
+                value: axis_state.start,
+                velocity: axis_state.velocity,
+            },
+            axis_state.target,
+            state.spring,
+            elapsed_seconds,
+        );
+        return evaluated.value;
     }
+    default_value
+}
+
+fn map_cursor_to_output_space(
+    source_x: f64,
+    source_y: f64,
+    zoom: f64,
+    offset_x: f64,
+    offset_y: f64,
+    source_width: f64,
+    source_height: f64,
+    target_width: f64,
+    target_height: f64,
+) -> (f64, f64) {
+    let crop = CameraCrop::new(zoom, offset_x, offset_y, source_width, source_height);
+    let camera_x = (source_x * crop.scale_x - crop.left).clamp(0.0, source_width);
+    let camera_y = (source_y * crop.scale_y - crop.top).clamp(0.0, source_height);
 
-    let anchor_target = ((target_internal as f64) * 0.35).round() as usize;
-    let anchor_count = anchor_target.clamp(1, target_internal);
+    let fit = FrameFit::new(source_width, source_height, target_width, target_height);
+    (
+        (camera_x * fit.scale_x + fit.pad_x).clamp(0.0, target_width),
+        (camera_y * fit.scale_y + fit.pad_y).clamp(0.0, target_height),
+    )
+}
 
-    let mut keep_mask = vec![false; len];
-    keep_mask[0] = true;
-    keep_mask[len - 1] = true;
+/// The zoomed layer of the camera as FFmpeg renders it in [`build_export_filter_graph`].
+///
+/// `scale=w='iw*(zoom)'` truncates the size to whole pixels, and `overlay` clamps the offset
+/// to `overlay_w-main_w` of that truncated size, then snaps its (negative) position to the
+/// even pixel grid of 4:2:0 video.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CameraCrop {
+    /// Source pixel to zoomed-layer pixel.
+    scale_x: f64,
+    scale_y: f64,
+    /// Zoomed-layer pixel shown at the left/top edge of the camera frame.
+    left: f64,
+    top: f64,
+}
 
-    let mut kept_internal = 0usize;
-    for index in select_uniform_internal_indices(len, anchor_count) {
-        if !keep_mask[index] {
-            keep_mask[index] = true;
-            kept_internal += 1;
+impl CameraCrop {
+    fn new(zoom: f64, offset_x: f64, offset_y: f64, source_width: f64, source_height: f64) -> Self {
+        // Same floor as `clamp_camera_zoom_expr`.
+        let zoom = zoom.max(1.0);
+        let axis = |offset: f64, source_size: f64| {
+            let scaled = match (source_size * zoom).trunc() {
+                size if size >= 1.0 => size,
+                _ => source_size,
+            };
+            let position = -offset.min(scaled - source_size).max(0.0);
+            (scaled / source_size, -snap_to_chroma_grid(position))
+        };
+        let (scale_x, left) = axis(offset_x, source_width);
+        let (scale_y, top) = axis(offset_y, source_height);
+        Self {
+            scale_x,
+            scale_y,
+            left,
+            top,
         }
     }
+}
 
-    let mut scored: Vec<(usize, f64)> = (1..(len - 1))
-        .map(|index| {
-            let score = score_for_index(index);
-            (
-                index,
-                if score.is_finite() {
-                    score.max(0.0)
-                } else {
-                    0.0
-                },
-            )
-        })
-        .collect();
-    scored.sort_by(|left, right| {
-        right
-            .1
-            .total_cmp(&left.1)
-            .then_with(|| left.0.cmp(&right.0))
-    });
+/// `overlay` and `pad` truncate positions to integers and clear the low bit for chroma.
+fn snap_to_chroma_grid(position: f64) -> f64 {
+    ((position as i64) & !1) as f64
+}
 
-    for (index, _) in scored {
-        if kept_internal >= target_internal {
-            break;
-        }
-        if keep_mask[index] {
-            continue;
-        }
-        keep_mask[index] = true;
-        kept_internal += 1;
-    }
+/// Letterboxing of the camera frame into the output, as done by
+/// `scale=...:force_original_aspect_ratio=decrease,pad=...:(ow-iw)/2:(oh-ih)/2`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FrameFit {
+    scale_x: f64,
+    scale_y: f64,
+    pad_x: f64,
+    pad_y: f64,
+}
 
-    if kept_internal < target_internal {
-        for index in select_uniform_internal_indices(len, target_internal) {
-            if kept_internal >= target_internal {
-                break;
-            }
-            if keep_mask[index] {
-                continue;
-            }
-            keep_mask[index] = true;
-            kept_internal += 1;
+impl FrameFit {
+    fn new(source_width: f64, source_height: f64, target_width: f64, target_height: f64) -> Self {
+        // `scale` derives each side from the other with rounding and keeps the smaller.
+        let fitted_width = target_width.min((target_height * source_width / source_height).round());
+        let fitted_height =
+            target_height.min((target_width * source_height / source_width).round());
+        // `pad` rounds its position down to the chroma grid.
+        let pad =
+            |target: f64, fitted: f64| snap_to_chroma_grid(((target - fitted) / 2.0).max(0.0));
+        Self {
+            scale_x: fitted_width.max(1.0) / source_width,
+            scale_y: fitted_height.max(1.0) / source_height,
+            pad_x: pad(target_width, fitted_width),
+            pad_y: pad(target_height, fitted_height),
         }
     }
-
-    let mut indices: Vec<usize> = keep_mask
-        .into_iter()
-        .enumerate()
-        .filter_map(|(index, keep)| keep.then_some(index))
-        .collect();
-    indices.sort_unstable();
-    indices
 }
 
-fn select_uniform_internal_indices(len: usize, count: usize) -> Vec<usize> {
-    if len <= 2 || count == 0 {
-        return Vec::new();
-    }
+fn probe_media_info(source_video: &Path) -> MediaProbe {
+    let ffmpeg = find_ffmpeg_exe();
+    let mut command = Command::new(ffmpeg);
+    apply_no_window_flags(&mut command);
 
-    let interior = len - 2;
-    let target = count.min(interior);
-    let mut result: Vec<usize> = Vec::with_capacity(target);
+    let output = command
+        .arg("-i")
+        .arg(source_video)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .ok();
 
-    for slot in 0..target {
-        let idx = 1 + ((slot + 1) * interior) / (target + 1);
-        let idx = idx.clamp(1, len - 2);
-        if result.last().copied() != Some(idx) {
-            result.push(idx);
-        }
-    }
+    let Some(output) = output else {
+        return MediaProbe::default();
+    };
 
-    if result.len() < target {
-        for idx in 1..(len - 1) {
-            if result.len() >= target {
-                break;
+    let text = String::from_utf8_lossy(&output.stderr);
+    let mut probe = MediaProbe::default();
+
+    for line in text.lines() {
+        if probe.duration_ms.is_none() {
+            probe.duration_ms = extract_ffmpeg_duration_ms(line);
+        }
+        if probe.width.is_none() || probe.height.is_none() {
+            if let Some((w, h)) = extract_ffmpeg_video_size(line) {
+                probe.width = Some(w);
+                probe.height = Some(h);
+                probe.fps = extract_ffmpeg_fps(line);
+                probe.variable_frame_rate = is_variable_frame_rate_stream(line);
             }
-            if result.contains(&idx) {
-                continue;
+        }
+        if line.contains("Stream #") && line.contains("Audio:") {
+            probe.has_audio = true;
+            if probe.audio_codec.is_none() {
+                probe.audio_codec = extract_ffmpeg_audio_codec(line);
             }
-            result.push(idx);
         }
     }
 
-    result
+    probe
 }
 
-fn decimate_u64_points(points: &[u64], max_points: usize) -> Vec<u64> {
-    if points.len() <= max_points || max_points < 2 {
-        return points.to_vec();
-    }
+fn extract_ffmpeg_duration_ms(line: &str) -> Option<u64> {
+    let marker = "Duration: ";
+    let start = line.find(marker)? + marker.len();
+    let value = line[start..].split(',').next()?.trim();
+    parse_hhmmss_ms(value)
+}
 
-    let mut result = Vec::with_capacity(max_points);
-    let last_index = points.len() - 1;
-    let max_index = max_points - 1;
-    let mut prev_idx = usize::MAX;
+/// Latest values from FFmpeg's machine-readable `-progress` output.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct FfmpegProgress {
+    pub(crate) out_time_ms: Option<u64>,
+    frame: Option<u64>,
+    fps: Option<f64>,
+    speed: Option<f64>,
+    pub(crate) ended: bool,
+}
 
-    for index in 0..max_points {
-        let sample_idx = index * last_index / max_index;
-        if sample_idx == prev_idx {
-            continue;
+impl FfmpegProgress {
+    /// Applies one `key=value` line; returns `true` when it closes a progress block.
+    pub(crate) fn apply_line(&mut self, line: &str) -> bool {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            return false;
+        };
+        let value = value.trim();
+
+        match key.trim() {
+            // `out_time_ms` is reported in microseconds as well, despite its name.
+            "out_time_us" | "out_time_ms" => {
+                if let Ok(micros) = value.parse::<u64>() {
+                    self.out_time_ms = Some(micros / 1_000);
+                }
+            }
+            "out_time" => {
+                if let Some(time_ms) = parse_hhmmss_ms(value) {
+                    self.out_time_ms = Some(time_ms);
+                }
+            }
+            "frame" => {
+                if let Ok(frame) = value.parse::<u64>() {
+                    self.frame = Some(frame);
+                }
+            }
+            "fps" => {
+                self.fps = value.parse::<f64>().ok().filter(|fps| *fps > 0.0);
+            }
+            "speed" => {
+                self.speed = value
+                    .trim_end_matches('x')
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|speed| *speed > 0.0);
+            }
+            "progress" => {
+                self.ended = value == "end";
+                return true;
+            }
+            _ => {}
         }
-        result.push(points[sample_idx]);
-        prev_idx = sample_idx;
-    }
 
-    if result.last().copied() != points.last().copied() {
-        result.push(points[last_index]);
+        false
     }
 
-    result
+    /// Completion in `0..=0.99`, preferring output time over the frame counter.
+    fn fraction(&self, source_duration_ms: u64, expected_total_frames: f64) -> Option<f64> {
+        let fraction = match (self.out_time_ms, self.frame) {
+            (Some(time_ms), _) if time_ms > 0 => time_ms as f64 / source_duration_ms.max(1) as f64,
+            (_, Some(frame)) if frame > 0 => frame as f64 / expected_total_frames.max(1.0),
+            _ => return None,
+        };
+        Some(fraction.clamp(0.0, 0.99))
+    }
 }
 
-fn compact_click_times(points: &[u64], min_gap_ms: u64) -> Vec<u64> {
-    if points.is_empty() {
-        return Vec::new();
+fn format_export_progress_message(progress: f64, snapshot: &FfmpegProgress) -> String {
+    let mut message = format!("Exporting... {}%", (progress * 100.0).round() as u32);
+    match (snapshot.fps, snapshot.speed) {
+        (Some(fps), Some(speed)) => message.push_str(&format!(" ({fps:.0} fps, {speed:.2}x)")),
+        (Some(fps), None) => message.push_str(&format!(" ({fps:.0} fps)")),
+        (None, Some(speed)) => message.push_str(&format!(" ({speed:.2}x)")),
+        (None, None) => {}
     }
+    message
+}
 
-    let mut sorted = points.to_vec();
-    sorted.sort_unstable();
-
-    let mut result = Vec::with_capacity(sorted.len());
-    let mut last_kept: Option<u64> = None;
-    for ts in sorted {
-        if let Some(last) = last_kept {
-            if ts.saturating_sub(last) < min_gap_ms {
-                continue;
-            }
+/// Last-resort fallback so UI progress is never stuck at 0 before FFmpeg reports anything.
+fn report_export_progress_estimate(
+    status_state: &Arc<Mutex<ExportStatus>>,
+    elapsed: std::time::Duration,
+    source_duration_ms: u64,
+) {
+    let estimated =
+        (elapsed.as_millis() as f64 / source_duration_ms.max(1) as f64).clamp(0.0, 0.95);
+    update_status(status_state, |status| {
+        if estimated > status.progress {
+            status.progress = estimated;
+            status.message = format!(
+                "Exporting... {}% (estimated)",
+                (estimated * 100.0).round() as u32
+            );
+        } else if status.progress <= 0.0 {
+            status.message = format!("Exporting... preparing frames ({}s)", elapsed.as_secs());
         }
-        result.push(ts);
-        last_kept = Some(ts);
-    }
-    result
+    });
 }
 
-fn build_piecewise_track_expr(points: &[(u64, f64)], duration_ms: u64) -> String {
-    if points.is_empty() {
-        return "0".to_string();
-    }
-
-    let mut normalized = points.to_vec();
-    normalized.sort_by_key(|(ts, _)| *ts);
-    normalized.dedup_by(|left, right| left.0 == right.0);
-
-    if normalized[0].0 > 0 {
-        normalized.insert(0, (0, normalized[0].1));
-    }
-    if normalized
-        .last()
-        .is_some_and(|(last_ts, _)| *last_ts < duration_ms)
-    {
-        let last = *normalized.last().expect("normalized has last");
-        normalized.push((duration_ms, last.1));
+fn extract_ffmpeg_video_size(line: &str) -> Option<(u32, u32)> {
+    if !line.contains(" Video: ") {
+        return None;
     }
 
-    let base = normalized[0].1;
-    let mut terms = vec![format_f64(base)];
+    for token in line.split(|c: char| c.is_whitespace() || c == ',' || c == '[' || c == ']') {
+        let Some((raw_w, raw_h)) = token.split_once('x') else {
+            continue;
+        };
 
-    for pair in normalized.windows(2) {
-        let (start_ms, start_value) = pair[0];
-        let (end_ms, end_value) = pair[1];
-        if end_ms <= start_ms {
+        let width_text = raw_w.trim_matches(|c: char| !c.is_ascii_digit());
+        let height_text = raw_h.trim_matches(|c: char| !c.is_ascii_digit());
+        if width_text.is_empty() || height_text.is_empty() {
             continue;
         }
 
-        let start_s = start_ms as f64 / 1000.0;
-        let end_s = end_ms as f64 / 1000.0;
-        let span_s = ((end_ms - start_ms) as f64 / 1000.0).max(0.0001);
-        let delta = end_value - start_value;
-        let interp_expr = format!(
-            "({start}+((t-{start_t})/{span})*({delta}))",
-            start = format_f64(start_value),
-            start_t = format_f64(start_s),
-            span = format_f64(span_s),
-            delta = format_f64(delta)
-        );
-        terms.push(format!(
-            "if(gte(t,{start})*lt(t,{end}),({interp})-({base}),0)",
-            start = format_f64(start_s),
-            end = format_f64(end_s),
-            interp = interp_expr,
-            base = format_f64(base)
-        ));
-    }
-
-    if let Some((last_ts, last_value)) = normalized.last() {
-        terms.push(format!(
-            "if(gte(t,{start}),({last})-({base}),0)",
-            start = format_f64(*last_ts as f64 / 1000.0),
-            last = format_f64(*last_value),
-            base = format_f64(base)
-        ));
-    }
-
-    terms.join("+")
-}
+        let width = match width_text.parse::<u32>() {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
+        let height = match height_text.parse::<u32>() {
+            Ok(value) => value,
+            Err(_) => continue,
+        };
 
-fn build_click_pulse_factor_expr(click_times_ms: &[u64]) -> String {
-    if click_times_ms.is_empty() {
-        return "1".to_string();
+        if width >= 64 && height >= 64 {
+            return Some((width, height));
+        }
     }
 
-    let mut terms = vec!["1".to_string()];
-    let amp = 1.0 - CLICK_PULSE_MIN_SCALE;
-    let down_s = CLICK_PULSE_DOWN_MS / 1000.0;
-    let up_s = (CLICK_PULSE_TOTAL_MS - CLICK_PULSE_DOWN_MS).max(1.0) / 1000.0;
+    None
+}
 
-    for click_ms in click_times_ms {
-        let click_s = *click_ms as f64 / 1000.0;
-        let down_end_s = click_s + down_s;
-        let up_end_s = click_s + (CLICK_PULSE_TOTAL_MS / 1000.0);
+/// Codec name from an ffmpeg audio stream line, e.g. `aac` from `Audio: aac (LC) (mp4a / ...)`.
+fn extract_ffmpeg_audio_codec(line: &str) -> Option<String> {
+    let marker = "Audio: ";
+    let start = line.find(marker)? + marker.len();
+    let codec = line[start..]
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .next()?
+        .trim()
+        .to_ascii_lowercase();
+    (!codec.is_empty()).then_some(codec)
+}
 
-        let down_expr = format!(
-            "1-({amp})*((t-{start})/{down})",
-            amp = format_f64(amp),
-            start = format_f64(click_s),
-            down = format_f64(down_s)
-        );
-        let up_expr = format!(
-            "{min}+({amp})*((t-{start})/{up})",
-            min = format_f64(CLICK_PULSE_MIN_SCALE),
-            amp = format_f64(amp),
-            start = format_f64(down_end_s),
-            up = format_f64(up_s)
-        );
+fn extract_ffmpeg_fps(line: &str) -> Option<f64> {
+    if !line.contains(" Video: ") || !line.contains(" fps") {
+        return None;
+    }
 
-        terms.push(format!(
-            "if(gte(t,{start})*lt(t,{end}),({value})-1,0)",
-            start = format_f64(click_s),
-            end = format_f64(down_end_s),
-            value = down_expr
-        ));
-        terms.push(format!(
-            "if(gte(t,{start})*lt(t,{end}),({value})-1,0)",
-            start = format_f64(down_end_s),
-            end = format_f64(up_end_s),
-            value = up_expr
-        ));
+    for chunk in line.split(',') {
+        let trimmed = chunk.trim();
+        if let Some(value) = trimmed.strip_suffix(" fps") {
+            if let Ok(parsed) = value.trim().parse::<f64>() {
+                if (1.0..=240.0).contains(&parsed) {
+                    return Some(parsed);
+                }
+            }
+        }
     }
 
-    terms.join("+")
+    None
 }
 
-fn sample_click_pulse_scale_scalar(click_times_ms: &[u64], ts_ms: u64) -> f64 {
-    if click_times_ms.is_empty() {
-        return 1.0;
+fn extract_ffmpeg_tbr(line: &str) -> Option<f64> {
+    if !line.contains(" Video: ") {
+        return None;
     }
 
-    let idx = click_times_ms.partition_point(|&click_ts| click_ts <= ts_ms);
-    if idx == 0 {
-        return 1.0;
-    }
+    line.split(',').find_map(|chunk| {
+        let value = chunk.trim().strip_suffix(" tbr")?.trim();
+        let (number, multiplier) = match value.strip_suffix('k') {
+            Some(number) => (number, 1_000.0),
+            None => (value, 1.0),
+        };
+        number
+            .parse::<f64>()
+            .ok()
+            .map(|parsed| parsed * multiplier)
+            .filter(|parsed| *parsed > 0.0)
+    })
+}
 
-    let click_ts = click_times_ms[idx - 1];
-    let dt_ms = ts_ms.saturating_sub(click_ts) as f64;
-    if dt_ms < 0.0 || dt_ms > CLICK_PULSE_TOTAL_MS {
-        return 1.0;
+/// Heuristic VFR check on an ffmpeg stream line: CFR sources report matching `fps` and `tbr`,
+/// while VFR captures (e.g. dshow fallbacks) usually show an averaged `fps` far from `tbr`.
+fn is_variable_frame_rate_stream(line: &str) -> bool {
+    match (extract_ffmpeg_fps(line), extract_ffmpeg_tbr(line)) {
+        (Some(fps), Some(tbr)) => (fps - tbr).abs() / tbr > VFR_RATE_MISMATCH_RATIO,
+        _ => false,
     }
+}
 
-    if dt_ms <= CLICK_PULSE_DOWN_MS {
-        let t = dt_ms / CLICK_PULSE_DOWN_MS.max(1.0);
-        return 1.0 - (1.0 - CLICK_PULSE_MIN_SCALE) * t;
+fn parse_hhmmss_ms(value: &str) -> Option<u64> {
+    let mut parts = value.split(':');
+    let hours = parts.next()?.parse::<u64>().ok()?;
+    let minutes = parts.next()?.parse::<u64>().ok()?;
+    let sec_part = parts.next()?;
+    if parts.next().is_some() {
+        return None;
     }
 
-    let up_duration = (CLICK_PULSE_TOTAL_MS - CLICK_PULSE_DOWN_MS).max(1.0);
-    let t = (dt_ms - CLICK_PULSE_DOWN_MS) / up_duration;
-    CLICK_PULSE_MIN_SCALE + (1.0 - CLICK_PULSE_MIN_SCALE) * t
+    let mut sec_split = sec_part.split('.');
+    let seconds = sec_split.next()?.parse::<u64>().ok()?;
+    let frac = sec_split.next().unwrap_or("0");
+    let frac_trimmed = &frac[..frac.len().min(3)];
+    let millis = format!("{:0<3}", frac_trimmed).parse::<u64>().ok()?;
+
+    Some(hours * 3_600_000 + minutes * 60_000 + seconds * 1_000 + millis)
 }
 
-fn update_status(state: &Arc<Mutex<ExportStatus>>, updater: impl FnOnce(&mut ExportStatus)) {
-    if let Ok(mut status) = state.lock() {
-        updater(&mut status);
-    }
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
 }
 
-fn resolve_project_file(path: &str) -> Result<PathBuf, String> {
-    let trimmed = path.trim();
-    if trimmed.is_empty() {
-        return Err("Project path is empty".to_string());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::events::CoordinateSpace;
+    use crate::models::project::{
+        Background, CameraSpring, CursorSettings, CursorShadow, ExportSettings, NormalizedRect,
+        ProjectSettings, Timeline, ZoomMode, ZoomSegment, ZoomTrigger,
+    };
+
+    fn sample_project() -> Project {
+        Project {
+            schema_version: SCHEMA_VERSION,
+            id: "test-project".to_string(),
+            name: "Test".to_string(),
+            description: None,
+            tags: Vec::new(),
+            created_at: 0,
+            video_path: "raw.mp4".to_string(),
+            proxy_video_path: None,
+            events_path: "events.json".to_string(),
+            duration_ms: 10_000,
+            video_width: 1920,
+            video_height: 1080,
+            timeline: Timeline {
+                zoom_segments: vec![ZoomSegment {
+                    id: "z1".to_string(),
+                    start_ts: 1_000,
+                    end_ts: 2_000,
+                    initial_rect: NormalizedRect {
+                        x: 0.4,
+                        y: 0.3,
+                        width: 0.2,
+                        height: 0.2,
+                    },
+                    target_points: vec![],
+                    spring: CameraSpring {
+                        mass: 1.0,
+                        stiffness: 170.0,
+                        damping: 26.0,
+                    },
+                    pan_trajectory: vec![],
+                    legacy_easing: None,
+                    mode: ZoomMode::Fixed,
+                    trigger: ZoomTrigger::AutoClick,
+                    is_auto: true,
+                    label: None,
+                }],
+            },
+            settings: ProjectSettings {
+                cursor: CursorSettings::default(),
+                background: Background::default(),
+                export: ExportSettings::default(),
+                camera: Default::default(),
+                frame_background: FrameBackground::None,
+            },
+            audio_issues: Vec::new(),
+            camera_engine_version: crate::models::project::CAMERA_ENGINE_VERSION,
+            monitor_composite_error: None,
+            audio_start_offsets: None,
+        }
     }
 
-    let input = PathBuf::from(trimmed);
-    if input
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
-    {
-        Ok(input)
-    } else {
-        Ok(input.join("project.json"))
+    fn zoom_segment(id: &str, start_ts: u64, end_ts: u64, rect: NormalizedRect) -> ZoomSegment {
+        ZoomSegment {
+            id: id.to_string(),
+            start_ts,
+            end_ts,
+            initial_rect: rect,
+            target_points: vec![],
+            spring: CameraSpring {
+                mass: 1.0,
+                stiffness: 170.0,
+                damping: 26.0,
+            },
+            pan_trajectory: vec![],
+            legacy_easing: None,
+            mode: ZoomMode::Fixed,
+            trigger: ZoomTrigger::AutoClick,
+            is_auto: true,
+            label: None,
+        }
     }
-}
 
-fn load_project_file(path: &Path) -> Result<Project, String> {
-    let raw = std::fs::read_to_string(path)
-        .map_err(|e| format!("Failed to read project file {}: {e}", path.display()))?;
-    let project: Project = serde_json::from_str(&raw)
-        .map_err(|e| format!("Failed to parse project file {}: {e}", path.display()))?;
+    #[test]
+    fn resumable_export_splits_long_renders_into_keyed_chunks() {
+        assert_eq!(
+            checkpoint_chunks(150_000, 60_000),
+            vec![(0, 60_000), (60_000, 120_000), (120_000, 150_000)]
+        );
+        assert_eq!(checkpoint_blocker(1, false, false, false, 150_000), None);
+        assert!(checkpoint_blocker(1, false, false, false, 30_000).is_some());
+        assert!(checkpoint_blocker(1, true, false, false, 150_000).is_some());
+        assert!(checkpoint_blocker(2, false, false, false, 150_000).is_some());
+        assert!(checkpoint_blocker(1, false, false, true, 150_000).is_some());
+
+        // Reference FNV-1a vector: keys must not change between builds.
+        let mut hash = Fnv1a::new();
+        hash.write(b"a");
+        assert_eq!(hash.finish(), 0xaf63_dc4c_8601_ec8c);
+
+        let graph = "crop=100:100:0:0,setpts=PTS-STARTPTS,fps=30[cam];[cam]null[vout]";
+        assert_eq!(
+            chunk_filter_graph(graph, 60_000, ExportColorFormat::default()).expect("chunk graph"),
+            "crop=100:100:0:0,setpts=PTS-STARTPTS+60.000/TB,fps=30[cam];[cam]null[vabs];\
+             [vabs]setpts=PTS-STARTPTS[vout]"
+        );
+        assert!(chunk_filter_graph(
+            "fps=30[cam];[cam]null[vout]",
+            0,
+            ExportColorFormat::default()
+        )
+        .is_err());
 
-    if project.schema_version != SCHEMA_VERSION {
-        return Err(format!(
-            "Unsupported project schemaVersion: expected {}, got {}",
-            SCHEMA_VERSION, project.schema_version
+        let project = sample_project();
+        let key = |project: &Project| {
+            export_checkpoint_key(
+                project,
+                None,
+                Path::new("raw.mp4"),
+                (1920, 1080, 30),
+                "h264",
+                ExportColorFormat::default(),
+                None,
+            )
+        };
+        let mut edited = project.clone();
+        edited.timeline.zoom_segments.push(zoom_segment(
+            "z1",
+            0,
+            1_000,
+            NormalizedRect {
+                x: 0.0,
+                y: 0.0,
+                width: 0.5,
+                height: 0.5,
+            },
         ));
+        assert_eq!(key(&project), key(&project));
+        assert_ne!(key(&project), key(&edited));
+
+        let dir = std::env::temp_dir().join(format!("nsc-checkpoint-test-{}", now_ms()));
+        std::fs::create_dir_all(&dir).expect("checkpoint dir");
+        let current = checkpoint_chunk_path(&dir, "rec-1", key(&project), (0, 60_000), "mp4");
+        let stale = checkpoint_chunk_path(&dir, "rec-1", key(&edited), (0, 60_000), "mp4");
+        let other = checkpoint_chunk_path(&dir, "rec-1-b", key(&edited), (0, 60_000), "mp4");
+        for path in [&current, &stale, &other] {
+            std::fs::write(path, b"chunk").expect("write chunk");
+        }
+        prune_stale_checkpoints(&dir, "rec-1", key(&project));
+        assert!(current.exists() && !stale.exists() && other.exists());
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
-    Ok(project)
-}
+    #[test]
+    fn very_short_recording_builds_a_finite_filter_graph() {
+        let mut project = sample_project();
+        project.duration_ms = 50;
+        project.timeline.zoom_segments = vec![zoom_segment(
+            "z1",
+            0,
+            50,
+            NormalizedRect {
+                x: 0.25,
+                y: 0.25,
+                width: 0.5,
+                height: 0.5,
+            },
+        )];
+        let events = EventsFile {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            recording_id: project.id.clone(),
+            start_time_ms: 0,
+            screen_width: 1920,
+            screen_height: 1080,
+            scale_factor: 1.0,
+            coordinate_space: None,
+            events: vec![
+                InputEvent::Move {
+                    ts: 0,
+                    x: 960.0,
+                    y: 540.0,
+                },
+                InputEvent::Click {
+                    ts: 30,
+                    x: 970.0,
+                    y: 545.0,
+                    button: crate::models::events::MouseButton::Left,
+                    ui_context: None,
+                },
+            ],
+        };
 
-fn load_events_file(project_dir: &Path, events_path: &str) -> Result<EventsFile, String> {
-    let path = resolve_media_path(project_dir, events_path)?;
-    let raw = std::fs::read_to_string(&path)
-        .map_err(|e| format!("Failed to read events file {}: {e}", path.display()))?;
-    let events: EventsFile = serde_json::from_str(&raw)
-        .map_err(|e| format!("Failed to parse events file {}: {e}", path.display()))?;
+        for (source_duration_ms, aspect_mode) in [
+            (50, AspectMode::Fit),
+            (1, AspectMode::Fit),
+            (50, AspectMode::AutoReframe),
+        ] {
+            project.settings.export.aspect_mode = aspect_mode;
+            let (graph, _, temp_files) = build_export_filter_graph(
+                &project,
+                Some(&events),
+                1080,
+                1920,
+                30,
+                source_duration_ms,
+                1920,
+                1080,
+                None,
+                false,
+            )
+            .expect("filter graph");
+            for path in temp_files {
+                let _ = std::fs::remove_file(path);
+            }
+            assert!(graph.ends_with("[vout]"), "{graph}");
+            assert!(
+                !graph.contains("NaN") && !graph.contains("inf"),
+                "non-finite value in {graph}"
+            );
+        }
+    }
 
-    if events.schema_version != EVENTS_SCHEMA_VERSION {
-        return Err(format!(
-            "Unsupported events schemaVersion: expected {}, got {}",
-            EVENTS_SCHEMA_VERSION, events.schema_version
-        ));
+    #[test]
+    fn filter_graph_uses_dynamic_zoom_pipeline() {
+        let project = sample_project();
+        let (graph, cursor_file, temp_files) = build_export_filter_graph(
+            &project, None, 1920, 1080, 30, 10_000, 1920, 1080, None, false,
+        )
+        .expect("filter graph");
+
+        assert!(cursor_file.is_none());
+        assert!(temp_files.is_empty());
+        assert!(graph.contains("split=2[base][zoom]"));
+        assert!(graph.contains("scale=w='iw*("));
+        assert!(graph.contains("exp("));
+        assert!(graph.contains("eval=frame"));
+        assert!(graph.contains("[base][scaled]overlay=x='-max(0,min("));
+        assert!(graph.contains("overlay_h-main_h"));
+        assert!(graph.contains("fps=30"));
+    }
+
+    #[test]
+    fn empty_timeline_skips_camera_stage() {
+        let mut project = sample_project();
+        project.timeline.zoom_segments.clear();
+        let (graph, _, _) = build_export_filter_graph(
+            &project, None, 1920, 1080, 30, 10_000, 1920, 1080, None, false,
+        )
+        .expect("filter graph");
+
+        assert!(!graph.contains("split=2"));
+        assert!(!graph.contains("[zoom]"));
+        assert!(!graph.contains("overlay"));
+        assert!(graph.starts_with("fps=30[cam];[cam]scale=1920:1080"));
+        assert!(graph.ends_with("[vout]"));
+
+        // A full-frame segment still leaves the camera at rest.
+        project.timeline.zoom_segments = vec![zoom_segment(
+            "full",
+            1_000,
+            2_000,
+            NormalizedRect {
+                x: 0.0,
+                y: 0.0,
+                width: 1.0,
+                height: 1.0,
+            },
+        )];
+        let (graph, _, _) = build_export_filter_graph(
+            &project, None, 1920, 1080, 30, 10_000, 1920, 1080, None, false,
+        )
+        .expect("filter graph");
+        assert!(!graph.contains("split=2"));
+    }
+
+    #[test]
+    fn supersampled_scaling_renders_the_camera_at_twice_the_size() {
+        let mut project = sample_project();
+        let (fast, _, _) = build_export_filter_graph(
+            &project, None, 1920, 1080, 30, 10_000, 1920, 1080, None, false,
+        )
+        .expect("filter graph");
+        assert!(!fast.contains("lanczos"));
+
+        project.settings.export.scaling_quality = ScalingQuality::Supersampled;
+        let (graph, _, _) = build_export_filter_graph(
+            &project, None, 1920, 1080, 30, 10_000, 1920, 1080, None, false,
+        )
+        .expect("filter graph");
+        assert!(graph.contains("[base]scale=iw*2:ih*2:flags=neighbor[canvas]"));
+        assert!(graph.contains(")*2':eval=frame:flags=lanczos[scaled]"));
+        assert!(graph
+            .contains("[cam]scale=1920:1080:force_original_aspect_ratio=decrease:flags=lanczos,"));
+        assert!(graph.ends_with("[vout]"));
+
+        // A 4K source already oversamples a 1080p export.
+        assert_eq!(supersample_factor(3840, 2160, 1920, 1080), 1);
+        assert_eq!(supersample_factor(2560, 1440, 1920, 1080), 2);
     }
 
-    Ok(events)
-}
+    #[test]
+    fn cursor_render_mode_selects_point_source() {
+        let events = vec![
+            InputEvent::Move {
+                ts: 0,
+                x: 0.0,
+                y: 0.0,
+            },
+            InputEvent::Move {
+                ts: 10,
+                x: 192.0,
+                y: 108.0,
+            },
+            InputEvent::Scroll {
+                ts: 20,
+                x: 960.0,
+                y: 540.0,
+                delta: crate::models::events::ScrollDelta { dx: 0.0, dy: -1.0 },
+            },
+        ];
+        let mut cursor = CursorSettings::default();
+        let point_xs = |cursor: &CursorSettings| {
+            export_cursor_points(&events, 1920.0, 1080.0, cursor)
+                .iter()
+                .map(|point| (point.ts, point.x))
+                .collect::<Vec<_>>()
+        };
 
-fn resolve_media_path(project_dir: &Path, raw_path: &str) -> Result<PathBuf, String> {
-    let trimmed = raw_path.trim();
-    if trimmed.is_empty() {
-        return Err("Project videoPath is empty".to_string());
+        cursor.render_mode = CursorRenderMode::Raw;
+        assert_eq!(point_xs(&cursor), vec![(0, 0.0), (10, 0.1)]);
+
+        // Smoothing resamples onto a 10 ms grid first, landing on the recorded timestamps.
+        cursor.render_mode = CursorRenderMode::Smoothed;
+        cursor.resample_hz = 100.0;
+        let smoothed = point_xs(&cursor);
+        assert_eq!(smoothed.len(), 3);
+        assert!(smoothed[1].1 > 0.0 && smoothed[1].1 < 0.1);
+
+        cursor.render_mode = CursorRenderMode::Off;
+        assert!(point_xs(&cursor).is_empty());
     }
 
-    let candidate = PathBuf::from(trimmed);
-    if candidate.is_absolute() {
-        Ok(candidate)
-    } else {
-        Ok(project_dir.join(candidate))
+    #[test]
+    fn filter_graph_omits_cursor_overlay_when_cursor_hidden() {
+        let mut project = sample_project();
+        project.settings.cursor.show_cursor = false;
+        let events = EventsFile {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            recording_id: project.id.clone(),
+            start_time_ms: 0,
+            screen_width: 1920,
+            screen_height: 1080,
+            scale_factor: 1.0,
+            coordinate_space: None,
+            events: vec![
+                InputEvent::Move {
+                    ts: 0,
+                    x: 100.0,
+                    y: 100.0,
+                },
+                InputEvent::Move {
+                    ts: 500,
+                    x: 400.0,
+                    y: 300.0,
+                },
+            ],
+        };
+
+        let (graph, cursor_file, temp_files) = build_export_filter_graph(
+            &project,
+            Some(&events),
+            1920,
+            1080,
+            30,
+            10_000,
+            1920,
+            1080,
+            None,
+            false,
+        )
+        .expect("filter graph");
+
+        assert!(cursor_file.is_none());
+        assert!(temp_files.is_empty());
+        assert!(!graph.contains("subtitles="));
+        assert!(!graph.contains("[framed]"));
+        assert!(graph.ends_with("black[vout]"));
     }
-}
 
-fn resolve_output_path(
-    project_dir: &Path,
-    project_id: &str,
-    output_path: Option<String>,
-) -> Result<PathBuf, String> {
-    if let Some(raw) = output_path {
-        let trimmed = raw.trim();
-        if !trimmed.is_empty() {
-            return Ok(PathBuf::from(trimmed));
+    #[test]
+    fn frame_background_variants_fill_the_letterbox() {
+        let size = (1080, 1920);
+        let fit = "scale=1080:1920:force_original_aspect_ratio=decrease";
+        assert_eq!(
+            frame_fill_filter(&FrameBackground::None, size, 30, ""),
+            format!("{fit},pad=1080:1920:(ow-iw)/2:(oh-ih)/2:black")
+        );
+        let solid = FrameBackground::Solid {
+            color: "#1a1a2e".to_string(),
+        };
+        assert_eq!(
+            frame_fill_filter(&solid, size, 30, ":flags=lanczos"),
+            format!("{fit}:flags=lanczos,pad=1080:1920:(ow-iw)/2:(oh-ih)/2:0x1A1A2E")
+        );
+        assert_eq!(
+            frame_fill_filter(&FrameBackground::Blur { sigma: 24.0 }, size, 30, ""),
+            format!(
+                "split=2[fgsrc][bgsrc];\
+                 [bgsrc]scale=1080:1920:force_original_aspect_ratio=increase,crop=1080:1920,setsar=1,\
+                 gblur=sigma=24.0000[bg];[fgsrc]{fit}[fg];[bg][fg]overlay=(W-w)/2:(H-h)/2"
+            )
+        );
+        let image = FrameBackground::Image {
+            path: "C:\\Media\\wall.png".to_string(),
+        };
+        assert_eq!(
+            frame_fill_filter(&image, size, 30, ""),
+            format!(
+                "{fit}[fg];movie=filename='C\\:/Media/wall.png',loop=loop=-1:size=1,setpts=N/30/TB,\
+                 scale=1080:1920:force_original_aspect_ratio=increase,crop=1080:1920,setsar=1[bg];\
+                 [bg][fg]overlay=(W-w)/2:(H-h)/2:shortest=1"
+            )
+        );
+
+        assert!(solid.validate().is_ok());
+        assert!(FrameBackground::Blur { sigma: 0.0 }.validate().is_err());
+        assert!(FrameBackground::Solid {
+            color: "navy".to_string()
         }
+        .validate()
+        .is_err());
     }
 
-    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
-    Ok(project_dir.join(format!("export-{project_id}-{timestamp}.mp4")))
-}
+    #[test]
+    fn blurred_frame_background_sits_under_the_cursor_overlay() {
+        let mut project = sample_project();
+        project.settings.frame_background = FrameBackground::Blur { sigma: 20.0 };
+        let events = EventsFile {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            recording_id: project.id.clone(),
+            start_time_ms: 0,
+            screen_width: 1920,
+            screen_height: 1080,
+            scale_factor: 1.0,
+            coordinate_space: None,
+            events: vec![
+                InputEvent::Move {
+                    ts: 0,
+                    x: 100.0,
+                    y: 100.0,
+                },
+                InputEvent::Move {
+                    ts: 500,
+                    x: 400.0,
+                    y: 300.0,
+                },
+            ],
+        };
 
-fn map_time_ms(ts: u64, from_duration_ms: u64, to_duration_ms: u64) -> u64 {
-    if from_duration_ms == 0 || to_duration_ms == 0 {
-        return 0;
+        let (graph, _, temp_files) = build_export_filter_graph(
+            &project,
+            Some(&events),
+            1080,
+            1920,
+            30,
+            10_000,
+            1920,
+            1080,
+            None,
+            false,
+        )
+        .expect("filter graph");
+        for path in temp_files {
+            let _ = std::fs::remove_file(path);
+        }
+
+        let blur = graph
+            .find("gblur=sigma=20.0000")
+            .expect("blurred background");
+        let framed = graph
+            .find("overlay=(W-w)/2:(H-h)/2[framed]")
+            .expect("framed output");
+        let cursor = graph.find("[framed]subtitles=").expect("cursor overlay");
+        assert!(graph.contains("[cam]split=2[fgsrc][bgsrc]"));
+        assert!(blur < framed && framed < cursor);
     }
-    let mapped = (ts as f64 / from_duration_ms as f64) * to_duration_ms as f64;
-    mapped.round().clamp(0.0, to_duration_ms as f64) as u64
-}
 
-fn apply_cursor_timing_offset_ms(ts_ms: u64, duration_ms: u64) -> u64 {
-    ts_ms
-        .saturating_add(CURSOR_TIMING_OFFSET_MS)
-        .min(duration_ms)
-}
+    #[test]
+    fn auto_reframe_crops_a_tracking_window_out_of_the_full_height_frame() {
+        assert_eq!(
+            auto_reframe_canvas((1920, 1080), (1080, 1920)),
+            Some((3412, 1920))
+        );
+        assert_eq!(auto_reframe_canvas((1920, 1080), (1920, 1080)), None);
 
-fn merge_time_ranges(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
-    if ranges.is_empty() {
-        return ranges;
-    }
-    ranges.sort_by_key(|(start, _)| *start);
-    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
-    for (start, end) in ranges.into_iter().filter(|(start, end)| end > start) {
-        if let Some((_, last_end)) = merged.last_mut() {
-            if start <= *last_end {
-                *last_end = (*last_end).max(end);
-                continue;
-            }
-        }
-        merged.push((start, end));
+        let mut project = sample_project();
+        project.settings.export.aspect_mode = AspectMode::AutoReframe;
+        let (graph, _, _) = build_export_filter_graph(
+            &project, None, 1080, 1920, 30, 10_000, 1920, 1080, None, false,
+        )
+        .expect("filter graph");
+        assert!(graph.contains("[cam]scale=3412:1920:force_original_aspect_ratio=decrease"));
+        assert!(graph.contains("[wide];[wide]crop=1080:1920:x='"));
+        assert!(graph.ends_with(":y=0[vout]"));
+
+        // Without activity the window stays centred.
+        let states = build_camera_states(&project, 10_000, 10_000, 1920, 1080, 30.0);
+        let idle = auto_reframe_track(
+            &project,
+            None,
+            &states,
+            10_000,
+            (1920, 1080),
+            (3412, 1920),
+            1080,
+            30.0,
+        );
+        assert_eq!(idle, vec![(0, 1166.0)]);
+
+        let events = EventsFile {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            recording_id: project.id.clone(),
+            start_time_ms: 0,
+            screen_width: 1920,
+            screen_height: 1080,
+            scale_factor: 1.0,
+            coordinate_space: None,
+            events: (0..20)
+                .map(|index| InputEvent::Move {
+                    ts: index * 500,
+                    x: 1800.0,
+                    y: 540.0,
+                })
+                .collect(),
+        };
+        let track = auto_reframe_track(
+            &project,
+            Some(&events),
+            &states,
+            10_000,
+            (1920, 1080),
+            (3412, 1920),
+            1080,
+            30.0,
+        );
+        assert!(!track.is_empty());
+        assert!(track.iter().all(|(_, left)| (0.0..=2332.0).contains(left)));
+
+        // Fit keeps the plain letterboxed graph.
+        project.settings.export.aspect_mode = AspectMode::Fit;
+        let (fit, _, _) = build_export_filter_graph(
+            &project, None, 1080, 1920, 30, 10_000, 1920, 1080, None, false,
+        )
+        .expect("filter graph");
+        assert!(!fit.contains("crop=1080:1920"));
     }
-    merged
-}
 
-fn map_hidden_ranges_to_source(
-    ranges: &[TimeRange],
-    project_duration_ms: u64,
-    source_duration_ms: u64,
-) -> Vec<(u64, u64)> {
-    if ranges.is_empty() || project_duration_ms == 0 || source_duration_ms == 0 {
-        return Vec::new();
+    #[test]
+    fn camera_returns_to_fullscreen_between_separated_segments() {
+        let mut project = sample_project();
+        project.timeline.zoom_segments = vec![
+            zoom_segment(
+                "z1",
+                1_000,
+                2_000,
+                NormalizedRect {
+                    x: 0.4,
+                    y: 0.3,
+                    width: 0.2,
+                    height: 0.2,
+                },
+            ),
+            zoom_segment(
+                "z2",
+                4_000,
+                5_000,
+                NormalizedRect {
+                    x: 0.2,
+                    y: 0.2,
+                    width: 0.25,
+                    height: 0.25,
+                },
+            ),
+        ];
+
+        let states = build_camera_states(&project, 10_000, 10_000, 1_920, 1_080, 30.0);
+        let gap_state = states
+            .iter()
+            .find(|state| state.start_frame >= 60.0 - 0.01 && state.start_frame <= 60.0 + 0.01)
+            .expect("expected camera state at first segment end");
+
+        assert!((gap_state.zoom.target - 1.0).abs() < 0.0001);
+        assert!(gap_state.offset_x.target.abs() < 0.0001);
+        assert!(gap_state.offset_y.target.abs() < 0.0001);
     }
-    let mapped = ranges
-        .iter()
-        .map(|range| {
+
+    #[test]
+    fn explicit_target_point_zoom_dollies_around_a_fixed_center() {
+        let rect = NormalizedRect {
+            x: 0.25,
+            y: 0.25,
+            width: 0.5,
+            height: 0.5,
+        };
+        let mut segment = zoom_segment("dolly", 1_000, 3_000, rect.clone());
+        segment.target_points = vec![
+            TargetPoint {
+                ts: 1_000,
+                rect: rect.clone(),
+                zoom: None,
+            },
+            TargetPoint {
+                ts: 2_000,
+                rect,
+                zoom: Some(4.0),
+            },
+        ];
+        let mut project = sample_project();
+        project.timeline.zoom_segments = vec![segment];
+
+        let states = build_camera_states(&project, 10_000, 10_000, 1_920, 1_080, 30.0);
+        let target_at = |frame: f64| {
+            let state = states
+                .iter()
+                .find(|state| (state.start_frame - frame).abs() < 0.01)
+                .expect("camera state");
             (
-                map_time_ms(range.start_ts, project_duration_ms, source_duration_ms),
-                map_time_ms(range.end_ts, project_duration_ms, source_duration_ms),
+                state.zoom.target,
+                state.offset_x.target,
+                state.offset_y.target,
             )
-        })
-        .collect::<Vec<_>>();
-    merge_time_ranges(mapped)
-}
+        };
+        // Without an override the zoom follows the rect size.
+        let (zoom, offset_x, offset_y) = target_at(30.0);
+        assert!((zoom - 2.0).abs() < 1e-9);
+        assert!((offset_x / zoom + 1_920.0 / zoom / 2.0 - 960.0).abs() < 1e-6);
+        // The override zooms in further while the crop stays centered on the same point.
+        let (zoom, offset_x, offset_y_zoomed) = target_at(60.0);
+        assert!((zoom - 4.0).abs() < 1e-9);
+        assert!((offset_x / zoom + 1_920.0 / zoom / 2.0 - 960.0).abs() < 1e-6);
+        assert!(
+            (offset_y_zoomed / 4.0 + 1_080.0 / 8.0 - (offset_y / 2.0 + 1_080.0 / 4.0)).abs() < 1e-6
+        );
+    }
 
-fn is_hidden_at(ts_ms: u64, hidden_ranges_ms: &[(u64, u64)]) -> bool {
-    hidden_ranges_ms
-        .iter()
-        .any(|(start, end)| ts_ms >= *start && ts_ms < *end)
-}
+    #[test]
+    fn segment_camera_samples_match_export_sampling() {
+        let project = sample_project();
+        let samples = sample_segment_camera_rects(&project, "z1", &[0, 1_500, 60_000])
+            .expect("segment samples");
+        assert_eq!(samples[0].ts, 1_000);
+        assert_eq!(samples[1].ts, 1_500);
+        assert_eq!(samples[2].ts, 2_000);
 
-fn visible_subranges(
-    start_ms: u64,
-    end_ms: u64,
-    hidden_ranges_ms: &[(u64, u64)],
-) -> Vec<(u64, u64)> {
-    if end_ms <= start_ms {
-        return Vec::new();
-    }
-    if hidden_ranges_ms.is_empty() {
-        return vec![(start_ms, end_ms)];
+        // Frame 45 at 30 fps is the same instant as 1500 ms.
+        let states = build_camera_states(&project, 10_000, 10_000, 1_920, 1_080, 30.0);
+        let zoom = sample_camera_axis_value(&states, 45.0, 30.0, |s| s.zoom, 1.0);
+        let offset_x = sample_camera_axis_value(&states, 45.0, 30.0, |s| s.offset_x, 0.0);
+        let offset_y = sample_camera_axis_value(&states, 45.0, 30.0, |s| s.offset_y, 0.0);
+        let expected = camera_values_to_rect(zoom, offset_x, offset_y, 1_920.0, 1_080.0);
+
+        let sample = &samples[1];
+        assert!((sample.zoom - zoom).abs() < 1e-6);
+        assert!((sample.rect.x - expected.x).abs() < 1e-6);
+        assert!((sample.rect.y - expected.y).abs() < 1e-6);
+        assert!((sample.rect.width - expected.width).abs() < 1e-6);
+        assert!(sample.zoom > 1.0);
     }
 
-    let mut out = vec![(start_ms, end_ms)];
-    for (hidden_start, hidden_end) in hidden_ranges_ms {
-        if *hidden_end <= *hidden_start {
-            continue;
-        }
-        let mut next = Vec::new();
-        for (frag_start, frag_end) in out {
-            if *hidden_end <= frag_start || *hidden_start >= frag_end {
-                next.push((frag_start, frag_end));
-                continue;
-            }
-            if *hidden_start > frag_start {
-                next.push((frag_start, (*hidden_start).min(frag_end)));
-            }
-            if *hidden_end < frag_end {
-                next.push(((*hidden_end).max(frag_start), frag_end));
-            }
-        }
-        out = next;
-        if out.is_empty() {
-            break;
-        }
+    #[test]
+    fn size_estimate_is_bounded_by_a_bitrate_cap() {
+        let crf = estimate_export_bytes((1920, 1080, 30), "h264", None, None, true, 60_000)
+            .expect("estimate");
+        assert_eq!(crf.mode, SizeEstimateMode::Crf);
+        assert_eq!(crf.audio_bitrate_kbps, 192);
+        assert!(crf.low_bytes < crf.estimated_bytes && crf.estimated_bytes < crf.high_bytes);
+
+        // Six CRF steps up halve the video bitrate.
+        let smaller = estimate_export_bytes((1920, 1080, 30), "h264", Some(24), None, true, 60_000)
+            .expect("estimate");
+        assert_eq!(smaller.video_bitrate_kbps, crf.video_bitrate_kbps / 2);
+
+        // 2500 kbps video + 192 kbps audio for 60 s, plus 1% container overhead.
+        let capped =
+            estimate_export_bytes((1920, 1080, 30), "h264", None, Some(2_500), true, 60_000)
+                .expect("estimate");
+        assert_eq!(capped.mode, SizeEstimateMode::Bitrate);
+        assert_eq!(capped.high_bytes, 20_391_900);
+        assert!(capped.estimated_bytes <= capped.high_bytes);
+
+        assert!(estimate_export_bytes((1920, 1080, 30), "av1", None, None, false, 1_000).is_err());
     }
-    out.into_iter().filter(|(s, e)| e > s).collect()
-}
-
-fn format_ass_time(ms: u64) -> String {
-    let total_centis = ms / 10;
-    let centis = total_centis % 100;
-    let total_seconds = total_centis / 100;
-    let seconds = total_seconds % 60;
-    let total_minutes = total_seconds / 60;
-    let minutes = total_minutes % 60;
-    let hours = total_minutes / 60;
-    format!("{hours}:{minutes:02}:{seconds:02}.{centis:02}")
-}
 
-fn escape_filter_path(path: &Path) -> String {
-    path.to_string_lossy()
-        .replace('\\', "/")
-        .replace(':', "\\:")
-        .replace('\'', "\\'")
-}
+    #[test]
+    fn recommendation_matches_source_without_upscaling() {
+        let high = recommend_export(
+            2_560,
+            1_440,
+            Some(60.0),
+            60,
+            Some(AspectRatio::Widescreen),
+            ExportQualityTier::High,
+        );
+        assert_eq!((high.width, high.height, high.fps), (2_560, 1_440, 60));
+        assert_eq!(high.codec, "h264");
+        assert_eq!(high.crf, 18);
+
+        let vertical = recommend_export(
+            2_560,
+            1_440,
+            Some(59.94),
+            60,
+            Some(AspectRatio::Vertical),
+            ExportQualityTier::Standard,
+        );
+        assert_eq!((vertical.width, vertical.height), (810, 1_440));
 
-#[derive(Debug, Clone, Copy)]
-struct PreviewCursorPoint {
-    ts: u64,
-    x: f64,
-    y: f64,
-}
+        let draft = recommend_export(1_280, 720, None, 60, None, ExportQualityTier::Draft);
+        assert_eq!((draft.width, draft.height, draft.fps), (1_280, 720, 30));
 
-// Keep export cursor math aligned with Edit.tsx preview:
-// - same event set (move/click/mouseUp/scroll)
-// - same EMA smoothing formula based on smoothing_factor.
-fn extract_preview_cursor_points(
-    events: &[InputEvent],
-    screen_width: f64,
-    screen_height: f64,
-    smoothing_factor: f64,
-) -> Vec<PreviewCursorPoint> {
-    if events.is_empty() || screen_width <= 0.0 || screen_height <= 0.0 {
-        return Vec::new();
+        let tiny = recommend_export(320, 200, None, 30, None, ExportQualityTier::Standard);
+        assert_eq!((tiny.width, tiny.height), (320, 200));
     }
 
-    let mut samples: Vec<PreviewCursorPoint> = events
-        .iter()
-        .filter_map(|event| match event {
-            InputEvent::Move { ts, x, y }
-            | InputEvent::Click { ts, x, y, .. }
-            | InputEvent::MouseUp { ts, x, y, .. }
-            | InputEvent::Scroll { ts, x, y, .. } => Some(PreviewCursorPoint {
-                ts: *ts,
-                x: (*x / screen_width).clamp(0.0, 1.0),
-                y: (*y / screen_height).clamp(0.0, 1.0),
-            }),
-            _ => None,
-        })
-        .collect();
+    #[test]
+    fn ffmpeg_video_size_parser_handles_common_line() {
+        let line = "  Stream #0:0: Video: h264, yuv420p(progressive), 1920x1080, 30 fps";
+        assert_eq!(extract_ffmpeg_video_size(line), Some((1920, 1080)));
+    }
 
-    if samples.is_empty() {
-        return samples;
+    #[test]
+    fn ffmpeg_fps_parser_handles_common_line() {
+        let line = "  Stream #0:0: Video: h264, yuv420p(progressive), 1920x1080, 29.97 fps, 30 tbr";
+        let fps = extract_ffmpeg_fps(line).expect("fps");
+        assert!((fps - 29.97).abs() < 0.0001);
     }
-    samples.sort_by_key(|sample| sample.ts);
 
-    if samples.len() <= 1 {
-        return samples;
+    #[test]
+    fn ffmpeg_progress_block_parses_key_values() {
+        let block = "frame=240\nfps=59.8\nstream_0_0_q=23.0\nout_time_us=4000000\nout_time_ms=4000000\nout_time=00:00:04.000000\nspeed=1.98x\nprogress=continue";
+        let mut progress = FfmpegProgress::default();
+        let closed = block
+            .lines()
+            .map(|line| progress.apply_line(line))
+            .collect::<Vec<_>>();
+
+        assert_eq!(closed.iter().filter(|closed| **closed).count(), 1);
+        assert!(*closed.last().expect("last line"));
+        assert_eq!(progress.out_time_ms, Some(4_000));
+        assert_eq!(progress.frame, Some(240));
+        assert_eq!(progress.fps, Some(59.8));
+        assert_eq!(progress.speed, Some(1.98));
+        assert!(!progress.ended);
+        assert_eq!(progress.fraction(10_000, 600.0), Some(0.4));
     }
 
-    let factor = smoothing_factor.clamp(0.0, 1.0);
-    if factor <= f64::EPSILON {
-        return samples;
+    #[test]
+    fn ffmpeg_progress_handles_unavailable_values_and_end() {
+        let mut progress = FfmpegProgress::default();
+        for line in [
+            "frame=0",
+            "out_time_us=N/A",
+            "speed=N/A",
+            "progress=continue",
+        ] {
+            progress.apply_line(line);
+        }
+        assert_eq!(progress.out_time_ms, None);
+        assert_eq!(progress.speed, None);
+        assert_eq!(progress.fraction(10_000, 300.0), None);
+
+        for line in ["frame=300", "out_time_us=12000000", "progress=end"] {
+            progress.apply_line(line);
+        }
+        assert!(progress.ended);
+        assert_eq!(progress.fraction(10_000, 300.0), Some(0.99));
     }
 
-    let alpha = 1.0 - factor * 0.9;
-    let mut smoothed_x = samples[0].x;
-    let mut smoothed_y = samples[0].y;
-    let mut smoothed = Vec::with_capacity(samples.len());
-    smoothed.push(samples[0]);
+    #[test]
+    fn ffmpeg_stream_line_detects_variable_frame_rate() {
+        let cfr = "  Stream #0:0: Video: h264, yuv420p(progressive), 1920x1080, 29.97 fps, 30 tbr";
+        let vfr = "  Stream #0:0: Video: h264, yuv420p(progressive), 1920x1080, 23.41 fps, 1k tbr";
+        assert!(!is_variable_frame_rate_stream(cfr));
+        assert!(is_variable_frame_rate_stream(vfr));
+    }
 
-    for sample in samples.iter().skip(1).copied() {
-        smoothed_x += alpha * (sample.x - smoothed_x);
-        smoothed_y += alpha * (sample.y - smoothed_y);
-        smoothed.push(PreviewCursorPoint {
-            ts: sample.ts,
-            x: smoothed_x,
-            y: smoothed_y,
-        });
+    #[test]
+    fn filter_graph_uses_timestamp_camera_for_vfr_source() {
+        let project = sample_project();
+        let (cfr_graph, _, _) = build_export_filter_graph(
+            &project, None, 1920, 1080, 30, 10_000, 1920, 1080, None, false,
+        )
+        .expect("cfr filter graph");
+        let (vfr_graph, _, _) = build_export_filter_graph(
+            &project, None, 1920, 1080, 30, 10_000, 1920, 1080, None, true,
+        )
+        .expect("vfr filter graph");
+
+        assert!(cfr_graph.contains("gte(n,"));
+        assert!(!cfr_graph.contains("setpts=PTS-STARTPTS"));
+        assert!(vfr_graph.contains("setpts=PTS-STARTPTS,fps=30"));
+        assert!(vfr_graph.contains("gte(t,"));
+        assert!(!vfr_graph.contains("gte(n,"));
+        assert!(!vfr_graph.contains("(n-"));
     }
 
-    smoothed
-}
+    #[test]
+    fn segment_summary_clamps_center_like_export_camera() {
+        let centered = NormalizedRect {
+            x: 0.25,
+            y: 0.25,
+            width: 0.5,
+            height: 0.5,
+        };
+        let (zoom, center_x, center_y) = segment_rect_camera_summary(&centered, 1920.0, 1080.0);
+        assert!((zoom - 2.0).abs() < 0.0001);
+        assert!((center_x - 0.5).abs() < 0.0001);
+        assert!((center_y - 0.5).abs() < 0.0001);
+
+        let corner = NormalizedRect {
+            x: 0.0,
+            y: 0.0,
+            width: 0.2,
+            height: 0.2,
+        };
+        let (zoom, center_x, center_y) = segment_rect_camera_summary(&corner, 1920.0, 1080.0);
+        assert!((zoom - 5.0).abs() < 0.0001);
+        assert!((center_x - 0.1).abs() < 0.0001);
+        assert!((center_y - 0.1).abs() < 0.0001);
+    }
 
-fn interpolate_cursor_position(points: &[(u64, f64, f64)], ts: u64) -> (f64, f64) {
-    if points.is_empty() {
-        return (0.0, 0.0);
+    #[test]
+    fn cursor_interpolation_is_linear_between_points() {
+        let points = vec![(0, 0.0, 0.0), (100, 100.0, 50.0)];
+        let (x, y) = interpolate_cursor_position(&points, 50);
+        assert!((x - 50.0).abs() < 0.0001);
+        assert!((y - 25.0).abs() < 0.0001);
     }
-    if ts <= points[0].0 {
-        return (points[0].1, points[0].2);
+
+    #[test]
+    fn cursor_track_samples_position_clicks_and_visibility() {
+        let events = EventsFile {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            recording_id: "test".to_string(),
+            start_time_ms: 0,
+            screen_width: 1000,
+            screen_height: 500,
+            scale_factor: 1.0,
+            coordinate_space: None,
+            events: vec![
+                InputEvent::Move {
+                    ts: 0,
+                    x: 0.0,
+                    y: 250.0,
+                },
+                InputEvent::Click {
+                    ts: 500,
+                    x: 500.0,
+                    y: 250.0,
+                    button: crate::models::events::MouseButton::Left,
+                    ui_context: None,
+                },
+                InputEvent::Move {
+                    ts: 1_000,
+                    x: 1_000.0,
+                    y: 250.0,
+                },
+            ],
+        };
+        let mut cursor = CursorSettings {
+            render_mode: CursorRenderMode::Raw,
+            ..CursorSettings::default()
+        };
+        let track = CursorTrack::new(&events, &cursor, 2_000);
+
+        // Export draws the cursor `CURSOR_TIMING_OFFSET_MS` late; the editor matches it.
+        let at_click = track.sample(500 + CURSOR_TIMING_OFFSET_MS, &cursor);
+        assert!((at_click.x - 500.0).abs() < 1e-9);
+        assert!((at_click.normalized_x - 0.5).abs() < 1e-9);
+        assert!((at_click.normalized_y - 0.5).abs() < 1e-9);
+        assert!(at_click.visible && at_click.click_active);
+
+        let later = track.sample(800, &cursor);
+        assert!((later.normalized_x - (0.5 + 0.5 * 255.0 / 500.0)).abs() < 1e-9);
+        assert!(later.visible && !later.click_active);
+
+        cursor.hidden_ranges = vec![TimeRange {
+            start_ts: 700,
+            end_ts: 900,
+        }];
+        assert!(!track.sample(800, &cursor).visible);
     }
-    let last = points[points.len() - 1];
-    if ts >= last.0 {
-        return (last.1, last.2);
+
+    #[test]
+    fn cursor_mapping_matches_video_filter_pixel_math() {
+        // zoom=1.5003 scales 1920x1080 to 2880x1620 (truncated); overlay x=-701.4 becomes
+        // -701 and snaps to -702 on the chroma grid, y=-300.2 becomes -300.
+        let (zoom, offset_x, offset_y) = (1.5003, 701.4, 300.2);
+        let (source_x, source_y) = (1_000.0, 500.0);
+        // Layer pixel 1500x750 minus the overlay position.
+        let (camera_x, camera_y) = (1_500.0 - 702.0, 750.0 - 300.0);
+
+        let (x, y) = map_cursor_to_output_space(
+            source_x, source_y, zoom, offset_x, offset_y, 1920.0, 1080.0, 1280.0, 720.0,
+        );
+        assert!((x - camera_x * 1280.0 / 1920.0).abs() < 1e-9);
+        assert!((y - camera_y * 720.0 / 1080.0).abs() < 1e-9);
+
+        // Square output: `scale` fits 1080x608 (607.5 rounded), `pad` puts it at y=236.
+        let (x, y) = map_cursor_to_output_space(
+            source_x, source_y, zoom, offset_x, offset_y, 1920.0, 1080.0, 1080.0, 1080.0,
+        );
+        assert!((x - camera_x * 1080.0 / 1920.0).abs() < 1e-9);
+        assert!((y - (camera_y * 608.0 / 1080.0 + 236.0)).abs() < 1e-9);
     }
 
-    let mut low = 0usize;
-    let mut high = points.len() - 1;
-    while low <= high {
-        let mid = (low + high) / 2;
-        if points[mid].0 == ts {
-            return (points[mid].1, points[mid].2);
+    #[test]
+    fn overshooting_spring_never_uncovers_frame_edges() {
+        let mut project = sample_project();
+        // Barely damped springs zoom into a corner, then back out to the full frame at 2s:
+        // the zoom overshoots below 1 and the offsets swing past their bounds.
+        let corner = NormalizedRect {
+            x: 0.75,
+            y: 0.75,
+            width: 0.25,
+            height: 0.25,
+        };
+        let full = NormalizedRect {
+            x: 0.0,
+            y: 0.0,
+            width: 1.0,
+            height: 1.0,
+        };
+        project.timeline.zoom_segments = vec![
+            zoom_segment("in", 1_000, 2_000, corner),
+            zoom_segment("out", 2_000, 4_000, full),
+        ];
+        for segment in &mut project.timeline.zoom_segments {
+            segment.spring = CameraSpring {
+                mass: 1.0,
+                stiffness: 400.0,
+                damping: 2.0,
+            };
         }
-        if points[mid].0 < ts {
-            low = mid + 1;
-        } else if mid == 0 {
-            break;
-        } else {
-            high = mid - 1;
+        let (sw, sh) = (1920.0, 1080.0);
+        let fps = 60.0;
+        let states = build_camera_states(&project, 10_000, 10_000, 1920, 1080, fps);
+
+        let mut min_raw_zoom = f64::MAX;
+        for frame in 0..600 {
+            let frame = frame as f64;
+            let zoom = sample_camera_axis_value(&states, frame, fps, |s| s.zoom, 1.0);
+            let offset_x = sample_camera_axis_value(&states, frame, fps, |s| s.offset_x, 0.0);
+            let offset_y = sample_camera_axis_value(&states, frame, fps, |s| s.offset_y, 0.0);
+            min_raw_zoom = min_raw_zoom.min(zoom);
+
+            // Visible source window of the camera frame.
+            let crop = CameraCrop::new(zoom, offset_x, offset_y, sw, sh);
+            let (crop_x, crop_y) = (crop.left / crop.scale_x, crop.top / crop.scale_y);
+            let (crop_w, crop_h) = (sw / crop.scale_x, sh / crop.scale_y);
+            assert!(crop_x >= 0.0 && crop_y >= 0.0, "frame {frame}");
+            assert!(crop_x + crop_w <= sw + 1e-6, "frame {frame}");
+            assert!(crop_y + crop_h <= sh + 1e-6, "frame {frame}");
         }
-    }
+        assert!(
+            min_raw_zoom < 0.99,
+            "spring should overshoot, min={min_raw_zoom}"
+        );
 
-    let next = points[low.min(points.len() - 1)];
-    let prev = points[low.saturating_sub(1)];
-    let span = next.0.saturating_sub(prev.0);
-    if span == 0 {
-        return (prev.1, prev.2);
+        let (graph, _, _) = build_export_filter_graph(
+            &project, None, 1920, 1080, 60, 10_000, 1920, 1080, None, false,
+        )
+        .expect("filter graph");
+        assert!(graph.contains("[zoom]scale=w='iw*(max(1,"));
     }
-    let t = (ts.saturating_sub(prev.0)) as f64 / span as f64;
-    (
-        prev.1 + (next.1 - prev.1) * t,
-        prev.2 + (next.2 - prev.2) * t,
-    )
-}
 
-fn sample_camera_axis_value(
-    states: &[CameraState],
-    frame: f64,
-    source_fps: f64,
-    axis: impl Fn(&CameraState) -> AxisSpringSegment,
-    default_value: f64,
-) -> f64 {
-    let safe_fps = source_fps.max(1.0);
-    for state in states {
-        if frame < state.start_frame || frame >= state.end_frame {
-            continue;
-        }
-        let elapsed_seconds = ((frame - state.start_frame) / safe_fps).max(0.0);
-        let axis_state = axis(state);
-        let evaluated = evaluate_spring_axis(
-            AxisSpringState {
-                value: axis_state.start,
-                velocity: axis_state.velocity,
-            },
-            axis_state.target,
-            state.spring,
-            elapsed_seconds,
+    #[test]
+    fn scaled_display_events_map_to_physical_video_pixels() {
+        // 150% display: the video is 2880x1620 while the hook logged logical pixels.
+        let mut events = EventsFile {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            recording_id: "hidpi".to_string(),
+            start_time_ms: 0,
+            screen_width: 2880,
+            screen_height: 1620,
+            scale_factor: 1.5,
+            coordinate_space: Some(CoordinateSpace::Logical),
+            events: vec![InputEvent::Move {
+                ts: 0,
+                x: 960.0,
+                y: 540.0,
+            }],
+        };
+        events.normalize_to_physical();
+        let cursor = CursorSettings {
+            render_mode: CursorRenderMode::Raw,
+            ..CursorSettings::default()
+        };
+        let sample = CursorTrack::new(&events, &cursor, 1_000).sample(500, &cursor);
+        assert!((sample.x - 1440.0).abs() < 1e-9 && (sample.y - 810.0).abs() < 1e-9);
+
+        let (x, y) = map_cursor_to_output_space(
+            sample.normalized_x * 2880.0,
+            sample.normalized_y * 1620.0,
+            1.0,
+            0.0,
+            0.0,
+            2880.0,
+            1620.0,
+            1920.0,
+            1080.0,
         );
-        return evaluated.value;
+        assert!((x - 960.0).abs() < 1e-9 && (y - 540.0).abs() < 1e-9);
     }
-    default_value
-}
 
-fn map_cursor_to_output_space(
-    source_x: f64,
-    source_y: f64,
-    zoom: f64,
-    offset_x: f64,
-    offset_y: f64,
-    source_width: f64,
-    source_height: f64,
-    target_width: f64,
-    target_height: f64,
-) -> (f64, f64) {
-    let safe_zoom = zoom.max(1.0);
-    let scaled_width = source_width * safe_zoom;
-    let scaled_height = source_height * safe_zoom;
-    let max_offset_x = (scaled_width - source_width).max(0.0);
-    let max_offset_y = (scaled_height - source_height).max(0.0);
-    let clamped_offset_x = offset_x.clamp(0.0, max_offset_x);
-    let clamped_offset_y = offset_y.clamp(0.0, max_offset_y);
-
-    let camera_x = (source_x * safe_zoom - clamped_offset_x).clamp(0.0, source_width);
-    let camera_y = (source_y * safe_zoom - clamped_offset_y).clamp(0.0, source_height);
-
-    let fit_scale = (target_width / source_width)
-        .min(target_height / source_height)
-        .max(0.0001);
-    let fitted_width = source_width * fit_scale;
-    let fitted_height = source_height * fit_scale;
-    let pad_x = (target_width - fitted_width) * 0.5;
-    let pad_y = (target_height - fitted_height) * 0.5;
+    #[test]
+    fn music_bed_is_mixed_under_source_audio_or_used_alone() {
+        let music = MusicBed {
+            path: PathBuf::from("music.mp3"),
+            volume_db: -18.0,
+            loop_to_video: true,
+            fade_ms: 2_000,
+        };
 
-    (
-        (camera_x * fit_scale + pad_x).clamp(0.0, target_width),
-        (camera_y * fit_scale + pad_y).clamp(0.0, target_height),
-    )
-}
+        let mixed = build_music_filter(2, Some("0:a"), &music, 30_000);
+        assert_eq!(
+            mixed,
+            "[2:a]atrim=duration=30.000,asetpts=PTS-STARTPTS,volume=-18.00dB,\
+             afade=t=in:st=0:d=2.000,afade=t=out:st=28.000:d=2.000[music];\
+             [0:a][music]amix=inputs=2:duration=first:dropout_transition=0:normalize=0[aout]"
+        );
 
-fn probe_media_info(source_video: &Path) -> MediaProbe {
-    let ffmpeg = find_ffmpeg_exe();
-    let mut command = Command::new(ffmpeg);
-    apply_no_window_flags(&mut command);
+        // Fades never overlap on short videos, and a silent source takes the music as is.
+        let alone = build_music_filter(1, None, &music, 3_000);
+        assert!(alone.starts_with("[1:a]"));
+        assert!(alone.contains("afade=t=out:st=1.500:d=1.500"));
+        assert!(alone.ends_with("[aout]"));
+        assert!(!alone.contains("amix"));
+    }
 
-    let output = command
-        .arg("-i")
-        .arg(source_video)
-        .stdin(Stdio::null())
-        .stdout(Stdio::null())
-        .stderr(Stdio::piped())
-        .output()
-        .ok();
+    #[test]
+    fn slow_motion_windows_merge_and_stretch_the_output() {
+        let events = EventsFile {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            recording_id: "rec".to_string(),
+            start_time_ms: 0,
+            screen_width: 1920,
+            screen_height: 1080,
+            scale_factor: 1.0,
+            coordinate_space: None,
+            events: [1_000, 1_400, 5_000, 9_900]
+                .into_iter()
+                .map(|ts| InputEvent::Click {
+                    ts,
+                    x: 0.0,
+                    y: 0.0,
+                    button: crate::models::events::MouseButton::Left,
+                    ui_context: None,
+                })
+                .collect(),
+        };
+        let slow_motion = ClickSlowMotion {
+            window_ms: 600,
+            factor: 2.0,
+        };
+        // The click at 5 s is hidden and the one at 9.9 s is cut at the end of the video.
+        let hidden = [TimeRange {
+            start_ts: 4_500,
+            end_ts: 5_500,
+        }];
+
+        let remap = build_click_time_remap(&slow_motion, Some(&events), &hidden, 10_000, 10_000)
+            .expect("slow motion ranges");
+        assert_eq!(remap.ranges, vec![(700, 1_700), (9_600, 10_000)]);
+        assert_eq!(remap.output_duration_ms(10_000), 11_400);
+        // Chapter marks move by the slowed time before them.
+        assert_eq!(remap.output_ms(500), 500);
+        assert_eq!(remap.output_ms(1_200), 1_700);
+        assert_eq!(remap.output_ms(5_000), 6_000);
+        assert_eq!(remap.output_ms(10_000), 11_400);
+        assert_eq!(
+            map_ffmetadata_chapters("[CHAPTER]\nSTART=5000\nEND=10000", |ms| remap.output_ms(ms)),
+            "[CHAPTER]\nSTART=6000\nEND=11400\n"
+        );
+        assert_eq!(
+            remap.pieces(10_000),
+            vec![
+                (0, 700, false),
+                (700, 1_700, true),
+                (1_700, 9_600, false),
+                (9_600, 10_000, true),
+            ]
+        );
 
-    let Some(output) = output else {
-        return MediaProbe::default();
-    };
+        let video = remap.video_filter("vcomposed", "vout", 10_000, 30);
+        assert!(video.starts_with("[vcomposed]split=4[vs0][vs1][vs2][vs3];"));
+        assert!(video.contains("[vs1]trim=start=0.700:end=1.700,setpts=2.0000*(PTS-STARTPTS)[vp1]"));
+        assert!(video.ends_with("[vp0][vp1][vp2][vp3]concat=n=4:v=1:a=0,fps=30[vout]"));
 
-    let text = String::from_utf8_lossy(&output.stderr);
-    let mut probe = MediaProbe::default();
+        let audio = remap.audio_filter("0:a", "aslow", 10_000);
+        assert!(audio
+            .contains("[as1]atrim=start=0.700:end=1.700,asetpts=PTS-STARTPTS,atempo=0.5000[ap1]"));
+        assert!(audio.ends_with("concat=n=4:v=0:a=1[aslow]"));
 
-    for line in text.lines() {
-        if probe.duration_ms.is_none() {
-            probe.duration_ms = extract_ffmpeg_duration_ms(line);
-        }
-        if probe.width.is_none() || probe.height.is_none() {
-            if let Some((w, h)) = extract_ffmpeg_video_size(line) {
-                probe.width = Some(w);
-                probe.height = Some(h);
-            }
-        }
-        if probe.duration_ms.is_some() && probe.width.is_some() && probe.height.is_some() {
-            break;
-        }
+        let disabled = ClickSlowMotion {
+            window_ms: 600,
+            factor: 1.0,
+        };
+        assert!(build_click_time_remap(&disabled, Some(&events), &[], 10_000, 10_000).is_none());
     }
 
-    probe
-}
-
-fn extract_ffmpeg_duration_ms(line: &str) -> Option<u64> {
-    let marker = "Duration: ";
-    let start = line.find(marker)? + marker.len();
-    let value = line[start..].split(',').next()?.trim();
-    parse_hhmmss_ms(value)
-}
+    #[test]
+    fn loop_blend_moves_the_start_over_the_end() {
+        let blend = LoopBlend::from_args(Some(true), Some(800)).expect("loop blend");
+        assert_eq!(blend.crossfade_ms_for(10_000), Some(800));
+        // Capped at a quarter of the clip, and skipped when that is too short to see.
+        assert_eq!(blend.crossfade_ms_for(2_000), Some(500));
+        assert_eq!(blend.crossfade_ms_for(300), None);
+        assert!(LoopBlend::from_args(None, Some(800)).is_none());
+        assert_eq!(
+            LoopBlend::from_args(Some(true), None).map(|blend| blend.crossfade_ms),
+            Some(DEFAULT_LOOP_CROSSFADE_MS)
+        );
 
-fn extract_ffmpeg_progress_time_ms(line: &str) -> Option<u64> {
-    if let Some(raw) = line.strip_prefix("out_time=") {
-        return parse_hhmmss_ms(raw.trim());
+        assert_eq!(
+            loop_video_filter("vunlooped", "vout", 10_000, 800),
+            "[vunlooped]split=2[lpv0][lpv1];\
+             [lpv0]trim=start=0.800,setpts=PTS-STARTPTS[lpbody];\
+             [lpv1]trim=end=0.800,setpts=PTS-STARTPTS[lphead];\
+             [lpbody][lphead]xfade=transition=fade:duration=0.800:offset=8.400[vout]"
+        );
+        assert!(loop_audio_filter("0:a", "aloop", 800)
+            .ends_with("[lpabody][lpahead]acrossfade=d=0.800[aloop]"));
     }
 
-    if let Some(raw) = line.strip_prefix("out_time_us=") {
-        let micros = raw.trim().parse::<u64>().ok()?;
-        return Some(micros / 1_000);
+    #[test]
+    fn passthrough_only_when_export_would_match_the_source() {
+        let mut project = sample_project();
+        project.settings.cursor.show_cursor = false;
+        let output = Path::new("out.mp4");
+        let check = |project: &Project, codec: &str, output: &Path, fps: Option<f64>| {
+            passthrough_blocker(
+                project,
+                None,
+                false,
+                false,
+                (1920, 1080, 60),
+                codec,
+                output,
+                (1920, 1080),
+                fps,
+                false,
+            )
+        };
+
+        assert_eq!(
+            check(&project, "h264", output, Some(60.0)),
+            Some("zoom segments")
+        );
+
+        // A segment that keeps the full frame is a no-op.
+        project.timeline.zoom_segments[0].initial_rect = NormalizedRect {
+            x: 0.0,
+            y: 0.0,
+            width: 1.0,
+            height: 1.0,
+        };
+        assert_eq!(check(&project, "h264", output, Some(60.0)), None);
+        assert_eq!(check(&project, "h264", output, Some(59.94)), None);
+        project.timeline.zoom_segments[0].label = Some(SegmentLabel {
+            text: "Intro".to_string(),
+            position: LabelPosition::Auto,
+            font_scale: 0.045,
+            color: "#FFFFFF".to_string(),
+            background_opacity: 0.6,
+        });
+        assert_eq!(
+            check(&project, "h264", output, Some(60.0)),
+            Some("segment labels")
+        );
+        project.timeline.zoom_segments[0].label = None;
+        assert_eq!(
+            check(&project, "h264", output, Some(30.0)),
+            Some("frame rate change")
+        );
+        assert_eq!(
+            check(&project, "vp9", output, Some(60.0)),
+            Some("codec change")
+        );
+        assert_eq!(
+            check(&project, "h264", Path::new("out.webm"), Some(60.0)),
+            Some("output container")
+        );
+        assert_eq!(
+            passthrough_blocker(
+                &project,
+                None,
+                false,
+                true,
+                (1920, 1080, 60),
+                "h264",
+                output,
+                (1920, 1080),
+                Some(60.0),
+                false,
+            ),
+            Some("audio edits")
+        );
+        assert_eq!(
+            passthrough_blocker(
+                &project,
+                None,
+                false,
+                false,
+                (1280, 720, 60),
+                "h264",
+                output,
+                (1920, 1080),
+                Some(60.0),
+                false,
+            ),
+            Some("resolution change")
+        );
     }
 
-    if let Some(raw) = line.strip_prefix("out_time_ms=") {
-        let value = raw.trim().parse::<u64>().ok()?;
-        // ffmpeg progress often reports out_time_ms in microseconds.
-        return Some(value / 1_000);
+    #[test]
+    fn export_verification_flags_short_or_starved_outputs() {
+        let path = Path::new("out.mp4");
+        // 10 s at ~4 Mbps, within a frame of the expected length.
+        let sound = check_export_output(path, 5_000_000, Some(10_040), 10_000);
+        assert!(sound.issues.is_empty());
+        assert_eq!(sound.bitrate_kbps.map(f64::round), Some(3_984.0));
+
+        let truncated = check_export_output(path, 2_000_000, Some(4_000), 10_000);
+        assert_eq!(truncated.issues.len(), 1);
+        assert!(truncated.issues[0].contains("differs from the expected 10.0s"));
+
+        // Long exports get a proportional tolerance.
+        assert!(
+            check_export_output(path, 300_000_000, Some(601_500), 600_000)
+                .issues
+                .is_empty()
+        );
+
+        let starved = check_export_output(path, 20_000, Some(10_000), 10_000);
+        assert_eq!(starved.issues.len(), 1);
+        assert!(starved.issues[0].contains("implausibly low"));
+
+        let unknown = check_export_output(path, 20_000, None, 10_000);
+        assert_eq!(unknown.bitrate_kbps, None);
+        assert_eq!(
+            unknown.issues,
+            vec!["Exported file reports no duration".to_string()]
+        );
     }
 
-    extract_ffmpeg_time_ms(line)
-}
+    #[test]
+    fn audio_copy_only_for_unedited_codecs_the_container_holds() {
+        let line = "  Stream #0:1[0x2](und): Audio: aac (LC) (mp4a / 0x6134706D), 48000 Hz, stereo, fltp, 192 kb/s (default)";
+        let codec = extract_ffmpeg_audio_codec(line);
+        assert_eq!(codec.as_deref(), Some("aac"));
+        assert_eq!(
+            extract_ffmpeg_audio_codec("  Stream #0:1: Audio: opus, 48000 Hz, stereo, fltp")
+                .as_deref(),
+            Some("opus")
+        );
 
-fn extract_ffmpeg_progress_frame(line: &str) -> Option<u64> {
-    let raw = line.strip_prefix("frame=")?;
-    raw.trim().parse::<u64>().ok()
-}
+        let mp4 = Path::new("out.mp4");
+        assert_eq!(audio_copy_blocker(Some("aac"), mp4, false), None);
+        assert_eq!(
+            audio_copy_blocker(Some("aac"), Path::new("out.MKV"), false),
+            None
+        );
+        assert_eq!(
+            audio_copy_blocker(Some("aac"), mp4, true),
+            Some("audio edits")
+        );
+        assert_eq!(
+            audio_copy_blocker(None, mp4, false),
+            Some("unknown source audio codec")
+        );
+        assert_eq!(
+            audio_copy_blocker(Some("aac"), Path::new("out.webm"), false),
+            Some("audio codec not supported by the container")
+        );
+        assert_eq!(
+            audio_copy_blocker(Some("opus"), Path::new("out.webm"), false),
+            None
+        );
+        assert_eq!(
+            audio_copy_blocker(Some("pcm_s16le"), mp4, false),
+            Some("audio codec not supported by the container")
+        );
+        assert_eq!(
+            audio_copy_blocker(Some("aac"), Path::new("out.avi"), false),
+            Some("output container")
+        );
+    }
 
-fn extract_ffmpeg_status_frame(line: &str) -> Option<u64> {
-    let marker = "frame=";
-    let start = line.find(marker)? + marker.len();
-    let mut chars = line[start..].chars().peekable();
-    while matches!(chars.peek(), Some(ch) if ch.is_whitespace()) {
-        chars.next();
+    #[test]
+    fn renditions_split_the_rendered_video_and_filtered_audio() {
+        let spec = |label: &str, width: u32, height: u32| RenditionSpec {
+            label: label.to_string(),
+            width,
+            height,
+            crf: None,
+            max_bitrate_kbps: Some(2_500),
+        };
+        let outputs = rendition_outputs(
+            Path::new("out/demo.mp4"),
+            &[spec("share", 1280, 720), spec("archive", 2560, 1440)],
+        )
+        .expect("renditions");
+        assert_eq!(outputs[0].path, Path::new("out/demo-archive.mp4"));
+        assert_eq!((outputs[0].width, outputs[0].height), (2560, 1440));
+        assert_eq!(outputs[1].path, Path::new("out/demo-share.mp4"));
+        assert!(rendition_outputs(Path::new("demo.mp4"), &[spec("a/b", 1280, 720)]).is_err());
+        assert!(rendition_outputs(
+            Path::new("demo.mp4"),
+            &[spec("x", 1280, 720), spec("x", 640, 360)]
+        )
+        .is_err());
+
+        let (filter, maps) = build_rendition_split(&outputs, "[aout]", &FrameBackground::None, 30);
+        assert_eq!(
+            filter,
+            ";[vout]split=2[vr0][vr1];\
+             [vr1]scale=1280:720:force_original_aspect_ratio=decrease,pad=1280:720:(ow-iw)/2:(oh-ih)/2:black[vrs1];\
+             [aout]asplit=2[ar0][ar1]"
+        );
+        assert_eq!(
+            maps,
+            [
+                ("[vr0]".to_string(), "[ar0]".to_string()),
+                ("[vrs1]".to_string(), "[ar1]".to_string()),
+            ]
+        );
+
+        // Input streams can be mapped repeatedly, and a single output needs no split.
+        // Smaller renditions fill their margins like the main output.
+        let solid = FrameBackground::Solid {
+            color: "#1A1A2E".to_string(),
+        };
+        let (filter, _) = build_rendition_split(&outputs, "0:a?", &solid, 30);
+        assert!(filter.contains("pad=1280:720:(ow-iw)/2:(oh-ih)/2:0x1A1A2E[vrs1]"));
+
+        let (_, maps) = build_rendition_split(&outputs, "0:a?", &FrameBackground::None, 30);
+        assert!(maps.iter().all(|(_, audio)| audio == "0:a?"));
+        let (filter, maps) =
+            build_rendition_split(&outputs[..1], "0:a?", &FrameBackground::None, 30);
+        assert!(filter.is_empty());
+        assert_eq!(maps, [("[vout]".to_string(), "0:a?".to_string())]);
     }
 
-    let mut digits = String::new();
-    while matches!(chars.peek(), Some(ch) if ch.is_ascii_digit()) {
-        digits.push(chars.next()?);
-    }
-    if digits.is_empty() {
-        return None;
+    #[test]
+    fn export_threads_are_capped_by_core_count() {
+        assert_eq!(validate_export_threads(None), Ok(None));
+        assert_eq!(validate_export_threads(Some(0)), Ok(None));
+        assert_eq!(validate_export_threads(Some(1)), Ok(Some(1)));
+        assert!(validate_export_threads(Some(u32::MAX)).is_err());
     }
-    digits.parse::<u64>().ok()
-}
-
-fn parse_ffmpeg_progress_snapshot(snapshot: &str) -> (Option<u64>, Option<u64>, bool) {
-    let mut last_time_ms = None;
-    let mut last_frame = None;
-    let mut ended = false;
-
-    for raw in snapshot.lines() {
-        let line = raw.trim();
-        if line.is_empty() {
-            continue;
-        }
 
-        if let Some(time_ms) = extract_ffmpeg_progress_time_ms(line) {
-            last_time_ms = Some(time_ms);
-            continue;
-        }
-        if let Some(frame) = extract_ffmpeg_progress_frame(line) {
-            last_frame = Some(frame);
-            continue;
-        }
-        if line == "progress=end" {
-            ended = true;
+    #[test]
+    fn pixel_format_is_validated_against_codec() {
+        let resolve = ExportColorFormat::resolve;
+        assert_eq!(
+            resolve("h264", None, None),
+            Ok(ExportColorFormat::default())
+        );
+        assert_eq!(
+            resolve("h264", Some("YUV420P"), Some("tv")),
+            Ok(ExportColorFormat::default())
+        );
+        assert!(resolve("h264", Some("yuv444p"), None).is_err());
+        assert!(resolve("h264", Some("yuv420p10le"), None).is_err());
+        assert!(resolve("h265", Some("rgb24"), None).is_err());
+        assert!(resolve("vp9", None, Some("studio")).is_err());
+
+        for codec in ["h265", "vp9"] {
+            for pix_fmt in HIGH_FIDELITY_PIX_FMTS {
+                let format = resolve(codec, Some(pix_fmt), None).expect("supported pair");
+                assert_eq!(format.pix_fmt, pix_fmt);
+                assert!(!format.full_range);
+            }
         }
-    }
-
-    (last_time_ms, last_frame, ended)
-}
-
-fn extract_ffmpeg_time_ms(line: &str) -> Option<u64> {
-    let marker = "time=";
-    let start = line.find(marker)? + marker.len();
-    let value = line[start..].split_whitespace().next()?;
-    parse_hhmmss_ms(value)
-}
 
-fn extract_ffmpeg_video_size(line: &str) -> Option<(u32, u32)> {
-    if !line.contains(" Video: ") {
-        return None;
+        // Full range on the default pixel format still converts and tags the output.
+        let full = resolve("h264", None, Some("pc")).expect("full range");
+        assert!(full.full_range);
+        assert_eq!(
+            full.video_filter().as_deref(),
+            Some("scale=out_color_matrix=bt709:out_range=full,format=yuv420p")
+        );
+        assert_eq!(ExportColorFormat::default().video_filter(), None);
     }
 
-    for token in line.split(|c: char| c.is_whitespace() || c == ',' || c == '[' || c == ']') {
-        let Some((raw_w, raw_h)) = token.split_once('x') else {
-            continue;
+    #[test]
+    fn export_crop_redefines_the_source_frame() {
+        let full = NormalizedRect {
+            x: 0.0,
+            y: 0.0,
+            width: 1.0,
+            height: 1.0,
         };
+        assert_eq!(SourceCrop::from_rect(&full, 1920, 1080), None);
+
+        let crop = SourceCrop::from_rect(
+            &NormalizedRect {
+                x: 0.25,
+                y: 0.25,
+                width: 0.5,
+                height: 0.5,
+            },
+            1920,
+            1080,
+        )
+        .expect("crop");
+        assert_eq!(
+            (crop.x, crop.y, crop.width, crop.height),
+            (480, 270, 960, 540)
+        );
 
-        let width_text = raw_w.trim_matches(|c: char| !c.is_ascii_digit());
-        let height_text = raw_h.trim_matches(|c: char| !c.is_ascii_digit());
-        if width_text.is_empty() || height_text.is_empty() {
-            continue;
-        }
+        let graph = build_export_filter_graph(
+            &sample_project(),
+            None,
+            1920,
+            1080,
+            30,
+            10_000,
+            crop.width,
+            crop.height,
+            Some(crop),
+            false,
+        )
+        .expect("filter graph")
+        .0;
+        assert!(graph.starts_with("crop=960:540:480:270,fps=30,"));
 
-        let width = match width_text.parse::<u32>() {
-            Ok(value) => value,
-            Err(_) => continue,
-        };
-        let height = match height_text.parse::<u32>() {
-            Ok(value) => value,
-            Err(_) => continue,
+        // A segment over the crop's top-left quarter stays there in cropped space.
+        let mut project = sample_project();
+        project.timeline.zoom_segments[0].initial_rect = NormalizedRect {
+            x: 0.25,
+            y: 0.25,
+            width: 0.25,
+            height: 0.25,
         };
-
-        if width >= 64 && height >= 64 {
-            return Some((width, height));
+        let project = crop.project_into(project);
+        let rect = &project.timeline.zoom_segments[0].initial_rect;
+        assert!(rect.x.abs() < 1e-9 && rect.y.abs() < 1e-9);
+        assert!((rect.width - 0.5).abs() < 1e-9 && (rect.height - 0.5).abs() < 1e-9);
+
+        let events = crop.events_into(EventsFile {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            recording_id: "test".to_string(),
+            start_time_ms: 0,
+            screen_width: 1920,
+            screen_height: 1080,
+            scale_factor: 1.0,
+            coordinate_space: None,
+            events: vec![InputEvent::Move {
+                ts: 0,
+                x: 960.0,
+                y: 540.0,
+            }],
+        });
+        match &events.events[0] {
+            InputEvent::Move { x, y, .. } => {
+                assert!((x - 960.0).abs() < 1e-9 && (y - 540.0).abs() < 1e-9);
+            }
+            other => panic!("unexpected event {other:?}"),
         }
     }
 
-    None
-}
-
-#[cfg(test)]
-fn extract_ffmpeg_fps(line: &str) -> Option<f64> {
-    if !line.contains(" Video: ") || !line.contains(" fps") {
-        return None;
-    }
-
-    for chunk in line.split(',') {
-        let trimmed = chunk.trim();
-        if let Some(value) = trimmed.strip_suffix(" fps") {
-            if let Ok(parsed) = value.trim().parse::<f64>() {
-                if (1.0..=240.0).contains(&parsed) {
-                    return Some(parsed);
+    #[test]
+    fn malformed_project_json_is_clamped_on_load() {
+        let raw = r##"{
+            "schemaVersion": 1,
+            "id": "p",
+            "name": "broken",
+            "createdAt": 0,
+            "videoPath": "raw.mp4",
+            "eventsPath": "events.json",
+            "durationMs": 0,
+            "videoWidth": 1920,
+            "videoHeight": 1080,
+            "timeline": { "zoomSegments": [
+                {
+                    "id": "outside",
+                    "startTs": 1000,
+                    "endTs": 4000,
+                    "initialRect": { "x": 0.9, "y": -0.5, "width": 0.5, "height": 0.0 },
+                    "targetPoints": [
+                        { "ts": 9000, "rect": { "x": 0.1, "y": 0.1, "width": 2.0, "height": 0.5 } }
+                    ]
+                },
+                {
+                    "id": "reversed",
+                    "startTs": 3000,
+                    "endTs": 2000,
+                    "initialRect": { "x": 0.0, "y": 0.0, "width": 0.5, "height": 0.5 }
                 }
+            ] },
+            "settings": {
+                "cursor": {
+                    "size": 1.0,
+                    "color": "#FFFFFF",
+                    "smoothingFactor": 0.8,
+                    "hiddenRanges": [{ "startTs": 5000, "endTs": 6000 }]
+                },
+                "background": { "type": "solid", "color": "#000000" },
+                "export": { "width": 1920, "height": 1080, "fps": 60, "codec": "h264" }
             }
-        }
+        }"##;
+        let mut project: Project = serde_json::from_str(raw).unwrap();
+
+        let corrections = sanitize_project(&mut project);
+        assert!(!corrections.is_empty());
+        assert_eq!(project.duration_ms, 4_000);
+
+        let segments = &project.timeline.zoom_segments;
+        assert_eq!(segments.len(), 1);
+        let rect = &segments[0].initial_rect;
+        assert!((rect.x - 0.5).abs() < 1e-9);
+        assert_eq!(rect.y, 0.0);
+        assert_eq!(rect.width, 0.5);
+        assert_eq!(rect.height, 0.001);
+        let point = &segments[0].target_points[0];
+        assert_eq!(point.ts, 4_000);
+        assert_eq!(point.rect.width, 1.0);
+        assert_eq!(point.rect.x, 0.0);
+        // The hidden range lies past the end of the recording.
+        assert!(project.settings.cursor.hidden_ranges.is_empty());
+
+        // A valid project is left untouched.
+        assert!(sanitize_project(&mut project).is_empty());
     }
 
-    None
-}
-
-fn parse_hhmmss_ms(value: &str) -> Option<u64> {
-    let mut parts = value.split(':');
-    let hours = parts.next()?.parse::<u64>().ok()?;
-    let minutes = parts.next()?.parse::<u64>().ok()?;
-    let sec_part = parts.next()?;
-    if parts.next().is_some() {
-        return None;
+    #[test]
+    fn atempo_is_chained_below_half_speed() {
+        assert_eq!(atempo_chain(0.5), "atempo=0.5000");
+        assert_eq!(atempo_chain(0.25), "atempo=0.5,atempo=0.5000");
+        assert_eq!(atempo_chain(1.0 / 3.0), "atempo=0.5,atempo=0.6667");
     }
 
-    let mut sec_split = sec_part.split('.');
-    let seconds = sec_split.next()?.parse::<u64>().ok()?;
-    let frac = sec_split.next().unwrap_or("0");
-    let frac_trimmed = &frac[..frac.len().min(3)];
-    let millis = format!("{:0<3}", frac_trimmed).parse::<u64>().ok()?;
-
-    Some(hours * 3_600_000 + minutes * 60_000 + seconds * 1_000 + millis)
-}
-
-fn now_ms() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::project::{
-        Background, CameraSpring, CursorSettings, ExportSettings, NormalizedRect, ProjectSettings,
-        Timeline, ZoomMode, ZoomSegment, ZoomTrigger,
-    };
-
-    fn sample_project() -> Project {
-        Project {
-            schema_version: SCHEMA_VERSION,
-            id: "test-project".to_string(),
-            name: "Test".to_string(),
-            created_at: 0,
-            video_path: "raw.mp4".to_string(),
-            proxy_video_path: None,
-            events_path: "events.json".to_string(),
-            duration_ms: 10_000,
-            video_width: 1920,
-            video_height: 1080,
-            timeline: Timeline {
-                zoom_segments: vec![ZoomSegment {
-                    id: "z1".to_string(),
-                    start_ts: 1_000,
-                    end_ts: 2_000,
-                    initial_rect: NormalizedRect {
-                        x: 0.4,
-                        y: 0.3,
-                        width: 0.2,
-                        height: 0.2,
-                    },
-                    target_points: vec![],
-                    spring: CameraSpring {
-                        mass: 1.0,
-                        stiffness: 170.0,
-                        damping: 26.0,
-                    },
-                    pan_trajectory: vec![],
-                    legacy_easing: None,
-                    mode: ZoomMode::Fixed,
-                    trigger: ZoomTrigger::AutoClick,
-                    is_auto: true,
-                }],
-            },
-            settings: ProjectSettings {
-                cursor: CursorSettings::default(),
-                background: Background::default(),
-                export: ExportSettings::default(),
-            },
-        }
+    #[test]
+    fn benchmark_reports_rates_and_the_slowest_stage() {
+        let report = ExportBenchmark::new(
+            (10_000, 1920, 1080, 30),
+            "h264".to_string(),
+            (40, 160, 4_800),
+            300,
+        );
+        assert_eq!(report.dominant_stage, ExportStage::Encode);
+        assert!((report.encode_fps - 62.5).abs() < 1e-9);
+        assert!((report.realtime_factor - 2.0).abs() < 1e-9);
+
+        let report = ExportBenchmark::new(
+            (1_000, 1280, 720, 30),
+            "h264".to_string(),
+            (50, 3_000, 950),
+            30,
+        );
+        assert_eq!(report.dominant_stage, ExportStage::FilterGraph);
+        assert!((report.realtime_factor - 0.25).abs() < 1e-9);
     }
 
-    fn zoom_segment(id: &str, start_ts: u64, end_ts: u64, rect: NormalizedRect) -> ZoomSegment {
-        ZoomSegment {
-            id: id.to_string(),
-            start_ts,
-            end_ts,
-            initial_rect: rect,
-            target_points: vec![],
-            spring: CameraSpring {
-                mass: 1.0,
-                stiffness: 170.0,
-                damping: 26.0,
-            },
-            pan_trajectory: vec![],
-            legacy_easing: None,
-            mode: ZoomMode::Fixed,
-            trigger: ZoomTrigger::AutoClick,
-            is_auto: true,
-        }
+    #[test]
+    fn metadata_tags_come_from_the_project_and_overrides() {
+        let mut project = sample_project();
+        project.name = "Demo: a=b\nline two".to_string();
+        project.description = Some("  ".to_string());
+        project.created_at = 1_700_000_000_000;
+
+        let tags = export_metadata_tags(&project, None, None);
+        assert_eq!(tags[0], ("title", "Demo: a=b line two".to_string()));
+        assert_eq!(
+            tags[1],
+            ("creation_time", "2023-11-14T22:13:20.000Z".to_string())
+        );
+        assert_eq!(tags[2].0, "comment");
+        assert!(tags[2].1.starts_with("NeuroScreenCaster "));
+        assert_eq!(tags.len(), 3);
+
+        let tags = export_metadata_tags(&project, Some("Release notes"), Some(" \t "));
+        assert_eq!(tags[0], ("title", "Release notes".to_string()));
+        assert!(tags[2].1.starts_with("NeuroScreenCaster "));
     }
 
     #[test]
-    fn filter_graph_uses_dynamic_zoom_pipeline() {
-        let project = sample_project();
-        let (graph, cursor_file, temp_file) =
-            build_export_filter_graph(&project, None, 1920, 1080, 30, 10_000, 1920, 1080)
-                .expect("filter graph");
+    fn title_cards_are_letterboxed_and_shift_chapters() {
+        let card = TitleCard::from_args(Some(" intro.png ".to_string()), Some(60_000)).unwrap();
+        assert_eq!(card.path, PathBuf::from("intro.png"));
+        assert_eq!(card.duration_ms, MAX_TITLE_CARD_MS);
+        assert!(TitleCard::from_args(Some("  ".to_string()), None).is_none());
+
+        let output = ExportOutput {
+            path: PathBuf::from("out/export.mp4"),
+            width: 1920,
+            height: 1080,
+            crf: None,
+            max_bitrate_kbps: None,
+        };
+        assert_eq!(
+            title_card_video_filter(&output, 30, ExportColorFormat::default()),
+            "scale=1920:1080:force_original_aspect_ratio=decrease,pad=1920:1080:(ow-iw)/2:(oh-ih)/2:black,setsar=1,fps=30,format=yuv420p"
+        );
+        assert_eq!(
+            title_card_part_path(&output.path, "intro"),
+            PathBuf::from("out/.export.intro.mp4")
+        );
+        assert_eq!(
+            build_concat_list(&[PathBuf::from("a.mp4"), PathBuf::from("it's.mp4")]),
+            "file 'a.mp4'\nfile 'it'\\''s.mp4'\n"
+        );
 
-        assert!(cursor_file.is_none());
-        assert!(temp_file.is_none());
-        assert!(graph.contains("split=2[base][zoom]"));
-        assert!(graph.contains("scale=w='iw*("));
-        assert!(graph.contains("exp("));
-        assert!(graph.contains("eval=frame"));
-        assert!(graph.contains("[base][scaled]overlay=x='-max(0,min("));
-        assert!(graph.contains("overlay_h-main_h"));
-        assert!(graph.contains("fps=30"));
+        let metadata =
+            ";FFMETADATA1\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=4000\ntitle=END=1\n";
+        assert_eq!(
+            shift_ffmetadata_chapters(metadata, 3_000),
+            ";FFMETADATA1\n\n[CHAPTER]\nTIMEBASE=1/1000\nSTART=3000\nEND=7000\ntitle=END=1\n"
+        );
     }
 
     #[test]
-    fn camera_returns_to_fullscreen_between_separated_segments() {
-        let mut project = sample_project();
-        project.timeline.zoom_segments = vec![
-            zoom_segment(
-                "z1",
-                1_000,
-                2_000,
-                NormalizedRect {
-                    x: 0.4,
-                    y: 0.3,
-                    width: 0.2,
-                    height: 0.2,
-                },
-            ),
-            zoom_segment(
-                "z2",
-                4_000,
-                5_000,
-                NormalizedRect {
-                    x: 0.2,
-                    y: 0.2,
-                    width: 0.25,
-                    height: 0.25,
-                },
+    fn still_filter_freezes_the_camera_into_a_crop() {
+        let source_crop = SourceCrop::from_rect(
+            &NormalizedRect {
+                x: 0.25,
+                y: 0.25,
+                width: 0.5,
+                height: 0.5,
+            },
+            1920,
+            1080,
+        );
+        let camera = CameraCrop::new(2.0, 600.0, 300.0, 960.0, 540.0);
+        assert_eq!(
+            build_still_filter(
+                source_crop,
+                camera,
+                (960, 540),
+                (1280, 720),
+                &FrameBackground::None,
+                None
             ),
-        ];
+            "crop=960:540:480:270,scale=1920:1080:flags=lanczos,crop=960:540:600:300,\
+             scale=1280:720:force_original_aspect_ratio=decrease:flags=lanczos,\
+             pad=1280:720:(ow-iw)/2:(oh-ih)/2:black"
+        );
 
-        let states = build_camera_states(&project, 10_000, 10_000, 1_920, 1_080, 30.0);
-        let gap_state = states
-            .iter()
-            .find(|state| state.start_frame >= 60.0 - 0.01 && state.start_frame <= 60.0 + 0.01)
-            .expect("expected camera state at first segment end");
+        // At 1x the camera stage is dropped entirely.
+        let identity = CameraCrop::new(1.0, 0.0, 0.0, 1920.0, 1080.0);
+        let filter = build_still_filter(
+            None,
+            identity,
+            (1920, 1080),
+            (1920, 1080),
+            &FrameBackground::None,
+            None,
+        );
+        assert!(filter.starts_with("scale=1920:1080:force_original_aspect_ratio=decrease"));
 
-        assert!((gap_state.zoom.target - 1.0).abs() < 0.0001);
-        assert!(gap_state.offset_x.target.abs() < 0.0001);
-        assert!(gap_state.offset_y.target.abs() < 0.0001);
+        assert_eq!(
+            StillFormat::from_path(Path::new("shot.JPG")),
+            Ok(StillFormat::Jpeg)
+        );
+        assert!(StillFormat::from_path(Path::new("shot.gif")).is_err());
     }
 
     #[test]
-    fn ffmpeg_video_size_parser_handles_common_line() {
-        let line = "  Stream #0:0: Video: h264, yuv420p(progressive), 1920x1080, 30 fps";
-        assert_eq!(extract_ffmpeg_video_size(line), Some((1920, 1080)));
+    fn vector_cursor_uses_the_selected_shape_and_colors() {
+        let mut project = sample_project();
+        project.settings.cursor.shape = CursorShape::Crosshair;
+        project.settings.cursor.fill_color = "#FF8000".to_string();
+        project.settings.cursor.outline_color = "#00FF00".to_string();
+        project.settings.cursor.outline_scale = 0.0;
+        let events = EventsFile {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            recording_id: "test".to_string(),
+            start_time_ms: 0,
+            screen_width: 1920,
+            screen_height: 1080,
+            scale_factor: 1.0,
+            coordinate_space: None,
+            events: vec![InputEvent::Move {
+                ts: 0,
+                x: 960.0,
+                y: 540.0,
+            }],
+        };
+
+        let ass = build_still_cursor_ass_file(
+            &project,
+            &events,
+            500,
+            1_000,
+            (1.0, 0.0, 0.0),
+            (1920, 1080),
+            (1920, 1080),
+        )
+        .expect("cursor script")
+        .expect("visible cursor");
+        let script = std::fs::read_to_string(&ass).expect("cursor script");
+        let _ = std::fs::remove_file(&ass);
+
+        assert!(script.contains("Style: Cursor,Arial,12,&H000080FF,&H000080FF,&H0000FF00,"));
+        let dialogue = script
+            .lines()
+            .find(|line| line.starts_with("Dialogue:"))
+            .expect("cursor event");
+        assert!(dialogue.ends_with(VECTOR_CURSOR_CROSSHAIR_PATH));
+        assert!(dialogue.contains("\\bord0.00"));
+        // The crosshair's centre, not its corner, lands on the cursor.
+        assert!(dialogue.contains("\\pos(946,526)"));
+
+        project.settings.cursor = CursorSettings::default();
+        let ass = build_still_cursor_ass_file(
+            &project,
+            &events,
+            500,
+            1_000,
+            (1.0, 0.0, 0.0),
+            (1920, 1080),
+            (1920, 1080),
+        )
+        .expect("cursor script")
+        .expect("visible cursor");
+        let script = std::fs::read_to_string(&ass).expect("cursor script");
+        let _ = std::fs::remove_file(&ass);
+        assert!(script.contains("Style: Cursor,Arial,12,&H00000000,&H00000000,&H00FFFFFF,"));
+        assert!(script.contains("\\pos(960,540)"));
+        assert!(script.trim_end().ends_with(VECTOR_CURSOR_ARROW_PATH));
     }
 
     #[test]
-    fn ffmpeg_fps_parser_handles_common_line() {
-        let line = "  Stream #0:0: Video: h264, yuv420p(progressive), 1920x1080, 29.97 fps, 30 tbr";
-        let fps = extract_ffmpeg_fps(line).expect("fps");
-        assert!((fps - 29.97).abs() < 0.0001);
+    fn cursor_shadow_is_a_blurred_layer_under_the_cursor() {
+        let mut project = sample_project();
+        project.settings.cursor.shadow = Some(CursorShadow {
+            blur: 0.1,
+            offset: 0.05,
+            color: "#FF0000".to_string(),
+        });
+        let events = EventsFile {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            recording_id: "test".to_string(),
+            start_time_ms: 0,
+            screen_width: 1920,
+            screen_height: 1080,
+            scale_factor: 1.0,
+            coordinate_space: None,
+            events: vec![InputEvent::Move {
+                ts: 0,
+                x: 960.0,
+                y: 540.0,
+            }],
+        };
+
+        let ass = build_still_cursor_ass_file(
+            &project,
+            &events,
+            500,
+            1_000,
+            (1.0, 0.0, 0.0),
+            (1920, 1080),
+            (1920, 1080),
+        )
+        .expect("cursor script")
+        .expect("visible cursor");
+        let script = std::fs::read_to_string(&ass).expect("cursor script");
+        let _ = std::fs::remove_file(&ass);
+        let dialogues: Vec<&str> = script
+            .lines()
+            .filter(|line| line.starts_with("Dialogue:"))
+            .collect();
+        assert_eq!(dialogues.len(), 2);
+        // A 32.4 px cursor: 3.24 px of blur, shifted by 1.62 px.
+        assert!(dialogues[0].starts_with("Dialogue: 0,"));
+        assert!(dialogues[0].contains("\\shad0\\blur3.24\\1c&H0000FF&\\3c&H0000FF&\\pos(962,542)"));
+        assert!(dialogues[1].starts_with("Dialogue: 1,"));
+        assert!(dialogues[1].contains("\\shad0\\pos(960,540)"));
+
+        project.settings.cursor.shadow = None;
+        let ass = build_still_cursor_ass_file(
+            &project,
+            &events,
+            500,
+            1_000,
+            (1.0, 0.0, 0.0),
+            (1920, 1080),
+            (1920, 1080),
+        )
+        .expect("cursor script")
+        .expect("visible cursor");
+        let script = std::fs::read_to_string(&ass).expect("cursor script");
+        let _ = std::fs::remove_file(&ass);
+        assert_eq!(script.matches("Dialogue:").count(), 1);
+        assert!(!script.contains("\\blur"));
     }
 
     #[test]
-    fn cursor_interpolation_is_linear_between_points() {
-        let points = vec![(0, 0.0, 0.0), (100, 100.0, 50.0)];
-        let (x, y) = interpolate_cursor_position(&points, 50);
-        assert!((x - 50.0).abs() < 0.0001);
-        assert!((y - 25.0).abs() < 0.0001);
+    fn segment_labels_are_composited_after_the_camera() {
+        let mut project = sample_project();
+        let label = SegmentLabel {
+            text: "Open {settings}\nthen save".to_string(),
+            position: LabelPosition::Auto,
+            font_scale: 0.05,
+            color: "#FF8000".to_string(),
+            background_opacity: 0.5,
+        };
+        project.timeline.zoom_segments[0].label = Some(label.clone());
+        let mut lower = zoom_segment(
+            "z2",
+            4_000,
+            5_000,
+            NormalizedRect {
+                x: 0.1,
+                y: 0.7,
+                width: 0.2,
+                height: 0.2,
+            },
+        );
+        lower.label = Some(label);
+        project.timeline.zoom_segments.push(lower);
+
+        let (graph, _, temp_files) = build_export_filter_graph(
+            &project, None, 1920, 1080, 30, 20_000, 1920, 1080, None, false,
+        )
+        .expect("filter graph");
+        assert_eq!(temp_files.len(), 1);
+        assert!(graph.contains("[labeled];[labeled]subtitles=filename='"));
+        assert!(graph.ends_with("[vout]"));
+
+        let script = std::fs::read_to_string(&temp_files[0]).expect("label script");
+        for path in &temp_files {
+            let _ = std::fs::remove_file(path);
+        }
+        let dialogues: Vec<&str> = script
+            .lines()
+            .filter(|line| line.starts_with("Dialogue:"))
+            .collect();
+        assert_eq!(dialogues.len(), 2);
+        // Project time is stretched onto the 20 s source.
+        assert!(dialogues[0].starts_with("Dialogue: 1,0:00:02.00,0:00:04.00,Label,"));
+        // The upper target gets its label at the bottom and the lower one at the top.
+        assert!(dialogues[0].contains("\\an2\\fs54"));
+        assert!(dialogues[1].contains("\\an8"));
+        assert!(dialogues[0].contains("\\c&H0080FF&"));
+        assert!(dialogues[0].contains("\\3a&H80&"));
+        assert!(dialogues[0].ends_with("}Open (settings)\\Nthen save"));
+
+        project
+            .timeline
+            .zoom_segments
+            .iter_mut()
+            .for_each(|segment| segment.label = None);
+        let (graph, _, temp_files) = build_export_filter_graph(
+            &project, None, 1920, 1080, 30, 20_000, 1920, 1080, None, false,
+        )
+        .expect("filter graph");
+        assert!(temp_files.is_empty());
+        assert!(!graph.contains("subtitles"));
     }
 }
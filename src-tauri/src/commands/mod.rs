@@ -1,5 +1,7 @@
 pub mod app;
 pub mod capture;
 pub mod cursor;
+#[cfg(feature = "dev-tools")]
+pub mod dev;
 pub mod export;
 pub mod project;
@@ -1,17 +1,73 @@
 //! project_core — загрузка/сохранение project.json.
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::time::UNIX_EPOCH;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::algorithm::activity::{
+    self, summarize_activity, ActivitySummary, AutoPauseSuggestion, DEFAULT_AUTO_PAUSE_MIN_IDLE_MS,
+    DEFAULT_AUTO_PAUSE_PADDING_MS, DEFAULT_IDLE_GAP_MS,
+};
+use crate::algorithm::camera_engine::{
+    decimate_camera_samples, decimate_target_points, CameraDecisionTrace, CameraTrackPoint,
+    SmartCameraConfig, MAX_CAMERA_TRACK_POINTS,
+};
+use crate::algorithm::chapters::{
+    build_ffmetadata_chapters, detect_context_chapters, ChapterMarker, DEFAULT_MIN_CHAPTER_DWELL_MS,
+};
+use crate::algorithm::event_cleanup::{self, EventCleanupOptions, EventCleanupReport};
+use crate::algorithm::heatmap::{build_click_heatmap, ClickHeatmap, DEFAULT_HEATMAP_COLUMNS};
+use crate::capture::state::{AutoZoomTriggerMode, RecorderState};
+use crate::commands::capture::{
+    build_auto_zoom_segments, build_camera_segments, build_editor_proxy,
+    camera_config_for_trigger_mode, simulate_camera_track, trace_camera_decisions,
+    trim_events_to_range, trim_raw_video, ProxyOptions, ProxyState, VIDEO_ONLY_BACKUP_FILE_NAME,
+};
+use crate::commands::export::{
+    render_click_heatmap_png, sample_segment_camera_rects, sanitize_loaded_project,
+    segment_rect_camera_summary, CameraRectSample, CursorSample, CursorTrack, ExportState,
+};
 use crate::models::events::{EventsFile, SCHEMA_VERSION as EVENTS_SCHEMA_VERSION};
-use crate::models::project::{Project, SCHEMA_VERSION};
-use serde::Serialize;
+use crate::models::project::{
+    AspectRatio, CursorRenderMode, NormalizedRect, PanKeyframe, Project, SegmentLabel, TargetPoint,
+    TimeRange, ZoomSegment, CAMERA_ENGINE_VERSION, SCHEMA_VERSION,
+};
+use serde::{Deserialize, Serialize};
+
+/// Вычисленные параметры одного zoom-сегмента для отображения в редакторе.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoomSegmentSummary {
+    pub id: String,
+    pub start_ts: u64,
+    pub end_ts: u64,
+    pub duration_ms: u64,
+    /// Эффективный zoom начального прямоугольника (`1 / max(width, height)`).
+    pub zoom: f64,
+    /// Максимальный zoom среди начального прямоугольника и target points.
+    pub max_zoom: f64,
+    /// Центр кадра в нормализованных координатах (0..1) с учётом ограничений экспорта.
+    pub center_x: f64,
+    pub center_y: f64,
+    pub is_auto: bool,
+}
+
+/// Имя файла FFmpeg-метаданных с главами внутри папки проекта.
+pub(crate) const CHAPTERS_METADATA_FILE: &str = "chapters.ffmeta";
+
+/// Имя PNG с тепловой картой кликов внутри папки проекта.
+const CLICK_HEATMAP_FILE: &str = "click-heatmap.png";
+/// Ограничение длинной стороны PNG тепловой карты.
+const HEATMAP_IMAGE_MAX_DIMENSION: u32 = 1920;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectListItem {
     pub id: String,
     pub name: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
     pub created_at: u64,
     pub duration_ms: u64,
     pub video_width: u32,
@@ -21,6 +77,94 @@ pub struct ProjectListItem {
     pub modified_time_ms: u64,
 }
 
+/// Поле сортировки списка проектов.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProjectSort {
+    #[default]
+    CreatedAt,
+    Duration,
+    Name,
+}
+
+/// Фильтр и сортировка для `list_projects`; пустые поля не ограничивают выборку.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectListQuery {
+    /// Подстрока названия или описания (без учёта регистра).
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Нижняя граница `createdAt` (мс, включительно).
+    #[serde(default)]
+    pub created_from: Option<u64>,
+    /// Верхняя граница `createdAt` (мс, включительно).
+    #[serde(default)]
+    pub created_to: Option<u64>,
+    /// Метка, которая должна быть у проекта (без учёта регистра).
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub sort: ProjectSort,
+    /// По умолчанию название — по возрастанию, остальное — новые/длинные первыми.
+    #[serde(default)]
+    pub descending: Option<bool>,
+}
+
+impl ProjectListQuery {
+    fn matches(&self, item: &ProjectListItem) -> bool {
+        let needle = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_lowercase)
+        };
+        if let Some(name) = needle(&self.name) {
+            let in_name = item.name.to_lowercase().contains(&name);
+            let in_description = item
+                .description
+                .as_deref()
+                .is_some_and(|description| description.to_lowercase().contains(&name));
+            if !in_name && !in_description {
+                return false;
+            }
+        }
+        if let Some(tag) = needle(&self.tag) {
+            if !item
+                .tags
+                .iter()
+                .any(|item_tag| item_tag.to_lowercase() == tag)
+            {
+                return false;
+            }
+        }
+        self.created_from
+            .is_none_or(|created_from| item.created_at >= created_from)
+            && self
+                .created_to
+                .is_none_or(|created_to| item.created_at <= created_to)
+    }
+
+    fn sort(&self, projects: &mut [ProjectListItem]) {
+        let descending = self.descending.unwrap_or(self.sort != ProjectSort::Name);
+        projects.sort_by(|a, b| {
+            let ordering = match self.sort {
+                ProjectSort::CreatedAt => a
+                    .created_at
+                    .cmp(&b.created_at)
+                    .then(a.modified_time_ms.cmp(&b.modified_time_ms)),
+                ProjectSort::Duration => a.duration_ms.cmp(&b.duration_ms),
+                ProjectSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            };
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+}
+
 /// Загружает проект из файла `project.json`.
 ///
 /// Поддерживает как путь к файлу, так и путь к директории проекта.
@@ -32,7 +176,7 @@ pub async fn get_project(project_path: String) -> Result<Project, String> {
     let raw = std::fs::read_to_string(&path)
         .map_err(|e| format!("Failed to read project file {}: {e}", path.display()))?;
 
-    let project: Project = serde_json::from_str(&raw)
+    let mut project: Project = serde_json::from_str(&raw)
         .map_err(|e| format!("Failed to parse project.json {}: {e}", path.display()))?;
 
     if project.schema_version != SCHEMA_VERSION {
@@ -42,6 +186,7 @@ pub async fn get_project(project_path: String) -> Result<Project, String> {
         ));
     }
 
+    sanitize_loaded_project(&mut project, &path);
     Ok(project)
 }
 
@@ -50,7 +195,36 @@ pub async fn get_project(project_path: String) -> Result<Project, String> {
 /// Поддерживает путь к `project.json` или путь к директории проекта.
 #[tauri::command]
 pub async fn get_events(project_path: String) -> Result<EventsFile, String> {
-    let project_file = resolve_project_file(&project_path)?;
+    let (_, mut events) = read_events_file(&project_path)?;
+    events.normalize_to_physical();
+    Ok(events)
+}
+
+/// Явно переводит координаты старой записи из логических пикселей в физические и
+/// перезаписывает events.json.
+///
+/// Файлы без пометки системы координат считаются физическими; эта команда нужна для
+/// записей, сделанных при DPI-масштабировании до появления пометки, если зумы и клики
+/// в них смещены к левому верхнему углу.
+#[tauri::command]
+pub async fn convert_events_from_logical(project_path: String) -> Result<EventsFile, String> {
+    let (events_file, mut events) = read_events_file(&project_path)?;
+    events.convert_from_logical()?;
+    let events_json = serde_json::to_string_pretty(&events)
+        .map_err(|e| format!("Failed to serialize events: {e}"))?;
+    std::fs::write(&events_file, events_json)
+        .map_err(|e| format!("Failed to write {}: {e}", events_file.display()))?;
+    log::info!(
+        "convert_events_from_logical: {} scaled by {}",
+        events_file.display(),
+        events.scale_factor
+    );
+    Ok(events)
+}
+
+/// Читает events.json проекта как есть, без пересчёта координат.
+fn read_events_file(project_path: &str) -> Result<(PathBuf, EventsFile), String> {
+    let project_file = resolve_project_file(project_path)?;
     let project_raw = std::fs::read_to_string(&project_file).map_err(|e| {
         format!(
             "Failed to read project file {}: {e}",
@@ -91,7 +265,1073 @@ pub async fn get_events(project_path: String) -> Result<EventsFile, String> {
         ));
     }
 
-    Ok(events)
+    Ok((events_file, events))
+}
+
+/// Очищенный events.json и отчёт о том, что было исправлено.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizedEvents {
+    pub events: EventsFile,
+    pub report: EventCleanupReport,
+}
+
+/// Чистит события проекта: сортирует их, прижимает координаты к экрану, убирает дубли
+/// и по запросу отбрасывает типы событий и прореживает движения мыши.
+///
+/// При `write = true` результат записывается вместо events.json (в физических пикселях).
+#[tauri::command]
+pub async fn normalize_events(
+    project_path: String,
+    options: Option<EventCleanupOptions>,
+    write: Option<bool>,
+) -> Result<NormalizedEvents, String> {
+    let mut events = get_events(project_path.clone()).await?;
+    let report = event_cleanup::normalize_events(&mut events, &options.unwrap_or_default());
+
+    if write.unwrap_or(false) {
+        let project = get_project(project_path.clone()).await?;
+        let project_file = resolve_project_file(&project_path)?;
+        let project_dir = project_file.parent().ok_or_else(|| {
+            format!(
+                "Project file has no parent directory: {}",
+                project_file.display()
+            )
+        })?;
+        let events_file = project_dir.join(Path::new(project.events_path.trim()));
+        let events_json = serde_json::to_string_pretty(&events)
+            .map_err(|e| format!("Failed to serialize events: {e}"))?;
+        std::fs::write(&events_file, events_json)
+            .map_err(|e| format!("Failed to write {}: {e}", events_file.display()))?;
+        log::info!(
+            "normalize_events: id={} {} -> {} events",
+            project.id,
+            report.input_count,
+            report.output_count
+        );
+    }
+
+    Ok(NormalizedEvents { events, report })
+}
+
+/// Строит главы по устойчивым переключениям между приложениями (по `uiContext.appName` кликов).
+///
+/// `min_dwell_ms` — минимальное время пребывания в приложении, чтобы открыть новую главу.
+/// При `write_metadata = true` рядом с проектом сохраняется `chapters.ffmeta`,
+/// который экспорт встраивает в итоговый файл.
+#[tauri::command]
+pub async fn generate_chapters(
+    project_path: String,
+    min_dwell_ms: Option<u64>,
+    write_metadata: Option<bool>,
+) -> Result<Vec<ChapterMarker>, String> {
+    let project = get_project(project_path.clone()).await?;
+    let events = get_events(project_path.clone()).await?;
+
+    let chapters = detect_context_chapters(
+        &events.events,
+        project.duration_ms,
+        min_dwell_ms.unwrap_or(DEFAULT_MIN_CHAPTER_DWELL_MS),
+    );
+
+    if write_metadata.unwrap_or(false) {
+        let project_file = resolve_project_file(&project_path)?;
+        let project_dir = project_file.parent().ok_or_else(|| {
+            format!(
+                "Project file has no parent directory: {}",
+                project_file.display()
+            )
+        })?;
+        let metadata_path = project_dir.join(CHAPTERS_METADATA_FILE);
+        if chapters.is_empty() {
+            if metadata_path.exists() {
+                std::fs::remove_file(&metadata_path).map_err(|e| {
+                    format!(
+                        "Failed to remove chapters metadata {}: {e}",
+                        metadata_path.display()
+                    )
+                })?;
+            }
+        } else {
+            std::fs::write(&metadata_path, build_ffmetadata_chapters(&chapters)).map_err(|e| {
+                format!(
+                    "Failed to write chapters metadata {}: {e}",
+                    metadata_path.display()
+                )
+            })?;
+        }
+    }
+
+    log::info!(
+        "generate_chapters: id={} chapters={}",
+        project.id,
+        chapters.len()
+    );
+    Ok(chapters)
+}
+
+/// Возвращает расширенное представление таймлайна: zoom, центр и длительность каждого сегмента.
+///
+/// Расчёт совпадает с камерой экспорта, поэтому UI не дублирует математику zoom.
+#[tauri::command]
+pub async fn get_timeline_summary(project_path: String) -> Result<Vec<ZoomSegmentSummary>, String> {
+    let project = get_project(project_path).await?;
+    let source_width = project.video_width as f64;
+    let source_height = project.video_height as f64;
+
+    let mut summaries = project
+        .timeline
+        .zoom_segments
+        .iter()
+        .map(|segment| {
+            let (zoom, center_x, center_y) =
+                segment_rect_camera_summary(&segment.initial_rect, source_width, source_height);
+            let max_zoom = segment
+                .target_points
+                .iter()
+                .map(|point| {
+                    segment_rect_camera_summary(
+                        &point.effective_rect(),
+                        source_width,
+                        source_height,
+                    )
+                    .0
+                })
+                .fold(zoom, f64::max);
+
+            ZoomSegmentSummary {
+                id: segment.id.clone(),
+                start_ts: segment.start_ts,
+                end_ts: segment.end_ts,
+                duration_ms: segment.end_ts.saturating_sub(segment.start_ts),
+                zoom,
+                max_zoom,
+                center_x,
+                center_y,
+                is_auto: segment.is_auto,
+            }
+        })
+        .collect::<Vec<_>>();
+    summaries.sort_by_key(|summary| summary.start_ts);
+
+    Ok(summaries)
+}
+
+/// Сводка активности записи: активное и простойное время, клики, прокрутка и нажатия клавиш.
+///
+/// `idle_gap_ms` — пауза ввода, после которой время считается простоем.
+#[tauri::command]
+pub async fn get_activity_summary(
+    project_path: String,
+    idle_gap_ms: Option<u64>,
+) -> Result<ActivitySummary, String> {
+    let project = get_project(project_path.clone()).await?;
+    let events = get_events(project_path).await?;
+    Ok(summarize_activity(
+        &events.events,
+        project.duration_ms,
+        idle_gap_ms.unwrap_or(DEFAULT_IDLE_GAP_MS).max(1),
+    ))
+}
+
+/// Предлагает вырезать паузы ввода не короче `min_idle_ms` (по умолчанию 5 с).
+///
+/// С каждой стороны паузы, примыкающей к действиям, остаётся `padding_ms`. Возвращает
+/// диапазоны и сколько времени они сэкономят; проект не меняется.
+#[tauri::command]
+pub async fn suggest_auto_pauses(
+    project_path: String,
+    min_idle_ms: Option<u64>,
+    padding_ms: Option<u64>,
+) -> Result<AutoPauseSuggestion, String> {
+    let project = get_project(project_path.clone()).await?;
+    let events = get_events(project_path).await?;
+    Ok(activity::suggest_auto_pauses(
+        &events.events,
+        project.duration_ms,
+        min_idle_ms.unwrap_or(DEFAULT_AUTO_PAUSE_MIN_IDLE_MS),
+        padding_ms.unwrap_or(DEFAULT_AUTO_PAUSE_PADDING_MS),
+    ))
+}
+
+/// Тепловая карта кликов и путь к её PNG-изображению.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClickHeatmapImage {
+    pub image_path: String,
+    pub heatmap: ClickHeatmap,
+}
+
+/// Строит тепловую карту всех кликов записи и сохраняет её в `click-heatmap.png` в папке
+/// проекта.
+///
+/// `columns` — ширина сетки плотности (высота следует пропорциям экрана). При
+/// `overlay_frame = true` карта накладывается на кадр из середины записи, иначе фон прозрачный.
+#[tauri::command]
+pub async fn generate_click_heatmap(
+    project_path: String,
+    columns: Option<u32>,
+    overlay_frame: Option<bool>,
+) -> Result<ClickHeatmapImage, String> {
+    let project = get_project(project_path.clone()).await?;
+    let events = get_events(project_path.clone()).await?;
+    let project_file = resolve_project_file(&project_path)?;
+    let project_dir = project_file
+        .parent()
+        .ok_or_else(|| {
+            format!(
+                "Project file has no parent directory: {}",
+                project_file.display()
+            )
+        })?
+        .to_path_buf();
+
+    let heatmap = build_click_heatmap(
+        &events.events,
+        events.screen_width,
+        events.screen_height,
+        columns.unwrap_or(DEFAULT_HEATMAP_COLUMNS),
+    );
+    if heatmap.click_count == 0 {
+        return Err("The recording has no clicks".to_string());
+    }
+
+    let video_path = project_dir.join(&project.video_path);
+    let background = overlay_frame
+        .unwrap_or(false)
+        .then_some(video_path)
+        .filter(|path| path.is_file());
+    // Aspect ratio of the recording, capped so the PNG stays light.
+    let longest_side = project.video_width.max(project.video_height).max(1);
+    let scale = (HEATMAP_IMAGE_MAX_DIMENSION as f64 / longest_side as f64).min(1.0);
+    let size = (
+        ((project.video_width as f64 * scale) as u32 & !1).max(2),
+        ((project.video_height as f64 * scale) as u32 & !1).max(2),
+    );
+    let image_path = project_dir.join(CLICK_HEATMAP_FILE);
+    let frame_ts = project.duration_ms / 2;
+
+    let render_heatmap = heatmap.clone();
+    let render_path = image_path.clone();
+    tokio::task::spawn_blocking(move || {
+        render_click_heatmap_png(
+            &render_heatmap,
+            background.as_deref().map(|path| (path, frame_ts)),
+            size,
+            &render_path,
+        )
+    })
+    .await
+    .map_err(|e| format!("Click heatmap task join error: {e}"))??;
+
+    log::info!(
+        "generate_click_heatmap: id={} clicks={} grid={}x{}",
+        project.id,
+        heatmap.click_count,
+        heatmap.columns,
+        heatmap.rows
+    );
+    Ok(ClickHeatmapImage {
+        image_path: image_path.to_string_lossy().to_string(),
+        heatmap,
+    })
+}
+
+/// Кэш траекторий курсора для `cursor_at`: по одной на файл проекта.
+#[derive(Clone, Default)]
+pub struct CursorTrackCache(pub Arc<Mutex<HashMap<PathBuf, CachedCursorTrack>>>);
+
+/// Траектория вместе с параметрами, из которых она построена.
+pub struct CachedCursorTrack {
+    smoothing_factor: f64,
+    render_mode: CursorRenderMode,
+    duration_ms: u64,
+    events_modified: Option<SystemTime>,
+    track: Arc<CursorTrack>,
+}
+
+/// Возвращает положение курсора в момент `ts` так, как его нарисует экспорт.
+///
+/// Траектория строится один раз и кэшируется, пока не изменятся `smoothing_factor`,
+/// режим отрисовки или events.json, поэтому вызов дёшев при перемотке.
+/// `smoothing_factor` позволяет показать ещё не сохранённое значение ползунка.
+#[tauri::command]
+pub async fn cursor_at(
+    cache: tauri::State<'_, CursorTrackCache>,
+    project_path: String,
+    ts: u64,
+    smoothing_factor: Option<f64>,
+) -> Result<CursorSample, String> {
+    let project_file = resolve_project_file(&project_path)?;
+    let project = get_project(project_path.clone()).await?;
+    let mut cursor = project.settings.cursor.clone();
+    if let Some(smoothing_factor) = smoothing_factor.filter(|value| value.is_finite()) {
+        cursor.smoothing_factor = smoothing_factor.clamp(0.0, 1.0);
+    }
+    let events_modified = events_modified_at(&project_file, &project);
+
+    let cached = {
+        let entries = cache
+            .0
+            .lock()
+            .map_err(|_| "Failed to access cursor track cache".to_string())?;
+        entries
+            .get(&project_file)
+            .filter(|entry| {
+                entry.smoothing_factor == cursor.smoothing_factor
+                    && entry.render_mode == cursor.render_mode
+                    && entry.duration_ms == project.duration_ms
+                    && entry.events_modified == events_modified
+            })
+            .map(|entry| entry.track.clone())
+    };
+    let track = match cached {
+        Some(track) => track,
+        None => {
+            let events = get_events(project_path).await?;
+            let track = Arc::new(CursorTrack::new(&events, &cursor, project.duration_ms));
+            cache
+                .0
+                .lock()
+                .map_err(|_| "Failed to access cursor track cache".to_string())?
+                .insert(
+                    project_file,
+                    CachedCursorTrack {
+                        smoothing_factor: cursor.smoothing_factor,
+                        render_mode: cursor.render_mode,
+                        duration_ms: project.duration_ms,
+                        events_modified,
+                        track: track.clone(),
+                    },
+                );
+            track
+        }
+    };
+
+    Ok(track.sample(ts.min(project.duration_ms), &cursor))
+}
+
+/// Время изменения events.json проекта; `None`, если его не удалось узнать.
+fn events_modified_at(project_file: &Path, project: &Project) -> Option<SystemTime> {
+    project_file
+        .parent()
+        .map(|dir| dir.join(project.events_path.trim()))
+        .and_then(|path| std::fs::metadata(path).ok())
+        .and_then(|metadata| metadata.modified().ok())
+}
+
+/// Возвращает прямоугольник камеры zoom-сегмента в заданные моменты времени.
+///
+/// Пружины считаются так же, как в экспорте, поэтому редактор рисует рамку кадра
+/// без запуска рендера. Метки времени вне сегмента прижимаются к его границам.
+#[tauri::command]
+pub async fn sample_segment_camera(
+    project: Project,
+    segment_id: String,
+    timestamps: Vec<u64>,
+) -> Result<Vec<CameraRectSample>, String> {
+    sample_segment_camera_rects(&project, &segment_id, &timestamps)
+}
+
+/// Предпросмотр траектории умной камеры для наложения на таймлайн.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraTrackPreview {
+    /// Параметры, с которыми построена траектория (удобная стартовая точка для ползунков).
+    pub config: SmartCameraConfig,
+    /// Число сэмплов симуляции до прореживания.
+    pub total_samples: usize,
+    pub points: Vec<CameraTrackPoint>,
+}
+
+/// Прогоняет умную камеру по событиям проекта с переданными параметрами.
+///
+/// Сегменты не строятся и проект не меняется. Без `config` берутся параметры,
+/// которые использовала бы пересборка авто-zoom. Результат прореживается до
+/// `max_points` (не больше `MAX_CAMERA_TRACK_POINTS`).
+#[tauri::command]
+pub async fn preview_camera_track(
+    project_path: String,
+    config: Option<SmartCameraConfig>,
+    trigger_mode: Option<AutoZoomTriggerMode>,
+    max_points: Option<usize>,
+) -> Result<CameraTrackPreview, String> {
+    let project = get_project(project_path.clone()).await?;
+    let events = get_events(project_path).await?;
+    let config = config.unwrap_or_else(|| {
+        camera_config_for_trigger_mode(
+            trigger_mode.unwrap_or(project.settings.camera.trigger_mode),
+            &project.settings.camera,
+        )
+    });
+    let hidden_ranges = cursor_hidden_ranges(&project);
+
+    let samples = simulate_camera_track(
+        &events.events,
+        project.video_width,
+        project.video_height,
+        project.duration_ms,
+        project.settings.export.output_aspect_ratio(),
+        &hidden_ranges,
+        &config,
+    );
+    let points = decimate_camera_samples(&samples, max_points.unwrap_or(MAX_CAMERA_TRACK_POINTS));
+    Ok(CameraTrackPreview {
+        config,
+        total_samples: samples.len(),
+        points,
+    })
+}
+
+/// Трассировка решений умной камеры по событиям проекта: какие клики отсеяны
+/// режимом активации, как они сгруппированы, какие границы и zoom выбраны и на
+/// сколько переход сдвинут минимальным интервалом. Проект не меняется.
+///
+/// Без `config` берутся параметры, которые использовала бы пересборка авто-zoom.
+#[tauri::command]
+pub async fn debug_camera_decisions(
+    project_path: String,
+    config: Option<SmartCameraConfig>,
+    trigger_mode: Option<AutoZoomTriggerMode>,
+) -> Result<CameraDecisionTrace, String> {
+    let project = get_project(project_path.clone()).await?;
+    let events = get_events(project_path).await?;
+    let config = config.unwrap_or_else(|| {
+        camera_config_for_trigger_mode(
+            trigger_mode.unwrap_or(project.settings.camera.trigger_mode),
+            &project.settings.camera,
+        )
+    });
+    let trace = trace_camera_decisions(
+        &events.events,
+        project.video_width,
+        project.video_height,
+        project.settings.export.output_aspect_ratio(),
+        &cursor_hidden_ranges(&project),
+        &config,
+    );
+    log::info!(
+        "debug_camera_decisions: id={} clicks={} clusters={}",
+        project.id,
+        trace.clicks.len(),
+        trace.clusters.len()
+    );
+    Ok(trace)
+}
+
+/// Кэш сегментов `compute_camera_segments`: по одному набору на файл проекта.
+#[derive(Clone, Default)]
+pub struct CameraSegmentsCache(pub Arc<Mutex<HashMap<PathBuf, CachedCameraSegments>>>);
+
+/// Сегменты вместе с входными данными, из которых они построены.
+pub struct CachedCameraSegments {
+    inputs: CameraSegmentsInputs,
+    segments: Arc<Vec<ZoomSegment>>,
+}
+
+#[derive(PartialEq)]
+struct CameraSegmentsInputs {
+    /// Параметры камеры в JSON: у `SmartCameraConfig` нет сравнения.
+    config: String,
+    video_width: u32,
+    video_height: u32,
+    duration_ms: u64,
+    output_aspect_ratio: f64,
+    hidden_ranges: Vec<(u64, u64)>,
+    events_modified: Option<SystemTime>,
+}
+
+/// Сегменты умной камеры, которые построила бы пересборка авто-zoom.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraSegmentsPreview {
+    /// Параметры, с которыми построены сегменты.
+    pub config: SmartCameraConfig,
+    /// Сегменты с прореженными `target_points`.
+    pub segments: Vec<ZoomSegment>,
+    /// Число точек траектории во всех сегментах до прореживания.
+    pub total_target_points: usize,
+}
+
+/// Строит авто-zoom сегменты с переданными параметрами камеры, не меняя проект.
+///
+/// Позволяет сравнить параметры до `regenerate_auto_zoom`. Без `config` берутся
+/// параметры, которые использовала бы пересборка. Результат кэшируется, пока не
+/// изменятся параметры, проект или events.json; `target_points` каждого сегмента
+/// прореживаются до `max_target_points` (не больше `MAX_CAMERA_TRACK_POINTS`).
+#[tauri::command]
+pub async fn compute_camera_segments(
+    cache: tauri::State<'_, CameraSegmentsCache>,
+    project_path: String,
+    config: Option<SmartCameraConfig>,
+    trigger_mode: Option<AutoZoomTriggerMode>,
+    max_target_points: Option<usize>,
+) -> Result<CameraSegmentsPreview, String> {
+    let project_file = resolve_project_file(&project_path)?;
+    let project = get_project(project_path.clone()).await?;
+    let config = config.unwrap_or_else(|| {
+        camera_config_for_trigger_mode(
+            trigger_mode.unwrap_or(project.settings.camera.trigger_mode),
+            &project.settings.camera,
+        )
+    });
+    let inputs = CameraSegmentsInputs {
+        config: serde_json::to_string(&config)
+            .map_err(|e| format!("Failed to serialize camera config: {e}"))?,
+        video_width: project.video_width,
+        video_height: project.video_height,
+        duration_ms: project.duration_ms,
+        output_aspect_ratio: project.settings.export.output_aspect_ratio(),
+        hidden_ranges: cursor_hidden_ranges(&project),
+        events_modified: events_modified_at(&project_file, &project),
+    };
+
+    let cached = cache
+        .0
+        .lock()
+        .map_err(|_| "Failed to access camera segments cache".to_string())?
+        .get(&project_file)
+        .filter(|entry| entry.inputs == inputs)
+        .map(|entry| entry.segments.clone());
+    let segments = match cached {
+        Some(segments) => segments,
+        None => {
+            let events = get_events(project_path).await?;
+            let segments = Arc::new(build_camera_segments(
+                &events.events,
+                inputs.video_width,
+                inputs.video_height,
+                inputs.duration_ms,
+                inputs.output_aspect_ratio,
+                &inputs.hidden_ranges,
+                &config,
+            ));
+            log::info!(
+                "compute_camera_segments: id={} segments={}",
+                project.id,
+                segments.len()
+            );
+            cache
+                .0
+                .lock()
+                .map_err(|_| "Failed to access camera segments cache".to_string())?
+                .insert(
+                    project_file,
+                    CachedCameraSegments {
+                        inputs,
+                        segments: segments.clone(),
+                    },
+                );
+            segments
+        }
+    };
+
+    let max_target_points = max_target_points.unwrap_or(MAX_CAMERA_TRACK_POINTS);
+    Ok(CameraSegmentsPreview {
+        config,
+        total_target_points: segments
+            .iter()
+            .map(|segment| segment.target_points.len())
+            .sum(),
+        segments: segments
+            .iter()
+            .map(|segment| ZoomSegment {
+                target_points: decimate_target_points(&segment.target_points, max_target_points),
+                ..segment.clone()
+            })
+            .collect(),
+    })
+}
+
+/// Интервалы скрытого курсора проекта: клики в них не управляют камерой.
+pub(crate) fn cursor_hidden_ranges(project: &Project) -> Vec<(u64, u64)> {
+    project
+        .settings
+        .cursor
+        .hidden_ranges
+        .iter()
+        .map(|range| (range.start_ts, range.end_ts))
+        .collect()
+}
+
+/// Задаёт описание и метки проекта и сохраняет его.
+///
+/// Пустое описание удаляется; метки обрезаются, пустые и повторы отбрасываются.
+#[tauri::command]
+pub async fn set_project_metadata(
+    project_path: String,
+    description: Option<String>,
+    tags: Vec<String>,
+) -> Result<Project, String> {
+    let mut project = get_project(project_path.clone()).await?;
+    project.set_metadata(description, tags);
+    log::info!(
+        "set_project_metadata: id={} tags={}",
+        project.id,
+        project.tags.len()
+    );
+    save_project(project.clone(), Some(project_path)).await?;
+    Ok(project)
+}
+
+/// Задаёт подпись zoom-сегмента, которая выводится поверх видео при экспорте.
+#[tauri::command]
+pub async fn set_segment_label(
+    project_path: String,
+    segment_id: String,
+    label: SegmentLabel,
+) -> Result<Project, String> {
+    let mut project = get_project(project_path.clone()).await?;
+    project.set_segment_label(&segment_id, Some(label))?;
+    log::info!("set_segment_label: id={} segment={segment_id}", project.id);
+    save_project(project.clone(), Some(project_path)).await?;
+    Ok(project)
+}
+
+/// Убирает подпись zoom-сегмента.
+#[tauri::command]
+pub async fn clear_segment_label(
+    project_path: String,
+    segment_id: String,
+) -> Result<Project, String> {
+    let mut project = get_project(project_path.clone()).await?;
+    project.set_segment_label(&segment_id, None)?;
+    save_project(project.clone(), Some(project_path)).await?;
+    Ok(project)
+}
+
+/// Задаёт явное приближение точки траектории с меткой `ts`: центр кадра остаётся
+/// центром `rect`, а приближение плавно меняется пружиной камеры. `zoom = None`
+/// возвращает приближение, выводимое из размера `rect`.
+#[tauri::command]
+pub async fn set_target_point_zoom(
+    project_path: String,
+    segment_id: String,
+    ts: u64,
+    zoom: Option<f64>,
+) -> Result<Project, String> {
+    let mut project = get_project(project_path.clone()).await?;
+    project.set_target_point_zoom(&segment_id, ts, zoom)?;
+    save_project(project.clone(), Some(project_path)).await?;
+    Ok(project)
+}
+
+/// Пересчитывает автоматические zoom-сегменты под текущие настройки экспорта.
+///
+/// Ручные сегменты сохраняются; новые авто-сегменты, пересекающиеся с ними, отбрасываются.
+/// Без `trigger_mode` берётся режим срабатывания из настроек проекта; переданный режим
+/// сохраняется в проект.
+/// Сегменты, построенные старой версией камеры, пересчитываются только с
+/// `upgrade_camera_engine = true`, чтобы экспорт старого проекта не менялся незаметно.
+#[tauri::command]
+pub async fn regenerate_auto_zoom(
+    project_path: String,
+    trigger_mode: Option<AutoZoomTriggerMode>,
+    upgrade_camera_engine: Option<bool>,
+) -> Result<Project, String> {
+    let mut project = get_project(project_path.clone()).await?;
+    let events = get_events(project_path.clone()).await?;
+    regenerate_auto_zoom_segments(
+        &mut project,
+        &events,
+        trigger_mode,
+        upgrade_camera_engine.unwrap_or(false),
+    )?;
+    save_project(project.clone(), Some(project_path)).await?;
+    Ok(project)
+}
+
+/// Меняет соотношение сторон проекта: размер экспорта и кадрирование авто-zoom.
+///
+/// Короткая сторона экспорта сохраняется, авто-сегменты пересчитываются под новую
+/// пропорцию, чтобы камера и экспорт не расходились.
+#[tauri::command]
+pub async fn set_export_aspect_ratio(
+    project_path: String,
+    aspect_ratio: AspectRatio,
+    trigger_mode: Option<AutoZoomTriggerMode>,
+    upgrade_camera_engine: Option<bool>,
+) -> Result<Project, String> {
+    let mut project = get_project(project_path.clone()).await?;
+    let events = get_events(project_path.clone()).await?;
+    project.settings.export.apply_aspect_ratio(aspect_ratio);
+    log::info!(
+        "set_export_aspect_ratio: id={} aspect={:?} export={}x{}",
+        project.id,
+        aspect_ratio,
+        project.settings.export.width,
+        project.settings.export.height
+    );
+    regenerate_auto_zoom_segments(
+        &mut project,
+        &events,
+        trigger_mode,
+        upgrade_camera_engine.unwrap_or(false),
+    )?;
+    save_project(project.clone(), Some(project_path)).await?;
+    Ok(project)
+}
+
+fn regenerate_auto_zoom_segments(
+    project: &mut Project,
+    events: &EventsFile,
+    trigger_mode: Option<AutoZoomTriggerMode>,
+    upgrade_camera_engine: bool,
+) -> Result<(), String> {
+    if project.has_newer_camera_engine() && !upgrade_camera_engine {
+        return Err(format!(
+            "Auto-zoom was generated by camera engine v{}; pass upgradeCameraEngine to regenerate it with v{}",
+            project.camera_engine_version, CAMERA_ENGINE_VERSION
+        ));
+    }
+    if let Some(trigger_mode) = trigger_mode {
+        project.settings.camera.trigger_mode = trigger_mode;
+    }
+    let trigger_mode = project.settings.camera.trigger_mode;
+    let hidden_ranges = cursor_hidden_ranges(project);
+    let auto_segments = build_auto_zoom_segments(
+        &events.events,
+        project.video_width,
+        project.video_height,
+        project.duration_ms,
+        project.settings.export.output_aspect_ratio(),
+        &hidden_ranges,
+        trigger_mode,
+        &project.settings.camera,
+    );
+
+    let mut segments: Vec<ZoomSegment> = std::mem::take(&mut project.timeline.zoom_segments)
+        .into_iter()
+        .filter(|segment| !segment.is_auto)
+        .collect();
+    let manual_count = segments.len();
+    let overlaps_manual = |candidate: &ZoomSegment| {
+        segments[..manual_count]
+            .iter()
+            .any(|manual| candidate.start_ts < manual.end_ts && manual.start_ts < candidate.end_ts)
+    };
+    let kept_auto = auto_segments
+        .into_iter()
+        .filter(|segment| !overlaps_manual(segment))
+        .collect::<Vec<_>>();
+    log::info!(
+        "regenerate_auto_zoom: id={} manual={} auto={}",
+        project.id,
+        manual_count,
+        kept_auto.len()
+    );
+    segments.extend(kept_auto);
+    segments.sort_by_key(|segment| segment.start_ts);
+    project.timeline.zoom_segments = segments;
+    project.camera_engine_version = CAMERA_ENGINE_VERSION;
+    Ok(())
+}
+
+/// Пересобирает прокси-видео редактора (`proxy-edit.mp4`) из `raw.mp4`.
+///
+/// Старый прокси заменяется только после успешного кодирования. Путь к прокси
+/// записывается в `project.json`; возвращается обновлённый проект.
+#[tauri::command]
+pub async fn rebuild_proxy(
+    project_path: String,
+    options: Option<ProxyOptions>,
+) -> Result<Project, String> {
+    let project = get_project(project_path.clone()).await?;
+    let project_file = resolve_project_file(&project_path)?;
+    let project_dir = project_file
+        .parent()
+        .ok_or_else(|| {
+            format!(
+                "Project file has no parent directory: {}",
+                project_file.display()
+            )
+        })?
+        .to_path_buf();
+    let options = options.unwrap_or_default();
+
+    log::info!("rebuild_proxy: id={} options={options:?}", project.id);
+    let proxy_name =
+        tokio::task::spawn_blocking(move || build_editor_proxy(&project_dir, &options))
+            .await
+            .map_err(|e| format!("Proxy task join error: {e}"))??;
+
+    // The encode can take minutes; patch the current file so edits saved meanwhile survive.
+    patch_project_file(&project_file, |project| {
+        project.proxy_video_path = Some(proxy_name)
+    })
+}
+
+/// Удаляет копию видео без звука (`raw-video-only.mp4`), оставшуюся после сведения звука.
+///
+/// Возвращает число освобождённых байт; если копии нет, возвращает 0.
+#[tauri::command]
+pub async fn delete_video_only_backup(project_path: String) -> Result<u64, String> {
+    let project_file = resolve_project_file(&project_path)?;
+    let project_dir = project_file.parent().ok_or_else(|| {
+        format!(
+            "Project file has no parent directory: {}",
+            project_file.display()
+        )
+    })?;
+    let backup_path = project_dir.join(VIDEO_ONLY_BACKUP_FILE_NAME);
+    let Ok(metadata) = std::fs::metadata(&backup_path) else {
+        return Ok(0);
+    };
+    std::fs::remove_file(&backup_path)
+        .map_err(|e| format!("Failed to delete {}: {e}", backup_path.display()))?;
+    log::info!(
+        "delete_video_only_backup: removed {} ({} bytes)",
+        backup_path.display(),
+        metadata.len()
+    );
+    Ok(metadata.len())
+}
+
+/// Необратимо обрезает запись до диапазона `[start_ms, end_ms)`.
+///
+/// Перезаписывает `raw.mp4`, сдвигает события, zoom-сегменты и скрытые диапазоны курсора,
+/// обновляет `durationMs` и пересобирает прокси. Требует `confirm = true`; перед первой
+/// обрезкой исходные файлы сохраняются как `*.original.*`. Видео, события и проект сначала
+/// пишутся во временные файлы и подменяют исходные, только когда готовы все три.
+#[tauri::command]
+pub async fn trim_recording(
+    project_path: String,
+    start_ms: u64,
+    end_ms: u64,
+    confirm: bool,
+) -> Result<Project, String> {
+    if !confirm {
+        return Err(
+            "Trimming permanently rewrites the recording; pass confirm=true to proceed".to_string(),
+        );
+    }
+
+    let mut project = get_project(project_path.clone()).await?;
+    let end_ms = end_ms.min(project.duration_ms);
+    if start_ms >= end_ms {
+        return Err(format!(
+            "Invalid trim range {start_ms}..{end_ms} ms for a {} ms recording",
+            project.duration_ms
+        ));
+    }
+    if start_ms == 0 && end_ms == project.duration_ms {
+        return Ok(project);
+    }
+
+    let mut events = get_events(project_path.clone()).await?;
+    let project_file = resolve_project_file(&project_path)?;
+    let project_dir = project_file
+        .parent()
+        .ok_or_else(|| {
+            format!(
+                "Project file has no parent directory: {}",
+                project_file.display()
+            )
+        })?
+        .to_path_buf();
+    log::info!(
+        "trim_recording: id={} range={start_ms}..{end_ms}ms duration={}ms",
+        project.id,
+        project.duration_ms
+    );
+
+    project.settings.camera.validate()?;
+    project.timeline.zoom_segments = trim_zoom_segments(
+        std::mem::take(&mut project.timeline.zoom_segments),
+        start_ms,
+        end_ms,
+    );
+    project.settings.cursor.hidden_ranges = project
+        .settings
+        .cursor
+        .hidden_ranges
+        .iter()
+        .filter_map(|range| trim_time_range(range.start_ts, range.end_ts, start_ms, end_ms))
+        .map(|(start_ts, end_ts)| TimeRange { start_ts, end_ts })
+        .collect();
+    project.duration_ms = end_ms - start_ms;
+    // Старый прокси не совпадает с обрезанным видео; путь к новому дописывается после сборки.
+    let had_proxy = project.proxy_video_path.take().is_some();
+
+    events.events = trim_events_to_range(std::mem::take(&mut events.events), start_ms, end_ms);
+    events.start_time_ms = events.start_time_ms.saturating_add(start_ms);
+
+    let events_file = project_dir.join(Path::new(project.events_path.trim()));
+    tokio::task::spawn_blocking(move || -> Result<Project, String> {
+        backup_original_recording(&project_dir, &project_file, &events_file)?;
+        let trimmed_video = trim_raw_video(&project_dir, start_ms, end_ms)?;
+        let staged_events = events_file.with_extension("json.trim.tmp");
+        let staged_project = project_file.with_extension("json.trim.tmp");
+        let written = write_json_file(&staged_events, &events)
+            .and_then(|()| write_json_file(&staged_project, &project));
+        let staged = [
+            (trimmed_video, project_dir.join("raw.mp4")),
+            (staged_events, events_file),
+            (staged_project, project_file.clone()),
+        ];
+        if let Err(err) = written {
+            for (staged_path, _) in &staged {
+                let _ = std::fs::remove_file(staged_path);
+            }
+            return Err(err);
+        }
+
+        {
+            let _guard = PROJECT_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            for (staged_path, target) in &staged {
+                std::fs::rename(staged_path, target).map_err(|e| {
+                    format!(
+                        "Failed to replace {}: {e}; the originals are kept as *.original.*",
+                        target.display()
+                    )
+                })?;
+            }
+        }
+
+        // Главы построены по старой шкале времени.
+        let _ = std::fs::remove_file(project_dir.join(CHAPTERS_METADATA_FILE));
+
+        if !had_proxy {
+            return Ok(project);
+        }
+        match build_editor_proxy(&project_dir, &ProxyOptions::default()) {
+            Ok(name) => patch_project_file(&project_file, |project| {
+                project.proxy_video_path = Some(name)
+            }),
+            Err(err) => {
+                log::warn!("trim_recording: proxy rebuild failed, using raw.mp4: {err}");
+                Ok(project)
+            }
+        }
+    })
+    .await
+    .map_err(|e| format!("Trim task join error: {e}"))?
+}
+
+fn write_json_file(path: &Path, value: &impl Serialize) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {e}", path.display()))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Один раз копирует исходные `raw.mp4`, события и `project.json` рядом с ними.
+fn backup_original_recording(
+    project_dir: &Path,
+    project_file: &Path,
+    events_file: &Path,
+) -> Result<(), String> {
+    let raw_video = project_dir.join("raw.mp4");
+    let backups = [
+        (raw_video.as_path(), "raw.original.mp4"),
+        (events_file, "events.original.json"),
+        (project_file, "project.original.json"),
+    ];
+    for (source, backup_name) in backups {
+        let backup = project_dir.join(backup_name);
+        if backup.exists() || !source.exists() {
+            continue;
+        }
+        std::fs::copy(source, &backup).map_err(|e| {
+            format!(
+                "Failed to back up {} to {}: {e}",
+                source.display(),
+                backup.display()
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Пересечение `[start_ts, end_ts)` с диапазоном обрезки в новой шкале времени.
+fn trim_time_range(start_ts: u64, end_ts: u64, start_ms: u64, end_ms: u64) -> Option<(u64, u64)> {
+    let start = start_ts.max(start_ms);
+    let end = end_ts.min(end_ms);
+    (end > start).then(|| (start - start_ms, end - start_ms))
+}
+
+/// Сдвигает сегменты в новую шкалу времени. Если начало обрезки попадает внутрь
+/// траектории, в точке обрезки добавляется интерполированная точка, чтобы камера
+/// стартовала там же, где была в исходной записи.
+fn trim_zoom_segments(segments: Vec<ZoomSegment>, start_ms: u64, end_ms: u64) -> Vec<ZoomSegment> {
+    let in_range = |ts: u64| (start_ms..=end_ms).contains(&ts);
+    segments
+        .into_iter()
+        .filter_map(|mut segment| {
+            let (start_ts, end_ts) =
+                trim_time_range(segment.start_ts, segment.end_ts, start_ms, end_ms)?;
+            segment.start_ts = start_ts;
+            segment.end_ts = end_ts;
+            if let Some(point) = target_point_at(&segment.target_points, start_ms) {
+                segment.target_points.insert(0, point);
+            }
+            if let Some(keyframe) = pan_keyframe_at(&segment.pan_trajectory, start_ms) {
+                segment.pan_trajectory.insert(0, keyframe);
+            }
+            segment.target_points.retain(|point| in_range(point.ts));
+            for point in &mut segment.target_points {
+                point.ts -= start_ms;
+            }
+            segment
+                .pan_trajectory
+                .retain(|keyframe| in_range(keyframe.ts));
+            for keyframe in &mut segment.pan_trajectory {
+                keyframe.ts -= start_ms;
+            }
+            Some(segment)
+        })
+        .collect()
+}
+
+/// Точка траектории в момент `ts`, если `ts` лежит после первой точки и не совпадает ни с одной
+/// из них: между соседними точками — линейная интерполяция, после последней — её копия.
+fn target_point_at(points: &[TargetPoint], ts: u64) -> Option<TargetPoint> {
+    let next_index = points.partition_point(|point| point.ts < ts);
+    let previous = points.get(next_index.checked_sub(1)?)?;
+    let Some(next) = points.get(next_index) else {
+        return Some(TargetPoint {
+            ts,
+            ..previous.clone()
+        });
+    };
+    if next.ts == ts {
+        return None;
+    }
+    let t = (ts - previous.ts) as f64 / (next.ts - previous.ts) as f64;
+    let lerp = |from: f64, to: f64| from + (to - from) * t;
+    Some(TargetPoint {
+        ts,
+        rect: NormalizedRect {
+            x: lerp(previous.rect.x, next.rect.x),
+            y: lerp(previous.rect.y, next.rect.y),
+            width: lerp(previous.rect.width, next.rect.width),
+            height: lerp(previous.rect.height, next.rect.height),
+        },
+        zoom: match (previous.zoom, next.zoom) {
+            (Some(from), Some(to)) => Some(lerp(from, to)),
+            (zoom, _) => zoom,
+        },
+    })
+}
+
+/// Ключевой кадр панорамирования в момент `ts`, по тем же правилам, что [`target_point_at`].
+fn pan_keyframe_at(keyframes: &[PanKeyframe], ts: u64) -> Option<PanKeyframe> {
+    let next_index = keyframes.partition_point(|keyframe| keyframe.ts < ts);
+    let previous = keyframes.get(next_index.checked_sub(1)?)?;
+    let Some(next) = keyframes.get(next_index) else {
+        return Some(PanKeyframe {
+            ts,
+            ..previous.clone()
+        });
+    };
+    if next.ts == ts {
+        return None;
+    }
+    let t = (ts - previous.ts) as f64 / (next.ts - previous.ts) as f64;
+    Some(PanKeyframe {
+        ts,
+        offset_x: previous.offset_x + (next.offset_x - previous.offset_x) * t,
+        offset_y: previous.offset_y + (next.offset_y - previous.offset_y) * t,
+    })
 }
 
 /// Сохраняет проект в `project.json`.
@@ -109,6 +1349,7 @@ pub async fn save_project(
             project.schema_version
         ));
     }
+    project.settings.camera.validate()?;
 
     let path = match project_path {
         Some(path) if !path.trim().is_empty() => resolve_project_file(&path)?,
@@ -124,18 +1365,56 @@ pub async fn save_project(
         })?;
     }
 
-    let json = serde_json::to_string_pretty(&project)
-        .map_err(|e| format!("Failed to serialize project {}: {e}", project.id))?;
-    std::fs::write(&path, json)
-        .map_err(|e| format!("Failed to write project file {}: {e}", path.display()))?;
+    let _guard = PROJECT_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    write_project_json(&path, &project)?;
 
     log::info!("save_project: id={} path={}", project.id, path.display());
     Ok(path.to_string_lossy().to_string())
 }
 
+/// Serializes `project.json` writes between editor saves and background jobs.
+static PROJECT_WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Re-reads `project.json`, applies `patch` and writes the result back.
+///
+/// Long background jobs (proxy builds) use this instead of saving the snapshot they loaded
+/// when they started, so edits saved in the meantime are kept.
+pub(crate) fn patch_project_file(
+    project_file: &Path,
+    patch: impl FnOnce(&mut Project),
+) -> Result<Project, String> {
+    let _guard = PROJECT_WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let json = std::fs::read_to_string(project_file)
+        .map_err(|e| format!("Failed to read {}: {e}", project_file.display()))?;
+    let mut project: Project = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse {}: {e}", project_file.display()))?;
+    patch(&mut project);
+    write_project_json(project_file, &project)?;
+    Ok(project)
+}
+
+/// Writes through a temporary file so readers never see a half-written `project.json`.
+fn write_project_json(path: &Path, project: &Project) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(project)
+        .map_err(|e| format!("Failed to serialize project {}: {e}", project.id))?;
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, json)
+        .map_err(|e| format!("Failed to write project file {}: {e}", temp_path.display()))?;
+    std::fs::rename(&temp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp_path);
+        format!("Failed to write project file {}: {e}", path.display())
+    })
+}
+
 /// Возвращает список проектов из стандартной папки `{Videos}/NeuroScreenCaster`.
+///
+/// `query` отбирает проекты по названию, дате создания и метке и задаёт сортировку;
+/// без него возвращаются все проекты, новые первыми.
 #[tauri::command]
-pub async fn list_projects() -> Result<Vec<ProjectListItem>, String> {
+pub async fn list_projects(
+    query: Option<ProjectListQuery>,
+) -> Result<Vec<ProjectListItem>, String> {
+    let query = query.unwrap_or_default();
     let root = projects_root()?;
     if !root.exists() {
         return Ok(Vec::new());
@@ -204,6 +1483,8 @@ pub async fn list_projects() -> Result<Vec<ProjectListItem>, String> {
         projects.push(ProjectListItem {
             id: project.id,
             name: project.name,
+            description: project.description,
+            tags: project.tags,
             created_at: project.created_at,
             duration_ms: project.duration_ms,
             video_width: project.video_width,
@@ -214,15 +1495,96 @@ pub async fn list_projects() -> Result<Vec<ProjectListItem>, String> {
         });
     }
 
-    projects.sort_by(|a, b| {
-        b.created_at
-            .cmp(&a.created_at)
-            .then(b.modified_time_ms.cmp(&a.modified_time_ms))
-    });
-
+    projects.retain(|item| query.matches(item));
+    query.sort(&mut projects);
     Ok(projects)
 }
 
+/// Удаляет папку проекта целиком: видео, прокси, события, project.json и экспорты внутри.
+///
+/// Удаляются только папки внутри `{Videos}/NeuroScreenCaster`; проект, который сейчас
+/// записывается, экспортируется или для которого собирается прокси, не удаляется.
+#[tauri::command]
+pub async fn delete_project(
+    recorder: tauri::State<'_, RecorderState>,
+    export: tauri::State<'_, ExportState>,
+    proxy: tauri::State<'_, ProxyState>,
+    project_path: String,
+) -> Result<(), String> {
+    let project_file = resolve_project_file(&project_path)?;
+    let project_dir = project_file
+        .parent()
+        .ok_or_else(|| {
+            format!(
+                "Project file has no parent directory: {}",
+                project_file.display()
+            )
+        })?
+        .canonicalize()
+        .map_err(|e| format!("Project not found at {}: {e}", project_file.display()))?;
+
+    let root = projects_root()?;
+    let root = root.canonicalize().unwrap_or(root);
+    if project_dir.parent() != Some(root.as_path()) {
+        return Err(format!(
+            "Refusing to delete {}: not a project folder inside {}",
+            project_dir.display(),
+            root.display()
+        ));
+    }
+    if !project_dir.join("project.json").is_file() {
+        return Err(format!(
+            "Refusing to delete {}: project.json not found",
+            project_dir.display()
+        ));
+    }
+
+    let same_dir = |path: &Path| path.canonicalize().is_ok_and(|path| path == project_dir);
+    if let Some(active) = recorder.0.lock().await.as_ref() {
+        if same_dir(&active.output_dir) {
+            return Err("Cannot delete a project while it is being recorded".to_string());
+        }
+    }
+    {
+        let status = export
+            .0
+            .lock()
+            .map_err(|_| "Failed to access export status".to_string())?;
+        let exporting = status.is_running
+            && [status.project_dir.as_deref(), status.output_path.as_deref()]
+                .into_iter()
+                .flatten()
+                .any(|path| {
+                    let path = Path::new(path);
+                    same_dir(path) || path.parent().is_some_and(|parent| same_dir(parent))
+                });
+        if exporting {
+            return Err("Cannot delete a project while it is being exported".to_string());
+        }
+    }
+    {
+        // Папка проекта названа по id записи, которым помечены фоновые сборки прокси.
+        let jobs = proxy
+            .0
+            .lock()
+            .map_err(|_| "Failed to access proxy status".to_string())?;
+        let building_proxy = project_dir
+            .file_name()
+            .and_then(|name| jobs.get(name.to_string_lossy().as_ref()))
+            .is_some_and(|status| status.is_running);
+        if building_proxy {
+            return Err(
+                "Cannot delete a project while its editor proxy is being built".to_string(),
+            );
+        }
+    }
+
+    std::fs::remove_dir_all(&project_dir)
+        .map_err(|e| format!("Failed to delete project {}: {e}", project_dir.display()))?;
+    log::info!("delete_project: removed {}", project_dir.display());
+    Ok(())
+}
+
 fn resolve_project_file(path: &str) -> Result<PathBuf, String> {
     let trimmed = path.trim();
     if trimmed.is_empty() {
@@ -255,3 +1617,116 @@ fn projects_root() -> Result<PathBuf, String> {
         .ok_or("Failed to resolve Videos directory")?;
     Ok(base.join("NeuroScreenCaster"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, created_at: u64, duration_ms: u64, tags: &[&str]) -> ProjectListItem {
+        ProjectListItem {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: None,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            created_at,
+            duration_ms,
+            video_width: 1920,
+            video_height: 1080,
+            project_path: String::new(),
+            folder_path: String::new(),
+            modified_time_ms: created_at,
+        }
+    }
+
+    fn select(projects: &[ProjectListItem], query: ProjectListQuery) -> Vec<String> {
+        let mut selected = projects
+            .iter()
+            .filter(|item| query.matches(item))
+            .cloned()
+            .collect::<Vec<_>>();
+        query.sort(&mut selected);
+        selected.into_iter().map(|item| item.name).collect()
+    }
+
+    #[test]
+    fn project_query_filters_and_sorts() {
+        let mut described = item("Standup", 3_000, 5_000, &["Work"]);
+        described.description = Some("Weekly demo".to_string());
+        let projects = vec![
+            item("Demo intro", 1_000, 20_000, &["demo", "work"]),
+            item("bug report", 2_000, 10_000, &[]),
+            described,
+        ];
+
+        assert_eq!(
+            select(&projects, ProjectListQuery::default()),
+            ["Standup", "bug report", "Demo intro"]
+        );
+        assert_eq!(
+            select(
+                &projects,
+                ProjectListQuery {
+                    name: Some(" DEMO ".to_string()),
+                    ..ProjectListQuery::default()
+                }
+            ),
+            ["Standup", "Demo intro"]
+        );
+        assert_eq!(
+            select(
+                &projects,
+                ProjectListQuery {
+                    tag: Some("work".to_string()),
+                    sort: ProjectSort::Duration,
+                    ..ProjectListQuery::default()
+                }
+            ),
+            ["Demo intro", "Standup"]
+        );
+        assert_eq!(
+            select(
+                &projects,
+                ProjectListQuery {
+                    created_from: Some(1_500),
+                    created_to: Some(3_000),
+                    sort: ProjectSort::Name,
+                    ..ProjectListQuery::default()
+                }
+            ),
+            ["bug report", "Standup"]
+        );
+        assert_eq!(
+            select(
+                &projects,
+                ProjectListQuery {
+                    sort: ProjectSort::Name,
+                    descending: Some(true),
+                    ..ProjectListQuery::default()
+                }
+            ),
+            ["Standup", "Demo intro", "bug report"]
+        );
+    }
+
+    #[test]
+    fn trim_start_inside_a_trajectory_keeps_the_interpolated_camera() {
+        let point = |ts: u64, x: f64| TargetPoint {
+            ts,
+            rect: NormalizedRect {
+                x,
+                y: 0.0,
+                width: 0.5,
+                height: 0.5,
+            },
+            zoom: None,
+        };
+        let points = [point(1_000, 0.0), point(3_000, 0.4)];
+
+        let cut = target_point_at(&points, 2_000).expect("interpolated point");
+        assert_eq!(cut.ts, 2_000);
+        assert!((cut.rect.x - 0.2).abs() < 1e-9);
+        assert!(target_point_at(&points, 500).is_none());
+        assert!(target_point_at(&points, 3_000).is_none());
+        assert!((target_point_at(&points, 4_000).expect("held point").rect.x - 0.4).abs() < 1e-9);
+    }
+}
@@ -1,16 +1,25 @@
 pub mod algorithm;
 pub mod capture;
+pub mod cli;
 pub mod commands;
+pub mod logging;
 pub mod models;
 pub mod telemetry;
 
 use capture::preview::NativePreviewState;
 use capture::state::RecorderState;
+use commands::capture::{CaptureExclusionState, ProxyState};
 use commands::export::ExportState;
+use commands::project::{CameraSegmentsCache, CursorTrackCache};
+use tauri::Manager;
 use telemetry::logger::{spawn_rdev_thread, TelemetryGlobal, TelemetryState};
 
 pub fn run() {
-    env_logger::init();
+    logging::init();
+
+    if let Some(exit_code) = cli::run(std::env::args().skip(1)) {
+        std::process::exit(exit_code);
+    }
 
     let telemetry_global = TelemetryGlobal::new();
     spawn_rdev_thread(telemetry_global.clone());
@@ -18,30 +27,91 @@ pub fn run() {
     tauri::Builder::default()
         .manage(RecorderState::new())
         .manage(NativePreviewState::new())
-        .manage(TelemetryState(telemetry_global))
+        .manage(TelemetryState(telemetry_global.clone()))
         .manage(ExportState::default())
+        .manage(ProxyState::default())
+        .manage(CaptureExclusionState::default())
+        .manage(CursorTrackCache::default())
+        .manage(CameraSegmentsCache::default())
+        .on_page_load(|webview, _payload| {
+            // Windows opened mid-recording (e.g. the control overlay) must stay out of capture.
+            let exclusion = webview.state::<CaptureExclusionState>();
+            if exclusion.is_active() {
+                if let Err(err) = webview.window().set_content_protected(true) {
+                    log::warn!("Failed to exclude {} from capture: {err}", webview.label());
+                }
+            }
+        })
+        .setup(move |app| {
+            commands::app::spawn_hotkey_dispatcher(app.handle().clone(), telemetry_global);
+            Ok(())
+        })
         .plugin(tauri_plugin_shell::init())
         .invoke_handler(tauri::generate_handler![
             commands::app::exit_application,
+            commands::app::open_in_file_manager,
+            commands::app::get_log_path,
+            commands::app::open_logs,
+            commands::app::set_ffmpeg_path,
+            commands::app::set_keep_video_only_backup,
+            commands::app::get_hotkeys,
+            commands::app::set_hotkeys,
             commands::capture::start_native_preview,
             commands::capture::get_native_preview_frame,
             commands::capture::stop_native_preview,
             commands::capture::is_ctrl_pressed,
             commands::capture::list_audio_input_devices,
+            commands::capture::list_system_audio_outputs,
             commands::capture::start_recording,
+            commands::capture::start_multi_monitor_recording,
+            commands::capture::save_capture_profile,
+            commands::capture::list_capture_profiles,
+            commands::capture::start_recording_from_profile,
             commands::capture::stop_recording,
             commands::capture::pause_recording,
             commands::capture::resume_recording,
             commands::capture::set_recording_cursor_visibility,
+            commands::capture::get_recording_stats,
+            commands::capture::check_recording_capabilities,
+            commands::capture::get_proxy_status,
             commands::cursor::get_cursor_asset_info,
+            #[cfg(feature = "dev-tools")]
+            commands::dev::render_synthetic_export,
             commands::export::start_export,
+            commands::export::start_multi_rendition_export,
             commands::export::cancel_export,
             commands::export::pick_export_folder,
             commands::export::get_export_status,
             commands::export::reset_export_status,
+            commands::export::recommend_export_settings,
+            commands::export::estimate_export_size,
+            commands::export::benchmark_export,
+            commands::export::export_still,
+            commands::project::generate_chapters,
             commands::project::get_project,
             commands::project::get_events,
+            commands::project::normalize_events,
+            commands::project::convert_events_from_logical,
+            commands::project::get_timeline_summary,
+            commands::project::get_activity_summary,
+            commands::project::suggest_auto_pauses,
+            commands::project::generate_click_heatmap,
+            commands::project::cursor_at,
+            commands::project::set_project_metadata,
+            commands::project::set_segment_label,
+            commands::project::clear_segment_label,
+            commands::project::set_target_point_zoom,
+            commands::project::preview_camera_track,
+            commands::project::debug_camera_decisions,
+            commands::project::compute_camera_segments,
+            commands::project::sample_segment_camera,
+            commands::project::regenerate_auto_zoom,
+            commands::project::set_export_aspect_ratio,
+            commands::project::rebuild_proxy,
+            commands::project::delete_video_only_backup,
+            commands::project::trim_recording,
             commands::project::list_projects,
+            commands::project::delete_project,
             commands::project::save_project,
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,218 @@
+//! Application log: `log::` records go to a size-capped rotating file in the app data
+//! directory so packaged builds leave something to attach to bug reports.
+//!
+//! Debug builds additionally keep the `env_logger` stderr output.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+const LOG_FILE_NAME: &str = "neuroscreencaster.log";
+/// Active file is rotated once it grows past this size.
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept next to the active one (`.1` is the newest).
+const MAX_ROTATED_FILES: u32 = 4;
+/// Rotated files older than this are removed on startup.
+const MAX_LOG_AGE: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
+struct LogFile {
+    file: File,
+    written: u64,
+}
+
+struct FileLogger {
+    path: PathBuf,
+    level: LevelFilter,
+    file: Mutex<Option<LogFile>>,
+    stderr: Option<env_logger::Logger>,
+}
+
+/// Directory holding the log files: `{LocalData}/NeuroScreenCaster/logs/`.
+pub fn log_dir() -> Result<PathBuf, String> {
+    let base = dirs::data_local_dir()
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local").join("share")))
+        .ok_or("Failed to resolve app data directory")?;
+
+    Ok(base.join("NeuroScreenCaster").join("logs"))
+}
+
+/// Path of the active log file.
+pub fn log_file_path() -> Result<PathBuf, String> {
+    Ok(log_dir()?.join(LOG_FILE_NAME))
+}
+
+/// Installs the global logger. Falls back to stderr-only logging when the log file
+/// cannot be opened.
+pub fn init() {
+    let stderr = cfg!(debug_assertions).then(|| {
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).build()
+    });
+
+    let opened = log_file_path().and_then(|path| {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+            remove_stale_logs(dir);
+        }
+        let file = open_log_file(&path)?;
+        Ok((path, file))
+    });
+
+    let (path, file) = match opened {
+        Ok((path, file)) => (path, Some(file)),
+        Err(err) => {
+            env_logger::init();
+            log::warn!("logging: file log disabled: {err}");
+            return;
+        }
+    };
+
+    let level = stderr
+        .as_ref()
+        .map(|logger| logger.filter().max(LevelFilter::Info))
+        .unwrap_or(LevelFilter::Info);
+    let logger = FileLogger {
+        path,
+        level,
+        file: Mutex::new(file),
+        stderr,
+    };
+
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if let Some(stderr) = &self.stderr {
+            if stderr.enabled(record.metadata()) {
+                stderr.log(record);
+            }
+        }
+        if record.level() > LevelFilter::Info {
+            return;
+        }
+
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        let Ok(mut guard) = self.file.lock() else {
+            return;
+        };
+        if guard
+            .as_ref()
+            .is_some_and(|file| file.written + line.len() as u64 > MAX_LOG_FILE_BYTES)
+        {
+            *guard = None;
+            rotate_logs(&self.path);
+            *guard = open_log_file(&self.path).ok();
+        }
+        if let Some(file) = guard.as_mut() {
+            if file.file.write_all(line.as_bytes()).is_ok() {
+                file.written += line.len() as u64;
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut guard) = self.file.lock() {
+            if let Some(file) = guard.as_mut() {
+                let _ = file.file.flush();
+            }
+        }
+    }
+}
+
+fn open_log_file(path: &Path) -> Result<LogFile, String> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open log file {}: {e}", path.display()))?;
+    let written = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+    Ok(LogFile { file, written })
+}
+
+fn rotated_path(path: &Path, index: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+/// Shifts `log` -> `log.1` -> `log.2` ..., dropping the oldest file.
+fn rotate_logs(path: &Path) {
+    let _ = std::fs::remove_file(rotated_path(path, MAX_ROTATED_FILES));
+    for index in (1..MAX_ROTATED_FILES).rev() {
+        let from = rotated_path(path, index);
+        if from.exists() {
+            let _ = std::fs::rename(&from, rotated_path(path, index + 1));
+        }
+    }
+    let _ = std::fs::rename(path, rotated_path(path, 1));
+}
+
+fn remove_stale_logs(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_rotated = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(LOG_FILE_NAME) && name != LOG_FILE_NAME);
+        if !is_rotated {
+            continue;
+        }
+        let expired = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > MAX_LOG_AGE);
+        if expired {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_shifts_files_and_drops_the_oldest() {
+        let dir = std::env::temp_dir().join(format!("nsc-log-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(LOG_FILE_NAME);
+
+        for generation in 0..=MAX_ROTATED_FILES + 1 {
+            std::fs::write(&path, generation.to_string()).unwrap();
+            rotate_logs(&path);
+        }
+
+        assert!(!path.exists());
+        let newest = std::fs::read_to_string(rotated_path(&path, 1)).unwrap();
+        assert_eq!(newest, (MAX_ROTATED_FILES + 1).to_string());
+        let oldest = std::fs::read_to_string(rotated_path(&path, MAX_ROTATED_FILES)).unwrap();
+        assert_eq!(oldest, "2");
+        assert!(!rotated_path(&path, MAX_ROTATED_FILES + 1).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,118 @@
+//! Настройки приложения между запусками (app-settings.json).
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+const APP_SETTINGS_FILE: &str = "app-settings.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    /// Последний выбранный монитор для превью и записи.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_monitor_index: Option<u32>,
+    /// Именованные профили захвата.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub capture_profiles: Vec<CaptureProfile>,
+    /// Путь к FFmpeg, выбранному пользователем (например, сборка с NVENC);
+    /// проверяется раньше встроенного и системного.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ffmpeg_path: Option<String>,
+    /// Сохранять исходное видео без звука (`raw-video-only.mp4`) после сведения
+    /// звука в `raw.mp4`; по умолчанию копия удаляется после успешного сведения.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keep_video_only_backup: bool,
+    /// Глобальные горячие клавиши управления записью.
+    #[serde(default, skip_serializing_if = "HotkeyBindings::is_empty")]
+    pub hotkeys: HotkeyBindings,
+}
+
+/// Сочетания клавиш в виде `Ctrl+Shift+R`; `None` — действие без горячей клавиши.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyBindings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toggle_recording: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toggle_pause: Option<String>,
+}
+
+impl HotkeyBindings {
+    pub fn is_empty(&self) -> bool {
+        self.toggle_recording.is_none() && self.toggle_pause.is_none()
+    }
+}
+
+/// Монитор, узнаваемый после переподключения: индекс меняется при смене док-станции,
+/// поэтому профиль хранит имя, системное имя устройства и разрешение.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorIdentity {
+    /// Имя монитора из EDID (например, «DELL U2720Q»).
+    pub name: String,
+    /// Системное имя устройства (`\\.\DISPLAY2`); может смениться при переподключении.
+    pub device_name: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Именованный набор параметров записи.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureProfile {
+    pub name: String,
+    pub monitor: MonitorIdentity,
+    /// Параметры `StartRecordingOptions` в том виде, в каком их передал UI.
+    #[serde(default)]
+    pub options: serde_json::Value,
+}
+
+/// Путь к файлу настроек: `{Config}/NeuroScreenCaster/app-settings.json`.
+fn app_settings_path() -> Result<PathBuf, String> {
+    let base = dirs::config_dir()
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))
+        .ok_or("Failed to resolve config directory")?;
+
+    Ok(base.join("NeuroScreenCaster").join(APP_SETTINGS_FILE))
+}
+
+/// Читает настройки; отсутствующий или повреждённый файл даёт значения по умолчанию.
+pub fn load_app_settings() -> AppSettings {
+    let path = match app_settings_path() {
+        Ok(path) => path,
+        Err(err) => {
+            log::warn!("app_settings: {err}");
+            return AppSettings::default();
+        }
+    };
+    let Ok(raw) = std::fs::read_to_string(&path) else {
+        return AppSettings::default();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|err| {
+        log::warn!("app_settings: ignoring invalid {}: {err}", path.display());
+        AppSettings::default()
+    })
+}
+
+pub fn save_app_settings(settings: &AppSettings) -> Result<(), String> {
+    let path = app_settings_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize app settings: {e}"))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Обновляет настройки через `update` и сохраняет их, если что-то изменилось.
+pub fn update_app_settings(update: impl FnOnce(&mut AppSettings)) -> Result<(), String> {
+    let mut settings = load_app_settings();
+    let before = settings.clone();
+    update(&mut settings);
+    if settings == before {
+        return Ok(());
+    }
+    save_app_settings(&settings)
+}
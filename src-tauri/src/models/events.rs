@@ -15,10 +15,22 @@ pub struct BoundingRect {
     pub height: u32,
 }
 
+impl BoundingRect {
+    /// Масштабирует прямоугольник независимо по осям (с округлением до пикселя).
+    pub fn scale(&mut self, scale_x: f64, scale_y: f64) {
+        self.x = (self.x as f64 * scale_x).round() as i32;
+        self.y = (self.y as f64 * scale_y).round() as i32;
+        self.width = (self.width as f64 * scale_x).round() as u32;
+        self.height = (self.height as f64 * scale_y).round() as u32;
+    }
+}
+
 /// Контекст UI-элемента, полученный через UI Automation при клике.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UiContext {
+    /// Имя исполняемого файла приложения без расширения (например, `chrome`).
+    /// В записях старых версий — `pid:<id>`.
     pub app_name: Option<String>,
     pub control_name: Option<String>,
     pub bounding_rect: Option<BoundingRect>,
@@ -81,6 +93,10 @@ pub enum InputEvent {
         ts: u64,
         #[serde(rename = "keyCode", alias = "key_code")]
         key_code: String,
+        /// Положение текстовой каретки (физические экранные координаты), если ОС его
+        /// сообщает; снимается с прореживанием, поэтому есть не у каждого нажатия.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        caret: Option<BoundingRect>,
     },
     /// Отпускание клавиши.
     KeyUp {
@@ -102,6 +118,17 @@ impl InputEvent {
             InputEvent::KeyUp { ts, .. } => *ts,
         }
     }
+
+    /// Возвращает координаты указателя; у клавиатурных событий их нет.
+    pub fn position(&self) -> Option<(f64, f64)> {
+        match self {
+            InputEvent::Move { x, y, .. }
+            | InputEvent::Click { x, y, .. }
+            | InputEvent::MouseUp { x, y, .. }
+            | InputEvent::Scroll { x, y, .. } => Some((*x, *y)),
+            InputEvent::KeyDown { .. } | InputEvent::KeyUp { .. } => None,
+        }
+    }
 }
 
 /// Корневой контейнер файла events.json.
@@ -118,9 +145,111 @@ pub struct EventsFile {
     pub screen_height: u32,
     /// DPI scale (например 1.25 для 125%).
     pub scale_factor: f64,
+    /// Пиксели, в которых записаны координаты событий; `None` — неизвестно (старые записи).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coordinate_space: Option<CoordinateSpace>,
     pub events: Vec<InputEvent>,
 }
 
+/// Система координат событий мыши.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CoordinateSpace {
+    /// Физические пиксели, как у захваченного видео (`screen_width`×`screen_height`).
+    Physical,
+    /// Логические пиксели Windows: физические, делённые на `scale_factor`.
+    Logical,
+}
+
+impl EventsFile {
+    /// Множитель, переводящий координаты событий в физические пиксели экрана.
+    ///
+    /// Файлы без пометки считаются физическими: хук пишет координаты DPI-aware процесса.
+    /// Логическими они становятся только по явной просьбе ([`Self::convert_from_logical`]).
+    pub fn physical_scale(&self) -> f64 {
+        match self.coordinate_space {
+            Some(CoordinateSpace::Logical)
+                if self.scale_factor.is_finite() && self.scale_factor > 1.0 =>
+            {
+                self.scale_factor
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Явно переводит файл без пометки из логических пикселей в физические — для старых
+    /// записей, где хук писал DPI-масштабированные координаты.
+    ///
+    /// Файлы, уже помеченные как `Physical`, не меняются.
+    pub fn convert_from_logical(&mut self) -> Result<(), String> {
+        if self.coordinate_space == Some(CoordinateSpace::Physical) {
+            return Err("Events are already stored in physical pixels".to_string());
+        }
+        self.coordinate_space = Some(CoordinateSpace::Logical);
+        self.normalize_to_physical();
+        Ok(())
+    }
+
+    /// Переводит координаты событий в физические пиксели и помечает файл как `Physical`.
+    ///
+    /// Повторный вызов ничего не меняет.
+    pub fn normalize_to_physical(&mut self) {
+        let scale = self.physical_scale();
+        if scale != 1.0 {
+            self.scale_events(scale, scale);
+        }
+        self.coordinate_space = Some(CoordinateSpace::Physical);
+    }
+
+    /// Масштабирует физические координаты под видео `width`×`height` (запись в уменьшенном
+    /// разрешении) и записывает этот размер в `screen_width`/`screen_height`.
+    ///
+    /// Вызывается после [`Self::normalize_to_physical`]; при совпадающем размере ничего не
+    /// меняет.
+    pub fn scale_to_video_size(&mut self, width: u32, height: u32) {
+        if (width, height) == (self.screen_width, self.screen_height) {
+            return;
+        }
+        let scale_x = width as f64 / self.screen_width.max(1) as f64;
+        let scale_y = height as f64 / self.screen_height.max(1) as f64;
+        self.scale_events(scale_x, scale_y);
+        self.screen_width = width;
+        self.screen_height = height;
+    }
+
+    /// Масштабирует позиции событий вместе с прямоугольниками UI-элементов и каретки.
+    fn scale_events(&mut self, scale_x: f64, scale_y: f64) {
+        for event in &mut self.events {
+            match event {
+                InputEvent::Move { x, y, .. }
+                | InputEvent::MouseUp { x, y, .. }
+                | InputEvent::Scroll { x, y, .. } => {
+                    *x *= scale_x;
+                    *y *= scale_y;
+                }
+                InputEvent::Click {
+                    x, y, ui_context, ..
+                } => {
+                    *x *= scale_x;
+                    *y *= scale_y;
+                    if let Some(rect) = ui_context
+                        .as_mut()
+                        .and_then(|context| context.bounding_rect.as_mut())
+                    {
+                        rect.scale(scale_x, scale_y);
+                    }
+                }
+                InputEvent::KeyDown { caret, .. } => {
+                    if let Some(rect) = caret {
+                        rect.scale(scale_x, scale_y);
+                    }
+                }
+                InputEvent::KeyUp { .. } => {}
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,6 +283,7 @@ mod tests {
         let event = InputEvent::KeyDown {
             ts: 100,
             key_code: "KeyA".to_string(),
+            caret: None,
         };
 
         let json = serde_json::to_string(&event).expect("serialize keyDown");
@@ -161,6 +291,142 @@ mod tests {
         assert!(!json.contains("\"key_code\""));
     }
 
+    #[test]
+    fn logical_coordinates_are_scaled_to_physical_pixels() {
+        let mut events = EventsFile {
+            schema_version: SCHEMA_VERSION,
+            recording_id: "rec".to_string(),
+            start_time_ms: 0,
+            screen_width: 2880,
+            screen_height: 1620,
+            scale_factor: 1.5,
+            coordinate_space: None,
+            events: vec![
+                InputEvent::Move {
+                    ts: 0,
+                    x: 1920.0,
+                    y: 1080.0,
+                },
+                InputEvent::KeyDown {
+                    ts: 1,
+                    key_code: "KeyA".to_string(),
+                    caret: Some(BoundingRect {
+                        x: 100,
+                        y: 200,
+                        width: 2,
+                        height: 20,
+                    }),
+                },
+                InputEvent::Click {
+                    ts: 2,
+                    x: 400.0,
+                    y: 200.0,
+                    button: MouseButton::Left,
+                    ui_context: Some(UiContext {
+                        app_name: None,
+                        control_name: None,
+                        bounding_rect: Some(BoundingRect {
+                            x: 300,
+                            y: 100,
+                            width: 200,
+                            height: 40,
+                        }),
+                    }),
+                },
+            ],
+        };
+        // Untagged files are never guessed to be logical: the hook reports physical pixels.
+        assert_eq!(events.physical_scale(), 1.0);
+        let mut untagged = events.clone();
+        untagged.normalize_to_physical();
+        assert_eq!(untagged.events[0].position(), Some((1920.0, 1080.0)));
+        assert_eq!(untagged.coordinate_space, Some(CoordinateSpace::Physical));
+
+        events
+            .convert_from_logical()
+            .expect("untagged file converts");
+        assert_eq!(events.events[0].position(), Some((2880.0, 1620.0)));
+        assert_eq!(events.coordinate_space, Some(CoordinateSpace::Physical));
+        match &events.events[1] {
+            InputEvent::KeyDown {
+                caret: Some(rect), ..
+            } => assert_eq!((rect.x, rect.y, rect.width, rect.height), (150, 300, 3, 30)),
+            _ => panic!("expected keyDown event with a caret"),
+        }
+        match &events.events[2] {
+            InputEvent::Click { ui_context, .. } => {
+                let rect = ui_context
+                    .as_ref()
+                    .and_then(|context| context.bounding_rect.as_ref())
+                    .expect("bounding rect");
+                assert_eq!(
+                    (rect.x, rect.y, rect.width, rect.height),
+                    (450, 150, 300, 60)
+                );
+            }
+            _ => panic!("expected click event"),
+        }
+        events.normalize_to_physical();
+        assert_eq!(events.events[0].position(), Some((2880.0, 1620.0)));
+        assert!(events.convert_from_logical().is_err());
+    }
+
+    #[test]
+    fn physical_coordinates_are_scaled_to_a_reduced_video_size() {
+        let mut events = EventsFile {
+            schema_version: SCHEMA_VERSION,
+            recording_id: "test".to_string(),
+            start_time_ms: 0,
+            screen_width: 3840,
+            screen_height: 2160,
+            scale_factor: 2.0,
+            coordinate_space: Some(CoordinateSpace::Physical),
+            events: vec![
+                InputEvent::Move {
+                    ts: 0,
+                    x: 3000.0,
+                    y: 1000.0,
+                },
+                InputEvent::Click {
+                    ts: 10,
+                    x: 400.0,
+                    y: 200.0,
+                    button: MouseButton::Left,
+                    ui_context: Some(UiContext {
+                        app_name: None,
+                        control_name: None,
+                        bounding_rect: Some(BoundingRect {
+                            x: 300,
+                            y: 100,
+                            width: 201,
+                            height: 40,
+                        }),
+                    }),
+                },
+            ],
+        };
+
+        events.scale_to_video_size(1920, 1080);
+        assert_eq!((events.screen_width, events.screen_height), (1920, 1080));
+        assert_eq!(events.events[0].position(), Some((1500.0, 500.0)));
+        assert_eq!(events.events[1].position(), Some((200.0, 100.0)));
+        match &events.events[1] {
+            InputEvent::Click { ui_context, .. } => {
+                let rect = ui_context
+                    .as_ref()
+                    .and_then(|context| context.bounding_rect.as_ref())
+                    .expect("bounding rect");
+                assert_eq!(
+                    (rect.x, rect.y, rect.width, rect.height),
+                    (150, 50, 101, 20)
+                );
+            }
+            _ => panic!("expected click event"),
+        }
+        // Scaling lives in the events, not in the coordinate space.
+        assert_eq!(events.physical_scale(), 1.0);
+    }
+
     #[test]
     fn accepts_legacy_snake_case_fields_during_deserialization() {
         let click_legacy = r#"{
@@ -1,2 +1,3 @@
+pub mod app_settings;
 pub mod events;
 pub mod project;
@@ -5,6 +5,15 @@ use serde::{Deserialize, Serialize};
 
 pub const SCHEMA_VERSION: u32 = 1;
 
+/// Версия алгоритма умной камеры; повышается, когда для тех же событий он строит
+/// другие авто-сегменты.
+pub const CAMERA_ENGINE_VERSION: u32 = 3;
+
+/// Проекты без `cameraEngineVersion` созданы до введения версии.
+fn legacy_camera_engine_version() -> u32 {
+    1
+}
+
 /// Прямоугольная область в нормализованных координатах (0.0–1.0).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -37,6 +46,32 @@ pub struct PanKeyframe {
 pub struct TargetPoint {
     pub ts: u64,
     pub rect: NormalizedRect,
+    /// Явное приближение в этой точке; `None` — приближение задаётся размером `rect`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom: Option<f64>,
+}
+
+/// Допустимое явное приближение точки траектории.
+pub const TARGET_POINT_ZOOM_RANGE: (f64, f64) = (1.0, 20.0);
+
+impl TargetPoint {
+    /// Прямоугольник, который видит камера: при явном `zoom` `rect` масштабируется вокруг
+    /// своего центра так, чтобы его большая сторона стала `1 / zoom`.
+    pub fn effective_rect(&self) -> NormalizedRect {
+        let Some(zoom) = self.zoom.filter(|zoom| zoom.is_finite()) else {
+            return self.rect.clone();
+        };
+        let (min, max) = TARGET_POINT_ZOOM_RANGE;
+        let scale = 1.0 / zoom.clamp(min, max) / self.rect.width.max(self.rect.height).max(0.0001);
+        let width = self.rect.width * scale;
+        let height = self.rect.height * scale;
+        NormalizedRect {
+            x: self.rect.x + (self.rect.width - width) / 2.0,
+            y: self.rect.y + (self.rect.height - height) / 2.0,
+            width,
+            height,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,6 +148,112 @@ pub struct ZoomSegment {
     /// true — создан алгоритмом, false — пользователем вручную.
     #[serde(default)]
     pub is_auto: bool,
+    /// Подпись, которая выводится поверх видео на время сегмента.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<SegmentLabel>,
+}
+
+/// Максимальная длина текста подписи (символов).
+pub const SEGMENT_LABEL_MAX_CHARS: usize = 200;
+/// Допустимый размер шрифта подписи (доля высоты кадра).
+pub const LABEL_FONT_SCALE_RANGE: (f64, f64) = (0.02, 0.12);
+
+/// Где выводится подпись сегмента.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LabelPosition {
+    /// Сверху или снизу — с той стороны, где нет приближаемой области.
+    Auto,
+    Top,
+    Bottom,
+}
+
+impl Default for LabelPosition {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+/// Подпись зум-сегмента при экспорте.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentLabel {
+    pub text: String,
+    #[serde(default)]
+    pub position: LabelPosition,
+    /// Высота шрифта как доля высоты кадра.
+    #[serde(default = "default_label_font_scale")]
+    pub font_scale: f64,
+    /// Цвет текста в формате `#RRGGBB`.
+    #[serde(default = "default_label_color")]
+    pub color: String,
+    /// Непрозрачность подложки под текстом (0 — без подложки).
+    #[serde(default = "default_label_background_opacity")]
+    pub background_opacity: f64,
+}
+
+fn default_label_font_scale() -> f64 {
+    0.045
+}
+
+fn default_label_color() -> String {
+    "#FFFFFF".to_string()
+}
+
+fn default_label_background_opacity() -> f64 {
+    0.6
+}
+
+impl SegmentLabel {
+    /// Проверяет текст, цвет и числовые параметры подписи.
+    pub fn validate(&self) -> Result<(), String> {
+        let text = self.text.trim();
+        if text.is_empty() {
+            return Err("Segment label text must not be empty".to_string());
+        }
+        let chars = text.chars().count();
+        if chars > SEGMENT_LABEL_MAX_CHARS {
+            return Err(format!(
+                "Segment label must be at most {SEGMENT_LABEL_MAX_CHARS} characters, got {chars}"
+            ));
+        }
+        let (min, max) = LABEL_FONT_SCALE_RANGE;
+        if !(self.font_scale.is_finite() && (min..=max).contains(&self.font_scale)) {
+            return Err(format!(
+                "Segment label fontScale must be within {min}–{max}, got {}",
+                self.font_scale
+            ));
+        }
+        if !(self.background_opacity.is_finite() && (0.0..=1.0).contains(&self.background_opacity))
+        {
+            return Err(format!(
+                "Segment label backgroundOpacity must be within 0–1, got {}",
+                self.background_opacity
+            ));
+        }
+        if self.rgb().is_none() {
+            return Err(format!(
+                "Segment label color must be #RRGGBB, got {}",
+                self.color
+            ));
+        }
+        Ok(())
+    }
+
+    /// Цвет текста как (r, g, b); `None`, если строка не в формате `#RRGGBB`.
+    pub fn rgb(&self) -> Option<(u8, u8, u8)> {
+        parse_hex_rgb(&self.color)
+    }
+}
+
+/// Разбирает цвет `#RRGGBB` в (r, g, b).
+pub fn parse_hex_rgb(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
 }
 
 /// Таймлайн проекта.
@@ -131,8 +272,156 @@ pub struct CursorSettings {
     pub color: String,
     /// 0.0 = нет сглаживания, 1.0 = максимальное.
     pub smoothing_factor: f64,
+    /// Частота пересэмплирования траектории перед сглаживанием (Гц, 30–240).
+    #[serde(default = "default_cursor_resample_hz")]
+    pub resample_hz: f64,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub hidden_ranges: Vec<TimeRange>,
+    /// false — экспорт без синтетического курсора (например, если курсор уже «вшит» в видео).
+    #[serde(default = "default_show_cursor")]
+    pub show_cursor: bool,
+    /// Источник траектории курсора при экспорте.
+    #[serde(default)]
+    pub render_mode: CursorRenderMode,
+    /// Форма векторного курсора при экспорте.
+    #[serde(default)]
+    pub shape: CursorShape,
+    /// Заливка векторного курсора в формате `#RRGGBB`.
+    #[serde(default = "default_cursor_fill_color")]
+    pub fill_color: String,
+    /// Обводка векторного курсора в формате `#RRGGBB`.
+    #[serde(default = "default_cursor_outline_color")]
+    pub outline_color: String,
+    /// Множитель толщины обводки (1.0 — стандартная, 0.0 — без обводки).
+    #[serde(default = "default_cursor_outline_scale")]
+    pub outline_scale: f64,
+    /// Тень или свечение под курсором; `None` — без тени.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow: Option<CursorShadow>,
+}
+
+/// Размытая копия курсора под ним: со смещением — тень, без смещения — свечение.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorShadow {
+    /// Радиус размытия как доля высоты курсора.
+    #[serde(default = "default_cursor_shadow_blur")]
+    pub blur: f64,
+    /// Смещение вправо-вниз как доля высоты курсора (0 — свечение вокруг курсора).
+    #[serde(default = "default_cursor_shadow_offset")]
+    pub offset: f64,
+    /// Цвет тени в формате `#RRGGBB`.
+    #[serde(default = "default_cursor_shadow_color")]
+    pub color: String,
+}
+
+fn default_cursor_shadow_blur() -> f64 {
+    0.08
+}
+
+fn default_cursor_shadow_offset() -> f64 {
+    0.04
+}
+
+fn default_cursor_shadow_color() -> String {
+    "#000000".to_string()
+}
+
+impl Default for CursorShadow {
+    fn default() -> Self {
+        CursorShadow {
+            blur: default_cursor_shadow_blur(),
+            offset: default_cursor_shadow_offset(),
+            color: default_cursor_shadow_color(),
+        }
+    }
+}
+
+impl CursorShadow {
+    /// Цвет как (r, g, b); при неверной строке — чёрный.
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        parse_hex_rgb(&self.color).unwrap_or((0, 0, 0))
+    }
+
+    /// Размытие и смещение в пикселях для курсора высотой `cursor_height_px`.
+    pub fn pixels(&self, cursor_height_px: f64) -> (f64, f64) {
+        let ratio = |value: f64| {
+            if value.is_finite() {
+                value.clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+        (
+            ratio(self.blur) * cursor_height_px,
+            ratio(self.offset) * cursor_height_px,
+        )
+    }
+}
+
+impl CursorSettings {
+    /// Заливка как (r, g, b); при неверной строке — чёрный.
+    pub fn fill_rgb(&self) -> (u8, u8, u8) {
+        parse_hex_rgb(&self.fill_color).unwrap_or((0, 0, 0))
+    }
+
+    /// Обводка как (r, g, b); при неверной строке — белый.
+    pub fn outline_rgb(&self) -> (u8, u8, u8) {
+        parse_hex_rgb(&self.outline_color).unwrap_or((255, 255, 255))
+    }
+}
+
+/// Встроенные формы векторного курсора.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CursorShape {
+    /// Классическая стрелка, остриё — в точке курсора.
+    Arrow,
+    /// Рука с указательным пальцем, кончик пальца — в точке курсора.
+    Hand,
+    /// Перекрестие с центром в точке курсора.
+    Crosshair,
+    /// Круглая точка с центром в точке курсора.
+    Dot,
+}
+
+impl Default for CursorShape {
+    fn default() -> Self {
+        Self::Arrow
+    }
+}
+
+/// Как курсор рисуется при экспорте.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CursorRenderMode {
+    /// Исходные точки движений и кликов без сглаживания.
+    Raw,
+    /// Сглаженная траектория (`smoothing_factor`).
+    #[default]
+    Smoothed,
+    /// Курсор не рисуется.
+    Off,
+}
+
+fn default_show_cursor() -> bool {
+    true
+}
+
+fn default_cursor_resample_hz() -> f64 {
+    crate::algorithm::cursor_smoothing::DEFAULT_RESAMPLE_HZ
+}
+
+fn default_cursor_fill_color() -> String {
+    "#000000".to_string()
+}
+
+fn default_cursor_outline_color() -> String {
+    "#FFFFFF".to_string()
+}
+
+fn default_cursor_outline_scale() -> f64 {
+    1.0
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,7 +437,15 @@ impl Default for CursorSettings {
             size: 1.0,
             color: "#FFFFFF".to_string(),
             smoothing_factor: 0.8,
+            resample_hz: default_cursor_resample_hz(),
             hidden_ranges: Vec::new(),
+            show_cursor: true,
+            render_mode: CursorRenderMode::Smoothed,
+            shape: CursorShape::Arrow,
+            fill_color: default_cursor_fill_color(),
+            outline_color: default_cursor_outline_color(),
+            outline_scale: default_cursor_outline_scale(),
+            shadow: None,
         }
     }
 }
@@ -175,6 +472,92 @@ impl Default for Background {
     }
 }
 
+/// Допустимая сила размытия для `FrameBackground::Blur`.
+pub const FRAME_BACKGROUND_BLUR_SIGMA_RANGE: (f64, f64) = (1.0, 100.0);
+
+/// Чем экспорт заполняет кадр вокруг вписанного видео, когда пропорции не совпадают.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FrameBackground {
+    /// Чёрные поля, как в проектах без этой настройки.
+    #[default]
+    None,
+    /// Поля цвета `#RRGGBB`.
+    Solid { color: String },
+    /// Размытая копия кадра, растянутая на весь выход.
+    Blur { sigma: f64 },
+    /// Картинка (абсолютный путь), растянутая с обрезкой на весь выход.
+    Image { path: String },
+}
+
+impl FrameBackground {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            FrameBackground::None => Ok(()),
+            FrameBackground::Solid { color } => parse_hex_rgb(color)
+                .map(|_| ())
+                .ok_or_else(|| format!("Frame background color must be #RRGGBB, got {color}")),
+            FrameBackground::Blur { sigma } => {
+                let (min, max) = FRAME_BACKGROUND_BLUR_SIGMA_RANGE;
+                if sigma.is_finite() && (min..=max).contains(sigma) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Frame background blur sigma must be within {min}–{max}, got {sigma}"
+                    ))
+                }
+            }
+            FrameBackground::Image { path } => {
+                if path.trim().is_empty() {
+                    Err("Frame background image path is empty".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// Соотношение сторон итогового видео; задаёт и размер экспорта, и кадрирование камеры.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AspectRatio {
+    #[serde(rename = "16:9")]
+    Widescreen,
+    #[serde(rename = "9:16")]
+    Vertical,
+    #[serde(rename = "1:1")]
+    Square,
+    #[serde(rename = "4:3")]
+    Standard,
+}
+
+impl AspectRatio {
+    /// Пропорция в виде `(ширина, высота)`.
+    pub fn ratio(self) -> (u32, u32) {
+        match self {
+            AspectRatio::Widescreen => (16, 9),
+            AspectRatio::Vertical => (9, 16),
+            AspectRatio::Square => (1, 1),
+            AspectRatio::Standard => (4, 3),
+        }
+    }
+
+    /// Размер кадра с заданной короткой стороной (чётные значения для H.264).
+    pub fn frame_size(self, short_side: u32) -> (u32, u32) {
+        let (ratio_w, ratio_h) = self.ratio();
+        let short_side = short_side.max(2) + short_side % 2;
+        let long_side = (short_side as f64 * ratio_w.max(ratio_h) as f64
+            / ratio_w.min(ratio_h) as f64)
+            .round() as u32;
+        let long_side = long_side + long_side % 2;
+        if ratio_w >= ratio_h {
+            (long_side, short_side)
+        } else {
+            (short_side, long_side)
+        }
+    }
+}
+
 /// Настройки экспорта.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -183,6 +566,91 @@ pub struct ExportSettings {
     pub height: u32,
     pub fps: u32,
     pub codec: String,
+    /// Выбранное соотношение сторон; `None` — произвольный размер `width`×`height`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<AspectRatio>,
+    /// Замедление вокруг кликов; `None` — выключено.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub click_slow_motion: Option<ClickSlowMotion>,
+    /// Постоянная область исходного кадра, которая экспортируется вместо всего экрана;
+    /// камера и курсор работают внутри неё. `None` — весь кадр.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crop: Option<NormalizedRect>,
+    /// Качество масштабирования кадра камерой.
+    #[serde(default)]
+    pub scaling_quality: ScalingQuality,
+    /// Как широкий исходник укладывается в более узкий кадр экспорта.
+    #[serde(default)]
+    pub aspect_mode: AspectMode,
+    /// Сдвиг звука относительно видео при экспорте (мс): положительный задерживает звук,
+    /// отрицательный подаёт его раньше. Поправка поверх сдвига, измеренного при записи.
+    #[serde(default)]
+    pub audio_offset_ms: i64,
+}
+
+/// Допустимый сдвиг звука при экспорте (мс).
+pub const AUDIO_OFFSET_MS_RANGE: (i64, i64) = (-5_000, 5_000);
+
+/// Укладка исходника в экспорт другой пропорции.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AspectMode {
+    /// Кадр целиком вписывается в выход, поля заполняются фоном кадра.
+    #[default]
+    Fit,
+    /// Кадр заполняет выход по высоте, а окно кадрирования следует за центром
+    /// активности умной камеры (вертикальные клипы 9:16 из записи экрана).
+    AutoReframe,
+}
+
+/// Как экспорт масштабирует кадр при zoom.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ScalingQuality {
+    /// Однопроходное билинейное масштабирование — быстро, но мелкий текст при
+    /// глубоком zoom мылится.
+    #[default]
+    Fast,
+    /// Камера рендерится в повышенном внутреннем разрешении и сжимается фильтром
+    /// Lanczos: текст остаётся чётким ценой более долгого экспорта.
+    Supersampled,
+}
+
+/// Замедленное воспроизведение (видео, курсор, камера и звук) вокруг каждого клика.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClickSlowMotion {
+    /// Длина замедляемого окна, центрированного на клике (мс, 200–5000).
+    #[serde(default = "default_slow_motion_window_ms")]
+    pub window_ms: u64,
+    /// Во сколько раз замедлить окно (1–4; 2.0 — вдвое медленнее).
+    #[serde(default = "default_slow_motion_factor")]
+    pub factor: f64,
+}
+
+fn default_slow_motion_window_ms() -> u64 {
+    600
+}
+
+fn default_slow_motion_factor() -> f64 {
+    2.0
+}
+
+impl ExportSettings {
+    /// Соотношение сторон, под которое кадрирует камера.
+    ///
+    /// Считается по фактическому размеру: `aspect_ratio` лишь запоминает выбор в UI.
+    pub fn output_aspect_ratio(&self) -> f64 {
+        self.width as f64 / self.height.max(1) as f64
+    }
+
+    /// Применяет соотношение сторон, сохраняя короткую сторону текущего размера.
+    pub fn apply_aspect_ratio(&mut self, aspect_ratio: AspectRatio) {
+        let (width, height) = aspect_ratio.frame_size(self.width.min(self.height));
+        self.width = width;
+        self.height = height;
+        self.aspect_ratio = Some(aspect_ratio);
+    }
 }
 
 impl Default for ExportSettings {
@@ -192,10 +660,211 @@ impl Default for ExportSettings {
             height: 1080,
             fps: 60,
             codec: "h264".to_string(),
+            aspect_ratio: Some(AspectRatio::Widescreen),
+            click_slow_motion: None,
+            crop: None,
+            scaling_quality: ScalingQuality::Fast,
+            aspect_mode: AspectMode::Fit,
+            audio_offset_ms: 0,
+        }
+    }
+}
+
+/// Допустимые значения общей чувствительности камеры.
+pub const CAMERA_SENSITIVITY_RANGE: (f64, f64) = (0.0, 1.0);
+/// Допустимые значения доли мёртвой зоны.
+pub const DEAD_ZONE_RATIO_RANGE: (f64, f64) = (0.05, 0.90);
+/// Допустимые значения дистанции выхода из захвата.
+pub const ESCAPE_DISTANCE_RATIO_RANGE: (f64, f64) = (0.20, 2.00);
+/// Допустимые значения доли жёсткого края.
+pub const HARD_EDGE_RATIO_RANGE: (f64, f64) = (0.05, 0.95);
+/// Допустимый запас вокруг UI-элемента при semantic zoom (доля его размера).
+pub const ZOOM_PADDING_RATIO_RANGE: (f64, f64) = (0.0, 1.5);
+/// Допустимый множитель сдвига камеры при прокрутке.
+pub const SCROLL_FOLLOW_STRENGTH_RANGE: (f64, f64) = (0.0, 3.0);
+/// Допустимый предел приближения авто-zoom.
+pub const MAX_ZOOM_RANGE: (f64, f64) = (1.0, 6.0);
+/// Допустимое окно накопления кликов для активации zoom (мс).
+pub const ACTIVATION_WINDOW_MS_RANGE: (u64, u64) = (250, 10_000);
+/// Допустимое число кликов в окне для активации zoom.
+pub const MIN_CLICKS_TO_ACTIVATE_RANGE: (u64, u64) = (1, 5);
+/// Допустимый разрыв между кликами одного кластера (мс).
+pub const CLICK_CLUSTER_GAP_MS_RANGE: (u64, u64) = (1, 2_000);
+/// Допустимый минимальный интервал между соседними zoom (мс).
+pub const MIN_ZOOM_INTERVAL_MS_RANGE: (u64, u64) = (1, 10_000);
+/// Допустимое опережение zoom относительно клика (мс).
+pub const ZOOM_LEAD_MS_RANGE: (u64, u64) = (0, 2_000);
+
+/// Какие клики запускают авто-zoom.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutoZoomTriggerMode {
+    #[default]
+    SingleClick,
+    MultiClickWindow,
+    CtrlClick,
+}
+
+/// Чувствительность умной камеры.
+///
+/// Один ползунок `sensitivity` масштабирует мёртвую зону, дистанцию выхода и жёсткий
+/// край вместе; явно заданные значения переопределяют результат по отдельности.
+/// Параметры активации и кластеризации кликов переопределяют пресет режима
+/// срабатывания авто-zoom (`AutoZoomTriggerMode`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraSettings {
+    /// 0.0 — камера «липкая», 1.0 — «дёрганая»; 0.5 — значения по умолчанию.
+    #[serde(default = "default_camera_sensitivity")]
+    pub sensitivity: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_zone_ratio: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escape_distance_ratio: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hard_edge_ratio: Option<f64>,
+    /// «Плотность» zoom: запас вокруг кликнутого элемента (0.2 — по умолчанию);
+    /// больше — шире кадр и больше контекста.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom_padding_ratio: Option<f64>,
+    /// Предел приближения (2.0 — по умолчанию); мелкие элементы упираются в него
+    /// раньше, чем в запас.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_zoom: Option<f64>,
+    /// Насколько камера следует за прокруткой (1.0 — по умолчанию, 0 — стоит на месте).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scroll_follow_strength: Option<f64>,
+    /// Окно, в котором накапливаются клики для активации zoom (мс).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activation_window_ms: Option<u64>,
+    /// Сколько кликов в окне нужно для активации zoom.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_clicks_to_activate: Option<u64>,
+    /// Клики ближе этого разрыва объединяются в один кластер (мс).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub click_cluster_gap_ms: Option<u64>,
+    /// Минимальный интервал между соседними zoom (мс).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_zoom_interval_ms: Option<u64>,
+    /// На сколько раньше клика начинается zoom (мс), независимо от предвосхищения
+    /// по замедлению курсора.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom_lead_ms: Option<u64>,
+    /// Камера следует за текстовой кареткой при наборе (если ОС сообщает её положение).
+    #[serde(default)]
+    pub caret_follow: bool,
+    /// Режим срабатывания авто-zoom, выбранный при записи; его использует пересборка.
+    #[serde(default)]
+    pub trigger_mode: AutoZoomTriggerMode,
+}
+
+fn default_camera_sensitivity() -> f64 {
+    0.5
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        CameraSettings {
+            sensitivity: default_camera_sensitivity(),
+            dead_zone_ratio: None,
+            escape_distance_ratio: None,
+            hard_edge_ratio: None,
+            zoom_padding_ratio: None,
+            max_zoom: None,
+            scroll_follow_strength: None,
+            activation_window_ms: None,
+            min_clicks_to_activate: None,
+            click_cluster_gap_ms: None,
+            min_zoom_interval_ms: None,
+            zoom_lead_ms: None,
+            caret_follow: false,
+            trigger_mode: AutoZoomTriggerMode::default(),
         }
     }
 }
 
+impl CameraSettings {
+    /// Множитель для базовых значений: ×2 при 0.0, ×1 при 0.5, ×0.5 при 1.0.
+    pub fn ratio_scale(&self) -> f64 {
+        let (min, max) = CAMERA_SENSITIVITY_RANGE;
+        let sensitivity = if self.sensitivity.is_finite() {
+            self.sensitivity.clamp(min, max)
+        } else {
+            default_camera_sensitivity()
+        };
+        2f64.powf(1.0 - 2.0 * sensitivity)
+    }
+
+    /// Проверяет, что все значения лежат в допустимых диапазонах.
+    pub fn validate(&self) -> Result<(), String> {
+        check_range("sensitivity", self.sensitivity, CAMERA_SENSITIVITY_RANGE)?;
+        let overrides = [
+            ("deadZoneRatio", self.dead_zone_ratio, DEAD_ZONE_RATIO_RANGE),
+            (
+                "escapeDistanceRatio",
+                self.escape_distance_ratio,
+                ESCAPE_DISTANCE_RATIO_RANGE,
+            ),
+            ("hardEdgeRatio", self.hard_edge_ratio, HARD_EDGE_RATIO_RANGE),
+            (
+                "zoomPaddingRatio",
+                self.zoom_padding_ratio,
+                ZOOM_PADDING_RATIO_RANGE,
+            ),
+            ("maxZoom", self.max_zoom, MAX_ZOOM_RANGE),
+            (
+                "scrollFollowStrength",
+                self.scroll_follow_strength,
+                SCROLL_FOLLOW_STRENGTH_RANGE,
+            ),
+        ];
+        for (name, value, range) in overrides {
+            if let Some(value) = value {
+                check_range(name, value, range)?;
+            }
+        }
+        let click_overrides = [
+            (
+                "activationWindowMs",
+                self.activation_window_ms,
+                ACTIVATION_WINDOW_MS_RANGE,
+            ),
+            (
+                "minClicksToActivate",
+                self.min_clicks_to_activate,
+                MIN_CLICKS_TO_ACTIVATE_RANGE,
+            ),
+            (
+                "clickClusterGapMs",
+                self.click_cluster_gap_ms,
+                CLICK_CLUSTER_GAP_MS_RANGE,
+            ),
+            (
+                "minZoomIntervalMs",
+                self.min_zoom_interval_ms,
+                MIN_ZOOM_INTERVAL_MS_RANGE,
+            ),
+            ("zoomLeadMs", self.zoom_lead_ms, ZOOM_LEAD_MS_RANGE),
+        ];
+        for (name, value, (min, max)) in click_overrides {
+            if let Some(value) = value {
+                check_range(name, value as f64, (min as f64, max as f64))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_range(name: &str, value: f64, (min, max): (f64, f64)) -> Result<(), String> {
+    if value.is_finite() && (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Camera setting {name} must be within {min}–{max}, got {value}"
+        ))
+    }
+}
+
 /// Настройки проекта.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -203,6 +872,11 @@ pub struct ProjectSettings {
     pub cursor: CursorSettings,
     pub background: Background,
     pub export: ExportSettings,
+    #[serde(default)]
+    pub camera: CameraSettings,
+    /// Заполнение полей кадра в экспорте.
+    #[serde(default)]
+    pub frame_background: FrameBackground,
 }
 
 /// Корневой объект project.json.
@@ -212,6 +886,12 @@ pub struct Project {
     pub schema_version: u32,
     pub id: String,
     pub name: String,
+    /// Произвольное описание записи.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Метки для поиска и фильтрации в списке проектов.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     /// Unix timestamp (мс) создания проекта.
     pub created_at: u64,
     /// Путь к сырому видеофайлу относительно папки проекта.
@@ -227,4 +907,151 @@ pub struct Project {
     pub video_height: u32,
     pub timeline: Timeline,
     pub settings: ProjectSettings,
+    /// Аудиодорожки, оборвавшиеся во время записи.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audio_issues: Vec<AudioTrackIssue>,
+    /// Версия алгоритма камеры, построившего авто-сегменты (`isAuto`).
+    /// Экспорт использует сохранённые сегменты как есть, поэтому версия меняется только
+    /// при явном пересчёте.
+    #[serde(default = "legacy_camera_engine_version")]
+    pub camera_engine_version: u32,
+    /// Ошибка склейки записей нескольких мониторов. Если задана, в `video_path` лежит
+    /// только первый монитор, а записи остальных (`raw-monitor-*.mp4`) остались в папке
+    /// проекта.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor_composite_error: Option<String>,
+    /// Задержка старта звуковых дорожек, измеренная при записи.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_start_offsets: Option<AudioStartOffsets>,
+}
+
+/// На сколько миллисекунд захват дорожки начался раньше первого кадра видео
+/// (отрицательное значение — позже).
+///
+/// При сведении звука в `raw.mp4` этот сдвиг уже компенсирован; остаточное расхождение
+/// правится через `ExportSettings::audio_offset_ms`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioStartOffsets {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_ms: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub microphone_ms: Option<i64>,
+}
+
+/// Источник аудиодорожки записи.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioTrackKind {
+    System,
+    Microphone,
+}
+
+/// Дорожка, процесс захвата которой завершился раньше остановки записи
+/// (например, отключили микрофон): после `ended_at_ms` звука в ней нет.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioTrackIssue {
+    pub track: AudioTrackKind,
+    /// Момент обрыва на таймлайне проекта (мс, без пауз).
+    pub ended_at_ms: u64,
+}
+
+impl AudioTrackIssue {
+    /// Текст предупреждения для логов и статуса экспорта.
+    pub fn warning(&self) -> String {
+        let track = match self.track {
+            AudioTrackKind::System => "System audio",
+            AudioTrackKind::Microphone => "Microphone",
+        };
+        format!("{track} track ended early at {}ms", self.ended_at_ms)
+    }
+}
+
+impl Project {
+    /// Авто-сегменты построены старой версией камеры и пересчёт их изменит.
+    pub fn has_newer_camera_engine(&self) -> bool {
+        self.camera_engine_version < CAMERA_ENGINE_VERSION
+            && self
+                .timeline
+                .zoom_segments
+                .iter()
+                .any(|segment| segment.is_auto)
+    }
+
+    /// Задаёт описание и метки: пустое описание удаляется, метки обрезаются
+    /// и очищаются от пустых и повторяющихся (без учёта регистра).
+    pub fn set_metadata(&mut self, description: Option<String>, tags: Vec<String>) {
+        self.description = description
+            .map(|description| description.trim().to_string())
+            .filter(|description| !description.is_empty());
+        self.tags.clear();
+        for tag in tags {
+            let tag = tag.trim();
+            let key = tag.to_lowercase();
+            if !tag.is_empty()
+                && !self
+                    .tags
+                    .iter()
+                    .any(|existing| existing.to_lowercase() == key)
+            {
+                self.tags.push(tag.to_string());
+            }
+        }
+    }
+
+    /// Задаёт (`Some`) или снимает (`None`) явное приближение точки траектории сегмента
+    /// с меткой времени `ts`.
+    pub fn set_target_point_zoom(
+        &mut self,
+        segment_id: &str,
+        ts: u64,
+        zoom: Option<f64>,
+    ) -> Result<(), String> {
+        if let Some(zoom) = zoom {
+            let (min, max) = TARGET_POINT_ZOOM_RANGE;
+            if !(zoom.is_finite() && (min..=max).contains(&zoom)) {
+                return Err(format!(
+                    "Target point zoom must be within {min}–{max}, got {zoom}"
+                ));
+            }
+        }
+        let segment = self
+            .timeline
+            .zoom_segments
+            .iter_mut()
+            .find(|segment| segment.id == segment_id)
+            .ok_or_else(|| format!("Zoom segment not found: {segment_id}"))?;
+        let point = segment
+            .target_points
+            .iter_mut()
+            .find(|point| point.ts == ts)
+            .ok_or_else(|| format!("Segment {segment_id} has no target point at {ts} ms"))?;
+        point.zoom = zoom;
+        Ok(())
+    }
+
+    /// Задаёт (`Some`) или снимает (`None`) подпись zoom-сегмента; текст подписи обрезается.
+    pub fn set_segment_label(
+        &mut self,
+        segment_id: &str,
+        label: Option<SegmentLabel>,
+    ) -> Result<(), String> {
+        let label = match label {
+            Some(mut label) => {
+                label.text = label.text.trim().to_string();
+                label.validate()?;
+                Some(label)
+            }
+            None => None,
+        };
+        let segment = self
+            .timeline
+            .zoom_segments
+            .iter_mut()
+            .find(|segment| segment.id == segment_id)
+            .ok_or_else(|| format!("Zoom segment not found: {segment_id}"))?;
+        segment.label = label;
+        Ok(())
+    }
 }
@@ -0,0 +1,383 @@
+//! Глобальные горячие клавиши управления записью.
+//!
+//! Сочетания распознаются в том же rdev-потоке, что и телеметрия
+//! (`logger::handle_rdev_event`), и уходят через канал `TelemetryGlobal::hotkey_tx`;
+//! приложение пересылает их окну событием [`GLOBAL_HOTKEY_EVENT`], а экран записи
+//! (`Record.tsx`) вызывает соответствующую команду записи.
+//!
+//! Сочетания записываются строками вида `Ctrl+Shift+R`. Клавиши хранятся в виде
+//! `format!("{key:?}")` — так же, как `key_code` в `events.json`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::app_settings::HotkeyBindings;
+
+/// Событие окна с `HotkeyAction` в качестве payload.
+pub const GLOBAL_HOTKEY_EVENT: &str = "global-hotkey";
+
+/// Действие, назначаемое на горячую клавишу.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyAction {
+    /// Начать запись или остановить текущую.
+    ToggleRecording,
+    /// Поставить запись на паузу или продолжить.
+    TogglePause,
+}
+
+/// Клавиши, которые можно назначить, с альтернативными написаниями:
+/// `(имя для отображения, rdev-код, синонимы)`.
+const NAMED_KEYS: &[(&str, &str, &[&str])] = &[
+    ("Space", "Space", &[]),
+    ("Enter", "Return", &["return"]),
+    ("Esc", "Escape", &["escape"]),
+    ("Tab", "Tab", &[]),
+    ("Home", "Home", &[]),
+    ("End", "End", &[]),
+    ("PageUp", "PageUp", &["pgup"]),
+    ("PageDown", "PageDown", &["pgdn"]),
+    ("Insert", "Insert", &["ins"]),
+    ("Delete", "Delete", &["del"]),
+    ("Pause", "Pause", &["break"]),
+    ("PrintScreen", "PrintScreen", &["prtsc"]),
+    ("ScrollLock", "ScrollLock", &[]),
+    ("Up", "UpArrow", &["arrowup"]),
+    ("Down", "DownArrow", &["arrowdown"]),
+    ("Left", "LeftArrow", &["arrowleft"]),
+    ("Right", "RightArrow", &["arrowright"]),
+];
+
+/// Системные сочетания, которые rdev не перехватывает: назначенные на них действия
+/// срабатывали бы вместе с копированием, закрытием окна и т.п.
+const RESERVED_HOTKEYS: &[&str] = &[
+    "Ctrl+A",
+    "Ctrl+C",
+    "Ctrl+S",
+    "Ctrl+V",
+    "Ctrl+X",
+    "Ctrl+Y",
+    "Ctrl+Z",
+    "Alt+Tab",
+    "Alt+F4",
+    "Ctrl+Alt+Delete",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Modifiers {
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    meta: bool,
+}
+
+impl Modifiers {
+    /// Обновляет состояние по событию; `false`, если клавиша не модификатор.
+    fn apply(&mut self, key: rdev::Key, pressed: bool) -> bool {
+        let slot = match key {
+            rdev::Key::ControlLeft | rdev::Key::ControlRight => &mut self.ctrl,
+            rdev::Key::Alt | rdev::Key::AltGr => &mut self.alt,
+            rdev::Key::ShiftLeft | rdev::Key::ShiftRight => &mut self.shift,
+            rdev::Key::MetaLeft | rdev::Key::MetaRight => &mut self.meta,
+            _ => return false,
+        };
+        *slot = pressed;
+        true
+    }
+}
+
+/// Сочетание клавиш: модификаторы плюс одна обычная клавиша.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hotkey {
+    modifiers: Modifiers,
+    key_code: String,
+}
+
+impl Hotkey {
+    /// Разбирает строку вида `Ctrl+Shift+R` (регистр и пробелы не важны).
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut modifiers = Modifiers::default();
+        let mut key_code = None;
+        for part in text.split('+').map(str::trim) {
+            let lower = part.to_ascii_lowercase();
+            let slot = match lower.as_str() {
+                "ctrl" | "control" => &mut modifiers.ctrl,
+                "alt" => &mut modifiers.alt,
+                "shift" => &mut modifiers.shift,
+                "win" | "meta" | "super" | "cmd" => &mut modifiers.meta,
+                _ => {
+                    if key_code.is_some() {
+                        return Err(format!("Hotkey {text} has more than one key"));
+                    }
+                    key_code = Some(
+                        key_code_for_name(&lower)
+                            .ok_or_else(|| format!("Unknown key in hotkey {text}: {part}"))?,
+                    );
+                    continue;
+                }
+            };
+            *slot = true;
+        }
+        let key_code = key_code.ok_or_else(|| format!("Hotkey {text} has no key"))?;
+        let hotkey = Self {
+            modifiers,
+            key_code,
+        };
+
+        // Без Ctrl/Alt/Win сочетание срабатывало бы при обычном наборе текста.
+        let is_function_key = hotkey.key_code.len() > 1 && hotkey.key_code.starts_with('F');
+        if !(modifiers.ctrl || modifiers.alt || modifiers.meta || is_function_key) {
+            return Err(format!(
+                "Hotkey {hotkey} needs Ctrl, Alt or Win unless it uses a function key"
+            ));
+        }
+        let canonical = hotkey.to_string();
+        if RESERVED_HOTKEYS.contains(&canonical.as_str()) {
+            return Err(format!("Hotkey {canonical} is a system shortcut"));
+        }
+        Ok(hotkey)
+    }
+}
+
+impl std::fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Modifiers {
+            ctrl,
+            alt,
+            shift,
+            meta,
+        } = self.modifiers;
+        for (enabled, name) in [
+            (ctrl, "Ctrl"),
+            (alt, "Alt"),
+            (shift, "Shift"),
+            (meta, "Win"),
+        ] {
+            if enabled {
+                write!(f, "{name}+")?;
+            }
+        }
+        f.write_str(&key_display_name(&self.key_code))
+    }
+}
+
+/// rdev-код клавиши по её имени в нижнем регистре: `r` → `KeyR`, `5` → `Num5`.
+fn key_code_for_name(name: &str) -> Option<String> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphabetic() {
+            return Some(format!("Key{}", c.to_ascii_uppercase()));
+        }
+        if c.is_ascii_digit() {
+            return Some(format!("Num{c}"));
+        }
+    }
+    if let Some(number) = name.strip_prefix('f').and_then(|n| n.parse::<u32>().ok()) {
+        return (1..=12).contains(&number).then(|| format!("F{number}"));
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(display, _, aliases)| display.eq_ignore_ascii_case(name) || aliases.contains(&name))
+        .map(|(_, code, _)| code.to_string())
+}
+
+fn key_display_name(key_code: &str) -> String {
+    if let Some((display, _, _)) = NAMED_KEYS.iter().find(|(_, code, _)| *code == key_code) {
+        return display.to_string();
+    }
+    key_code
+        .strip_prefix("Key")
+        .or_else(|| key_code.strip_prefix("Num"))
+        .unwrap_or(key_code)
+        .to_string()
+}
+
+/// Проверяет привязки и возвращает их с сочетаниями; одно сочетание не может
+/// управлять двумя действиями.
+pub fn parse_bindings(bindings: &HotkeyBindings) -> Result<Vec<(Hotkey, HotkeyAction)>, String> {
+    let mut parsed: Vec<(Hotkey, HotkeyAction)> = Vec::new();
+    for (text, action) in [
+        (&bindings.toggle_recording, HotkeyAction::ToggleRecording),
+        (&bindings.toggle_pause, HotkeyAction::TogglePause),
+    ] {
+        let Some(text) = text.as_deref().map(str::trim).filter(|t| !t.is_empty()) else {
+            continue;
+        };
+        let hotkey = Hotkey::parse(text)?;
+        if let Some((_, other)) = parsed.iter().find(|(bound, _)| *bound == hotkey) {
+            return Err(format!("Hotkey {hotkey} is already bound to {other:?}"));
+        }
+        parsed.push((hotkey, action));
+    }
+    Ok(parsed)
+}
+
+/// Привязки в каноническом написании, как их показывает UI.
+pub fn canonical_bindings(parsed: &[(Hotkey, HotkeyAction)]) -> HotkeyBindings {
+    let find = |action: HotkeyAction| {
+        parsed
+            .iter()
+            .find(|(_, bound)| *bound == action)
+            .map(|(hotkey, _)| hotkey.to_string())
+    };
+    HotkeyBindings {
+        toggle_recording: find(HotkeyAction::ToggleRecording),
+        toggle_pause: find(HotkeyAction::TogglePause),
+    }
+}
+
+/// Результат обработки события клавиатуры.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyInput {
+    /// Событие не относится к горячим клавишам и обрабатывается как обычно.
+    Pass,
+    /// Нажатие завершило сочетание.
+    Fire(HotkeyAction),
+    /// Автоповтор или отпускание клавиши сработавшего сочетания: в запись не попадает.
+    Swallow,
+}
+
+/// Отслеживает модификаторы независимо от `TelemetryGlobal::is_ctrl_pressed`, поэтому
+/// не влияет на зум по Ctrl+клику.
+#[derive(Debug, Default)]
+pub struct HotkeyMatcher {
+    bindings: Vec<(Hotkey, HotkeyAction)>,
+    modifiers: Modifiers,
+    /// Клавиша сработавшего сочетания, пока она удерживается.
+    held: Option<String>,
+}
+
+impl HotkeyMatcher {
+    pub fn set_bindings(&mut self, bindings: Vec<(Hotkey, HotkeyAction)>) {
+        self.bindings = bindings;
+        self.held = None;
+    }
+
+    pub fn handle(&mut self, event_type: &rdev::EventType) -> HotkeyInput {
+        let (key, pressed) = match event_type {
+            rdev::EventType::KeyPress(key) => (*key, true),
+            rdev::EventType::KeyRelease(key) => (*key, false),
+            _ => return HotkeyInput::Pass,
+        };
+        // Модификаторы всегда доходят до записи и до отслеживания Ctrl.
+        if self.modifiers.apply(key, pressed) {
+            return HotkeyInput::Pass;
+        }
+
+        let key_code = format!("{key:?}");
+        if self.held.as_deref() == Some(key_code.as_str()) {
+            if !pressed {
+                self.held = None;
+            }
+            return HotkeyInput::Swallow;
+        }
+        if !pressed {
+            return HotkeyInput::Pass;
+        }
+        let action = self
+            .bindings
+            .iter()
+            .find(|(hotkey, _)| hotkey.modifiers == self.modifiers && hotkey.key_code == key_code)
+            .map(|(_, action)| *action);
+        match action {
+            Some(action) => {
+                self.held = Some(key_code);
+                HotkeyInput::Fire(action)
+            }
+            None => HotkeyInput::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hotkeys_parse_to_canonical_form_and_reject_conflicts() {
+        let hotkey = Hotkey::parse(" shift + ctrl + r ").expect("valid hotkey");
+        assert_eq!(hotkey.to_string(), "Ctrl+Shift+R");
+        assert_eq!(
+            Hotkey::parse("Alt+PgDn").expect("valid hotkey").to_string(),
+            "Alt+PageDown"
+        );
+        assert_eq!(Hotkey::parse("F9").expect("valid hotkey").to_string(), "F9");
+
+        assert!(Hotkey::parse("R").is_err());
+        assert!(Hotkey::parse("Shift+R").is_err());
+        assert!(Hotkey::parse("Ctrl+Shift").is_err());
+        assert!(Hotkey::parse("Ctrl+R+T").is_err());
+        assert!(Hotkey::parse("Ctrl+Banana").is_err());
+        assert!(Hotkey::parse("control+c").is_err());
+
+        let bindings = HotkeyBindings {
+            toggle_recording: Some("ctrl+shift+r".to_string()),
+            toggle_pause: Some(" ".to_string()),
+        };
+        let parsed = parse_bindings(&bindings).expect("valid bindings");
+        assert_eq!(
+            canonical_bindings(&parsed),
+            HotkeyBindings {
+                toggle_recording: Some("Ctrl+Shift+R".to_string()),
+                toggle_pause: None,
+            }
+        );
+        let clash = HotkeyBindings {
+            toggle_recording: Some("Ctrl+Shift+R".to_string()),
+            toggle_pause: Some("Shift+Ctrl+R".to_string()),
+        };
+        assert!(parse_bindings(&clash).is_err());
+    }
+
+    #[test]
+    fn matcher_fires_once_per_press_and_swallows_the_trigger_key() {
+        let mut matcher = HotkeyMatcher::default();
+        matcher.set_bindings(
+            parse_bindings(&HotkeyBindings {
+                toggle_recording: Some("Ctrl+Shift+R".to_string()),
+                toggle_pause: Some("F9".to_string()),
+            })
+            .expect("valid bindings"),
+        );
+        let press = |key| rdev::EventType::KeyPress(key);
+        let release = |key| rdev::EventType::KeyRelease(key);
+
+        assert_eq!(matcher.handle(&press(rdev::Key::KeyR)), HotkeyInput::Pass);
+        assert_eq!(matcher.handle(&release(rdev::Key::KeyR)), HotkeyInput::Pass);
+        assert_eq!(
+            matcher.handle(&press(rdev::Key::ControlLeft)),
+            HotkeyInput::Pass
+        );
+        // Ctrl+R alone is not the bound combination.
+        assert_eq!(matcher.handle(&press(rdev::Key::KeyR)), HotkeyInput::Pass);
+        assert_eq!(matcher.handle(&release(rdev::Key::KeyR)), HotkeyInput::Pass);
+        assert_eq!(
+            matcher.handle(&press(rdev::Key::ShiftRight)),
+            HotkeyInput::Pass
+        );
+        assert_eq!(
+            matcher.handle(&press(rdev::Key::KeyR)),
+            HotkeyInput::Fire(HotkeyAction::ToggleRecording)
+        );
+        // Auto-repeat while held does not toggle again.
+        assert_eq!(
+            matcher.handle(&press(rdev::Key::KeyR)),
+            HotkeyInput::Swallow
+        );
+        assert_eq!(
+            matcher.handle(&release(rdev::Key::KeyR)),
+            HotkeyInput::Swallow
+        );
+        assert_eq!(
+            matcher.handle(&release(rdev::Key::ShiftRight)),
+            HotkeyInput::Pass
+        );
+        assert_eq!(
+            matcher.handle(&release(rdev::Key::ControlLeft)),
+            HotkeyInput::Pass
+        );
+        assert_eq!(
+            matcher.handle(&press(rdev::Key::F9)),
+            HotkeyInput::Fire(HotkeyAction::TogglePause)
+        );
+    }
+}
@@ -8,12 +8,14 @@
 //!   3. `stop_session` отправляет `RawInput::Stop` в процессор и сбрасывает канал.
 //!      Вызывающий ждёт JoinHandle процессора и получает итоговый `Vec<InputEvent>`.
 
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{sync_channel, SyncSender};
+use std::sync::mpsc::{sync_channel, Sender, SyncSender};
 use std::sync::{Arc, Mutex};
 use std::time::UNIX_EPOCH;
 
-use crate::models::events::{InputEvent, MouseButton, ScrollDelta};
+use crate::models::events::{BoundingRect, InputEvent, MouseButton, ScrollDelta};
+use crate::telemetry::hotkeys::{HotkeyAction, HotkeyInput, HotkeyMatcher};
 
 // ─── Внутренние типы ─────────────────────────────────────────────────────────
 
@@ -55,6 +57,124 @@ pub enum RawInput {
     Stop,
 }
 
+/// Какие типы событий сессия записывает. Отключённые типы отбрасываются ещё в
+/// rdev-потоке и не попадают ни в процессор, ни в `events.json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureFilter {
+    /// Нажатия и отпускания клавиш. Без них недоступен зум по Ctrl+клику.
+    pub record_keystrokes: bool,
+    /// Нажатия и отпускания кнопок мыши.
+    pub record_clicks: bool,
+    pub record_moves: bool,
+    pub record_scroll: bool,
+    /// Не записывать клавиши, пока фокус ввода в поле пароля.
+    pub redact_secure_fields: bool,
+}
+
+impl Default for CaptureFilter {
+    fn default() -> Self {
+        Self {
+            record_keystrokes: true,
+            record_clicks: true,
+            record_moves: true,
+            record_scroll: true,
+            redact_secure_fields: true,
+        }
+    }
+}
+
+/// Как долго результат проверки фокуса считается актуальным (мс).
+const SECURE_FOCUS_RECHECK_MS: u64 = 500;
+
+/// Кэширует проверку «фокус в поле пароля», чтобы не опрашивать UI Automation
+/// на каждую клавишу. Клик и клавиши, переносящие фокус (Tab, Alt, Enter), сбрасывают кэш.
+#[derive(Debug, Default)]
+struct SecureFocusCache {
+    checked_at: Option<u64>,
+    secure: bool,
+}
+
+impl SecureFocusCache {
+    fn is_secure(&mut self, ts_abs: u64, probe: impl FnOnce() -> bool) -> bool {
+        let stale = self.checked_at.map_or(true, |at| {
+            ts_abs.saturating_sub(at) >= SECURE_FOCUS_RECHECK_MS
+        });
+        if stale {
+            self.secure = probe();
+            self.checked_at = Some(ts_abs);
+        }
+        self.secure
+    }
+
+    fn invalidate(&mut self) {
+        self.checked_at = None;
+    }
+
+    /// Сбрасывает кэш после клавиши, которая могла перенести фокус. Вызывается и при
+    /// отпускании: Alt+Tab переключает окно, когда отпущен Alt.
+    fn note_key(&mut self, key: rdev::Key) {
+        if moves_focus(key) {
+            self.invalidate();
+        }
+    }
+}
+
+fn moves_focus(key: rdev::Key) -> bool {
+    matches!(
+        key,
+        rdev::Key::Tab
+            | rdev::Key::Alt
+            | rdev::Key::AltGr
+            | rdev::Key::Return
+            | rdev::Key::KpReturn
+    )
+}
+
+/// Как часто при наборе текста снимается положение каретки (мс).
+const CARET_SAMPLE_INTERVAL_MS: u64 = 150;
+
+/// Прореживает опрос каретки: при наборе она смещается на символ за нажатие, и
+/// снимок на каждую клавишу только раздувает events.json. Клик сбрасывает
+/// прореживание: он мог перенести каретку.
+#[derive(Debug, Default)]
+struct CaretSampler {
+    sampled_at: Option<u64>,
+}
+
+impl CaretSampler {
+    fn sample(
+        &mut self,
+        ts_abs: u64,
+        probe: impl FnOnce() -> Option<BoundingRect>,
+    ) -> Option<BoundingRect> {
+        let due = self.sampled_at.map_or(true, |at| {
+            ts_abs.saturating_sub(at) >= CARET_SAMPLE_INTERVAL_MS
+        });
+        if !due {
+            return None;
+        }
+        self.sampled_at = Some(ts_abs);
+        probe()
+    }
+
+    fn invalidate(&mut self) {
+        self.sampled_at = None;
+    }
+}
+
+impl CaptureFilter {
+    fn allows(&self, event_type: &rdev::EventType) -> bool {
+        match event_type {
+            rdev::EventType::MouseMove { .. } => self.record_moves,
+            rdev::EventType::ButtonPress(_) | rdev::EventType::ButtonRelease(_) => {
+                self.record_clicks
+            }
+            rdev::EventType::Wheel { .. } => self.record_scroll,
+            rdev::EventType::KeyPress(_) | rdev::EventType::KeyRelease(_) => self.record_keystrokes,
+        }
+    }
+}
+
 // ─── Разделяемое глобальное состояние ────────────────────────────────────────
 
 /// Состояние, разделяемое между rdev-потоком и IPC-командами.
@@ -68,6 +188,12 @@ pub struct TelemetryGlobal {
     pub is_paused: AtomicBool,
     /// Last observed state of Ctrl modifier from global keyboard hook.
     pub is_ctrl_pressed: AtomicBool,
+    /// Типы событий, которые записывает текущая сессия.
+    pub filter: Mutex<CaptureFilter>,
+    /// Распознавание глобальных горячих клавиш.
+    pub hotkeys: Mutex<HotkeyMatcher>,
+    /// Получатель сработавших горячих клавиш; `None` — они игнорируются.
+    pub hotkey_tx: Mutex<Option<Sender<HotkeyAction>>>,
 }
 
 impl TelemetryGlobal {
@@ -77,6 +203,9 @@ impl TelemetryGlobal {
             last_pos: Mutex::new((0.0, 0.0)),
             is_paused: AtomicBool::new(false),
             is_ctrl_pressed: AtomicBool::new(false),
+            filter: Mutex::new(CaptureFilter::default()),
+            hotkeys: Mutex::new(HotkeyMatcher::default()),
+            hotkey_tx: Mutex::new(None),
         })
     }
 }
@@ -113,6 +242,18 @@ fn handle_rdev_event(global: &Arc<TelemetryGlobal>, event: rdev::Event) {
         _ => {}
     }
 
+    // Горячие клавиши работают и на паузе: ими запись продолжают.
+    match global.hotkeys.lock().unwrap().handle(&event.event_type) {
+        HotkeyInput::Pass => {}
+        HotkeyInput::Fire(action) => {
+            if let Some(tx) = global.hotkey_tx.lock().unwrap().as_ref() {
+                tx.send(action).ok();
+            }
+            return;
+        }
+        HotkeyInput::Swallow => return,
+    }
+
     if global.is_paused.load(Ordering::Relaxed) {
         return;
     }
@@ -132,9 +273,16 @@ fn handle_rdev_event(global: &Arc<TelemetryGlobal>, event: rdev::Event) {
         }
     };
 
+    // Позиция нужна кликам и прокрутке, даже когда сами перемещения не записываются.
+    if let rdev::EventType::MouseMove { x, y } = event.event_type {
+        *global.last_pos.lock().unwrap() = (x, y);
+    }
+    if !global.filter.lock().unwrap().allows(&event.event_type) {
+        return;
+    }
+
     match event.event_type {
         rdev::EventType::MouseMove { x, y } => {
-            *global.last_pos.lock().unwrap() = (x, y);
             tx.send(RawInput::Move { ts_abs, x, y }).ok();
         }
         rdev::EventType::ButtonPress(button) => {
@@ -182,12 +330,15 @@ fn handle_rdev_event(global: &Arc<TelemetryGlobal>, event: rdev::Event) {
 /// Начинает новую сессию телеметрии.
 ///
 /// Создаёт канал и запускает поток-процессор. Возвращает `JoinHandle`, при
-/// `.join()` которого получаем `Vec<InputEvent>` — все накопленные события.
+/// `.join()` которого получаем `Vec<InputEvent>` — все накопленные события
+/// тех типов, которые разрешает `filter`.
 pub fn start_session(
     global: &Arc<TelemetryGlobal>,
     start_ms: u64,
+    filter: CaptureFilter,
 ) -> std::thread::JoinHandle<Vec<InputEvent>> {
     global.is_paused.store(false, Ordering::Relaxed);
+    *global.filter.lock().unwrap() = filter;
     let (tx, rx) = sync_channel::<RawInput>(8192);
     *global.current_tx.lock().unwrap() = Some(tx);
 
@@ -195,6 +346,10 @@ pub fn start_session(
         .name("nsc-telemetry-proc".to_string())
         .spawn(move || {
             let mut events = Vec::<InputEvent>::new();
+            let mut secure_focus = SecureFocusCache::default();
+            let mut caret_sampler = CaretSampler::default();
+            // Клавиши, нажатые в поле пароля: их отпускание тоже не записывается.
+            let mut redacted_keys = HashSet::<String>::new();
 
             for raw in rx {
                 match raw {
@@ -214,6 +369,8 @@ pub fn start_session(
                         y,
                         button,
                     } => {
+                        secure_focus.invalidate();
+                        caret_sampler.invalidate();
                         let ui_context = crate::telemetry::ui_context::get_ui_context(x, y);
                         events.push(InputEvent::Click {
                             ts: ts_abs.saturating_sub(start_ms),
@@ -257,16 +414,36 @@ pub fn start_session(
                     }
 
                     RawInput::KeyDown { ts_abs, key } => {
+                        let key_code = format!("{key:?}");
+                        if filter.redact_secure_fields
+                            && secure_focus.is_secure(
+                                ts_abs,
+                                crate::telemetry::ui_context::focused_control_is_secure,
+                            )
+                        {
+                            secure_focus.note_key(key);
+                            redacted_keys.insert(key_code);
+                            continue;
+                        }
+                        secure_focus.note_key(key);
+                        let caret = caret_sampler
+                            .sample(ts_abs, crate::telemetry::ui_context::get_caret_bounds);
                         events.push(InputEvent::KeyDown {
                             ts: ts_abs.saturating_sub(start_ms),
-                            key_code: format!("{key:?}"),
+                            key_code,
+                            caret,
                         });
                     }
 
                     RawInput::KeyUp { ts_abs, key } => {
+                        secure_focus.note_key(key);
+                        let key_code = format!("{key:?}");
+                        if redacted_keys.remove(&key_code) {
+                            continue;
+                        }
                         events.push(InputEvent::KeyUp {
                             ts: ts_abs.saturating_sub(start_ms),
-                            key_code: format!("{key:?}"),
+                            key_code,
                         });
                     }
                 }
@@ -305,3 +482,200 @@ fn rdev_button(button: rdev::Button) -> MouseButton {
 fn is_ctrl_key(key: rdev::Key) -> bool {
     matches!(key, rdev::Key::ControlLeft | rdev::Key::ControlRight)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn feed(global: &Arc<TelemetryGlobal>, ts_ms: u64, event_type: rdev::EventType) {
+        handle_rdev_event(
+            global,
+            rdev::Event {
+                time: UNIX_EPOCH + Duration::from_millis(ts_ms),
+                name: None,
+                event_type,
+            },
+        );
+    }
+
+    fn record(filter: CaptureFilter) -> Vec<InputEvent> {
+        let global = TelemetryGlobal::new();
+        let start_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let processor = start_session(&global, start_ms, filter);
+        feed(
+            &global,
+            start_ms + 10,
+            rdev::EventType::MouseMove { x: 40.0, y: 30.0 },
+        );
+        feed(
+            &global,
+            start_ms + 20,
+            rdev::EventType::KeyPress(rdev::Key::KeyP),
+        );
+        feed(
+            &global,
+            start_ms + 30,
+            rdev::EventType::KeyRelease(rdev::Key::KeyP),
+        );
+        feed(
+            &global,
+            start_ms + 40,
+            rdev::EventType::Wheel {
+                delta_x: 0,
+                delta_y: -1,
+            },
+        );
+        stop_session(&global);
+        processor.join().expect("telemetry processor")
+    }
+
+    #[test]
+    fn secure_focus_check_is_cached_until_stale_or_invalidated() {
+        let mut cache = SecureFocusCache::default();
+        let mut probes = 0;
+        let mut probe = |secure: bool| {
+            probes += 1;
+            secure
+        };
+        assert!(cache.is_secure(1_000, || probe(true)));
+        // Within the recheck window the cached answer is reused.
+        assert!(cache.is_secure(1_200, || probe(false)));
+        assert!(!cache.is_secure(1_000 + SECURE_FOCUS_RECHECK_MS, || probe(false)));
+        cache.invalidate();
+        assert!(cache.is_secure(1_600, || probe(true)));
+        assert_eq!(probes, 3);
+
+        use crate::telemetry::ui_context::is_secure_control_name;
+        assert!(is_secure_control_name("Password"));
+        assert!(is_secure_control_name("Введите пароль"));
+        assert!(!is_secure_control_name("Search"));
+    }
+
+    #[test]
+    fn secure_focus_is_rechecked_after_a_focus_moving_key() {
+        let mut cache = SecureFocusCache::default();
+        // Typing into a search box, then Tab into a password field 100 ms later.
+        assert!(!cache.is_secure(1_000, || false));
+        cache.note_key(rdev::Key::KeyA);
+        assert!(!cache.is_secure(1_050, || true));
+        cache.note_key(rdev::Key::Tab);
+        assert!(cache.is_secure(1_100, || true));
+
+        // Alt+Tab switches windows on the Alt release.
+        let mut cache = SecureFocusCache::default();
+        assert!(!cache.is_secure(2_000, || false));
+        cache.note_key(rdev::Key::Alt);
+        assert!(!cache.is_secure(2_050, || false));
+        cache.note_key(rdev::Key::Alt);
+        assert!(cache.is_secure(2_100, || true));
+    }
+
+    #[test]
+    fn caret_is_sampled_at_most_once_per_interval() {
+        let mut sampler = CaretSampler::default();
+        let caret = || {
+            Some(BoundingRect {
+                x: 100,
+                y: 200,
+                width: 1,
+                height: 18,
+            })
+        };
+        assert!(sampler.sample(1_000, caret).is_some());
+        assert!(sampler.sample(1_050, caret).is_none());
+        assert!(sampler
+            .sample(1_000 + CARET_SAMPLE_INTERVAL_MS, caret)
+            .is_some());
+        sampler.invalidate();
+        assert!(sampler.sample(1_200, caret).is_some());
+    }
+
+    #[test]
+    fn disabled_event_types_never_reach_the_session() {
+        let everything = record(CaptureFilter {
+            // Keeps the test off UI Automation.
+            redact_secure_fields: false,
+            ..CaptureFilter::default()
+        });
+        assert_eq!(everything.len(), 4);
+
+        let events = record(CaptureFilter {
+            record_keystrokes: false,
+            record_moves: false,
+            ..CaptureFilter::default()
+        });
+        assert_eq!(events.len(), 1);
+        // The scroll keeps the position of the unrecorded move.
+        match &events[0] {
+            InputEvent::Scroll { x, y, .. } => assert_eq!((*x, *y), (40.0, 30.0)),
+            other => panic!("unexpected event {other:?}"),
+        }
+        assert!(!events.iter().any(|event| matches!(
+            event,
+            InputEvent::Move { .. } | InputEvent::KeyDown { .. } | InputEvent::KeyUp { .. }
+        )));
+    }
+
+    #[test]
+    fn hotkeys_are_dispatched_without_disturbing_ctrl_tracking() {
+        use crate::models::app_settings::HotkeyBindings;
+        use crate::telemetry::hotkeys::parse_bindings;
+
+        let global = TelemetryGlobal::new();
+        global.hotkeys.lock().unwrap().set_bindings(
+            parse_bindings(&HotkeyBindings {
+                toggle_recording: Some("Ctrl+Shift+R".to_string()),
+                toggle_pause: None,
+            })
+            .expect("valid bindings"),
+        );
+        let (tx, rx) = std::sync::mpsc::channel();
+        *global.hotkey_tx.lock().unwrap() = Some(tx);
+        let start_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let processor = start_session(
+            &global,
+            start_ms,
+            CaptureFilter {
+                redact_secure_fields: false,
+                ..CaptureFilter::default()
+            },
+        );
+        for (offset, event_type) in [
+            rdev::EventType::KeyPress(rdev::Key::ControlLeft),
+            rdev::EventType::KeyPress(rdev::Key::ShiftLeft),
+            rdev::EventType::KeyPress(rdev::Key::KeyR),
+            rdev::EventType::KeyRelease(rdev::Key::KeyR),
+            rdev::EventType::KeyRelease(rdev::Key::ShiftLeft),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            feed(&global, start_ms + 10 * offset as u64, event_type);
+        }
+        assert!(global.is_ctrl_pressed.load(Ordering::Relaxed));
+        feed(
+            &global,
+            start_ms + 60,
+            rdev::EventType::KeyRelease(rdev::Key::ControlLeft),
+        );
+        assert!(!global.is_ctrl_pressed.load(Ordering::Relaxed));
+        stop_session(&global);
+        let events = processor.join().expect("telemetry processor");
+
+        assert_eq!(rx.try_recv(), Ok(HotkeyAction::ToggleRecording));
+        // Modifiers are still recorded; the trigger key is not.
+        assert_eq!(events.len(), 4);
+        assert!(!events.iter().any(|event| matches!(
+            event,
+            InputEvent::KeyDown { key_code, .. } | InputEvent::KeyUp { key_code, .. }
+                if key_code == "KeyR"
+        )));
+    }
+}
@@ -1,2 +1,3 @@
+pub mod hotkeys;
 pub mod logger;
 pub mod ui_context;
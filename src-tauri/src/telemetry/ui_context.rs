@@ -3,6 +3,15 @@
 //! Функция `get_ui_context` вызывается синхронно из потока-процессора телеметрии
 //! при каждом клике. Любые ошибки (COM, таймаут, Protected UI) дают `None`,
 //! что считается допустимым fallback-ом.
+//!
+//! `focused_control_is_secure` проверяет элемент с фокусом ввода перед записью
+//! нажатий клавиш; при ошибке или панике поле считается полем пароля, чтобы
+//! неизвестный результат не приводил к записи пароля.
+//!
+//! `get_caret_bounds` читает системную каретку (`GetGUIThreadInfo`). Её сообщают
+//! только приложения, которые создают каретку через Win32 (`CreateCaret`): стандартные
+//! поля ввода, WinForms, Блокнот, Office. Chromium/Electron (браузеры, VS Code) и
+//! WPF рисуют каретку сами — для них возвращается `None`, и камера ведёт себя по кликам.
 
 use crate::models::events::{BoundingRect, UiContext};
 
@@ -23,7 +32,7 @@ fn query_uia(x: f64, y: f64) -> Option<UiContext> {
     let app_name = element
         .get_process_id()
         .ok()
-        .map(|pid| format!("pid:{pid}"));
+        .and_then(|pid| process_image_name(pid as u32));
     let control_name = element.get_name().ok().filter(|s| !s.is_empty());
 
     let bounding_rect = element.get_bounding_rectangle().ok().map(|r| BoundingRect {
@@ -39,3 +48,106 @@ fn query_uia(x: f64, y: f64) -> Option<UiContext> {
         bounding_rect,
     })
 }
+
+/// Имя исполняемого файла процесса без расширения (например, `chrome`), или `None`,
+/// если процесс недоступен.
+fn process_image_name(pid: u32) -> Option<String> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+    let mut buffer = [0u16; 1024];
+    let mut len = buffer.len() as u32;
+    let queried = unsafe {
+        QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        )
+    };
+    let _ = unsafe { CloseHandle(process) };
+    queried.ok()?;
+
+    let path = String::from_utf16_lossy(&buffer[..len as usize]);
+    std::path::Path::new(&path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .filter(|stem| !stem.is_empty())
+}
+
+/// Возвращает прямоугольник текстовой каретки активного окна в экранных координатах,
+/// или `None`, если каретки нет или приложение её не сообщает.
+pub fn get_caret_bounds() -> Option<BoundingRect> {
+    std::panic::catch_unwind(query_caret).ok().flatten()
+}
+
+fn query_caret() -> Option<BoundingRect> {
+    use windows::Win32::Foundation::POINT;
+    use windows::Win32::Graphics::Gdi::ClientToScreen;
+    use windows::Win32::UI::WindowsAndMessaging::{GetGUIThreadInfo, GUITHREADINFO};
+
+    let mut info = GUITHREADINFO {
+        cbSize: std::mem::size_of::<GUITHREADINFO>() as u32,
+        ..Default::default()
+    };
+    // Поток 0 — поток окна переднего плана.
+    unsafe { GetGUIThreadInfo(0, &mut info) }.ok()?;
+    if info.hwndCaret.0 == 0 {
+        return None;
+    }
+    // rcCaret задан в клиентских координатах окна с кареткой.
+    let rect = info.rcCaret;
+    let mut origin = POINT {
+        x: rect.left,
+        y: rect.top,
+    };
+    if !unsafe { ClientToScreen(info.hwndCaret, &mut origin) }.as_bool() {
+        return None;
+    }
+    Some(BoundingRect {
+        x: origin.x,
+        y: origin.y,
+        // Каретка обычно шириной 1–2 px; нулевая ширина всё равно задаёт точку.
+        width: (rect.right - rect.left).max(1) as u32,
+        height: (rect.bottom - rect.top).max(1) as u32,
+    })
+}
+
+/// Слова в имени элемента, по которым поле считается полем пароля.
+const SECURE_CONTROL_NAME_HINTS: [&str; 5] =
+    ["password", "passwd", "passcode", "пароль", "pin code"];
+
+/// `true`, если элемент с фокусом ввода — поле пароля: UIA помечает его `IsPassword`
+/// или его имя похоже на имя поля пароля. Ошибка UIA или паника тоже дают `true`.
+pub fn focused_control_is_secure() -> bool {
+    std::panic::catch_unwind(query_focused_secure).unwrap_or(true)
+}
+
+fn query_focused_secure() -> bool {
+    use uiautomation::UIAutomation;
+
+    let Some(element) = UIAutomation::new()
+        .ok()
+        .and_then(|auto| auto.get_focused_element().ok())
+    else {
+        return true;
+    };
+    element.is_password().unwrap_or(true)
+        || element
+            .get_name()
+            .ok()
+            .is_some_and(|name| is_secure_control_name(&name))
+}
+
+/// Эвристика по имени элемента (например, «Password», «Введите пароль»).
+pub fn is_secure_control_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SECURE_CONTROL_NAME_HINTS
+        .iter()
+        .any(|hint| name.contains(hint))
+}
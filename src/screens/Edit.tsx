@@ -17,6 +17,8 @@ import "./Edit.css";
 interface ProjectListItem {
   id: string;
   name: string;
+  description: string | null;
+  tags: string[];
   createdAt: number;
   durationMs: number;
   videoWidth: number;
@@ -101,6 +103,7 @@ const CLICK_PULSE_MIN_SCALE = 0.82;
 const CLICK_PULSE_TOTAL_MS = 150;
 const CLICK_PULSE_DOWN_MS = 65;
 const CURSOR_TIMING_OFFSET_MS = 45;
+const DEFAULT_CURSOR_RESAMPLE_HZ = 120;
 const VECTOR_CURSOR_WIDTH = 72;
 const VECTOR_CURSOR_HEIGHT = 110;
 const TIMELINE_MIN_ZOOM_PERCENT = 0;
@@ -234,6 +237,23 @@ function normalizeRect(rect: NormalizedRect): NormalizedRect {
   return { x, y, width, height };
 }
 
+/** Mirrors the export: an explicit zoom resizes the rect around its center. */
+function getTargetPointRect(point: TargetPoint): NormalizedRect {
+  const zoom = point.zoom;
+  if (zoom === undefined || !Number.isFinite(zoom)) {
+    return point.rect;
+  }
+  const scale = 1 / clamp(zoom, 1, 20) / Math.max(point.rect.width, point.rect.height, 0.0001);
+  const width = point.rect.width * scale;
+  const height = point.rect.height * scale;
+  return {
+    x: point.rect.x + (point.rect.width - width) / 2,
+    y: point.rect.y + (point.rect.height - height) / 2,
+    width,
+    height,
+  };
+}
+
 function normalizeSegmentMode(mode: ZoomMode | undefined): ZoomMode {
   return mode === "follow-cursor" ? "follow-cursor" : DEFAULT_SEGMENT_MODE;
 }
@@ -330,7 +350,7 @@ function getSegmentTargetPoints(segment: ZoomSegment): TargetPoint[] {
   const explicitPoints = (segment.targetPoints ?? [])
     .map((point) => ({
       ts: clamp(point.ts, segment.startTs, segment.endTs),
-      rect: normalizeRect(point.rect),
+      rect: normalizeRect(getTargetPointRect(point)),
     }))
     .sort((a, b) => a.ts - b.ts);
 
@@ -831,34 +851,113 @@ function mapTimeMs(valueMs: number, fromDurationMs: number, toDurationMs: number
   return clamp(Math.round((valueMs / fromDurationMs) * toDurationMs), 0, toDurationMs);
 }
 
-function extractCursorSamples(eventsFile: EventsFile | null, smoothingFactor: number): CursorSample[] {
+type RawCursorSample = CursorSample & { isClick: boolean };
+
+/** Один сэмпл на метку времени; клик вытесняет обычное движение (как dedupe_points в бэкенде). */
+function dedupeCursorSamples(samples: RawCursorSample[]): RawCursorSample[] {
+  const deduped: RawCursorSample[] = [];
+  for (const sample of samples) {
+    const last = deduped[deduped.length - 1];
+    if (last && last.ts === sample.ts) {
+      if (sample.isClick || !last.isClick) {
+        deduped[deduped.length - 1] = sample;
+      }
+      continue;
+    }
+    deduped.push(sample);
+  }
+  return deduped;
+}
+
+/** Пересэмплирование на равномерную сетку перед сглаживанием, как resample_points в бэкенде. */
+function resampleCursorSamples(samples: RawCursorSample[], resampleHz: number): RawCursorSample[] {
+  const sorted = dedupeCursorSamples(samples);
+  if (sorted.length < 2) {
+    return sorted;
+  }
+  const startTs = sorted[0].ts;
+  const endTs = sorted[sorted.length - 1].ts;
+  if (endTs <= startTs) {
+    return sorted;
+  }
+
+  const hz = Number.isFinite(resampleHz) ? clamp(resampleHz, 30, 240) : DEFAULT_CURSOR_RESAMPLE_HZ;
+  const stepMs = 1000 / hz;
+  const timestamps: number[] = [];
+  for (let t = startTs; t < endTs; t += stepMs) {
+    timestamps.push(Math.round(t));
+  }
+  timestamps.push(endTs);
+  for (const sample of sorted) {
+    if (sample.isClick) {
+      timestamps.push(sample.ts);
+    }
+  }
+  timestamps.sort((a, b) => a - b);
+
+  const last = sorted[sorted.length - 1];
+  const result: RawCursorSample[] = [];
+  let segmentIndex = 0;
+  for (const ts of timestamps) {
+    if (result.length > 0 && result[result.length - 1].ts === ts) {
+      continue;
+    }
+    const isClick = sorted.some((original) => original.isClick && original.ts === ts);
+    if (ts <= startTs || ts >= endTs) {
+      const edge = ts <= startTs ? sorted[0] : last;
+      result.push({ ts, x: edge.x, y: edge.y, isClick });
+      continue;
+    }
+    while (segmentIndex + 1 < sorted.length && sorted[segmentIndex + 1].ts < ts) {
+      segmentIndex += 1;
+    }
+    const left = sorted[segmentIndex];
+    const right = sorted[segmentIndex + 1];
+    const ratio = clamp((ts - left.ts) / (right.ts - left.ts), 0, 1);
+    result.push({
+      ts,
+      x: left.x + (right.x - left.x) * ratio,
+      y: left.y + (right.y - left.y) * ratio,
+      isClick,
+    });
+  }
+  return result;
+}
+
+function extractCursorSamples(
+  eventsFile: EventsFile | null,
+  smoothingFactor: number,
+  resampleHz: number
+): CursorSample[] {
   if (!eventsFile || eventsFile.screenWidth <= 0 || eventsFile.screenHeight <= 0) {
     return [];
   }
 
-  const samples: CursorSample[] = [];
+  const samples: RawCursorSample[] = [];
   for (const event of eventsFile.events) {
     if (event.type === "move" || event.type === "click" || event.type === "mouseUp" || event.type === "scroll") {
       samples.push({
         ts: event.ts,
         x: clamp(event.x / eventsFile.screenWidth, 0, 1),
         y: clamp(event.y / eventsFile.screenHeight, 0, 1),
+        isClick: event.type === "click",
       });
     }
   }
 
-  const sorted = samples.sort((a, b) => a.ts - b.ts);
-  if (sorted.length <= 1) {
-    return sorted;
-  }
-
+  samples.sort((a, b) => a.ts - b.ts);
   // 0.0 = no smoothing, 1.0 = maximum smoothing.
   const factor = clamp(smoothingFactor, 0, 1);
+  const sorted = samples.length > 1 && factor > 0 ? resampleCursorSamples(samples, resampleHz) : samples;
+  if (sorted.length <= 1 || factor <= 0) {
+    return sorted.map(({ ts, x, y }) => ({ ts, x, y }));
+  }
+
   const alpha = 1 - factor * 0.9;
   let smoothedX = sorted[0].x;
   let smoothedY = sorted[0].y;
 
-  const smoothed = [sorted[0]];
+  const smoothed: CursorSample[] = [{ ts: sorted[0].ts, x: sorted[0].x, y: sorted[0].y }];
   for (let index = 1; index < sorted.length; index += 1) {
     const sample = sorted[index];
     smoothedX = smoothedX + alpha * (sample.x - smoothedX);
@@ -1149,6 +1248,15 @@ function chooseMarkerStepMs(pxPerMs: number): number {
   return 60_000;
 }
 
+interface ProxyStatus {
+  isRunning: boolean;
+  progress: number;
+  proxyVideoPath: string | null;
+  error: string | null;
+}
+
+const PROXY_STATUS_POLL_MS = 1500;
+
 function serializeProjectSnapshot(project: Project): string {
   return JSON.stringify(project);
 }
@@ -1293,8 +1401,13 @@ export default function EditScreen({ onDirtyChange, onSaveHandlerChange }: EditS
     [project?.timeline.zoomSegments]
   );
   const cursorSamples = useMemo(
-    () => extractCursorSamples(eventsFile, project?.settings.cursor.smoothingFactor ?? 0.8),
-    [eventsFile, project?.settings.cursor.smoothingFactor]
+    () =>
+      extractCursorSamples(
+        eventsFile,
+        project?.settings.cursor.smoothingFactor ?? 0.8,
+        project?.settings.cursor.resampleHz ?? DEFAULT_CURSOR_RESAMPLE_HZ
+      ),
+    [eventsFile, project?.settings.cursor.smoothingFactor, project?.settings.cursor.resampleHz]
   );
   const clickTimestamps = useMemo(() => extractClickTimestamps(eventsFile), [eventsFile]);
   const cursorHiddenRanges = useMemo(
@@ -1670,6 +1783,54 @@ export default function EditScreen({ onDirtyChange, onSaveHandlerChange }: EditS
     };
   }, [project?.proxyVideoPath, project?.videoPath, loadedProjectPath]);
 
+  // The proxy of a fresh recording is built in the background; pick it up once ready
+  // without marking the project as edited. Until then the editor plays raw.mp4.
+  useEffect(() => {
+    const projectId = project?.id;
+    if (!projectId || project?.proxyVideoPath) {
+      return;
+    }
+
+    let isCancelled = false;
+    const poll = async () => {
+      try {
+        const status = await invoke<ProxyStatus | null>("get_proxy_status", {
+          recordingId: projectId,
+        });
+        if (isCancelled || !status) {
+          return;
+        }
+        if (!status.isRunning) {
+          window.clearInterval(timer);
+        }
+        const proxyVideoPath = status.proxyVideoPath;
+        if (!proxyVideoPath) {
+          return;
+        }
+        const snapshot = savedProjectSnapshotRef.current;
+        if (snapshot) {
+          savedProjectSnapshotRef.current = serializeProjectSnapshot({
+            ...(JSON.parse(snapshot) as Project),
+            proxyVideoPath,
+          });
+        }
+        setProject((current) =>
+          current && current.id === projectId ? { ...current, proxyVideoPath } : current
+        );
+      } catch {
+        window.clearInterval(timer);
+      }
+    };
+
+    const timer = window.setInterval(() => void poll(), PROXY_STATUS_POLL_MS);
+    void poll();
+
+    return () => {
+      isCancelled = true;
+      window.clearInterval(timer);
+    };
+  }, [project?.id, project?.proxyVideoPath]);
+
   useEffect(() => {
     const video = videoRef.current;
     if (!video) {
@@ -2343,6 +2504,27 @@ export default function EditScreen({ onDirtyChange, onSaveHandlerChange }: EditS
               )}
 
               <div className="sidebar-controls">
+                <label>
+                  <span>Cursor Overlay</span>
+                  <select
+                    value={project.settings.cursor.showCursor === false ? "hidden" : "visible"}
+                    onChange={(event) =>
+                      updateProject((current) => ({
+                        ...current,
+                        settings: {
+                          ...current.settings,
+                          cursor: {
+                            ...current.settings.cursor,
+                            showCursor: event.target.value === "visible",
+                          },
+                        },
+                      }))
+                    }
+                  >
+                    <option value="visible">Show in export</option>
+                    <option value="hidden">Hide (camera motion only)</option>
+                  </select>
+                </label>
                 <label>
                   <span>Cursor Size</span>
                   <input
@@ -1,12 +1,14 @@
 import { useCallback, useEffect, useMemo, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { join } from "@tauri-apps/api/path";
-import type { Project } from "../types/project";
+import type { AspectRatio, Project } from "../types/project";
 import "./Export.css";
 
 interface ProjectListItem {
   id: string;
   name: string;
+  description: string | null;
+  tags: string[];
   createdAt: number;
   durationMs: number;
   videoWidth: number;
@@ -24,9 +26,28 @@ interface ExportStatus {
   error: string | null;
   startedAtMs: number | null;
   finishedAtMs: number | null;
+  frame: number | null;
+  fps: number | null;
+  speed: number | null;
+  /** Files of a multi-rendition export, largest first; empty for a single output. */
+  renditionPaths: string[];
+  /** Non-fatal source problems, e.g. an audio track that ended early. */
+  warnings: string[];
+  /** Probe of every written file once the export has finished. */
+  verification: ExportVerification[];
+}
+
+interface ExportVerification {
+  path: string;
+  fileSizeBytes: number;
+  durationMs: number | null;
+  expectedDurationMs: number;
+  bitrateKbps: number | null;
+  issues: string[];
 }
 
 const CODEC_OPTIONS = ["h264", "h265", "vp9"] as const;
+const ASPECT_RATIO_OPTIONS: AspectRatio[] = ["16:9", "9:16", "1:1", "4:3"];
 const EXPORT_LAST_OUTPUT_DIR_KEY = "nsc.export.lastOutputDirectory";
 
 const DEFAULT_STATUS: ExportStatus = {
@@ -37,6 +58,12 @@ const DEFAULT_STATUS: ExportStatus = {
   error: null,
   startedAtMs: null,
   finishedAtMs: null,
+  frame: null,
+  fps: null,
+  speed: null,
+  renditionPaths: [],
+  warnings: [],
+  verification: [],
 };
 
 function formatDate(ms: number | null): string {
@@ -76,6 +103,8 @@ export default function ExportScreen() {
   const [selectedProjectName, setSelectedProjectName] = useState<string>("");
   const [width, setWidth] = useState(1920);
   const [height, setHeight] = useState(1080);
+  const [aspectRatio, setAspectRatio] = useState<AspectRatio | "custom">("16:9");
+  const [isApplyingAspectRatio, setIsApplyingAspectRatio] = useState(false);
   const [fps, setFps] = useState(60);
   const [codec, setCodec] = useState<(typeof CODEC_OPTIONS)[number]>("h264");
   const [outputDirectory, setOutputDirectory] = useState(() => {
@@ -178,6 +207,7 @@ export default function ExportScreen() {
       });
       setWidth(loaded.settings.export.width);
       setHeight(loaded.settings.export.height);
+      setAspectRatio(loaded.settings.export.aspectRatio ?? "custom");
       const nextCodec = loaded.settings.export.codec.toLowerCase();
       setCodec(
         CODEC_OPTIONS.includes(nextCodec as (typeof CODEC_OPTIONS)[number])
@@ -191,6 +221,31 @@ export default function ExportScreen() {
     }
   };
 
+  // Changing the aspect ratio also re-frames auto zoom, so it is applied to the project
+  // on the backend rather than only to the export size fields.
+  const handleAspectRatioChange = async (next: AspectRatio | "custom") => {
+    setAspectRatio(next);
+    if (next === "custom" || !selectedProjectPath) {
+      return;
+    }
+    setIsApplyingAspectRatio(true);
+    setError(null);
+    try {
+      const updated = await invoke<Project>("set_export_aspect_ratio", {
+        projectPath: selectedProjectPath,
+        aspectRatio: next,
+        // Re-framing regenerates auto zoom, so the user opts into the current camera here.
+        upgradeCameraEngine: true,
+      });
+      setWidth(updated.settings.export.width);
+      setHeight(updated.settings.export.height);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setIsApplyingAspectRatio(false);
+    }
+  };
+
   const fetchStatus = async () => {
     try {
       const nextStatus = await invoke<ExportStatus>("get_export_status");
@@ -379,6 +434,23 @@ export default function ExportScreen() {
           <section className="export-card">
             <h2>Output Settings</h2>
             <div className="export-grid">
+              <label className="export-field">
+                <span>Aspect Ratio</span>
+                <select
+                  value={aspectRatio}
+                  disabled={isApplyingAspectRatio || isLoadingProject || !selectedProjectPath}
+                  onChange={(event) =>
+                    void handleAspectRatioChange(event.target.value as AspectRatio | "custom")
+                  }
+                >
+                  {ASPECT_RATIO_OPTIONS.map((item) => (
+                    <option key={item} value={item}>
+                      {item}
+                    </option>
+                  ))}
+                  <option value="custom">Custom</option>
+                </select>
+              </label>
               <label className="export-field">
                 <span>Width</span>
                 <input
@@ -386,7 +458,10 @@ export default function ExportScreen() {
                   min={320}
                   max={7680}
                   value={width}
-                  onChange={(event) => setWidth(Math.max(320, Number(event.target.value) || 320))}
+                  onChange={(event) => {
+                    setAspectRatio("custom");
+                    setWidth(Math.max(320, Number(event.target.value) || 320));
+                  }}
                 />
               </label>
               <label className="export-field">
@@ -396,7 +471,10 @@ export default function ExportScreen() {
                   min={240}
                   max={4320}
                   value={height}
-                  onChange={(event) => setHeight(Math.max(240, Number(event.target.value) || 240))}
+                  onChange={(event) => {
+                    setAspectRatio("custom");
+                    setHeight(Math.max(240, Number(event.target.value) || 240));
+                  }}
                 />
               </label>
               <label className="export-field">
@@ -509,6 +587,11 @@ export default function ExportScreen() {
 
       <div className="export-banners">
         {status.error && <div className="export-banner export-banner--error">{status.error}</div>}
+        {(status.warnings ?? []).map((warning) => (
+          <div key={warning} className="export-banner export-banner--info">
+            {warning}
+          </div>
+        ))}
         {error && <div className="export-banner export-banner--error">{error}</div>}
         {info && <div className="export-banner export-banner--info">{info}</div>}
       </div>
@@ -1,5 +1,6 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { primaryMonitor } from "@tauri-apps/api/window";
 import { WebviewWindow, getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import {
@@ -26,17 +27,79 @@ interface StartRecordingOptions {
   autoZoomTriggerMode: AutoZoomTriggerMode;
   quality: RecordingQuality;
   targetFps: RecordingFps;
+  /** Доля нативного разрешения для записи (0.25–1.0). */
+  captureScale?: number;
   audioCaptureMode: RecordingAudioMode;
   microphoneDevice?: string;
+  systemAudioDevice?: string;
+  audioChannels?: 1 | 2;
+  audioSampleRate?: number;
+  minFreeDiskSpaceMb?: number;
+  criticalFreeDiskSpaceMb?: number;
+  autoExport?: boolean;
+  generateProxy?: boolean;
+  maxDurationMs?: number;
+  audioPrerollMs?: number;
+  hideWindowIfUnprotected?: boolean;
+  colorFormat?: "bgra8" | "rgba16f";
+  keyframeIntervalMs?: number;
+  lowMotionEconomy?: boolean;
+  minUpdateIntervalMs?: number;
+  recordKeystrokes?: boolean;
+  recordClicks?: boolean;
+  recordMoves?: boolean;
+  recordScroll?: boolean;
+  redactSecureFields?: boolean;
+}
+
+const RECORDING_AUTO_STOPPED_EVENT = "recording-auto-stopped";
+const RECORDING_ERROR_EVENT = "recording-error";
+/** Глобальные горячие клавиши (`HotkeyAction` на бэкенде); шлются всему приложению. */
+const GLOBAL_HOTKEY_EVENT = "global-hotkey";
+
+type HotkeyAction = "toggleRecording" | "togglePause";
+
+interface RecordingAutoStoppedPayload {
+  recordingId: string;
+  reason: string;
+  error: string | null;
+}
+
+interface RecordingErrorPayload {
+  recordingId: string;
+  error: string;
+  finalized: boolean;
+}
+
+interface SystemAudioOutput {
+  id: string;
+  name: string;
+  isDefault: boolean;
 }
 
 interface NativePreviewFrame {
   dataUrl: string;
   width: number;
   height: number;
+  sourceWidth: number;
+  sourceHeight: number;
   sequence: number;
 }
 
+interface NativePreviewOptions {
+  maxDimension?: number;
+  targetFps?: number;
+  /** "event" pushes every frame as NATIVE_PREVIEW_FRAME_EVENT instead of waiting for polls. */
+  delivery?: "poll" | "event";
+}
+
+const PREVIEW_OPTIONS: NativePreviewOptions = {
+  maxDimension: 1280,
+  targetFps: 15,
+  delivery: "event",
+};
+const NATIVE_PREVIEW_FRAME_EVENT = "native-preview-frame";
+
 interface RecordScreenProps {
   isActive: boolean;
 }
@@ -87,13 +150,15 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
   const [selectedMicrophoneDevice, setSelectedMicrophoneDevice] = useState("");
   const [isLoadingMicrophones, setIsLoadingMicrophones] = useState(false);
   const [microphoneError, setMicrophoneError] = useState<string | null>(null);
+  const [systemAudioOutputs, setSystemAudioOutputs] = useState<SystemAudioOutput[]>([]);
+  const [selectedSystemAudioOutput, setSelectedSystemAudioOutput] = useState("");
   const [showCursor, setShowCursor] = useState(true);
 
   const tickerRef = useRef<number | null>(null);
   const elapsedBeforePauseMsRef = useRef(0);
   const resumedAtMsRef = useRef<number | null>(null);
   const stateRef = useRef<RecordState>("idle");
-  const previewPollRef = useRef<number | null>(null);
+  const previewUnlistenRef = useRef<(() => void) | null>(null);
   const previewRequestInFlightRef = useRef(false);
   const previewSequenceRef = useRef(0);
   const isPreviewLoadingRef = useRef(false);
@@ -270,9 +335,17 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
   }, []);
 
   const stopPreviewPolling = useCallback(() => {
-    if (previewPollRef.current !== null) {
-      clearInterval(previewPollRef.current);
-      previewPollRef.current = null;
+    if (previewUnlistenRef.current !== null) {
+      previewUnlistenRef.current();
+      previewUnlistenRef.current = null;
+    }
+  }, []);
+
+  const applyPreviewFrame = useCallback((frame: NativePreviewFrame | null) => {
+    if (frame && frame.sequence !== previewSequenceRef.current) {
+      previewSequenceRef.current = frame.sequence;
+      setPreviewImageSrc(frame.dataUrl);
+      setPreviewError(null);
     }
   }, []);
 
@@ -283,17 +356,12 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
     previewRequestInFlightRef.current = true;
 
     try {
-      const frame = await invoke<NativePreviewFrame | null>("get_native_preview_frame");
-      if (frame && frame.sequence !== previewSequenceRef.current) {
-        previewSequenceRef.current = frame.sequence;
-        setPreviewImageSrc(frame.dataUrl);
-        setPreviewError(null);
-      }
+      applyPreviewFrame(await invoke<NativePreviewFrame | null>("get_native_preview_frame"));
     } catch (err) {
       setPreviewError(String(err));
     }
     previewRequestInFlightRef.current = false;
-  }, []);
+  }, [applyPreviewFrame]);
 
   const stopPreview = useCallback(async () => {
     stopPreviewPolling();
@@ -318,12 +386,15 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
     setIsPreviewLoading(true);
     setPreviewError(null);
     try {
-      await invoke("start_native_preview", { monitorIndex: 0 });
-      await fetchPreviewFrame();
       stopPreviewPolling();
-      previewPollRef.current = window.setInterval(() => {
-        void fetchPreviewFrame();
-      }, 1000 / 12);
+      // Subscribe first so no frame published during start-up is missed.
+      previewUnlistenRef.current = await getCurrentWebviewWindow().listen<NativePreviewFrame>(
+        NATIVE_PREVIEW_FRAME_EVENT,
+        (event) => applyPreviewFrame(event.payload)
+      );
+      await invoke("start_native_preview", { options: PREVIEW_OPTIONS });
+      // Picks up the frame carried over from an earlier session, if any.
+      await fetchPreviewFrame();
     } catch (err) {
       setPreviewError(String(err));
       await stopPreview();
@@ -331,13 +402,17 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
       isPreviewLoadingRef.current = false;
       setIsPreviewLoading(false);
     }
-  }, [fetchPreviewFrame, stopPreview, stopPreviewPolling]);
+  }, [applyPreviewFrame, fetchPreviewFrame, stopPreview, stopPreviewPolling]);
 
   useEffect(() => {
-    if (!isActive || state !== "idle") {
+    if (!isActive) {
       void stopPreview();
       return;
     }
+    if (state !== "idle") {
+      // While recording, the recorder keeps publishing frames into the same preview slot.
+      return;
+    }
 
     const timer = window.setTimeout(() => {
       void startPreview();
@@ -388,6 +463,37 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
     };
   }, [isActive]);
 
+  useEffect(() => {
+    if (!isActive) {
+      return;
+    }
+
+    let cancelled = false;
+    const loadSystemAudioOutputs = async () => {
+      try {
+        const outputs = await invoke<SystemAudioOutput[]>("list_system_audio_outputs");
+        if (cancelled) {
+          return;
+        }
+        setSystemAudioOutputs(outputs);
+        setSelectedSystemAudioOutput((current) =>
+          outputs.some((output) => output.id === current) ? current : ""
+        );
+      } catch {
+        // Output selection is optional; the default endpoint is used when listing fails.
+        if (!cancelled) {
+          setSystemAudioOutputs([]);
+          setSelectedSystemAudioOutput("");
+        }
+      }
+    };
+
+    void loadSystemAudioOutputs();
+    return () => {
+      cancelled = true;
+    };
+  }, [isActive]);
+
   useEffect(() => {
     if (state === "idle") {
       stopCtrlPolling();
@@ -442,6 +548,11 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
     const microphoneDeviceForStart = requiresMicrophone && selectedMicrophoneDevice
       ? selectedMicrophoneDevice
       : undefined;
+    const requiresSystemAudio =
+      audioCaptureMode === "system-only" || audioCaptureMode === "microphone-and-system";
+    const systemAudioDeviceForStart = requiresSystemAudio && selectedSystemAudioOutput
+      ? selectedSystemAudioOutput
+      : undefined;
 
     if (requiresMicrophone && !microphoneDeviceForStart) {
       setError("Select microphone device before recording.");
@@ -449,15 +560,15 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
     }
 
     try {
-      await stopPreview();
       const options: StartRecordingOptions = {
         autoZoomTriggerMode,
         quality: recordingQuality,
         targetFps: recordingFps,
         audioCaptureMode,
         microphoneDevice: microphoneDeviceForStart,
+        systemAudioDevice: systemAudioDeviceForStart,
       };
-      const id = await invoke<string>("start_recording", { monitorIndex: 0, options });
+      const id = await invoke<string>("start_recording", { options });
       setRecordingId(id);
       resumedAtMsRef.current = performance.now();
       setState("recording");
@@ -478,7 +589,7 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
     recordingFps,
     recordingQuality,
     selectedMicrophoneDevice,
-    stopPreview,
+    selectedSystemAudioOutput,
     startTicker,
   ]);
 
@@ -582,9 +693,89 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
     };
   }, [handlePause, handleResume, handleSetCursorVisibility, handleStop]);
 
+  useEffect(() => {
+    const unlistenPromise = listen<HotkeyAction>(GLOBAL_HOTKEY_EVENT, (event) => {
+      if (event.payload === "toggleRecording") {
+        if (state === "idle") {
+          void handleStart();
+        } else {
+          void handleStop();
+        }
+        return;
+      }
+      if (event.payload === "togglePause") {
+        if (state === "recording") {
+          void handlePause();
+        } else if (state === "paused") {
+          void handleResume();
+        }
+      }
+    });
+
+    return () => {
+      void unlistenPromise.then((unlisten) => {
+        unlisten();
+      });
+    };
+  }, [handlePause, handleResume, handleStart, handleStop, state]);
+
+  useEffect(() => {
+    const appWindow = getCurrentWebviewWindow();
+    const unlistenPromise = appWindow.listen<RecordingAutoStoppedPayload>(
+      RECORDING_AUTO_STOPPED_EVENT,
+      (event) => {
+        stopTicker();
+        setRecordingId(null);
+        setShowCursor(true);
+        setState("idle");
+        elapsedBeforePauseMsRef.current = 0;
+        resumedAtMsRef.current = null;
+        setDuration(0);
+        setError(
+          event.payload.error
+            ? `Recording stopped automatically: ${event.payload.reason} (${event.payload.error})`
+            : `Recording stopped automatically: ${event.payload.reason}`
+        );
+      }
+    );
+
+    return () => {
+      void unlistenPromise.then((unlisten) => {
+        unlisten();
+      });
+    };
+  }, [stopTicker]);
+
+  useEffect(() => {
+    const appWindow = getCurrentWebviewWindow();
+    const unlistenPromise = appWindow.listen<RecordingErrorPayload>(RECORDING_ERROR_EVENT, (event) => {
+      stopTicker();
+      setRecordingId(null);
+      setShowCursor(true);
+      setState("idle");
+      elapsedBeforePauseMsRef.current = 0;
+      resumedAtMsRef.current = null;
+      setDuration(0);
+      setError(
+        event.payload.finalized
+          ? `Recording failed: ${event.payload.error}. The captured part was saved.`
+          : `Recording failed: ${event.payload.error}`
+      );
+    });
+
+    return () => {
+      void unlistenPromise.then((unlisten) => {
+        unlisten();
+      });
+    };
+  }, [stopTicker]);
+
   const isIdle = state === "idle";
   const microphoneSelectionVisible =
     audioCaptureMode === "microphone-only" || audioCaptureMode === "microphone-and-system";
+  const systemAudioSelectionVisible =
+    (audioCaptureMode === "system-only" || audioCaptureMode === "microphone-and-system") &&
+    systemAudioOutputs.length > 1;
   const statusText =
     state === "idle"
       ? "Ready to record"
@@ -667,6 +858,24 @@ export default function RecordScreen({ isActive }: RecordScreenProps) {
                 {microphoneError && <small className="record-field-error">{microphoneError}</small>}
               </label>
             )}
+
+            {systemAudioSelectionVisible && (
+              <label className="record-field">
+                <span className="record-field-label">System Audio Output</span>
+                <select
+                  value={selectedSystemAudioOutput}
+                  onChange={(event) => setSelectedSystemAudioOutput(event.target.value)}
+                  disabled={!isIdle}
+                >
+                  <option value="">Default output</option>
+                  {systemAudioOutputs.map((output) => (
+                    <option key={output.id} value={output.id}>
+                      {output.isDefault ? `${output.name} (default)` : output.name}
+                    </option>
+                  ))}
+                </select>
+              </label>
+            )}
           </section>
 
           <section className="record-settings-group">
@@ -15,6 +15,7 @@ export interface BoundingRect {
 }
 
 export interface UiContext {
+  /** Имя исполняемого файла приложения (например, `chrome`); в старых записях — `pid:<id>`. */
   appName: string | null;
   controlName: string | null;
   boundingRect: BoundingRect | null;
@@ -65,6 +66,8 @@ export interface KeyDownEvent {
   type: "keyDown";
   ts: number;
   keyCode: string;
+  /** Положение текстовой каретки, если ОС его сообщает. */
+  caret?: BoundingRect;
 }
 
 export interface KeyUpEvent {
@@ -94,9 +97,37 @@ export interface EventsFile {
   screenHeight: number;
   /** DPI scale монитора (1.0, 1.25, 1.5...). */
   scaleFactor: number;
+  /** Пиксели координат событий; бэкенд отдаёт события уже в "physical". */
+  coordinateSpace?: "physical" | "logical";
   events: InputEvent[];
 }
 
+/** Дополнительные правила `normalize_events`. */
+export interface EventCleanupOptions {
+  /** Движения ближе этого интервала к предыдущему сохранённому отбрасываются (мс). */
+  minMoveIntervalMs?: number;
+  dropKeystrokes?: boolean;
+  dropMoves?: boolean;
+  dropScroll?: boolean;
+}
+
+/** Что исправил `normalize_events`. */
+export interface EventCleanupReport {
+  inputCount: number;
+  outputCount: number;
+  reordered: boolean;
+  invalidRemoved: number;
+  clamped: number;
+  duplicatesRemoved: number;
+  filtered: number;
+  throttled: number;
+}
+
+export interface NormalizedEvents {
+  events: EventsFile;
+  report: EventCleanupReport;
+}
+
 // --- Утилиты ---
 
 /** Извлекает все click-события из потока. */
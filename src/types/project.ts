@@ -5,6 +5,9 @@
 
 export const PROJECT_SCHEMA_VERSION = 1 as const;
 
+/** Текущая версия алгоритма умной камеры (CAMERA_ENGINE_VERSION в бэкенде). */
+export const CAMERA_ENGINE_VERSION = 3 as const;
+
 // --- Примитивы ---
 
 /** Прямоугольник в нормализованных координатах [0.0–1.0]. */
@@ -26,6 +29,8 @@ export interface PanKeyframe {
 export interface TargetPoint {
   ts: number;
   rect: NormalizedRect;
+  /** Явное приближение (1–20); без него приближение задаётся размером `rect`. */
+  zoom?: number;
 }
 
 export interface CameraSpring {
@@ -53,12 +58,36 @@ export interface ZoomSegment {
   trigger?: ZoomTrigger;
   /** true — создан алгоритмом авто-зума; false — пользователем вручную. */
   isAuto: boolean;
+  /** Подпись поверх видео на время сегмента (`set_segment_label`). */
+  label?: SegmentLabel;
+}
+
+export type LabelPosition = "auto" | "top" | "bottom";
+
+/** Подпись зум-сегмента при экспорте. */
+export interface SegmentLabel {
+  text: string;
+  /** "auto" — сверху или снизу, с той стороны, где нет приближаемой области. */
+  position?: LabelPosition;
+  /** Высота шрифта как доля высоты кадра (0.02–0.12). */
+  fontScale?: number;
+  /** Цвет текста `#RRGGBB`. */
+  color?: string;
+  /** Непрозрачность подложки под текстом (0–1). */
+  backgroundOpacity?: number;
 }
 
 export interface Timeline {
   zoomSegments: ZoomSegment[];
 }
 
+/** Кадр камеры сегмента в момент `ts` (результат `sample_segment_camera`). */
+export interface CameraRectSample {
+  ts: number;
+  zoom: number;
+  rect: NormalizedRect;
+}
+
 // --- Настройки ---
 
 export interface CursorSettings {
@@ -67,10 +96,40 @@ export interface CursorSettings {
   color: string;
   /** [0.0, 1.0] — сила сглаживания траектории. */
   smoothingFactor: number;
+  /** Частота пересэмплирования перед сглаживанием, Гц [30, 240]. */
+  resampleHz?: number;
   /** Интервалы таймлайна, где курсор скрыт. */
   hiddenRanges?: TimeRange[];
+  /** false — экспорт без синтетического курсора. */
+  showCursor?: boolean;
+  /** Источник траектории курсора при экспорте. */
+  renderMode?: CursorRenderMode;
+  /** Форма векторного курсора при экспорте. */
+  shape?: CursorShape;
+  /** Заливка векторного курсора, `#RRGGBB`. */
+  fillColor?: string;
+  /** Обводка векторного курсора, `#RRGGBB`. */
+  outlineColor?: string;
+  /** Множитель толщины обводки (1.0 — стандартная, 0 — без обводки). */
+  outlineScale?: number;
+  /** Тень или свечение под курсором; отсутствует — без тени. */
+  shadow?: CursorShadow;
+}
+
+/** Размытая копия курсора под ним; `offset: 0` даёт свечение вместо тени. */
+export interface CursorShadow {
+  /** Радиус размытия как доля высоты курсора. */
+  blur: number;
+  /** Смещение вправо-вниз как доля высоты курсора. */
+  offset: number;
+  /** Цвет тени, `#RRGGBB`. */
+  color: string;
 }
 
+export type CursorRenderMode = "raw" | "smoothed" | "off";
+
+export type CursorShape = "arrow" | "hand" | "crosshair" | "dot";
+
 export interface TimeRange {
   startTs: number;
   endTs: number;
@@ -80,17 +139,192 @@ export type Background =
   | { type: "solid"; color: string }
   | { type: "gradient"; from: string; to: string; direction: string };
 
+/** Заполнение полей кадра в экспорте, когда пропорции видео и выхода не совпадают. */
+export type FrameBackground =
+  | { type: "none" }
+  | { type: "solid"; color: string }
+  | { type: "blur"; sigma: number }
+  | { type: "image"; path: string };
+
+/** Соотношение сторон итогового видео (задаёт размер экспорта и кадрирование камеры). */
+export type AspectRatio = "16:9" | "9:16" | "1:1" | "4:3";
+
 export interface ExportSettings {
   width: number;
   height: number;
   fps: number;
   codec: "h264" | "h265" | "vp9";
+  /** Выбранная пропорция; отсутствует, если размер задан вручную. */
+  aspectRatio?: AspectRatio;
+  /** Замедление вокруг кликов; отсутствует — выключено. */
+  clickSlowMotion?: ClickSlowMotion;
+  /** Постоянная область исходного кадра для экспорта; отсутствует — весь кадр. */
+  crop?: NormalizedRect;
+  /** `supersampled` — чёткий текст при глубоком zoom ценой времени экспорта. */
+  scalingQuality?: ScalingQuality;
+  /** `autoReframe` — окно кадрирования узкого выхода следует за активностью. */
+  aspectMode?: AspectMode;
+  /** Сдвиг звука при экспорте (мс, −5000…5000): положительный задерживает звук. */
+  audioOffsetMs?: number;
+}
+
+/** Укладка исходника в экспорт другой пропорции. */
+export type AspectMode = "fit" | "autoReframe";
+
+/** Качество масштабирования кадра камерой при экспорте. */
+export type ScalingQuality = "fast" | "supersampled";
+
+export interface ClickSlowMotion {
+  /** Длина окна вокруг клика (мс, 200–5000). */
+  windowMs: number;
+  /** Во сколько раз замедлить окно (1–4). */
+  factor: number;
+}
+
+/** Чувствительность умной камеры. */
+export interface CameraSettings {
+  /** 0 — «липкая» камера, 1 — «дёрганая»; 0.5 — по умолчанию. */
+  sensitivity: number;
+  /** Доля мёртвой зоны (0.05–0.9); перекрывает ползунок. */
+  deadZoneRatio?: number;
+  /** Дистанция выхода из захвата (0.2–2.0); перекрывает ползунок. */
+  escapeDistanceRatio?: number;
+  /** Доля жёсткого края (0.05–0.95); перекрывает ползунок. */
+  hardEdgeRatio?: number;
+  /** Запас вокруг кликнутого элемента (0–1.5); 0.2 — по умолчанию. */
+  zoomPaddingRatio?: number;
+  /** Предел приближения авто-zoom (1–6); 2 — по умолчанию. */
+  maxZoom?: number;
+  /** Сила следования камеры за прокруткой (0–3); 0 — камера стоит на месте. */
+  scrollFollowStrength?: number;
+  /** Окно накопления кликов для активации zoom, мс (250–10000). */
+  activationWindowMs?: number;
+  /** Кликов в окне для активации zoom (1–5). */
+  minClicksToActivate?: number;
+  /** Разрыв между кликами одного кластера, мс (1–2000). */
+  clickClusterGapMs?: number;
+  /** Минимальный интервал между соседними zoom, мс (1–10000). */
+  minZoomIntervalMs?: number;
+  /** Опережение zoom относительно клика, мс (0–2000). */
+  zoomLeadMs?: number;
+  /** Камера следует за текстовой кареткой при наборе (где ОС её сообщает). */
+  caretFollow?: boolean;
+  /** Режим срабатывания авто-zoom, выбранный при записи; его использует пересборка. */
+  triggerMode?: AutoZoomTriggerMode;
+}
+
+/** Какие клики запускают авто-zoom. */
+export type AutoZoomTriggerMode = "single-click" | "multi-click-window" | "ctrl-click";
+
+/**
+ * Полный набор параметров умной камеры (preview_camera_track).
+ * Поля можно опускать — бэкенд подставит значения по умолчанию.
+ */
+export interface SmartCameraConfig {
+  fixedDtMs: number;
+  deadZoneRatio: number;
+  hardEdgeRatio: number;
+  hardEdgePanSpeedPxPerS: number;
+  escapeDistanceRatio: number;
+  scrollShiftRatio: number;
+  scrollIdleResetMs: number;
+  globalScrollDurationMs: number;
+  globalScrollViewportTravelRatio: number;
+  semanticPaddingRatio: number;
+  fallbackZoom: number;
+  freeRoamZoom: number;
+  maxZoomLimit: number;
+  safeZoneMarginRatio: number;
+  maxLookaheadMs: number;
+  velocityThresholdPxPerMs: number;
+  zoomLeadMs: number;
+  clickActivationMode: "single-click" | "multi-click-window" | "ctrl-click";
+  activationWindowMs: number;
+  minClicksToActivate: number;
+  clickClusterGapMs: number;
+  clickClusterElementScale: number;
+  minClickClusterRadiusPx: number;
+  minZoomIntervalMs: number;
+  minLockDurationMs: number;
+  minSegmentDurationMs: number;
+  extendShortSegments: boolean;
+  lockRecentWindowMs: number;
+  caretFollow: boolean;
+  springMass: number;
+  springStiffness: number;
+  springDamping: number;
+  segmentTargetSampleMs: number;
+}
+
+export interface CameraTrackPoint {
+  ts: number;
+  centerX: number;
+  centerY: number;
+  zoom: number;
+  /** Камера в захвате (LockedFocus). */
+  locked: boolean;
+}
+
+export interface CameraTrackPreview {
+  config: SmartCameraConfig;
+  /** Число сэмплов симуляции до прореживания. */
+  totalSamples: number;
+  points: CameraTrackPoint[];
+}
+
+/** Дорожка, оборвавшаяся во время записи (например, отключили микрофон). */
+export interface AudioTrackIssue {
+  track: "system" | "microphone";
+  /** Момент обрыва на таймлайне проекта (мс). */
+  endedAtMs: number;
+}
+
+/** Тепловая карта кликов (generate_click_heatmap). */
+export interface ClickHeatmapImage {
+  imagePath: string;
+  heatmap: {
+    columns: number;
+    rows: number;
+    clickCount: number;
+    /** Плотность по строкам сетки; самая «горячая» ячейка равна 1. */
+    cells: number[];
+  };
+}
+
+/** Трассировка решений умной камеры (debug_camera_decisions). */
+export interface CameraDecisionTrace {
+  clicks: {
+    ts: number;
+    x: number;
+    y: number;
+    hasBounds: boolean;
+    ctrlPressed: boolean;
+    /** Причина отсева клика; null — клик прошёл режим активации. */
+    gatedOut: "ctrlNotPressed" | "activationWindow" | null;
+  }[];
+  clusters: {
+    firstClickTs: number;
+    lastClickTs: number;
+    clickTs: number[];
+    bounds: { x: number; y: number; width: number; height: number } | null;
+    usedBounds: boolean;
+    prerollStartTs: number;
+    startTs: number;
+    delayedByMinIntervalMs: number;
+    centerX: number;
+    centerY: number;
+    zoom: number;
+    outcome: "zoomed" | "skippedNoOpZoom";
+  }[];
 }
 
 export interface ProjectSettings {
   cursor: CursorSettings;
   background: Background;
   export: ExportSettings;
+  camera: CameraSettings;
+  /** Отсутствует в старых проектах — тогда поля чёрные. */
+  frameBackground?: FrameBackground;
 }
 
 // --- Корневой объект ---
@@ -99,6 +333,10 @@ export interface Project {
   schemaVersion: typeof PROJECT_SCHEMA_VERSION;
   id: string;
   name: string;
+  /** Произвольное описание записи. */
+  description?: string;
+  /** Метки для поиска и фильтрации. */
+  tags?: string[];
   /** Unix timestamp (мс) создания проекта. */
   createdAt: number;
   /** Путь к сырому видеофайлу относительно папки проекта. */
@@ -113,12 +351,38 @@ export interface Project {
   videoHeight: number;
   timeline: Timeline;
   settings: ProjectSettings;
+  /** Аудиодорожки, оборвавшиеся во время записи. */
+  audioIssues?: AudioTrackIssue[];
+  /** Версия камеры, построившей авто-сегменты; отсутствует у старых проектов (v1). */
+  cameraEngineVersion?: number;
+  /** Ошибка склейки мониторов: видео содержит только первый монитор. */
+  monitorCompositeError?: string;
+  /** Задержка старта звуковых дорожек, измеренная при записи. */
+  audioStartOffsets?: AudioStartOffsets;
+}
+
+/** Насколько раньше видео стартовала дорожка (мс); отрицательное — позже. */
+export interface AudioStartOffsets {
+  systemMs?: number;
+  microphoneMs?: number;
 }
 
 // --- Фабрики / дефолты ---
 
 export function defaultCursorSettings(): CursorSettings {
-  return { size: 1.0, color: "#FFFFFF", smoothingFactor: 0.8, hiddenRanges: [] };
+  return {
+    size: 1.0,
+    color: "#FFFFFF",
+    smoothingFactor: 0.8,
+    resampleHz: 120,
+    hiddenRanges: [],
+    showCursor: true,
+    renderMode: "smoothed",
+    shape: "arrow",
+    fillColor: "#000000",
+    outlineColor: "#FFFFFF",
+    outlineScale: 1.0,
+  };
 }
 
 export function defaultBackground(): Background {
@@ -126,7 +390,11 @@ export function defaultBackground(): Background {
 }
 
 export function defaultExportSettings(): ExportSettings {
-  return { width: 1920, height: 1080, fps: 60, codec: "h264" };
+  return { width: 1920, height: 1080, fps: 60, codec: "h264", aspectRatio: "16:9" };
+}
+
+export function defaultCameraSettings(): CameraSettings {
+  return { sensitivity: 0.5 };
 }
 
 export function defaultProjectSettings(): ProjectSettings {
@@ -134,6 +402,7 @@ export function defaultProjectSettings(): ProjectSettings {
     cursor: defaultCursorSettings(),
     background: defaultBackground(),
     export: defaultExportSettings(),
+    camera: defaultCameraSettings(),
   };
 }
 
@@ -158,5 +427,17 @@ export function createProject(
     videoHeight,
     timeline: { zoomSegments: [] },
     settings: defaultProjectSettings(),
+    cameraEngineVersion: CAMERA_ENGINE_VERSION,
   };
 }
+
+/**
+ * Авто-zoom построен старой версией камеры: UI может предложить пересчёт
+ * (`regenerate_auto_zoom` с `upgradeCameraEngine: true`).
+ */
+export function hasNewerCameraEngine(project: Project): boolean {
+  return (
+    (project.cameraEngineVersion ?? 1) < CAMERA_ENGINE_VERSION &&
+    project.timeline.zoomSegments.some((segment) => segment.isAuto)
+  );
+}